@@ -0,0 +1,83 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
+use rocketmq_error::RocketMQResult;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::runtime::RPCHook;
+
+use crate::acl_utils;
+use crate::session_credentials::SessionCredentials;
+use crate::session_credentials::ACCESS_KEY;
+use crate::session_credentials::SECURITY_TOKEN;
+use crate::session_credentials::SIGNATURE;
+
+/// Signs every request sent by a client with the credentials of its account.
+pub struct AclClientRPCHook {
+    session_credentials: SessionCredentials,
+}
+
+impl AclClientRPCHook {
+    pub fn new(session_credentials: SessionCredentials) -> Self {
+        Self {
+            session_credentials,
+        }
+    }
+
+    pub fn session_credentials(&self) -> &SessionCredentials {
+        &self.session_credentials
+    }
+}
+
+impl RPCHook for AclClientRPCHook {
+    fn do_before_request(
+        &self,
+        _remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        // the signature covers the custom header, so it has to be flattened first
+        request.make_custom_header_to_net();
+        request.add_ext_field(ACCESS_KEY, self.session_credentials.access_key.clone());
+        if let Some(security_token) = &self.session_credentials.security_token {
+            request.add_ext_field(SECURITY_TOKEN, security_token.clone());
+        }
+        let fields = request
+            .get_ext_fields()
+            .map(|ext_fields| {
+                ext_fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default();
+        let signature = acl_utils::cal_signature(
+            &acl_utils::combine_request_content(request, &fields),
+            &self.session_credentials.secret_key,
+        );
+        request.add_ext_field(SIGNATURE, signature);
+        Ok(())
+    }
+
+    fn do_after_response(
+        &self,
+        _remote_addr: SocketAddr,
+        _response: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use thiserror::Error;
+
+pub type AclResult<T> = Result<T, AclError>;
+
+#[derive(Debug, Error)]
+pub enum AclError {
+    /// The request is not allowed to access the resource.
+    #[error("{0}")]
+    AccessDenied(String),
+
+    /// The acl configuration, or the request, can not be parsed.
+    #[error("{0}")]
+    InvalidConfig(String),
+
+    #[error("acl file io error: {0}")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,72 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::runtime::RPCHook;
+use tracing::warn;
+
+use crate::plain::plain_access_validator::PlainAccessValidator;
+
+/// Rejects the requests the acl file does not allow with `ResponseCode::NoPermission`.
+pub struct AclServerRPCHook {
+    validator: Arc<PlainAccessValidator>,
+}
+
+impl AclServerRPCHook {
+    pub fn new(validator: Arc<PlainAccessValidator>) -> Self {
+        Self { validator }
+    }
+}
+
+impl RPCHook for AclServerRPCHook {
+    fn do_before_request(
+        &self,
+        remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        let result = self
+            .validator
+            .parse(request, remote_addr)
+            .and_then(|access_resource| self.validator.validate(&access_resource));
+        if let Err(e) = result {
+            warn!(
+                "acl check failed, code={}, remote={}: {}",
+                request.code(),
+                remote_addr,
+                e
+            );
+            return Err(RocketmqError::AbortProcessError(
+                ResponseCode::NoPermission as i32,
+                e.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn do_after_response(
+        &self,
+        _remote_addr: SocketAddr,
+        _response: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        Ok(())
+    }
+}
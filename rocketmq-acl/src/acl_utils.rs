@@ -0,0 +1,221 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use cheetah_string::CheetahString;
+use ring::digest;
+use ring::hmac;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use serde_json::Map;
+use serde_json::Value;
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::Yaml;
+use yaml_rust2::YamlEmitter;
+use yaml_rust2::YamlLoader;
+
+use crate::acl_error::AclError;
+use crate::acl_error::AclResult;
+use crate::session_credentials::SIGNATURE;
+
+/// Signs `data` with HmacSHA1 and returns the base64 encoded signature, compatible with the
+/// signature computed by the Java clients.
+pub fn cal_signature(data: &[u8], secret_key: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret_key.as_bytes());
+    BASE64_STANDARD.encode(hmac::sign(&key, data))
+}
+
+/// The key requests of an ACL 2.0 user are signed with: the base64 encoded SHA-256 of
+/// `username:password`. The broker keeps only this digest, never the password itself.
+pub fn user_signing_key(username: &str, password: &str) -> String {
+    let mut context = digest::Context::new(&digest::SHA256);
+    context.update(username.as_bytes());
+    context.update(b":");
+    context.update(password.as_bytes());
+    BASE64_STANDARD.encode(context.finish())
+}
+
+/// Checks a base64 encoded HmacSHA1 `signature` of `data` in constant time.
+pub fn verify_signature(data: &[u8], secret_key: &str, signature: &str) -> bool {
+    let Ok(signature) = BASE64_STANDARD.decode(signature) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA1_FOR_LEGACY_USE_ONLY, secret_key.as_bytes());
+    hmac::verify(&key, data, &signature).is_ok()
+}
+
+/// The content a request signature covers: the values of the sorted ext fields, except the
+/// signature itself, followed by the body.
+pub fn combine_request_content(
+    request: &RemotingCommand,
+    fields: &BTreeMap<CheetahString, CheetahString>,
+) -> Vec<u8> {
+    let mut content = Vec::new();
+    for (key, value) in fields {
+        if key != SIGNATURE {
+            content.extend_from_slice(value.as_bytes());
+        }
+    }
+    if let Some(body) = request.get_body() {
+        content.extend_from_slice(body);
+    }
+    content
+}
+
+/// Reads a yaml file as json, returns `None` if the file does not exist or is empty. Scalars are
+/// kept as strings, so secret keys made of digits only still load as strings.
+pub fn get_yaml_data_object(path: &Path) -> AclResult<Option<Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    let docs = YamlLoader::load_from_str(&content).map_err(|e| {
+        AclError::InvalidConfig(format!("parse acl file {} failed: {}", path.display(), e))
+    })?;
+    Ok(docs.first().map(yaml_to_json))
+}
+
+/// Writes `data` to `path` as yaml.
+pub fn write_data_object(path: &Path, data: &Value) -> AclResult<()> {
+    let mut content = String::new();
+    YamlEmitter::new(&mut content)
+        .dump(&json_to_yaml(data))
+        .map_err(|e| {
+            AclError::InvalidConfig(format!("write acl file {} failed: {}", path.display(), e))
+        })?;
+    content.push('\n');
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, content)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn yaml_to_json(yaml: &Yaml) -> Value {
+    match yaml {
+        Yaml::Real(value) | Yaml::String(value) => Value::String(value.clone()),
+        Yaml::Integer(value) => Value::String(value.to_string()),
+        Yaml::Boolean(value) => Value::Bool(*value),
+        Yaml::Array(values) => Value::Array(values.iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => {
+            let mut map = Map::new();
+            for (key, value) in hash {
+                // empty values load as missing, so they fall back to the serde defaults
+                if matches!(value, Yaml::Null) {
+                    continue;
+                }
+                let key = match key {
+                    Yaml::String(key) | Yaml::Real(key) => key.clone(),
+                    Yaml::Integer(key) => key.to_string(),
+                    Yaml::Boolean(key) => key.to_string(),
+                    _ => continue,
+                };
+                map.insert(key, yaml_to_json(value));
+            }
+            Value::Object(map)
+        }
+        Yaml::Alias(_) | Yaml::Null | Yaml::BadValue => Value::Null,
+    }
+}
+
+fn json_to_yaml(value: &Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(value) => Yaml::Boolean(*value),
+        Value::Number(value) => match value.as_i64() {
+            Some(value) => Yaml::Integer(value),
+            None => Yaml::Real(value.to_string()),
+        },
+        Value::String(value) => Yaml::String(value.clone()),
+        Value::Array(values) => Yaml::Array(values.iter().map(json_to_yaml).collect()),
+        Value::Object(map) => {
+            let mut hash = Hash::new();
+            for (key, value) in map {
+                hash.insert(Yaml::String(key.clone()), json_to_yaml(value));
+            }
+            Yaml::Hash(hash)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn cal_signature_matches_hmac_sha1() {
+        // echo -n "12345678" | openssl dgst -sha1 -hmac "rocketmq2" -binary | base64
+        assert_eq!(
+            cal_signature(b"12345678", "rocketmq2"),
+            "qByPzXC9kAptfo8Z7wb6m6JPaCI="
+        );
+    }
+
+    #[test]
+    fn verify_signature_accepts_only_the_matching_signature() {
+        assert!(verify_signature(
+            b"12345678",
+            "rocketmq2",
+            "qByPzXC9kAptfo8Z7wb6m6JPaCI="
+        ));
+        assert!(!verify_signature(
+            b"12345679",
+            "rocketmq2",
+            "qByPzXC9kAptfo8Z7wb6m6JPaCI="
+        ));
+        assert!(!verify_signature(b"12345678", "rocketmq2", "not base64!"));
+    }
+
+    #[test]
+    fn user_signing_key_is_salted_with_the_username() {
+        // echo -n "rocketmq:12345678" | openssl dgst -sha256 -binary | base64
+        assert_eq!(
+            user_signing_key("rocketmq", "12345678"),
+            "QjHg0lBaGpk6C9+tQUCB3e30SCSA2oImfrbLrDypOhM="
+        );
+        assert_ne!(
+            user_signing_key("alice", "12345678"),
+            user_signing_key("bob", "12345678")
+        );
+    }
+
+    #[test]
+    fn yaml_file_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("conf").join("plain_acl.yml");
+        assert!(get_yaml_data_object(&path).unwrap().is_none());
+
+        let data = json!({
+            "globalWhiteRemoteAddresses": ["10.10.103.*"],
+            "accounts": [{
+                "accessKey": "RocketMQ",
+                "secretKey": "12345678",
+                "admin": false,
+                "topicPerms": ["topicA=DENY", "topicB=PUB|SUB"],
+            }],
+        });
+        write_data_object(&path, &data).unwrap();
+        assert_eq!(get_yaml_data_object(&path).unwrap(), Some(data));
+    }
+}
@@ -0,0 +1,29 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! ACL 1.0 for rocketmq: clients sign every request with an access key/secret key pair through
+//! [`acl_client_rpc_hook::AclClientRPCHook`], brokers and name servers check the signature and the
+//! topic/group permissions of the access key through
+//! [`acl_server_rpc_hook::AclServerRPCHook`], backed by a hot-reloaded plain acl file.
+
+pub mod acl_client_rpc_hook;
+pub mod acl_error;
+pub mod acl_server_rpc_hook;
+pub mod acl_utils;
+pub mod permission;
+pub mod plain;
+pub mod session_credentials;
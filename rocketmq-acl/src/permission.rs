@@ -0,0 +1,176 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_remoting::code::request_code::RequestCode;
+
+use crate::acl_error::AclError;
+use crate::acl_error::AclResult;
+use crate::plain::plain_access_resource::PlainAccessResource;
+
+pub const DENY: u8 = 1;
+pub const ANY: u8 = 1 << 1;
+pub const PUB: u8 = 1 << 2;
+pub const SUB: u8 = 1 << 3;
+
+pub const DENY_NAME: &str = "DENY";
+pub const PUB_NAME: &str = "PUB";
+pub const SUB_NAME: &str = "SUB";
+pub const PUB_SUB_NAME: &str = "PUB|SUB";
+pub const SUB_PUB_NAME: &str = "SUB|PUB";
+
+/// Whether `owned_perm` grants `needed_perm`, a denied resource grants nothing.
+pub fn check_permission(needed_perm: u8, owned_perm: u8) -> bool {
+    if owned_perm & DENY > 0 {
+        return false;
+    }
+    if needed_perm & ANY > 0 {
+        return owned_perm & PUB > 0 || owned_perm & SUB > 0;
+    }
+    needed_perm & owned_perm > 0
+}
+
+/// Parses `PUB`, `SUB`, `PUB|SUB` or `DENY`, anything else is denied.
+pub fn parse_perm_from_string(perm: Option<&str>) -> u8 {
+    match perm.map(str::trim) {
+        Some(PUB_NAME) => PUB,
+        Some(SUB_NAME) => SUB,
+        Some(PUB_SUB_NAME) | Some(SUB_PUB_NAME) => PUB | SUB,
+        _ => DENY,
+    }
+}
+
+/// Adds the `resource=PERM` entries to the resource permissions of `plain_access_resource`,
+/// groups are stored as their retry topic.
+pub fn parse_resource_perms(
+    plain_access_resource: &mut PlainAccessResource,
+    is_topic: bool,
+    resources: &[CheetahString],
+) -> AclResult<()> {
+    for resource in resources {
+        let Some((name, perm)) = resource.split_once('=') else {
+            return Err(AclError::InvalidConfig(format!(
+                "Parse resource permission failed for {}:{}",
+                if is_topic { "topic" } else { "group" },
+                resource
+            )));
+        };
+        let name = name.trim();
+        let name = if is_topic {
+            CheetahString::from(name)
+        } else {
+            CheetahString::from_string(PlainAccessResource::get_retry_topic(name))
+        };
+        plain_access_resource
+            .resource_perm_map
+            .insert(name, parse_perm_from_string(Some(perm)));
+    }
+    Ok(())
+}
+
+/// Checks every entry is formatted as `resource=PERM` with a known permission.
+pub fn check_resource_perms(resources: &[CheetahString]) -> AclResult<()> {
+    for resource in resources {
+        let Some((_, perm)) = resource.split_once('=') else {
+            return Err(AclError::InvalidConfig(format!(
+                "Parse Resource format error for {}. The expected resource format is 'Res=Perm'. \
+                 For example: topicA=SUB",
+                resource
+            )));
+        };
+        let perm = perm.trim();
+        if perm != DENY_NAME && parse_perm_from_string(Some(perm)) == DENY {
+            return Err(AclError::InvalidConfig(format!(
+                "Parse resource permission error for {}. The expected permissions are 'SUB' or \
+                 'PUB' or 'SUB|PUB' or 'PUB|SUB'.",
+                resource
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Requests changing the cluster configuration, only admin accounts may send them.
+pub fn need_admin_perm(code: i32) -> bool {
+    matches!(
+        RequestCode::from(code),
+        RequestCode::UpdateAndCreateTopic
+            | RequestCode::UpdateAndCreateTopicList
+            | RequestCode::UpdateBrokerConfig
+            | RequestCode::DeleteTopicInBroker
+            | RequestCode::UpdateAndCreateSubscriptionGroup
+            | RequestCode::DeleteSubscriptionGroup
+            | RequestCode::UpdateAndCreateStaticTopic
+            | RequestCode::UpdateAndCreateAclConfig
+            | RequestCode::DeleteAclConfig
+            | RequestCode::UpdateGlobalWhiteAddrsConfig
+            | RequestCode::DeleteTopicInNamesrv
+            | RequestCode::PutKvConfig
+            | RequestCode::DeleteKvConfig
+            | RequestCode::UpdateNamesrvConfig
+            | RequestCode::WipeWritePermOfBroker
+            | RequestCode::AddWritePermOfBroker
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_permission_honours_deny_and_any() {
+        assert!(check_permission(PUB, PUB | SUB));
+        assert!(check_permission(SUB, SUB));
+        assert!(!check_permission(PUB, SUB));
+        assert!(!check_permission(PUB, PUB | DENY));
+        assert!(check_permission(ANY, SUB));
+        assert!(!check_permission(ANY, DENY));
+    }
+
+    #[test]
+    fn parse_perm_from_string_defaults_to_deny() {
+        assert_eq!(parse_perm_from_string(Some("PUB")), PUB);
+        assert_eq!(parse_perm_from_string(Some(" SUB|PUB ")), PUB | SUB);
+        assert_eq!(parse_perm_from_string(Some("ALL")), DENY);
+        assert_eq!(parse_perm_from_string(None), DENY);
+    }
+
+    #[test]
+    fn parse_and_check_resource_perms() {
+        let mut resource = PlainAccessResource::default();
+        parse_resource_perms(
+            &mut resource,
+            true,
+            &["topicA=DENY".into(), "topicB=PUB|SUB".into()],
+        )
+        .unwrap();
+        parse_resource_perms(&mut resource, false, &["groupA=SUB".into()]).unwrap();
+        assert_eq!(resource.resource_perm_map["topicA"], DENY);
+        assert_eq!(resource.resource_perm_map["topicB"], PUB | SUB);
+        assert_eq!(resource.resource_perm_map["%RETRY%groupA"], SUB);
+
+        assert!(check_resource_perms(&["topicA=DENY".into()]).is_ok());
+        assert!(check_resource_perms(&["topicA".into()]).is_err());
+        assert!(check_resource_perms(&["topicA=ALL".into()]).is_err());
+    }
+
+    #[test]
+    fn admin_codes_need_admin_perm() {
+        assert!(need_admin_perm(RequestCode::UpdateAndCreateTopic.to_i32()));
+        assert!(need_admin_perm(RequestCode::DeleteAclConfig.to_i32()));
+        assert!(!need_admin_perm(RequestCode::SendMessage.to_i32()));
+    }
+}
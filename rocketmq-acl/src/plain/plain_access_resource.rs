@@ -0,0 +1,75 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::mix_all::RETRY_GROUP_TOPIC_PREFIX;
+
+use crate::plain::remote_address_strategy::RemoteAddressStrategy;
+
+/// Either the resources a request needs, parsed from the request, or the resources an account
+/// owns, built from the acl file.
+#[derive(Debug, Clone, Default)]
+pub struct PlainAccessResource {
+    pub access_key: Option<CheetahString>,
+    pub secret_key: Option<CheetahString>,
+    pub white_remote_address: Option<CheetahString>,
+    pub admin: bool,
+    pub default_topic_perm: u8,
+    pub default_group_perm: u8,
+    /// Topics, and groups as their retry topic, mapped to their permission.
+    pub resource_perm_map: HashMap<CheetahString, u8>,
+    pub remote_address_strategy: Option<RemoteAddressStrategy>,
+    pub request_code: i32,
+    /// The content the signature of the request covers.
+    pub content: Vec<u8>,
+    pub signature: Option<CheetahString>,
+    pub secret_token: Option<CheetahString>,
+}
+
+impl PlainAccessResource {
+    pub fn add_resource_and_perm(&mut self, resource: Option<&str>, perm: u8) {
+        let Some(resource) = resource.filter(|resource| !resource.is_empty()) else {
+            return;
+        };
+        self.resource_perm_map
+            .insert(CheetahString::from(resource), perm);
+    }
+
+    pub fn is_retry_topic(topic: &str) -> bool {
+        topic.starts_with(RETRY_GROUP_TOPIC_PREFIX)
+    }
+
+    pub fn get_retry_topic(group: &str) -> String {
+        mix_all::get_retry_topic(group)
+    }
+
+    pub fn get_group_from_retry_topic(retry_topic: &str) -> &str {
+        retry_topic
+            .strip_prefix(RETRY_GROUP_TOPIC_PREFIX)
+            .unwrap_or(retry_topic)
+    }
+
+    pub fn print_str(resource: &str, is_group: bool) -> String {
+        if is_group {
+            format!("group:{}", Self::get_group_from_retry_topic(resource))
+        } else {
+            format!("topic:{}", resource)
+        }
+    }
+}
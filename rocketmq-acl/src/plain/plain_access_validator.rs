@@ -0,0 +1,243 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::DataVersion;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+
+use crate::acl_error::AclError;
+use crate::acl_error::AclResult;
+use crate::acl_utils;
+use crate::permission;
+use crate::plain::plain_access_resource::PlainAccessResource;
+use crate::plain::plain_permission_manager::PlainPermissionManager;
+use crate::session_credentials::ACCESS_KEY;
+use crate::session_credentials::SECURITY_TOKEN;
+use crate::session_credentials::SIGNATURE;
+
+/// Validates requests against the accounts of the plain acl file.
+pub struct PlainAccessValidator {
+    plain_permission_manager: Arc<PlainPermissionManager>,
+}
+
+impl PlainAccessValidator {
+    /// Loads the acl file and starts watching it for changes.
+    pub fn new(acl_file: impl Into<PathBuf>) -> AclResult<Self> {
+        let plain_permission_manager = Arc::new(PlainPermissionManager::new(acl_file));
+        plain_permission_manager.load()?;
+        plain_permission_manager.start_watch();
+        Ok(Self {
+            plain_permission_manager,
+        })
+    }
+
+    /// Collects the resources `request` accesses and the permissions it needs on them.
+    pub fn parse(
+        &self,
+        request: &RemotingCommand,
+        remote_addr: SocketAddr,
+    ) -> AclResult<PlainAccessResource> {
+        let mut access_resource = PlainAccessResource {
+            white_remote_address: Some(remote_addr.ip().to_string().into()),
+            request_code: request.code(),
+            ..PlainAccessResource::default()
+        };
+        let Some(ext_fields) = request.get_ext_fields() else {
+            // only the white lists can let such a request pass
+            return Ok(access_resource);
+        };
+        access_resource.access_key = ext_fields.get(ACCESS_KEY).cloned();
+        access_resource.signature = ext_fields.get(SIGNATURE).cloned();
+        access_resource.secret_token = ext_fields.get(SECURITY_TOKEN).cloned();
+
+        let field = |name: &str| ext_fields.get(name).map(CheetahString::as_str);
+        match RequestCode::from(request.code()) {
+            RequestCode::SendMessage => {
+                Self::add_send_topic(&mut access_resource, field("topic"));
+            }
+            RequestCode::SendMessageV2 | RequestCode::SendBatchMessage => {
+                Self::add_send_topic(&mut access_resource, field("b"));
+            }
+            RequestCode::ConsumerSendMsgBack => {
+                Self::add_group(&mut access_resource, field("group"));
+            }
+            RequestCode::PullMessage => {
+                access_resource.add_resource_and_perm(field("topic"), permission::SUB);
+                Self::add_group(&mut access_resource, field("consumerGroup"));
+            }
+            RequestCode::QueryMessage => {
+                access_resource.add_resource_and_perm(field("topic"), permission::SUB);
+            }
+            RequestCode::HeartBeat => {
+                let Some(body) = request.get_body() else {
+                    return Err(AclError::AccessDenied(
+                        "The heartbeat request has no body".to_string(),
+                    ));
+                };
+                let heartbeat_data = HeartbeatData::decode(body).map_err(|e| {
+                    AclError::AccessDenied(format!("decode heartbeat data failed: {}", e))
+                })?;
+                for consumer_data in &heartbeat_data.consumer_data_set {
+                    Self::add_group(&mut access_resource, Some(&consumer_data.group_name));
+                    for subscription_data in &consumer_data.subscription_data_set {
+                        access_resource
+                            .add_resource_and_perm(Some(&subscription_data.topic), permission::SUB);
+                    }
+                }
+            }
+            RequestCode::UnregisterClient | RequestCode::GetConsumerListByGroup => {
+                Self::add_group(&mut access_resource, field("consumerGroup"));
+            }
+            RequestCode::UpdateConsumerOffset => {
+                Self::add_group(&mut access_resource, field("consumerGroup"));
+                access_resource.add_resource_and_perm(field("topic"), permission::SUB);
+            }
+            _ => {}
+        }
+
+        let fields = ext_fields
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        access_resource.content = acl_utils::combine_request_content(request, &fields);
+        Ok(access_resource)
+    }
+
+    /// Sending to a retry topic is consuming for the group of the topic.
+    fn add_send_topic(access_resource: &mut PlainAccessResource, topic: Option<&str>) {
+        match topic {
+            Some(topic) if PlainAccessResource::is_retry_topic(topic) => {
+                access_resource.add_resource_and_perm(Some(topic), permission::SUB);
+            }
+            topic => access_resource.add_resource_and_perm(topic, permission::PUB),
+        }
+    }
+
+    fn add_group(access_resource: &mut PlainAccessResource, group: Option<&str>) {
+        if let Some(group) = group {
+            access_resource.add_resource_and_perm(
+                Some(&PlainAccessResource::get_retry_topic(group)),
+                permission::SUB,
+            );
+        }
+    }
+
+    pub fn validate(&self, access_resource: &PlainAccessResource) -> AclResult<()> {
+        self.plain_permission_manager.validate(access_resource)
+    }
+
+    pub fn update_access_config(&self, config: PlainAccessConfig) -> AclResult<()> {
+        self.plain_permission_manager.update_access_config(config)
+    }
+
+    pub fn delete_access_config(&self, access_key: &str) -> AclResult<()> {
+        self.plain_permission_manager
+            .delete_access_config(access_key)
+    }
+
+    pub fn update_global_white_addrs_config(
+        &self,
+        global_white_addrs: Vec<CheetahString>,
+    ) -> AclResult<()> {
+        self.plain_permission_manager
+            .update_global_white_addrs_config(global_white_addrs)
+    }
+
+    pub fn get_acl_config_version(&self) -> DataVersion {
+        self.plain_permission_manager.get_data_version()
+    }
+
+    pub fn get_all_acl_config_version(
+        &self,
+    ) -> std::collections::HashMap<CheetahString, DataVersion> {
+        self.plain_permission_manager.get_all_acl_config_version()
+    }
+
+    pub fn shutdown(&self) {
+        self.plain_permission_manager.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    const ACL_FILE: &str = r#"
+accounts:
+  - accessKey: RocketMQ
+    secretKey: 12345678
+    defaultTopicPerm: DENY
+    defaultGroupPerm: SUB
+    topicPerms:
+      - topicA=PUB
+"#;
+
+    fn sign(request: &mut RemotingCommand, secret_key: &str) {
+        let fields = request
+            .get_ext_fields()
+            .unwrap()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<BTreeMap<_, _>>();
+        let signature = acl_utils::cal_signature(
+            &acl_utils::combine_request_content(request, &fields),
+            secret_key,
+        );
+        request.add_ext_field(SIGNATURE, signature);
+    }
+
+    #[test]
+    fn parse_and_validate_send_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let acl_file = dir.path().join("plain_acl.yml");
+        std::fs::write(&acl_file, ACL_FILE).unwrap();
+        let validator = PlainAccessValidator::new(acl_file).unwrap();
+        let remote_addr: SocketAddr = "127.0.0.1:10911".parse().unwrap();
+
+        let mut request = RemotingCommand::create_remoting_command(RequestCode::SendMessage)
+            .set_ext_fields(HashMap::from([
+                (CheetahString::from("topic"), CheetahString::from("topicA")),
+                (
+                    CheetahString::from(ACCESS_KEY),
+                    CheetahString::from("RocketMQ"),
+                ),
+            ]));
+        sign(&mut request, "12345678");
+        let resource = validator.parse(&request, remote_addr).unwrap();
+        assert_eq!(resource.white_remote_address.as_deref(), Some("127.0.0.1"));
+        assert_eq!(
+            resource.resource_perm_map.get("topicA"),
+            Some(&permission::PUB)
+        );
+        assert!(validator.validate(&resource).is_ok());
+
+        request.add_ext_field("topic", "topicB");
+        let resource = validator.parse(&request, remote_addr).unwrap();
+        assert!(validator.validate(&resource).is_err());
+        validator.shutdown();
+    }
+}
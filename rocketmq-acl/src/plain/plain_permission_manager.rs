@@ -0,0 +1,560 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_remoting::protocol::DataVersion;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::error;
+use tracing::info;
+
+use crate::acl_error::AclError;
+use crate::acl_error::AclResult;
+use crate::acl_utils;
+use crate::permission;
+use crate::plain::plain_access_resource::PlainAccessResource;
+use crate::plain::remote_address_strategy::RemoteAddressStrategy;
+
+/// The acl file, relative to the rocketmq home directory.
+pub const DEFAULT_PLAIN_ACL_FILE: &str = "conf/plain_acl.yml";
+
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The content of the plain acl file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlainAccessData {
+    pub global_white_remote_addresses: Vec<CheetahString>,
+    pub accounts: Vec<PlainAccessConfig>,
+}
+
+#[derive(Default)]
+struct AclTable {
+    global_white_remote_address_strategy: Vec<RemoteAddressStrategy>,
+    plain_access_resource_map: HashMap<CheetahString, PlainAccessResource>,
+    access_data: PlainAccessData,
+}
+
+/// Holds the accounts of the plain acl file and checks requests against them. The file is
+/// reloaded when it changes on disk, and rewritten when accounts are updated through the admin
+/// requests.
+pub struct PlainPermissionManager {
+    acl_file: PathBuf,
+    table: RwLock<AclTable>,
+    data_version: Mutex<DataVersion>,
+    last_modified: Mutex<Option<SystemTime>>,
+    shutdown: AtomicBool,
+}
+
+impl PlainPermissionManager {
+    pub fn new(acl_file: impl Into<PathBuf>) -> Self {
+        Self {
+            acl_file: acl_file.into(),
+            table: RwLock::new(AclTable::default()),
+            data_version: Mutex::new(DataVersion::default()),
+            last_modified: Mutex::new(None),
+            shutdown: AtomicBool::new(false),
+        }
+    }
+
+    pub fn acl_file(&self) -> &Path {
+        &self.acl_file
+    }
+
+    /// Loads the acl file, the current accounts are kept if it can not be parsed.
+    pub fn load(&self) -> AclResult<()> {
+        let mut table = self.table.write();
+        let last_modified = self.file_modified_time();
+        let Some(data) = acl_utils::get_yaml_data_object(&self.acl_file)? else {
+            return Err(AclError::InvalidConfig(format!(
+                "No acl file found at {}",
+                self.acl_file.display()
+            )));
+        };
+        let access_data = serde_json::from_value::<PlainAccessData>(data).map_err(|e| {
+            AclError::InvalidConfig(format!(
+                "parse acl file {} failed: {}",
+                self.acl_file.display(),
+                e
+            ))
+        })?;
+        *table = Self::build_table(access_data)?;
+        *self.last_modified.lock() = last_modified;
+        self.data_version.lock().next_version();
+        info!(
+            "load acl file {}, {} accounts",
+            self.acl_file.display(),
+            table.plain_access_resource_map.len()
+        );
+        Ok(())
+    }
+
+    /// Reloads the acl file whenever its modification time changes, until the manager is shut
+    /// down or dropped.
+    pub fn start_watch(self: &Arc<Self>) {
+        let manager = Arc::downgrade(self);
+        let result = thread::Builder::new()
+            .name("AclFileWatchService".to_string())
+            .spawn(move || loop {
+                thread::sleep(WATCH_INTERVAL);
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if manager.shutdown.load(Ordering::Acquire) {
+                    break;
+                }
+                manager.reload_if_changed();
+            });
+        if let Err(e) = result {
+            error!("start acl file watch service failed: {}", e);
+        }
+    }
+
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+    }
+
+    fn reload_if_changed(&self) {
+        let Some(modified) = self.file_modified_time() else {
+            return;
+        };
+        if *self.last_modified.lock() == Some(modified) {
+            return;
+        }
+        info!("acl file {} changed, reload it", self.acl_file.display());
+        if let Err(e) = self.load() {
+            error!("reload acl file {} failed: {}", self.acl_file.display(), e);
+            *self.last_modified.lock() = Some(modified);
+        }
+    }
+
+    fn file_modified_time(&self) -> Option<SystemTime> {
+        fs::metadata(&self.acl_file)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn build_table(access_data: PlainAccessData) -> AclResult<AclTable> {
+        let mut global_white_remote_address_strategy =
+            Vec::with_capacity(access_data.global_white_remote_addresses.len());
+        for address in &access_data.global_white_remote_addresses {
+            global_white_remote_address_strategy
+                .push(RemoteAddressStrategy::parse(Some(address.as_str()))?);
+        }
+        let mut plain_access_resource_map = HashMap::with_capacity(access_data.accounts.len());
+        for account in &access_data.accounts {
+            let resource = Self::build_plain_access_resource(account)?;
+            let access_key = resource.access_key.clone().unwrap_or_default();
+            if plain_access_resource_map
+                .insert(access_key.clone(), resource)
+                .is_some()
+            {
+                return Err(AclError::InvalidConfig(format!(
+                    "The accessKey {} is repeated in the acl file",
+                    access_key
+                )));
+            }
+        }
+        Ok(AclTable {
+            global_white_remote_address_strategy,
+            plain_access_resource_map,
+            access_data,
+        })
+    }
+
+    /// Builds the resources an account owns.
+    pub fn build_plain_access_resource(
+        config: &PlainAccessConfig,
+    ) -> AclResult<PlainAccessResource> {
+        let (Some(access_key), Some(secret_key)) = (&config.access_key, &config.secret_key) else {
+            return Err(AclError::InvalidConfig(
+                "The accessKey and secretKey cannot be null".to_string(),
+            ));
+        };
+        if access_key.len() <= 6 || secret_key.len() <= 6 {
+            return Err(AclError::InvalidConfig(format!(
+                "The accessKey={} and secretKey cannot be null and length should longer than 6",
+                access_key
+            )));
+        }
+        permission::check_resource_perms(&config.topic_perms)?;
+        permission::check_resource_perms(&config.group_perms)?;
+        let mut resource = PlainAccessResource {
+            access_key: Some(access_key.clone()),
+            secret_key: Some(secret_key.clone()),
+            white_remote_address: config.white_remote_address.clone(),
+            admin: config.admin,
+            default_topic_perm: permission::parse_perm_from_string(
+                config.default_topic_perm.as_deref(),
+            ),
+            default_group_perm: permission::parse_perm_from_string(
+                config.default_group_perm.as_deref(),
+            ),
+            remote_address_strategy: Some(RemoteAddressStrategy::parse(
+                config.white_remote_address.as_deref(),
+            )?),
+            ..PlainAccessResource::default()
+        };
+        permission::parse_resource_perms(&mut resource, false, &config.group_perms)?;
+        permission::parse_resource_perms(&mut resource, true, &config.topic_perms)?;
+        Ok(resource)
+    }
+
+    pub fn validate(&self, plain_access_resource: &PlainAccessResource) -> AclResult<()> {
+        let table = self.table.read();
+        let remote_address = plain_access_resource.white_remote_address.as_deref();
+        // the global white list skips all other checks
+        if table
+            .global_white_remote_address_strategy
+            .iter()
+            .any(|strategy| strategy.matches(remote_address))
+        {
+            return Ok(());
+        }
+        let Some(access_key) = plain_access_resource.access_key.as_ref() else {
+            return Err(AclError::AccessDenied(
+                "No accessKey is configured".to_string(),
+            ));
+        };
+        let Some(owned_access) = table.plain_access_resource_map.get(access_key) else {
+            return Err(AclError::AccessDenied(format!(
+                "No acl config for {}",
+                access_key
+            )));
+        };
+        // so does the white list of the account
+        if owned_access
+            .remote_address_strategy
+            .as_ref()
+            .is_some_and(|strategy| strategy.matches(remote_address))
+        {
+            return Ok(());
+        }
+        let signature_matched =
+            plain_access_resource
+                .signature
+                .as_deref()
+                .is_some_and(|signature| {
+                    acl_utils::verify_signature(
+                        &plain_access_resource.content,
+                        owned_access.secret_key.as_deref().unwrap_or_default(),
+                        signature,
+                    )
+                });
+        if !signature_matched {
+            return Err(AclError::AccessDenied(format!(
+                "Check signature failed for accessKey={}",
+                access_key
+            )));
+        }
+        Self::check_perm(plain_access_resource, owned_access)
+    }
+
+    fn check_perm(
+        need_checked_access: &PlainAccessResource,
+        owned_access: &PlainAccessResource,
+    ) -> AclResult<()> {
+        if permission::need_admin_perm(need_checked_access.request_code) && !owned_access.admin {
+            return Err(AclError::AccessDenied(format!(
+                "Need admin permission for request code={}, but accessKey={} is not",
+                need_checked_access.request_code,
+                owned_access.access_key.as_deref().unwrap_or_default()
+            )));
+        }
+        if owned_access.resource_perm_map.is_empty() && owned_access.admin {
+            return Ok(());
+        }
+        for (resource, needed_perm) in &need_checked_access.resource_perm_map {
+            let is_group = PlainAccessResource::is_retry_topic(resource);
+            match owned_access.resource_perm_map.get(resource) {
+                None => {
+                    let owned_perm = if is_group {
+                        owned_access.default_group_perm
+                    } else {
+                        owned_access.default_topic_perm
+                    };
+                    if !permission::check_permission(*needed_perm, owned_perm) {
+                        return Err(AclError::AccessDenied(format!(
+                            "No default permission for {}",
+                            PlainAccessResource::print_str(resource, is_group)
+                        )));
+                    }
+                }
+                Some(owned_perm) => {
+                    if !permission::check_permission(*needed_perm, *owned_perm) {
+                        return Err(AclError::AccessDenied(format!(
+                            "No permission for {}",
+                            PlainAccessResource::print_str(resource, is_group)
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the account of `config.access_key`, or replaces it.
+    pub fn update_access_config(&self, config: PlainAccessConfig) -> AclResult<()> {
+        Self::build_plain_access_resource(&config)?;
+        self.update_access_data(|access_data| {
+            match access_data
+                .accounts
+                .iter_mut()
+                .find(|account| account.access_key == config.access_key)
+            {
+                Some(account) => *account = config,
+                None => access_data.accounts.push(config),
+            }
+            Ok(())
+        })
+    }
+
+    pub fn delete_access_config(&self, access_key: &str) -> AclResult<()> {
+        self.update_access_data(|access_data| {
+            let accounts = access_data.accounts.len();
+            access_data
+                .accounts
+                .retain(|account| account.access_key.as_deref() != Some(access_key));
+            if accounts == access_data.accounts.len() {
+                return Err(AclError::InvalidConfig(format!(
+                    "No acl config for accessKey={}",
+                    access_key
+                )));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn update_global_white_addrs_config(
+        &self,
+        global_white_addrs: Vec<CheetahString>,
+    ) -> AclResult<()> {
+        self.update_access_data(|access_data| {
+            access_data.global_white_remote_addresses = global_white_addrs;
+            Ok(())
+        })
+    }
+
+    fn update_access_data(
+        &self,
+        update: impl FnOnce(&mut PlainAccessData) -> AclResult<()>,
+    ) -> AclResult<()> {
+        let mut table = self.table.write();
+        let mut access_data = table.access_data.clone();
+        update(&mut access_data)?;
+        let new_table = Self::build_table(access_data)?;
+        let data = serde_json::to_value(&new_table.access_data)
+            .map_err(|e| AclError::InvalidConfig(e.to_string()))?;
+        acl_utils::write_data_object(&self.acl_file, &data)?;
+        *table = new_table;
+        *self.last_modified.lock() = self.file_modified_time();
+        self.data_version.lock().next_version();
+        Ok(())
+    }
+
+    pub fn get_data_version(&self) -> DataVersion {
+        self.data_version.lock().clone()
+    }
+
+    /// The data version of every acl file, keyed by the file path.
+    pub fn get_all_acl_config_version(&self) -> HashMap<CheetahString, DataVersion> {
+        HashMap::from([(
+            CheetahString::from_string(self.acl_file.display().to_string()),
+            self.get_data_version(),
+        )])
+    }
+}
+
+impl Drop for PlainPermissionManager {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    const ACL_FILE: &str = r#"
+globalWhiteRemoteAddresses:
+  - 10.10.103.*
+accounts:
+  - accessKey: RocketMQ
+    secretKey: 12345678
+    whiteRemoteAddress:
+    admin: false
+    defaultTopicPerm: DENY
+    defaultGroupPerm: SUB
+    topicPerms:
+      - topicA=DENY
+      - topicB=PUB|SUB
+    groupPerms:
+      - groupA=DENY
+  - accessKey: rocketmq2
+    secretKey: 12345678
+    whiteRemoteAddress: 192.168.1.*
+    admin: true
+"#;
+
+    fn new_manager(dir: &Path) -> PlainPermissionManager {
+        let acl_file = dir.join("plain_acl.yml");
+        fs::write(&acl_file, ACL_FILE).unwrap();
+        let manager = PlainPermissionManager::new(acl_file);
+        manager.load().unwrap();
+        manager
+    }
+
+    fn signed_resource(
+        access_key: &str,
+        secret_key: &str,
+        remote_address: &str,
+    ) -> PlainAccessResource {
+        let content = b"content".to_vec();
+        PlainAccessResource {
+            access_key: Some(access_key.into()),
+            white_remote_address: Some(remote_address.into()),
+            signature: Some(acl_utils::cal_signature(&content, secret_key).into()),
+            content,
+            ..PlainAccessResource::default()
+        }
+    }
+
+    #[test]
+    fn validate_checks_white_list_signature_and_perms() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = new_manager(dir.path());
+
+        // global white list and account white list skip the signature
+        assert!(manager
+            .validate(&signed_resource("unknown", "wrong", "10.10.103.20"))
+            .is_ok());
+        assert!(manager
+            .validate(&signed_resource("rocketmq2", "wrong", "192.168.1.20"))
+            .is_ok());
+
+        assert!(manager
+            .validate(&signed_resource("RocketMQ", "wrong", "127.0.0.1"))
+            .is_err());
+        let mut resource = signed_resource("RocketMQ", "12345678", "127.0.0.1");
+        resource.add_resource_and_perm(Some("topicB"), permission::PUB);
+        resource.add_resource_and_perm(Some("%RETRY%groupB"), permission::SUB);
+        assert!(manager.validate(&resource).is_ok());
+
+        resource.add_resource_and_perm(Some("topicA"), permission::PUB);
+        assert!(manager.validate(&resource).is_err());
+
+        let mut resource = signed_resource("RocketMQ", "12345678", "127.0.0.1");
+        resource.add_resource_and_perm(Some("topicC"), permission::SUB);
+        assert!(matches!(
+            manager.validate(&resource),
+            Err(AclError::AccessDenied(message)) if message == "No default permission for topic:topicC"
+        ));
+
+        let mut resource = signed_resource("RocketMQ", "12345678", "127.0.0.1");
+        resource.request_code = 17;
+        assert!(manager.validate(&resource).is_err());
+    }
+
+    #[test]
+    fn update_and_delete_access_config_rewrite_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = new_manager(dir.path());
+        let counter = manager.get_data_version().get_counter();
+
+        manager
+            .update_access_config(PlainAccessConfig {
+                access_key: Some("new_access_key".into()),
+                secret_key: Some("new_secret_key".into()),
+                default_topic_perm: Some("PUB".into()),
+                ..PlainAccessConfig::default()
+            })
+            .unwrap();
+        assert_eq!(manager.get_data_version().get_counter(), counter + 1);
+        assert!(manager
+            .update_access_config(PlainAccessConfig {
+                access_key: Some("short".into()),
+                secret_key: Some("short".into()),
+                ..PlainAccessConfig::default()
+            })
+            .is_err());
+
+        let mut resource = signed_resource("new_access_key", "new_secret_key", "127.0.0.1");
+        resource.add_resource_and_perm(Some("topicC"), permission::PUB);
+        assert!(manager.validate(&resource).is_ok());
+
+        manager.delete_access_config("RocketMQ").unwrap();
+        assert!(manager.delete_access_config("RocketMQ").is_err());
+        manager
+            .update_global_white_addrs_config(vec!["127.0.0.1".into()])
+            .unwrap();
+
+        let reloaded = PlainPermissionManager::new(manager.acl_file());
+        reloaded.load().unwrap();
+        let table = reloaded.table.read();
+        assert_eq!(
+            table.access_data.global_white_remote_addresses,
+            vec![CheetahString::from("127.0.0.1")]
+        );
+        let access_keys = table
+            .plain_access_resource_map
+            .keys()
+            .map(|key| (key.to_string(), ()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            access_keys.into_keys().collect::<Vec<_>>(),
+            vec!["new_access_key".to_string(), "rocketmq2".to_string()]
+        );
+    }
+
+    #[test]
+    fn watch_reloads_changed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Arc::new(new_manager(dir.path()));
+        manager.start_watch();
+        let counter = manager.get_data_version().get_counter();
+
+        let content = ACL_FILE.replace("rocketmq2", "rocketmq3");
+        // make sure the modification time changes on file systems with coarse timestamps
+        thread::sleep(Duration::from_millis(1100));
+        fs::write(manager.acl_file(), content).unwrap();
+        let deadline = SystemTime::now() + Duration::from_secs(5);
+        while manager.get_data_version().get_counter() == counter && SystemTime::now() < deadline {
+            thread::sleep(Duration::from_millis(100));
+        }
+        assert!(manager
+            .table
+            .read()
+            .plain_access_resource_map
+            .contains_key("rocketmq3"));
+        manager.shutdown();
+    }
+}
@@ -0,0 +1,187 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+
+use crate::acl_error::AclError;
+use crate::acl_error::AclResult;
+
+/// How an account, or the global white list, matches the remote address of a request.
+///
+/// Supported formats are `*` for any address, a single address, a comma separated list such as
+/// `192.168.0.1,192.168.0.2` or `192.168.0.{1,2}`, and IPv4 ranges such as `192.168.1.*` or
+/// `192.168.1.1-100`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteAddressStrategy {
+    /// No address configured, matches nothing. Java calls this the blank strategy.
+    None,
+    /// `*`, matches every address. Java calls this the null strategy.
+    Any,
+    One(String),
+    Multiple(HashSet<String>),
+    /// The inclusive range of each IPv4 segment.
+    Range([(u8, u8); 4]),
+}
+
+impl RemoteAddressStrategy {
+    pub fn parse(remote_addr: Option<&str>) -> AclResult<Self> {
+        let remote_addr = match remote_addr.map(str::trim) {
+            None | Some("") => return Ok(RemoteAddressStrategy::None),
+            Some(remote_addr) => remote_addr,
+        };
+        if matches!(remote_addr, "*" | "*.*.*.*" | "*:*:*:*:*:*:*:*") {
+            return Ok(RemoteAddressStrategy::Any);
+        }
+        if remote_addr.contains(',') {
+            let addresses = match remote_addr.split_once('{') {
+                Some((prefix, rest)) => rest
+                    .trim_end_matches('}')
+                    .split(',')
+                    .map(|item| format!("{}{}", prefix, item.trim()))
+                    .collect::<Vec<_>>(),
+                None => remote_addr
+                    .split(',')
+                    .map(|item| item.trim().to_string())
+                    .collect(),
+            };
+            let mut set = HashSet::with_capacity(addresses.len());
+            for address in addresses {
+                set.insert(normalize(&address).ok_or_else(|| invalid(remote_addr))?);
+            }
+            return Ok(RemoteAddressStrategy::Multiple(set));
+        }
+        if let Some(address) = normalize(remote_addr) {
+            return Ok(RemoteAddressStrategy::One(address));
+        }
+        if remote_addr.contains('*') || remote_addr.contains('-') {
+            return parse_range(remote_addr).ok_or_else(|| invalid(remote_addr));
+        }
+        Err(invalid(remote_addr))
+    }
+
+    pub fn matches(&self, remote_addr: Option<&str>) -> bool {
+        let remote_addr = match (self, remote_addr) {
+            (RemoteAddressStrategy::Any, _) => return true,
+            (_, None) => return false,
+            (_, Some(remote_addr)) => remote_addr,
+        };
+        match self {
+            RemoteAddressStrategy::None => false,
+            RemoteAddressStrategy::Any => true,
+            RemoteAddressStrategy::One(address) => {
+                normalize(remote_addr).is_some_and(|remote_addr| &remote_addr == address)
+            }
+            RemoteAddressStrategy::Multiple(addresses) => {
+                normalize(remote_addr).is_some_and(|remote_addr| addresses.contains(&remote_addr))
+            }
+            RemoteAddressStrategy::Range(segments) => {
+                remote_addr.parse::<Ipv4Addr>().is_ok_and(|remote_addr| {
+                    remote_addr
+                        .octets()
+                        .iter()
+                        .zip(segments)
+                        .all(|(octet, (start, end))| start <= octet && octet <= end)
+                })
+            }
+        }
+    }
+}
+
+fn normalize(address: &str) -> Option<String> {
+    address
+        .parse::<IpAddr>()
+        .ok()
+        .map(|address| address.to_string())
+}
+
+fn parse_range(remote_addr: &str) -> Option<RemoteAddressStrategy> {
+    let parts = remote_addr.split('.').collect::<Vec<_>>();
+    if parts.len() > 4 || (parts.len() < 4 && parts.last() != Some(&"*")) {
+        return None;
+    }
+    let mut segments = [(0u8, u8::MAX); 4];
+    for (segment, part) in segments.iter_mut().zip(&parts) {
+        *segment = match *part {
+            "*" => (0, u8::MAX),
+            part => match part.split_once('-') {
+                Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                None => {
+                    let value = part.parse().ok()?;
+                    (value, value)
+                }
+            },
+        };
+        if segment.0 > segment.1 {
+            return None;
+        }
+    }
+    Some(RemoteAddressStrategy::Range(segments))
+}
+
+fn invalid(remote_addr: &str) -> AclError {
+    AclError::InvalidConfig(format!(
+        "Netaddress examine scope Exception netaddress: {}",
+        remote_addr
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_match_addresses() {
+        let none = RemoteAddressStrategy::parse(None).unwrap();
+        assert_eq!(none, RemoteAddressStrategy::None);
+        assert!(!none.matches(Some("127.0.0.1")));
+        assert_eq!(
+            RemoteAddressStrategy::parse(Some(" ")).unwrap(),
+            RemoteAddressStrategy::None
+        );
+
+        let any = RemoteAddressStrategy::parse(Some("*")).unwrap();
+        assert_eq!(any, RemoteAddressStrategy::Any);
+        assert!(any.matches(Some("10.0.0.1")));
+        assert!(any.matches(None));
+
+        let one = RemoteAddressStrategy::parse(Some("127.0.0.1")).unwrap();
+        assert!(one.matches(Some("127.0.0.1")));
+        assert!(!one.matches(Some("127.0.0.2")));
+
+        let multiple = RemoteAddressStrategy::parse(Some("192.168.0.{1,2}")).unwrap();
+        assert!(multiple.matches(Some("192.168.0.2")));
+        assert!(!multiple.matches(Some("192.168.0.3")));
+        let multiple = RemoteAddressStrategy::parse(Some("10.0.0.1, 10.0.0.9")).unwrap();
+        assert!(multiple.matches(Some("10.0.0.9")));
+
+        let range = RemoteAddressStrategy::parse(Some("192.168.1-10.*")).unwrap();
+        assert!(range.matches(Some("192.168.5.200")));
+        assert!(!range.matches(Some("192.168.11.1")));
+        let range = RemoteAddressStrategy::parse(Some("10.*")).unwrap();
+        assert!(range.matches(Some("10.3.2.1")));
+        assert!(!range.matches(None));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_addresses() {
+        assert!(RemoteAddressStrategy::parse(Some("192.168.0.{1,abc}")).is_err());
+        assert!(RemoteAddressStrategy::parse(Some("192.168.10-1.*")).is_err());
+        assert!(RemoteAddressStrategy::parse(Some("192.168")).is_err());
+        assert!(RemoteAddressStrategy::parse(Some("192.168.1")).is_err());
+    }
+}
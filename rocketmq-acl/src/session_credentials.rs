@@ -0,0 +1,52 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+
+use crate::acl_utils;
+
+pub const ACCESS_KEY: &str = "AccessKey";
+pub const SECRET_KEY: &str = "SecretKey";
+pub const SIGNATURE: &str = "Signature";
+pub const SECURITY_TOKEN: &str = "SecurityToken";
+
+/// The credentials a client signs its requests with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionCredentials {
+    pub access_key: CheetahString,
+    pub secret_key: CheetahString,
+    pub security_token: Option<CheetahString>,
+}
+
+impl SessionCredentials {
+    pub fn new(access_key: impl Into<CheetahString>, secret_key: impl Into<CheetahString>) -> Self {
+        Self {
+            access_key: access_key.into(),
+            secret_key: secret_key.into(),
+            security_token: None,
+        }
+    }
+
+    /// Credentials of an ACL 2.0 user, which signs with a key derived from its password.
+    pub fn of_user(username: &str, password: &str) -> Self {
+        Self::new(username, acl_utils::user_signing_key(username, password))
+    }
+
+    pub fn with_security_token(mut self, security_token: impl Into<CheetahString>) -> Self {
+        self.security_token = Some(security_token.into());
+        self
+    }
+}
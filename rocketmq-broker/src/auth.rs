@@ -0,0 +1,40 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Authentication and authorization of the requests a broker serves, the ACL 2.0 model: users
+//! authenticate by signing requests with their password, and the policies of a user decide which
+//! actions it may take on topics, groups and the cluster.
+
+pub(crate) mod acl;
+pub(crate) mod auth_metadata_manager;
+pub(crate) mod auth_server_rpc_hook;
+pub(crate) mod authentication;
+pub(crate) mod authorization;
+pub(crate) mod user;
+
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum AuthError {
+    #[error("{0}")]
+    Authentication(String),
+
+    #[error("{0}")]
+    Authorization(String),
+
+    #[error("{0}")]
+    InvalidMetadata(String),
+}
+
+pub(crate) type AuthResult<T> = Result<T, AuthError>;
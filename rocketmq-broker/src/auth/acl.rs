@@ -0,0 +1,691 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt::Display;
+use std::net::IpAddr;
+
+use cheetah_string::CheetahString;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+use rocketmq_remoting::protocol::body::acl_info::PolicyEntryInfo;
+use rocketmq_remoting::protocol::body::acl_info::PolicyInfo;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+
+use crate::auth::AuthError;
+use crate::auth::AuthResult;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ResourceType {
+    /// Matches the resources of every type.
+    Any,
+    Cluster,
+    Namespace,
+    Topic,
+    Group,
+}
+
+impl ResourceType {
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            ResourceType::Any,
+            ResourceType::Cluster,
+            ResourceType::Namespace,
+            ResourceType::Topic,
+            ResourceType::Group,
+        ]
+        .into_iter()
+        .find(|resource_type| resource_type.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResourceType::Any => "Any",
+            ResourceType::Cluster => "Cluster",
+            ResourceType::Namespace => "Namespace",
+            ResourceType::Topic => "Topic",
+            ResourceType::Group => "Group",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum ResourcePattern {
+    Any,
+    Literal,
+    Prefixed,
+}
+
+/// A resource, or a pattern of resources, written as `Type:name`. `*` stands for every resource,
+/// `Type:*` for every resource of a type and `Type:prefix*` for the resources whose names start
+/// with the prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Resource {
+    pub resource_type: ResourceType,
+    pub resource_name: CheetahString,
+    pub resource_pattern: ResourcePattern,
+}
+
+impl Resource {
+    pub fn of(resource_type: ResourceType, resource_name: impl Into<CheetahString>) -> Self {
+        Self {
+            resource_type,
+            resource_name: resource_name.into(),
+            resource_pattern: ResourcePattern::Literal,
+        }
+    }
+
+    pub fn topic(topic: impl Into<CheetahString>) -> Self {
+        Self::of(ResourceType::Topic, topic)
+    }
+
+    pub fn group(group: impl Into<CheetahString>) -> Self {
+        Self::of(ResourceType::Group, group)
+    }
+
+    pub fn cluster(cluster: impl Into<CheetahString>) -> Self {
+        Self::of(ResourceType::Cluster, cluster)
+    }
+
+    pub fn parse(resource: &str) -> AuthResult<Self> {
+        let resource = resource.trim();
+        if resource == "*" {
+            return Ok(Self {
+                resource_type: ResourceType::Any,
+                resource_name: CheetahString::empty(),
+                resource_pattern: ResourcePattern::Any,
+            });
+        }
+        let invalid =
+            || AuthError::InvalidMetadata(format!("The resource {} is invalid", resource));
+        let (resource_type, resource_name) = resource.split_once(':').ok_or_else(invalid)?;
+        let resource_type = ResourceType::parse(resource_type).ok_or_else(invalid)?;
+        if resource_type == ResourceType::Any || resource_name.is_empty() {
+            return Err(invalid());
+        }
+        let (resource_name, resource_pattern) = if resource_name == "*" {
+            ("", ResourcePattern::Any)
+        } else if let Some(prefix) = resource_name.strip_suffix('*') {
+            (prefix, ResourcePattern::Prefixed)
+        } else {
+            (resource_name, ResourcePattern::Literal)
+        };
+        Ok(Self {
+            resource_type,
+            resource_name: resource_name.into(),
+            resource_pattern,
+        })
+    }
+
+    /// Whether `resource`, a literal resource, is one of the resources this one designates.
+    pub fn is_match(&self, resource: &Resource) -> bool {
+        if self.resource_type == ResourceType::Any {
+            return true;
+        }
+        if self.resource_type != resource.resource_type {
+            return false;
+        }
+        match self.resource_pattern {
+            ResourcePattern::Any => true,
+            ResourcePattern::Literal => self.resource_name == resource.resource_name,
+            ResourcePattern::Prefixed => resource
+                .resource_name
+                .starts_with(self.resource_name.as_str()),
+        }
+    }
+
+    /// Entries of more specific resources take precedence.
+    fn priority(&self) -> (u8, usize) {
+        match (self.resource_type, self.resource_pattern) {
+            (ResourceType::Any, _) => (0, 0),
+            (_, ResourcePattern::Any) => (1, 0),
+            (_, ResourcePattern::Prefixed) => (2, self.resource_name.len()),
+            (_, ResourcePattern::Literal) => (3, 0),
+        }
+    }
+}
+
+impl Display for Resource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.resource_type, self.resource_pattern) {
+            (ResourceType::Any, _) => write!(f, "*"),
+            (resource_type, ResourcePattern::Any) => write!(f, "{}:*", resource_type.name()),
+            (resource_type, ResourcePattern::Prefixed) => {
+                write!(f, "{}:{}*", resource_type.name(), self.resource_name)
+            }
+            (resource_type, ResourcePattern::Literal) => {
+                write!(f, "{}:{}", resource_type.name(), self.resource_name)
+            }
+        }
+    }
+}
+
+impl Serialize for Resource {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Resource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let resource = String::deserialize(deserializer)?;
+        Resource::parse(&resource).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) enum Action {
+    /// Grants or denies every action.
+    All,
+    Pub,
+    Sub,
+    Create,
+    Update,
+    Delete,
+    Get,
+    List,
+}
+
+impl Action {
+    pub fn parse(name: &str) -> Option<Self> {
+        [
+            Action::All,
+            Action::Pub,
+            Action::Sub,
+            Action::Create,
+            Action::Update,
+            Action::Delete,
+            Action::Get,
+            Action::List,
+        ]
+        .into_iter()
+        .find(|action| action.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::All => "All",
+            Action::Pub => "Pub",
+            Action::Sub => "Sub",
+            Action::Create => "Create",
+            Action::Update => "Update",
+            Action::Delete => "Delete",
+            Action::Get => "Get",
+            Action::List => "List",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Decision {
+    #[default]
+    Allow,
+    Deny,
+}
+
+impl Decision {
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("Allow") {
+            Some(Decision::Allow)
+        } else if name.eq_ignore_ascii_case("Deny") {
+            Some(Decision::Deny)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Decision::Allow => "Allow",
+            Decision::Deny => "Deny",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum PolicyType {
+    #[default]
+    Custom,
+    Default,
+}
+
+impl PolicyType {
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("Custom") {
+            Some(PolicyType::Custom)
+        } else if name.eq_ignore_ascii_case("Default") {
+            Some(PolicyType::Default)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PolicyType::Custom => "Custom",
+            PolicyType::Default => "Default",
+        }
+    }
+}
+
+/// The conditions an entry applies under, an empty list of source ips matches every address.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Environment {
+    #[serde(default)]
+    pub source_ips: Vec<CheetahString>,
+}
+
+impl Environment {
+    pub fn is_match(&self, source_ip: Option<IpAddr>) -> bool {
+        if self.source_ips.is_empty() {
+            return true;
+        }
+        let Some(source_ip) = source_ip else {
+            return false;
+        };
+        self.source_ips
+            .iter()
+            .any(|pattern| ip_matches(pattern, source_ip))
+    }
+}
+
+/// Matches an ip against a single address or a CIDR block.
+fn ip_matches(pattern: &str, ip: IpAddr) -> bool {
+    let Some((network, prefix_len)) = pattern.split_once('/') else {
+        return pattern.parse::<IpAddr>().is_ok_and(|address| address == ip);
+    };
+    let (Ok(network), Ok(prefix_len)) = (network.parse::<IpAddr>(), prefix_len.parse::<u32>())
+    else {
+        return false;
+    };
+    match (network, ip) {
+        (IpAddr::V4(network), IpAddr::V4(ip)) if prefix_len <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix_len).unwrap_or(0);
+            u32::from(network) & mask == u32::from(ip) & mask
+        }
+        (IpAddr::V6(network), IpAddr::V6(ip)) if prefix_len <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix_len).unwrap_or(0);
+            u128::from(network) & mask == u128::from(ip) & mask
+        }
+        _ => false,
+    }
+}
+
+fn validate_source_ip(pattern: &str) -> bool {
+    match pattern.split_once('/') {
+        None => pattern.parse::<IpAddr>().is_ok(),
+        Some((network, prefix_len)) => match network.parse::<IpAddr>() {
+            Ok(IpAddr::V4(_)) => prefix_len.parse::<u32>().is_ok_and(|len| len <= 32),
+            Ok(IpAddr::V6(_)) => prefix_len.parse::<u32>().is_ok_and(|len| len <= 128),
+            Err(_) => false,
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PolicyEntry {
+    pub resource: Resource,
+    pub actions: Vec<Action>,
+    #[serde(default)]
+    pub environment: Environment,
+    pub decision: Decision,
+}
+
+impl PolicyEntry {
+    pub fn is_match(&self, resource: &Resource, action: Action, source_ip: Option<IpAddr>) -> bool {
+        self.resource.is_match(resource)
+            && self
+                .actions
+                .iter()
+                .any(|owned| *owned == Action::All || *owned == action)
+            && self.environment.is_match(source_ip)
+    }
+
+    fn from_info(entry: &PolicyEntryInfo) -> AuthResult<Self> {
+        let resource = Resource::parse(entry.resource.as_deref().unwrap_or_default())?;
+        let actions = entry
+            .actions
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|action| !action.trim().is_empty())
+            .map(|action| {
+                Action::parse(action).ok_or_else(|| {
+                    AuthError::InvalidMetadata(format!("The action {} is unknown", action))
+                })
+            })
+            .collect::<AuthResult<Vec<_>>>()?;
+        if actions.is_empty() {
+            return Err(AuthError::InvalidMetadata(format!(
+                "The actions of {} are empty",
+                resource
+            )));
+        }
+        let source_ips = entry.source_ips.clone().unwrap_or_default();
+        if let Some(source_ip) = source_ips.iter().find(|ip| !validate_source_ip(ip)) {
+            return Err(AuthError::InvalidMetadata(format!(
+                "The source ip {} is invalid",
+                source_ip
+            )));
+        }
+        let decision = entry.decision.as_deref().ok_or_else(|| {
+            AuthError::InvalidMetadata(format!("The decision of {} is missing", resource))
+        })?;
+        let decision = Decision::parse(decision).ok_or_else(|| {
+            AuthError::InvalidMetadata(format!("The decision {} is unknown", decision))
+        })?;
+        Ok(Self {
+            resource,
+            actions,
+            environment: Environment { source_ips },
+            decision,
+        })
+    }
+
+    fn to_info(&self) -> PolicyEntryInfo {
+        PolicyEntryInfo {
+            resource: Some(self.resource.to_string().into()),
+            actions: Some(
+                self.actions
+                    .iter()
+                    .map(Action::name)
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    .into(),
+            ),
+            source_ips: Some(self.environment.source_ips.clone()),
+            decision: Some(self.decision.name().into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Policy {
+    pub policy_type: PolicyType,
+    pub entries: Vec<PolicyEntry>,
+}
+
+impl Policy {
+    /// Replaces the entries of the same resources, and adds the others.
+    fn update_entries(&mut self, entries: Vec<PolicyEntry>) {
+        for entry in entries {
+            match self
+                .entries
+                .iter_mut()
+                .find(|owned| owned.resource == entry.resource)
+            {
+                Some(owned) => *owned = entry,
+                None => self.entries.push(entry),
+            }
+        }
+    }
+}
+
+/// The policies of a subject.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Acl {
+    pub subject: CheetahString,
+    pub policies: Vec<Policy>,
+}
+
+impl Acl {
+    pub fn from_info(subject: &CheetahString, acl_info: &AclInfo) -> AuthResult<Self> {
+        let mut acl = Acl {
+            subject: subject.clone(),
+            policies: Vec::new(),
+        };
+        for policy in acl_info.policies.as_deref().unwrap_or_default() {
+            let policy_type = match policy.policy_type.as_deref() {
+                None | Some("") => PolicyType::Custom,
+                Some(policy_type) => PolicyType::parse(policy_type).ok_or_else(|| {
+                    AuthError::InvalidMetadata(format!(
+                        "The policy type {} is unknown",
+                        policy_type
+                    ))
+                })?,
+            };
+            let entries = policy
+                .entries
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(PolicyEntry::from_info)
+                .collect::<AuthResult<Vec<_>>>()?;
+            acl.update_policy(Policy {
+                policy_type,
+                entries,
+            });
+        }
+        if acl.policies.iter().all(|policy| policy.entries.is_empty()) {
+            return Err(AuthError::InvalidMetadata(format!(
+                "The policies of {} are empty",
+                subject
+            )));
+        }
+        Ok(acl)
+    }
+
+    pub fn to_info(&self) -> AclInfo {
+        AclInfo {
+            subject: Some(self.subject.clone()),
+            policies: Some(
+                self.policies
+                    .iter()
+                    .map(|policy| PolicyInfo {
+                        policy_type: Some(policy.policy_type.name().into()),
+                        entries: Some(policy.entries.iter().map(PolicyEntry::to_info).collect()),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Merges `policy` into the policy of the same type.
+    pub fn update_policy(&mut self, policy: Policy) {
+        match self
+            .policies
+            .iter_mut()
+            .find(|owned| owned.policy_type == policy.policy_type)
+        {
+            Some(owned) => owned.update_entries(policy.entries),
+            None => self.policies.push(policy),
+        }
+    }
+
+    /// Removes the entries of `resource`, of every policy unless `policy_type` is set. Returns
+    /// whether any entry was removed.
+    pub fn delete_policy(&mut self, policy_type: Option<PolicyType>, resource: &Resource) -> bool {
+        let mut deleted = false;
+        for policy in &mut self.policies {
+            if policy_type.is_some_and(|policy_type| policy_type != policy.policy_type) {
+                continue;
+            }
+            let entries = policy.entries.len();
+            policy.entries.retain(|entry| entry.resource != *resource);
+            deleted |= entries != policy.entries.len();
+        }
+        self.policies.retain(|policy| !policy.entries.is_empty());
+        deleted
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// The entry deciding whether the subject may take `action` on `resource`: custom policies
+    /// come before default ones, and more specific resources before less specific ones.
+    pub fn match_entry(
+        &self,
+        resource: &Resource,
+        action: Action,
+        source_ip: Option<IpAddr>,
+    ) -> Option<&PolicyEntry> {
+        for policy_type in [PolicyType::Custom, PolicyType::Default] {
+            let entry = self
+                .policies
+                .iter()
+                .filter(|policy| policy.policy_type == policy_type)
+                .flat_map(|policy| policy.entries.iter())
+                .filter(|entry| entry.is_match(resource, action, source_ip))
+                // on equal priority the deny entry wins
+                .max_by_key(|entry| (entry.resource.priority(), entry.decision == Decision::Deny));
+            if entry.is_some() {
+                return entry;
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        resource: &str,
+        actions: &str,
+        source_ips: &[&str],
+        decision: &str,
+    ) -> PolicyEntryInfo {
+        PolicyEntryInfo {
+            resource: Some(resource.into()),
+            actions: Some(actions.into()),
+            source_ips: Some(
+                source_ips
+                    .iter()
+                    .map(|ip| CheetahString::from(*ip))
+                    .collect(),
+            ),
+            decision: Some(decision.into()),
+        }
+    }
+
+    #[test]
+    fn parse_and_match_resources() {
+        let any = Resource::parse("*").unwrap();
+        let topics = Resource::parse("Topic:*").unwrap();
+        let prefixed = Resource::parse("Topic:order*").unwrap();
+        let literal = Resource::parse("Topic:order_a").unwrap();
+        assert_eq!(prefixed.resource_pattern, ResourcePattern::Prefixed);
+        assert_eq!(prefixed.to_string(), "Topic:order*");
+        assert_eq!(topics.to_string(), "Topic:*");
+
+        let topic = Resource::topic("order_a");
+        assert!(any.is_match(&topic));
+        assert!(topics.is_match(&topic));
+        assert!(prefixed.is_match(&topic));
+        assert!(literal.is_match(&topic));
+        assert!(!literal.is_match(&Resource::group("order_a")));
+        assert!(!prefixed.is_match(&Resource::topic("pay")));
+
+        assert!(Resource::parse("Unknown:a").is_err());
+        assert!(Resource::parse("Topic").is_err());
+        assert!(Resource::parse("Topic:").is_err());
+    }
+
+    #[test]
+    fn source_ips_match_addresses_and_cidr_blocks() {
+        let environment = Environment {
+            source_ips: vec!["192.168.0.0/16".into(), "10.0.0.1".into(), "::1".into()],
+        };
+        assert!(environment.is_match(Some("192.168.3.4".parse().unwrap())));
+        assert!(environment.is_match(Some("10.0.0.1".parse().unwrap())));
+        assert!(environment.is_match(Some("::1".parse().unwrap())));
+        assert!(!environment.is_match(Some("10.0.0.2".parse().unwrap())));
+        assert!(!environment.is_match(None));
+        assert!(Environment::default().is_match(None));
+        assert!(!validate_source_ip("192.168.0.0/33"));
+    }
+
+    #[test]
+    fn most_specific_entry_decides() {
+        let info = AclInfo {
+            subject: Some("User:rocketmq".into()),
+            policies: Some(vec![PolicyInfo {
+                policy_type: None,
+                entries: Some(vec![
+                    entry("Topic:*", "Pub,Sub", &[], "Allow"),
+                    entry("Topic:order*", "Pub", &[], "Deny"),
+                    entry("Topic:order_a", "All", &["127.0.0.1"], "Allow"),
+                ]),
+            }]),
+        };
+        let acl = Acl::from_info(&"User:rocketmq".into(), &info).unwrap();
+        let localhost = Some("127.0.0.1".parse().unwrap());
+        let remote = Some("10.0.0.1".parse().unwrap());
+
+        let decide = |topic: &str, action, ip| {
+            acl.match_entry(&Resource::topic(topic), action, ip)
+                .map(|entry| entry.decision)
+        };
+        assert_eq!(decide("pay", Action::Pub, remote), Some(Decision::Allow));
+        assert_eq!(decide("order_b", Action::Pub, remote), Some(Decision::Deny));
+        assert_eq!(
+            decide("order_b", Action::Sub, remote),
+            Some(Decision::Allow)
+        );
+        assert_eq!(
+            decide("order_a", Action::Pub, localhost),
+            Some(Decision::Allow)
+        );
+        assert_eq!(decide("order_a", Action::Pub, remote), Some(Decision::Deny));
+        assert_eq!(decide("pay", Action::Create, remote), None);
+    }
+
+    #[test]
+    fn update_and_delete_policies() {
+        let mut acl = Acl::from_info(
+            &"User:rocketmq".into(),
+            &AclInfo {
+                subject: None,
+                policies: Some(vec![PolicyInfo {
+                    policy_type: Some("Custom".into()),
+                    entries: Some(vec![entry("Topic:a", "Pub", &[], "Allow")]),
+                }]),
+            },
+        )
+        .unwrap();
+        acl.update_policy(Policy {
+            policy_type: PolicyType::Custom,
+            entries: vec![
+                PolicyEntry::from_info(&entry("Topic:a", "Sub", &[], "Deny")).unwrap(),
+                PolicyEntry::from_info(&entry("Group:g", "Sub", &[], "Allow")).unwrap(),
+            ],
+        });
+        assert_eq!(acl.policies.len(), 1);
+        assert_eq!(acl.policies[0].entries.len(), 2);
+        assert_eq!(acl.policies[0].entries[0].decision, Decision::Deny);
+
+        assert!(acl.delete_policy(None, &Resource::parse("Topic:a").unwrap()));
+        assert!(!acl.delete_policy(None, &Resource::parse("Topic:a").unwrap()));
+        assert!(acl.delete_policy(Some(PolicyType::Custom), &Resource::group("g")));
+        assert!(acl.is_empty());
+
+        let info = AclInfo {
+            subject: None,
+            policies: Some(vec![PolicyInfo {
+                policy_type: None,
+                entries: Some(vec![entry("Topic:a", "Publish", &[], "Allow")]),
+            }]),
+        };
+        assert!(Acl::from_info(&"User:rocketmq".into(), &info).is_err());
+    }
+}
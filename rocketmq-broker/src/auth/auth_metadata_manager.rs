@@ -0,0 +1,449 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use parking_lot::RwLock;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use rocketmq_store::config::message_store_config::MessageStoreConfig;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::info;
+
+use crate::auth::acl::Acl;
+use crate::auth::acl::PolicyType;
+use crate::auth::acl::Resource;
+use crate::auth::user::subject_username;
+use crate::auth::user::User;
+use crate::auth::user::UserType;
+use crate::auth::AuthError;
+use crate::auth::AuthResult;
+use crate::broker_path_config_helper;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthMetadata {
+    #[serde(default)]
+    users: HashMap<CheetahString /* username */, User>,
+    #[serde(default)]
+    acls: HashMap<CheetahString /* subject */, Acl>,
+}
+
+/// Keeps the users and the acls of the broker, every change is persisted at once.
+pub(crate) struct AuthMetadataManager {
+    message_store_config: Arc<MessageStoreConfig>,
+    metadata: RwLock<AuthMetadata>,
+}
+
+impl AuthMetadataManager {
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        Self {
+            message_store_config,
+            metadata: RwLock::new(AuthMetadata::default()),
+        }
+    }
+
+    pub fn create_user(&self, user_info: &UserInfo) -> AuthResult<()> {
+        let patch = User::from_info(user_info)?;
+        let username = patch
+            .username
+            .ok_or_else(|| AuthError::InvalidMetadata("The username is required".to_string()))?;
+        let password = patch
+            .password
+            .ok_or_else(|| AuthError::InvalidMetadata("The password is required".to_string()))?;
+        {
+            let mut metadata = self.metadata.write();
+            if metadata.users.contains_key(&username) {
+                return Err(AuthError::InvalidMetadata(format!(
+                    "The user {} is existed",
+                    username
+                )));
+            }
+            let mut user = User::of(
+                username.clone(),
+                &password,
+                patch.user_type.unwrap_or_default(),
+            );
+            user.user_status = patch.user_status.unwrap_or_default();
+            metadata.users.insert(username, user);
+        }
+        self.persist();
+        Ok(())
+    }
+
+    /// Creates `user` unless a user of the same name exists.
+    pub fn init_user(&self, user: User) {
+        {
+            let mut metadata = self.metadata.write();
+            if metadata.users.contains_key(&user.username) {
+                return;
+            }
+            info!("init the {} user {}", user.user_type.name(), user.username);
+            metadata.users.insert(user.username.clone(), user);
+        }
+        self.persist();
+    }
+
+    pub fn update_user(&self, user_info: &UserInfo) -> AuthResult<()> {
+        let patch = User::from_info(user_info)?;
+        let username = patch
+            .username
+            .ok_or_else(|| AuthError::InvalidMetadata("The username is required".to_string()))?;
+        {
+            let mut metadata = self.metadata.write();
+            let user = metadata.users.get_mut(&username).ok_or_else(|| {
+                AuthError::InvalidMetadata(format!("The user {} is not exist", username))
+            })?;
+            if let Some(password) = patch.password {
+                user.set_password(&password);
+            }
+            if let Some(user_type) = patch.user_type {
+                user.user_type = user_type;
+            }
+            if let Some(user_status) = patch.user_status {
+                user.user_status = user_status;
+            }
+        }
+        self.persist();
+        Ok(())
+    }
+
+    /// Deletes the user along with its acl.
+    pub fn delete_user(&self, username: &CheetahString) -> AuthResult<()> {
+        {
+            let mut metadata = self.metadata.write();
+            let user = metadata.users.remove(username).ok_or_else(|| {
+                AuthError::InvalidMetadata(format!("The user {} is not exist", username))
+            })?;
+            metadata.acls.remove(&user.subject());
+        }
+        self.persist();
+        Ok(())
+    }
+
+    pub fn get_user(&self, username: &str) -> Option<User> {
+        self.metadata.read().users.get(username).cloned()
+    }
+
+    /// The users whose names contain `filter`, ordered by name.
+    pub fn list_users(&self, filter: Option<&str>) -> Vec<User> {
+        let mut users: Vec<User> = self
+            .metadata
+            .read()
+            .users
+            .values()
+            .filter(|user| filter.map_or(true, |filter| user.username.contains(filter)))
+            .cloned()
+            .collect();
+        users.sort_by(|a, b| a.username.cmp(&b.username));
+        users
+    }
+
+    /// Creates the acl of a subject, or merges the policies into the existing one.
+    pub fn create_acl(&self, acl: Acl) -> AuthResult<()> {
+        self.check_subject(&acl.subject)?;
+        {
+            let mut metadata = self.metadata.write();
+            match metadata.acls.get_mut(&acl.subject) {
+                Some(owned) => acl
+                    .policies
+                    .into_iter()
+                    .for_each(|policy| owned.update_policy(policy)),
+                None => {
+                    metadata.acls.insert(acl.subject.clone(), acl);
+                }
+            }
+        }
+        self.persist();
+        Ok(())
+    }
+
+    pub fn update_acl(&self, acl: Acl) -> AuthResult<()> {
+        self.check_subject(&acl.subject)?;
+        {
+            let mut metadata = self.metadata.write();
+            let owned = metadata.acls.get_mut(&acl.subject).ok_or_else(|| {
+                AuthError::InvalidMetadata(format!("The acl of {} is not exist", acl.subject))
+            })?;
+            acl.policies
+                .into_iter()
+                .for_each(|policy| owned.update_policy(policy));
+        }
+        self.persist();
+        Ok(())
+    }
+
+    /// Deletes the entries of `resource`, or the whole acl of the subject without a resource.
+    pub fn delete_acl(
+        &self,
+        subject: &CheetahString,
+        policy_type: Option<PolicyType>,
+        resource: Option<&Resource>,
+    ) -> AuthResult<()> {
+        {
+            let mut metadata = self.metadata.write();
+            let not_exist =
+                || AuthError::InvalidMetadata(format!("The acl of {} is not exist", subject));
+            match resource {
+                None => {
+                    metadata.acls.remove(subject).ok_or_else(not_exist)?;
+                }
+                Some(resource) => {
+                    let acl = metadata.acls.get_mut(subject).ok_or_else(not_exist)?;
+                    if !acl.delete_policy(policy_type, resource) {
+                        return Err(AuthError::InvalidMetadata(format!(
+                            "The acl of {} has no policy of {}",
+                            subject, resource
+                        )));
+                    }
+                    if acl.is_empty() {
+                        metadata.acls.remove(subject);
+                    }
+                }
+            }
+        }
+        self.persist();
+        Ok(())
+    }
+
+    pub fn get_acl(&self, subject: &str) -> Option<Acl> {
+        self.metadata.read().acls.get(subject).cloned()
+    }
+
+    /// The acls whose subjects contain `subject_filter` and which hold a policy of a resource
+    /// containing `resource_filter`, ordered by subject.
+    pub fn list_acls(
+        &self,
+        subject_filter: Option<&str>,
+        resource_filter: Option<&str>,
+    ) -> Vec<Acl> {
+        let mut acls: Vec<Acl> = self
+            .metadata
+            .read()
+            .acls
+            .values()
+            .filter(|acl| subject_filter.map_or(true, |filter| acl.subject.contains(filter)))
+            .filter(|acl| {
+                resource_filter.map_or(true, |filter| {
+                    acl.policies
+                        .iter()
+                        .flat_map(|policy| policy.entries.iter())
+                        .any(|entry| entry.resource.to_string().contains(filter))
+                })
+            })
+            .cloned()
+            .collect();
+        acls.sort_by(|a, b| a.subject.cmp(&b.subject));
+        acls
+    }
+
+    /// Acls may only be bound to existing users.
+    fn check_subject(&self, subject: &str) -> AuthResult<()> {
+        let username = subject_username(subject).ok_or_else(|| {
+            AuthError::InvalidMetadata(format!("The subject {} is invalid", subject))
+        })?;
+        if !self.metadata.read().users.contains_key(username) {
+            return Err(AuthError::InvalidMetadata(format!(
+                "The subject {} is not exist",
+                subject
+            )));
+        }
+        Ok(())
+    }
+
+    /// Whether any super user exists, a broker enforcing auth without one cannot be administered.
+    pub fn has_super_user(&self) -> bool {
+        self.metadata
+            .read()
+            .users
+            .values()
+            .any(|user| user.user_type == UserType::Super)
+    }
+}
+
+impl ConfigManager for AuthMetadataManager {
+    fn config_file_path(&self) -> String {
+        broker_path_config_helper::get_auth_metadata_path(
+            self.message_store_config.store_path_root_dir.as_str(),
+        )
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let metadata = self.metadata.read();
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&*metadata).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&*metadata).expect("encode failed")
+        }
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        let metadata: AuthMetadata =
+            SerdeJsonUtils::from_json_str(json_string).expect("decode failed");
+        info!(
+            "load {} users and {} acls",
+            metadata.users.len(),
+            metadata.acls.len()
+        );
+        *self.metadata.write() = metadata;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_acl::acl_utils;
+    use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+    use rocketmq_remoting::protocol::body::acl_info::PolicyEntryInfo;
+    use rocketmq_remoting::protocol::body::acl_info::PolicyInfo;
+
+    use super::*;
+
+    fn manager(root_dir: &tempfile::TempDir) -> AuthMetadataManager {
+        let message_store_config = MessageStoreConfig {
+            store_path_root_dir: root_dir.path().to_string_lossy().to_string().into(),
+            ..MessageStoreConfig::default()
+        };
+        AuthMetadataManager::new(Arc::new(message_store_config))
+    }
+
+    fn user_info(username: &str, password: Option<&str>) -> UserInfo {
+        UserInfo {
+            username: Some(username.into()),
+            password: password.map(CheetahString::from),
+            ..UserInfo::default()
+        }
+    }
+
+    fn acl(subject: &str, resource: &str, decision: &str) -> Acl {
+        let info = AclInfo {
+            subject: Some(subject.into()),
+            policies: Some(vec![PolicyInfo {
+                policy_type: None,
+                entries: Some(vec![PolicyEntryInfo {
+                    resource: Some(resource.into()),
+                    actions: Some("Pub,Sub".into()),
+                    source_ips: None,
+                    decision: Some(decision.into()),
+                }]),
+            }]),
+        };
+        Acl::from_info(&subject.into(), &info).unwrap()
+    }
+
+    #[test]
+    fn manage_users() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let manager = manager(&root_dir);
+        manager
+            .create_user(&user_info("alice", Some("12345678")))
+            .unwrap();
+        manager
+            .create_user(&user_info("bob", Some("87654321")))
+            .unwrap();
+        assert!(manager.create_user(&user_info("bob", Some("x"))).is_err());
+        assert!(manager.create_user(&user_info("carol", None)).is_err());
+
+        let mut update = user_info("alice", None);
+        update.user_type = Some("Super".into());
+        manager.update_user(&update).unwrap();
+        let alice = manager.get_user("alice").unwrap();
+        assert!(alice.is_super());
+        assert_eq!(
+            alice.password_digest,
+            acl_utils::user_signing_key("alice", "12345678")
+        );
+        assert!(manager
+            .get_user("alice")
+            .unwrap()
+            .to_info()
+            .password
+            .is_none());
+        assert!(!manager.encode_pretty(false).contains("12345678"));
+        assert!(manager.has_super_user());
+
+        let names: Vec<_> = manager
+            .list_users(None)
+            .into_iter()
+            .map(|user| user.username)
+            .collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+        assert_eq!(manager.list_users(Some("bo")).len(), 1);
+
+        manager.delete_user(&"bob".into()).unwrap();
+        assert!(manager.get_user("bob").is_none());
+        assert!(manager.delete_user(&"bob".into()).is_err());
+    }
+
+    #[test]
+    fn manage_acls() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let manager = manager(&root_dir);
+        assert!(manager
+            .create_acl(acl("User:alice", "Topic:a", "Allow"))
+            .is_err());
+        manager
+            .create_user(&user_info("alice", Some("12345678")))
+            .unwrap();
+        manager
+            .create_acl(acl("User:alice", "Topic:a", "Allow"))
+            .unwrap();
+        manager
+            .create_acl(acl("User:alice", "Group:g", "Allow"))
+            .unwrap();
+        manager
+            .update_acl(acl("User:alice", "Topic:a", "Deny"))
+            .unwrap();
+        let entries = &manager.get_acl("User:alice").unwrap().policies[0].entries;
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(manager.list_acls(Some("alice"), Some("Group")).len(), 1);
+        assert!(manager.list_acls(None, Some("Topic:b")).is_empty());
+
+        let topic = Resource::topic("a");
+        manager
+            .delete_acl(&"User:alice".into(), None, Some(&topic))
+            .unwrap();
+        assert!(manager
+            .delete_acl(&"User:alice".into(), None, Some(&topic))
+            .is_err());
+        manager.delete_user(&"alice".into()).unwrap();
+        assert!(manager.get_acl("User:alice").is_none());
+    }
+
+    #[test]
+    fn persist_and_load() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let manager = manager(&root_dir);
+        manager
+            .create_user(&user_info("alice", Some("12345678")))
+            .unwrap();
+        manager
+            .create_acl(acl("User:alice", "Topic:order*", "Allow"))
+            .unwrap();
+
+        let loaded = self::manager(&root_dir);
+        assert!(loaded.load());
+        assert_eq!(loaded.get_user("alice"), manager.get_user("alice"));
+        assert_eq!(loaded.get_acl("User:alice"), manager.get_acl("User:alice"));
+    }
+}
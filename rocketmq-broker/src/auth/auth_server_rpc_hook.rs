@@ -0,0 +1,104 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::runtime::RPCHook;
+use tracing::warn;
+
+use crate::auth::auth_metadata_manager::AuthMetadataManager;
+use crate::auth::authentication;
+use crate::auth::authorization;
+use crate::auth::AuthResult;
+
+/// Authenticates, then authorizes, every request before it reaches a processor. Rejected
+/// requests are answered with `ResponseCode::NoPermission`.
+pub(crate) struct AuthServerRPCHook {
+    auth_metadata_manager: Arc<AuthMetadataManager>,
+    authentication_enabled: bool,
+    authorization_enabled: bool,
+    cluster_name: CheetahString,
+}
+
+impl AuthServerRPCHook {
+    pub fn new(
+        auth_metadata_manager: Arc<AuthMetadataManager>,
+        authentication_enabled: bool,
+        authorization_enabled: bool,
+        cluster_name: CheetahString,
+    ) -> Self {
+        Self {
+            auth_metadata_manager,
+            authentication_enabled,
+            authorization_enabled,
+            cluster_name,
+        }
+    }
+
+    fn check(&self, remote_addr: SocketAddr, request: &RemotingCommand) -> AuthResult<()> {
+        // authorization needs to know the user, so it implies authentication
+        let user = authentication::authenticate(&self.auth_metadata_manager, request)?;
+        if self.authorization_enabled {
+            authorization::authorize(
+                &self.auth_metadata_manager,
+                &user,
+                request,
+                Some(remote_addr.ip()),
+                &self.cluster_name,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl RPCHook for AuthServerRPCHook {
+    fn do_before_request(
+        &self,
+        remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        if !self.authentication_enabled && !self.authorization_enabled {
+            return Ok(());
+        }
+        if let Err(e) = self.check(remote_addr, request) {
+            warn!(
+                "auth check failed, code={}, remote={}: {}",
+                request.code(),
+                remote_addr,
+                e
+            );
+            return Err(RocketmqError::AbortProcessError(
+                ResponseCode::NoPermission as i32,
+                e.to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn do_after_response(
+        &self,
+        _remote_addr: SocketAddr,
+        _response: &mut RemotingCommand,
+    ) -> RocketMQResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,125 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+
+use rocketmq_acl::acl_utils;
+use rocketmq_acl::session_credentials::ACCESS_KEY;
+use rocketmq_acl::session_credentials::SIGNATURE;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+
+use crate::auth::auth_metadata_manager::AuthMetadataManager;
+use crate::auth::user::User;
+use crate::auth::AuthError;
+use crate::auth::AuthResult;
+
+/// Authenticates the user a request was signed by, the client signs it with the
+/// `AclClientRPCHook` using the credentials of `SessionCredentials::of_user`.
+pub(crate) fn authenticate(
+    manager: &AuthMetadataManager,
+    request: &RemotingCommand,
+) -> AuthResult<User> {
+    let fields = request
+        .get_ext_fields()
+        .map(|ext_fields| {
+            ext_fields
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect::<BTreeMap<_, _>>()
+        })
+        .unwrap_or_default();
+    let username = fields
+        .get(ACCESS_KEY)
+        .ok_or_else(|| AuthError::Authentication("No user is specified".to_string()))?;
+    let signature = fields.get(SIGNATURE).ok_or_else(|| {
+        AuthError::Authentication(format!("The request of User:{} is not signed", username))
+    })?;
+    let user = manager
+        .get_user(username)
+        .ok_or_else(|| AuthError::Authentication(format!("User:{} is not found", username)))?;
+    if !user.is_enabled() {
+        return Err(AuthError::Authentication(format!(
+            "User:{} is disabled",
+            username
+        )));
+    }
+    if !acl_utils::verify_signature(
+        &acl_utils::combine_request_content(request, &fields),
+        &user.password_digest,
+        signature,
+    ) {
+        return Err(AuthError::Authentication(format!(
+            "The signature of User:{} does not match",
+            username
+        )));
+    }
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+
+    use rocketmq_acl::acl_client_rpc_hook::AclClientRPCHook;
+    use rocketmq_acl::session_credentials::SessionCredentials;
+    use rocketmq_remoting::protocol::body::user_info::UserInfo;
+    use rocketmq_remoting::runtime::RPCHook;
+    use rocketmq_store::config::message_store_config::MessageStoreConfig;
+
+    use super::*;
+
+    fn signed_request(username: &str, password: &str) -> RemotingCommand {
+        let mut request = RemotingCommand::create_remoting_command(10);
+        request.add_ext_field("topic", "TopicTest");
+        let addr: SocketAddr = "127.0.0.1:10911".parse().unwrap();
+        AclClientRPCHook::new(SessionCredentials::of_user(username, password))
+            .do_before_request(addr, &mut request)
+            .unwrap();
+        request
+    }
+
+    #[test]
+    fn authenticate_signed_requests() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let manager = AuthMetadataManager::new(Arc::new(MessageStoreConfig {
+            store_path_root_dir: root_dir.path().to_string_lossy().to_string().into(),
+            ..MessageStoreConfig::default()
+        }));
+        manager
+            .create_user(&UserInfo {
+                username: Some("alice".into()),
+                password: Some("12345678".into()),
+                ..UserInfo::default()
+            })
+            .unwrap();
+
+        let user = authenticate(&manager, &signed_request("alice", "12345678")).unwrap();
+        assert_eq!(user.username, "alice");
+        assert!(authenticate(&manager, &signed_request("alice", "wrong")).is_err());
+        assert!(authenticate(&manager, &signed_request("bob", "12345678")).is_err());
+        assert!(authenticate(&manager, &RemotingCommand::create_remoting_command(10)).is_err());
+
+        manager
+            .update_user(&UserInfo {
+                username: Some("alice".into()),
+                user_status: Some("disable".into()),
+                ..UserInfo::default()
+            })
+            .unwrap();
+        assert!(authenticate(&manager, &signed_request("alice", "12345678")).is_err());
+    }
+}
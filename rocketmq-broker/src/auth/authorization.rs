@@ -0,0 +1,443 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::net::IpAddr;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::mix_all::RETRY_GROUP_TOPIC_PREFIX;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::protocol::body::request::lock_batch_request_body::LockBatchRequestBody;
+use rocketmq_remoting::protocol::body::subscription_group_list::SubscriptionGroupList;
+use rocketmq_remoting::protocol::body::unlock_batch_request_body::UnlockBatchRequestBody;
+use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+
+use crate::auth::acl::Action;
+use crate::auth::acl::Decision;
+use crate::auth::acl::Resource;
+use crate::auth::auth_metadata_manager::AuthMetadataManager;
+use crate::auth::user::User;
+use crate::auth::AuthError;
+use crate::auth::AuthResult;
+
+/// An action a request takes on a resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AuthorizationContext {
+    pub resource: Resource,
+    pub action: Action,
+}
+
+impl AuthorizationContext {
+    fn new(resource: Resource, action: Action) -> Self {
+        Self { resource, action }
+    }
+}
+
+/// Checks that the policies of `user` allow every action `request` takes. Super users may take
+/// any action, and an action no policy entry matches is denied, as is a request taking no action
+/// this module knows of.
+pub(crate) fn authorize(
+    manager: &AuthMetadataManager,
+    user: &User,
+    request: &RemotingCommand,
+    source_ip: Option<IpAddr>,
+    cluster_name: &CheetahString,
+) -> AuthResult<()> {
+    if user.is_super() {
+        return Ok(());
+    }
+    let contexts = build_contexts(request, cluster_name)?;
+    let subject = user.subject();
+    if contexts.is_empty() {
+        // producers send heartbeats without any consumer data
+        if RequestCode::from(request.code()) == RequestCode::HeartBeat {
+            return Ok(());
+        }
+        return Err(AuthError::Authorization(format!(
+            "No permission for {} to send request code {}",
+            subject,
+            request.code()
+        )));
+    }
+    let acl = manager.get_acl(&subject);
+    for context in contexts {
+        let decision = acl
+            .as_ref()
+            .and_then(|acl| acl.match_entry(&context.resource, context.action, source_ip))
+            .map(|entry| entry.decision);
+        if decision != Some(Decision::Allow) {
+            return Err(AuthError::Authorization(format!(
+                "No permission for {} to {} {}",
+                subject,
+                context.action.name(),
+                context.resource
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// The actions `request` takes, on the topics and groups its header or body names, or on the
+/// cluster for the administration requests.
+pub(crate) fn build_contexts(
+    request: &RemotingCommand,
+    cluster_name: &CheetahString,
+) -> AuthResult<Vec<AuthorizationContext>> {
+    let field = |name: &str| {
+        request
+            .get_ext_fields()
+            .and_then(|ext_fields| ext_fields.get(name))
+            .cloned()
+    };
+    let mut contexts = Vec::new();
+    let add_topic = |contexts: &mut Vec<_>, topic: Option<CheetahString>, action| {
+        if let Some(topic) = topic {
+            contexts.push(AuthorizationContext::new(Resource::topic(topic), action));
+        }
+    };
+    let add_group = |contexts: &mut Vec<_>, group: Option<CheetahString>, action| {
+        if let Some(group) = group {
+            contexts.push(AuthorizationContext::new(Resource::group(group), action));
+        }
+    };
+    match RequestCode::from(request.code()) {
+        code @ (RequestCode::SendMessage
+        | RequestCode::SendMessageV2
+        | RequestCode::SendBatchMessage) => {
+            let topic = if code == RequestCode::SendMessage {
+                field("topic")
+            } else {
+                field("b")
+            };
+            // sending to a retry topic is consuming for the group of the topic
+            match topic
+                .as_deref()
+                .and_then(|topic| topic.strip_prefix(RETRY_GROUP_TOPIC_PREFIX))
+            {
+                Some(group) => add_group(&mut contexts, Some(group.into()), Action::Sub),
+                None => add_topic(&mut contexts, topic, Action::Pub),
+            }
+        }
+        RequestCode::ConsumerSendMsgBack => {
+            add_group(&mut contexts, field("group"), Action::Sub);
+        }
+        RequestCode::PullMessage
+        | RequestCode::LitePullMessage
+        | RequestCode::PopMessage
+        | RequestCode::AckMessage
+        | RequestCode::ChangeMessageInvisibleTime
+        | RequestCode::UpdateConsumerOffset
+        | RequestCode::QueryConsumerOffset => {
+            add_topic(&mut contexts, field("topic"), Action::Sub);
+            add_group(&mut contexts, field("consumerGroup"), Action::Sub);
+        }
+        RequestCode::QueryMessage
+        | RequestCode::ViewMessageById
+        | RequestCode::GetMaxOffset
+        | RequestCode::GetMinOffset
+        | RequestCode::SearchOffsetByTimestamp
+        | RequestCode::GetEarliestMsgStoreTime => {
+            add_topic(&mut contexts, field("topic"), Action::Sub);
+        }
+        RequestCode::EndTransaction => {
+            add_topic(&mut contexts, field("topic"), Action::Pub);
+        }
+        RequestCode::LockBatchMq => {
+            let group = request
+                .get_body()
+                .map(|body| LockBatchRequestBody::decode(body))
+                .transpose()
+                .map_err(|e| {
+                    AuthError::Authorization(format!("decode lock batch request failed: {}", e))
+                })?
+                .and_then(|body| body.consumer_group);
+            add_group(&mut contexts, group, Action::Sub);
+        }
+        RequestCode::UnlockBatchMq => {
+            let group = request
+                .get_body()
+                .map(|body| UnlockBatchRequestBody::decode(body))
+                .transpose()
+                .map_err(|e| {
+                    AuthError::Authorization(format!("decode unlock batch request failed: {}", e))
+                })?
+                .and_then(|body| body.consumer_group);
+            add_group(&mut contexts, group, Action::Sub);
+        }
+        RequestCode::UnregisterClient | RequestCode::GetConsumerListByGroup => {
+            add_group(&mut contexts, field("consumerGroup"), Action::Sub);
+        }
+        RequestCode::HeartBeat => {
+            let heartbeat_data = request
+                .get_body()
+                .map(|body| HeartbeatData::decode(body))
+                .transpose()
+                .map_err(|e| {
+                    AuthError::Authorization(format!("decode heartbeat data failed: {}", e))
+                })?;
+            for consumer_data in heartbeat_data
+                .iter()
+                .flat_map(|heartbeat_data| heartbeat_data.consumer_data_set.iter())
+            {
+                add_group(
+                    &mut contexts,
+                    Some(consumer_data.group_name.clone()),
+                    Action::Sub,
+                );
+                for subscription_data in &consumer_data.subscription_data_set {
+                    if !subscription_data
+                        .topic
+                        .starts_with(RETRY_GROUP_TOPIC_PREFIX)
+                    {
+                        add_topic(
+                            &mut contexts,
+                            Some(subscription_data.topic.clone()),
+                            Action::Sub,
+                        );
+                    }
+                }
+            }
+        }
+        RequestCode::UpdateAndCreateTopic => {
+            add_topic(&mut contexts, field("topic"), Action::Create);
+        }
+        RequestCode::DeleteTopicInBroker => {
+            add_topic(&mut contexts, field("topic"), Action::Delete);
+        }
+        RequestCode::GetTopicConfig => {
+            add_topic(&mut contexts, field("topic"), Action::Get);
+        }
+        RequestCode::UpdateAndCreateSubscriptionGroup => {
+            let group = request
+                .get_body()
+                .map(|body| SubscriptionGroupConfig::decode(body))
+                .transpose()
+                .map_err(|e| {
+                    AuthError::Authorization(format!(
+                        "decode subscription group config failed: {}",
+                        e
+                    ))
+                })?
+                .map(|config| CheetahString::from(config.group_name()));
+            add_group(&mut contexts, group, Action::Create);
+        }
+        RequestCode::UpdateAndCreateSubscriptionGroupList => {
+            let group_config_list = request
+                .get_body()
+                .map(|body| SubscriptionGroupList::decode(body))
+                .transpose()
+                .map_err(|e| {
+                    AuthError::Authorization(format!(
+                        "decode subscription group list failed: {}",
+                        e
+                    ))
+                })?
+                .map(|list| list.group_config_list)
+                .unwrap_or_default();
+            for config in group_config_list {
+                add_group(
+                    &mut contexts,
+                    Some(CheetahString::from(config.group_name())),
+                    Action::Create,
+                );
+            }
+        }
+        RequestCode::DeleteSubscriptionGroup => {
+            add_group(&mut contexts, field("groupName"), Action::Delete);
+        }
+        RequestCode::GetSubscriptionGroupConfig => {
+            add_group(&mut contexts, field("group"), Action::Get);
+        }
+        RequestCode::UpdateBrokerConfig
+        | RequestCode::AuthCreateUser
+        | RequestCode::AuthUpdateUser
+        | RequestCode::AuthDeleteUser
+        | RequestCode::AuthCreateAcl
+        | RequestCode::AuthUpdateAcl
+        | RequestCode::AuthDeleteAcl => {
+            contexts.push(AuthorizationContext::new(
+                Resource::cluster(cluster_name.clone()),
+                Action::Update,
+            ));
+        }
+        RequestCode::GetBrokerConfig
+        | RequestCode::AuthGetUser
+        | RequestCode::AuthListUser
+        | RequestCode::AuthGetAcl
+        | RequestCode::AuthListAcl => {
+            contexts.push(AuthorizationContext::new(
+                Resource::cluster(cluster_name.clone()),
+                Action::Get,
+            ));
+        }
+        _ => {}
+    }
+    Ok(contexts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+    use rocketmq_remoting::protocol::body::acl_info::PolicyEntryInfo;
+    use rocketmq_remoting::protocol::body::acl_info::PolicyInfo;
+    use rocketmq_remoting::protocol::body::user_info::UserInfo;
+    use rocketmq_store::config::message_store_config::MessageStoreConfig;
+
+    use super::*;
+    use crate::auth::acl::Acl;
+
+    fn request(code: RequestCode, fields: &[(&str, &str)]) -> RemotingCommand {
+        let mut request = RemotingCommand::create_remoting_command(code);
+        for (key, value) in fields {
+            request.add_ext_field(*key, *value);
+        }
+        request
+    }
+
+    #[test]
+    fn build_contexts_of_requests() {
+        let cluster = CheetahString::from("DefaultCluster");
+        let contexts = build_contexts(
+            &request(RequestCode::SendMessageV2, &[("b", "TopicTest")]),
+            &cluster,
+        )
+        .unwrap();
+        assert_eq!(
+            contexts,
+            vec![AuthorizationContext::new(
+                Resource::topic("TopicTest"),
+                Action::Pub
+            )]
+        );
+
+        let contexts = build_contexts(
+            &request(RequestCode::SendMessage, &[("topic", "%RETRY%group_a")]),
+            &cluster,
+        )
+        .unwrap();
+        assert_eq!(contexts[0].resource, Resource::group("group_a"));
+        assert_eq!(contexts[0].action, Action::Sub);
+
+        let contexts = build_contexts(
+            &request(
+                RequestCode::PullMessage,
+                &[("topic", "TopicTest"), ("consumerGroup", "group_a")],
+            ),
+            &cluster,
+        )
+        .unwrap();
+        assert_eq!(contexts.len(), 2);
+
+        let contexts =
+            build_contexts(&request(RequestCode::AuthCreateUser, &[]), &cluster).unwrap();
+        assert_eq!(contexts[0].resource, Resource::cluster("DefaultCluster"));
+        assert_eq!(contexts[0].action, Action::Update);
+    }
+
+    #[test]
+    fn authorize_by_policies() {
+        let root_dir = tempfile::tempdir().unwrap();
+        let manager = AuthMetadataManager::new(Arc::new(MessageStoreConfig {
+            store_path_root_dir: root_dir.path().to_string_lossy().to_string().into(),
+            ..MessageStoreConfig::default()
+        }));
+        manager
+            .create_user(&UserInfo {
+                username: Some("alice".into()),
+                password: Some("12345678".into()),
+                ..UserInfo::default()
+            })
+            .unwrap();
+        let acl_info = AclInfo {
+            subject: Some("User:alice".into()),
+            policies: Some(vec![PolicyInfo {
+                policy_type: None,
+                entries: Some(vec![PolicyEntryInfo {
+                    resource: Some("Topic:order*".into()),
+                    actions: Some("Pub".into()),
+                    source_ips: None,
+                    decision: Some("Allow".into()),
+                }]),
+            }]),
+        };
+        manager
+            .create_acl(Acl::from_info(&"User:alice".into(), &acl_info).unwrap())
+            .unwrap();
+
+        let cluster = CheetahString::from("DefaultCluster");
+        let user = manager.get_user("alice").unwrap();
+        let send = |topic: &str| {
+            authorize(
+                &manager,
+                &user,
+                &request(RequestCode::SendMessage, &[("topic", topic)]),
+                None,
+                &cluster,
+            )
+        };
+        assert!(send("order_a").is_ok());
+        let err = send("pay").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No permission for User:alice to Pub Topic:pay"
+        );
+
+        // a request naming no resource is denied, but for producer heartbeats
+        let err = authorize(
+            &manager,
+            &user,
+            &request(RequestCode::SendMessage, &[]),
+            None,
+            &cluster,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No permission for User:alice to send request code 10"
+        );
+        assert!(authorize(
+            &manager,
+            &user,
+            &request(RequestCode::GetBrokerRuntimeInfo, &[]),
+            None,
+            &cluster,
+        )
+        .is_err());
+        assert!(authorize(
+            &manager,
+            &user,
+            &request(RequestCode::HeartBeat, &[]),
+            None,
+            &cluster,
+        )
+        .is_ok());
+
+        let mut user = user;
+        user.user_type = crate::auth::user::UserType::Super;
+        assert!(authorize(
+            &manager,
+            &user,
+            &request(RequestCode::SendMessage, &[("topic", "pay")]),
+            None,
+            &cluster,
+        )
+        .is_ok());
+    }
+}
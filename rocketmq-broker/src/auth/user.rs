@@ -0,0 +1,210 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_acl::acl_utils;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::auth::AuthError;
+use crate::auth::AuthResult;
+
+/// Acls are bound to subjects, the subject of a user is its name with this prefix.
+pub(crate) const USER_SUBJECT_PREFIX: &str = "User:";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum UserType {
+    /// Super users skip authorization.
+    Super,
+    #[default]
+    Normal,
+}
+
+impl UserType {
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("Super") {
+            Some(UserType::Super)
+        } else if name.eq_ignore_ascii_case("Normal") {
+            Some(UserType::Normal)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserType::Super => "Super",
+            UserType::Normal => "Normal",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum UserStatus {
+    #[default]
+    Enable,
+    Disable,
+}
+
+impl UserStatus {
+    pub fn parse(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("enable") {
+            Some(UserStatus::Enable)
+        } else if name.eq_ignore_ascii_case("disable") {
+            Some(UserStatus::Disable)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            UserStatus::Enable => "enable",
+            UserStatus::Disable => "disable",
+        }
+    }
+}
+
+/// A user of the broker. Only the digest of the password is kept, which is also the key the
+/// requests of the user are signed with, see [`acl_utils::user_signing_key`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct User {
+    pub username: CheetahString,
+    pub password_digest: CheetahString,
+    #[serde(default)]
+    pub user_type: UserType,
+    #[serde(default)]
+    pub user_status: UserStatus,
+}
+
+impl User {
+    pub fn of(username: impl Into<CheetahString>, password: &str, user_type: UserType) -> Self {
+        let mut user = Self {
+            username: username.into(),
+            password_digest: CheetahString::empty(),
+            user_type,
+            user_status: UserStatus::Enable,
+        };
+        user.set_password(password);
+        user
+    }
+
+    pub fn set_password(&mut self, password: &str) {
+        self.password_digest =
+            CheetahString::from_string(acl_utils::user_signing_key(&self.username, password));
+    }
+
+    /// Builds a user from the body of an admin request, missing fields are left to the caller.
+    pub fn from_info(user_info: &UserInfo) -> AuthResult<UserPatch> {
+        let user_type = match user_info.user_type.as_deref() {
+            None | Some("") => None,
+            Some(user_type) => Some(UserType::parse(user_type).ok_or_else(|| {
+                AuthError::InvalidMetadata(format!("The userType {} is unknown", user_type))
+            })?),
+        };
+        let user_status = match user_info.user_status.as_deref() {
+            None | Some("") => None,
+            Some(user_status) => Some(UserStatus::parse(user_status).ok_or_else(|| {
+                AuthError::InvalidMetadata(format!("The userStatus {} is unknown", user_status))
+            })?),
+        };
+        Ok(UserPatch {
+            username: user_info.username.clone().filter(|name| !name.is_empty()),
+            password: user_info
+                .password
+                .clone()
+                .filter(|password| !password.is_empty()),
+            user_type,
+            user_status,
+        })
+    }
+
+    /// The user as answered to admin requests, without any password.
+    pub fn to_info(&self) -> UserInfo {
+        UserInfo {
+            username: Some(self.username.clone()),
+            password: None,
+            user_type: Some(self.user_type.name().into()),
+            user_status: Some(self.user_status.name().into()),
+        }
+    }
+
+    pub fn subject(&self) -> CheetahString {
+        user_subject(&self.username)
+    }
+
+    pub fn is_super(&self) -> bool {
+        self.user_type == UserType::Super
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.user_status == UserStatus::Enable
+    }
+}
+
+/// The fields of a user an admin request sets.
+#[derive(Debug, Default)]
+pub(crate) struct UserPatch {
+    pub username: Option<CheetahString>,
+    pub password: Option<CheetahString>,
+    pub user_type: Option<UserType>,
+    pub user_status: Option<UserStatus>,
+}
+
+pub(crate) fn user_subject(username: &str) -> CheetahString {
+    CheetahString::from_string(format!("{}{}", USER_SUBJECT_PREFIX, username))
+}
+
+/// The name of the user a subject designates.
+pub(crate) fn subject_username(subject: &str) -> Option<&str> {
+    subject.strip_prefix(USER_SUBJECT_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_info_round_trips() {
+        let mut user = User::of("rocketmq", "12345678", UserType::Super);
+        user.user_status = UserStatus::Disable;
+        let info = user.to_info();
+        assert_eq!(info.user_type.as_deref(), Some("Super"));
+        assert_eq!(info.user_status.as_deref(), Some("disable"));
+
+        let patch = User::from_info(&info).unwrap();
+        assert_eq!(patch.username.as_deref(), Some("rocketmq"));
+        assert_eq!(patch.user_type, Some(UserType::Super));
+        assert_eq!(patch.user_status, Some(UserStatus::Disable));
+
+        let info = UserInfo {
+            user_type: Some("Unknown".into()),
+            ..UserInfo::default()
+        };
+        assert!(User::from_info(&info).is_err());
+    }
+
+    #[test]
+    fn subject_of_user() {
+        let user = User::of("rocketmq", "12345678", UserType::Normal);
+        assert_eq!(user.subject(), "User:rocketmq");
+        assert_eq!(subject_username("User:rocketmq"), Some("rocketmq"));
+        assert_eq!(subject_username("rocketmq"), None);
+    }
+}
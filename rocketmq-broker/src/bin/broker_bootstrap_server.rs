@@ -35,9 +35,10 @@ async fn main() -> RocketMQResult<()> {
     let (broker_config, message_store_config) = parse_config_file().unwrap_or_default();
     // boot strap broker
     Builder::new()
+        .set_server_config(broker_config.broker_server_config.clone())
         .set_broker_config(broker_config)
         .set_message_store_config(message_store_config)
-        .build()
+        .build()?
         .boot()
         .await;
     Ok(())
@@ -17,6 +17,7 @@
 
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
 use rocketmq_common::common::server::config::ServerConfig;
+use rocketmq_error::RocketMQResult;
 use rocketmq_rust::wait_for_signal;
 use rocketmq_store::config::message_store_config::MessageStoreConfig;
 use tracing::error;
@@ -89,14 +90,14 @@ impl Builder {
         self
     }
 
-    pub fn build(self) -> BrokerBootstrap {
-        BrokerBootstrap {
+    pub fn build(self) -> RocketMQResult<BrokerBootstrap> {
+        Ok(BrokerBootstrap {
             broker_runtime: BrokerRuntime::new(
                 self.broker_config,
                 self.message_store_config,
                 self.server_config,
-            ),
-        }
+            )?,
+        })
     }
 }
 
@@ -136,6 +136,25 @@ pub fn get_message_request_mode_path(root_dir: &str) -> String {
         .into_owned()
 }
 
+/// Returns the path to the auth metadata configuration file.
+///
+/// The file holds the users and the acls of the broker.
+///
+/// # Arguments
+///
+/// * `root_dir` - A string slice representing the root directory.
+///
+/// # Returns
+///
+/// A `String` containing the full path to the "authMetadata.json" file.
+pub fn get_auth_metadata_path(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("config")
+        .join("authMetadata.json")
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
@@ -284,4 +303,16 @@ mod test {
             .join("messageRequestMode.json");
         assert_eq!(path, expected_path.to_string_lossy().into_owned());
     }
+
+    #[test]
+    fn test_get_auth_metadata_path() {
+        let root_dir = PathBuf::from("/path/to/root")
+            .to_string_lossy()
+            .into_owned();
+        let path = get_auth_metadata_path(root_dir.as_str());
+        let expected_path = PathBuf::from(root_dir.clone())
+            .join("config")
+            .join("authMetadata.json");
+        assert_eq!(path, expected_path.to_string_lossy().into_owned());
+    }
 }
@@ -16,6 +16,7 @@
  */
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
@@ -23,20 +24,28 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use rocketmq_acl::acl_server_rpc_hook::AclServerRPCHook;
+use rocketmq_acl::plain::plain_access_validator::PlainAccessValidator;
+use rocketmq_acl::plain::plain_permission_manager::DEFAULT_PLAIN_ACL_FILE;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::common::broker::broker_role::BrokerRole;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::config_manager::ConfigManager;
 use rocketmq_common::common::constant::PermName;
 use rocketmq_common::common::mix_all;
 use rocketmq_common::common::server::config::ServerConfig;
 use rocketmq_common::common::statistics::state_getter::StateGetter;
+use rocketmq_common::utils::env_utils::EnvUtils;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_common::UtilAll::compute_next_morning_time_millis;
 use rocketmq_remoting::base::channel_event_listener::ChannelEventListener;
 use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::topic_config_wrapper::TopicConfigAndMappingSerializeWrapper;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::topic_config_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
 use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
+use rocketmq_remoting::protocol::namesrv::RegisterBrokerResult;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
 use rocketmq_remoting::protocol::DataVersion;
 use rocketmq_remoting::remoting_server::server::RocketMQServer;
@@ -49,12 +58,21 @@ use rocketmq_store::config::message_store_config::MessageStoreConfig;
 use rocketmq_store::message_store::local_file_message_store::LocalFileMessageStore;
 use rocketmq_store::stats::broker_stats::BrokerStats;
 use rocketmq_store::stats::broker_stats_manager::BrokerStatsManager;
+use rocketmq_store::timer::timer_checkpoint::TimerCheckpoint;
 use rocketmq_store::timer::timer_message_store::TimerMessageStore;
+use rocketmq_store::timer::timer_metrics::TimerMetrics;
+use tracing::error;
 use tracing::info;
 use tracing::warn;
 
+use crate::auth::auth_metadata_manager::AuthMetadataManager;
+use crate::auth::auth_server_rpc_hook::AuthServerRPCHook;
+use crate::auth::user::User;
+use crate::auth::user::UserType;
 use crate::broker::broker_hook::BrokerShutdownHook;
 use crate::broker::broker_pre_online_service::BrokerPreOnlineService;
+use crate::broker_path_config_helper::get_timer_check_path;
+use crate::broker_path_config_helper::get_timer_metrics_path;
 use crate::client::client_housekeeping_service::ClientHousekeepingService;
 use crate::client::consumer_ids_change_listener::ConsumerIdsChangeListener;
 use crate::client::default_consumer_ids_change_listener::DefaultConsumerIdsChangeListener;
@@ -65,7 +83,9 @@ use crate::client::rebalance::rebalance_lock_manager::RebalanceLockManager;
 use crate::coldctr::cold_data_cg_ctr_service::ColdDataCgCtrService;
 use crate::coldctr::cold_data_pull_request_hold_service::ColdDataPullRequestHoldService;
 use crate::controller::replicas_manager::ReplicasManager;
+use crate::dledger::dledger_role_change_handler::DLedgerRoleChangeHandler;
 use crate::failover::escape_bridge::EscapeBridge;
+use crate::filter::commit_log_dispatcher_calc_bit_map::CommitLogDispatcherCalcBitMap;
 use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
 use crate::hook::batch_check_before_put_message::BatchCheckBeforePutMessageHook;
 use crate::hook::check_before_put_message::CheckBeforePutMessageHook;
@@ -73,6 +93,8 @@ use crate::hook::schedule_message_hook::ScheduleMessageHook;
 use crate::latency::broker_fast_failure::BrokerFastFailure;
 use crate::long_polling::long_polling_service::pull_request_hold_service::PullRequestHoldService;
 use crate::long_polling::notify_message_arriving_listener::NotifyMessageArrivingListener;
+#[cfg(feature = "metrics")]
+use crate::metrics::broker_metrics_manager::BrokerMetricsManager;
 use crate::offset::manager::broadcast_offset_manager::BroadcastOffsetManager;
 use crate::offset::manager::consumer_offset_manager::ConsumerOffsetManager;
 use crate::offset::manager::consumer_order_info_manager::ConsumerOrderInfoManager;
@@ -117,6 +139,8 @@ pub(crate) struct BrokerRuntime {
     consumer_ids_change_listener: Arc<Box<dyn ConsumerIdsChangeListener + Send + Sync + 'static>>,
     topic_queue_mapping_clean_service: TopicQueueMappingCleanService,
     broker_pre_online_service: BrokerPreOnlineService,
+    #[cfg(feature = "metrics")]
+    broker_metrics_manager: Option<BrokerMetricsManager>,
     // receiver for shutdown signal
     pub(crate) shutdown_rx: Option<tokio::sync::broadcast::Receiver<()>>,
 }
@@ -126,13 +150,18 @@ impl BrokerRuntime {
         broker_config: BrokerConfig,
         message_store_config: MessageStoreConfig,
         server_config: ServerConfig,
-    ) -> Self {
+    ) -> rocketmq_error::RocketMQResult<Self> {
         let broker_address = format!("{}:{}", broker_config.broker_ip1, broker_config.listen_port);
         let store_host = broker_address
             .parse::<SocketAddr>()
             .expect("parse store_host failed");
         let runtime = RocketMQRuntime::new_multi(10, "broker-thread");
-        let broker_outer_api = BrokerOuterAPI::new(Arc::new(TokioClientConfig::default()));
+        let tls_config = server_config.tls_config().clone();
+        let broker_outer_api = BrokerOuterAPI::new(Arc::new(TokioClientConfig {
+            use_tls: tls_config.tls_enable,
+            tls_config,
+            ..TokioClientConfig::default()
+        }))?;
 
         let topic_queue_mapping_manager =
             TopicQueueMappingManager::new(Arc::new(broker_config.clone()));
@@ -145,9 +174,12 @@ impl BrokerRuntime {
             broker_config.get_broker_addr().into(),
         );
         let producer_manager = ProducerManager::new();
+        let consumer_filter_manager = ConsumerFilterManager::new(Arc::new(broker_config.clone()));
         let consumer_ids_change_listener: Arc<
             Box<dyn ConsumerIdsChangeListener + Send + Sync + 'static>,
-        > = Arc::new(Box::new(DefaultConsumerIdsChangeListener {}));
+        > = Arc::new(Box::new(DefaultConsumerIdsChangeListener::new(
+            consumer_filter_manager.clone(),
+        )));
         let consumer_manager = ConsumerManager::new_with_broker_stats(
             consumer_ids_change_listener.clone(),
             Arc::new(broker_config.clone()),
@@ -168,7 +200,7 @@ impl BrokerRuntime {
             topic_queue_mapping_manager,
             consumer_offset_manager: Default::default(),
             subscription_group_manager: None,
-            consumer_filter_manager: Some(Default::default()),
+            consumer_filter_manager: Some(consumer_filter_manager),
             consumer_order_info_manager: None,
             message_store: None,
             broker_stats: None,
@@ -193,6 +225,8 @@ impl BrokerRuntime {
             escape_bridge: None,
             pop_inflight_message_counter,
             replicas_manager: None,
+            plain_access_validator: None,
+            auth_metadata_manager: None,
             broker_fast_failure: BrokerFastFailure,
             cold_data_pull_request_hold_service: None,
             cold_data_cg_ctr_service: None,
@@ -229,7 +263,7 @@ impl BrokerRuntime {
         inner.client_housekeeping_service =
             Some(Arc::new(ClientHousekeepingService::new(inner.clone())));
 
-        Self {
+        Ok(Self {
             inner,
             transactional_message_service: None,
             broker_runtime: Some(runtime),
@@ -237,8 +271,10 @@ impl BrokerRuntime {
             consumer_ids_change_listener,
             topic_queue_mapping_clean_service: TopicQueueMappingCleanService,
             broker_pre_online_service: BrokerPreOnlineService,
+            #[cfg(feature = "metrics")]
+            broker_metrics_manager: None,
             shutdown_rx: None,
-        }
+        })
     }
 
     pub(crate) fn broker_config(&self) -> &BrokerConfig {
@@ -252,6 +288,11 @@ impl BrokerRuntime {
     pub async fn shutdown(&mut self) {
         self.shutdown_basic_service().await;
 
+        #[cfg(feature = "metrics")]
+        if let Some(mut broker_metrics_manager) = self.broker_metrics_manager.take() {
+            broker_metrics_manager.shutdown();
+        }
+
         self.inner.broker_outer_api.shutdown();
 
         if let Some(runtime) = self.broker_runtime.take() {
@@ -303,6 +344,14 @@ impl BrokerRuntime {
             broker_stats_manager.shutdown();
         }
 
+        if let Some(plain_access_validator) = self.inner.plain_access_validator.as_ref() {
+            plain_access_validator.shutdown();
+        }
+
+        if let Some(auth_metadata_manager) = self.inner.auth_metadata_manager.as_ref() {
+            auth_metadata_manager.persist();
+        }
+
         if let Some(pull_request_hold_service) = self.inner.pull_request_hold_service.as_mut() {
             pull_request_hold_service.shutdown();
         }
@@ -397,6 +446,37 @@ impl BrokerRuntime {
     }
 }
 
+#[cfg(test)]
+impl BrokerRuntime {
+    /// Builds a broker keeping its metadata and messages under `root_dir`, with the metadata
+    /// and the message store loaded and no service started.
+    pub(crate) async fn new_inner_for_test(
+        root_dir: &std::path::Path,
+    ) -> ArcMut<BrokerRuntimeInner<LocalFileMessageStore>> {
+        let root_dir = CheetahString::from(root_dir.to_string_lossy().to_string());
+        let mut runtime = BrokerRuntime::new(
+            BrokerConfig {
+                store_path_root_dir: root_dir.clone(),
+                ..BrokerConfig::default()
+            },
+            MessageStoreConfig {
+                store_path_root_dir: root_dir,
+                ..MessageStoreConfig::default()
+            },
+            ServerConfig::default(),
+        )
+        .unwrap();
+        assert!(runtime.initialize_metadata());
+        assert!(runtime.initialize_message_store().await);
+        assert!(runtime.inner.message_store.as_mut().unwrap().load().await);
+        // nothing is scheduled on it, and a runtime may not be dropped in an async context
+        if let Some(broker_runtime) = runtime.broker_runtime.take() {
+            broker_runtime.shutdown();
+        }
+        runtime.inner.clone()
+    }
+}
+
 /*impl Drop for BrokerRuntime {
     fn drop(&mut self) {
         let result =
@@ -437,8 +517,13 @@ impl BrokerRuntime {
     }
 
     async fn initialize_message_store(&mut self) -> bool {
-        if self.inner.message_store_config.store_type == StoreType::LocalFile {
-            info!("Use local file as message store");
+        let store_type = self.inner.message_store_config.store_type;
+        if store_type == StoreType::LocalFile || store_type == StoreType::RocksDB {
+            if store_type == StoreType::RocksDB {
+                info!("Use local file as message store, with kv backed consume queues");
+            } else {
+                info!("Use local file as message store");
+            }
             let mut message_store = ArcMut::new(LocalFileMessageStore::new(
                 Arc::new(self.inner.message_store_config.clone()),
                 Arc::new(self.inner.broker_config.clone()),
@@ -448,19 +533,54 @@ impl BrokerRuntime {
             ));
             let message_store_clone = message_store.clone();
             message_store.set_message_store_arc(message_store_clone);
+            message_store.add_first_dispatcher(Arc::new(CommitLogDispatcherCalcBitMap::new(
+                Arc::new(self.inner.broker_config.clone()),
+                self.inner.consumer_filter_manager().clone(),
+            )));
             if self.inner.message_store_config.is_timer_wheel_enable() {
-                let time_message_store = TimerMessageStore::new(Some(message_store.clone()));
-                message_store.set_timer_message_store(Arc::new(time_message_store));
+                let store_path_root_dir = self
+                    .inner
+                    .message_store_config
+                    .store_path_root_dir
+                    .to_string();
+                let timer_checkpoint =
+                    match TimerCheckpoint::new(get_timer_check_path(&store_path_root_dir)) {
+                        Ok(timer_checkpoint) => Arc::new(timer_checkpoint),
+                        Err(e) => {
+                            error!("Failed to create timer checkpoint: {}", e);
+                            return false;
+                        }
+                    };
+                let timer_metrics = Arc::new(TimerMetrics::new(get_timer_metrics_path(
+                    &store_path_root_dir,
+                )));
+                let timer_message_store = match TimerMessageStore::new(
+                    Arc::new(self.inner.message_store_config.clone()),
+                    timer_checkpoint,
+                    timer_metrics,
+                    Some(message_store.clone()),
+                ) {
+                    Ok(timer_message_store) => timer_message_store,
+                    Err(e) => {
+                        error!("Failed to create timer message store: {}", e);
+                        return false;
+                    }
+                };
+                message_store.set_timer_message_store(Arc::new(timer_message_store.clone()));
+                self.inner.timer_message_store = Some(timer_message_store);
             }
             //Maybe need to set message store to other components
             /*self.consumer_offset_manager
                 .set_message_store(Some(message_store.clone()));
             self.topic_config_manager
                 .set_message_store(Some(message_store.clone()));*/
+            if let Some(dledger_commit_log) = message_store.get_dledger_commit_log() {
+                dledger_commit_log.add_role_change_handler(
+                    DLedgerRoleChangeHandler::new(self.inner.clone()).into_role_change_handler(),
+                );
+            }
             self.inner.broker_stats = Some(BrokerStats::new(message_store.clone()));
             self.inner.message_store = Some(message_store);
-        } else if self.inner.message_store_config.store_type == StoreType::RocksDB {
-            info!("Use RocksDB as message store");
         } else {
             warn!("Unknown store type");
             return false;
@@ -472,21 +592,16 @@ impl BrokerRuntime {
         let mut result: bool = true;
 
         if self.inner.broker_config().enable_controller_mode {
-            info!("Start controller mode(Support for future versions)");
-            unimplemented!()
+            info!("Start controller mode");
+            self.inner.replicas_manager = Some(ReplicasManager::new(self.inner.clone()));
         }
         if self.inner.message_store.is_some() {
             self.register_message_store_hook();
             self.inner.message_store.as_mut().unwrap().load().await;
         }
 
-        if self
-            .inner
-            .broker_config
-            .timer_wheel_config
-            .timer_wheel_enable
-        {
-            result &= self.inner.timer_message_store.as_mut().unwrap().load();
+        if let Some(timer_message_store) = self.inner.timer_message_store.as_mut() {
+            result &= timer_message_store.load();
         }
 
         // maybe need to optimize
@@ -766,11 +881,82 @@ impl BrokerRuntime {
         self.inner.transaction_metrics_flush_service = Some(TransactionMetricsFlushService);
     }
 
-    fn initial_acl(&mut self) {}
+    fn initial_acl(&mut self) {
+        if !self.inner.broker_config.acl_enable {
+            info!("The broker does not enable acl");
+            return;
+        }
+        let acl_file = PathBuf::from(EnvUtils::get_rocketmq_home()).join(DEFAULT_PLAIN_ACL_FILE);
+        match PlainAccessValidator::new(&acl_file) {
+            Ok(validator) => {
+                info!("The broker enables acl, acl file: {}", acl_file.display());
+                self.inner.plain_access_validator = Some(Arc::new(validator));
+            }
+            // refuse to start rather than serving without the acl the broker is configured with
+            Err(e) => panic!(
+                "Failed to initialize acl from {}: {}",
+                acl_file.display(),
+                e
+            ),
+        }
+    }
 
     fn initial_rpc_hooks(&mut self) {}
 
-    fn initial_request_pipeline(&mut self) {}
+    fn initial_request_pipeline(&mut self) {
+        let broker_config = &self.inner.broker_config;
+        if !broker_config.authentication_enabled && !broker_config.authorization_enabled {
+            return;
+        }
+        let auth_metadata_manager =
+            AuthMetadataManager::new(Arc::new(self.inner.message_store_config.clone()));
+        if !auth_metadata_manager.load() {
+            panic!("Failed to load the auth metadata");
+        }
+        if !broker_config.init_authentication_user.is_empty() {
+            match SerdeJsonUtils::from_json_str::<UserInfo>(
+                broker_config.init_authentication_user.as_str(),
+            ) {
+                Ok(UserInfo {
+                    username: Some(username),
+                    password: Some(password),
+                    ..
+                }) => {
+                    auth_metadata_manager.init_user(User::of(username, &password, UserType::Super))
+                }
+                _ => panic!(
+                    "The initAuthenticationUser {} is invalid",
+                    broker_config.init_authentication_user
+                ),
+            }
+        }
+        if !auth_metadata_manager.has_super_user() {
+            warn!("No super user is configured, the auth metadata can not be administered");
+        }
+        info!(
+            "The broker enables authentication: {}, authorization: {}",
+            broker_config.authentication_enabled, broker_config.authorization_enabled
+        );
+        self.inner.auth_metadata_manager = Some(Arc::new(auth_metadata_manager));
+    }
+
+    fn new_auth_server_rpc_hook(&self) -> Option<AuthServerRPCHook> {
+        self.inner
+            .auth_metadata_manager
+            .as_ref()
+            .map(|auth_metadata_manager| {
+                AuthServerRPCHook::new(
+                    auth_metadata_manager.clone(),
+                    self.inner.broker_config.authentication_enabled,
+                    self.inner.broker_config.authorization_enabled,
+                    self.inner
+                        .broker_config
+                        .broker_identity
+                        .broker_cluster_name
+                        .clone(),
+                )
+            })
+    }
 
     fn start_basic_service(&mut self) {
         if let Some(ref mut message_store) = self.inner.message_store {
@@ -791,7 +977,13 @@ impl BrokerRuntime {
         let request_processor = self.init_processor();
         let fast_request_processor = request_processor.clone();
 
-        let server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        let mut server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        if let Some(validator) = self.inner.plain_access_validator.as_ref() {
+            server.register_rpc_hook(Box::new(AclServerRPCHook::new(validator.clone())));
+        }
+        if let Some(hook) = self.new_auth_server_rpc_hook() {
+            server.register_rpc_hook(Box::new(hook));
+        }
         //start nomarl broker remoting_server
         let client_housekeeping_service_main = self
             .inner
@@ -807,7 +999,13 @@ impl BrokerRuntime {
         //start fast broker remoting_server
         let mut fast_server_config = self.inner.server_config.clone();
         fast_server_config.listen_port = self.inner.server_config.listen_port - 2;
-        let fast_server = RocketMQServer::new(Arc::new(fast_server_config));
+        let mut fast_server = RocketMQServer::new(Arc::new(fast_server_config));
+        if let Some(validator) = self.inner.plain_access_validator.as_ref() {
+            fast_server.register_rpc_hook(Box::new(AclServerRPCHook::new(validator.clone())));
+        }
+        if let Some(hook) = self.new_auth_server_rpc_hook() {
+            fast_server.register_rpc_hook(Box::new(hook));
+        }
         tokio::spawn(async move {
             fast_server
                 .run(fast_request_processor, client_housekeeping_service_fast)
@@ -870,6 +1068,24 @@ impl BrokerRuntime {
         self.inner.update_namesrv_addr_inner().await;
     }
 
+    #[cfg(feature = "metrics")]
+    async fn start_metrics_exporter(&mut self) {
+        let broker_config = self.inner.broker_config();
+        if !broker_config.metrics_exporter_type.is_enable() {
+            return;
+        }
+        let host = broker_config.metrics_prom_exporter_host.clone();
+        let port = broker_config.metrics_prom_exporter_port;
+        let mut broker_metrics_manager = BrokerMetricsManager::new(&self.inner);
+        match broker_metrics_manager.start(host.as_str(), port).await {
+            Ok(()) => self.broker_metrics_manager = Some(broker_metrics_manager),
+            Err(e) => error!(
+                "Start broker metrics exporter on port {} failed: {}",
+                port, e
+            ),
+        }
+    }
+
     pub async fn start(&mut self) {
         self.inner.should_start_time.store(
             (get_current_millis() as i64
@@ -881,9 +1097,15 @@ impl BrokerRuntime {
         {
             self.inner.is_isolated.store(true, Ordering::Release);
         }
+        // the ReplicasManager registers the broker once the controller decided its role
+        if self.inner.broker_config.enable_controller_mode {
+            self.inner.is_isolated.store(true, Ordering::Release);
+        }
 
         self.inner.broker_outer_api.start().await;
         self.start_basic_service();
+        #[cfg(feature = "metrics")]
+        self.start_metrics_exporter().await;
 
         if !self.inner.is_isolated.load(Ordering::Acquire)
             && !self.inner.message_store_config.enable_dledger_commit_log
@@ -919,10 +1141,12 @@ impl BrokerRuntime {
                         .load(Ordering::Relaxed);
                     if get_current_millis() < start_time {
                         info!("Register to namesrv after {}", start_time);
+                        tokio::time::sleep(period).await;
                         continue;
                     }
                     if broker_runtime_inner.is_isolated.load(Ordering::Relaxed) {
                         info!("Skip register for broker is isolated");
+                        tokio::time::sleep(period).await;
                         continue;
                     }
                     // record current execution time
@@ -1074,16 +1298,18 @@ impl BrokerRuntime {
             self.inner.broker_config.broker_ip1, self.inner.server_config.listen_port
         ));
         let broker_id = self.inner.broker_config.broker_identity.broker_id;
+        let ha_server_addr = self.inner.get_ha_server_addr();
         //  let weak = Arc::downgrade(&self.inner.broker_outer_api);
 
-        self.inner
+        let register_broker_result_list = self
+            .inner
             .broker_outer_api
             .register_broker_all(
                 cluster_name,
                 broker_addr.clone(),
                 broker_name,
                 broker_id,
-                broker_addr,
+                ha_server_addr,
                 topic_config_wrapper,
                 vec![],
                 oneway,
@@ -1095,6 +1321,8 @@ impl BrokerRuntime {
                 self.inner.clone(),
             )
             .await;
+        self.inner
+            .handle_register_broker_result(register_broker_result_list.first());
     }
 }
 
@@ -1193,14 +1421,16 @@ impl<MS: MessageStore> BrokerRuntimeInner<MS> {
             this.broker_config.broker_ip1, this.server_config.listen_port
         ));
         let broker_id = this.broker_config.broker_identity.broker_id;
+        let ha_server_addr = this.get_ha_server_addr();
         //let weak = Arc::downgrade(&self.broker_out_api);
-        this.broker_outer_api
+        let register_broker_result_list = this
+            .broker_outer_api
             .register_broker_all(
                 cluster_name,
                 broker_addr.clone(),
                 broker_name,
                 broker_id,
-                broker_addr,
+                ha_server_addr,
                 topic_config_wrapper,
                 vec![],
                 oneway,
@@ -1214,6 +1444,36 @@ impl<MS: MessageStore> BrokerRuntimeInner<MS> {
                 this.clone(),
             )
             .await;
+        this.handle_register_broker_result(register_broker_result_list.first());
+    }
+
+    /// The address slaves connect to for HA replication.
+    pub fn get_ha_server_addr(&self) -> CheetahString {
+        CheetahString::from_string(format!(
+            "{}:{}",
+            self.broker_config
+                .broker_ip2
+                .clone()
+                .unwrap_or_else(|| self.broker_config.broker_ip1.clone()),
+            self.message_store_config.ha_listen_port
+        ))
+    }
+
+    fn handle_register_broker_result(&self, register_broker_result: Option<&RegisterBrokerResult>) {
+        let Some(register_broker_result) = register_broker_result else {
+            return;
+        };
+        // a statically configured master HA address is never overridden
+        if self.message_store_config.broker_role != BrokerRole::Slave
+            || self.message_store_config.ha_master_address.is_some()
+            || register_broker_result.ha_server_addr.is_empty()
+        {
+            return;
+        }
+        if let Some(message_store) = self.message_store.as_ref() {
+            message_store.update_ha_master_address(&register_broker_result.ha_server_addr);
+            message_store.update_master_address(&register_broker_result.master_addr);
+        }
     }
 }
 
@@ -1326,6 +1586,8 @@ pub(crate) struct BrokerRuntimeInner<MS> {
     escape_bridge: Option<EscapeBridge<MS>>,
     pop_inflight_message_counter: PopInflightMessageCounter,
     replicas_manager: Option<ReplicasManager>,
+    plain_access_validator: Option<Arc<PlainAccessValidator>>,
+    auth_metadata_manager: Option<Arc<AuthMetadataManager>>,
     broker_fast_failure: BrokerFastFailure,
     cold_data_pull_request_hold_service: Option<ColdDataPullRequestHoldService>,
     cold_data_cg_ctr_service: Option<ColdDataCgCtrService>,
@@ -2135,6 +2397,18 @@ impl<MS: MessageStore> BrokerRuntimeInner<MS> {
     pub fn get_broker_addr(&self) -> &CheetahString {
         &self.broker_addr
     }
+
+    pub fn replicas_manager(&self) -> Option<&ReplicasManager> {
+        self.replicas_manager.as_ref()
+    }
+
+    pub fn plain_access_validator(&self) -> Option<&Arc<PlainAccessValidator>> {
+        self.plain_access_validator.as_ref()
+    }
+
+    pub fn auth_metadata_manager(&self) -> Option<&Arc<AuthMetadataManager>> {
+        self.auth_metadata_manager.as_ref()
+    }
     pub fn sync_broker_member_group(&self) {
         warn!("sync_broker_member_group not implemented");
     }
@@ -15,17 +15,49 @@
  * limitations under the License.
  */
 use std::any::Any;
+use std::collections::HashSet;
 
+use cheetah_string::CheetahString;
+use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use tracing::warn;
 
 use crate::client::consumer_group_event::ConsumerGroupEvent;
 use crate::client::consumer_ids_change_listener::ConsumerIdsChangeListener;
+use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
 
-#[derive(Default)]
-pub struct DefaultConsumerIdsChangeListener {}
+pub struct DefaultConsumerIdsChangeListener {
+    consumer_filter_manager: ConsumerFilterManager,
+}
+
+impl DefaultConsumerIdsChangeListener {
+    pub(crate) fn new(consumer_filter_manager: ConsumerFilterManager) -> Self {
+        Self {
+            consumer_filter_manager,
+        }
+    }
+}
 
 impl ConsumerIdsChangeListener for DefaultConsumerIdsChangeListener {
-    fn handle(&self, _event: ConsumerGroupEvent, _group: &str, _args: &[&dyn Any]) {}
+    fn handle(&self, event: ConsumerGroupEvent, group: &str, args: &[&dyn Any]) {
+        match event {
+            ConsumerGroupEvent::Unregister => {
+                self.consumer_filter_manager
+                    .un_register(&CheetahString::from_slice(group));
+            }
+            ConsumerGroupEvent::Register => {
+                let Some(sub_list) = args
+                    .first()
+                    .and_then(|arg| arg.downcast_ref::<HashSet<SubscriptionData>>())
+                else {
+                    return;
+                };
+                let sub_list = sub_list.iter().collect::<Vec<_>>();
+                self.consumer_filter_manager
+                    .register_group(&CheetahString::from_slice(group), &sub_list);
+            }
+            _ => {}
+        }
+    }
 
     fn shutdown(&self) {
         warn!("DefaultConsumerIdsChangeListener shutdown not implemented");
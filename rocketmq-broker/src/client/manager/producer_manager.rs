@@ -16,12 +16,14 @@
  */
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::sync::atomic::AtomicI32;
 use std::sync::Arc;
 
 use cheetah_string::CheetahString;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::connection::Connection;
 use rocketmq_remoting::protocol::body::producer_info::ProducerInfo;
 use rocketmq_remoting::protocol::body::producer_table_info::ProducerTableInfo;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
@@ -113,6 +115,32 @@ impl ProducerManager {
         ProducerTableInfo::from(map)
     }
 
+    /// Returns the connections of the producers registered under `group`, or `None` if no
+    /// producer of the group has been seen.
+    pub fn get_group_connections(&self, group: &str) -> Option<HashSet<Connection>> {
+        let group_channel_table = self.group_channel_table.lock();
+        let channel_map = group_channel_table.get(group)?;
+        Some(
+            channel_map
+                .values()
+                .map(|client_channel_info| {
+                    let mut connection = Connection::new();
+                    connection.set_client_id(client_channel_info.client_id().clone());
+                    connection.set_language(client_channel_info.language());
+                    connection.set_version(client_channel_info.version());
+                    connection.set_client_addr(
+                        client_channel_info
+                            .channel()
+                            .remote_address()
+                            .to_string()
+                            .into(),
+                    );
+                    connection
+                })
+                .collect(),
+        )
+    }
+
     pub fn group_online(&self, group: String) -> bool {
         let binding = self.group_channel_table.lock();
         let channels = binding.get(group.as_str());
@@ -14,13 +14,33 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashMap;
+
 use cheetah_string::CheetahString;
 use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::mq_version::RocketMqVersion;
 use rocketmq_common::MessageDecoder;
 use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::get_consumer_status_body::GetConsumerStatusBody;
+use rocketmq_remoting::protocol::body::get_consumer_status_body::MessageQueueOffsetTable;
+use rocketmq_remoting::protocol::body::reset_offset_body::ResetOffsetBody;
 use rocketmq_remoting::protocol::header::check_transaction_state_request_header::CheckTransactionStateRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_status_request_header::GetConsumerStatusRequestHeader;
+use rocketmq_remoting::protocol::header::reset_offset_request_header::ResetOffsetRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use rocketmq_store::base::message_store::MessageStore;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+const CALL_CLIENT_TIMEOUT_MILLIS: u64 = 5000;
 
 #[derive(Default, Clone)]
 pub struct Broker2Client;
@@ -72,4 +92,266 @@ impl Broker2Client {
             },
         }
     }
+
+    /// Computes the new offsets of `group` on `topic` by `timestamp` (`-1` means the max offset)
+    /// and pushes them to every online consumer of the group. If the group has no online
+    /// consumer, the offsets are written straight into the consumer offset manager instead.
+    pub async fn reset_offset<MS: MessageStore>(
+        &self,
+        broker_runtime_inner: &BrokerRuntimeInner<MS>,
+        topic: &CheetahString,
+        group: &CheetahString,
+        timestamp: i64,
+        is_force: bool,
+    ) -> RemotingCommand {
+        let Some(topic_config) = broker_runtime_inner
+            .topic_config_manager()
+            .select_topic_config(topic)
+        else {
+            error!(
+                "[reset-offset] reset offset failed, no topic in this broker. topic={}",
+                topic
+            );
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::TopicNotExist,
+                format!(
+                    "[reset-offset] reset offset failed, no topic in this broker. topic={topic}"
+                ),
+            );
+        };
+        let message_store = broker_runtime_inner.message_store_unchecked();
+        let broker_name = &broker_runtime_inner
+            .broker_config()
+            .broker_identity
+            .broker_name;
+        let mut offset_table = HashMap::new();
+        for queue_id in 0..topic_config.write_queue_nums as i32 {
+            let consumer_offset = broker_runtime_inner
+                .consumer_offset_manager()
+                .query_offset(group, topic, queue_id);
+            if consumer_offset == -1 {
+                return RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    format!("The consumer group <{group}> not exist"),
+                );
+            }
+            let mut timestamp_offset = if timestamp == -1 {
+                message_store.get_max_offset_in_queue(topic, queue_id)
+            } else {
+                message_store.get_offset_in_queue_by_time(topic, queue_id, timestamp)
+            };
+            if timestamp_offset < 0 {
+                warn!(
+                    "reset offset is invalid. topic={}, queueId={}, timeStampOffset={}",
+                    topic, queue_id, timestamp_offset
+                );
+                timestamp_offset = 0;
+            }
+            let offset = if is_force || timestamp_offset < consumer_offset {
+                timestamp_offset
+            } else {
+                consumer_offset
+            };
+            offset_table.insert(
+                MessageQueue::from_parts(topic.clone(), broker_name.clone(), queue_id),
+                offset,
+            );
+        }
+
+        let reset_offset_body = ResetOffsetBody::new(offset_table);
+        let channels = broker_runtime_inner
+            .consumer_manager()
+            .get_consumer_group_info(group)
+            .map(|consumer_group_info| consumer_group_info.get_channel_info_table())
+            .filter(|channel_info_table| !channel_info_table.is_empty());
+        match channels {
+            Some(channel_info_table) => {
+                let request_header = ResetOffsetRequestHeader {
+                    topic: topic.clone(),
+                    group: group.clone(),
+                    timestamp,
+                    is_force,
+                    ..Default::default()
+                };
+                let body = match reset_offset_body.encode() {
+                    Ok(body) => body,
+                    Err(e) => {
+                        return RemotingCommand::create_response_command_with_code_remark(
+                            ResponseCode::SystemError,
+                            e.to_string(),
+                        )
+                    }
+                };
+                for entry in channel_info_table.iter() {
+                    let client_channel_info = entry.value();
+                    if client_channel_info.version() < RocketMqVersion::V307Snapshot as i32 {
+                        return RemotingCommand::create_response_command_with_code_remark(
+                            ResponseCode::SystemError,
+                            format!(
+                                "the client does not support this feature. version={}",
+                                client_channel_info.version()
+                            ),
+                        );
+                    }
+                    let request = RemotingCommand::create_request_command(
+                        RequestCode::ResetConsumerClientOffset,
+                        request_header.clone(),
+                    )
+                    .set_body(body.clone());
+                    match self.send_one_way(entry.key(), request).await {
+                        Ok(_) => info!(
+                            "[reset-offset] reset offset success. topic={}, group={}, clientId={}",
+                            topic,
+                            group,
+                            client_channel_info.client_id()
+                        ),
+                        Err(e) => error!(
+                            "[reset-offset] reset offset exception. topic={}, group={}, \
+                             clientId={}, {}",
+                            topic,
+                            group,
+                            client_channel_info.client_id(),
+                            e
+                        ),
+                    }
+                }
+            }
+            None => {
+                let client_host = CheetahString::from_static_str("ResetOffsetByAdmin");
+                for (mq, offset) in reset_offset_body.offset_table.iter() {
+                    broker_runtime_inner
+                        .consumer_offset_manager()
+                        .commit_offset(
+                            client_host.clone(),
+                            group,
+                            topic,
+                            mq.get_queue_id(),
+                            *offset,
+                        );
+                }
+                info!(
+                    "[reset-offset] consumer group is offline, offsets rewritten in broker. \
+                     topic={}, group={}, timestamp={}",
+                    topic, group, timestamp
+                );
+            }
+        }
+        match reset_offset_body.encode() {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                e.to_string(),
+            ),
+        }
+    }
+
+    /// Collects the consume offsets reported by the consumers of `group`, limited to
+    /// `origin_client_id` when it is not empty.
+    pub async fn get_consume_status<MS: MessageStore>(
+        &mut self,
+        broker_runtime_inner: &BrokerRuntimeInner<MS>,
+        topic: &CheetahString,
+        group: &CheetahString,
+        origin_client_id: &CheetahString,
+    ) -> RemotingCommand {
+        let Some(consumer_group_info) = broker_runtime_inner
+            .consumer_manager()
+            .get_consumer_group_info(group)
+        else {
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                format!("No Any Consumer online in the consumer group: [{group}]"),
+            );
+        };
+        let request_header = GetConsumerStatusRequestHeader {
+            topic: topic.clone(),
+            group: group.clone(),
+            ..Default::default()
+        };
+        let mut consumer_table = HashMap::new();
+        for entry in consumer_group_info.get_channel_info_table().iter() {
+            let client_channel_info = entry.value();
+            let client_id = client_channel_info.client_id();
+            if client_channel_info.version() < RocketMqVersion::V307Snapshot as i32 {
+                return RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    format!(
+                        "the client does not support this feature. version={}",
+                        client_channel_info.version()
+                    ),
+                );
+            }
+            if !origin_client_id.is_empty() && origin_client_id != client_id {
+                continue;
+            }
+            let request = RemotingCommand::create_request_command(
+                RequestCode::GetConsumerStatusFromClient,
+                request_header.clone(),
+            );
+            let mut channel = entry.key().clone();
+            match self
+                .call_client(&mut channel, request, CALL_CLIENT_TIMEOUT_MILLIS)
+                .await
+            {
+                Ok(response) if ResponseCode::from(response.code()) == ResponseCode::Success => {
+                    if let Some(body) = response.body() {
+                        match GetConsumerStatusBody::decode(body) {
+                            Ok(body) => {
+                                consumer_table.insert(
+                                    client_id.clone(),
+                                    MessageQueueOffsetTable(body.message_queue_table),
+                                );
+                                info!(
+                                    "[get-consumer-status] get consumer status success. topic={}, \
+                                     group={}, clientId={}",
+                                    topic, group, client_id
+                                );
+                            }
+                            Err(e) => error!(
+                                "[get-consumer-status] decode consumer status failed. \
+                                 clientId={}, {}",
+                                client_id, e
+                            ),
+                        }
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => error!(
+                    "[get-consumer-status] get consumer status exception. topic={}, group={}, \
+                     clientId={}, {}",
+                    topic, group, client_id, e
+                ),
+            }
+            if !origin_client_id.is_empty() && origin_client_id == client_id {
+                break;
+            }
+        }
+        let body = GetConsumerStatusBody {
+            consumer_table,
+            ..Default::default()
+        };
+        match body.encode() {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                e.to_string(),
+            ),
+        }
+    }
+
+    async fn send_one_way(
+        &self,
+        channel: &Channel,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        match channel.upgrade() {
+            None => Err(rocketmq_error::RocketmqError::ChannelError(
+                "Channel is closed".to_string(),
+            )),
+            Some(channel) => channel
+                .send_one_way(request, CALL_CLIENT_TIMEOUT_MILLIS)
+                .await
+                .map(|_| ()),
+        }
+    }
 }
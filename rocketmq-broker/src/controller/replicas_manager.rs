@@ -14,17 +14,660 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashSet;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::broker::broker_role::BrokerRole;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_common::FileUtils;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use rocketmq_store::message_store::local_file_message_store::LocalFileMessageStore;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::select;
+use tokio::sync::Notify;
+use tracing::error;
+use tracing::info;
 use tracing::warn;
 
+use crate::broker_runtime::BrokerRuntimeInner;
+
+/// The broker id assigned by the controller, persisted so that a restarted broker keeps it.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BrokerMetadata {
+    cluster_name: CheetahString,
+    broker_name: CheetahString,
+    broker_id: i64,
+}
+
 #[derive(Default)]
-pub struct ReplicasManager {}
+struct ReplicasState {
+    controller_leader_address: Option<CheetahString>,
+    broker_controller_id: Option<i64>,
+    master_broker_id: Option<i64>,
+    master_address: Option<CheetahString>,
+    master_epoch: i32,
+    sync_state_set_epoch: i32,
+    sync_state_set: HashSet<i64>,
+    is_master: bool,
+}
+
+/// Drives the role of the broker in controller mode.
+///
+/// The broker gets its id from the controller, registers to it and then becomes master or slave
+/// of the broker set as the controller decides. It keeps sending heartbeats to the controller
+/// and follows the master changes by polling the replica info.
+pub struct ReplicasManager {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<LocalFileMessageStore>>,
+    state: Arc<Mutex<ReplicasState>>,
+    role_change_lock: Arc<tokio::sync::Mutex<()>>,
+    shutdown: Arc<Notify>,
+}
+
+impl Clone for ReplicasManager {
+    fn clone(&self) -> Self {
+        Self {
+            broker_runtime_inner: self.broker_runtime_inner.clone(),
+            state: self.state.clone(),
+            role_change_lock: self.role_change_lock.clone(),
+            shutdown: self.shutdown.clone(),
+        }
+    }
+}
 
 impl ReplicasManager {
+    pub fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<LocalFileMessageStore>>) -> Self {
+        Self {
+            broker_runtime_inner,
+            state: Arc::new(Mutex::new(ReplicasState::default())),
+            role_change_lock: Arc::new(tokio::sync::Mutex::new(())),
+            shutdown: Arc::new(Notify::new()),
+        }
+    }
+
     pub fn start(&mut self) {
-        warn!("ReplicasManager started not implemented");
+        let this = self.clone();
+        tokio::spawn(async move {
+            let retry_interval = Duration::from_secs(5);
+            loop {
+                if this.start_basic_service().await {
+                    break;
+                }
+                select! {
+                    _ = tokio::time::sleep(retry_interval) => {}
+                    _ = this.shutdown.notified() => return,
+                }
+            }
+            info!("ReplicasManager joined the broker set");
+            let broker_config = this.broker_runtime_inner.broker_config();
+            let mut heartbeat_interval = tokio::time::interval(Duration::from_millis(
+                broker_config.broker_heartbeat_interval.max(1),
+            ));
+            let mut sync_interval = tokio::time::interval(Duration::from_millis(
+                broker_config.sync_broker_metadata_period.max(1),
+            ));
+            loop {
+                select! {
+                    _ = heartbeat_interval.tick() => this.send_heartbeat_to_controller().await,
+                    _ = sync_interval.tick() => this.sync_replica_info().await,
+                    _ = this.shutdown.notified() => break,
+                }
+            }
+        });
     }
 
     pub fn shutdown(&mut self) {
-        warn!("ReplicasManager shutdown not implemented");
+        self.shutdown.notify_waiters();
+    }
+
+    pub fn is_master(&self) -> bool {
+        self.state.lock().is_master
+    }
+
+    pub fn get_master_epoch(&self) -> i32 {
+        self.state.lock().master_epoch
+    }
+
+    pub fn get_sync_state_set(&self) -> HashSet<i64> {
+        self.state.lock().sync_state_set.clone()
+    }
+
+    /// The epoch history of the local commit log, asked by the slaves to find where they
+    /// diverge from this broker.
+    pub fn get_broker_epoch_cache(&self) -> EpochEntryCache {
+        let broker_config = self.broker_runtime_inner.broker_config();
+        let (epoch_list, max_offset) = match self.broker_runtime_inner.message_store() {
+            Some(message_store) => (
+                message_store
+                    .get_epoch_cache()
+                    .map(|epoch_cache| epoch_cache.get_all_entries())
+                    .unwrap_or_default(),
+                message_store.get_max_phy_offset(),
+            ),
+            None => (Vec::new(), 0),
+        };
+        EpochEntryCache::new(
+            broker_config.broker_identity.broker_cluster_name.clone(),
+            broker_config.broker_identity.broker_name.clone(),
+            self.state.lock().broker_controller_id.unwrap_or(-1),
+            epoch_list,
+            max_offset,
+        )
+    }
+
+    /// Finds the controller leader, gets a broker id and registers to the controller, then takes
+    /// the role the controller decided for this broker.
+    async fn start_basic_service(&self) -> bool {
+        if !self.update_controller_metadata().await {
+            return false;
+        }
+        let Some(broker_id) = self.get_or_apply_broker_id().await else {
+            return false;
+        };
+        self.state.lock().broker_controller_id = Some(broker_id);
+        // the controller only elects live brokers
+        self.send_heartbeat_to_controller().await;
+        self.register_broker_to_controller(broker_id).await
+    }
+
+    fn controller_leader_address(&self) -> Option<CheetahString> {
+        self.state.lock().controller_leader_address.clone()
+    }
+
+    async fn update_controller_metadata(&self) -> bool {
+        let Some(controller_addr) = self
+            .broker_runtime_inner
+            .broker_config()
+            .controller_addr
+            .clone()
+        else {
+            warn!("controller address is not configured");
+            return false;
+        };
+        for address in controller_addr
+            .split(';')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+        {
+            let address = CheetahString::from_slice(address);
+            match self
+                .broker_runtime_inner
+                .broker_outer_api()
+                .get_controller_meta_data(&address)
+                .await
+            {
+                Ok(meta_data) => {
+                    let leader_address = match meta_data.controller_leader_address {
+                        Some(leader_address) if !leader_address.is_empty() => leader_address,
+                        _ if meta_data.is_leader == Some(true) => address.clone(),
+                        _ => continue,
+                    };
+                    info!("update controller leader address to {}", leader_address);
+                    self.state.lock().controller_leader_address = Some(leader_address);
+                    return true;
+                }
+                Err(e) => warn!("get controller metadata from {} failed: {}", address, e),
+            }
+        }
+        false
+    }
+
+    fn broker_metadata_path(&self) -> String {
+        self.broker_runtime_inner
+            .message_store_config()
+            .get_store_path_broker_identity()
+    }
+
+    async fn get_or_apply_broker_id(&self) -> Option<i64> {
+        let metadata_path = self.broker_metadata_path();
+        if let Ok(content) = FileUtils::file_to_string(metadata_path.as_str()) {
+            if let Ok(metadata) = SerdeJsonUtils::from_json_str::<BrokerMetadata>(content.as_str())
+            {
+                return Some(metadata.broker_id);
+            }
+        }
+
+        let controller_leader_address = self.controller_leader_address()?;
+        let broker_outer_api = self.broker_runtime_inner.broker_outer_api();
+        let broker_identity = &self.broker_runtime_inner.broker_config().broker_identity;
+        let cluster_name = &broker_identity.broker_cluster_name;
+        let broker_name = &broker_identity.broker_name;
+        let next_broker_id = match broker_outer_api
+            .get_next_broker_id(cluster_name, broker_name, &controller_leader_address)
+            .await
+        {
+            Ok(response) => response.next_broker_id?,
+            Err(e) => {
+                error!("get next broker id from controller failed: {}", e);
+                return None;
+            }
+        };
+        let register_check_code = CheetahString::from_string(format!(
+            "{};{}",
+            self.broker_runtime_inner.get_broker_addr(),
+            get_current_millis()
+        ));
+        if let Err(e) = broker_outer_api
+            .apply_broker_id(
+                cluster_name,
+                broker_name,
+                next_broker_id,
+                &register_check_code,
+                &controller_leader_address,
+            )
+            .await
+        {
+            error!("apply broker id {} failed: {}", next_broker_id, e);
+            return None;
+        }
+        let metadata = BrokerMetadata {
+            cluster_name: cluster_name.clone(),
+            broker_name: broker_name.clone(),
+            broker_id: next_broker_id,
+        };
+        match SerdeJsonUtils::to_json(&metadata) {
+            Ok(content) => {
+                if let Err(e) = FileUtils::string_to_file(content.as_str(), metadata_path.as_str())
+                {
+                    error!("persist broker metadata to {} failed: {}", metadata_path, e);
+                    return None;
+                }
+            }
+            Err(e) => {
+                error!("encode broker metadata failed: {}", e);
+                return None;
+            }
+        }
+        info!("applied broker id {} from controller", next_broker_id);
+        Some(next_broker_id)
+    }
+
+    async fn register_broker_to_controller(&self, broker_id: i64) -> bool {
+        let Some(controller_leader_address) = self.controller_leader_address() else {
+            return false;
+        };
+        let broker_identity = &self.broker_runtime_inner.broker_config().broker_identity;
+        let result = self
+            .broker_runtime_inner
+            .broker_outer_api()
+            .register_broker_to_controller(
+                &broker_identity.broker_cluster_name,
+                &broker_identity.broker_name,
+                broker_id,
+                self.broker_runtime_inner.get_broker_addr(),
+                &controller_leader_address,
+            )
+            .await;
+        let (response_header, sync_state_set) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                error!("register broker {} to controller failed: {}", broker_id, e);
+                return false;
+            }
+        };
+        let sync_state_set = sync_state_set.unwrap_or_default();
+        match (
+            response_header.master_broker_id,
+            response_header.master_address,
+        ) {
+            (Some(master_broker_id), Some(master_address)) if !master_address.is_empty() => {
+                self.apply_role(
+                    broker_id,
+                    master_broker_id,
+                    master_address,
+                    response_header.master_epoch.unwrap_or_default(),
+                    sync_state_set.sync_state_set_epoch,
+                    sync_state_set.sync_state_set,
+                )
+                .await
+            }
+            _ => self.broker_elect(broker_id).await,
+        }
+    }
+
+    /// Asks the controller to elect a master, this broker is a candidate.
+    async fn broker_elect(&self, broker_id: i64) -> bool {
+        let Some(controller_leader_address) = self.controller_leader_address() else {
+            return false;
+        };
+        let broker_identity = &self.broker_runtime_inner.broker_config().broker_identity;
+        let result = self
+            .broker_runtime_inner
+            .broker_outer_api()
+            .broker_elect(
+                &controller_leader_address,
+                &broker_identity.broker_cluster_name,
+                &broker_identity.broker_name,
+                broker_id,
+            )
+            .await;
+        let (response_header, response_body) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("broker {} elect master failed: {}", broker_id, e);
+                return false;
+            }
+        };
+        let (Some(master_broker_id), Some(master_address)) = (
+            response_header.master_broker_id,
+            response_header.master_address,
+        ) else {
+            return false;
+        };
+        let sync_state_set = response_body
+            .map(|body| body.sync_state_set.into_iter().collect())
+            .unwrap_or_default();
+        self.apply_role(
+            broker_id,
+            master_broker_id,
+            master_address,
+            response_header.master_epoch.unwrap_or_default(),
+            response_header.sync_state_set_epoch.unwrap_or_default(),
+            sync_state_set,
+        )
+        .await
+    }
+
+    async fn apply_role(
+        &self,
+        broker_id: i64,
+        master_broker_id: i64,
+        master_address: CheetahString,
+        master_epoch: i32,
+        sync_state_set_epoch: i32,
+        sync_state_set: HashSet<i64>,
+    ) -> bool {
+        if master_broker_id == broker_id {
+            self.change_to_master(master_epoch, sync_state_set_epoch, sync_state_set)
+                .await
+        } else {
+            self.change_to_slave(master_address, master_epoch, master_broker_id)
+                .await
+        }
+    }
+
+    async fn change_to_master(
+        &self,
+        new_master_epoch: i32,
+        sync_state_set_epoch: i32,
+        sync_state_set: HashSet<i64>,
+    ) -> bool {
+        let _guard = self.role_change_lock.lock().await;
+        {
+            let mut state = self.state.lock();
+            if state.is_master && new_master_epoch <= state.master_epoch {
+                if sync_state_set_epoch > state.sync_state_set_epoch {
+                    state.sync_state_set_epoch = sync_state_set_epoch;
+                    state.sync_state_set = sync_state_set;
+                }
+                return true;
+            }
+            if new_master_epoch < state.master_epoch {
+                warn!(
+                    "ignore stale master epoch {}, current {}",
+                    new_master_epoch, state.master_epoch
+                );
+                return false;
+            }
+        }
+        let Some(message_store) = self.broker_runtime_inner.message_store().clone() else {
+            return false;
+        };
+        // serve writes only after everything replicated so far is dispatched
+        while message_store.dispatch_behind_bytes() > 0 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        if !message_store.change_to_master(new_master_epoch).await {
+            error!("change to master of epoch {} failed", new_master_epoch);
+            return false;
+        }
+        {
+            let mut state = self.state.lock();
+            state.master_broker_id = state.broker_controller_id;
+            state.master_address = Some(self.broker_runtime_inner.get_broker_addr().clone());
+            state.master_epoch = new_master_epoch;
+            state.sync_state_set_epoch = sync_state_set_epoch;
+            state.sync_state_set = sync_state_set;
+            state.is_master = true;
+        }
+        self.change_broker_role(BrokerRole::SyncMaster, mix_all::MASTER_ID)
+            .await;
+        info!("broker changed to master of epoch {}", new_master_epoch);
+        true
+    }
+
+    async fn change_to_slave(
+        &self,
+        master_address: CheetahString,
+        new_master_epoch: i32,
+        master_broker_id: i64,
+    ) -> bool {
+        let _guard = self.role_change_lock.lock().await;
+        let broker_id = {
+            let state = self.state.lock();
+            if !state.is_master
+                && new_master_epoch <= state.master_epoch
+                && state.master_address.as_ref() == Some(&master_address)
+            {
+                return true;
+            }
+            if new_master_epoch < state.master_epoch {
+                warn!(
+                    "ignore stale master epoch {}, current {}",
+                    new_master_epoch, state.master_epoch
+                );
+                return false;
+            }
+            let Some(broker_id) = state.broker_controller_id else {
+                return false;
+            };
+            broker_id
+        };
+        let Some(message_store) = self.broker_runtime_inner.message_store().clone() else {
+            return false;
+        };
+        if !self
+            .truncate_to_master(&message_store, &master_address)
+            .await
+        {
+            return false;
+        }
+        if !message_store
+            .change_to_slave(master_address.as_str(), new_master_epoch, broker_id)
+            .await
+        {
+            error!("change to slave of {} failed", master_address);
+            return false;
+        }
+        {
+            let mut state = self.state.lock();
+            state.master_broker_id = Some(master_broker_id);
+            state.master_address = Some(master_address.clone());
+            state.master_epoch = new_master_epoch;
+            state.sync_state_set.clear();
+            state.is_master = false;
+        }
+        self.change_broker_role(BrokerRole::Slave, broker_id as u64)
+            .await;
+        info!(
+            "broker changed to slave of {}, master epoch {}",
+            master_address, new_master_epoch
+        );
+        true
+    }
+
+    /// Drops the local data the master does not have, then takes over the epoch history of the
+    /// master.
+    async fn truncate_to_master(
+        &self,
+        message_store: &ArcMut<LocalFileMessageStore>,
+        master_address: &CheetahString,
+    ) -> bool {
+        let Some(epoch_cache) = message_store.get_epoch_cache() else {
+            return true;
+        };
+        let master_epoch_cache = match self
+            .broker_runtime_inner
+            .broker_outer_api()
+            .get_broker_epoch_cache(master_address)
+            .await
+        {
+            Ok(master_epoch_cache) => master_epoch_cache,
+            Err(e) => {
+                warn!(
+                    "get epoch cache from master {} failed: {}",
+                    master_address, e
+                );
+                return false;
+            }
+        };
+        if !epoch_cache.get_all_entries().is_empty() {
+            let local_max_offset = message_store.get_max_phy_offset();
+            let consistent_point = epoch_cache.find_consistent_point(
+                local_max_offset,
+                &master_epoch_cache.epoch_list,
+                master_epoch_cache.max_offset,
+            );
+            if consistent_point < 0 {
+                error!(
+                    "no consistent point with master {}, local epochs {:?}",
+                    master_address,
+                    epoch_cache.get_all_entries()
+                );
+                return false;
+            }
+            if consistent_point < local_max_offset {
+                info!(
+                    "truncate local commit log from {} to {}",
+                    local_max_offset, consistent_point
+                );
+                if let Err(e) = message_store.truncate_files(consistent_point) {
+                    error!("truncate files to {} failed: {}", consistent_point, e);
+                    return false;
+                }
+                epoch_cache.truncate_suffix_by_offset(consistent_point);
+            }
+        }
+        epoch_cache.set_epoch_entries(master_epoch_cache.epoch_list);
+        true
+    }
+
+    async fn change_broker_role(&self, broker_role: BrokerRole, broker_id: u64) {
+        let mut inner = self.broker_runtime_inner.clone();
+        inner.broker_config_mut().broker_identity.broker_id = broker_id;
+        inner.message_store_config_mut().broker_role = broker_role;
+        inner.change_special_service_status(broker_role != BrokerRole::Slave);
+        inner.is_isolated().store(false, Ordering::Release);
+        let this = inner.clone();
+        inner
+            .register_broker_all_inner(this, true, false, true)
+            .await;
+    }
+
+    async fn send_heartbeat_to_controller(&self) {
+        let (controller_leader_address, broker_id, master_epoch) = {
+            let state = self.state.lock();
+            (
+                state.controller_leader_address.clone(),
+                state.broker_controller_id,
+                state.master_epoch,
+            )
+        };
+        let (Some(controller_leader_address), Some(broker_id)) =
+            (controller_leader_address, broker_id)
+        else {
+            return;
+        };
+        let (max_offset, confirm_offset) = self
+            .broker_runtime_inner
+            .message_store()
+            .as_ref()
+            .map_or((0, 0), |message_store| {
+                (
+                    message_store.get_max_phy_offset(),
+                    message_store.get_confirm_offset(),
+                )
+            });
+        let broker_config = self.broker_runtime_inner.broker_config();
+        self.broker_runtime_inner
+            .broker_outer_api()
+            .send_heartbeat_to_controller(
+                &controller_leader_address,
+                &broker_config.broker_identity.broker_cluster_name,
+                self.broker_runtime_inner.get_broker_addr(),
+                &broker_config.broker_identity.broker_name,
+                broker_id,
+                broker_config.controller_heart_beat_timeout_mills,
+                master_epoch,
+                max_offset,
+                confirm_offset,
+                broker_config.broker_election_priority,
+            )
+            .await;
+    }
+
+    /// Follows the master changes made by the controller, e.g. after the master went down.
+    async fn sync_replica_info(&self) {
+        let (controller_leader_address, broker_id) = {
+            let state = self.state.lock();
+            (
+                state.controller_leader_address.clone(),
+                state.broker_controller_id,
+            )
+        };
+        let (Some(controller_leader_address), Some(broker_id)) =
+            (controller_leader_address, broker_id)
+        else {
+            return;
+        };
+        let result = self
+            .broker_runtime_inner
+            .broker_outer_api()
+            .get_replica_info(
+                &controller_leader_address,
+                &self
+                    .broker_runtime_inner
+                    .broker_config()
+                    .broker_identity
+                    .broker_name,
+            )
+            .await;
+        let (response_header, sync_state_set) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("sync replica info from controller failed: {}", e);
+                // the leader may have changed
+                self.update_controller_metadata().await;
+                return;
+            }
+        };
+        match (
+            response_header.master_broker_id,
+            response_header.master_address,
+        ) {
+            (Some(master_broker_id), Some(master_address)) if !master_address.is_empty() => {
+                self.apply_role(
+                    broker_id,
+                    master_broker_id,
+                    master_address,
+                    response_header.master_epoch.unwrap_or_default(),
+                    sync_state_set.sync_state_set_epoch,
+                    sync_state_set.sync_state_set,
+                )
+                .await;
+            }
+            _ => {
+                self.broker_elect(broker_id).await;
+            }
+        }
     }
 }
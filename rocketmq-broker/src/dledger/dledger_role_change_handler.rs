@@ -0,0 +1,109 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocketmq_common::common::broker::broker_role::BrokerRole;
+use rocketmq_common::common::mix_all;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use rocketmq_store::dledger::dledger_leader_elector::RoleChangeHandler;
+use rocketmq_store::dledger::member_state::DLedgerRole;
+use rocketmq_store::message_store::local_file_message_store::LocalFileMessageStore;
+use tracing::info;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+/// Switches the broker between master and slave as its DLedger member changes role.
+pub(crate) struct DLedgerRoleChangeHandler {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<LocalFileMessageStore>>,
+}
+
+impl DLedgerRoleChangeHandler {
+    pub(crate) fn new(
+        broker_runtime_inner: ArcMut<BrokerRuntimeInner<LocalFileMessageStore>>,
+    ) -> Self {
+        Self {
+            broker_runtime_inner,
+        }
+    }
+
+    pub(crate) fn into_role_change_handler(self) -> RoleChangeHandler {
+        let handler = Arc::new(self);
+        Arc::new(move |role, term| {
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                handler.handle(role, term).await;
+            });
+        })
+    }
+
+    async fn handle(&self, role: DLedgerRole, term: i64) {
+        info!("dledger role changed to {} in term {}", role, term);
+        match role {
+            DLedgerRole::Leader => {
+                // serve writes only after everything committed so far is dispatched
+                while let Some(message_store) = self.broker_runtime_inner.message_store() {
+                    if message_store.dispatch_behind_bytes() <= 0 {
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                }
+                self.change_role(BrokerRole::SyncMaster, mix_all::MASTER_ID)
+                    .await;
+            }
+            DLedgerRole::Follower => {
+                let broker_id = self.slave_broker_id();
+                self.change_role(BrokerRole::Slave, broker_id).await;
+            }
+            DLedgerRole::Candidate => {
+                if self.broker_runtime_inner.message_store_config().broker_role != BrokerRole::Slave
+                {
+                    let broker_id = self.slave_broker_id();
+                    self.change_role(BrokerRole::Slave, broker_id).await;
+                }
+            }
+        }
+    }
+
+    async fn change_role(&self, broker_role: BrokerRole, broker_id: u64) {
+        let mut inner = self.broker_runtime_inner.clone();
+        inner.broker_config_mut().broker_identity.broker_id = broker_id;
+        inner.message_store_config_mut().broker_role = broker_role;
+        let is_master = broker_role != BrokerRole::Slave;
+        inner.change_special_service_status(is_master);
+        let this = inner.clone();
+        inner
+            .register_broker_all_inner(this, true, false, true)
+            .await;
+        info!(
+            "broker changed to {:?} with broker id {}",
+            broker_role, broker_id
+        );
+    }
+
+    /// Followers register as slaves, the member id `n1` maps to broker id 2 and so on.
+    fn slave_broker_id(&self) -> u64 {
+        self.broker_runtime_inner
+            .message_store_config()
+            .dledger_self_id
+            .as_deref()
+            .and_then(|self_id| self_id.get(1..))
+            .and_then(|digits| digits.parse::<u64>().ok())
+            .map_or(1, |id| id + 1)
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_filter::utils::bits_array::BitsArray;
+use rocketmq_store::base::commit_log_dispatcher::CommitLogDispatcher;
+use rocketmq_store::base::dispatch_request::DispatchRequest;
+use tracing::debug;
+use tracing::error;
+use tracing::warn;
+
+use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
+use crate::filter::message_evaluation_context::MessageEvaluationContext;
+
+/// Calculates the bloom filter bit map of every dispatched message, a bit of a consumer group
+/// is set if the message matches the group's SQL92 expression. The bit map is saved in the
+/// consume queue extend file, so that most of the unmatched messages can be skipped without
+/// reading the commit log.
+pub(crate) struct CommitLogDispatcherCalcBitMap {
+    broker_config: Arc<BrokerConfig>,
+    consumer_filter_manager: ConsumerFilterManager,
+}
+
+impl CommitLogDispatcherCalcBitMap {
+    pub fn new(
+        broker_config: Arc<BrokerConfig>,
+        consumer_filter_manager: ConsumerFilterManager,
+    ) -> Self {
+        Self {
+            broker_config,
+            consumer_filter_manager,
+        }
+    }
+}
+
+impl CommitLogDispatcher for CommitLogDispatcherCalcBitMap {
+    fn dispatch(&self, dispatch_request: &mut DispatchRequest) {
+        if !self.broker_config.enable_calc_filter_bit_map {
+            return;
+        }
+        let Some(bloom_filter) = self.consumer_filter_manager.get_bloom_filter() else {
+            return;
+        };
+        let filter_datas = self
+            .consumer_filter_manager
+            .get_by_topic(&dispatch_request.topic);
+        if filter_datas.is_empty() {
+            return;
+        }
+
+        let start_time = get_current_millis();
+        let mut filter_bit_map = BitsArray::create(bloom_filter.m() as usize);
+        let context = MessageEvaluationContext::new(dispatch_request.properties_map.clone());
+        for filter_data in filter_datas.iter() {
+            let Some(compiled_expression) = filter_data.compiled_expression() else {
+                error!(
+                    "[BUG] Consumer in filter manager has no compiled expression! {}",
+                    filter_data
+                );
+                continue;
+            };
+            let Some(bloom_filter_data) = filter_data.bloom_filter_data() else {
+                error!(
+                    "[BUG] Consumer in filter manager has no bloom data! {}",
+                    filter_data
+                );
+                continue;
+            };
+
+            let matched = match compiled_expression.evaluate(&context) {
+                Ok(value) => value.downcast_ref::<bool>().copied().unwrap_or(false),
+                Err(e) => {
+                    error!(
+                        "Calc filter bit map error!commitLogOffset={}, consumer={}, {}",
+                        dispatch_request.commit_log_offset, filter_data, e
+                    );
+                    false
+                }
+            };
+            debug!(
+                "Result of Calc bit map:ret={}, data={}, props={:?}, offset={}",
+                matched,
+                filter_data,
+                dispatch_request.properties_map,
+                dispatch_request.commit_log_offset
+            );
+
+            // eval true
+            if matched {
+                if let Err(e) = bloom_filter.hash_to(bloom_filter_data, &mut filter_bit_map) {
+                    error!("Calc filter bit map error!consumer={}, {}", filter_data, e);
+                }
+            }
+        }
+        dispatch_request.bit_map = Some(filter_bit_map.into_bytes());
+
+        let elapsed_time = get_current_millis().saturating_sub(start_time);
+        // 1ms
+        if elapsed_time >= 1 {
+            warn!(
+                "Spend {} ms to calc bit map, consumerNum={}, topic={}",
+                elapsed_time,
+                filter_datas.len(),
+                dispatch_request.topic
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use cheetah_string::CheetahString;
+    use rocketmq_common::common::filter::expression_type::ExpressionType;
+
+    use super::*;
+
+    #[test]
+    fn dispatch_sets_bits_of_matched_groups() {
+        let broker_config = Arc::new(BrokerConfig {
+            enable_calc_filter_bit_map: true,
+            ..BrokerConfig::default()
+        });
+        let consumer_filter_manager = ConsumerFilterManager::new(broker_config.clone());
+        let topic = CheetahString::from_static_str("TopicTest");
+        let sql92 = CheetahString::from_static_str(ExpressionType::SQL92);
+        for (group, expression) in [("GroupA", "a = 1"), ("GroupB", "a = 2")] {
+            assert!(consumer_filter_manager.register(
+                &topic,
+                &CheetahString::from_static_str(group),
+                &CheetahString::from_static_str(expression),
+                &sql92,
+                1,
+            ));
+        }
+        let dispatcher =
+            CommitLogDispatcherCalcBitMap::new(broker_config, consumer_filter_manager.clone());
+
+        let mut properties = HashMap::new();
+        properties.insert(
+            CheetahString::from_static_str("a"),
+            CheetahString::from_static_str("1"),
+        );
+        let mut dispatch_request = DispatchRequest {
+            topic: topic.clone(),
+            properties_map: Some(properties),
+            ..DispatchRequest::default()
+        };
+        dispatcher.dispatch(&mut dispatch_request);
+
+        let bits = BitsArray::from_bytes(dispatch_request.bit_map.unwrap());
+        let bloom_filter = consumer_filter_manager.get_bloom_filter().unwrap();
+        let data_of = |group: &'static str| {
+            consumer_filter_manager
+                .get_consumer_filter_data(&topic, &CheetahString::from_static_str(group))
+                .unwrap()
+        };
+        assert!(bloom_filter
+            .is_hit(data_of("GroupA").bloom_filter_data().unwrap(), &bits)
+            .unwrap());
+        assert!(!bloom_filter
+            .is_hit(data_of("GroupB").bloom_filter_data().unwrap(), &bits)
+            .unwrap());
+    }
+
+    #[test]
+    fn dispatch_is_skipped_when_disabled() {
+        let broker_config = Arc::new(BrokerConfig::default());
+        let consumer_filter_manager = ConsumerFilterManager::new(broker_config.clone());
+        let dispatcher = CommitLogDispatcherCalcBitMap::new(broker_config, consumer_filter_manager);
+        let mut dispatch_request = DispatchRequest::default();
+        dispatcher.dispatch(&mut dispatch_request);
+        assert!(dispatch_request.bit_map.is_none());
+    }
+}
@@ -14,9 +14,11 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::fmt;
 use std::sync::Arc;
 
 use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_filter::expression::Expression;
 use rocketmq_filter::utils::bloom_filter_data::BloomFilterData;
 use serde::Deserialize;
@@ -105,4 +107,48 @@ impl ConsumerFilterData {
     pub fn compiled_expression(&self) -> &Option<Arc<Box<dyn Expression + Send + Sync + 'static>>> {
         &self.compiled_expression
     }
+
+    pub fn set_compiled_expression(
+        &mut self,
+        compiled_expression: Option<Arc<Box<dyn Expression + Send + Sync + 'static>>>,
+    ) {
+        self.compiled_expression = compiled_expression;
+    }
+
+    #[inline]
+    pub fn is_dead(&self) -> bool {
+        self.dead_time >= self.born_time
+    }
+
+    /// Milliseconds elapsed since this filter data became dead, `-1` if it is still alive.
+    pub fn how_long_after_death(&self) -> i64 {
+        if self.is_dead() {
+            return get_current_millis() as i64 - self.dead_time as i64;
+        }
+        -1
+    }
+
+    /// Check this filter data has been used to calculate bit map when msg was stored in server.
+    pub fn is_msg_in_live(&self, msg_store_time: i64) -> bool {
+        msg_store_time > self.born_time as i64
+    }
+}
+
+impl fmt::Display for ConsumerFilterData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ConsumerFilterData [consumer_group={}, topic={}, expression={:?}, \
+             expression_type={:?}, born_time={}, dead_time={}, bloom_filter_data={:?}, \
+             client_version={}]",
+            self.consumer_group,
+            self.topic,
+            self.expression,
+            self.expression_type,
+            self.born_time,
+            self.dead_time,
+            self.bloom_filter_data,
+            self.client_version
+        )
+    }
 }
@@ -21,9 +21,13 @@ use bytes::Bytes;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::filter::expression_type::ExpressionType;
 use rocketmq_common::common::message::message_decoder;
+use rocketmq_filter::expression::evaluation_context::EvaluationContext;
+use rocketmq_filter::utils::bits_array::BitsArray;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_store::consume_queue::consume_queue_ext::CqExtUnit;
 use rocketmq_store::filter::MessageFilter;
+use tracing::debug;
+use tracing::error;
 
 use crate::filter::consumer_filter_data::ConsumerFilterData;
 use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
@@ -66,10 +70,9 @@ impl MessageFilter for ExpressionMessageFilter {
         tags_code: Option<i64>,
         cq_ext_unit: Option<&CqExtUnit>,
     ) -> bool {
-        if self.subscription_data.is_none() {
+        let Some(subscription_data) = self.subscription_data.as_ref() else {
             return true;
-        }
-        let subscription_data = self.subscription_data.as_ref().unwrap();
+        };
         if subscription_data.class_filter_mode {
             return true;
         }
@@ -80,11 +83,67 @@ impl MessageFilter for ExpressionMessageFilter {
             if subscription_data.sub_string.as_str() == SubscriptionData::SUB_ALL {
                 return true;
             }
-            subscription_data
+            return subscription_data
                 .code_set
-                .contains(&(tags_code.unwrap() as i32))
-        } else {
-            unimplemented!("SQL92 expression type is not supported yet.")
+                .contains(&(tags_code.unwrap() as i32));
+        }
+        // no expression or no bloom
+        let Some(consumer_filter_data) = self.consumer_filter_data.as_ref() else {
+            return true;
+        };
+        let Some(bloom_filter_data) = consumer_filter_data.bloom_filter_data() else {
+            return true;
+        };
+        if consumer_filter_data.expression().is_none()
+            || consumer_filter_data.compiled_expression().is_none()
+        {
+            return true;
+        }
+
+        // message is before consumer
+        let Some(cq_ext_unit) = cq_ext_unit else {
+            debug!(
+                "Pull matched because not in live: {}, {:?}",
+                consumer_filter_data, cq_ext_unit
+            );
+            return true;
+        };
+        if !consumer_filter_data.is_msg_in_live(cq_ext_unit.msg_store_time()) {
+            debug!(
+                "Pull matched because not in live: {}, {:?}",
+                consumer_filter_data, cq_ext_unit
+            );
+            return true;
+        }
+
+        let Some(filter_bit_map) = cq_ext_unit.filter_bit_map() else {
+            return true;
+        };
+        let Some(bloom_filter) = self.consumer_filter_manager.get_bloom_filter() else {
+            return true;
+        };
+        if !self.bloom_data_valid
+            || filter_bit_map.len() * 8 != bloom_filter_data.bit_num() as usize
+        {
+            return true;
+        }
+
+        let bits_array = BitsArray::from_bytes(filter_bit_map.clone());
+        match bloom_filter.is_hit(bloom_filter_data, &bits_array) {
+            Ok(ret) => {
+                debug!(
+                    "Pull {} by bit map:{}, {}, {:?}",
+                    ret, consumer_filter_data, bits_array, cq_ext_unit
+                );
+                ret
+            }
+            Err(e) => {
+                error!(
+                    "bloom filter error, sub={:?}, filter={}, bitMap={}, {}",
+                    subscription_data, consumer_filter_data, bits_array, e
+                );
+                true
+            }
         }
     }
 
@@ -93,42 +152,183 @@ impl MessageFilter for ExpressionMessageFilter {
         msg_buffer: Option<&[u8]>,
         properties: Option<&HashMap<CheetahString, CheetahString>>,
     ) -> bool {
-        if self.subscription_data.is_none() {
+        let Some(subscription_data) = self.subscription_data.as_ref() else {
             return true;
-        }
-        let subscription_data = self.subscription_data.as_ref().unwrap();
+        };
         if subscription_data.class_filter_mode {
             return true;
         }
         if ExpressionType::is_tag_type(Some(subscription_data.expression_type.as_str())) {
             return true;
         }
-        if self.consumer_filter_data.is_none() {
+        let Some(real_filter_data) = self.consumer_filter_data.as_ref() else {
             return true;
-        }
-        let real_filter_data = self.consumer_filter_data.as_ref().unwrap();
-        if real_filter_data.expression().is_none()
-            || real_filter_data.expression_type().is_none()
-            || real_filter_data.compiled_expression().is_none()
-        {
+        };
+        // no expression
+        let Some(compiled_expression) = real_filter_data.compiled_expression() else {
+            return true;
+        };
+        if real_filter_data.expression().is_none() {
             return true;
         }
 
-        let temp_properties = if properties.is_none() && msg_buffer.is_some() {
-            let bytes = msg_buffer.unwrap();
-            let mut bytes_ = Bytes::copy_from_slice(bytes);
-            message_decoder::decode_properties(&mut bytes_)
-        } else {
-            None
+        let context = match properties {
+            Some(properties) => MessageEvaluationContext::with_borrowed(Some(properties)),
+            None => MessageEvaluationContext::new(msg_buffer.and_then(|bytes| {
+                let mut bytes = Bytes::copy_from_slice(bytes);
+                message_decoder::decode_properties(&mut bytes)
+            })),
         };
-        let context = MessageEvaluationContext::new(temp_properties);
-        if let Some(filter) = real_filter_data.compiled_expression() {
-            match filter.evaluate(&context) {
-                Ok(value) => *value.downcast_ref::<bool>().unwrap_or(&false),
-                Err(_) => false,
+        match compiled_expression.evaluate(&context) {
+            Ok(value) => {
+                let matched = value.downcast_ref::<bool>().copied().unwrap_or(false);
+                debug!(
+                    "Pull eval result: {}, {}, {:?}",
+                    matched,
+                    real_filter_data,
+                    context.key_values()
+                );
+                matched
+            }
+            Err(e) => {
+                error!(
+                    "Message Filter error, {}, {:?}, {}",
+                    real_filter_data,
+                    context.key_values(),
+                    e
+                );
+                false
             }
-        } else {
-            true
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::broker::broker_config::BrokerConfig;
+    use rocketmq_common::common::message::MessageConst;
+    use rocketmq_common::TimeUtils::get_current_millis;
+    use rocketmq_remoting::protocol::filter::filter_api::FilterAPI;
+
+    use super::*;
+
+    fn sql_filter(expression: &str) -> ExpressionMessageFilter {
+        let subscription_data = SubscriptionData {
+            topic: CheetahString::from_static_str("TopicTest"),
+            sub_string: CheetahString::from(expression),
+            expression_type: CheetahString::from_static_str(ExpressionType::SQL92),
+            ..Default::default()
+        };
+        let consumer_filter_data = ConsumerFilterManager::build(
+            CheetahString::from_static_str("TopicTest"),
+            CheetahString::from_static_str("GroupTest"),
+            Some(CheetahString::from(expression)),
+            Some(CheetahString::from_static_str(ExpressionType::SQL92)),
+            1,
+        );
+        assert!(consumer_filter_data.is_some());
+        ExpressionMessageFilter::new(
+            Some(subscription_data),
+            consumer_filter_data,
+            Arc::new(ConsumerFilterManager::default()),
+        )
+    }
+
+    fn properties(pairs: &[(&str, &str)]) -> HashMap<CheetahString, CheetahString> {
+        pairs
+            .iter()
+            .map(|(k, v)| (CheetahString::from(*k), CheetahString::from(*v)))
+            .collect()
+    }
+
+    #[test]
+    fn sql_filter_matches_by_properties() {
+        let filter = sql_filter("a BETWEEN 1 AND 10 AND TAGS IN ('TagA', 'TagB')");
+        assert!(filter.is_matched_by_consume_queue(Some(1), None));
+        assert!(filter.is_matched_by_commit_log(
+            None,
+            Some(&properties(&[
+                ("a", "5"),
+                (MessageConst::PROPERTY_TAGS, "TagA")
+            ]))
+        ));
+        assert!(!filter.is_matched_by_commit_log(
+            None,
+            Some(&properties(&[
+                ("a", "11"),
+                (MessageConst::PROPERTY_TAGS, "TagA")
+            ]))
+        ));
+        assert!(!filter.is_matched_by_commit_log(None, Some(&properties(&[]))));
+    }
+
+    #[test]
+    fn sql_filter_matches_by_bit_map() {
+        let topic = CheetahString::from_static_str("TopicTest");
+        let group = CheetahString::from_static_str("GroupTest");
+        let sql92 = CheetahString::from_static_str(ExpressionType::SQL92);
+        let consumer_filter_manager = Arc::new(ConsumerFilterManager::new(Arc::new(
+            BrokerConfig::default(),
+        )));
+        consumer_filter_manager.register(
+            &topic,
+            &group,
+            &CheetahString::from_static_str("a = 1"),
+            &sql92,
+            1,
+        );
+        let consumer_filter_data = consumer_filter_manager.get_consumer_filter_data(&topic, &group);
+        let bloom_filter_data = consumer_filter_data
+            .as_ref()
+            .and_then(|data| data.bloom_filter_data().cloned())
+            .unwrap();
+        let filter = ExpressionMessageFilter::new(
+            Some(SubscriptionData {
+                topic: topic.clone(),
+                sub_string: CheetahString::from_static_str("a = 1"),
+                expression_type: sql92,
+                ..Default::default()
+            }),
+            consumer_filter_data,
+            consumer_filter_manager.clone(),
+        );
+
+        let bloom_filter = consumer_filter_manager.get_bloom_filter().unwrap();
+        let store_time = get_current_millis() as i64 + 1000;
+        let mut bits = BitsArray::create(bloom_filter.m() as usize);
+        let unmatched = CqExtUnit::new(0, store_time, Some(bits.bytes().to_vec()));
+        bloom_filter.hash_to(&bloom_filter_data, &mut bits).unwrap();
+        let matched = CqExtUnit::new(0, store_time, Some(bits.into_bytes()));
+
+        assert!(filter.is_matched_by_consume_queue(Some(0), Some(&matched)));
+        assert!(!filter.is_matched_by_consume_queue(Some(0), Some(&unmatched)));
+        // messages stored before the consumer registered can't be judged by bit map
+        let before_born = CqExtUnit::new(0, 0, unmatched.filter_bit_map().clone());
+        assert!(filter.is_matched_by_consume_queue(Some(0), Some(&before_born)));
+        assert!(filter.is_matched_by_consume_queue(Some(0), None));
+    }
+
+    #[test]
+    fn sql_filter_does_not_match_on_evaluation_error() {
+        let filter = sql_filter("a > 1");
+        assert!(!filter.is_matched_by_commit_log(None, Some(&properties(&[("a", "abc")]))));
+    }
+
+    #[test]
+    fn tag_filter_matches_by_tags_code() {
+        let subscription_data = FilterAPI::build_subscription_data(
+            &CheetahString::from_static_str("TopicTest"),
+            &CheetahString::from_static_str("TagA"),
+        )
+        .unwrap();
+        let tags_code = *subscription_data.code_set.iter().next().unwrap() as i64;
+        let filter = ExpressionMessageFilter::new(
+            Some(subscription_data),
+            None,
+            Arc::new(ConsumerFilterManager::default()),
+        );
+        assert!(filter.is_matched_by_consume_queue(Some(tags_code), None));
+        assert!(!filter.is_matched_by_consume_queue(Some(tags_code + 1), None));
+        assert!(filter.is_matched_by_commit_log(None, None));
+    }
+}
@@ -22,12 +22,20 @@ use cheetah_string::CheetahString;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
 use rocketmq_common::common::config_manager::ConfigManager;
 use rocketmq_common::common::filter::expression_type::ExpressionType;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
 use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_error::RocketmqError;
+use rocketmq_filter::expression::Expression;
+use rocketmq_filter::filter_factory::FilterFactory;
 use rocketmq_filter::utils::bloom_filter::BloomFilter;
+use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
+use tracing::error;
+use tracing::info;
 
 use crate::broker_path_config_helper::get_consumer_filter_path;
 use crate::filter::consumer_filter_data::ConsumerFilterData;
 use crate::filter::manager::consumer_filter_wrapper::ConsumerFilterWrapper;
+use crate::filter::manager::consumer_filter_wrapper::FilterDataMapByTopic;
 
 const MS_24_HOUR: u64 = Duration::from_hours(24).as_millis() as u64;
 
@@ -57,33 +65,74 @@ impl ConsumerFilterManager {
     }
 }
 
-//Fully implemented will be removed
-#[allow(unused_variables)]
 impl ConfigManager for ConsumerFilterManager {
-    fn decode0(&mut self, key: &[u8], body: &[u8]) {
-        todo!()
-    }
-
-    fn stop(&mut self) -> bool {
-        todo!()
-    }
-
     fn config_file_path(&self) -> String {
         get_consumer_filter_path(self.broker_config.store_path_root_dir.as_str())
     }
 
-    fn encode(&mut self) -> String {
-        todo!()
-    }
-
     fn encode_pretty(&self, pretty_format: bool) -> String {
-        "".to_string()
+        // clean
+        self.clean();
+        let wrapper = self.consumer_filter_wrapper.read();
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&*wrapper).expect("encode pretty failed")
+        } else {
+            SerdeJsonUtils::to_json(&*wrapper).expect("encode failed")
+        }
     }
 
-    fn decode(&self, json_string: &str) {}
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        let mut load = match SerdeJsonUtils::from_json_str::<ConsumerFilterWrapper>(json_string) {
+            Ok(load) => load,
+            Err(e) => {
+                error!("decode consumer filter data error: {}", e);
+                return;
+            }
+        };
+        let mut bloom_changed = false;
+        'outer: for data_map_by_topic in load.filter_data_by_topic.values_mut() {
+            for filter_data in data_map_by_topic.group_filter_data.values_mut() {
+                match Self::compile(filter_data.expression(), filter_data.expression_type()) {
+                    Ok(compiled_expression) => {
+                        filter_data.set_compiled_expression(compiled_expression)
+                    }
+                    Err(e) => error!("load filter data error, {}, {}", filter_data, e),
+                }
+
+                // check whether bloom filter is changed
+                // if changed, ignore the bit map calculated before.
+                if let (Some(bloom_filter), Some(bloom_filter_data)) =
+                    (self.bloom_filter.as_ref(), filter_data.bloom_filter_data())
+                {
+                    if !bloom_filter.is_valid(Some(bloom_filter_data)) {
+                        bloom_changed = true;
+                        info!(
+                            "Bloom filter is changed!So ignore all filter data persisted! {:?}",
+                            filter_data.bloom_filter_data()
+                        );
+                        break 'outer;
+                    }
+                }
+
+                info!("load exist consumer filter data: {}", filter_data);
+
+                if filter_data.dead_time() == 0 {
+                    // we think all consumers are dead when load
+                    let dead_time = get_current_millis().saturating_sub(30 * 1000);
+                    filter_data.set_dead_time(dead_time.max(filter_data.born_time()));
+                }
+            }
+        }
+
+        if !bloom_changed {
+            self.consumer_filter_wrapper.write().filter_data_by_topic = load.filter_data_by_topic;
+        }
+    }
 }
 
-#[allow(unused_variables)]
 impl ConsumerFilterManager {
     pub fn build(
         topic: CheetahString,
@@ -105,32 +154,288 @@ impl ConsumerFilterManager {
         consumer_filter_data.set_expression_type(type_);
         consumer_filter_data.set_client_version(client_version);
 
-        /*        let filter_factory = FilterFactory;
-                match filter_factory.get(&type_).compile(&expression) {
-                    Ok(compiled_expression) => {
-                        consumer_filter_data.set_compiled_expression(compiled_expression);
-                    }
-                    Err(e) => {
-                        eprintln!(
-                            "parse error: expr={}, topic={}, group={}, error={}",
-                            expression, topic, consumer_group, e
-                        );
-                        return None;
-                    }
-                }
-        */
+        match Self::compile(
+            consumer_filter_data.expression(),
+            consumer_filter_data.expression_type(),
+        ) {
+            Ok(compiled_expression) => {
+                consumer_filter_data.set_compiled_expression(compiled_expression);
+            }
+            Err(e) => {
+                error!(
+                    "parse error: expr={:?}, topic={}, group={}, error={}",
+                    consumer_filter_data.expression(),
+                    consumer_filter_data.topic(),
+                    consumer_filter_data.consumer_group(),
+                    e
+                );
+                return None;
+            }
+        }
         Some(consumer_filter_data)
     }
 
+    /// Compiles `expression` with the filter registered for `type_` in [`FilterFactory`].
+    pub fn compile(
+        expression: Option<&CheetahString>,
+        type_: Option<&CheetahString>,
+    ) -> rocketmq_error::RocketMQResult<Option<Arc<Box<dyn Expression + Send + Sync + 'static>>>>
+    {
+        let (Some(expression), Some(type_)) = (expression, type_) else {
+            return Ok(None);
+        };
+        match FilterFactory::instance().get(type_.as_str()) {
+            None => Err(RocketmqError::MQFilterError(format!(
+                "Filter type {} is not supported",
+                type_
+            ))),
+            Some(filter_spi) => Ok(Some(Arc::new(filter_spi.compile(expression.as_str())?))),
+        }
+    }
+
+    pub fn register_group(&self, consumer_group: &CheetahString, sub_list: &[&SubscriptionData]) {
+        for subscription_data in sub_list {
+            self.register(
+                &subscription_data.topic,
+                consumer_group,
+                &subscription_data.sub_string,
+                &subscription_data.expression_type,
+                subscription_data.sub_version as u64,
+            );
+        }
+
+        // make illegal topic dead.
+        let mut wrapper = self.consumer_filter_wrapper.write();
+        for data_map_by_topic in wrapper.filter_data_by_topic.values_mut() {
+            if let Some(filter_data) = data_map_by_topic
+                .group_filter_data
+                .get_mut(consumer_group.as_str())
+            {
+                let exist = sub_list
+                    .iter()
+                    .any(|subscription_data| &subscription_data.topic == filter_data.topic());
+                if !exist && !filter_data.is_dead() {
+                    filter_data.set_dead_time(get_current_millis());
+                    info!(
+                        "Consumer filter changed: {}, make illegal topic dead:{}",
+                        consumer_group, filter_data
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn register(
+        &self,
+        topic: &CheetahString,
+        consumer_group: &CheetahString,
+        expression: &CheetahString,
+        type_: &CheetahString,
+        client_version: u64,
+    ) -> bool {
+        if ExpressionType::is_tag_type(Some(type_.as_str())) {
+            return false;
+        }
+        if expression.is_empty() {
+            return false;
+        }
+        let bloom_filter_data = self.bloom_filter.as_ref().map(|bloom_filter| {
+            bloom_filter.generate(format!("{}#{}", consumer_group, topic).as_str())
+        });
+        let mut wrapper = self.consumer_filter_wrapper.write();
+        let filter_data_map_by_topic = wrapper
+            .filter_data_by_topic
+            .entry(topic.to_string())
+            .or_insert_with(|| FilterDataMapByTopic::new(topic.as_str()));
+        filter_data_map_by_topic.register(
+            consumer_group.as_str(),
+            expression,
+            type_,
+            bloom_filter_data,
+            client_version,
+        )
+    }
+
+    pub fn un_register(&self, consumer_group: &CheetahString) {
+        let mut wrapper = self.consumer_filter_wrapper.write();
+        for data_map_by_topic in wrapper.filter_data_by_topic.values_mut() {
+            data_map_by_topic.un_register(consumer_group.as_str());
+        }
+    }
+
     pub fn get_consumer_filter_data(
         &self,
         topic: &CheetahString,
         consumer_group: &CheetahString,
     ) -> Option<ConsumerFilterData> {
-        None
+        self.consumer_filter_wrapper
+            .read()
+            .filter_data_by_topic
+            .get(topic.as_str())
+            .and_then(|data_map_by_topic| {
+                data_map_by_topic
+                    .group_filter_data
+                    .get(consumer_group.as_str())
+                    .cloned()
+            })
+    }
+
+    pub fn get_by_topic(&self, topic: &CheetahString) -> Vec<ConsumerFilterData> {
+        self.consumer_filter_wrapper
+            .read()
+            .filter_data_by_topic
+            .get(topic.as_str())
+            .map(|data_map_by_topic| {
+                data_map_by_topic
+                    .group_filter_data
+                    .values()
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_by_group(&self, consumer_group: &CheetahString) -> Vec<ConsumerFilterData> {
+        self.consumer_filter_wrapper
+            .read()
+            .filter_data_by_topic
+            .values()
+            .filter_map(|data_map_by_topic| {
+                data_map_by_topic
+                    .group_filter_data
+                    .get(consumer_group.as_str())
+                    .cloned()
+            })
+            .collect()
     }
 
     pub fn get_bloom_filter(&self) -> Option<&BloomFilter> {
         self.bloom_filter.as_ref()
     }
+
+    fn clean(&self) {
+        let mut wrapper = self.consumer_filter_wrapper.write();
+        wrapper
+            .filter_data_by_topic
+            .retain(|topic, data_map_by_topic| {
+                data_map_by_topic
+                    .group_filter_data
+                    .retain(|_, filter_data| {
+                        if filter_data.how_long_after_death() >= MS_24_HOUR as i64 {
+                            info!("Remove filter consumer {}, died too long!", filter_data);
+                            return false;
+                        }
+                        true
+                    });
+                if data_map_by_topic.group_filter_data.is_empty() {
+                    info!("Topic has no consumer, remove it! {}", topic);
+                    return false;
+                }
+                true
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql92() -> CheetahString {
+        CheetahString::from_static_str(ExpressionType::SQL92)
+    }
+
+    #[test]
+    fn build_compiles_sql_expression() {
+        let data = ConsumerFilterManager::build(
+            "topic".into(),
+            "group".into(),
+            Some("a > 1".into()),
+            Some(sql92()),
+            1,
+        )
+        .unwrap();
+        assert!(data.compiled_expression().is_some());
+        assert!(!data.is_dead());
+
+        assert!(ConsumerFilterManager::build(
+            "topic".into(),
+            "group".into(),
+            Some("a >".into()),
+            Some(sql92()),
+            1,
+        )
+        .is_none());
+        assert!(ConsumerFilterManager::build(
+            "topic".into(),
+            "group".into(),
+            Some("TagA".into()),
+            Some(ExpressionType::TAG.into()),
+            1,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn register_and_un_register_by_group() {
+        let manager = ConsumerFilterManager::new(Arc::new(BrokerConfig::default()));
+        let topic = CheetahString::from_static_str("topic");
+        let group = CheetahString::from_static_str("group");
+        let subscription_data = SubscriptionData {
+            topic: topic.clone(),
+            sub_string: "a = 1".into(),
+            expression_type: sql92(),
+            sub_version: 1,
+            ..Default::default()
+        };
+        manager.register_group(&group, &[&subscription_data]);
+        let data = manager.get_consumer_filter_data(&topic, &group).unwrap();
+        assert_eq!(data.expression().unwrap(), "a = 1");
+        assert_eq!(manager.get_by_group(&group).len(), 1);
+        assert_eq!(manager.get_by_topic(&topic).len(), 1);
+
+        // older version is ignored
+        assert!(!manager.register(&topic, &group, &"a = 2".into(), &sql92(), 0));
+        // newer version replaces the expression
+        assert!(manager.register(&topic, &group, &"a = 2".into(), &sql92(), 2));
+        let data = manager.get_consumer_filter_data(&topic, &group).unwrap();
+        assert_eq!(data.expression().unwrap(), "a = 2");
+        assert_eq!(data.client_version(), 2);
+
+        manager.un_register(&group);
+        assert!(manager
+            .get_consumer_filter_data(&topic, &group)
+            .unwrap()
+            .is_dead());
+
+        // subscribing to another topic makes the old one dead
+        let other = SubscriptionData {
+            topic: "other".into(),
+            ..subscription_data
+        };
+        manager.register_group(&group, &[&other]);
+        assert!(manager
+            .get_consumer_filter_data(&topic, &group)
+            .unwrap()
+            .is_dead());
+        assert!(!manager
+            .get_consumer_filter_data(&"other".into(), &group)
+            .unwrap()
+            .is_dead());
+    }
+
+    #[test]
+    fn encode_and_decode_recompiles_expression() {
+        let manager = ConsumerFilterManager::new(Arc::new(BrokerConfig::default()));
+        let topic = CheetahString::from_static_str("topic");
+        let group = CheetahString::from_static_str("group");
+        assert!(manager.register(&topic, &group, &"a IN ('x')".into(), &sql92(), 1));
+        let json = manager.encode_pretty(false);
+
+        let loaded = ConsumerFilterManager::new(Arc::new(BrokerConfig::default()));
+        loaded.decode(json.as_str());
+        let data = loaded.get_consumer_filter_data(&topic, &group).unwrap();
+        assert!(data.compiled_expression().is_some());
+        // consumers are considered dead until they register again
+        assert!(data.is_dead());
+    }
 }
@@ -16,20 +16,147 @@
  */
 use std::collections::HashMap;
 
+use cheetah_string::CheetahString;
+use rocketmq_filter::utils::bloom_filter_data::BloomFilterData;
 use serde::Deserialize;
 use serde::Serialize;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
 
 use crate::filter::consumer_filter_data::ConsumerFilterData;
+use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ConsumerFilterWrapper {
-    filter_data_by_topic: HashMap<String /* Topic */, FilterDataMapByTopic>,
+    pub(crate) filter_data_by_topic: HashMap<String /* Topic */, FilterDataMapByTopic>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterDataMapByTopic {
-    filter_data_map: HashMap<String /* consumer group */, ConsumerFilterData>,
+    pub(crate) group_filter_data: HashMap<String /* consumer group */, ConsumerFilterData>,
     topic: String,
 }
+
+impl FilterDataMapByTopic {
+    pub fn new(topic: impl Into<String>) -> Self {
+        Self {
+            group_filter_data: HashMap::new(),
+            topic: topic.into(),
+        }
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn un_register(&mut self, consumer_group: &str) {
+        if let Some(data) = self.group_filter_data.get_mut(consumer_group) {
+            if data.is_dead() {
+                return;
+            }
+            let now = rocketmq_common::TimeUtils::get_current_millis();
+            info!("Unregister consumer filter: {}, deadTime: {}", data, now);
+            data.set_dead_time(now);
+        }
+    }
+
+    pub fn register(
+        &mut self,
+        consumer_group: &str,
+        expression: &CheetahString,
+        type_: &CheetahString,
+        bloom_filter_data: Option<BloomFilterData>,
+        client_version: u64,
+    ) -> bool {
+        let Some(old) = self.group_filter_data.get_mut(consumer_group) else {
+            let consumer_filter_data = ConsumerFilterManager::build(
+                CheetahString::from_slice(self.topic.as_str()),
+                CheetahString::from_slice(consumer_group),
+                Some(expression.clone()),
+                Some(type_.clone()),
+                client_version,
+            );
+            let Some(mut consumer_filter_data) = consumer_filter_data else {
+                return false;
+            };
+            consumer_filter_data.set_bloom_filter_data(bloom_filter_data);
+            info!("New consumer filter registered: {}", consumer_filter_data);
+            self.group_filter_data
+                .insert(consumer_group.to_string(), consumer_filter_data);
+            return true;
+        };
+
+        if client_version <= old.client_version() {
+            if old.expression_type() != Some(type_) || old.expression() != Some(expression) {
+                warn!(
+                    "Ignore consumer({} : {}) filter, because of version {} <= {}, but maybe info \
+                     changed!old={:?}:{:?}, ignored={}:{}",
+                    consumer_group,
+                    self.topic,
+                    client_version,
+                    old.client_version(),
+                    old.expression_type(),
+                    old.expression(),
+                    type_,
+                    expression
+                );
+            }
+            if client_version == old.client_version() && old.is_dead() {
+                re_alive(old);
+                return true;
+            }
+            return false;
+        }
+
+        let mut change =
+            old.expression() != Some(expression) || old.expression_type() != Some(type_);
+        if old.bloom_filter_data() != bloom_filter_data.as_ref() {
+            change = true;
+        }
+
+        // if subscribe data is changed, or consumer is died too long.
+        if change {
+            let consumer_filter_data = ConsumerFilterManager::build(
+                CheetahString::from_slice(self.topic.as_str()),
+                CheetahString::from_slice(consumer_group),
+                Some(expression.clone()),
+                Some(type_.clone()),
+                client_version,
+            );
+            let Some(mut consumer_filter_data) = consumer_filter_data else {
+                // new expression compile error, remove old, let client report error.
+                error!(
+                    "Consumer filter info change, but compile error, group: {}, topic: {}, \
+                     expression: {}",
+                    consumer_group, self.topic, expression
+                );
+                self.group_filter_data.remove(consumer_group);
+                return false;
+            };
+            consumer_filter_data.set_bloom_filter_data(bloom_filter_data);
+            info!(
+                "Consumer filter info change, old: {}, new: {}, change: {}",
+                old, consumer_filter_data, change
+            );
+            *old = consumer_filter_data;
+        } else {
+            old.set_client_version(client_version);
+            if old.is_dead() {
+                re_alive(old);
+            }
+        }
+        true
+    }
+}
+
+fn re_alive(filter_data: &mut ConsumerFilterData) {
+    let old_dead_time = filter_data.dead_time();
+    filter_data.set_dead_time(0);
+    info!(
+        "Re alive consumer filter: {}, oldDeadTime: {}",
+        filter_data, old_dead_time
+    );
+}
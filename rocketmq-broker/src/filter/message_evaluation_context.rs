@@ -14,22 +14,34 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use cheetah_string::CheetahString;
 use rocketmq_filter::expression::evaluation_context::EvaluationContext;
 
-pub struct MessageEvaluationContext {
-    properties: Option<HashMap<CheetahString, CheetahString>>,
+pub struct MessageEvaluationContext<'a> {
+    properties: Option<Cow<'a, HashMap<CheetahString, CheetahString>>>,
 }
 
-impl MessageEvaluationContext {
+impl MessageEvaluationContext<'_> {
     pub fn new(properties: Option<HashMap<CheetahString, CheetahString>>) -> Self {
-        Self { properties }
+        Self {
+            properties: properties.map(Cow::Owned),
+        }
     }
 }
 
-impl EvaluationContext for MessageEvaluationContext {
+impl<'a> MessageEvaluationContext<'a> {
+    /// Evaluates against the caller's properties without copying them.
+    pub fn with_borrowed(properties: Option<&'a HashMap<CheetahString, CheetahString>>) -> Self {
+        Self {
+            properties: properties.map(Cow::Borrowed),
+        }
+    }
+}
+
+impl EvaluationContext for MessageEvaluationContext<'_> {
     fn get(&self, name: &str) -> Option<&CheetahString> {
         self.properties
             .as_ref()
@@ -37,7 +49,9 @@ impl EvaluationContext for MessageEvaluationContext {
     }
 
     fn key_values(&self) -> Option<HashMap<CheetahString, CheetahString>> {
-        self.properties.clone()
+        self.properties
+            .as_ref()
+            .map(|props| props.clone().into_owned())
     }
 }
 
@@ -57,7 +71,7 @@ mod tests {
             CheetahString::from_static_str("value"),
         );
         let context = MessageEvaluationContext::new(Some(properties.clone()));
-        assert_eq!(context.properties, Some(properties));
+        assert_eq!(context.properties.as_deref(), Some(&properties));
     }
 
     #[test]
@@ -108,4 +122,20 @@ mod tests {
         let context = MessageEvaluationContext::new(None);
         assert!(context.key_values().is_none());
     }
+
+    #[test]
+    fn with_borrowed_reads_without_copying() {
+        let mut properties = HashMap::new();
+        properties.insert(
+            CheetahString::from_static_str("key"),
+            CheetahString::from_static_str("value"),
+        );
+        let context = MessageEvaluationContext::with_borrowed(Some(&properties));
+        assert!(matches!(context.properties, Some(Cow::Borrowed(_))));
+        assert_eq!(
+            context.get("key"),
+            Some(&CheetahString::from_static_str("value"))
+        );
+        assert_eq!(context.key_values(), Some(properties.clone()));
+    }
 }
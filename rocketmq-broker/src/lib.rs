@@ -25,6 +25,7 @@ pub use broker_bootstrap::Builder;
 
 pub mod command;
 
+pub(crate) mod auth;
 pub(crate) mod broker;
 pub(crate) mod broker_bootstrap;
 pub(crate) mod broker_path_config_helper;
@@ -32,12 +33,15 @@ pub(crate) mod broker_runtime;
 pub(crate) mod client;
 pub(crate) mod coldctr;
 pub(crate) mod controller;
+pub(crate) mod dledger;
 pub(crate) mod failover;
 pub(crate) mod filter;
 pub(crate) mod hook;
 pub(crate) mod latency;
 pub(crate) mod load_balance;
 pub(crate) mod long_polling;
+#[cfg(feature = "metrics")]
+pub(crate) mod metrics;
 pub(crate) mod mqtrace;
 pub(crate) mod offset;
 pub(crate) mod out_api;
@@ -0,0 +1,402 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::metrics::metric_registry::MetricRegistry;
+use rocketmq_common::common::metrics::prometheus_exporter::PrometheusExporter;
+use rocketmq_common::common::metrics::text_encoder::MetricType;
+use rocketmq_common::common::metrics::text_encoder::TextEncoder;
+use rocketmq_common::common::metrics::MetricsLabels;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::running::running_stats::RunningStats;
+use rocketmq_common::common::stats::Stats;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_rust::ArcMut;
+use rocketmq_rust::WeakArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use rocketmq_store::stats::broker_stats_manager::split_account_stat_key;
+use tracing::info;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+const NODE_TYPE_BROKER: &str = "broker";
+const TOPIC_GROUP_SEPARATOR: char = '@';
+const LABEL_STAT_KIND: &str = "kind";
+const LABEL_STAT_ITEM: &str = "item";
+
+/// Exposes the broker statistics in the Prometheus text format.
+///
+/// Nothing is recorded on the hot path: every metric is read at scrape time from the
+/// `BrokerStatsManager` and its account `StatisticsManager`, the consumer offsets, the pop
+/// inflight counter, the `StoreStatsService` and the store.
+pub(crate) struct BrokerMetricsManager {
+    registry: Arc<MetricRegistry>,
+    exporter: Option<PrometheusExporter>,
+}
+
+impl BrokerMetricsManager {
+    pub fn new<MS: MessageStore>(broker_runtime_inner: &ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        let registry = Arc::new(MetricRegistry::new());
+        let inner = ArcMut::downgrade(broker_runtime_inner);
+        registry.register(Arc::new(move |encoder: &mut TextEncoder| {
+            if let Some(inner) = WeakArcMut::upgrade(&inner) {
+                collect_broker_metrics(inner.as_ref(), encoder);
+            }
+        }));
+        BrokerMetricsManager {
+            registry,
+            exporter: None,
+        }
+    }
+
+    pub async fn start(&mut self, host: &str, port: u16) -> io::Result<()> {
+        let exporter = PrometheusExporter::start(host, port, self.registry.clone()).await?;
+        self.exporter = Some(exporter);
+        Ok(())
+    }
+
+    pub fn shutdown(&mut self) {
+        if let Some(exporter) = self.exporter.take() {
+            exporter.shutdown();
+            info!("Broker metrics exporter shutdown");
+        }
+    }
+}
+
+fn collect_broker_metrics<MS: MessageStore>(
+    inner: &BrokerRuntimeInner<MS>,
+    encoder: &mut TextEncoder,
+) {
+    let broker_identity = &inner.broker_config().broker_identity;
+    let base_labels = [
+        (
+            MetricsLabels::CLUSTER_NAME,
+            broker_identity.broker_cluster_name.as_str(),
+        ),
+        (MetricsLabels::NODE_TYPE, NODE_TYPE_BROKER),
+        (MetricsLabels::NODE_ID, broker_identity.broker_name.as_str()),
+    ];
+
+    collect_stats_metrics(inner, &base_labels, encoder);
+    collect_account_metrics(inner, &base_labels, encoder);
+    collect_consumer_metrics(inner, &base_labels, encoder);
+    collect_store_metrics(inner, &base_labels, encoder);
+
+    encoder.write_gauge(
+        "rocketmq_topic_number",
+        "Number of topics on the broker.",
+        &base_labels,
+        inner
+            .topic_config_manager()
+            .topic_config_table()
+            .lock()
+            .len() as f64,
+    );
+    encoder.write_gauge(
+        "rocketmq_consumer_group_number",
+        "Number of subscription groups on the broker.",
+        &base_labels,
+        inner
+            .subscription_group_manager()
+            .subscription_group_wrapper()
+            .lock()
+            .subscription_group_table()
+            .len() as f64,
+    );
+}
+
+/// Message and byte counters kept by the `BrokerStatsManager`.
+fn collect_stats_metrics<MS: MessageStore>(
+    inner: &BrokerRuntimeInner<MS>,
+    base_labels: &[(&str, &str)],
+    encoder: &mut TextEncoder,
+) {
+    let stats_table = inner.broker_stats_manager().get_stats_table();
+    let stats_table = stats_table.read();
+    let families = [
+        (
+            Stats::TOPIC_PUT_NUMS,
+            "rocketmq_messages_in_total",
+            "Total number of incoming messages.",
+        ),
+        (
+            Stats::TOPIC_PUT_SIZE,
+            "rocketmq_throughput_in_total",
+            "Total bytes of incoming messages.",
+        ),
+        (
+            Stats::GROUP_GET_NUMS,
+            "rocketmq_messages_out_total",
+            "Total number of outgoing messages.",
+        ),
+        (
+            Stats::GROUP_GET_SIZE,
+            "rocketmq_throughput_out_total",
+            "Total bytes of outgoing messages.",
+        ),
+    ];
+    for (stats_name, metric_name, help) in families {
+        encoder.write_family(metric_name, help, MetricType::Counter);
+        let Some(stats_item_set) = stats_table.get(stats_name) else {
+            continue;
+        };
+        for entry in stats_item_set.get_stats_item_table().iter() {
+            let value = entry.value().get_value().load(Ordering::Relaxed) as f64;
+            let mut labels = base_labels.to_vec();
+            match entry.key().split_once(TOPIC_GROUP_SEPARATOR) {
+                Some((topic, group)) => {
+                    labels.push((MetricsLabels::TOPIC, topic));
+                    labels.push((MetricsLabels::CONSUMER_GROUP, group));
+                }
+                None => labels.push((MetricsLabels::TOPIC, entry.key().as_str())),
+            }
+            encoder.write_sample(metric_name, &labels, value);
+        }
+    }
+}
+
+/// Per account counters kept by the `StatisticsManager` of the `BrokerStatsManager`.
+fn collect_account_metrics<MS: MessageStore>(
+    inner: &BrokerRuntimeInner<MS>,
+    base_labels: &[(&str, &str)],
+    encoder: &mut TextEncoder,
+) {
+    encoder.write_family(
+        "rocketmq_account_stats_total",
+        "Account statistics by kind and item.",
+        MetricType::Counter,
+    );
+    for item in inner
+        .broker_stats_manager()
+        .get_account_stat_manager()
+        .statistics_items()
+    {
+        // the key is owner|instance|topic|group|type
+        let key_parts = split_account_stat_key(item.stat_object());
+        for (item_name, value) in item.item_names().iter().zip(item.item_accumulates()) {
+            let mut labels = base_labels.to_vec();
+            labels.push((LABEL_STAT_KIND, item.stat_kind()));
+            labels.push((LABEL_STAT_ITEM, item_name.as_str()));
+            if let [_, _, topic, group, ..] = key_parts.as_slice() {
+                labels.push((MetricsLabels::TOPIC, topic));
+                labels.push((MetricsLabels::CONSUMER_GROUP, group));
+            }
+            encoder.write_sample(
+                "rocketmq_account_stats_total",
+                &labels,
+                value.load(Ordering::Relaxed) as f64,
+            );
+        }
+    }
+}
+
+/// Consumer lag, pop inflight messages and transaction half messages.
+fn collect_consumer_metrics<MS: MessageStore>(
+    inner: &BrokerRuntimeInner<MS>,
+    base_labels: &[(&str, &str)],
+    encoder: &mut TextEncoder,
+) {
+    let Some(message_store) = inner.message_store() else {
+        return;
+    };
+    let offset_table = inner.consumer_offset_manager().offset_table_snapshot();
+    let lag_of = |topic: &str, queue_offsets: &HashMap<i32, i64>| -> i64 {
+        let topic = CheetahString::from_slice(topic);
+        queue_offsets
+            .iter()
+            .map(|(queue_id, offset)| {
+                (message_store.get_max_offset_in_queue(&topic, *queue_id) - offset).max(0)
+            })
+            .sum()
+    };
+
+    let half_key = format!(
+        "{}{}{}",
+        TopicValidator::RMQ_SYS_TRANS_HALF_TOPIC,
+        TOPIC_GROUP_SEPARATOR,
+        mix_all::CID_SYS_RMQ_TRANS
+    );
+    let mut half_messages = 0;
+    encoder.write_family(
+        "rocketmq_consumer_lag_messages",
+        "Number of messages not yet consumed by the group.",
+        MetricType::Gauge,
+    );
+    for (key, queue_offsets) in offset_table.iter() {
+        let Some((topic, group)) = key.split_once(TOPIC_GROUP_SEPARATOR) else {
+            continue;
+        };
+        let lag = lag_of(topic, queue_offsets);
+        if key.as_str() == half_key {
+            half_messages = lag;
+            continue;
+        }
+        let mut labels = base_labels.to_vec();
+        labels.push((MetricsLabels::TOPIC, topic));
+        labels.push((MetricsLabels::CONSUMER_GROUP, group));
+        encoder.write_sample("rocketmq_consumer_lag_messages", &labels, lag as f64);
+    }
+
+    encoder.write_family(
+        "rocketmq_pop_inflight_messages",
+        "Number of popped messages not yet acked.",
+        MetricType::Gauge,
+    );
+    for (key, num) in inner
+        .pop_inflight_message_counter()
+        .in_flight_message_num_table()
+    {
+        let Some((topic, group)) = key.split_once(TOPIC_GROUP_SEPARATOR) else {
+            continue;
+        };
+        let mut labels = base_labels.to_vec();
+        labels.push((MetricsLabels::TOPIC, topic));
+        labels.push((MetricsLabels::CONSUMER_GROUP, group));
+        encoder.write_sample("rocketmq_pop_inflight_messages", &labels, num as f64);
+    }
+
+    encoder.write_gauge(
+        "rocketmq_half_messages",
+        "Number of transaction half messages not yet checked.",
+        base_labels,
+        half_messages as f64,
+    );
+}
+
+/// Disk usage and timer backlog reported by the message store.
+fn collect_store_metrics<MS: MessageStore>(
+    inner: &BrokerRuntimeInner<MS>,
+    base_labels: &[(&str, &str)],
+    encoder: &mut TextEncoder,
+) {
+    let Some(message_store) = inner.message_store() else {
+        return;
+    };
+    let store_stats_service = message_store.get_store_stats_service();
+    let store_counters = [
+        (
+            "rocketmq_store_put_messages_total",
+            "Total number of messages put into the store.",
+            store_stats_service.get_put_message_times_total(),
+        ),
+        (
+            "rocketmq_store_put_bytes_total",
+            "Total bytes of messages put into the store.",
+            store_stats_service.get_put_message_size_total(),
+        ),
+        (
+            "rocketmq_store_put_failures_total",
+            "Total number of failed puts.",
+            store_stats_service
+                .get_put_message_failed_times()
+                .load(Ordering::Relaxed) as u64,
+        ),
+        (
+            "rocketmq_store_get_found_total",
+            "Total number of gets that found messages.",
+            store_stats_service
+                .get_message_times_total_found()
+                .load(Ordering::Relaxed) as u64,
+        ),
+        (
+            "rocketmq_store_get_miss_total",
+            "Total number of gets that found no message.",
+            store_stats_service
+                .get_message_times_total_miss()
+                .load(Ordering::Relaxed) as u64,
+        ),
+        (
+            "rocketmq_store_get_transferred_messages_total",
+            "Total number of messages returned by gets.",
+            store_stats_service
+                .get_message_transferred_msg_count()
+                .load(Ordering::Relaxed) as u64,
+        ),
+    ];
+    for (metric_name, help, value) in store_counters {
+        encoder.write_family(metric_name, help, MetricType::Counter);
+        encoder.write_sample(metric_name, base_labels, value as f64);
+    }
+
+    let disk_ratio = message_store
+        .get_runtime_info()
+        .get(RunningStats::CommitLogDiskRatio.as_str())
+        .and_then(|ratio| ratio.parse::<f64>().ok());
+    if let Some(disk_ratio) = disk_ratio {
+        encoder.write_gauge(
+            "rocketmq_commitlog_disk_ratio",
+            "Used ratio of the commit log disk.",
+            base_labels,
+            disk_ratio,
+        );
+    }
+
+    if let Some(timer_message_store) = message_store.get_timer_message_store() {
+        encoder.write_gauge(
+            "rocketmq_timer_enqueue_lag",
+            "Number of timer messages waiting to be enqueued.",
+            base_labels,
+            timer_message_store.get_enqueue_behind_messages() as f64,
+        );
+        encoder.write_gauge(
+            "rocketmq_timer_enqueue_latency",
+            "Timer enqueue latency in seconds.",
+            base_labels,
+            timer_message_store.get_enqueue_behind() as f64,
+        );
+        encoder.write_gauge(
+            "rocketmq_timer_dequeue_latency",
+            "Timer dequeue latency in seconds.",
+            base_labels,
+            timer_message_store.get_dequeue_behind() as f64,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::broker_runtime::BrokerRuntime;
+
+    #[tokio::test]
+    async fn collects_store_and_account_statistics() {
+        let root_dir = TempDir::new().unwrap();
+        let inner = BrokerRuntime::new_inner_for_test(root_dir.path()).await;
+        inner
+            .message_store_unchecked()
+            .get_store_stats_service()
+            .get_message_times_total_found()
+            .fetch_add(3, Ordering::Relaxed);
+
+        let mut encoder = TextEncoder::new();
+        collect_broker_metrics(inner.as_ref(), &mut encoder);
+        let text = encoder.finish();
+        assert!(text.lines().any(|line| {
+            line.starts_with("rocketmq_store_get_found_total{") && line.ends_with(" 3")
+        }));
+        assert!(text.contains("# TYPE rocketmq_store_put_messages_total counter\n"));
+        assert!(text.contains("# TYPE rocketmq_account_stats_total counter\n"));
+    }
+}
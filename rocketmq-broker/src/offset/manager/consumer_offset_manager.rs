@@ -93,6 +93,32 @@ impl ConsumerOffsetManager {
         }
     }
 
+    /// Removes every offset the consumer `group` holds on this broker.
+    pub fn remove_offset(&self, group: &CheetahString) {
+        let is_group_key = |topic_at_group: &CheetahString| {
+            let arrays: Vec<&str> = topic_at_group.split(TOPIC_GROUP_SEPARATOR).collect();
+            arrays.len() == 2 && arrays[1] == group.as_str()
+        };
+        self.consumer_offset_wrapper
+            .offset_table
+            .write()
+            .retain(|topic_at_group, offsets| {
+                if is_group_key(topic_at_group) {
+                    warn!("Clean group's offset, {}, {:?}", topic_at_group, offsets);
+                    return false;
+                }
+                true
+            });
+        self.consumer_offset_wrapper
+            .reset_offset_table
+            .write()
+            .retain(|topic_at_group, _| !is_group_key(topic_at_group));
+        self.consumer_offset_wrapper
+            .pull_offset_table
+            .write()
+            .retain(|topic_at_group, _| !is_group_key(topic_at_group));
+    }
+
     pub fn which_group_by_topic(&self, topic: &str) -> HashSet<CheetahString> {
         let read_guard = self.consumer_offset_wrapper.offset_table.read();
         let mut groups = HashSet::new();
@@ -188,6 +214,12 @@ impl ConsumerOffsetManager {
         -1
     }
 
+    /// Returns a copy of the committed offsets, keyed by `topic@group` and queue id.
+    #[cfg(feature = "metrics")]
+    pub fn offset_table_snapshot(&self) -> HashMap<CheetahString, HashMap<i32, i64>> {
+        self.consumer_offset_wrapper.offset_table.read().clone()
+    }
+
     pub fn which_topic_by_consumer(&self, group: &CheetahString) -> HashSet<CheetahString> {
         let read_guard = self.consumer_offset_wrapper.offset_table.read();
         let mut topics = HashSet::new();
@@ -40,16 +40,32 @@ use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
 use rocketmq_common::MessageAccessor::MessageAccessor;
 use rocketmq_common::MessageDecoder;
 use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_error::RocketMQResult;
 use rocketmq_error::RocketmqError;
 use rocketmq_remoting::clients::rocketmq_default_impl::RocketmqDefaultClient;
 use rocketmq_remoting::clients::RemotingClient;
+use rocketmq_remoting::code::request_code::ControllerRequestCode;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::protocol::body::broker_body::register_broker_body::RegisterBrokerBody;
+use rocketmq_remoting::protocol::body::elect_master_response_body::ElectMasterResponseBody;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::response::lock_batch_response_body::LockBatchResponseBody;
+use rocketmq_remoting::protocol::body::sync_state_set::SyncStateSet;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::topic_config_wrapper::TopicConfigAndMappingSerializeWrapper;
+use rocketmq_remoting::protocol::header::broker::broker_heartbeat_request_header::BrokerHeartbeatRequestHeader;
 use rocketmq_remoting::protocol::header::client_request_header::GetRouteInfoRequestHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::elect_master_request_header::ElectMasterRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdResponseHeader;
+use rocketmq_remoting::protocol::header::controller::get_replica_info_header::GetReplicaInfoRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_replica_info_header::GetReplicaInfoResponseHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerRequestHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerResponseHeader;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
 use rocketmq_remoting::protocol::header::lock_batch_mq_request_header::LockBatchMqRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header::SendMessageRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header_v2::SendMessageRequestHeaderV2;
@@ -93,38 +109,38 @@ pub struct BrokerOuterAPI {
 }
 
 impl BrokerOuterAPI {
-    pub fn new(tokio_client_config: Arc<TokioClientConfig>) -> Self {
+    pub fn new(tokio_client_config: Arc<TokioClientConfig>) -> RocketMQResult<Self> {
         let client = ArcMut::new(RocketmqDefaultClient::new(
             tokio_client_config,
             DefaultRemotingRequestProcessor,
-        ));
+        )?);
         let client_metadata = ClientMetadata::new();
-        Self {
+        Ok(Self {
             remoting_client: client.clone(),
             name_server_address: None,
             rpc_client: RpcClientImpl::new(client_metadata.clone(), client),
             client_metadata,
-        }
+        })
     }
 
     pub fn new_with_hook(
         tokio_client_config: Arc<TokioClientConfig>,
         rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
-    ) -> Self {
+    ) -> RocketMQResult<Self> {
         let mut client = ArcMut::new(RocketmqDefaultClient::new(
             tokio_client_config,
             DefaultRemotingRequestProcessor,
-        ));
+        )?);
         let client_metadata = ClientMetadata::new();
         if let Some(rpc_hook) = rpc_hook {
             client.register_rpc_hook(rpc_hook);
         }
-        Self {
+        Ok(Self {
             remoting_client: client.clone(),
             name_server_address: None,
             rpc_client: RpcClientImpl::new(client_metadata.clone(), client),
             client_metadata,
-        }
+        })
     }
 
     fn create_request(broker_name: CheetahString, topic_config: TopicConfig) -> RemotingCommand {
@@ -615,6 +631,230 @@ impl BrokerOuterAPI {
             ))
         }
     }
+
+    /// Asks a controller for the metadata of the controller group, e.g. who is the leader.
+    pub async fn get_controller_meta_data(
+        &self,
+        controller_address: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<GetMetaDataResponseHeader> {
+        let request = RemotingCommand::create_remoting_command(
+            ControllerRequestCode::ControllerGetMetadataInfo,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return response.decode_command_custom_header::<GetMetaDataResponseHeader>();
+        }
+        Err(controller_error(&response, controller_address))
+    }
+
+    pub async fn get_next_broker_id(
+        &self,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+        controller_address: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<GetNextBrokerIdResponseHeader> {
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerGetNextBrokerId,
+            GetNextBrokerIdRequestHeader::new(cluster_name.clone(), broker_name.clone()),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return response.decode_command_custom_header::<GetNextBrokerIdResponseHeader>();
+        }
+        Err(controller_error(&response, controller_address))
+    }
+
+    pub async fn apply_broker_id(
+        &self,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+        broker_id: i64,
+        register_check_code: &CheetahString,
+        controller_address: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerApplyBrokerId,
+            ApplyBrokerIdRequestHeader::new(
+                cluster_name.clone(),
+                broker_name.clone(),
+                broker_id,
+                register_check_code.clone(),
+            ),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return Ok(());
+        }
+        Err(controller_error(&response, controller_address))
+    }
+
+    /// Registers this broker to the controller, returns the current master and sync state set
+    /// of the broker set.
+    pub async fn register_broker_to_controller(
+        &self,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+        broker_id: i64,
+        broker_address: &CheetahString,
+        controller_address: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<(
+        RegisterBrokerToControllerResponseHeader,
+        Option<SyncStateSet>,
+    )> {
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerRegisterBroker,
+            RegisterBrokerToControllerRequestHeader::new(
+                cluster_name.clone(),
+                broker_name.clone(),
+                broker_id,
+                broker_address.clone(),
+            ),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let response_header = response
+                .decode_command_custom_header::<RegisterBrokerToControllerResponseHeader>()?;
+            let sync_state_set = match response.body() {
+                Some(body) => Some(SyncStateSet::decode(body)?),
+                None => None,
+            };
+            return Ok((response_header, sync_state_set));
+        }
+        Err(controller_error(&response, controller_address))
+    }
+
+    pub async fn get_replica_info(
+        &self,
+        controller_address: &CheetahString,
+        broker_name: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<(GetReplicaInfoResponseHeader, SyncStateSet)> {
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerGetReplicaInfo,
+            GetReplicaInfoRequestHeader::new(broker_name.clone()),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let response_header =
+                response.decode_command_custom_header::<GetReplicaInfoResponseHeader>()?;
+            let sync_state_set = match response.body() {
+                Some(body) => SyncStateSet::decode(body)?,
+                None => SyncStateSet::default(),
+            };
+            return Ok((response_header, sync_state_set));
+        }
+        Err(controller_error(&response, controller_address))
+    }
+
+    /// Asks the controller to elect a master for the broker set, an existing master is not an
+    /// error.
+    pub async fn broker_elect(
+        &self,
+        controller_address: &CheetahString,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+        broker_id: i64,
+    ) -> rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, Option<ElectMasterResponseBody>)>
+    {
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerElectMaster,
+            ElectMasterRequestHeader::of_broker_trigger(
+                cluster_name.clone(),
+                broker_name.clone(),
+                broker_id,
+            ),
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_address), request, 3000)
+            .await?;
+        match ResponseCode::from(response.code()) {
+            ResponseCode::Success | ResponseCode::ControllerMasterStillExist => {
+                let response_header =
+                    response.decode_command_custom_header::<ElectMasterResponseHeader>()?;
+                let response_body = match response.body() {
+                    Some(body) => Some(ElectMasterResponseBody::decode(body)?),
+                    None => None,
+                };
+                Ok((response_header, response_body))
+            }
+            _ => Err(controller_error(&response, controller_address)),
+        }
+    }
+
+    /// Reports the liveness and replication progress of this broker to the controller.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn send_heartbeat_to_controller(
+        &self,
+        controller_address: &CheetahString,
+        cluster_name: &CheetahString,
+        broker_addr: &CheetahString,
+        broker_name: &CheetahString,
+        broker_id: i64,
+        timeout_millis: u64,
+        epoch: i32,
+        max_offset: i64,
+        confirm_offset: i64,
+        election_priority: i32,
+    ) {
+        let request_header = BrokerHeartbeatRequestHeader {
+            cluster_name: cluster_name.clone(),
+            broker_addr: broker_addr.clone(),
+            broker_name: broker_name.clone(),
+            broker_id: Some(broker_id),
+            epoch: Some(epoch),
+            max_offset: Some(max_offset),
+            confirm_offset: Some(confirm_offset),
+            heartbeat_timeout_mills: Some(timeout_millis as i64),
+            election_priority: Some(election_priority),
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::BrokerHeartbeat, request_header);
+        self.remoting_client
+            .invoke_oneway(controller_address, request, timeout_millis)
+            .await;
+    }
+
+    /// Fetches the epoch history of the commit log of another broker, usually the master.
+    pub async fn get_broker_epoch_cache(
+        &self,
+        broker_addr: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<EpochEntryCache> {
+        let request =
+            RemotingCommand::create_remoting_command(ControllerRequestCode::GetBrokerEpochCache);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(broker_addr), request, 3000)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return EpochEntryCache::decode(body);
+            }
+        }
+        Err(controller_error(&response, broker_addr))
+    }
+}
+
+fn controller_error(response: &RemotingCommand, addr: &CheetahString) -> RocketmqError {
+    RocketmqError::MQBrokerError(
+        response.code(),
+        response.remark().map_or("".to_string(), |s| s.to_string()),
+        addr.to_string(),
+    )
 }
 
 fn process_pull_result(
@@ -379,9 +379,13 @@ where
             )
         };
 
-        //this.brokerController.getBrokerStatsManager().incBrokerAckNums(ackCount);
-        //this.brokerController.getBrokerStatsManager().incGroupAckNums(consumeGroup,topic,
-        // ackCount);
+        let broker_stats_manager = self.broker_runtime_inner.broker_stats_manager();
+        broker_stats_manager.inc_broker_ack_nums(ack_count as i32);
+        broker_stats_manager.inc_group_ack_nums(
+            consume_group.as_str(),
+            topic.as_str(),
+            ack_count as i32,
+        );
         ack_msg.set_consumer_group(consume_group.clone());
         ack_msg.set_topic(topic.clone());
         ack_msg.set_queue_id(qid);
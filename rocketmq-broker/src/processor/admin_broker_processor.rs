@@ -15,34 +15,50 @@
  * limitations under the License.
  */
 
+use rocketmq_remoting::code::request_code::ControllerRequestCode;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::check_rocksdb_cqwrite_progress_response_body::CheckRocksdbCqWriteProgressResponseBody;
+use rocketmq_remoting::protocol::header::check_rocksdb_cq_write_progress_request_header::CheckRocksdbCqWriteProgressRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::message_store::MessageStore;
 use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
+use crate::processor::admin_broker_processor::acl_request_handler::AclRequestHandler;
+use crate::processor::admin_broker_processor::auth_request_handler::AuthRequestHandler;
 use crate::processor::admin_broker_processor::batch_mq_handler::BatchMqHandler;
 use crate::processor::admin_broker_processor::broker_config_request_handler::BrokerConfigRequestHandler;
 use crate::processor::admin_broker_processor::consumer_request_handler::ConsumerRequestHandler;
 use crate::processor::admin_broker_processor::offset_request_handler::OffsetRequestHandler;
+use crate::processor::admin_broker_processor::producer_request_handler::ProducerRequestHandler;
+use crate::processor::admin_broker_processor::subscription_group_request_handler::SubscriptionGroupRequestHandler;
 use crate::processor::admin_broker_processor::topic_request_handler::TopicRequestHandler;
 
+mod acl_request_handler;
+mod auth_request_handler;
 mod batch_mq_handler;
 mod broker_config_request_handler;
 mod consumer_request_handler;
 mod offset_request_handler;
+mod producer_request_handler;
+mod subscription_group_request_handler;
 mod topic_request_handler;
 
 pub struct AdminBrokerProcessor<MS> {
     topic_request_handler: TopicRequestHandler<MS>,
     broker_config_request_handler: BrokerConfigRequestHandler<MS>,
     consumer_request_handler: ConsumerRequestHandler<MS>,
+    producer_request_handler: ProducerRequestHandler<MS>,
     offset_request_handler: OffsetRequestHandler<MS>,
+    subscription_group_request_handler: SubscriptionGroupRequestHandler<MS>,
     batch_mq_handler: BatchMqHandler<MS>,
+    acl_request_handler: AclRequestHandler<MS>,
+    auth_request_handler: AuthRequestHandler<MS>,
     broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
 }
 
@@ -52,14 +68,23 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
         let broker_config_request_handler =
             BrokerConfigRequestHandler::new(broker_runtime_inner.clone());
         let consumer_request_handler = ConsumerRequestHandler::new(broker_runtime_inner.clone());
+        let producer_request_handler = ProducerRequestHandler::new(broker_runtime_inner.clone());
         let offset_request_handler = OffsetRequestHandler::new(broker_runtime_inner.clone());
+        let subscription_group_request_handler =
+            SubscriptionGroupRequestHandler::new(broker_runtime_inner.clone());
         let batch_mq_handler = BatchMqHandler::new(broker_runtime_inner.clone());
+        let acl_request_handler = AclRequestHandler::new(broker_runtime_inner.clone());
+        let auth_request_handler = AuthRequestHandler::new(broker_runtime_inner.clone());
         AdminBrokerProcessor {
             topic_request_handler,
             broker_config_request_handler,
             consumer_request_handler,
+            producer_request_handler,
             offset_request_handler,
+            subscription_group_request_handler,
             batch_mq_handler,
+            acl_request_handler,
+            auth_request_handler,
             broker_runtime_inner,
         }
     }
@@ -114,16 +139,66 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .get_topic_stats_info(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::UpdateAndCreateSubscriptionGroup => {
+                self.subscription_group_request_handler
+                    .update_and_create_subscription_group(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::UpdateAndCreateSubscriptionGroupList => {
+                self.subscription_group_request_handler
+                    .update_and_create_subscription_group_list(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::GetAllSubscriptionGroupConfig => {
+                self.subscription_group_request_handler
+                    .get_all_subscription_group(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::DeleteSubscriptionGroup => {
+                self.subscription_group_request_handler
+                    .delete_subscription_group(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::GetConsumerConnectionList => {
                 self.consumer_request_handler
                     .get_consumer_connection_list(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::GetProducerConnectionList => {
+                self.producer_request_handler
+                    .get_producer_connection_list(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::GetConsumeStats => {
                 self.consumer_request_handler
                     .get_consume_stats(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::GetConsumerRunningInfo => {
+                self.consumer_request_handler
+                    .get_consumer_running_info(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::ConsumeMessageDirectly => {
+                self.consumer_request_handler
+                    .consume_message_directly(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::InvokeBrokerToResetOffset => {
+                self.consumer_request_handler
+                    .reset_offset(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::InvokeBrokerToGetConsumerStatus => {
+                self.consumer_request_handler
+                    .get_consumer_status(channel, ctx, request_code, request)
+                    .await
+            }
+            RequestCode::GetBrokerConsumeStats => {
+                self.consumer_request_handler
+                    .fetch_all_consume_stats_in_broker(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::GetAllConsumerOffset => {
                 self.consumer_request_handler
                     .get_all_consumer_offset(channel, ctx, request_code, request)
@@ -139,6 +214,11 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .get_broker_runtime_info(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::ViewBrokerStatsData => {
+                self.broker_config_request_handler
+                    .view_broker_stats_data(channel, ctx, request_code, request)
+                    .await
+            }
             RequestCode::QueryTopicConsumeByWho => {
                 self.topic_request_handler
                     .query_topic_consume_by_who(channel, ctx, request_code, request)
@@ -171,9 +251,87 @@ impl<MS: MessageStore> AdminBrokerProcessor<MS> {
                     .unlock_batch_mq(channel, ctx, request_code, request)
                     .await
             }
+            RequestCode::UpdateAndCreateAclConfig => Some(
+                self.acl_request_handler
+                    .update_and_create_access_config(request),
+            ),
+            RequestCode::DeleteAclConfig => {
+                Some(self.acl_request_handler.delete_access_config(request))
+            }
+            RequestCode::UpdateGlobalWhiteAddrsConfig => Some(
+                self.acl_request_handler
+                    .update_global_white_addrs_config(request),
+            ),
+            RequestCode::GetBrokerClusterAclInfo => {
+                Some(self.acl_request_handler.get_broker_cluster_acl_info())
+            }
+            RequestCode::AuthCreateUser => Some(self.auth_request_handler.create_user(request)),
+            RequestCode::AuthUpdateUser => Some(self.auth_request_handler.update_user(request)),
+            RequestCode::AuthDeleteUser => Some(self.auth_request_handler.delete_user(request)),
+            RequestCode::AuthGetUser => Some(self.auth_request_handler.get_user(request)),
+            RequestCode::AuthListUser => Some(self.auth_request_handler.list_users(request)),
+            RequestCode::AuthCreateAcl => Some(self.auth_request_handler.create_acl(request)),
+            RequestCode::AuthUpdateAcl => Some(self.auth_request_handler.update_acl(request)),
+            RequestCode::AuthDeleteAcl => Some(self.auth_request_handler.delete_acl(request)),
+            RequestCode::AuthGetAcl => Some(self.auth_request_handler.get_acl(request)),
+            RequestCode::AuthListAcl => Some(self.auth_request_handler.list_acls(request)),
+            RequestCode::CheckRocksdbCqWriteProgress => {
+                Some(self.check_rocksdb_cq_write_progress(request))
+            }
+            RequestCode::Unknown
+                if request.code() == ControllerRequestCode::GetBrokerEpochCache.to_i32() =>
+            {
+                Some(self.get_broker_epoch_cache())
+            }
             _ => Some(get_unknown_cmd_response(request_code)),
         }
     }
+
+    fn check_rocksdb_cq_write_progress(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request
+            .decode_command_custom_header::<CheckRocksdbCqWriteProgressRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => {
+                return RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    e.to_string(),
+                )
+            }
+        };
+        let diff_result = self
+            .broker_runtime_inner
+            .message_store()
+            .as_ref()
+            .unwrap()
+            .check_rocksdb_cq_write_progress(&request_header.topic);
+        let body = CheckRocksdbCqWriteProgressResponseBody {
+            diff_result: Some(diff_result.into()),
+        };
+        match body.encode() {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                e.to_string(),
+            ),
+        }
+    }
+
+    fn get_broker_epoch_cache(&self) -> RemotingCommand {
+        let Some(replicas_manager) = self.broker_runtime_inner.replicas_manager() else {
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                "this request only for controllerMode",
+            );
+        };
+        match replicas_manager.get_broker_epoch_cache().encode() {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                e.to_string(),
+            ),
+        }
+    }
 }
 
 fn get_unknown_cmd_response(request_code: RequestCode) -> RemotingCommand {
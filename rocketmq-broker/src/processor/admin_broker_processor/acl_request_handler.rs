@@ -0,0 +1,190 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_acl::plain::plain_access_validator::PlainAccessValidator;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::body::cluster_acl_version_info::ClusterAclVersionInfo;
+use rocketmq_remoting::protocol::header::create_access_config_request_header::CreateAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::delete_access_config_request_header::DeleteAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::update_global_white_addrs_config_request_header::UpdateGlobalWhiteAddrsConfigRequestHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use tracing::info;
+use tracing::warn;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+#[derive(Clone)]
+pub(super) struct AclRequestHandler<MS> {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+}
+
+impl<MS: MessageStore> AclRequestHandler<MS> {
+    pub(super) fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        Self {
+            broker_runtime_inner,
+        }
+    }
+
+    fn validator(&self) -> Result<&PlainAccessValidator, RemotingCommand> {
+        self.broker_runtime_inner
+            .plain_access_validator()
+            .map(|validator| validator.as_ref())
+            .ok_or_else(|| {
+                RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    "The broker does not enable acl",
+                )
+            })
+    }
+
+    pub fn update_and_create_access_config(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header =
+            match request.decode_command_custom_header::<CreateAccessConfigRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => return system_error(e),
+            };
+        let validator = match self.validator() {
+            Ok(validator) => validator,
+            Err(response) => return response,
+        };
+        let access_key = request_header.access_key.clone();
+        let config = PlainAccessConfig {
+            access_key: Some(request_header.access_key),
+            secret_key: request_header.secret_key,
+            white_remote_address: request_header.white_remote_address,
+            admin: request_header.admin,
+            default_topic_perm: request_header.default_topic_perm,
+            default_group_perm: request_header.default_group_perm,
+            topic_perms: split_perms(request_header.topic_perms),
+            group_perms: split_perms(request_header.group_perms),
+        };
+        match validator.update_access_config(config) {
+            Ok(()) => {
+                info!(
+                    "update and create access config success, accessKey={}",
+                    access_key
+                );
+                RemotingCommand::create_response_command()
+            }
+            Err(e) => {
+                warn!(
+                    "update and create access config {} failed: {}",
+                    access_key, e
+                );
+                system_error(e)
+            }
+        }
+    }
+
+    pub fn delete_access_config(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header =
+            match request.decode_command_custom_header::<DeleteAccessConfigRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => return system_error(e),
+            };
+        let validator = match self.validator() {
+            Ok(validator) => validator,
+            Err(response) => return response,
+        };
+        match validator.delete_access_config(&request_header.access_key) {
+            Ok(()) => {
+                info!(
+                    "delete access config success, accessKey={}",
+                    request_header.access_key
+                );
+                RemotingCommand::create_response_command()
+            }
+            Err(e) => {
+                warn!(
+                    "delete access config {} failed: {}",
+                    request_header.access_key, e
+                );
+                system_error(e)
+            }
+        }
+    }
+
+    pub fn update_global_white_addrs_config(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request
+            .decode_command_custom_header::<UpdateGlobalWhiteAddrsConfigRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let validator = match self.validator() {
+            Ok(validator) => validator,
+            Err(response) => return response,
+        };
+        let global_white_addrs = request_header
+            .global_white_addrs
+            .split(',')
+            .map(str::trim)
+            .filter(|addr| !addr.is_empty())
+            .map(CheetahString::from)
+            .collect();
+        match validator.update_global_white_addrs_config(global_white_addrs) {
+            Ok(()) => RemotingCommand::create_response_command(),
+            Err(e) => {
+                warn!("update global white addrs config failed: {}", e);
+                system_error(e)
+            }
+        }
+    }
+
+    pub fn get_broker_cluster_acl_info(&self) -> RemotingCommand {
+        let validator = match self.validator() {
+            Ok(validator) => validator,
+            Err(response) => return response,
+        };
+        let broker_config = self.broker_runtime_inner.broker_config();
+        let info = ClusterAclVersionInfo {
+            broker_name: broker_config.broker_identity.broker_name.clone(),
+            broker_addr: self.broker_runtime_inner.get_broker_addr().clone(),
+            acl_config_data_version: Some(validator.get_acl_config_version()),
+            all_acl_config_data_version: validator.get_all_acl_config_version(),
+            cluster_name: broker_config.broker_identity.broker_cluster_name.clone(),
+        };
+        match info.encode() {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => system_error(e),
+        }
+    }
+}
+
+fn split_perms(perms: Option<CheetahString>) -> Vec<CheetahString> {
+    perms
+        .map(|perms| {
+            perms
+                .split(',')
+                .map(str::trim)
+                .filter(|perm| !perm.is_empty())
+                .map(CheetahString::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn system_error(e: impl std::fmt::Display) -> RemotingCommand {
+    RemotingCommand::create_response_command_with_code_remark(
+        ResponseCode::SystemError,
+        e.to_string(),
+    )
+}
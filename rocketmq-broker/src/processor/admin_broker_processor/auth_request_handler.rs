@@ -0,0 +1,333 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::CreateAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::DeleteAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::GetAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::ListAclsRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::UpdateAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::CreateUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::DeleteUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::GetUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::ListUsersRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::UpdateUserRequestHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use serde::Serialize;
+use tracing::info;
+use tracing::warn;
+
+use crate::auth::acl::Acl;
+use crate::auth::acl::PolicyType;
+use crate::auth::acl::Resource;
+use crate::auth::auth_metadata_manager::AuthMetadataManager;
+use crate::auth::AuthResult;
+use crate::broker_runtime::BrokerRuntimeInner;
+
+/// Handles the administration of the users and acls of the ACL 2.0 model.
+#[derive(Clone)]
+pub(super) struct AuthRequestHandler<MS> {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+}
+
+impl<MS: MessageStore> AuthRequestHandler<MS> {
+    pub(super) fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        Self {
+            broker_runtime_inner,
+        }
+    }
+
+    fn manager(&self) -> Result<&AuthMetadataManager, RemotingCommand> {
+        self.broker_runtime_inner
+            .auth_metadata_manager()
+            .map(|manager| manager.as_ref())
+            .ok_or_else(|| {
+                RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    "The broker does not enable authentication or authorization",
+                )
+            })
+    }
+
+    pub fn create_user(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<CreateUserRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let user_info = match decode_user_info(&request, &request_header.username) {
+            Ok(user_info) => user_info,
+            Err(response) => return response,
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.create_user(&user_info),
+            format!("create user {}", request_header.username),
+        )
+    }
+
+    pub fn update_user(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<UpdateUserRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let user_info = match decode_user_info(&request, &request_header.username) {
+            Ok(user_info) => user_info,
+            Err(response) => return response,
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.update_user(&user_info),
+            format!("update user {}", request_header.username),
+        )
+    }
+
+    pub fn delete_user(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<DeleteUserRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.delete_user(&request_header.username),
+            format!("delete user {}", request_header.username),
+        )
+    }
+
+    pub fn get_user(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<GetUserRequestHeader>() {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        match manager.get_user(&request_header.username) {
+            Some(user) => json_response(&user.to_info()),
+            None => system_error(format!("The user {} is not exist", request_header.username)),
+        }
+    }
+
+    pub fn list_users(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<ListUsersRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        let users: Vec<UserInfo> = manager
+            .list_users(request_header.filter.as_deref().filter(|f| !f.is_empty()))
+            .iter()
+            .map(|user| user.to_info())
+            .collect();
+        json_response(&users)
+    }
+
+    pub fn create_acl(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<CreateAclRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let acl = match decode_acl(&request, &request_header.subject) {
+            Ok(acl) => acl,
+            Err(response) => return response,
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.create_acl(acl),
+            format!("create acl of {}", request_header.subject),
+        )
+    }
+
+    pub fn update_acl(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<UpdateAclRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let acl = match decode_acl(&request, &request_header.subject) {
+            Ok(acl) => acl,
+            Err(response) => return response,
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.update_acl(acl),
+            format!("update acl of {}", request_header.subject),
+        )
+    }
+
+    pub fn delete_acl(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<DeleteAclRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let policy_type = match request_header.policy_type.as_deref() {
+            None | Some("") => None,
+            Some(policy_type) => match PolicyType::parse(policy_type) {
+                Some(policy_type) => Some(policy_type),
+                None => return system_error(format!("The policy type {} is unknown", policy_type)),
+            },
+        };
+        let resource = match request_header.resource.as_deref() {
+            None | Some("") => None,
+            Some(resource) => match Resource::parse(resource) {
+                Ok(resource) => Some(resource),
+                Err(e) => return system_error(e),
+            },
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        to_response(
+            manager.delete_acl(&request_header.subject, policy_type, resource.as_ref()),
+            format!("delete acl of {}", request_header.subject),
+        )
+    }
+
+    pub fn get_acl(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<GetAclRequestHeader>() {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        match manager.get_acl(&request_header.subject) {
+            Some(acl) => json_response(&acl.to_info()),
+            None => system_error(format!(
+                "The acl of {} is not exist",
+                request_header.subject
+            )),
+        }
+    }
+
+    pub fn list_acls(&self, request: RemotingCommand) -> RemotingCommand {
+        let request_header = match request.decode_command_custom_header::<ListAclsRequestHeader>() {
+            Ok(request_header) => request_header,
+            Err(e) => return system_error(e),
+        };
+        let manager = match self.manager() {
+            Ok(manager) => manager,
+            Err(response) => return response,
+        };
+        let acls: Vec<AclInfo> = manager
+            .list_acls(
+                request_header
+                    .subject_filter
+                    .as_deref()
+                    .filter(|f| !f.is_empty()),
+                request_header
+                    .resource_filter
+                    .as_deref()
+                    .filter(|f| !f.is_empty()),
+            )
+            .iter()
+            .map(Acl::to_info)
+            .collect();
+        json_response(&acls)
+    }
+}
+
+/// The user in the body, named after the header.
+fn decode_user_info(
+    request: &RemotingCommand,
+    username: &CheetahString,
+) -> Result<UserInfo, RemotingCommand> {
+    let mut user_info = match request.get_body() {
+        Some(body) => UserInfo::decode(body).map_err(system_error)?,
+        None => return Err(system_error("The user info is missing")),
+    };
+    if user_info
+        .username
+        .as_ref()
+        .is_some_and(|name| !name.is_empty() && name != username)
+    {
+        return Err(system_error(format!(
+            "The username {} does not match the request header",
+            username
+        )));
+    }
+    user_info.username = Some(username.clone());
+    Ok(user_info)
+}
+
+/// The acl in the body, bound to the subject of the header.
+fn decode_acl(request: &RemotingCommand, subject: &CheetahString) -> Result<Acl, RemotingCommand> {
+    let acl_info = match request.get_body() {
+        Some(body) => AclInfo::decode(body).map_err(system_error)?,
+        None => return Err(system_error("The acl info is missing")),
+    };
+    Acl::from_info(subject, &acl_info).map_err(system_error)
+}
+
+fn to_response(result: AuthResult<()>, operation: String) -> RemotingCommand {
+    match result {
+        Ok(()) => {
+            info!("{} success", operation);
+            RemotingCommand::create_response_command()
+        }
+        Err(e) => {
+            warn!("{} failed: {}", operation, e);
+            system_error(e)
+        }
+    }
+}
+
+fn json_response<T: Serialize>(body: &T) -> RemotingCommand {
+    match body.encode() {
+        Ok(body) => RemotingCommand::create_response_command().set_body(body),
+        Err(e) => system_error(e),
+    }
+}
+
+fn system_error(e: impl std::fmt::Display) -> RemotingCommand {
+    RemotingCommand::create_response_command_with_code_remark(
+        ResponseCode::SystemError,
+        e.to_string(),
+    )
+}
@@ -20,14 +20,22 @@ use std::collections::HashMap;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::mix_all;
 use rocketmq_common::common::mq_version::RocketMqVersion;
+use rocketmq_common::common::stats::stats_snapshot::StatsSnapshot;
 use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::broker_item::BrokerStatsItem;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
+use rocketmq_remoting::protocol::header::view_broker_stats_data_request_header::ViewBrokerStatsDataRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::subscription::broker_stats_data::BrokerStatsData;
+use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::message_store::MessageStore;
 use sysinfo::Disks;
+use tracing::info;
+use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
 
@@ -49,9 +57,74 @@ impl<MS: MessageStore> BrokerConfigRequestHandler<MS> {
         _channel: Channel,
         _ctx: ConnectionHandlerContext,
         _request_code: RequestCode,
-        _request: RemotingCommand,
+        request: RemotingCommand,
     ) -> Option<RemotingCommand> {
-        todo!()
+        let response = RemotingCommand::create_response_command();
+        let Some(body) = request.body() else {
+            return Some(response);
+        };
+        let Ok(body) = std::str::from_utf8(body) else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("broker config body is not utf-8"),
+            );
+        };
+        let Some(properties) = mix_all::string_to_properties(body) else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("string_to_properties error"),
+            );
+        };
+        let config_blacklist = self
+            .broker_runtime_inner
+            .broker_config()
+            .get_config_blacklist();
+        if config_blacklist
+            .iter()
+            .any(|key| properties.contains_key(key))
+        {
+            return Some(
+                response
+                    .set_code(ResponseCode::NoPermission)
+                    .set_remark("Can not update config in black list."),
+            );
+        }
+        info!("updateBrokerConfig, new config: [{:?}]", properties);
+        match self
+            .broker_runtime_inner
+            .broker_config_mut()
+            .update(&properties)
+        {
+            Ok(unknown_keys) => {
+                if !unknown_keys.is_empty() {
+                    warn!(
+                        "updateBrokerConfig, ignore unknown keys: {:?}",
+                        unknown_keys
+                    );
+                }
+            }
+            Err(error) => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(format!("Update error {}", error)),
+                );
+            }
+        }
+        if properties.contains_key("brokerPermission") {
+            self.broker_runtime_inner
+                .topic_config_manager()
+                .data_version()
+                .mut_from_ref()
+                .next_version();
+            let broker_runtime_inner = self.broker_runtime_inner.clone();
+            self.broker_runtime_inner
+                .register_broker_all_inner(broker_runtime_inner, false, false, true)
+                .await;
+        }
+        Some(response)
     }
 
     pub async fn get_broker_config(
@@ -80,7 +153,7 @@ impl<MS: MessageStore> BrokerConfigRequestHandler<MS> {
             .collect::<HashMap<_, _>>();
         let mut body = String::new();
         for (key, value) in combine_map {
-            body.push_str(&format!("{}:{}\n", key, value));
+            body.push_str(&format!("{}={}\n", key, value));
         }
         if !body.is_empty() {
             response.set_body_mut_ref(body);
@@ -88,6 +161,50 @@ impl<MS: MessageStore> BrokerConfigRequestHandler<MS> {
         Some(response)
     }
 
+    pub async fn view_broker_stats_data(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<ViewBrokerStatsDataRequestHeader>()
+            .unwrap();
+        let response = RemotingCommand::create_response_command();
+        let Some(stats_item) = self
+            .broker_runtime_inner
+            .broker_stats_manager()
+            .get_stats_item(&request_header.stats_name, &request_header.stats_key)
+        else {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(format!(
+                        "The stats <{}> <{}> not exist",
+                        request_header.stats_name, request_header.stats_key
+                    )),
+            );
+        };
+
+        let to_broker_stats_item = |snapshot: StatsSnapshot| {
+            BrokerStatsItem::new(snapshot.get_sum(), snapshot.get_tps(), snapshot.get_avgpt())
+        };
+        let broker_stats_data = BrokerStatsData::new(
+            to_broker_stats_item(stats_item.get_stats_data_in_minute()),
+            to_broker_stats_item(stats_item.get_stats_data_in_hour()),
+            to_broker_stats_item(stats_item.get_stats_data_in_day()),
+        );
+        match broker_stats_data.encode() {
+            Ok(body) => Some(response.set_body(body)),
+            Err(e) => Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(e.to_string()),
+            ),
+        }
+    }
+
     pub async fn get_broker_runtime_info(
         &mut self,
         _channel: Channel,
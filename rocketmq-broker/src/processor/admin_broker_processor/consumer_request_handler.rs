@@ -15,37 +15,53 @@
  * limitations under the License.
  */
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 
+use cheetah_string::CheetahString;
 use rocketmq_common::common::config_manager::ConfigManager;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::mq_version::RocketMqVersion;
+use rocketmq_common::MessageDecoder;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::consume_stats_list::ConsumeStatsList;
 use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
 use rocketmq_remoting::protocol::body::connection::Connection;
 use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
+use rocketmq_remoting::protocol::header::consume_message_directly_result_request_header::ConsumeMessageDirectlyResultRequestHeader;
+use rocketmq_remoting::protocol::header::get_consume_stats_in_broker_header::GetConsumeStatsInBrokerHeader;
 use rocketmq_remoting::protocol::header::get_consume_stats_request_header::GetConsumeStatsRequestHeader;
 use rocketmq_remoting::protocol::header::get_consumer_connection_list_request_header::GetConsumerConnectionListRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_running_info_request_header::GetConsumerRunningInfoRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_status_request_header::GetConsumerStatusRequestHeader;
+use rocketmq_remoting::protocol::header::reset_offset_request_header::ResetOffsetRequestHeader;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
 use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::message_store::MessageStore;
+use tracing::info;
 use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
+use crate::client::net::broker_to_client::Broker2Client;
+
+const CALL_CONSUMER_TIMEOUT_MILLIS: u64 = 5000;
 
 #[derive(Clone)]
 pub(super) struct ConsumerRequestHandler<MS> {
     broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+    broker_to_client: Broker2Client,
 }
 
 impl<MS> ConsumerRequestHandler<MS> {
     pub fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
         Self {
             broker_runtime_inner,
+            broker_to_client: Broker2Client,
         }
     }
 }
@@ -78,6 +94,7 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     subscription_table.insert(key_value.key().clone(), key_value.clone());
                 }
 
+                let mut connection_set = HashSet::new();
                 for channel_info in consumer_group_info.get_channel_info_table().iter() {
                     let mut connection = Connection::new();
                     connection.set_client_id(channel_info.client_id().clone());
@@ -85,8 +102,9 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     connection.set_version(channel_info.version());
                     connection
                         .set_client_addr(channel_info.key().remote_address().to_string().into());
-                    body_data.get_connection_set().insert(connection);
+                    connection_set.insert(connection);
                 }
+                body_data.set_connection_set(connection_set);
                 let body = body_data
                     .encode()
                     .expect("consumer connection list encode failed");
@@ -218,7 +236,7 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
                     }
                 }
 
-                consume_stats.get_offset_table().insert(mq, offset_wrapper);
+                consume_stats.offset_table.insert(mq, offset_wrapper);
             }
 
             let consume_tps = self
@@ -234,6 +252,137 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
         Some(response)
     }
 
+    pub async fn fetch_all_consume_stats_in_broker(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = request
+            .decode_command_custom_header::<GetConsumeStatsInBrokerHeader>()
+            .unwrap();
+        let groups: Vec<CheetahString> = self
+            .broker_runtime_inner
+            .subscription_group_manager()
+            .subscription_group_wrapper()
+            .lock()
+            .subscription_group_table
+            .keys()
+            .cloned()
+            .collect();
+
+        let mut broker_consume_stats_list = Vec::with_capacity(groups.len());
+        let mut total_diff = 0;
+        let mut total_inflight_diff = 0;
+        for group in groups {
+            let topics = self
+                .broker_runtime_inner
+                .consumer_offset_manager()
+                .which_topic_by_consumer(&group);
+            let mut consume_stats_list = Vec::new();
+            for topic in topics.iter() {
+                let Some(topic_config) = self
+                    .broker_runtime_inner
+                    .topic_config_manager()
+                    .select_topic_config(topic)
+                else {
+                    warn!(
+                        "AdminBrokerProcessor#fetchAllConsumeStatsInBroker: topic config does not \
+                         exist, topic={}",
+                        topic
+                    );
+                    continue;
+                };
+                if request_header.is_order && !topic_config.order {
+                    continue;
+                }
+                if self
+                    .broker_runtime_inner
+                    .consumer_manager()
+                    .find_subscription_data(&group, topic)
+                    .is_none()
+                    && self
+                        .broker_runtime_inner
+                        .consumer_manager()
+                        .find_subscription_data_count(&group)
+                        > 0
+                {
+                    warn!(
+                        "AdminBrokerProcessor#fetchAllConsumeStatsInBroker: topic does not exist \
+                         in consumer group's subscription, topic={}, consumer group={}",
+                        topic, group
+                    );
+                    continue;
+                }
+
+                let mut consume_stats = ConsumeStats::new();
+                for queue_id in 0..topic_config.write_queue_nums as i32 {
+                    let mq = MessageQueue::from_parts(
+                        topic.clone(),
+                        self.broker_runtime_inner
+                            .broker_config()
+                            .broker_name
+                            .clone(),
+                        queue_id,
+                    );
+                    let message_store = self.broker_runtime_inner.message_store().as_ref().unwrap();
+                    let broker_offset = message_store
+                        .get_max_offset_in_queue(topic, queue_id)
+                        .max(0);
+                    let consumer_offset = self
+                        .broker_runtime_inner
+                        .consumer_offset_manager()
+                        .query_offset(&group, topic, queue_id)
+                        .max(0);
+
+                    let mut offset_wrapper = OffsetWrapper::new();
+                    offset_wrapper.set_broker_offset(broker_offset);
+                    offset_wrapper.set_consumer_offset(consumer_offset);
+                    offset_wrapper.set_pull_offset(consumer_offset);
+                    let time_offset = consumer_offset - 1;
+                    if time_offset >= 0 {
+                        let last_timestamp =
+                            message_store.get_message_store_timestamp(topic, queue_id, time_offset);
+                        if last_timestamp > 0 {
+                            offset_wrapper.set_last_timestamp(last_timestamp);
+                        }
+                    }
+                    consume_stats.offset_table.insert(mq, offset_wrapper);
+                }
+                consume_stats.consume_tps += self
+                    .broker_runtime_inner
+                    .broker_stats_manager()
+                    .tps_group_get_nums(&group, topic);
+                total_diff += consume_stats.compute_total_diff();
+                total_inflight_diff += consume_stats.compute_inflight_total_diff();
+                consume_stats_list.push(consume_stats);
+            }
+            let mut subscription_topic_consume_map = HashMap::with_capacity(1);
+            subscription_topic_consume_map.insert(group, consume_stats_list);
+            broker_consume_stats_list.push(subscription_topic_consume_map);
+        }
+
+        let consume_stats_list = ConsumeStatsList {
+            consume_stats_list: broker_consume_stats_list,
+            broker_addr: Some(self.broker_runtime_inner.get_broker_addr().clone()),
+            total_diff,
+            total_inflight_diff,
+        };
+        let mut response = RemotingCommand::create_response_command();
+        match consume_stats_list.encode() {
+            Ok(body) => {
+                response.set_body_mut_ref(body);
+                Some(response)
+            }
+            Err(e) => Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(e.to_string()),
+            ),
+        }
+    }
+
     pub async fn get_all_consumer_offset(
         &mut self,
         _channel: Channel,
@@ -257,4 +406,427 @@ impl<MS: MessageStore> ConsumerRequestHandler<MS> {
             )
         }
     }
+
+    pub async fn get_consumer_running_info(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header =
+            match request.decode_command_custom_header::<GetConsumerRunningInfoRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => {
+                    return Some(RemotingCommand::create_response_command_with_code_remark(
+                        ResponseCode::SystemError,
+                        e.to_string(),
+                    ))
+                }
+            };
+        Some(
+            self.call_consumer(
+                request_code,
+                request,
+                &request_header.consumer_group,
+                &request_header.client_id,
+            )
+            .await,
+        )
+    }
+
+    pub async fn consume_message_directly(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request_code: RequestCode,
+        mut request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header = match request
+            .decode_command_custom_header::<ConsumeMessageDirectlyResultRequestHeader>()
+        {
+            Ok(request_header) => request_header,
+            Err(e) => {
+                return Some(RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    e.to_string(),
+                ))
+            }
+        };
+        request.add_ext_field(
+            "brokerName",
+            self.broker_runtime_inner
+                .broker_config()
+                .broker_identity
+                .broker_name
+                .clone(),
+        );
+        if let Some(ref msg_id) = request_header.msg_id {
+            let message_id = MessageDecoder::decode_message_id(msg_id);
+            let message_data = self
+                .broker_runtime_inner
+                .message_store_unchecked()
+                .select_one_message_by_offset(message_id.offset)
+                .and_then(|result| result.get_bytes());
+            if let Some(body) = message_data {
+                request.set_body_mut_ref(body);
+            }
+        }
+        Some(
+            self.call_consumer(
+                request_code,
+                request,
+                &request_header.consumer_group,
+                &request_header.client_id.unwrap_or_default(),
+            )
+            .await,
+        )
+    }
+
+    pub async fn reset_offset(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header =
+            match request.decode_command_custom_header::<ResetOffsetRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => {
+                    return Some(RemotingCommand::create_response_command_with_code_remark(
+                        ResponseCode::SystemError,
+                        e.to_string(),
+                    ))
+                }
+            };
+        info!(
+            "[reset-offset] reset offset started by {}. topic={}, group={}, timestamp={}, \
+             isForce={}",
+            channel.remote_address(),
+            request_header.topic,
+            request_header.group,
+            request_header.timestamp,
+            request_header.is_force
+        );
+        Some(
+            self.broker_to_client
+                .reset_offset(
+                    &self.broker_runtime_inner,
+                    &request_header.topic,
+                    &request_header.group,
+                    request_header.timestamp,
+                    request_header.is_force,
+                )
+                .await,
+        )
+    }
+
+    pub async fn get_consumer_status(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let request_header =
+            match request.decode_command_custom_header::<GetConsumerStatusRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => {
+                    return Some(RemotingCommand::create_response_command_with_code_remark(
+                        ResponseCode::SystemError,
+                        e.to_string(),
+                    ))
+                }
+            };
+        info!(
+            "[get-consumer-status] get consumer status by {}. topic={}, group={}",
+            channel.remote_address(),
+            request_header.topic,
+            request_header.group
+        );
+        Some(
+            self.broker_to_client
+                .get_consume_status(
+                    &self.broker_runtime_inner,
+                    &request_header.topic,
+                    &request_header.group,
+                    &request_header.client_addr.unwrap_or_default(),
+                )
+                .await,
+        )
+    }
+
+    /// Forwards `request` to the consumer `client_id` of `consumer_group` and returns its reply.
+    async fn call_consumer(
+        &mut self,
+        request_code: RequestCode,
+        request: RemotingCommand,
+        consumer_group: &CheetahString,
+        client_id: &CheetahString,
+    ) -> RemotingCommand {
+        let Some(client_channel_info) = self
+            .broker_runtime_inner
+            .consumer_manager()
+            .find_channel_by_client_id(consumer_group, client_id)
+        else {
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                format!("The Consumer <{client_id}> <{consumer_group}> not online"),
+            );
+        };
+        if client_channel_info.version() < RocketMqVersion::V318Snapshot as i32 {
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                format!(
+                    "The Consumer <{}> Version <{}> too low to finish, please upgrade it to \
+                     V3_1_8_SNAPSHOT",
+                    client_id,
+                    RocketMqVersion::try_from(client_channel_info.version()).map_or_else(
+                        |_| client_channel_info.version().to_string(),
+                        |v| { v.to_string() }
+                    )
+                ),
+            );
+        }
+        let mut new_request = RemotingCommand::create_remoting_command(request_code);
+        if let Some(ext_fields) = request.ext_fields() {
+            new_request = new_request.set_ext_fields(ext_fields.clone());
+        }
+        if let Some(body) = request.body().clone() {
+            new_request.set_body_mut_ref(body);
+        }
+        let mut channel = client_channel_info.channel().clone();
+        match self
+            .broker_to_client
+            .call_client(&mut channel, new_request, CALL_CONSUMER_TIMEOUT_MILLIS)
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                format!("invoke consumer <{client_id}> <{consumer_group}> Exception: {e}"),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::config::TopicConfig;
+    use rocketmq_common::common::consumer::consume_from_where::ConsumeFromWhere;
+    use rocketmq_remoting::connection::Connection;
+    use rocketmq_remoting::net::channel::ChannelInner;
+    use rocketmq_remoting::protocol::body::get_consumer_status_body::GetConsumerStatusBody;
+    use rocketmq_remoting::protocol::body::reset_offset_body::ResetOffsetBody;
+    use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
+    use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
+    use rocketmq_remoting::protocol::LanguageCode;
+    use rocketmq_remoting::protocol::RemotingDeserializable;
+    use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContextWrapper;
+    use rocketmq_store::message_store::local_file_message_store::LocalFileMessageStore;
+    use tempfile::TempDir;
+    use tokio::net::TcpListener;
+    use tokio::net::TcpStream;
+
+    use super::*;
+    use crate::broker_runtime::BrokerRuntime;
+    use crate::client::client_channel_info::ClientChannelInfo;
+
+    const TOPIC: &str = "TopicTest";
+    const GROUP: &str = "GroupTest";
+
+    async fn new_handler() -> (ConsumerRequestHandler<LocalFileMessageStore>, TempDir) {
+        let root_dir = TempDir::new().unwrap();
+        let broker_runtime_inner = BrokerRuntime::new_inner_for_test(root_dir.path()).await;
+        broker_runtime_inner
+            .topic_config_manager()
+            .put_topic_config(TopicConfig::with_queues(TOPIC, 2, 2));
+        (ConsumerRequestHandler::new(broker_runtime_inner), root_dir)
+    }
+
+    /// A channel whose connection is gone, every request sent through it fails right away.
+    async fn closed_channel() -> Channel {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap())
+            .await
+            .unwrap();
+        let local_address = stream.local_addr().unwrap();
+        let remote_address = stream.peer_addr().unwrap();
+        let channel_inner = ArcMut::new(ChannelInner::new(
+            Connection::new(stream),
+            ArcMut::new(HashMap::new()),
+        ));
+        Channel::new(
+            ArcMut::downgrade(&channel_inner),
+            local_address,
+            remote_address,
+        )
+    }
+
+    fn register_consumer(
+        handler: &ConsumerRequestHandler<LocalFileMessageStore>,
+        channel: Channel,
+    ) {
+        handler
+            .broker_runtime_inner
+            .consumer_manager()
+            .register_consumer(
+                &CheetahString::from_static_str(GROUP),
+                ClientChannelInfo::new(
+                    channel,
+                    CheetahString::from_static_str("client-a"),
+                    LanguageCode::RUST,
+                    RocketMqVersion::CURRENT_VERSION as i32,
+                ),
+                ConsumeType::ConsumePassively,
+                MessageModel::Clustering,
+                ConsumeFromWhere::ConsumeFromLastOffset,
+                HashSet::new(),
+                false,
+            );
+    }
+
+    fn commit_offsets(handler: &ConsumerRequestHandler<LocalFileMessageStore>, offset: i64) {
+        for queue_id in 0..2 {
+            handler
+                .broker_runtime_inner
+                .consumer_offset_manager()
+                .commit_offset(
+                    CheetahString::from_static_str("127.0.0.1"),
+                    &CheetahString::from_static_str(GROUP),
+                    &CheetahString::from_static_str(TOPIC),
+                    queue_id,
+                    offset,
+                );
+        }
+    }
+
+    fn query_offset(handler: &ConsumerRequestHandler<LocalFileMessageStore>, queue_id: i32) -> i64 {
+        handler
+            .broker_runtime_inner
+            .consumer_offset_manager()
+            .query_offset(
+                &CheetahString::from_static_str(GROUP),
+                &CheetahString::from_static_str(TOPIC),
+                queue_id,
+            )
+    }
+
+    async fn reset_offset(
+        handler: &mut ConsumerRequestHandler<LocalFileMessageStore>,
+        topic: &str,
+    ) -> RemotingCommand {
+        let channel = closed_channel().await;
+        let ctx = ArcMut::new(ConnectionHandlerContextWrapper::new(channel.clone()));
+        let mut request = RemotingCommand::create_request_command(
+            RequestCode::InvokeBrokerToResetOffset,
+            ResetOffsetRequestHeader {
+                topic: CheetahString::from(topic),
+                group: CheetahString::from_static_str(GROUP),
+                timestamp: -1,
+                is_force: true,
+                ..Default::default()
+            },
+        );
+        request.make_custom_header_to_net();
+        handler
+            .reset_offset(
+                channel,
+                ctx,
+                RequestCode::InvokeBrokerToResetOffset,
+                request,
+            )
+            .await
+            .unwrap()
+    }
+
+    async fn get_consumer_status(
+        handler: &mut ConsumerRequestHandler<LocalFileMessageStore>,
+    ) -> RemotingCommand {
+        let channel = closed_channel().await;
+        let ctx = ArcMut::new(ConnectionHandlerContextWrapper::new(channel.clone()));
+        let mut request = RemotingCommand::create_request_command(
+            RequestCode::InvokeBrokerToGetConsumerStatus,
+            GetConsumerStatusRequestHeader {
+                topic: CheetahString::from_static_str(TOPIC),
+                group: CheetahString::from_static_str(GROUP),
+                ..Default::default()
+            },
+        );
+        request.make_custom_header_to_net();
+        handler
+            .get_consumer_status(
+                channel,
+                ctx,
+                RequestCode::InvokeBrokerToGetConsumerStatus,
+                request,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reset_offset_rewrites_offsets_of_offline_group() {
+        let (mut handler, _root_dir) = new_handler().await;
+        commit_offsets(&handler, 10);
+
+        let response = reset_offset(&mut handler, TOPIC).await;
+        assert_eq!(ResponseCode::from(response.code()), ResponseCode::Success);
+        let body = ResetOffsetBody::decode(response.body().as_ref().unwrap()).unwrap();
+        assert_eq!(body.offset_table.len(), 2);
+        assert!(body.offset_table.values().all(|offset| *offset == 0));
+        assert_eq!(query_offset(&handler, 0), 0);
+        assert_eq!(query_offset(&handler, 1), 0);
+    }
+
+    #[tokio::test]
+    async fn reset_offset_leaves_offsets_of_online_group_to_consumers() {
+        let (mut handler, _root_dir) = new_handler().await;
+        commit_offsets(&handler, 10);
+        register_consumer(&handler, closed_channel().await);
+
+        let response = reset_offset(&mut handler, TOPIC).await;
+        assert_eq!(ResponseCode::from(response.code()), ResponseCode::Success);
+        let body = ResetOffsetBody::decode(response.body().as_ref().unwrap()).unwrap();
+        assert!(body.offset_table.values().all(|offset| *offset == 0));
+        assert_eq!(query_offset(&handler, 0), 10);
+    }
+
+    #[tokio::test]
+    async fn reset_offset_rejects_unknown_topic_and_group() {
+        let (mut handler, _root_dir) = new_handler().await;
+        let response = reset_offset(&mut handler, "TopicMissing").await;
+        assert_eq!(
+            ResponseCode::from(response.code()),
+            ResponseCode::TopicNotExist
+        );
+
+        let response = reset_offset(&mut handler, TOPIC).await;
+        assert_eq!(
+            ResponseCode::from(response.code()),
+            ResponseCode::SystemError
+        );
+        assert_eq!(
+            response.remark().map(|remark| remark.as_str()),
+            Some("The consumer group <GroupTest> not exist")
+        );
+    }
+
+    #[tokio::test]
+    async fn get_consumer_status_without_consumers() {
+        let (mut handler, _root_dir) = new_handler().await;
+        let response = get_consumer_status(&mut handler).await;
+        assert_eq!(
+            ResponseCode::from(response.code()),
+            ResponseCode::SystemError
+        );
+
+        // the only consumer can not be reached, the status is answered without it
+        register_consumer(&handler, closed_channel().await);
+        let response = get_consumer_status(&mut handler).await;
+        assert_eq!(ResponseCode::from(response.code()), ResponseCode::Success);
+        let body = GetConsumerStatusBody::decode(response.body().as_ref().unwrap()).unwrap();
+        assert!(body.consumer_table.is_empty());
+    }
 }
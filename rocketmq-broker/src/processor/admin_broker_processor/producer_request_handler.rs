@@ -0,0 +1,85 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::header::get_producer_connection_list_request_header::GetProducerConnectionListRequestHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+
+#[derive(Clone)]
+pub(super) struct ProducerRequestHandler<MS> {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+}
+
+impl<MS> ProducerRequestHandler<MS> {
+    pub fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        Self {
+            broker_runtime_inner,
+        }
+    }
+}
+
+impl<MS: MessageStore> ProducerRequestHandler<MS> {
+    pub async fn get_producer_connection_list(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let request_header = match request
+            .decode_command_custom_header::<GetProducerConnectionListRequestHeader>()
+        {
+            Ok(header) => header,
+            Err(error) => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(error.to_string()),
+                )
+            }
+        };
+        match self
+            .broker_runtime_inner
+            .producer_manager()
+            .get_group_connections(request_header.producer_group.as_str())
+        {
+            Some(connection_set) => {
+                let body = ProducerConnection { connection_set }
+                    .encode()
+                    .expect("producer connection list encode failed");
+                Some(response.set_body(body))
+            }
+            None => Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(format!(
+                        "the producer group[{}] not exist",
+                        request_header.producer_group
+                    )),
+            ),
+        }
+    }
+}
@@ -0,0 +1,256 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::subscription_group_list::SubscriptionGroupList;
+use rocketmq_remoting::protocol::header::delete_subscription_group_request_header::DeleteSubscriptionGroupRequestHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
+use rocketmq_rust::ArcMut;
+use rocketmq_store::base::message_store::MessageStore;
+use tracing::info;
+
+use crate::broker_runtime::BrokerRuntimeInner;
+use crate::subscription::manager::subscription_group_manager::CHARACTER_MAX_LENGTH;
+
+#[derive(Clone)]
+pub(super) struct SubscriptionGroupRequestHandler<MS> {
+    broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>,
+}
+
+impl<MS> SubscriptionGroupRequestHandler<MS> {
+    pub fn new(broker_runtime_inner: ArcMut<BrokerRuntimeInner<MS>>) -> Self {
+        SubscriptionGroupRequestHandler {
+            broker_runtime_inner,
+        }
+    }
+}
+
+impl<MS: MessageStore> SubscriptionGroupRequestHandler<MS> {
+    pub async fn update_and_create_subscription_group(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        info!(
+            "AdminBrokerProcessor#updateAndCreateSubscriptionGroup called by {}",
+            channel.remote_address()
+        );
+        let mut config = match request
+            .get_body()
+            .map(|body| SubscriptionGroupConfig::decode(body))
+        {
+            Some(Ok(config)) => config,
+            Some(Err(e)) => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(format!("decode subscription group config failed: {e}")),
+                )
+            }
+            None => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark("subscription group config is empty"),
+                )
+            }
+        };
+        if let Err(remark) = validate_group_name(config.group_name()) {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(remark),
+            );
+        }
+        if let Err(remark) = self
+            .broker_runtime_inner
+            .subscription_group_manager()
+            .update_subscription_group_config(&mut config)
+        {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(remark),
+            );
+        }
+        Some(response)
+    }
+
+    pub async fn update_and_create_subscription_group_list(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let mut group_config_list = match request
+            .get_body()
+            .map(|body| SubscriptionGroupList::decode(body))
+        {
+            Some(Ok(list)) => list.group_config_list,
+            Some(Err(e)) => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(format!("decode subscription group list failed: {e}")),
+                )
+            }
+            None => {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark("subscription group list is empty"),
+                )
+            }
+        };
+        info!(
+            "AdminBrokerProcessor#updateAndCreateSubscriptionGroupList called by {}, size={}",
+            channel.remote_address(),
+            group_config_list.len()
+        );
+        for config in group_config_list.iter() {
+            if let Err(remark) = validate_group_name(config.group_name()) {
+                return Some(
+                    response
+                        .set_code(ResponseCode::SystemError)
+                        .set_remark(remark),
+                );
+            }
+        }
+        if let Err(remark) = self
+            .broker_runtime_inner
+            .subscription_group_manager()
+            .update_subscription_group_config_list(&mut group_config_list)
+        {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark(remark),
+            );
+        }
+        Some(response)
+    }
+
+    pub async fn get_all_subscription_group(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        _request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let content = self
+            .broker_runtime_inner
+            .subscription_group_manager()
+            .encode_pretty(false);
+        if content.is_empty() {
+            return Some(
+                response
+                    .set_code(ResponseCode::SystemError)
+                    .set_remark("No subscription group in this broker"),
+            );
+        }
+        Some(response.set_body(content))
+    }
+
+    pub async fn delete_subscription_group(
+        &mut self,
+        channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        _request_code: RequestCode,
+        request: RemotingCommand,
+    ) -> Option<RemotingCommand> {
+        let response = RemotingCommand::create_response_command();
+        let request_header =
+            match request.decode_command_custom_header::<DeleteSubscriptionGroupRequestHeader>() {
+                Ok(request_header) => request_header,
+                Err(e) => {
+                    return Some(
+                        response
+                            .set_code(ResponseCode::SystemError)
+                            .set_remark(e.to_string()),
+                    )
+                }
+            };
+        info!(
+            "AdminBrokerProcessor#deleteSubscriptionGroup, caller={}",
+            channel.remote_address()
+        );
+        self.broker_runtime_inner
+            .subscription_group_manager()
+            .delete_subscription_group_config(&request_header.group_name);
+        if request_header.clean_offset {
+            self.broker_runtime_inner
+                .consumer_offset_manager()
+                .remove_offset(&request_header.group_name);
+        }
+        if self
+            .broker_runtime_inner
+            .broker_config()
+            .auto_delete_unused_stats
+        {
+            self.broker_runtime_inner
+                .broker_stats_manager()
+                .on_group_deleted(&request_header.group_name);
+        }
+        Some(response)
+    }
+}
+
+fn validate_group_name(group_name: &str) -> Result<(), CheetahString> {
+    if group_name.is_empty() {
+        return Err("The consumer group name is blank".into());
+    }
+    if group_name.len() > CHARACTER_MAX_LENGTH {
+        return Err(format!(
+            "The consumer group name is longer than {CHARACTER_MAX_LENGTH} characters"
+        )
+        .into());
+    }
+    if TopicValidator::is_topic_or_group_illegal(group_name) {
+        return Err(format!(
+            "The consumer group [{group_name}] contains illegal characters, allowing only \
+             ^[%|a-zA-Z0-9_-]+$"
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_group_name_rejects_invalid_names() {
+        assert!(validate_group_name("group_a-1").is_ok());
+        assert!(validate_group_name("").is_err());
+        assert!(validate_group_name("group a").is_err());
+        assert!(validate_group_name(&"g".repeat(CHARACTER_MAX_LENGTH + 1)).is_err());
+    }
+}
@@ -20,24 +20,31 @@ use std::sync::Arc;
 
 use cheetah_string::CheetahString;
 use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::filter::expression_type::ExpressionType;
 use rocketmq_common::common::mix_all;
 use rocketmq_common::common::mix_all::IS_SUB_CHANGE;
 use rocketmq_common::common::mix_all::IS_SUPPORT_HEART_BEAT_V2;
 use rocketmq_common::common::sys_flag::topic_sys_flag;
 use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
 use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::RemotingSysResponseCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::check_client_request_body::CheckClientRequestBody;
 use rocketmq_remoting::protocol::header::unregister_client_request_header::UnregisterClientRequestHeader;
 use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
 use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
 use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::message_store::MessageStore;
 use tracing::info;
+use tracing::warn;
 
 use crate::broker_runtime::BrokerRuntimeInner;
 use crate::client::client_channel_info::ClientChannelInfo;
+use crate::filter::manager::consumer_filter_manager::ConsumerFilterManager;
 
 pub struct ClientManageProcessor<MS> {
     consumer_group_heartbeat_table: Arc<
@@ -74,15 +81,62 @@ where
         match request_code {
             RequestCode::HeartBeat => self.heart_beat(channel, ctx, request),
             RequestCode::UnregisterClient => self.unregister_client(channel, ctx, request),
-            RequestCode::CheckClientConfig => {
-                unimplemented!("CheckClientConfig")
-            }
+            RequestCode::CheckClientConfig => self.check_client_config(channel, ctx, request),
             _ => {
                 unimplemented!("CheckClientConfig")
             }
         }
     }
 
+    fn check_client_config(
+        &self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<Option<RemotingCommand>> {
+        let response = RemotingCommand::create_response_command();
+        let request_body = request
+            .body()
+            .as_ref()
+            .and_then(|body| CheckClientRequestBody::decode(body.as_ref()).ok());
+        if let Some(request_body) = request_body {
+            let subscription_data = &request_body.subscription_data;
+            if ExpressionType::is_tag_type(Some(subscription_data.expression_type.as_str())) {
+                return Ok(Some(response));
+            }
+            if !self
+                .broker_runtime_inner
+                .broker_config()
+                .enable_property_filter
+            {
+                return Ok(Some(
+                    response
+                        .set_code(RemotingSysResponseCode::SystemError)
+                        .set_remark(format!(
+                            "The broker does not support consumer to filter message by {}",
+                            subscription_data.expression_type
+                        )),
+                ));
+            }
+            if let Err(e) = ConsumerFilterManager::compile(
+                Some(&subscription_data.sub_string),
+                Some(&subscription_data.expression_type),
+            ) {
+                warn!(
+                    "Client {}@{} filter message, but failed to compile expression! sub={:?}, \
+                     error={}",
+                    request_body.client_id, request_body.group, subscription_data, e
+                );
+                return Ok(Some(
+                    response
+                        .set_code(ResponseCode::SubscriptionParseFailed)
+                        .set_remark(e.to_string()),
+                ));
+            }
+        }
+        Ok(Some(response))
+    }
+
     fn unregister_client(
         &self,
         channel: Channel,
@@ -194,6 +194,22 @@ impl PopInflightMessageCounter {
         }
     }
 
+    /// Returns the in-flight message number of every `topic@group`.
+    #[cfg(feature = "metrics")]
+    pub fn in_flight_message_num_table(&self) -> HashMap<CheetahString, i64> {
+        self.topic_in_flight_message_num
+            .lock()
+            .iter()
+            .map(|(key, queues)| {
+                let num = queues
+                    .values()
+                    .map(|num| num.load(Ordering::Relaxed).max(0))
+                    .sum();
+                (key.clone(), num)
+            })
+            .collect()
+    }
+
     fn build_key(topic: &CheetahString, group: &CheetahString) -> CheetahString {
         format!("{}{}{}", topic, Self::TOPIC_GROUP_SEPARATOR, group).into()
     }
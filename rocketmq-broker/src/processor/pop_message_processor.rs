@@ -1252,6 +1252,9 @@ where
             // Add the difference between the offset of all pulled messages and the start offset
             ck.add_diff(((*msg_queue_offset) as i64 - offset) as i32);
         }
+        let broker_stats_manager = self.broker_runtime_inner.broker_stats_manager();
+        broker_stats_manager.inc_broker_ck_nums(1);
+        broker_stats_manager.inc_group_ck_nums(request_header.consumer_group.as_str(), topic, 1);
         let pop_buffer_merge_service_ref_mut = self.pop_buffer_merge_service.mut_from_ref();
 
         // put check point into memory
@@ -1302,6 +1302,7 @@ where
         msg_inner.properties_string = message_properties_to_string(msg_ext.get_properties());
 
         let inner_topic = msg_inner.get_topic().clone();
+        let inner_queue_id = msg_inner.message_ext_inner.queue_id;
         let put_message_result = self
             .broker_runtime_inner
             .message_store_mut()
@@ -1314,20 +1315,48 @@ where
             .and_then(|value| value.get(BrokerStatsManager::COMMERCIAL_OWNER).cloned());
         let (response, succeeded) = match put_message_result.put_message_status() {
             PutMessageStatus::PutOk => {
-                let mut _back_topic = msg_ext.get_topic().clone();
+                let mut back_topic = msg_ext.get_topic().clone();
                 let correct_topic = msg_ext.get_property(&CheetahString::from_static_str(
                     MessageConst::PROPERTY_RETRY_TOPIC,
                 ));
                 if let Some(topic) = correct_topic {
-                    _back_topic = topic;
+                    back_topic = topic;
                 }
 
+                let broker_stats_manager = self.broker_runtime_inner.broker_stats_manager();
                 if TopicValidator::RMQ_SYS_SCHEDULE_TOPIC == inner_topic {
-                    //TODO: implement this
+                    let wrote_bytes = put_message_result
+                        .append_message_result()
+                        .map_or(0, |result| result.wrote_bytes);
+                    broker_stats_manager.inc_topic_put_nums(inner_topic.as_str(), 1, 1);
+                    broker_stats_manager.inc_topic_put_size(inner_topic.as_str(), wrote_bytes);
+                    broker_stats_manager.inc_queue_put_nums(
+                        inner_topic.as_str(),
+                        inner_queue_id,
+                        1,
+                        1,
+                    );
+                    broker_stats_manager.inc_queue_put_size(
+                        inner_topic.as_str(),
+                        inner_queue_id,
+                        wrote_bytes,
+                    );
                 }
+                broker_stats_manager
+                    .inc_send_back_nums(request_header.group.as_str(), back_topic.as_str());
 
                 if is_dlq {
-                    // TODO: implement this
+                    broker_stats_manager.inc_dlq_stat_value(
+                        BrokerStatsManager::SNDBCK2DLQ_TIMES,
+                        commercial_owner.as_ref().map_or("", |owner| owner.as_str()),
+                        request_header.group.as_str(),
+                        request_header
+                            .origin_topic
+                            .as_ref()
+                            .map_or("", |topic| topic.as_str()),
+                        "SEND_BACK_TO_DLQ",
+                        1,
+                    );
                 }
                 (RemotingCommand::create_response_command(), true)
             }
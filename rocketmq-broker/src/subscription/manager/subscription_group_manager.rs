@@ -15,21 +15,21 @@
  * limitations under the License.
  */
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use cheetah_string::CheetahString;
+use rocketmq_common::common::attribute::attribute_util::AttributeUtil;
+use rocketmq_common::common::attribute::subscription_group_attributes::SubscriptionGroupAttributes;
 use rocketmq_common::common::config_manager::ConfigManager;
 use rocketmq_common::common::mix_all::is_sys_consumer_group;
 use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_remoting::protocol::body::subscription_group_wrapper::SubscriptionGroupWrapper;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
-use rocketmq_remoting::protocol::DataVersion;
 use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_rust::ArcMut;
 use rocketmq_store::base::message_store::MessageStore;
-use serde::Deserialize;
-use serde::Serialize;
 use tracing::info;
+use tracing::warn;
 
 use crate::broker_path_config_helper::get_subscription_group_path;
 use crate::broker_runtime::BrokerRuntimeInner;
@@ -155,22 +155,100 @@ where
                     subscription_group_config_new
                 );
             }
-            let state_machine_version =
-                if let Some(ref store) = self.broker_runtime_inner.message_store() {
-                    store.get_state_machine_version()
-                } else {
-                    0
-                };
-            self.subscription_group_wrapper
-                .lock()
-                .data_version
-                .next_version_with(state_machine_version);
+            self.update_data_version();
             self.persist();
             subscription_group_config = Some(subscription_group_config_new);
         }
         subscription_group_config
     }
 
+    /// Creates or replaces the config of a subscription group and persists it.
+    pub fn update_subscription_group_config(
+        &self,
+        config: &mut SubscriptionGroupConfig,
+    ) -> Result<(), String> {
+        self.update_subscription_group_config_without_persist(config)?;
+        self.persist();
+        Ok(())
+    }
+
+    /// Creates or replaces a batch of subscription group configs, persisting once at the end.
+    pub fn update_subscription_group_config_list(
+        &self,
+        config_list: &mut [SubscriptionGroupConfig],
+    ) -> Result<(), String> {
+        for config in config_list.iter_mut() {
+            self.update_subscription_group_config_without_persist(config)?;
+        }
+        self.persist();
+        Ok(())
+    }
+
+    fn update_subscription_group_config_without_persist(
+        &self,
+        config: &mut SubscriptionGroupConfig,
+    ) -> Result<(), String> {
+        let group_name = CheetahString::from(config.group_name());
+        let current_attributes = self
+            .find_subscription_group_config_inner(&group_name)
+            .map(|current| current.attributes().clone());
+        let final_attributes = AttributeUtil::alter_current_attributes(
+            current_attributes.is_none(),
+            SubscriptionGroupAttributes::all(),
+            &current_attributes.unwrap_or_default(),
+            config.attributes(),
+        )
+        .map_err(|e| format!("{e:?}"))?;
+        config.set_attributes(final_attributes);
+        let old = self
+            .subscription_group_wrapper
+            .lock()
+            .subscription_group_table
+            .insert(group_name, config.clone());
+        match old {
+            Some(old) => info!(
+                "update subscription group config, old: {:?} new: {:?}",
+                old, config
+            ),
+            None => info!("create new subscription group, {:?}", config),
+        }
+        self.update_data_version();
+        Ok(())
+    }
+
+    /// Deletes the config of a subscription group together with its forbidden settings.
+    pub fn delete_subscription_group_config(&self, group_name: &CheetahString) {
+        let old = {
+            let mut wrapper = self.subscription_group_wrapper.lock();
+            wrapper.forbidden_table.remove(group_name);
+            wrapper.subscription_group_table.remove(group_name)
+        };
+        match old {
+            Some(old) => {
+                info!("delete subscription group OK, subscription group:{:?}", old);
+                self.update_data_version();
+                self.persist();
+            }
+            None => warn!(
+                "delete subscription group failed, subscription groupName: {} not exist",
+                group_name
+            ),
+        }
+    }
+
+    fn update_data_version(&self) {
+        let state_machine_version =
+            if let Some(ref store) = self.broker_runtime_inner.message_store() {
+                store.get_state_machine_version()
+            } else {
+                0
+            };
+        self.subscription_group_wrapper
+            .lock()
+            .data_version
+            .next_version_with(state_machine_version);
+    }
+
     fn find_subscription_group_config_inner(
         &self,
         group: &CheetahString,
@@ -208,21 +286,3 @@ where
         }
     }
 }
-
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub(crate) struct SubscriptionGroupWrapper {
-    subscription_group_table: HashMap<CheetahString, SubscriptionGroupConfig>,
-    forbidden_table: HashMap<CheetahString, HashMap<CheetahString, i32>>,
-    data_version: DataVersion,
-}
-
-impl SubscriptionGroupWrapper {
-    pub fn subscription_group_table(&self) -> &HashMap<CheetahString, SubscriptionGroupConfig> {
-        &self.subscription_group_table
-    }
-
-    pub fn forbidden_table(&self) -> &HashMap<CheetahString, HashMap<CheetahString, i32>> {
-        &self.forbidden_table
-    }
-}
@@ -0,0 +1,335 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! `mqadmin` style sub commands built on top of [`DefaultMQAdminExt`].
+
+pub mod broker_commands;
+pub mod consumer_commands;
+pub mod message_commands;
+pub mod topic_commands;
+
+use cheetah_string::CheetahString;
+use chrono::Local;
+use chrono::TimeZone;
+use clap::Args;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::UtilAll::parse_date;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::connection::Connection;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::output::print_rows;
+use crate::output::OutputFormat;
+
+/// Timestamp pattern accepted by the offset related commands, e.g. `2024-05-01#12:30:00:000`.
+pub const TIMESTAMP_PATTERN: &str = "%Y-%m-%d#%H:%M:%S:%3f";
+
+/// Options shared by every sub command.
+#[derive(Debug, Clone)]
+pub struct CommandContext {
+    pub namesrv_addr: String,
+    pub output: OutputFormat,
+}
+
+impl CommandContext {
+    pub fn new(namesrv_addr: impl Into<String>, output: OutputFormat) -> Self {
+        Self {
+            namesrv_addr: namesrv_addr.into(),
+            output,
+        }
+    }
+
+    pub fn print<T>(&self, rows: &[T])
+    where
+        T: Tabled + Serialize,
+    {
+        print_rows(rows, self.output);
+    }
+
+    async fn start_admin(&self) -> RocketMQResult<DefaultMQAdminExt> {
+        let mut admin = DefaultMQAdminExt::new();
+        admin.client_config_mut().namesrv_addr =
+            Some(CheetahString::from(self.namesrv_addr.as_str()));
+        MQAdminExt::start(&mut admin).await?;
+        Ok(admin)
+    }
+}
+
+/// A sub command that only needs an admin client and produces table rows.
+pub(crate) trait AdminSubCommand {
+    type Row: Tabled + Serialize;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>>;
+}
+
+/// Starts an admin client, runs `command` with it and prints the resulting rows.
+pub(crate) async fn execute_admin_command<C>(
+    command: &C,
+    ctx: &CommandContext,
+) -> RocketMQResult<()>
+where
+    C: AdminSubCommand,
+{
+    let mut admin = ctx.start_admin().await?;
+    let result = command.run(&admin).await;
+    MQAdminExt::shutdown(&mut admin).await;
+    ctx.print(&result?);
+    Ok(())
+}
+
+/// Selects either a single broker or every master broker of a cluster.
+#[derive(Debug, Clone, Args)]
+#[group(required = true, multiple = false)]
+pub struct BrokerTarget {
+    #[arg(short = 'b', long = "brokerAddr", help = "broker address")]
+    pub broker_addr: Option<String>,
+
+    #[arg(short = 'c', long = "clusterName", help = "cluster name")]
+    pub cluster_name: Option<String>,
+}
+
+impl BrokerTarget {
+    /// Resolves the broker addresses this target refers to.
+    pub(crate) async fn resolve(
+        &self,
+        admin: &DefaultMQAdminExt,
+    ) -> RocketMQResult<Vec<CheetahString>> {
+        if let Some(broker_addr) = &self.broker_addr {
+            return Ok(vec![CheetahString::from(broker_addr.as_str())]);
+        }
+        let cluster_name = self.cluster_name.as_deref().unwrap_or_default();
+        let cluster_info = admin.examine_broker_cluster_info().await?;
+        let master_addrs = master_addrs_of_cluster(&cluster_info, cluster_name);
+        if master_addrs.is_empty() {
+            return Err(RocketmqError::IllegalArgument(format!(
+                "no master broker found in cluster {}",
+                cluster_name
+            )));
+        }
+        Ok(master_addrs)
+    }
+}
+
+/// Returns the master addresses of every broker in `cluster_name`, sorted by broker name.
+pub fn master_addrs_of_cluster(
+    cluster_info: &ClusterInfo,
+    cluster_name: &str,
+) -> Vec<CheetahString> {
+    let (Some(cluster_addr_table), Some(broker_addr_table)) = (
+        cluster_info.cluster_addr_table.as_ref(),
+        cluster_info.broker_addr_table.as_ref(),
+    ) else {
+        return vec![];
+    };
+    let Some(broker_names) = cluster_addr_table.get(cluster_name) else {
+        return vec![];
+    };
+    let mut broker_names = broker_names.iter().collect::<Vec<_>>();
+    broker_names.sort();
+    broker_names
+        .into_iter()
+        .filter_map(|broker_name| broker_addr_table.get(broker_name))
+        .filter_map(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+        .cloned()
+        .collect()
+}
+
+/// Parses a timestamp given as `now`, epoch milliseconds or [`TIMESTAMP_PATTERN`] in local time.
+pub fn parse_timestamp(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("now") {
+        return Some(get_current_millis());
+    }
+    if let Ok(millis) = value.parse::<u64>() {
+        return Some(millis);
+    }
+    let date_time = parse_date(value, TIMESTAMP_PATTERN)?;
+    let millis = Local
+        .from_local_datetime(&date_time)
+        .earliest()?
+        .timestamp_millis();
+    u64::try_from(millis).ok()
+}
+
+pub(crate) fn format_timestamp(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        "-".to_string()
+    } else {
+        time_millis_to_human_string2(timestamp)
+    }
+}
+
+/// Result of a write operation against a single broker, cluster or name server.
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationRow {
+    #[tabled(rename = "Target")]
+    pub target: String,
+    #[tabled(rename = "Result")]
+    pub result: String,
+}
+
+impl OperationRow {
+    pub fn success(target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            result: "success".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionRow {
+    #[tabled(rename = "Client Id")]
+    pub client_id: String,
+    #[tabled(rename = "Client Addr")]
+    pub client_addr: String,
+    #[tabled(rename = "Language")]
+    pub language: String,
+    #[tabled(rename = "Version")]
+    pub version: String,
+}
+
+impl From<&Connection> for ConnectionRow {
+    fn from(connection: &Connection) -> Self {
+        ConnectionRow {
+            client_id: connection.get_client_id().to_string(),
+            client_addr: connection.get_client_addr().to_string(),
+            language: connection.get_language().to_string(),
+            version: version_desc(connection.get_version()),
+        }
+    }
+}
+
+/// Sorts connection rows so the output is stable between invocations.
+pub(crate) fn connection_rows<'a>(
+    connections: impl IntoIterator<Item = &'a Connection>,
+) -> Vec<ConnectionRow> {
+    let mut rows = connections
+        .into_iter()
+        .map(ConnectionRow::from)
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+    rows
+}
+
+fn version_desc(version: i32) -> String {
+    rocketmq_common::common::mq_version::RocketMqVersion::try_from(version)
+        .map(|version| version.to_string())
+        .unwrap_or_else(|_| version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::collections::HashSet;
+
+    use chrono::NaiveDate;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn broker_data(cluster: &str, broker_name: &str, addrs: &[(u64, &str)]) -> BrokerData {
+        BrokerData::new(
+            CheetahString::from(cluster),
+            CheetahString::from(broker_name),
+            addrs
+                .iter()
+                .map(|(id, addr)| (*id, CheetahString::from(*addr)))
+                .collect(),
+            None,
+        )
+    }
+
+    #[test]
+    fn master_addrs_of_cluster_returns_sorted_masters() {
+        let mut broker_addr_table = HashMap::new();
+        broker_addr_table.insert(
+            CheetahString::from("broker-b"),
+            broker_data("DefaultCluster", "broker-b", &[(0, "10.0.0.2:10911")]),
+        );
+        broker_addr_table.insert(
+            CheetahString::from("broker-a"),
+            broker_data(
+                "DefaultCluster",
+                "broker-a",
+                &[(0, "10.0.0.1:10911"), (1, "10.0.0.3:10911")],
+            ),
+        );
+        broker_addr_table.insert(
+            CheetahString::from("broker-c"),
+            broker_data("DefaultCluster", "broker-c", &[(1, "10.0.0.4:10911")]),
+        );
+        let mut cluster_addr_table = HashMap::new();
+        cluster_addr_table.insert(
+            CheetahString::from("DefaultCluster"),
+            HashSet::from([
+                CheetahString::from("broker-a"),
+                CheetahString::from("broker-b"),
+                CheetahString::from("broker-c"),
+            ]),
+        );
+        let cluster_info = ClusterInfo::new(Some(broker_addr_table), Some(cluster_addr_table));
+
+        assert_eq!(
+            master_addrs_of_cluster(&cluster_info, "DefaultCluster"),
+            vec![
+                CheetahString::from("10.0.0.1:10911"),
+                CheetahString::from("10.0.0.2:10911")
+            ]
+        );
+        assert!(master_addrs_of_cluster(&cluster_info, "OtherCluster").is_empty());
+        assert!(master_addrs_of_cluster(&ClusterInfo::default(), "DefaultCluster").is_empty());
+    }
+
+    #[test]
+    fn parse_timestamp_accepts_millis_and_now() {
+        assert_eq!(parse_timestamp("1700000000000"), Some(1_700_000_000_000));
+        let before = get_current_millis();
+        let now = parse_timestamp("now").unwrap();
+        assert!(now >= before);
+    }
+
+    #[test]
+    fn parse_timestamp_accepts_date_pattern_in_local_time() {
+        let expected = Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2024, 5, 1)
+                    .unwrap()
+                    .and_hms_milli_opt(12, 30, 15, 250)
+                    .unwrap(),
+            )
+            .earliest()
+            .unwrap()
+            .timestamp_millis() as u64;
+        assert_eq!(parse_timestamp("2024-05-01#12:30:15:250"), Some(expected));
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_garbage() {
+        assert_eq!(parse_timestamp("yesterday"), None);
+        assert_eq!(parse_timestamp("2024-05-01 12:30:15"), None);
+    }
+}
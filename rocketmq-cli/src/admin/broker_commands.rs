@@ -0,0 +1,347 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_error::RocketMQResult;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::admin::AdminSubCommand;
+use crate::admin::BrokerTarget;
+use crate::admin::OperationRow;
+
+#[derive(Debug, Clone, Args)]
+pub struct ClusterListSubCommand {
+    #[arg(short = 'c', long = "clusterName", help = "which cluster")]
+    pub cluster_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterRow {
+    #[tabled(rename = "Cluster Name")]
+    pub cluster_name: String,
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "BID")]
+    pub broker_id: u64,
+    #[tabled(rename = "Addr")]
+    pub addr: String,
+    #[tabled(rename = "Version")]
+    pub version: String,
+    #[tabled(rename = "InTPS")]
+    pub in_tps: String,
+    #[tabled(rename = "OutTPS")]
+    pub out_tps: String,
+    #[tabled(rename = "InTotalToday")]
+    pub in_total_today: i64,
+    #[tabled(rename = "OutTotalToday")]
+    pub out_total_today: i64,
+}
+
+impl ClusterRow {
+    /// Fills the runtime columns from the `KVTable` returned by the broker.
+    pub fn apply_runtime_stats(&mut self, stats: &HashMap<CheetahString, CheetahString>) {
+        let value = |key: &str| {
+            stats
+                .get(key)
+                .map(|value| value.as_str())
+                .unwrap_or_default()
+        };
+        let number = |key: &str| value(key).trim().parse::<i64>().unwrap_or_default();
+        self.version = value("brokerVersionDesc").to_string();
+        self.in_tps = first_tps(value("putTps"));
+        self.out_tps = first_tps(value("getTransferredTps"));
+        self.in_total_today = number("msgPutTotalTodayNow") - number("msgPutTotalTodayMorning");
+        self.out_total_today = number("msgGetTotalTodayNow") - number("msgGetTotalTodayMorning");
+    }
+}
+
+/// The broker reports tps over several windows, e.g. `"12.5 10.0 8.0"`; the first is the latest.
+fn first_tps(value: &str) -> String {
+    let tps = value
+        .split_whitespace()
+        .next()
+        .and_then(|tps| tps.parse::<f64>().ok())
+        .unwrap_or_default();
+    format!("{:.2}", tps)
+}
+
+/// Builds one row per broker instance, optionally restricted to `cluster_name`.
+pub fn cluster_rows(cluster_info: &ClusterInfo, cluster_name: Option<&str>) -> Vec<ClusterRow> {
+    let (Some(cluster_addr_table), Some(broker_addr_table)) = (
+        cluster_info.cluster_addr_table.as_ref(),
+        cluster_info.broker_addr_table.as_ref(),
+    ) else {
+        return vec![];
+    };
+    let mut cluster_names = cluster_addr_table
+        .keys()
+        .filter(|name| cluster_name.map_or(true, |cluster_name| name.as_str() == cluster_name))
+        .collect::<Vec<_>>();
+    cluster_names.sort();
+
+    let mut rows = Vec::new();
+    for cluster in cluster_names {
+        let mut broker_names = cluster_addr_table[cluster].iter().collect::<Vec<_>>();
+        broker_names.sort();
+        for broker_name in broker_names {
+            let Some(broker_data) = broker_addr_table.get(broker_name) else {
+                continue;
+            };
+            let mut broker_addrs = broker_data.broker_addrs().iter().collect::<Vec<_>>();
+            broker_addrs.sort();
+            for (broker_id, addr) in broker_addrs {
+                rows.push(ClusterRow {
+                    cluster_name: cluster.to_string(),
+                    broker_name: broker_name.to_string(),
+                    broker_id: *broker_id,
+                    addr: addr.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    rows
+}
+
+impl AdminSubCommand for ClusterListSubCommand {
+    type Row = ClusterRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let cluster_info = admin.examine_broker_cluster_info().await?;
+        let mut rows = cluster_rows(&cluster_info, self.cluster_name.as_deref());
+        for row in rows.iter_mut() {
+            // A broker that cannot be reached is still listed, just without runtime figures.
+            if let Ok(kv_table) = admin
+                .fetch_broker_runtime_stats(CheetahString::from(row.addr.as_str()))
+                .await
+            {
+                row.apply_runtime_stats(&kv_table.table);
+            }
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerKeyValueRow {
+    #[tabled(rename = "Broker Addr")]
+    pub broker_addr: String,
+    #[tabled(rename = "Key")]
+    pub key: String,
+    #[tabled(rename = "Value")]
+    pub value: String,
+}
+
+pub fn broker_key_value_rows(
+    broker_addr: &str,
+    table: &HashMap<CheetahString, CheetahString>,
+) -> Vec<BrokerKeyValueRow> {
+    let mut rows = table
+        .iter()
+        .map(|(key, value)| BrokerKeyValueRow {
+            broker_addr: broker_addr.to_string(),
+            key: key.to_string(),
+            value: value.to_string(),
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.key.cmp(&b.key));
+    rows
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BrokerStatusSubCommand {
+    #[command(flatten)]
+    pub target: BrokerTarget,
+}
+
+impl AdminSubCommand for BrokerStatusSubCommand {
+    type Row = BrokerKeyValueRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            let kv_table = admin
+                .fetch_broker_runtime_stats(broker_addr.clone())
+                .await?;
+            rows.extend(broker_key_value_rows(&broker_addr, &kv_table.table));
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct UpdateBrokerConfigSubCommand {
+    #[command(flatten)]
+    pub target: BrokerTarget,
+
+    #[arg(short = 'k', long = "key", help = "config key")]
+    pub key: String,
+
+    #[arg(short = 'v', long = "value", help = "config value")]
+    pub value: String,
+}
+
+impl AdminSubCommand for UpdateBrokerConfigSubCommand {
+    type Row = OperationRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let properties = HashMap::from([(
+            CheetahString::from(self.key.as_str()),
+            CheetahString::from(self.value.as_str()),
+        )]);
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            admin
+                .update_broker_config(broker_addr.clone(), properties.clone())
+                .await?;
+            rows.push(OperationRow::success(broker_addr));
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct GetBrokerConfigSubCommand {
+    #[command(flatten)]
+    pub target: BrokerTarget,
+}
+
+impl AdminSubCommand for GetBrokerConfigSubCommand {
+    type Row = BrokerKeyValueRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            let config = admin.get_broker_config(broker_addr.clone()).await?;
+            rows.extend(broker_key_value_rows(&broker_addr, &config));
+        }
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn cluster_info() -> ClusterInfo {
+        let mut broker_addr_table = HashMap::new();
+        broker_addr_table.insert(
+            CheetahString::from("broker-a"),
+            BrokerData::new(
+                CheetahString::from("ClusterA"),
+                CheetahString::from("broker-a"),
+                HashMap::from([
+                    (1, CheetahString::from("10.0.0.2:10911")),
+                    (0, CheetahString::from("10.0.0.1:10911")),
+                ]),
+                None,
+            ),
+        );
+        broker_addr_table.insert(
+            CheetahString::from("broker-b"),
+            BrokerData::new(
+                CheetahString::from("ClusterB"),
+                CheetahString::from("broker-b"),
+                HashMap::from([(0, CheetahString::from("10.0.1.1:10911"))]),
+                None,
+            ),
+        );
+        let cluster_addr_table = HashMap::from([
+            (
+                CheetahString::from("ClusterA"),
+                HashSet::from([CheetahString::from("broker-a")]),
+            ),
+            (
+                CheetahString::from("ClusterB"),
+                HashSet::from([CheetahString::from("broker-b")]),
+            ),
+        ]);
+        ClusterInfo::new(Some(broker_addr_table), Some(cluster_addr_table))
+    }
+
+    #[test]
+    fn cluster_rows_list_every_instance_in_order() {
+        let rows = cluster_rows(&cluster_info(), None);
+        let keys = rows
+            .iter()
+            .map(|row| (row.cluster_name.as_str(), row.broker_id, row.addr.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ("ClusterA", 0, "10.0.0.1:10911"),
+                ("ClusterA", 1, "10.0.0.2:10911"),
+                ("ClusterB", 0, "10.0.1.1:10911"),
+            ]
+        );
+    }
+
+    #[test]
+    fn cluster_rows_filter_by_cluster() {
+        let rows = cluster_rows(&cluster_info(), Some("ClusterB"));
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].broker_name, "broker-b");
+    }
+
+    #[test]
+    fn apply_runtime_stats_parses_tps_and_totals() {
+        let stats = [
+            ("brokerVersionDesc", "V5_3_1"),
+            ("putTps", "12.5 10.0 8.0"),
+            ("getTransferredTps", "3"),
+            ("msgPutTotalTodayMorning", "100"),
+            ("msgPutTotalTodayNow", "250"),
+            ("msgGetTotalTodayMorning", "40"),
+            ("msgGetTotalTodayNow", "90"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (CheetahString::from(key), CheetahString::from(value)))
+        .collect::<HashMap<_, _>>();
+        let mut row = ClusterRow::default();
+        row.apply_runtime_stats(&stats);
+        assert_eq!(row.version, "V5_3_1");
+        assert_eq!(row.in_tps, "12.50");
+        assert_eq!(row.out_tps, "3.00");
+        assert_eq!(row.in_total_today, 150);
+        assert_eq!(row.out_total_today, 50);
+    }
+
+    #[test]
+    fn broker_key_value_rows_are_sorted_by_key() {
+        let table = HashMap::from([
+            (CheetahString::from("b"), CheetahString::from("2")),
+            (CheetahString::from("a"), CheetahString::from("1")),
+        ]);
+        let rows = broker_key_value_rows("127.0.0.1:10911", &table);
+        assert_eq!(rows[0].key, "a");
+        assert_eq!(rows[1].value, "2");
+        assert_eq!(rows[1].broker_addr, "127.0.0.1:10911");
+    }
+}
@@ -0,0 +1,431 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use clap::ArgAction;
+use clap::Args;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::mix_all;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::admin::connection_rows;
+use crate::admin::format_timestamp;
+use crate::admin::parse_timestamp;
+use crate::admin::AdminSubCommand;
+use crate::admin::BrokerTarget;
+use crate::admin::ConnectionRow;
+use crate::admin::OperationRow;
+
+#[derive(Debug, Clone, Args)]
+pub struct UpdateSubGroupSubCommand {
+    #[arg(short = 'g', long = "groupName", help = "consumer group name")]
+    pub group_name: String,
+
+    #[command(flatten)]
+    pub target: BrokerTarget,
+
+    #[arg(short = 's', long = "consumeEnable", action = ArgAction::Set, help = "consume enable")]
+    pub consume_enable: Option<bool>,
+
+    #[arg(short = 'm', long = "consumeFromMinEnable", action = ArgAction::Set, help = "from min offset")]
+    pub consume_from_min_enable: Option<bool>,
+
+    #[arg(short = 'd', long = "consumeBroadcastEnable", action = ArgAction::Set, help = "broadcast")]
+    pub consume_broadcast_enable: Option<bool>,
+
+    #[arg(short = 'o', long = "consumeMessageOrderly", action = ArgAction::Set, help = "consume message orderly")]
+    pub consume_message_orderly: Option<bool>,
+
+    #[arg(short = 'q', long = "retryQueueNums", help = "retry queue nums")]
+    pub retry_queue_nums: Option<i32>,
+
+    #[arg(short = 'r', long = "retryMaxTimes", help = "retry max times")]
+    pub retry_max_times: Option<i32>,
+
+    #[arg(short = 'i', long = "brokerId", help = "consumer from which broker id")]
+    pub broker_id: Option<u64>,
+
+    #[arg(
+        short = 'w',
+        long = "whichBrokerWhenConsumeSlowly",
+        help = "which broker id when consume slowly"
+    )]
+    pub which_broker_when_consume_slowly: Option<u64>,
+
+    #[arg(short = 'a', long = "notifyConsumerIdsChanged", action = ArgAction::Set, help = "notify consumerId changed")]
+    pub notify_consumer_ids_changed_enable: Option<bool>,
+}
+
+impl UpdateSubGroupSubCommand {
+    fn subscription_group_config(&self) -> SubscriptionGroupConfig {
+        let mut config =
+            SubscriptionGroupConfig::new(CheetahString::from(self.group_name.as_str()));
+        if let Some(value) = self.consume_enable {
+            config.set_consume_enable(value);
+        }
+        if let Some(value) = self.consume_from_min_enable {
+            config.set_consume_from_min_enable(value);
+        }
+        if let Some(value) = self.consume_broadcast_enable {
+            config.set_consume_broadcast_enable(value);
+        }
+        if let Some(value) = self.consume_message_orderly {
+            config.set_consume_message_orderly(value);
+        }
+        if let Some(value) = self.retry_queue_nums {
+            config.set_retry_queue_nums(value);
+        }
+        if let Some(value) = self.retry_max_times {
+            config.set_retry_max_times(value);
+        }
+        if let Some(value) = self.broker_id {
+            config.set_broker_id(value);
+        }
+        if let Some(value) = self.which_broker_when_consume_slowly {
+            config.set_which_broker_when_consume_slowly(value);
+        }
+        if let Some(value) = self.notify_consumer_ids_changed_enable {
+            config.set_notify_consumer_ids_changed_enable(value);
+        }
+        config
+    }
+}
+
+impl AdminSubCommand for UpdateSubGroupSubCommand {
+    type Row = OperationRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let config = self.subscription_group_config();
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            admin
+                .create_and_update_subscription_group_config(broker_addr.clone(), config.clone())
+                .await?;
+            rows.push(OperationRow::success(broker_addr));
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DeleteSubGroupSubCommand {
+    #[arg(short = 'g', long = "groupName", help = "subscription group name")]
+    pub group_name: String,
+
+    #[command(flatten)]
+    pub target: BrokerTarget,
+
+    #[arg(short = 'r', long = "removeOffset", action = ArgAction::Set, default_value_t = false, help = "remove offset")]
+    pub remove_offset: bool,
+}
+
+impl AdminSubCommand for DeleteSubGroupSubCommand {
+    type Row = OperationRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let group_name = CheetahString::from(self.group_name.as_str());
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            admin
+                .delete_subscription_group(
+                    broker_addr.clone(),
+                    group_name.clone(),
+                    Some(self.remove_offset),
+                )
+                .await?;
+            rows.push(OperationRow::success(broker_addr));
+        }
+        if let Some(cluster_name) = &self.target.cluster_name {
+            // Like the Java tool, the retry and DLQ topics are cleaned up on a best effort basis.
+            for topic in [
+                mix_all::get_retry_topic(&self.group_name),
+                mix_all::get_dlq_topic(&self.group_name),
+            ] {
+                let _ = admin
+                    .delete_topic(
+                        CheetahString::from(topic),
+                        CheetahString::from(cluster_name.as_str()),
+                    )
+                    .await;
+            }
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConsumerProgressSubCommand {
+    #[arg(short = 'g', long = "groupName", help = "consumer group name")]
+    pub group_name: String,
+
+    #[arg(short = 't', long = "topicName", help = "topic name")]
+    pub topic: Option<String>,
+
+    #[arg(short = 'c', long = "cluster", help = "cluster name")]
+    pub cluster_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumerProgressRow {
+    #[tabled(rename = "Topic")]
+    pub topic: String,
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "QID")]
+    pub queue_id: i32,
+    #[tabled(rename = "Broker Offset")]
+    pub broker_offset: i64,
+    #[tabled(rename = "Consumer Offset")]
+    pub consumer_offset: i64,
+    #[tabled(rename = "Diff")]
+    pub diff: i64,
+    #[tabled(rename = "Last Time")]
+    pub last_time: String,
+}
+
+pub fn consumer_progress_rows(consume_stats: &ConsumeStats) -> Vec<ConsumerProgressRow> {
+    let offset_table = consume_stats.get_offset_table();
+    let mut message_queues = offset_table.keys().collect::<Vec<_>>();
+    message_queues.sort();
+    message_queues
+        .into_iter()
+        .map(|mq| {
+            let offset = &offset_table[mq];
+            ConsumerProgressRow {
+                topic: mq.get_topic().to_string(),
+                broker_name: mq.get_broker_name().to_string(),
+                queue_id: mq.get_queue_id(),
+                broker_offset: offset.get_broker_offset(),
+                consumer_offset: offset.get_consumer_offset(),
+                diff: offset.get_broker_offset() - offset.get_consumer_offset(),
+                last_time: format_timestamp(offset.get_last_timestamp()),
+            }
+        })
+        .collect()
+}
+
+impl AdminSubCommand for ConsumerProgressSubCommand {
+    type Row = ConsumerProgressRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let consume_stats = admin
+            .examine_consume_stats(
+                CheetahString::from(self.group_name.as_str()),
+                self.topic.as_deref().map(CheetahString::from),
+                self.cluster_name.as_deref().map(CheetahString::from),
+                None,
+                None,
+            )
+            .await?;
+        Ok(consumer_progress_rows(&consume_stats))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConsumerConnectionSubCommand {
+    #[arg(short = 'g', long = "consumerGroup", help = "consumer group name")]
+    pub consumer_group: String,
+}
+
+impl AdminSubCommand for ConsumerConnectionSubCommand {
+    type Row = ConnectionRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let consumer_connection = admin
+            .examine_consumer_connection_info(
+                CheetahString::from(self.consumer_group.as_str()),
+                None,
+            )
+            .await?;
+        Ok(connection_rows(
+            consumer_connection.get_connection_set().iter(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ProducerConnectionSubCommand {
+    #[arg(short = 'g', long = "producerGroup", help = "producer group name")]
+    pub producer_group: String,
+
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+}
+
+impl AdminSubCommand for ProducerConnectionSubCommand {
+    type Row = ConnectionRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let producer_connection = admin
+            .examine_producer_connection_info(
+                CheetahString::from(self.producer_group.as_str()),
+                CheetahString::from(self.topic.as_str()),
+            )
+            .await?;
+        Ok(connection_rows(producer_connection.connection_set.iter()))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ResetOffsetByTimeSubCommand {
+    #[arg(short = 'g', long = "group", help = "set the consumer group")]
+    pub group: String,
+
+    #[arg(short = 't', long = "topic", help = "set the topic")]
+    pub topic: String,
+
+    #[arg(
+        short = 's',
+        long = "timestamp",
+        help = "set the timestamp[now|currentTimeMillis|yyyy-MM-dd#HH:mm:ss:SSS]"
+    )]
+    pub timestamp: String,
+
+    #[arg(short = 'f', long = "force", action = ArgAction::Set, default_value_t = true, help = "set the force rollback by timestamp switch[true|false]")]
+    pub force: bool,
+
+    #[arg(short = 'c', long = "cluster", help = "cluster name")]
+    pub cluster_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetOffsetRow {
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "QID")]
+    pub queue_id: i32,
+    #[tabled(rename = "Offset")]
+    pub offset: u64,
+}
+
+pub fn reset_offset_rows(offset_table: &HashMap<MessageQueue, u64>) -> Vec<ResetOffsetRow> {
+    let mut message_queues = offset_table.keys().collect::<Vec<_>>();
+    message_queues.sort();
+    message_queues
+        .into_iter()
+        .map(|mq| ResetOffsetRow {
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            offset: offset_table[mq],
+        })
+        .collect()
+}
+
+impl AdminSubCommand for ResetOffsetByTimeSubCommand {
+    type Row = ResetOffsetRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let timestamp = parse_timestamp(&self.timestamp).ok_or_else(|| {
+            RocketmqError::IllegalArgument(format!("invalid timestamp {}", self.timestamp))
+        })?;
+        let offset_table = admin
+            .reset_offset_by_timestamp(
+                self.cluster_name.as_deref().map(CheetahString::from),
+                CheetahString::from(self.topic.as_str()),
+                CheetahString::from(self.group.as_str()),
+                timestamp,
+                self.force,
+            )
+            .await?;
+        Ok(reset_offset_rows(&offset_table))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
+
+    use super::*;
+
+    #[test]
+    fn update_sub_group_only_overrides_given_options() {
+        let command = UpdateSubGroupSubCommand {
+            group_name: "group".to_string(),
+            target: BrokerTarget {
+                broker_addr: None,
+                cluster_name: Some("DefaultCluster".to_string()),
+            },
+            consume_enable: Some(false),
+            consume_from_min_enable: None,
+            consume_broadcast_enable: None,
+            consume_message_orderly: Some(true),
+            retry_queue_nums: None,
+            retry_max_times: Some(3),
+            broker_id: None,
+            which_broker_when_consume_slowly: None,
+            notify_consumer_ids_changed_enable: None,
+        };
+        let config = command.subscription_group_config();
+        let default_config = SubscriptionGroupConfig::default();
+        assert_eq!(config.group_name(), "group");
+        assert!(!config.consume_enable());
+        assert!(config.consume_message_orderly());
+        assert_eq!(config.retry_max_times(), 3);
+        assert_eq!(config.retry_queue_nums(), default_config.retry_queue_nums());
+        assert_eq!(
+            config.consume_broadcast_enable(),
+            default_config.consume_broadcast_enable()
+        );
+    }
+
+    #[test]
+    fn consumer_progress_rows_compute_diff() {
+        let mut offset = OffsetWrapper::new();
+        offset.set_broker_offset(120);
+        offset.set_consumer_offset(100);
+        let mut stats = ConsumeStats::new();
+        stats.set_offset_table(HashMap::from([(
+            MessageQueue::from_parts("TopicTest", "broker-a", 2),
+            offset,
+        )]));
+        let rows = consumer_progress_rows(&stats);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].topic, "TopicTest");
+        assert_eq!(rows[0].queue_id, 2);
+        assert_eq!(rows[0].diff, 20);
+        assert_eq!(rows[0].last_time, "-");
+    }
+
+    #[test]
+    fn reset_offset_rows_are_sorted() {
+        let offset_table = HashMap::from([
+            (MessageQueue::from_parts("TopicTest", "broker-b", 0), 7),
+            (MessageQueue::from_parts("TopicTest", "broker-a", 1), 5),
+            (MessageQueue::from_parts("TopicTest", "broker-a", 0), 3),
+        ]);
+        let rows = reset_offset_rows(&offset_table);
+        let keys = rows
+            .iter()
+            .map(|row| (row.broker_name.as_str(), row.queue_id, row.offset))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![("broker-a", 0, 3), ("broker-a", 1, 5), ("broker-b", 0, 7)]
+        );
+    }
+}
@@ -0,0 +1,604 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use cheetah_string::CheetahString;
+use clap::Args;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_client_rust::consumer::default_lite_pull_consumer::DefaultLitePullConsumer;
+use rocketmq_client_rust::consumer::lite_pull_consumer::LitePullConsumer;
+use rocketmq_client_rust::producer::default_mq_producer::DefaultMQProducer;
+use rocketmq_client_rust::producer::mq_producer::MQProducer;
+use rocketmq_client_rust::producer::send_result::SendResult;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::admin::format_timestamp;
+use crate::admin::parse_timestamp;
+use crate::admin::AdminSubCommand;
+use crate::admin::CommandContext;
+
+const POLL_TIMEOUT_MILLIS: u64 = 1000;
+
+/// Number of consecutive empty polls after which a queue is considered drained.
+const MAX_IDLE_POLLS: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRow {
+    #[tabled(rename = "Message Id")]
+    pub msg_id: String,
+    #[tabled(rename = "Topic")]
+    pub topic: String,
+    #[tabled(rename = "Tags")]
+    pub tags: String,
+    #[tabled(rename = "Keys")]
+    pub keys: String,
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "QID")]
+    pub queue_id: i32,
+    #[tabled(rename = "Queue Offset")]
+    pub queue_offset: i64,
+    #[tabled(rename = "Born Host")]
+    pub born_host: String,
+    #[tabled(rename = "Store Host")]
+    pub store_host: String,
+    #[tabled(rename = "Born Time")]
+    pub born_time: String,
+    #[tabled(rename = "Store Time")]
+    pub store_time: String,
+    #[tabled(rename = "Reconsume Times")]
+    pub reconsume_times: i32,
+    #[tabled(rename = "Body")]
+    pub body: String,
+}
+
+impl From<&MessageExt> for MessageRow {
+    fn from(msg: &MessageExt) -> Self {
+        MessageRow {
+            msg_id: msg.msg_id().to_string(),
+            topic: msg.topic().to_string(),
+            tags: msg
+                .get_tags()
+                .map(|tags| tags.to_string())
+                .unwrap_or_default(),
+            keys: msg
+                .get_keys()
+                .map(|keys| keys.to_string())
+                .unwrap_or_default(),
+            broker_name: msg.broker_name().to_string(),
+            queue_id: msg.queue_id(),
+            queue_offset: msg.queue_offset(),
+            born_host: msg.born_host().to_string(),
+            store_host: msg.store_host().to_string(),
+            born_time: format_timestamp(msg.born_timestamp()),
+            store_time: format_timestamp(msg.store_timestamp()),
+            reconsume_times: msg.reconsume_times(),
+            body: msg
+                .body()
+                .map(|body| String::from_utf8_lossy(&body).into_owned())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct QueryMsgByIdSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+
+    #[arg(
+        short = 'i',
+        long = "msgId",
+        value_delimiter = ',',
+        help = "Message Id, separated by ','"
+    )]
+    pub msg_ids: Vec<String>,
+}
+
+impl AdminSubCommand for QueryMsgByIdSubCommand {
+    type Row = MessageRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let mut rows = Vec::with_capacity(self.msg_ids.len());
+        for msg_id in &self.msg_ids {
+            let message = admin
+                .view_message(
+                    CheetahString::from(self.topic.as_str()),
+                    CheetahString::from(msg_id.trim()),
+                )
+                .await?;
+            rows.push(MessageRow::from(&message));
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct QueryMsgByKeySubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+
+    #[arg(short = 'k', long = "msgKey", help = "Message Key")]
+    pub key: String,
+
+    #[arg(
+        short = 'c',
+        long = "maxNum",
+        default_value_t = 64,
+        help = "The maximum number of messages returned by the query"
+    )]
+    pub max_num: i32,
+
+    #[arg(
+        short = 'b',
+        long = "beginTimestamp",
+        default_value_t = 0,
+        help = "Begin timestamp(ms)"
+    )]
+    pub begin_timestamp: i64,
+
+    #[arg(short = 'e', long = "endTimestamp", default_value_t = i64::MAX, help = "End timestamp(ms)")]
+    pub end_timestamp: i64,
+}
+
+impl AdminSubCommand for QueryMsgByKeySubCommand {
+    type Row = MessageRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let query_result = admin
+            .query_message_by_key(
+                CheetahString::from(self.topic.as_str()),
+                CheetahString::from(self.key.as_str()),
+                self.max_num,
+                self.begin_timestamp,
+                self.end_timestamp,
+            )
+            .await?;
+        Ok(query_result
+            .message_list()
+            .iter()
+            .map(MessageRow::from)
+            .collect())
+    }
+}
+
+/// Where a pull based command starts reading each selected queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartPosition {
+    Begin,
+    Offset(i64),
+    Timestamp(u64),
+}
+
+/// Keeps the queues matching the optional broker name and queue id.
+pub fn select_message_queues(
+    message_queues: Vec<MessageQueue>,
+    broker_name: Option<&str>,
+    queue_id: Option<i32>,
+) -> Vec<MessageQueue> {
+    let mut message_queues = message_queues
+        .into_iter()
+        .filter(|mq| broker_name.map_or(true, |broker_name| mq.get_broker_name() == broker_name))
+        .filter(|mq| queue_id.map_or(true, |queue_id| mq.get_queue_id() == queue_id))
+        .collect::<Vec<_>>();
+    message_queues.sort();
+    message_queues
+}
+
+/// Reads up to `max_count` messages with a lite pull consumer in assign mode, without committing
+/// any offset.
+async fn pull_messages(
+    ctx: &CommandContext,
+    consumer_group: &str,
+    topic: &str,
+    broker_name: Option<&str>,
+    queue_id: Option<i32>,
+    start: StartPosition,
+    max_count: usize,
+) -> RocketMQResult<Vec<MessageExt>> {
+    let consumer = DefaultLitePullConsumer::builder()
+        .consumer_group(consumer_group)
+        .name_server_addr(ctx.namesrv_addr.as_str())
+        .auto_commit(false)
+        .build();
+    consumer.start().await?;
+    let result = pull_from_queues(&consumer, topic, broker_name, queue_id, start, max_count).await;
+    consumer.shutdown().await;
+    result
+}
+
+async fn pull_from_queues(
+    consumer: &DefaultLitePullConsumer,
+    topic: &str,
+    broker_name: Option<&str>,
+    queue_id: Option<i32>,
+    start: StartPosition,
+    max_count: usize,
+) -> RocketMQResult<Vec<MessageExt>> {
+    let message_queues = select_message_queues(
+        consumer.fetch_message_queues(topic).await?,
+        broker_name,
+        queue_id,
+    );
+    if message_queues.is_empty() {
+        return Err(RocketmqError::IllegalArgument(format!(
+            "no message queue of topic {} matches the given broker and queue",
+            topic
+        )));
+    }
+    consumer.assign(message_queues.clone()).await;
+    for mq in &message_queues {
+        match start {
+            StartPosition::Begin => consumer.seek_to_begin(mq).await?,
+            StartPosition::Offset(offset) => consumer.seek(mq, offset).await?,
+            StartPosition::Timestamp(timestamp) => {
+                let offset = consumer.offset_for_timestamp(mq, timestamp).await?;
+                consumer.seek(mq, offset).await?
+            }
+        }
+    }
+
+    let mut messages = Vec::new();
+    let mut idle_polls = 0;
+    while messages.len() < max_count && idle_polls < MAX_IDLE_POLLS {
+        let polled = consumer.poll_with_timeout(POLL_TIMEOUT_MILLIS).await;
+        if polled.is_empty() {
+            idle_polls += 1;
+            continue;
+        }
+        idle_polls = 0;
+        messages.extend(polled);
+    }
+    messages.truncate(max_count);
+    Ok(messages)
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct QueryMsgByOffsetSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+
+    #[arg(short = 'b', long = "brokerName", help = "Broker Name")]
+    pub broker_name: String,
+
+    #[arg(short = 'i', long = "queueId", help = "Queue Id")]
+    pub queue_id: i32,
+
+    #[arg(short = 'o', long = "offset", help = "Queue Offset")]
+    pub offset: i64,
+}
+
+impl QueryMsgByOffsetSubCommand {
+    pub async fn execute(&self, ctx: &CommandContext) -> RocketMQResult<()> {
+        let messages = pull_messages(
+            ctx,
+            mix_all::TOOLS_CONSUMER_GROUP,
+            &self.topic,
+            Some(&self.broker_name),
+            Some(self.queue_id),
+            StartPosition::Offset(self.offset),
+            1,
+        )
+        .await?;
+        let message = messages
+            .iter()
+            .find(|msg| msg.queue_offset() == self.offset)
+            .ok_or_else(|| {
+                RocketmqError::IllegalArgument(format!(
+                    "no message found at offset {} of {}:{}:{}",
+                    self.offset, self.topic, self.broker_name, self.queue_id
+                ))
+            })?;
+        ctx.print(&[MessageRow::from(message)]);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConsumeMessageSubCommand {
+    #[arg(short = 't', long = "topic", help = "Topic name")]
+    pub topic: String,
+
+    #[arg(short = 'b', long = "brokerName", help = "Broker name")]
+    pub broker_name: Option<String>,
+
+    #[arg(short = 'i', long = "queueId", help = "Queue Id")]
+    pub queue_id: Option<i32>,
+
+    #[arg(short = 'o', long = "offset", requires_all = ["broker_name", "queue_id"], help = "Queue Offset")]
+    pub offset: Option<i64>,
+
+    #[arg(short = 'g', long = "consumerGroup", default_value = mix_all::TOOLS_CONSUMER_GROUP, help = "Consumer group name")]
+    pub consumer_group: String,
+
+    #[arg(
+        short = 's',
+        long = "beginTimestamp",
+        conflicts_with = "offset",
+        help = "Begin timestamp[now|currentTimeMillis|yyyy-MM-dd#HH:mm:ss:SSS]"
+    )]
+    pub begin_timestamp: Option<String>,
+
+    #[arg(
+        short = 'c',
+        long = "MessageNumber",
+        default_value_t = 128,
+        help = "Number of message to be consumed"
+    )]
+    pub message_number: usize,
+}
+
+impl ConsumeMessageSubCommand {
+    fn start_position(&self) -> RocketMQResult<StartPosition> {
+        if let Some(offset) = self.offset {
+            return Ok(StartPosition::Offset(offset));
+        }
+        match &self.begin_timestamp {
+            Some(value) => parse_timestamp(value)
+                .map(StartPosition::Timestamp)
+                .ok_or_else(|| {
+                    RocketmqError::IllegalArgument(format!("invalid timestamp {}", value))
+                }),
+            None => Ok(StartPosition::Begin),
+        }
+    }
+
+    pub async fn execute(&self, ctx: &CommandContext) -> RocketMQResult<()> {
+        let messages = pull_messages(
+            ctx,
+            &self.consumer_group,
+            &self.topic,
+            self.broker_name.as_deref(),
+            self.queue_id,
+            self.start_position()?,
+            self.message_number,
+        )
+        .await?;
+        let rows = messages.iter().map(MessageRow::from).collect::<Vec<_>>();
+        ctx.print(&rows);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SendMessageSubCommand {
+    #[arg(short = 't', long = "topic", help = "Topic name")]
+    pub topic: String,
+
+    #[arg(
+        short = 'p',
+        long = "body",
+        help = "UTF-8 string format of the message body"
+    )]
+    pub body: String,
+
+    #[arg(short = 'k', long = "key", help = "Message keys")]
+    pub keys: Option<String>,
+
+    #[arg(short = 'c', long = "tags", help = "Message tags")]
+    pub tags: Option<String>,
+
+    #[arg(
+        short = 'b',
+        long = "broker",
+        requires = "queue_id",
+        help = "Send message to target broker"
+    )]
+    pub broker_name: Option<String>,
+
+    #[arg(
+        short = 'i',
+        long = "qid",
+        requires = "broker_name",
+        help = "Send message to target queue"
+    )]
+    pub queue_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendResultRow {
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "QID")]
+    pub queue_id: i32,
+    #[tabled(rename = "Send Result")]
+    pub send_status: String,
+    #[tabled(rename = "MsgId")]
+    pub msg_id: String,
+    #[tabled(rename = "Queue Offset")]
+    pub queue_offset: u64,
+}
+
+impl From<&SendResult> for SendResultRow {
+    fn from(result: &SendResult) -> Self {
+        SendResultRow {
+            broker_name: result
+                .message_queue
+                .as_ref()
+                .map(|mq| mq.get_broker_name().to_string())
+                .unwrap_or_default(),
+            queue_id: result
+                .message_queue
+                .as_ref()
+                .map(|mq| mq.get_queue_id())
+                .unwrap_or_default(),
+            send_status: format!("{:?}", result.send_status),
+            msg_id: result
+                .msg_id
+                .as_ref()
+                .map(|msg_id| msg_id.to_string())
+                .unwrap_or_default(),
+            queue_offset: result.queue_offset,
+        }
+    }
+}
+
+impl SendMessageSubCommand {
+    fn message(&self) -> Message {
+        let mut message = Message::new(self.topic.as_str(), self.body.as_bytes());
+        if let Some(tags) = &self.tags {
+            message.set_tags(CheetahString::from(tags.as_str()));
+        }
+        if let Some(keys) = &self.keys {
+            message.set_keys(CheetahString::from(keys.as_str()));
+        }
+        message
+    }
+
+    pub async fn execute(&self, ctx: &CommandContext) -> RocketMQResult<()> {
+        let mut producer = DefaultMQProducer::builder()
+            .producer_group(get_current_millis().to_string())
+            .name_server_addr(ctx.namesrv_addr.as_str())
+            .build();
+        producer.start().await?;
+        let message = self.message();
+        let result = match (&self.broker_name, self.queue_id) {
+            (Some(broker_name), Some(queue_id)) => {
+                let mq =
+                    MessageQueue::from_parts(self.topic.as_str(), broker_name.as_str(), queue_id);
+                producer.send_to_queue(message, mq).await
+            }
+            _ => producer.send(message).await,
+        };
+        producer.shutdown().await;
+        ctx.print(&[SendResultRow::from(&result?)]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use rocketmq_client_rust::producer::send_status::SendStatus;
+
+    use super::*;
+
+    #[test]
+    fn message_row_from_message_ext() {
+        let mut msg = MessageExt {
+            message: Message::with_keys("TopicTest", "TagA", "key-1", b"hello"),
+            ..Default::default()
+        };
+        msg.set_msg_id(CheetahString::from("7F0000010000000000000000"));
+        msg.set_broker_name(CheetahString::from("broker-a"));
+        msg.set_queue_id(3);
+        msg.set_queue_offset(42);
+        msg.set_store_host("127.0.0.1:10911".parse::<SocketAddr>().unwrap());
+
+        let row = MessageRow::from(&msg);
+        assert_eq!(row.msg_id, "7F0000010000000000000000");
+        assert_eq!(row.topic, "TopicTest");
+        assert_eq!(row.tags, "TagA");
+        assert_eq!(row.keys, "key-1");
+        assert_eq!(row.broker_name, "broker-a");
+        assert_eq!(row.queue_id, 3);
+        assert_eq!(row.queue_offset, 42);
+        assert_eq!(row.store_host, "127.0.0.1:10911");
+        assert_eq!(row.body, "hello");
+    }
+
+    #[test]
+    fn select_message_queues_filters_by_broker_and_queue() {
+        let message_queues = vec![
+            MessageQueue::from_parts("TopicTest", "broker-b", 0),
+            MessageQueue::from_parts("TopicTest", "broker-a", 1),
+            MessageQueue::from_parts("TopicTest", "broker-a", 0),
+        ];
+        assert_eq!(
+            select_message_queues(message_queues.clone(), None, None),
+            vec![
+                MessageQueue::from_parts("TopicTest", "broker-a", 0),
+                MessageQueue::from_parts("TopicTest", "broker-a", 1),
+                MessageQueue::from_parts("TopicTest", "broker-b", 0),
+            ]
+        );
+        assert_eq!(
+            select_message_queues(message_queues.clone(), Some("broker-a"), Some(1)),
+            vec![MessageQueue::from_parts("TopicTest", "broker-a", 1)]
+        );
+        assert!(select_message_queues(message_queues, Some("broker-c"), None).is_empty());
+    }
+
+    #[test]
+    fn consume_message_start_position() {
+        let mut command = ConsumeMessageSubCommand {
+            topic: "TopicTest".to_string(),
+            broker_name: None,
+            queue_id: None,
+            offset: None,
+            consumer_group: mix_all::TOOLS_CONSUMER_GROUP.to_string(),
+            begin_timestamp: None,
+            message_number: 128,
+        };
+        assert_eq!(command.start_position().unwrap(), StartPosition::Begin);
+        command.begin_timestamp = Some("1700000000000".to_string());
+        assert_eq!(
+            command.start_position().unwrap(),
+            StartPosition::Timestamp(1_700_000_000_000)
+        );
+        command.begin_timestamp = Some("not a time".to_string());
+        assert!(command.start_position().is_err());
+        command.offset = Some(10);
+        assert_eq!(command.start_position().unwrap(), StartPosition::Offset(10));
+    }
+
+    #[test]
+    fn send_message_builds_message_with_tags_and_keys() {
+        let command = SendMessageSubCommand {
+            topic: "TopicTest".to_string(),
+            body: "hello".to_string(),
+            keys: Some("key-1".to_string()),
+            tags: Some("TagA".to_string()),
+            broker_name: None,
+            queue_id: None,
+        };
+        let message = command.message();
+        assert_eq!(message.topic().as_str(), "TopicTest");
+        assert_eq!(message.get_tags().as_deref(), Some("TagA"));
+        assert_eq!(message.get_keys().as_deref(), Some("key-1"));
+        assert_eq!(message.body().unwrap().as_ref(), b"hello");
+    }
+
+    #[test]
+    fn send_result_row_from_send_result() {
+        let result = SendResult {
+            send_status: SendStatus::SendOk,
+            msg_id: Some(CheetahString::from("MSG-1")),
+            message_queue: Some(MessageQueue::from_parts("TopicTest", "broker-a", 2)),
+            queue_offset: 9,
+            ..Default::default()
+        };
+        let row = SendResultRow::from(&result);
+        assert_eq!(row.broker_name, "broker-a");
+        assert_eq!(row.queue_id, 2);
+        assert_eq!(row.send_status, "SendOk");
+        assert_eq!(row.msg_id, "MSG-1");
+        assert_eq!(row.queue_offset, 9);
+    }
+}
@@ -0,0 +1,385 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use cheetah_string::CheetahString;
+use clap::ArgAction;
+use clap::Args;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_common::common::attribute::attribute_parser::AttributeParser;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use serde::Serialize;
+use tabled::Tabled;
+
+use crate::admin::format_timestamp;
+use crate::admin::AdminSubCommand;
+use crate::admin::BrokerTarget;
+use crate::admin::OperationRow;
+
+#[derive(Debug, Clone, Args)]
+pub struct UpdateTopicSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+
+    #[command(flatten)]
+    pub target: BrokerTarget,
+
+    #[arg(
+        short = 'r',
+        long = "readQueueNums",
+        default_value_t = 8,
+        help = "set read queue nums"
+    )]
+    pub read_queue_nums: u32,
+
+    #[arg(
+        short = 'w',
+        long = "writeQueueNums",
+        default_value_t = 8,
+        help = "set write queue nums"
+    )]
+    pub write_queue_nums: u32,
+
+    #[arg(short = 'p', long = "perm", default_value_t = PermName::PERM_READ | PermName::PERM_WRITE, help = "set topic's permission(2|4|6), intro[2:W 4:R; 6:RW]")]
+    pub perm: u32,
+
+    #[arg(short = 'o', long = "order", action = ArgAction::Set, default_value_t = false, help = "set topic's order(true|false)")]
+    pub order: bool,
+
+    #[arg(short = 'a', long = "attributes", help = "attribute(+a=b,+c=d,-e)")]
+    pub attributes: Option<String>,
+}
+
+impl UpdateTopicSubCommand {
+    fn topic_config(&self) -> RocketMQResult<TopicConfig> {
+        let mut topic_config = TopicConfig::with_queues(
+            self.topic.as_str(),
+            self.read_queue_nums,
+            self.write_queue_nums,
+        );
+        topic_config.perm = self.perm;
+        topic_config.order = self.order;
+        if let Some(attributes) = &self.attributes {
+            topic_config.attributes = AttributeParser::parse_to_map(attributes)
+                .map_err(RocketmqError::IllegalArgument)?
+                .into_iter()
+                .map(|(key, value)| (CheetahString::from(key), CheetahString::from(value)))
+                .collect();
+        }
+        Ok(topic_config)
+    }
+}
+
+impl AdminSubCommand for UpdateTopicSubCommand {
+    type Row = OperationRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let topic_config = self.topic_config()?;
+        let mut rows = Vec::new();
+        for broker_addr in self.target.resolve(admin).await? {
+            admin
+                .create_and_update_topic_config(broker_addr.clone(), topic_config.clone())
+                .await?;
+            rows.push(OperationRow::success(broker_addr));
+        }
+        Ok(rows)
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DeleteTopicSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+
+    #[arg(
+        short = 'c',
+        long = "clusterName",
+        help = "delete topic from which cluster"
+    )]
+    pub cluster_name: String,
+}
+
+impl AdminSubCommand for DeleteTopicSubCommand {
+    type Row = OperationRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        admin
+            .delete_topic(
+                CheetahString::from(self.topic.as_str()),
+                CheetahString::from(self.cluster_name.as_str()),
+            )
+            .await?;
+        Ok(vec![OperationRow::success(self.cluster_name.as_str())])
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TopicListSubCommand {}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicRow {
+    #[tabled(rename = "Topic")]
+    pub topic: String,
+}
+
+pub fn topic_rows(topic_list: &TopicList) -> Vec<TopicRow> {
+    let mut rows = topic_list
+        .topic_list
+        .iter()
+        .map(|topic| TopicRow {
+            topic: topic.to_string(),
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.topic.cmp(&b.topic));
+    rows
+}
+
+impl AdminSubCommand for TopicListSubCommand {
+    type Row = TopicRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        Ok(topic_rows(&admin.fetch_all_topic_list().await?))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TopicRouteSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicRouteRow {
+    #[tabled(rename = "Cluster")]
+    pub cluster: String,
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "Broker Addrs")]
+    pub broker_addrs: String,
+    #[tabled(rename = "Read Queues")]
+    pub read_queue_nums: u32,
+    #[tabled(rename = "Write Queues")]
+    pub write_queue_nums: u32,
+    #[tabled(rename = "Perm")]
+    pub perm: String,
+}
+
+pub fn topic_route_rows(route_data: &TopicRouteData) -> Vec<TopicRouteRow> {
+    let mut rows = route_data
+        .broker_datas
+        .iter()
+        .map(|broker_data| {
+            let mut broker_addrs = broker_data.broker_addrs().iter().collect::<Vec<_>>();
+            broker_addrs.sort();
+            let queue_data = route_data
+                .queue_datas
+                .iter()
+                .find(|queue_data| queue_data.broker_name() == broker_data.broker_name());
+            TopicRouteRow {
+                cluster: broker_data.cluster().to_string(),
+                broker_name: broker_data.broker_name().to_string(),
+                broker_addrs: broker_addrs
+                    .into_iter()
+                    .map(|(broker_id, addr)| format!("{}={}", broker_id, addr))
+                    .collect::<Vec<_>>()
+                    .join(","),
+                read_queue_nums: queue_data.map(|q| q.read_queue_nums()).unwrap_or_default(),
+                write_queue_nums: queue_data.map(|q| q.write_queue_nums()).unwrap_or_default(),
+                perm: queue_data
+                    .map(|q| PermName::perm2string(q.perm()))
+                    .unwrap_or_default(),
+            }
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| a.broker_name.cmp(&b.broker_name));
+    rows
+}
+
+impl AdminSubCommand for TopicRouteSubCommand {
+    type Row = TopicRouteRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let route_data = admin
+            .examine_topic_route_info(CheetahString::from(self.topic.as_str()))
+            .await?
+            .ok_or_else(|| {
+                RocketmqError::IllegalArgument(format!("No topic route info for {}", self.topic))
+            })?;
+        Ok(topic_route_rows(&route_data))
+    }
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct TopicStatusSubCommand {
+    #[arg(short = 't', long = "topic", help = "topic name")]
+    pub topic: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Tabled, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicStatusRow {
+    #[tabled(rename = "Broker Name")]
+    pub broker_name: String,
+    #[tabled(rename = "QID")]
+    pub queue_id: i32,
+    #[tabled(rename = "Min Offset")]
+    pub min_offset: i64,
+    #[tabled(rename = "Max Offset")]
+    pub max_offset: i64,
+    #[tabled(rename = "Last Updated")]
+    pub last_updated: String,
+}
+
+pub fn topic_status_rows(stats: &TopicStatsTable) -> Vec<TopicStatusRow> {
+    let mut rows = stats
+        .get_offset_table()
+        .iter()
+        .map(|(mq, offset)| TopicStatusRow {
+            broker_name: mq.get_broker_name().to_string(),
+            queue_id: mq.get_queue_id(),
+            min_offset: offset.get_min_offset(),
+            max_offset: offset.get_max_offset(),
+            last_updated: format_timestamp(offset.get_last_update_timestamp()),
+        })
+        .collect::<Vec<_>>();
+    rows.sort_by(|a, b| {
+        a.broker_name
+            .cmp(&b.broker_name)
+            .then(a.queue_id.cmp(&b.queue_id))
+    });
+    rows
+}
+
+impl AdminSubCommand for TopicStatusSubCommand {
+    type Row = TopicStatusRow;
+
+    async fn run(&self, admin: &DefaultMQAdminExt) -> RocketMQResult<Vec<Self::Row>> {
+        let stats = admin
+            .examine_topic_stats(CheetahString::from(self.topic.as_str()), None)
+            .await?;
+        Ok(topic_status_rows(&stats))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_common::common::message::message_queue::MessageQueue;
+    use rocketmq_remoting::protocol::admin::topic_offset::TopicOffset;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+    use rocketmq_remoting::protocol::route::route_data_view::QueueData;
+
+    use super::*;
+
+    #[test]
+    fn update_topic_builds_topic_config() {
+        let command = UpdateTopicSubCommand {
+            topic: "TopicTest".to_string(),
+            target: BrokerTarget {
+                broker_addr: Some("127.0.0.1:10911".to_string()),
+                cluster_name: None,
+            },
+            read_queue_nums: 4,
+            write_queue_nums: 2,
+            perm: PermName::PERM_READ,
+            order: true,
+            attributes: Some("+message.type=NORMAL".to_string()),
+        };
+        let topic_config = command.topic_config().unwrap();
+        assert_eq!(topic_config.topic_name.as_deref(), Some("TopicTest"));
+        assert_eq!(topic_config.read_queue_nums, 4);
+        assert_eq!(topic_config.write_queue_nums, 2);
+        assert_eq!(topic_config.perm, PermName::PERM_READ);
+        assert!(topic_config.order);
+        assert_eq!(
+            topic_config
+                .attributes
+                .get("+message.type")
+                .map(|value| value.as_str()),
+            Some("NORMAL")
+        );
+    }
+
+    #[test]
+    fn topic_rows_are_sorted() {
+        let topic_list = TopicList {
+            topic_list: vec![CheetahString::from("b"), CheetahString::from("a")],
+            broker_addr: None,
+        };
+        let rows = topic_rows(&topic_list);
+        assert_eq!(rows[0].topic, "a");
+        assert_eq!(rows[1].topic, "b");
+    }
+
+    #[test]
+    fn topic_route_rows_join_brokers_and_queues() {
+        let mut route_data = TopicRouteData::new();
+        route_data.broker_datas.push(BrokerData::new(
+            CheetahString::from("DefaultCluster"),
+            CheetahString::from("broker-a"),
+            HashMap::from([
+                (1, CheetahString::from("10.0.0.2:10911")),
+                (0, CheetahString::from("10.0.0.1:10911")),
+            ]),
+            None,
+        ));
+        route_data.queue_datas.push(QueueData::new(
+            CheetahString::from("broker-a"),
+            8,
+            4,
+            PermName::PERM_READ | PermName::PERM_WRITE,
+            0,
+        ));
+        let rows = topic_route_rows(&route_data);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].cluster, "DefaultCluster");
+        assert_eq!(rows[0].broker_addrs, "0=10.0.0.1:10911,1=10.0.0.2:10911");
+        assert_eq!(rows[0].read_queue_nums, 8);
+        assert_eq!(rows[0].write_queue_nums, 4);
+        assert_eq!(rows[0].perm, "RW-");
+    }
+
+    #[test]
+    fn topic_status_rows_are_sorted_by_queue() {
+        let mut offset_table = HashMap::new();
+        for queue_id in [1, 0] {
+            let mut offset = TopicOffset::new();
+            offset.set_min_offset(queue_id as i64);
+            offset.set_max_offset(100 + queue_id as i64);
+            offset_table.insert(
+                MessageQueue::from_parts("TopicTest", "broker-a", queue_id),
+                offset,
+            );
+        }
+        let mut stats = TopicStatsTable::new();
+        stats.set_offset_table(offset_table);
+        let rows = topic_status_rows(&stats);
+        assert_eq!(rows[0].queue_id, 0);
+        assert_eq!(rows[1].queue_id, 1);
+        assert_eq!(rows[1].max_offset, 101);
+        assert_eq!(rows[1].last_updated, "-");
+    }
+}
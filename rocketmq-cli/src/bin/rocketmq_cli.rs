@@ -15,15 +15,15 @@
  * limitations under the License.
  */
 use clap::Parser;
-use rocketmq_cli::command_line::Commands;
 use rocketmq_cli::command_line::RootCli;
-use rocketmq_cli::content_show::print_content;
+use rocketmq_rust::rocketmq;
 
-fn main() {
+#[rocketmq::main]
+async fn main() {
     let cli = RootCli::parse();
-    match cli.command {
-        Commands::ReadMessageLog { config, from, to } => {
-            print_content(from, to, config);
-        }
+    let ctx = cli.context();
+    if let Err(error) = cli.command.execute(&ctx).await {
+        eprintln!("{} command failed: {}", env!("CARGO_BIN_NAME"), error);
+        std::process::exit(1);
     }
 }
@@ -18,14 +18,65 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use clap::Subcommand;
+use rocketmq_error::RocketMQResult;
+
+use crate::admin::broker_commands::BrokerStatusSubCommand;
+use crate::admin::broker_commands::ClusterListSubCommand;
+use crate::admin::broker_commands::GetBrokerConfigSubCommand;
+use crate::admin::broker_commands::UpdateBrokerConfigSubCommand;
+use crate::admin::consumer_commands::ConsumerConnectionSubCommand;
+use crate::admin::consumer_commands::ConsumerProgressSubCommand;
+use crate::admin::consumer_commands::DeleteSubGroupSubCommand;
+use crate::admin::consumer_commands::ProducerConnectionSubCommand;
+use crate::admin::consumer_commands::ResetOffsetByTimeSubCommand;
+use crate::admin::consumer_commands::UpdateSubGroupSubCommand;
+use crate::admin::execute_admin_command;
+use crate::admin::message_commands::ConsumeMessageSubCommand;
+use crate::admin::message_commands::QueryMsgByIdSubCommand;
+use crate::admin::message_commands::QueryMsgByKeySubCommand;
+use crate::admin::message_commands::QueryMsgByOffsetSubCommand;
+use crate::admin::message_commands::SendMessageSubCommand;
+use crate::admin::topic_commands::DeleteTopicSubCommand;
+use crate::admin::topic_commands::TopicListSubCommand;
+use crate::admin::topic_commands::TopicRouteSubCommand;
+use crate::admin::topic_commands::TopicStatusSubCommand;
+use crate::admin::topic_commands::UpdateTopicSubCommand;
+use crate::admin::CommandContext;
+use crate::content_show::print_content;
+use crate::output::OutputFormat;
 
 #[derive(Parser, Debug)]
 #[command(author = "mxsm", version = "0.2.0", about = "RocketMQ CLI(Rust)")]
 pub struct RootCli {
+    #[arg(
+        short = 'n',
+        long = "namesrvAddr",
+        global = true,
+        env = "NAMESRV_ADDR",
+        default_value = "127.0.0.1:9876",
+        help = "Name server address list, eg: '192.168.0.1:9876;192.168.0.2:9876'"
+    )]
+    pub namesrv_addr: String,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "Output format of the command result"
+    )]
+    pub output: OutputFormat,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
+impl RootCli {
+    pub fn context(&self) -> CommandContext {
+        CommandContext::new(self.namesrv_addr.as_str(), self.output)
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     #[command(
@@ -64,4 +115,261 @@ pub enum Commands {
         )]
         to: Option<u32>,
     },
+    #[command(name = "updateTopic", about = "Update or create topic")]
+    UpdateTopic(UpdateTopicSubCommand),
+
+    #[command(
+        name = "deleteTopic",
+        about = "Delete topic from broker and NameServer"
+    )]
+    DeleteTopic(DeleteTopicSubCommand),
+
+    #[command(name = "topicList", about = "Fetch all topic list from name server")]
+    TopicList(TopicListSubCommand),
+
+    #[command(name = "topicRoute", about = "Examine topic route info")]
+    TopicRoute(TopicRouteSubCommand),
+
+    #[command(name = "topicStatus", about = "Examine topic Status info")]
+    TopicStatus(TopicStatusSubCommand),
+
+    #[command(name = "updateSubGroup", about = "Update or create subscription group")]
+    UpdateSubGroup(UpdateSubGroupSubCommand),
+
+    #[command(
+        name = "deleteSubGroup",
+        about = "Delete subscription group from broker"
+    )]
+    DeleteSubGroup(DeleteSubGroupSubCommand),
+
+    #[command(name = "consumerProgress", about = "Query consumer's progress, speed")]
+    ConsumerProgress(ConsumerProgressSubCommand),
+
+    #[command(
+        name = "consumerConnection",
+        about = "Query consumer's socket connection and client version"
+    )]
+    ConsumerConnection(ConsumerConnectionSubCommand),
+
+    #[command(
+        name = "producerConnection",
+        about = "Query producer's socket connection and client version"
+    )]
+    ProducerConnection(ProducerConnectionSubCommand),
+
+    #[command(name = "clusterList", about = "List cluster infos")]
+    ClusterList(ClusterListSubCommand),
+
+    #[command(name = "brokerStatus", about = "Fetch broker runtime status data")]
+    BrokerStatus(BrokerStatusSubCommand),
+
+    #[command(name = "updateBrokerConfig", about = "Update broker's config")]
+    UpdateBrokerConfig(UpdateBrokerConfigSubCommand),
+
+    #[command(
+        name = "getBrokerConfig",
+        about = "Get broker config by cluster or special broker"
+    )]
+    GetBrokerConfig(GetBrokerConfigSubCommand),
+
+    #[command(
+        name = "resetOffsetByTime",
+        about = "Reset consumer offset by timestamp"
+    )]
+    ResetOffsetByTime(ResetOffsetByTimeSubCommand),
+
+    #[command(name = "queryMsgById", about = "Query Message by Id")]
+    QueryMsgById(QueryMsgByIdSubCommand),
+
+    #[command(name = "queryMsgByKey", about = "Query Message by Key")]
+    QueryMsgByKey(QueryMsgByKeySubCommand),
+
+    #[command(name = "queryMsgByOffset", about = "Query Message by offset")]
+    QueryMsgByOffset(QueryMsgByOffsetSubCommand),
+
+    #[command(name = "sendMessage", about = "Send a message")]
+    SendMessage(SendMessageSubCommand),
+
+    #[command(name = "consumeMessage", about = "Consume message")]
+    ConsumeMessage(ConsumeMessageSubCommand),
+}
+
+impl Commands {
+    pub async fn execute(self, ctx: &CommandContext) -> RocketMQResult<()> {
+        match self {
+            Commands::ReadMessageLog { config, from, to } => {
+                print_content(from, to, config);
+                Ok(())
+            }
+            Commands::UpdateTopic(command) => execute_admin_command(&command, ctx).await,
+            Commands::DeleteTopic(command) => execute_admin_command(&command, ctx).await,
+            Commands::TopicList(command) => execute_admin_command(&command, ctx).await,
+            Commands::TopicRoute(command) => execute_admin_command(&command, ctx).await,
+            Commands::TopicStatus(command) => execute_admin_command(&command, ctx).await,
+            Commands::UpdateSubGroup(command) => execute_admin_command(&command, ctx).await,
+            Commands::DeleteSubGroup(command) => execute_admin_command(&command, ctx).await,
+            Commands::ConsumerProgress(command) => execute_admin_command(&command, ctx).await,
+            Commands::ConsumerConnection(command) => execute_admin_command(&command, ctx).await,
+            Commands::ProducerConnection(command) => execute_admin_command(&command, ctx).await,
+            Commands::ClusterList(command) => execute_admin_command(&command, ctx).await,
+            Commands::BrokerStatus(command) => execute_admin_command(&command, ctx).await,
+            Commands::UpdateBrokerConfig(command) => execute_admin_command(&command, ctx).await,
+            Commands::GetBrokerConfig(command) => execute_admin_command(&command, ctx).await,
+            Commands::ResetOffsetByTime(command) => execute_admin_command(&command, ctx).await,
+            Commands::QueryMsgById(command) => execute_admin_command(&command, ctx).await,
+            Commands::QueryMsgByKey(command) => execute_admin_command(&command, ctx).await,
+            Commands::QueryMsgByOffset(command) => command.execute(ctx).await,
+            Commands::SendMessage(command) => command.execute(ctx).await,
+            Commands::ConsumeMessage(command) => command.execute(ctx).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn root_cli_definition_is_valid() {
+        RootCli::command().debug_assert();
+    }
+
+    #[test]
+    fn parse_global_options_after_sub_command() {
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "topicList",
+            "-n",
+            "10.0.0.1:9876",
+            "--output",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(cli.namesrv_addr, "10.0.0.1:9876");
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert!(matches!(cli.command, Commands::TopicList(_)));
+    }
+
+    #[test]
+    fn parse_update_topic_with_defaults() {
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "updateTopic",
+            "-t",
+            "TopicTest",
+            "-c",
+            "DefaultCluster",
+        ])
+        .unwrap();
+        let Commands::UpdateTopic(command) = cli.command else {
+            panic!("expected updateTopic");
+        };
+        assert_eq!(command.topic, "TopicTest");
+        assert_eq!(
+            command.target.cluster_name.as_deref(),
+            Some("DefaultCluster")
+        );
+        assert_eq!(command.read_queue_nums, 8);
+        assert_eq!(command.write_queue_nums, 8);
+        assert_eq!(command.perm, 6);
+        assert!(!command.order);
+        assert_eq!(cli.output, OutputFormat::Table);
+    }
+
+    #[test]
+    fn broker_target_requires_exactly_one_of_broker_or_cluster() {
+        assert!(RootCli::try_parse_from(["rocketmq-cli-rust", "getBrokerConfig"]).is_err());
+        assert!(RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "getBrokerConfig",
+            "-b",
+            "127.0.0.1:10911",
+            "-c",
+            "DefaultCluster",
+        ])
+        .is_err());
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "getBrokerConfig",
+            "-b",
+            "127.0.0.1:10911",
+        ])
+        .unwrap();
+        let Commands::GetBrokerConfig(command) = cli.command else {
+            panic!("expected getBrokerConfig");
+        };
+        assert_eq!(
+            command.target.broker_addr.as_deref(),
+            Some("127.0.0.1:10911")
+        );
+    }
+
+    #[test]
+    fn parse_reset_offset_by_time() {
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "resetOffsetByTime",
+            "-g",
+            "group",
+            "-t",
+            "TopicTest",
+            "-s",
+            "now",
+            "-f",
+            "false",
+        ])
+        .unwrap();
+        let Commands::ResetOffsetByTime(command) = cli.command else {
+            panic!("expected resetOffsetByTime");
+        };
+        assert_eq!(command.timestamp, "now");
+        assert!(!command.force);
+        assert!(command.cluster_name.is_none());
+    }
+
+    #[test]
+    fn parse_query_msg_by_id_splits_ids() {
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "queryMsgById",
+            "-t",
+            "TopicTest",
+            "-i",
+            "id1,id2",
+        ])
+        .unwrap();
+        let Commands::QueryMsgById(command) = cli.command else {
+            panic!("expected queryMsgById");
+        };
+        assert_eq!(command.msg_ids, vec!["id1".to_string(), "id2".to_string()]);
+    }
+
+    #[test]
+    fn send_message_requires_broker_and_queue_together() {
+        assert!(RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "sendMessage",
+            "-t",
+            "TopicTest",
+            "-p",
+            "hello",
+            "-b",
+            "broker-a",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn read_message_log_still_parses() {
+        let cli = RootCli::try_parse_from([
+            "rocketmq-cli-rust",
+            "read-message-log",
+            "-c",
+            "/tmp/00000000000000000000",
+        ])
+        .unwrap();
+        assert!(matches!(cli.command, Commands::ReadMessageLog { .. }));
+    }
 }
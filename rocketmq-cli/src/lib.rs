@@ -15,5 +15,7 @@
  * limitations under the License.
  */
 
+pub mod admin;
 pub mod command_line;
 pub mod content_show;
+pub mod output;
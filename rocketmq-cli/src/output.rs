@@ -0,0 +1,101 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Rendering of command results, either as a plain table or as pretty printed JSON.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use tabled::Table;
+use tabled::Tabled;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+/// Renders `rows` in the requested format.
+pub fn render<T>(rows: &[T], format: OutputFormat) -> String
+where
+    T: Tabled + Serialize,
+{
+    match format {
+        OutputFormat::Table => Table::new(rows).to_string(),
+        OutputFormat::Json => serde_json::to_string_pretty(rows)
+            .unwrap_or_else(|error| format!("{{\"error\": \"{}\"}}", error)),
+    }
+}
+
+pub fn print_rows<T>(rows: &[T], format: OutputFormat)
+where
+    T: Tabled + Serialize,
+{
+    println!("{}", render(rows, format));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Tabled, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Row {
+        #[tabled(rename = "Broker Name")]
+        broker_name: String,
+        #[tabled(rename = "Queue Id")]
+        queue_id: i32,
+    }
+
+    fn rows() -> Vec<Row> {
+        vec![
+            Row {
+                broker_name: "broker-a".to_string(),
+                queue_id: 0,
+            },
+            Row {
+                broker_name: "broker-b".to_string(),
+                queue_id: 3,
+            },
+        ]
+    }
+
+    #[test]
+    fn render_table_contains_header_and_cells() {
+        let output = render(&rows(), OutputFormat::Table);
+        assert!(output.contains("Broker Name"));
+        assert!(output.contains("Queue Id"));
+        assert!(output.contains("broker-a"));
+        assert!(output.contains("broker-b"));
+    }
+
+    #[test]
+    fn render_json_is_an_array_of_camel_case_objects() {
+        let output = render(&rows(), OutputFormat::Json);
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let array = value.as_array().unwrap();
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[1]["brokerName"], "broker-b");
+        assert_eq!(array[1]["queueId"], 3);
+    }
+
+    #[test]
+    fn render_json_of_no_rows_is_empty_array() {
+        let output = render::<Row>(&[], OutputFormat::Json);
+        assert_eq!(output, "[]");
+    }
+}
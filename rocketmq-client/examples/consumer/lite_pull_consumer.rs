@@ -0,0 +1,51 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use rocketmq_client_rust::consumer::default_lite_pull_consumer::DefaultLitePullConsumer;
+use rocketmq_client_rust::consumer::lite_pull_consumer::LitePullConsumer;
+use rocketmq_error::RocketMQResult;
+use rocketmq_rust::rocketmq;
+use tracing::info;
+
+pub const CONSUMER_GROUP: &str = "please_rename_unique_group_name_5";
+pub const DEFAULT_NAMESRVADDR: &str = "127.0.0.1:9876";
+pub const TOPIC: &str = "TopicTest";
+
+#[rocketmq::main]
+pub async fn main() -> RocketMQResult<()> {
+    //init logger
+    rocketmq_common::log::init_logger();
+
+    let consumer = DefaultLitePullConsumer::builder()
+        .consumer_group(CONSUMER_GROUP)
+        .name_server_addr(DEFAULT_NAMESRVADDR)
+        .auto_commit(true)
+        .build();
+    consumer.subscribe(TOPIC).await?;
+    consumer.start().await?;
+    loop {
+        tokio::select! {
+            messages = consumer.poll_with_timeout(1000) => {
+                for msg in messages {
+                    info!("Receive message: {:?}", msg);
+                }
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    consumer.shutdown().await;
+    Ok(())
+}
@@ -24,29 +24,48 @@ use std::time::Duration;
 
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
+use rand::Rng;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::base::service_state::ServiceState;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_common::common::mix_all;
+use rocketmq_common::common::topic::TopicValidator;
 use rocketmq_common::common::FAQUrl;
+use rocketmq_error::mq_client_err;
 use rocketmq_error::ClientErr;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::consume_stats_list::ConsumeStatsList;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+use rocketmq_remoting::protocol::body::acl_info::PolicyEntryInfo;
+use rocketmq_remoting::protocol::body::acl_info::PolicyInfo;
+use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
 use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
+use rocketmq_remoting::protocol::body::check_rocksdb_cqwrite_progress_response_body::CheckRocksdbCqWriteProgressResponseBody;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
 use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
 use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::subscription_group_wrapper::SubscriptionGroupWrapper;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
+use rocketmq_remoting::protocol::subscription::broker_stats_data::BrokerStatsData;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
 use tracing::info;
@@ -54,6 +73,7 @@ use tracing::info;
 use crate::admin::mq_admin_ext_async::MQAdminExt;
 use crate::admin::mq_admin_ext_async_inner::MQAdminExtInnerImpl;
 use crate::base::client_config::ClientConfig;
+use crate::base::query_result::QueryResult;
 use crate::common::admin_tool_result::AdminToolResult;
 use crate::factory::mq_client_instance::MQClientInstance;
 use crate::implementation::mq_client_manager::MQClientManager;
@@ -135,7 +155,7 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
                     MQClientManager::get_instance().get_or_create_mq_client_instance(
                         self.client_config.as_ref().clone(),
                         self.rpc_hook.clone(),
-                    ),
+                    )?,
                 );
 
                 let group = &self.admin_ext_group.clone();
@@ -216,14 +236,32 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .update_broker_config(
+                &broker_addr,
+                &properties,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn get_broker_config(
         &self,
         broker_addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<HashMap<CheetahString, CheetahString>> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_broker_config(&broker_addr, self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn create_and_update_topic_config(
@@ -231,7 +269,19 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: TopicConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_topic(
+                &addr,
+                CheetahString::from_static_str(TopicValidator::AUTO_CREATE_TOPIC_KEY_TOPIC),
+                &config,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn create_and_update_topic_config_list(
@@ -247,7 +297,14 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: PlainAccessConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_plain_access_config(&addr, config, self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn delete_plain_access_config(
@@ -255,7 +312,14 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         access_key: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .delete_access_config(&addr, access_key, self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn update_global_white_addr_config(
@@ -264,14 +328,35 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         global_white_addrs: CheetahString,
         acl_file_full_path: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .update_global_white_addrs_config(
+                &addr,
+                global_white_addrs,
+                acl_file_full_path,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn examine_broker_cluster_acl_version_info(
         &self,
         addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<CheetahString> {
-        todo!()
+        let info = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_broker_cluster_acl_info(&addr, self.timeout_millis.as_millis() as u64)
+            .await?;
+        Ok(info.to_json()?.into())
     }
 
     async fn create_and_update_subscription_group_config(
@@ -279,7 +364,14 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         config: SubscriptionGroupConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_subscription_group(&addr, &config, self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn create_and_update_subscription_group_config_list(
@@ -287,7 +379,18 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         configs: Vec<SubscriptionGroupConfig>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_subscription_group_list(
+                &broker_addr,
+                configs,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn examine_subscription_group_config(
@@ -295,7 +398,16 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addr: CheetahString,
         group: CheetahString,
     ) -> rocketmq_error::RocketMQResult<SubscriptionGroupConfig> {
-        todo!()
+        let mut wrapper = self
+            .get_all_subscription_group(addr, self.timeout_millis.as_millis() as u64)
+            .await?;
+        match wrapper.subscription_group_table.remove(&group) {
+            Some(config) => Ok(config),
+            None => mq_client_err!(
+                ResponseCode::SubscriptionGroupNotExist,
+                format!("The subscription group {group} does not exist")
+            ),
+        }
     }
 
     async fn examine_topic_stats(
@@ -303,7 +415,40 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<TopicStatsTable> {
-        todo!()
+        let mq_client_api = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        let timeout_millis = self.timeout_millis.as_millis() as u64;
+        if let Some(broker_addr) = broker_addr {
+            return mq_client_api
+                .get_topic_stats_info(&broker_addr, topic, timeout_millis)
+                .await;
+        }
+        let topic_route_data = self.examine_topic_route_info(topic.clone()).await?;
+        let mut offset_table = HashMap::new();
+        if let Some(topic_route_data) = topic_route_data {
+            for broker_data in topic_route_data.broker_datas.iter() {
+                if let Some(addr) = broker_data.select_broker_addr() {
+                    let table = mq_client_api
+                        .get_topic_stats_info(&addr, topic.clone(), timeout_millis)
+                        .await?;
+                    offset_table.extend(table.get_offset_table());
+                }
+            }
+        }
+        if offset_table.is_empty() {
+            return mq_client_err!(
+                ResponseCode::TopicNotExist,
+                format!("Not found the topic stats info, topic={topic}")
+            );
+        }
+        let mut topic_stats_table = TopicStatsTable::new();
+        topic_stats_table.set_offset_table(offset_table);
+        Ok(topic_stats_table)
     }
 
     async fn examine_topic_stats_concurrent(
@@ -314,7 +459,14 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
     }
 
     async fn fetch_all_topic_list(&self) -> rocketmq_error::RocketMQResult<TopicList> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_topic_list_from_name_server(self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn fetch_topics_by_cluster(
@@ -328,7 +480,14 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         &self,
         broker_addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<KVTable> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_broker_runtime_info(&broker_addr, self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn examine_consume_stats(
@@ -339,11 +498,98 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: Option<CheetahString>,
         timeout_millis: Option<u64>,
     ) -> rocketmq_error::RocketMQResult<ConsumeStats> {
-        todo!()
+        let timeout_millis = timeout_millis.unwrap_or(self.timeout_millis.as_millis() as u64 * 3);
+        let broker_addrs = match broker_addr {
+            Some(broker_addr) => vec![broker_addr],
+            None => {
+                let mut route_topics = vec![CheetahString::from_string(mix_all::get_retry_topic(
+                    consumer_group.as_str(),
+                ))];
+                if let Some(topic) = topic.as_ref() {
+                    route_topics.push(topic.clone());
+                }
+                let mut topic_route_data = None;
+                for route_topic in route_topics {
+                    if let Ok(Some(route)) = self.examine_topic_route_info(route_topic).await {
+                        topic_route_data = Some(route);
+                        break;
+                    }
+                }
+                let Some(topic_route_data) = topic_route_data else {
+                    return mq_client_err!(
+                        ResponseCode::TopicNotExist,
+                        format!(
+                            "Not found the route info of consumer group {}",
+                            consumer_group
+                        )
+                    );
+                };
+                topic_route_data
+                    .broker_datas
+                    .iter()
+                    .filter(|broker_data| {
+                        cluster_name
+                            .as_ref()
+                            .map_or(true, |cluster_name| broker_data.cluster() == cluster_name)
+                    })
+                    .filter_map(|broker_data| broker_data.select_broker_addr())
+                    .collect()
+            }
+        };
+
+        let mq_client_api_impl = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        let mut result = ConsumeStats::new();
+        for addr in broker_addrs.iter() {
+            let consume_stats = mq_client_api_impl
+                .get_consume_stats(addr, consumer_group.clone(), topic.clone(), timeout_millis)
+                .await?;
+            result.offset_table.extend(consume_stats.offset_table);
+            result.consume_tps += consume_stats.consume_tps;
+        }
+        if result.offset_table.is_empty() {
+            return mq_client_err!(
+                ResponseCode::ConsumerNotOnline,
+                "Not found the consumer group consume stats, because return offset table is \
+                 empty, maybe the consumer not consume any message"
+            );
+        }
+        Ok(result)
+    }
+
+    async fn check_rocksdb_cq_write_progress(
+        &self,
+        broker_addr: CheetahString,
+        topic: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<CheckRocksdbCqWriteProgressResponseBody> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .check_rocksdb_cq_write_progress(
+                &broker_addr,
+                topic,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn examine_broker_cluster_info(&self) -> rocketmq_error::RocketMQResult<ClusterInfo> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_broker_cluster_info(self.timeout_millis.as_millis() as u64)
+            .await
     }
 
     async fn examine_topic_route_info(
@@ -365,7 +611,46 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         consumer_group: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<ConsumerConnection> {
-        todo!()
+        let broker_addr = match broker_addr {
+            Some(broker_addr) => Some(broker_addr),
+            None => {
+                let retry_topic =
+                    CheetahString::from_string(mix_all::get_retry_topic(consumer_group.as_str()));
+                self.examine_topic_route_info(retry_topic)
+                    .await?
+                    .and_then(|topic_route_data| {
+                        let broker_datas = &topic_route_data.broker_datas;
+                        if broker_datas.is_empty() {
+                            return None;
+                        }
+                        broker_datas[rand::rng().random_range(0..broker_datas.len())]
+                            .select_broker_addr()
+                    })
+            }
+        };
+        let mut result = ConsumerConnection::new();
+        if let Some(ref broker_addr) = broker_addr {
+            result = self
+                .client_instance
+                .as_ref()
+                .unwrap()
+                .mq_client_api_impl
+                .as_ref()
+                .unwrap()
+                .get_consumer_connection_list(
+                    broker_addr,
+                    consumer_group,
+                    self.timeout_millis.as_millis() as u64,
+                )
+                .await?;
+        }
+        if result.get_connection_set().is_empty() {
+            return mq_client_err!(
+                ResponseCode::ConsumerNotOnline,
+                "Not found the consumer group connection"
+            );
+        }
+        Ok(result)
     }
 
     async fn examine_producer_connection_info(
@@ -373,11 +658,48 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         producer_group: CheetahString,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<ProducerConnection> {
-        todo!()
+        let broker_addr =
+            self.examine_topic_route_info(topic)
+                .await?
+                .and_then(|topic_route_data| {
+                    let broker_datas = &topic_route_data.broker_datas;
+                    if broker_datas.is_empty() {
+                        return None;
+                    }
+                    broker_datas[rand::rng().random_range(0..broker_datas.len())]
+                        .select_broker_addr()
+                });
+        let mut result = ProducerConnection::default();
+        if let Some(ref broker_addr) = broker_addr {
+            result = self
+                .client_instance
+                .as_ref()
+                .unwrap()
+                .mq_client_api_impl
+                .as_ref()
+                .unwrap()
+                .get_producer_connection_list(
+                    broker_addr,
+                    producer_group,
+                    self.timeout_millis.as_millis() as u64,
+                )
+                .await?;
+        }
+        if result.connection_set.is_empty() {
+            return mq_client_err!("Not found the producer group connection");
+        }
+        Ok(result)
     }
 
     async fn get_name_server_address_list(&self) -> Vec<CheetahString> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_name_server_address_list()
+            .to_vec()
     }
 
     async fn wipe_write_perm_of_broker(
@@ -425,7 +747,31 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         topic_name: CheetahString,
         cluster_name: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let cluster_info = self.examine_broker_cluster_info().await?;
+        let broker_addrs = cluster_info
+            .cluster_addr_table
+            .as_ref()
+            .and_then(|table| table.get(&cluster_name))
+            .into_iter()
+            .flatten()
+            .filter_map(|broker_name| {
+                cluster_info
+                    .broker_addr_table
+                    .as_ref()
+                    .and_then(|table| table.get(broker_name))
+            })
+            .flat_map(|broker_data| broker_data.broker_addrs().values().cloned())
+            .collect::<HashSet<CheetahString>>();
+        if broker_addrs.is_empty() {
+            return mq_client_err!(format!(
+                "Not found the broker addresses of cluster {}",
+                cluster_name
+            ));
+        }
+        self.delete_topic_in_broker(broker_addrs, topic_name.clone())
+            .await?;
+        self.delete_topic_in_name_server(HashSet::new(), Some(cluster_name), topic_name)
+            .await
     }
 
     async fn delete_topic_in_broker(
@@ -433,7 +779,23 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         addrs: HashSet<CheetahString>,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let mq_client_api = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        for addr in addrs {
+            mq_client_api
+                .delete_topic_in_broker(
+                    &addr,
+                    topic.clone(),
+                    self.timeout_millis.as_millis() as u64,
+                )
+                .await?;
+        }
+        Ok(())
     }
 
     async fn delete_topic_in_name_server(
@@ -442,7 +804,33 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         cluster_name: Option<CheetahString>,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let mq_client_api = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        let addrs = if addrs.is_empty() {
+            mq_client_api
+                .get_name_server_address_list()
+                .iter()
+                .cloned()
+                .collect()
+        } else {
+            addrs
+        };
+        for addr in addrs {
+            mq_client_api
+                .delete_topic_in_name_server(
+                    &addr,
+                    cluster_name.clone(),
+                    topic.clone(),
+                    self.timeout_millis.as_millis() as u64,
+                )
+                .await?;
+        }
+        Ok(())
     }
 
     async fn delete_subscription_group(
@@ -451,7 +839,19 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         group_name: CheetahString,
         remove_offset: Option<bool>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .delete_subscription_group(
+                &addr,
+                group_name,
+                remove_offset.unwrap_or_default(),
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn create_and_update_kv_config(
@@ -479,7 +879,37 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         timestamp: u64,
         is_force: bool,
     ) -> rocketmq_error::RocketMQResult<HashMap<MessageQueue, u64>> {
-        todo!()
+        let Some(topic_route_data) = self.examine_topic_route_info(topic.clone()).await? else {
+            return mq_client_err!(format!("No topic route data for topic {topic}"));
+        };
+        let mq_client_api_impl = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        let mut all_offset_table = HashMap::new();
+        for broker_data in &topic_route_data.broker_datas {
+            if let Some(addr) = broker_data.select_broker_addr() {
+                let offset_table = mq_client_api_impl
+                    .invoke_broker_to_reset_offset(
+                        &addr,
+                        topic.clone(),
+                        group.clone(),
+                        timestamp,
+                        is_force,
+                        self.timeout_millis.as_millis() as u64,
+                    )
+                    .await?;
+                all_offset_table.extend(
+                    offset_table
+                        .into_iter()
+                        .map(|(mq, offset)| (mq, offset.max(0) as u64)),
+                );
+            }
+        }
+        Ok(all_offset_table)
     }
 
     async fn reset_offset_new(
@@ -513,7 +943,65 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         &self,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<GroupList> {
-        todo!()
+        let Some(topic_route_data) = self.examine_topic_route_info(topic.clone()).await? else {
+            return mq_client_err!(
+                ResponseCode::TopicNotExist,
+                format!("Not found the route info of topic {}", topic)
+            );
+        };
+        let mq_client_api_impl = self
+            .client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap();
+        let mut group_list = HashSet::new();
+        for broker_data in topic_route_data.broker_datas.iter() {
+            if let Some(addr) = broker_data.select_broker_addr() {
+                let groups = mq_client_api_impl
+                    .query_topic_consume_by_who(
+                        &addr,
+                        topic.clone(),
+                        self.timeout_millis.as_millis() as u64,
+                    )
+                    .await?;
+                group_list.extend(groups.group_list);
+            }
+        }
+        Ok(GroupList::new(group_list))
+    }
+
+    async fn view_message(
+        &self,
+        topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<MessageExt> {
+        let mut mq_admin_impl = self.client_instance.as_ref().unwrap().mq_admin_impl.clone();
+        if let Ok(message) = mq_admin_impl
+            .query_message_by_uniq_key(&topic, &msg_id)
+            .await
+        {
+            return Ok(message);
+        }
+        mq_admin_impl.view_message(&topic, &msg_id).await
+    }
+
+    async fn query_message_by_key(
+        &self,
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> rocketmq_error::RocketMQResult<QueryResult> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_admin_impl
+            .clone()
+            .query_message(&topic, &key, max_num, begin, end, false)
+            .await
     }
 
     async fn query_topics_by_consumer(
@@ -569,7 +1057,38 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         jstack: bool,
         metrics: Option<bool>,
     ) -> rocketmq_error::RocketMQResult<ConsumerRunningInfo> {
-        todo!()
+        let retry_topic = CheetahString::from_string(mix_all::get_retry_topic(&consumer_group));
+        let broker_addr =
+            self.examine_topic_route_info(retry_topic)
+                .await?
+                .and_then(|topic_route_data| {
+                    topic_route_data
+                        .broker_datas
+                        .iter()
+                        .find_map(|broker_data| broker_data.select_broker_addr())
+                });
+        match broker_addr {
+            Some(broker_addr) => {
+                self.client_instance
+                    .as_ref()
+                    .unwrap()
+                    .mq_client_api_impl
+                    .as_ref()
+                    .unwrap()
+                    .get_consumer_running_info(
+                        &broker_addr,
+                        consumer_group,
+                        client_id,
+                        jstack,
+                        self.timeout_millis.as_millis() as u64,
+                    )
+                    .await
+            }
+            None => mq_client_err!(
+                ResponseCode::ConsumerNotOnline,
+                format!("Not found the broker of consumer group {consumer_group}")
+            ),
+        }
     }
 
     async fn consume_message_directly(
@@ -603,6 +1122,27 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         todo!()
     }
 
+    async fn view_broker_stats_data(
+        &self,
+        broker_addr: CheetahString,
+        stats_name: CheetahString,
+        stats_key: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<BrokerStatsData> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .view_broker_stats_data(
+                &broker_addr,
+                stats_name,
+                stats_key,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
     async fn get_cluster_list(
         &self,
         topic: String,
@@ -610,6 +1150,22 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         todo!()
     }
 
+    async fn fetch_consume_stats_in_broker(
+        &self,
+        broker_addr: CheetahString,
+        is_order: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumeStatsList> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .fetch_consume_stats_in_broker(&broker_addr, is_order, timeout_millis)
+            .await
+    }
+
     async fn get_topic_cluster_list(
         &self,
         topic: String,
@@ -617,6 +1173,21 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         todo!()
     }
 
+    async fn get_all_subscription_group(
+        &self,
+        broker_addr: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<SubscriptionGroupWrapper> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_all_subscription_group(&broker_addr, timeout_millis)
+            .await
+    }
+
     async fn get_all_topic_config(
         &self,
         broker_addr: CheetahString,
@@ -728,6 +1299,76 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         todo!()
     }
 
+    async fn get_in_sync_state_data(
+        &self,
+        controller_address: CheetahString,
+        brokers: Vec<CheetahString>,
+    ) -> rocketmq_error::RocketMQResult<BrokerReplicasInfo> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_in_sync_state_data(
+                &controller_address,
+                brokers,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
+    async fn get_broker_epoch_cache(
+        &self,
+        broker_addr: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<EpochEntryCache> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_broker_epoch_cache(&broker_addr, self.timeout_millis.as_millis() as u64)
+            .await
+    }
+
+    async fn get_controller_meta_data(
+        &self,
+        controller_addr: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<GetMetaDataResponseHeader> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_controller_meta_data(&controller_addr, self.timeout_millis.as_millis() as u64)
+            .await
+    }
+
+    async fn elect_master(
+        &self,
+        controller_addr: CheetahString,
+        cluster_name: CheetahString,
+        broker_name: CheetahString,
+        broker_id: Option<u64>,
+    ) -> rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, BrokerMemberGroup)> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .elect_master(
+                &controller_addr,
+                &cluster_name,
+                &broker_name,
+                broker_id.map(|broker_id| broker_id as i64),
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
     async fn reset_master_flush_offset(
         &self,
         broker_addr: CheetahString,
@@ -801,7 +1442,24 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         password: CheetahString,
         user_type: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let user_info = UserInfo {
+            username: Some(username),
+            password: Some(password),
+            user_type: non_empty(user_type),
+            user_status: None,
+        };
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_user(
+                &broker_addr,
+                user_info,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn update_user(
@@ -812,7 +1470,24 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         user_type: CheetahString,
         user_status: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let user_info = UserInfo {
+            username: Some(username),
+            password: non_empty(password),
+            user_type: non_empty(user_type),
+            user_status: non_empty(user_status),
+        };
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .update_user(
+                &broker_addr,
+                user_info,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn delete_user(
@@ -820,7 +1495,56 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         broker_addr: CheetahString,
         username: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .delete_user(
+                &broker_addr,
+                username,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
+    async fn get_user(
+        &self,
+        broker_addr: CheetahString,
+        username: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<UserInfo> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_user(
+                &broker_addr,
+                username,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
+    async fn list_users(
+        &self,
+        broker_addr: CheetahString,
+        filter: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<Vec<UserInfo>> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .list_users(
+                &broker_addr,
+                non_empty(filter),
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn create_acl(
@@ -832,7 +1556,19 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let acl_info = build_acl_info(subject, resources, actions, source_ips, decision);
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .create_acl(
+                &broker_addr,
+                acl_info,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn update_acl(
@@ -844,7 +1580,19 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        let acl_info = build_acl_info(subject, resources, actions, source_ips, decision);
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .update_acl(
+                &broker_addr,
+                acl_info,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
     }
 
     async fn delete_acl(
@@ -853,6 +1601,96 @@ impl MQAdminExt for DefaultMQAdminExtImpl {
         subject: CheetahString,
         resource: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .delete_acl(
+                &broker_addr,
+                subject,
+                None,
+                non_empty(resource),
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
+    async fn get_acl(
+        &self,
+        broker_addr: CheetahString,
+        subject: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<AclInfo> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .get_acl(
+                &broker_addr,
+                subject,
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+
+    async fn list_acl(
+        &self,
+        broker_addr: CheetahString,
+        subject_filter: CheetahString,
+        resource_filter: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<Vec<AclInfo>> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_client_api_impl
+            .as_ref()
+            .unwrap()
+            .list_acl(
+                &broker_addr,
+                non_empty(subject_filter),
+                non_empty(resource_filter),
+                self.timeout_millis.as_millis() as u64,
+            )
+            .await
+    }
+}
+
+fn non_empty(value: CheetahString) -> Option<CheetahString> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// A custom policy granting, or denying, the same actions on every resource.
+fn build_acl_info(
+    subject: CheetahString,
+    resources: Vec<CheetahString>,
+    actions: Vec<CheetahString>,
+    source_ips: Vec<CheetahString>,
+    decision: CheetahString,
+) -> AclInfo {
+    let actions = CheetahString::from_string(
+        actions
+            .iter()
+            .map(CheetahString::as_str)
+            .collect::<Vec<_>>()
+            .join(","),
+    );
+    let entries = resources
+        .into_iter()
+        .map(|resource| PolicyEntryInfo {
+            resource: Some(resource),
+            actions: Some(actions.clone()),
+            source_ips: Some(source_ips.clone()),
+            decision: Some(decision.clone()),
+        })
+        .collect();
+    AclInfo {
+        subject: Some(subject),
+        policies: Some(vec![PolicyInfo {
+            policy_type: None,
+            entries: Some(entries),
+        }]),
     }
 }
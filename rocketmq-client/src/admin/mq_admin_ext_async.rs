@@ -21,23 +21,36 @@ use cheetah_string::CheetahString;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::consume_stats_list::ConsumeStatsList;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
 use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
+use rocketmq_remoting::protocol::body::check_rocksdb_cqwrite_progress_response_body::CheckRocksdbCqWriteProgressResponseBody;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
 use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
 use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::subscription_group_wrapper::SubscriptionGroupWrapper;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
+use rocketmq_remoting::protocol::subscription::broker_stats_data::BrokerStatsData;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 
+use crate::base::query_result::QueryResult;
 use crate::common::admin_tool_result::AdminToolResult;
 
 #[allow(dead_code)]
@@ -156,11 +169,11 @@ pub trait MQAdminExtLocal: Sync {
         timeout_millis: Option<u64>,
     ) -> rocketmq_error::RocketMQResult<ConsumeStats>;
 
-    /*async fn check_rocksdb_cq_write_progress(
+    async fn check_rocksdb_cq_write_progress(
         &self,
         broker_addr: CheetahString,
         topic: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<CheckRocksdbCqWriteProgressResponseBody>;*/
+    ) -> rocketmq_error::RocketMQResult<CheckRocksdbCqWriteProgressResponseBody>;
 
     async fn examine_broker_cluster_info(&self) -> rocketmq_error::RocketMQResult<ClusterInfo>;
 
@@ -313,6 +326,23 @@ pub trait MQAdminExtLocal: Sync {
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<GroupList>;
 
+    /// Looks a message up by its client generated message id, falling back to the offset
+    /// message id assigned by the broker.
+    async fn view_message(
+        &self,
+        topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<MessageExt>;
+
+    async fn query_message_by_key(
+        &self,
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> rocketmq_error::RocketMQResult<QueryResult>;
+
     async fn query_topics_by_consumer(
         &self,
         group: CheetahString,
@@ -402,35 +432,35 @@ pub trait MQAdminExtLocal: Sync {
         is_offline: bool,
     ) -> rocketmq_error::RocketMQResult<()>;
 
-    /*async fn view_broker_stats_data(
+    async fn view_broker_stats_data(
         &self,
         broker_addr: CheetahString,
         stats_name: CheetahString,
         stats_key: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<BrokerStatsData>;*/
+    ) -> rocketmq_error::RocketMQResult<BrokerStatsData>;
 
     async fn get_cluster_list(
         &self,
         topic: String,
     ) -> rocketmq_error::RocketMQResult<HashSet<CheetahString>>;
 
-    /*async fn fetch_consume_stats_in_broker(
+    async fn fetch_consume_stats_in_broker(
         &self,
         broker_addr: CheetahString,
         is_order: bool,
         timeout_millis: u64,
-    ) ->rocketmq_error::RocketMQResult<ConsumeStatsList>;*/
+    ) -> rocketmq_error::RocketMQResult<ConsumeStatsList>;
 
     async fn get_topic_cluster_list(
         &self,
         topic: String,
     ) -> rocketmq_error::RocketMQResult<HashSet<CheetahString>>;
 
-    /*async fn get_all_subscription_group(
+    async fn get_all_subscription_group(
         &self,
         broker_addr: CheetahString,
         timeout_millis: u64,
-    ) ->rocketmq_error::RocketMQResult<SubscriptionGroupWrapper>;*/
+    ) -> rocketmq_error::RocketMQResult<SubscriptionGroupWrapper>;
 
     /*async fn get_user_subscription_group(
         &self,
@@ -535,23 +565,23 @@ pub trait MQAdminExtLocal: Sync {
         msg_id: CheetahString,
     ) ->rocketmq_error::RocketMQResult<MessageExt>;
 
-    async fn get_broker_ha_status(&self, broker_addr: CheetahString) ->rocketmq_error::RocketMQResult<HARuntimeInfo>;
+    async fn get_broker_ha_status(&self, broker_addr: CheetahString) ->rocketmq_error::RocketMQResult<HARuntimeInfo>;*/
 
     async fn get_in_sync_state_data(
         &self,
         controller_address: CheetahString,
         brokers: Vec<CheetahString>,
-    ) ->rocketmq_error::RocketMQResult<BrokerReplicasInfo>;
+    ) -> rocketmq_error::RocketMQResult<BrokerReplicasInfo>;
 
     async fn get_broker_epoch_cache(
         &self,
         broker_addr: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<EpochEntryCache>;
+    ) -> rocketmq_error::RocketMQResult<EpochEntryCache>;
 
     async fn get_controller_meta_data(
         &self,
         controller_addr: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<GetMetaDataResponseHeader>;*/
+    ) -> rocketmq_error::RocketMQResult<GetMetaDataResponseHeader>;
 
     async fn reset_master_flush_offset(
         &self,
@@ -570,13 +600,13 @@ pub trait MQAdminExtLocal: Sync {
         controllers: Vec<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<()>;
 
-    /*async fn elect_master(
+    async fn elect_master(
         &self,
         controller_addr: CheetahString,
         cluster_name: CheetahString,
         broker_name: CheetahString,
         broker_id: Option<u64>,
-    ) ->rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, BrokerMemberGroup)>;*/
+    ) -> rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, BrokerMemberGroup)>;
 
     async fn clean_controller_broker_data(
         &self,
@@ -645,17 +675,17 @@ pub trait MQAdminExtLocal: Sync {
         username: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()>;
 
-    /*async fn get_user(
+    async fn get_user(
         &self,
         broker_addr: CheetahString,
         username: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<UserInfo>;*/
+    ) -> rocketmq_error::RocketMQResult<UserInfo>;
 
-    /* async fn list_users(
+    async fn list_users(
         &self,
         broker_addr: CheetahString,
         filter: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<Vec<UserInfo>>;*/
+    ) -> rocketmq_error::RocketMQResult<Vec<UserInfo>>;
 
     async fn create_acl(
         &self,
@@ -696,16 +726,16 @@ pub trait MQAdminExtLocal: Sync {
         resource: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()>;
 
-    /*async fn get_acl(
+    async fn get_acl(
         &self,
         broker_addr: CheetahString,
         subject: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<AclInfo>;*/
+    ) -> rocketmq_error::RocketMQResult<AclInfo>;
 
-    /*async fn list_acl(
+    async fn list_acl(
         &self,
         broker_addr: CheetahString,
         subject_filter: CheetahString,
         resource_filter: CheetahString,
-    ) ->rocketmq_error::RocketMQResult<Vec<AclInfo>>;*/
+    ) -> rocketmq_error::RocketMQResult<Vec<AclInfo>>;
 }
@@ -22,6 +22,8 @@ use std::time::Duration;
 
 use cheetah_string::CheetahString;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::metrics::metrics_exporter_type::MetricsExporterType;
+use rocketmq_common::common::server::tls_config::TlsConfig;
 use rocketmq_common::utils::name_server_address_utils::NameServerAddressUtils;
 use rocketmq_common::utils::name_server_address_utils::NAMESRV_ENDPOINT_PATTERN;
 use rocketmq_common::utils::network_util::NetworkUtil;
@@ -54,6 +56,14 @@ pub struct ClientConfig {
     pub vip_channel_enabled: bool,
     pub use_heartbeat_v2: bool,
     pub use_tls: bool,
+    /// Skips the verification of the broker and name server certificates, for test environments
+    /// only.
+    pub tls_test_mode_enable: bool,
+    /// The certificate presented to servers requiring client auth, a PEM file.
+    pub tls_client_cert_path: Option<CheetahString>,
+    pub tls_client_key_path: Option<CheetahString>,
+    /// The CA the certificates of the servers are verified with, a PEM file.
+    pub tls_client_trust_cert_path: Option<CheetahString>,
     pub socks_proxy_config: CheetahString,
     pub mq_client_api_timeout: u64,
     pub detect_timeout: u32,
@@ -65,6 +75,10 @@ pub struct ClientConfig {
     pub enable_heartbeat_channel_event_listener: bool,
     pub enable_trace: bool,
     pub trace_topic: Option<CheetahString>,
+    pub metrics_exporter_type: MetricsExporterType,
+    /// Host the Prometheus exporter binds, loopback unless configured otherwise.
+    pub metrics_prom_exporter_host: CheetahString,
+    pub metrics_prom_exporter_port: u16,
 }
 
 impl Default for ClientConfig {
@@ -84,6 +98,7 @@ impl ClientConfig {
     pub const HEART_BEAT_V2: &'static str = "com.rocketmq.heartbeat.v2";
 
     pub fn new() -> Self {
+        let tls_config = TlsConfig::from_env();
         ClientConfig {
             namesrv_addr: NameServerAddressUtils::get_name_server_addresses()
                 .map(|addr| addr.into()),
@@ -118,7 +133,13 @@ impl ClientConfig {
                 .unwrap_or_else(|_| "false".to_string())
                 .parse::<bool>()
                 .unwrap_or(false),
-            use_tls: false,
+            use_tls: tls_config.tls_enable,
+            tls_test_mode_enable: tls_config.tls_test_mode_enable,
+            tls_client_cert_path: tls_config.tls_client_cert_path.map(|path| path.into()),
+            tls_client_key_path: tls_config.tls_client_key_path.map(|path| path.into()),
+            tls_client_trust_cert_path: tls_config
+                .tls_client_trust_cert_path
+                .map(|path| path.into()),
             socks_proxy_config: env::var(Self::SOCKS_PROXY_CONFIG)
                 .unwrap_or_else(|_| "{}".to_string())
                 .into(),
@@ -136,6 +157,9 @@ impl ClientConfig {
             enable_heartbeat_channel_event_listener: true,
             enable_trace: false,
             trace_topic: None,
+            metrics_exporter_type: MetricsExporterType::Disable,
+            metrics_prom_exporter_host: CheetahString::from_static_str("127.0.0.1"),
+            metrics_prom_exporter_port: 5559,
         }
     }
 }
@@ -213,6 +237,27 @@ impl ClientConfig {
         sb
     }
 
+    /// The TLS settings the remoting client connects with.
+    pub fn tls_config(&self) -> TlsConfig {
+        TlsConfig {
+            tls_enable: self.use_tls,
+            tls_test_mode_enable: self.tls_test_mode_enable,
+            tls_client_cert_path: self
+                .tls_client_cert_path
+                .as_ref()
+                .map(|path| path.to_string()),
+            tls_client_key_path: self
+                .tls_client_key_path
+                .as_ref()
+                .map(|path| path.to_string()),
+            tls_client_trust_cert_path: self
+                .tls_client_trust_cert_path
+                .as_ref()
+                .map(|path| path.to_string()),
+            ..TlsConfig::default()
+        }
+    }
+
     #[inline]
     pub fn get_namesrv_addr(&self) -> Option<CheetahString> {
         if StringUtils::is_not_empty_str(self.namesrv_addr.as_deref())
@@ -0,0 +1,306 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+
+use crate::consumer::consumer_impl::process_queue::ProcessQueue;
+
+/// Consumption state of a single message queue owned by a lite pull consumer.
+struct MessageQueueState {
+    process_queue: Arc<ProcessQueue>,
+    paused: bool,
+    pull_offset: i64,
+    consume_offset: i64,
+    seek_offset: i64,
+}
+
+impl MessageQueueState {
+    fn new(process_queue: Arc<ProcessQueue>) -> Self {
+        Self {
+            process_queue,
+            paused: false,
+            pull_offset: -1,
+            consume_offset: -1,
+            seek_offset: -1,
+        }
+    }
+}
+
+/// Tracks the message queues assigned to a lite pull consumer, either by rebalance or by an
+/// explicit `assign`, together with their pull, consume and seek offsets.
+#[derive(Default)]
+pub(crate) struct AssignedMessageQueue {
+    assigned_message_queue_state: RwLock<HashMap<MessageQueue, MessageQueueState>>,
+}
+
+impl AssignedMessageQueue {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn message_queues(&self) -> HashSet<MessageQueue> {
+        self.assigned_message_queue_state
+            .read()
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn contains(&self, message_queue: &MessageQueue) -> bool {
+        self.assigned_message_queue_state
+            .read()
+            .contains_key(message_queue)
+    }
+
+    pub(crate) fn is_paused(&self, message_queue: &MessageQueue) -> bool {
+        self.assigned_message_queue_state
+            .read()
+            .get(message_queue)
+            .is_some_and(|state| state.paused)
+    }
+
+    pub(crate) fn pause(&self, message_queues: &[MessageQueue]) {
+        self.set_paused(message_queues, true);
+    }
+
+    pub(crate) fn resume(&self, message_queues: &[MessageQueue]) {
+        self.set_paused(message_queues, false);
+    }
+
+    fn set_paused(&self, message_queues: &[MessageQueue], paused: bool) {
+        let mut table = self.assigned_message_queue_state.write();
+        for message_queue in message_queues {
+            if let Some(state) = table.get_mut(message_queue) {
+                state.paused = paused;
+            }
+        }
+    }
+
+    pub(crate) fn get_process_queue(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> Option<Arc<ProcessQueue>> {
+        self.assigned_message_queue_state
+            .read()
+            .get(message_queue)
+            .map(|state| state.process_queue.clone())
+    }
+
+    pub(crate) fn get_pull_offset(&self, message_queue: &MessageQueue) -> i64 {
+        self.assigned_message_queue_state
+            .read()
+            .get(message_queue)
+            .map_or(-1, |state| state.pull_offset)
+    }
+
+    /// Records the next pull offset, ignoring results that belong to a process queue which has
+    /// since been replaced.
+    pub(crate) fn update_pull_offset(
+        &self,
+        message_queue: &MessageQueue,
+        offset: i64,
+        process_queue: &Arc<ProcessQueue>,
+    ) {
+        if let Some(state) = self
+            .assigned_message_queue_state
+            .write()
+            .get_mut(message_queue)
+        {
+            if Arc::ptr_eq(&state.process_queue, process_queue) {
+                state.pull_offset = offset;
+            }
+        }
+    }
+
+    pub(crate) fn get_consume_offset(&self, message_queue: &MessageQueue) -> i64 {
+        self.assigned_message_queue_state
+            .read()
+            .get(message_queue)
+            .map_or(-1, |state| state.consume_offset)
+    }
+
+    pub(crate) fn update_consume_offset(&self, message_queue: &MessageQueue, offset: i64) {
+        if let Some(state) = self
+            .assigned_message_queue_state
+            .write()
+            .get_mut(message_queue)
+        {
+            state.consume_offset = offset;
+        }
+    }
+
+    pub(crate) fn get_seek_offset(&self, message_queue: &MessageQueue) -> i64 {
+        self.assigned_message_queue_state
+            .read()
+            .get(message_queue)
+            .map_or(-1, |state| state.seek_offset)
+    }
+
+    pub(crate) fn set_seek_offset(&self, message_queue: &MessageQueue, offset: i64) {
+        if let Some(state) = self
+            .assigned_message_queue_state
+            .write()
+            .get_mut(message_queue)
+        {
+            state.seek_offset = offset;
+        }
+    }
+
+    /// Replaces the queues of `topic` with `assigned`. Queues that are no longer assigned have
+    /// their process queue dropped; new queues reuse the process queue from `process_queues`
+    /// when the rebalance already created one.
+    pub(crate) fn update_assign(
+        &self,
+        topic: &str,
+        assigned: &HashSet<MessageQueue>,
+        process_queues: &HashMap<MessageQueue, Arc<ProcessQueue>>,
+    ) {
+        let mut table = self.assigned_message_queue_state.write();
+        table.retain(|message_queue, state| {
+            if message_queue.get_topic() == topic && !assigned.contains(message_queue) {
+                state.process_queue.set_dropped(true);
+                false
+            } else {
+                true
+            }
+        });
+        Self::add_assigned(&mut table, assigned, process_queues);
+    }
+
+    /// Replaces every assigned queue with `assigned`, as done by `assign`.
+    pub(crate) fn update_assign_all(&self, assigned: &HashSet<MessageQueue>) {
+        let mut table = self.assigned_message_queue_state.write();
+        table.retain(|message_queue, state| {
+            if assigned.contains(message_queue) {
+                true
+            } else {
+                state.process_queue.set_dropped(true);
+                false
+            }
+        });
+        Self::add_assigned(&mut table, assigned, &HashMap::new());
+    }
+
+    fn add_assigned(
+        table: &mut HashMap<MessageQueue, MessageQueueState>,
+        assigned: &HashSet<MessageQueue>,
+        process_queues: &HashMap<MessageQueue, Arc<ProcessQueue>>,
+    ) {
+        for message_queue in assigned {
+            if !table.contains_key(message_queue) {
+                let process_queue = process_queues
+                    .get(message_queue)
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(ProcessQueue::new()));
+                table.insert(message_queue.clone(), MessageQueueState::new(process_queue));
+            }
+        }
+    }
+
+    pub(crate) fn remove_assign(&self, topic: &str) {
+        self.assigned_message_queue_state
+            .write()
+            .retain(|message_queue, state| {
+                if message_queue.get_topic() == topic {
+                    state.process_queue.set_dropped(true);
+                    false
+                } else {
+                    true
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(topic: &str, queue_id: i32) -> MessageQueue {
+        MessageQueue::from_parts(topic, "broker-a", queue_id)
+    }
+
+    #[test]
+    fn update_assign_only_touches_the_given_topic() {
+        let assigned = AssignedMessageQueue::new();
+        assigned.update_assign_all(&HashSet::from([queue("other", 0)]));
+        assigned.update_assign(
+            "topic",
+            &HashSet::from([queue("topic", 0), queue("topic", 1)]),
+            &HashMap::new(),
+        );
+        assert_eq!(assigned.message_queues().len(), 3);
+
+        let dropped = assigned.get_process_queue(&queue("topic", 1)).unwrap();
+        assigned.update_assign(
+            "topic",
+            &HashSet::from([queue("topic", 0)]),
+            &HashMap::new(),
+        );
+        assert!(dropped.is_dropped());
+        assert!(assigned.contains(&queue("other", 0)));
+        assert!(assigned.contains(&queue("topic", 0)));
+        assert!(!assigned.contains(&queue("topic", 1)));
+    }
+
+    #[test]
+    fn update_assign_reuses_rebalance_process_queue() {
+        let assigned = AssignedMessageQueue::new();
+        let process_queue = Arc::new(ProcessQueue::new());
+        let process_queues = HashMap::from([(queue("topic", 0), process_queue.clone())]);
+        assigned.update_assign(
+            "topic",
+            &HashSet::from([queue("topic", 0)]),
+            &process_queues,
+        );
+        assert!(Arc::ptr_eq(
+            &assigned.get_process_queue(&queue("topic", 0)).unwrap(),
+            &process_queue
+        ));
+    }
+
+    #[test]
+    fn offsets_and_pause_state() {
+        let assigned = AssignedMessageQueue::new();
+        let mq = queue("topic", 0);
+        assigned.update_assign_all(&HashSet::from([mq.clone()]));
+        assert_eq!(assigned.get_pull_offset(&mq), -1);
+        assert_eq!(assigned.get_seek_offset(&mq), -1);
+
+        let process_queue = assigned.get_process_queue(&mq).unwrap();
+        assigned.update_pull_offset(&mq, 10, &process_queue);
+        assigned.update_pull_offset(&mq, 99, &Arc::new(ProcessQueue::new()));
+        assert_eq!(assigned.get_pull_offset(&mq), 10);
+
+        assigned.update_consume_offset(&mq, 8);
+        assigned.set_seek_offset(&mq, 3);
+        assert_eq!(assigned.get_consume_offset(&mq), 8);
+        assert_eq!(assigned.get_seek_offset(&mq), 3);
+
+        assigned.pause(std::slice::from_ref(&mq));
+        assert!(assigned.is_paused(&mq));
+        assigned.resume(std::slice::from_ref(&mq));
+        assert!(!assigned.is_paused(&mq));
+
+        assigned.remove_assign("topic");
+        assert!(process_queue.is_dropped());
+        assert!(assigned.message_queues().is_empty());
+    }
+}
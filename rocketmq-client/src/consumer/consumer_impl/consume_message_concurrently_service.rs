@@ -45,6 +45,10 @@ use crate::consumer::listener::consume_concurrently_status::ConsumeConcurrentlyS
 use crate::consumer::listener::consume_return_type::ConsumeReturnType;
 use crate::consumer::listener::message_listener_concurrently::ArcBoxMessageListenerConcurrently;
 use crate::hook::consume_message_context::ConsumeMessageContext;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::message_tracer::MessageTracer;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::span::SpanStatus;
 
 pub struct ConsumeMessageConcurrentlyService {
     pub(crate) default_mqpush_consumer_impl: Option<ArcMut<DefaultMQPushConsumerImpl>>,
@@ -117,6 +121,17 @@ impl ConsumeMessageConcurrentlyService {
                 ack_index = -1;
             }
         }
+        if let Some(stats) = self
+            .default_mqpush_consumer_impl
+            .as_ref()
+            .and_then(|consumer| consumer.consumer_stats_manager())
+        {
+            let ok = (ack_index + 1) as u64;
+            let failed = consume_request.msgs.len() as u64 - ok;
+            let topic = consume_request.message_queue.get_topic();
+            stats.inc_consume_ok_tps(self.consumer_group.as_str(), topic, ok);
+            stats.inc_consume_failed_tps(self.consumer_group.as_str(), topic, failed);
+        }
 
         match self.consumer_config.message_model {
             MessageModel::Broadcasting => {
@@ -377,6 +392,8 @@ impl ConsumeRequest {
         let mut has_exception = false;
         let mut return_type = ConsumeReturnType::Success;
         let mut status = None;
+        #[cfg(feature = "opentelemetry")]
+        let mut otel_cx = None;
 
         if !self.msgs.is_empty() {
             for msg in self.msgs.iter_mut() {
@@ -408,7 +425,17 @@ impl ConsumeRequest {
                 .iter()
                 .map(|msg| msg.as_ref())
                 .collect::<Vec<&MessageExt>>();
-            match self.message_listener.consume_message(&vec, &context) {
+            #[cfg(feature = "opentelemetry")]
+            {
+                otel_cx = crate::trace::otel::global::tracer()
+                    .map(|tracer| tracer.start_process_span(self.consumer_group.as_str(), &vec));
+            }
+            let consume_result = {
+                #[cfg(feature = "opentelemetry")]
+                let _otel_guard = otel_cx.clone().map(|cx| cx.attach());
+                self.message_listener.consume_message(&vec, &context)
+            };
+            match consume_result {
                 Ok(value) => {
                     status = Some(value);
                 }
@@ -446,6 +473,18 @@ impl ConsumeRequest {
             status = Some(ConsumeConcurrentlyStatus::ReconsumeLater);
         }
 
+        #[cfg(feature = "opentelemetry")]
+        if let Some(otel_cx) = otel_cx {
+            MessageTracer::end_span(
+                &otel_cx,
+                if status == Some(ConsumeConcurrentlyStatus::ConsumeSuccess) {
+                    SpanStatus::Ok
+                } else {
+                    SpanStatus::Error(return_type.to_string())
+                },
+            );
+        }
+
         if default_mqpush_consumer_impl.has_hook() {
             let cmc = consume_message_context.as_mut().unwrap();
             cmc.status = status.unwrap().to_string().into();
@@ -454,6 +493,14 @@ impl ConsumeRequest {
             default_mqpush_consumer_impl.execute_hook_after(&mut consume_message_context);
         }
 
+        if let Some(stats) = default_mqpush_consumer_impl.consumer_stats_manager() {
+            stats.inc_consume_rt(
+                self.consumer_group.as_str(),
+                self.message_queue.get_topic(),
+                consume_rt,
+            );
+        }
+
         if self.process_queue.is_dropped() {
             warn!(
                 "the message queue not be able to consume, because it's dropped. group={} {}",
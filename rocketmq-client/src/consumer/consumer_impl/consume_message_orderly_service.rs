@@ -52,6 +52,10 @@ use crate::consumer::message_queue_lock::MessageQueueLock;
 use crate::consumer::mq_consumer_inner::MQConsumerInnerLocal;
 use crate::hook::consume_message_context::ConsumeMessageContext;
 use crate::producer::mq_producer::MQProducer;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::message_tracer::MessageTracer;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::span::SpanStatus;
 
 static MAX_TIME_CONSUME_CONTINUOUSLY: Lazy<u64> = Lazy::new(|| {
     std::env::var("rocketmq.client.maxTimeConsumeContinuously")
@@ -289,6 +293,27 @@ impl ConsumeMessageOrderlyService {
         context: &ConsumeOrderlyContext,
         consume_request: &mut ConsumeRequest,
     ) -> bool {
+        if let Some(stats) = self
+            .default_mqpush_consumer_impl
+            .as_ref()
+            .and_then(|consumer| consumer.consumer_stats_manager())
+        {
+            let topic = consume_request.message_queue.get_topic();
+            let consumed = match status {
+                ConsumeOrderlyStatus::Success | ConsumeOrderlyStatus::Commit => true,
+                ConsumeOrderlyStatus::Rollback => context.is_auto_commit(),
+                ConsumeOrderlyStatus::SuspendCurrentQueueAMoment => false,
+            };
+            if consumed {
+                stats.inc_consume_ok_tps(self.consumer_group.as_str(), topic, msgs.len() as u64);
+            } else {
+                stats.inc_consume_failed_tps(
+                    self.consumer_group.as_str(),
+                    topic,
+                    msgs.len() as u64,
+                );
+            }
+        }
         let (continue_consume, commit_offset) = if context.is_auto_commit() {
             match status {
                 ConsumeOrderlyStatus::Success
@@ -612,11 +637,18 @@ impl ConsumeRequest {
                     .iter()
                     .map(|msg| msg.as_ref())
                     .collect::<Vec<&MessageExt>>()[..];
+                #[cfg(feature = "opentelemetry")]
+                let otel_cx = crate::trace::otel::global::tracer()
+                    .map(|tracer| tracer.start_process_span(self.consumer_group.as_str(), vec));
 
-                match consume_message_orderly_service_inner
-                    .message_listener
-                    .consume_message(vec, &mut context)
-                {
+                let consume_result = {
+                    #[cfg(feature = "opentelemetry")]
+                    let _otel_guard = otel_cx.clone().map(|cx| cx.attach());
+                    consume_message_orderly_service_inner
+                        .message_listener
+                        .consume_message(vec, &mut context)
+                };
+                match consume_result {
                     Ok(value) => {
                         status = Some(value);
                     }
@@ -663,6 +695,17 @@ impl ConsumeRequest {
                 if status.is_none() {
                     status = Some(ConsumeOrderlyStatus::SuspendCurrentQueueAMoment);
                 }
+                #[cfg(feature = "opentelemetry")]
+                if let Some(otel_cx) = otel_cx {
+                    MessageTracer::end_span(
+                        &otel_cx,
+                        match status {
+                            Some(ConsumeOrderlyStatus::Success)
+                            | Some(ConsumeOrderlyStatus::Commit) => SpanStatus::Ok,
+                            _ => SpanStatus::Error(return_type.to_string()),
+                        },
+                    );
+                }
                 if default_mqpush_consumer_impl.has_hook() {
                     let status = *status.as_ref().unwrap();
                     consume_message_context.as_mut().unwrap().success = status
@@ -671,6 +714,13 @@ impl ConsumeRequest {
                     consume_message_context.as_mut().unwrap().status = status.to_string().into();
                     default_mqpush_consumer_impl.execute_hook_after(&mut consume_message_context);
                 }
+                if let Some(stats) = default_mqpush_consumer_impl.consumer_stats_manager() {
+                    stats.inc_consume_rt(
+                        self.consumer_group.as_str(),
+                        self.message_queue.get_topic(),
+                        consume_rt,
+                    );
+                }
                 let continue_consume = consume_message_orderly_service_inner
                     .process_consume_result(
                         msgs,
@@ -0,0 +1,1407 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::base::service_state::ServiceState;
+use rocketmq_common::common::consumer::consume_from_where::ConsumeFromWhere;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::mix_all::DEFAULT_CONSUMER_GROUP;
+use rocketmq_common::common::sys_flag::pull_sys_flag::PullSysFlag;
+use rocketmq_common::common::FAQUrl;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_error::mq_client_err;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::process_queue_info::ProcessQueueInfo;
+use rocketmq_remoting::protocol::filter::filter_api::FilterAPI;
+use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
+use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
+use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
+use rocketmq_remoting::runtime::RPCHook;
+use rocketmq_rust::ArcMut;
+use tokio::runtime::Handle;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::debug;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::base::client_config::ClientConfig;
+use crate::base::validators::Validators;
+use crate::consumer::consumer_impl::assigned_message_queue::AssignedMessageQueue;
+use crate::consumer::consumer_impl::process_queue::ProcessQueue;
+use crate::consumer::consumer_impl::pull_api_wrapper::PullAPIWrapper;
+use crate::consumer::consumer_impl::pull_request_ext::PullResultExt;
+use crate::consumer::consumer_impl::re_balance::rebalance_lite_pull_impl::RebalanceLitePullImpl;
+use crate::consumer::consumer_impl::re_balance::Rebalance;
+use crate::consumer::default_lite_pull_consumer::LitePullConsumerConfig;
+use crate::consumer::message_selector::MessageSelector;
+use crate::consumer::mq_consumer_inner::MQConsumerInner;
+use crate::consumer::mq_consumer_inner::MQConsumerInnerImpl;
+use crate::consumer::pull_callback::PullCallback;
+use crate::consumer::pull_status::PullStatus;
+use crate::consumer::store::local_file_offset_store::LocalFileOffsetStore;
+use crate::consumer::store::offset_store::OffsetStore;
+use crate::consumer::store::read_offset_type::ReadOffsetType;
+use crate::consumer::store::remote_broker_offset_store::RemoteBrokerOffsetStore;
+use crate::consumer::topic_message_queue_change_listener::TopicMessageQueueChangeListener;
+use crate::factory::mq_client_instance::MQClientInstance;
+use crate::implementation::communication_mode::CommunicationMode;
+use crate::implementation::mq_client_manager::MQClientManager;
+
+const PULL_TIME_DELAY_MILLS_WHEN_PAUSE: u64 = 1000;
+const PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL: u64 = 50;
+const SCHEDULE_TASK_INITIAL_DELAY_MILLIS: u64 = 10 * 1000;
+const SUBSCRIPTION_CONFLICT_EXCEPTION_MESSAGE: &str =
+    "Subscribe and assign are mutually exclusive.";
+const _1MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SubscriptionType {
+    None,
+    Subscribe,
+    Assign,
+}
+
+/// A batch of pulled messages waiting in the prefetch cache to be returned by `poll`.
+struct ConsumeRequest {
+    messages: Vec<ArcMut<MessageExt>>,
+    message_queue: MessageQueue,
+    process_queue: Arc<ProcessQueue>,
+}
+
+/// Lite pull always pulls synchronously, so the callback handed to the pull API is never invoked.
+struct SyncPullCallback;
+
+impl PullCallback for SyncPullCallback {
+    async fn on_success(&mut self, _pull_result: PullResultExt) {}
+
+    fn on_exception(&mut self, _e: Box<dyn Error + Send>) {}
+}
+
+pub struct DefaultLitePullConsumerImpl {
+    client_config: ArcMut<ClientConfig>,
+    consumer_config: ArcMut<LitePullConsumerConfig>,
+    pub(crate) rebalance_impl: ArcMut<RebalanceLitePullImpl>,
+    rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+    service_state: ArcMut<ServiceState>,
+    pub(crate) client_instance: Option<ArcMut<MQClientInstance>>,
+    pull_api_wrapper: Option<ArcMut<PullAPIWrapper>>,
+    pub(crate) offset_store: Option<ArcMut<OffsetStore>>,
+    subscription_type: parking_lot::Mutex<SubscriptionType>,
+    assigned_message_queue: Arc<AssignedMessageQueue>,
+    task_table: parking_lot::Mutex<HashMap<MessageQueue, Arc<AtomicBool>>>,
+    consume_request_cache: parking_lot::Mutex<VecDeque<ConsumeRequest>>,
+    consume_request_notify: Notify,
+    topic_to_sub_expression: parking_lot::RwLock<HashMap<CheetahString, CheetahString>>,
+    topic_message_queue_change_listener_map:
+        parking_lot::RwLock<HashMap<CheetahString, Arc<dyn TopicMessageQueueChangeListener>>>,
+    message_queues_for_topic: parking_lot::RwLock<HashMap<CheetahString, HashSet<MessageQueue>>>,
+    next_auto_commit_deadline: AtomicU64,
+    obj_lock: tokio::sync::Mutex<()>,
+    default_lite_pull_consumer_impl: Option<ArcMut<DefaultLitePullConsumerImpl>>,
+    consumer_start_timestamp: u64,
+}
+
+impl DefaultLitePullConsumerImpl {
+    pub fn new(
+        client_config: ClientConfig,
+        consumer_config: ArcMut<LitePullConsumerConfig>,
+        rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+    ) -> Self {
+        let mut this = Self {
+            client_config: ArcMut::new(client_config.clone()),
+            consumer_config: consumer_config.clone(),
+            rebalance_impl: ArcMut::new(RebalanceLitePullImpl::new(client_config, consumer_config)),
+            rpc_hook,
+            service_state: ArcMut::new(ServiceState::CreateJust),
+            client_instance: None,
+            pull_api_wrapper: None,
+            offset_store: None,
+            subscription_type: parking_lot::Mutex::new(SubscriptionType::None),
+            assigned_message_queue: Arc::new(AssignedMessageQueue::new()),
+            task_table: Default::default(),
+            consume_request_cache: Default::default(),
+            consume_request_notify: Notify::new(),
+            topic_to_sub_expression: Default::default(),
+            topic_message_queue_change_listener_map: Default::default(),
+            message_queues_for_topic: Default::default(),
+            next_auto_commit_deadline: AtomicU64::new(0),
+            obj_lock: Default::default(),
+            default_lite_pull_consumer_impl: None,
+            consumer_start_timestamp: get_current_millis(),
+        };
+        let wrapper = ArcMut::downgrade(&this.rebalance_impl);
+        this.rebalance_impl.set_rebalance_impl(wrapper);
+        this
+    }
+
+    pub fn set_default_lite_pull_consumer_impl(
+        &mut self,
+        default_lite_pull_consumer_impl: ArcMut<DefaultLitePullConsumerImpl>,
+    ) {
+        self.rebalance_impl
+            .set_default_lite_pull_consumer_impl(default_lite_pull_consumer_impl.clone());
+        self.default_lite_pull_consumer_impl = Some(default_lite_pull_consumer_impl);
+    }
+
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        *self.service_state == ServiceState::Running
+    }
+}
+
+impl DefaultLitePullConsumerImpl {
+    pub async fn start(&mut self) -> rocketmq_error::RocketMQResult<()> {
+        match *self.service_state {
+            ServiceState::CreateJust => {
+                info!(
+                    "the lite pull consumer [{}] start beginning. message_model={}, isUnitMode={}",
+                    self.consumer_config.consumer_group,
+                    self.consumer_config.message_model,
+                    self.consumer_config.unit_mode
+                );
+                *self.service_state = ServiceState::StartFailed;
+                self.check_config()?;
+                if self.consumer_config.message_model == MessageModel::Clustering {
+                    self.client_config.change_instance_name_to_pid();
+                }
+                let client_instance = MQClientManager::get_instance()
+                    .get_or_create_mq_client_instance(
+                        self.client_config.as_ref().clone(),
+                        self.rpc_hook.clone(),
+                    )?;
+                self.client_instance = Some(client_instance.clone());
+                self.rebalance_impl
+                    .set_consumer_group(self.consumer_config.consumer_group.clone());
+                self.rebalance_impl
+                    .set_message_model(self.consumer_config.message_model);
+                self.rebalance_impl.set_allocate_message_queue_strategy(
+                    self.consumer_config
+                        .allocate_message_queue_strategy
+                        .clone()
+                        .expect(
+                            "allocate_message_queue_strategy is null, please set it before start",
+                        ),
+                );
+                self.rebalance_impl
+                    .set_mq_client_factory(client_instance.clone());
+                self.pull_api_wrapper = Some(ArcMut::new(PullAPIWrapper::new(
+                    client_instance.clone(),
+                    self.consumer_config.consumer_group.clone(),
+                    self.consumer_config.unit_mode,
+                )));
+                match self.consumer_config.message_model {
+                    MessageModel::Broadcasting => {
+                        self.offset_store = Some(ArcMut::new(OffsetStore::new_with_local(
+                            LocalFileOffsetStore::new(
+                                client_instance.clone(),
+                                self.consumer_config.consumer_group.clone(),
+                            ),
+                        )));
+                    }
+                    MessageModel::Clustering => {
+                        self.offset_store = Some(ArcMut::new(OffsetStore::new_with_remote(
+                            RemoteBrokerOffsetStore::new(
+                                client_instance.clone(),
+                                self.consumer_config.consumer_group.clone(),
+                            ),
+                        )));
+                    }
+                }
+                self.offset_store.as_mut().unwrap().load().await?;
+
+                let registered = self
+                    .client_instance
+                    .as_mut()
+                    .unwrap()
+                    .register_consumer(
+                        self.consumer_config.consumer_group.as_ref(),
+                        MQConsumerInnerImpl {
+                            default_mqpush_consumer_impl: None,
+                            default_lite_pull_consumer_impl: Some(
+                                self.default_lite_pull_consumer_impl
+                                    .clone()
+                                    .expect("default_lite_pull_consumer_impl is None"),
+                            ),
+                        },
+                    )
+                    .await;
+                if !registered {
+                    *self.service_state = ServiceState::CreateJust;
+                    return mq_client_err!(format!(
+                        "The consumer group[{}] has been created before, specify another name \
+                         please.{}",
+                        self.consumer_config.consumer_group,
+                        FAQUrl::suggest_todo(FAQUrl::GROUP_NAME_DUPLICATE_URL)
+                    ));
+                }
+                let cloned = self.client_instance.as_mut().cloned().unwrap();
+                self.client_instance.as_mut().unwrap().start(cloned).await?;
+                self.next_auto_commit_deadline.store(
+                    get_current_millis() + self.consumer_config.auto_commit_interval_millis,
+                    Ordering::Release,
+                );
+                self.start_schedule_task();
+                *self.service_state = ServiceState::Running;
+                info!(
+                    "the lite pull consumer [{}] start OK",
+                    self.consumer_config.consumer_group
+                );
+                self.operate_after_running().await
+            }
+            ServiceState::Running => {
+                mq_client_err!("The LitePullConsumer service state is Running")
+            }
+            ServiceState::ShutdownAlready => {
+                mq_client_err!("The LitePullConsumer service state is ShutdownAlready")
+            }
+            ServiceState::StartFailed => {
+                mq_client_err!(format!(
+                    "The LitePullConsumer service state not OK, maybe started once,{:?},{}",
+                    *self.service_state,
+                    FAQUrl::suggest_todo(FAQUrl::CLIENT_SERVICE_NOT_OK)
+                ))
+            }
+        }
+    }
+
+    pub async fn shutdown(&mut self) {
+        match *self.service_state {
+            ServiceState::CreateJust => {
+                warn!(
+                    "the lite pull consumer [{}] do not start, so do nothing",
+                    self.consumer_config.consumer_group
+                );
+            }
+            ServiceState::Running => {
+                if self.consumer_config.auto_commit {
+                    self.commit_all(false).await;
+                }
+                self.persist_consumer_offset().await;
+                for (_, cancelled) in self.task_table.lock().drain() {
+                    cancelled.store(true, Ordering::Release);
+                }
+                self.consume_request_cache.lock().clear();
+                let client = self.client_instance.as_mut().unwrap();
+                client
+                    .unregister_consumer(self.consumer_config.consumer_group.as_str())
+                    .await;
+                client.shutdown().await;
+                self.rebalance_impl.destroy();
+                *self.service_state = ServiceState::ShutdownAlready;
+                info!(
+                    "the lite pull consumer [{}] shutdown OK",
+                    self.consumer_config.consumer_group
+                );
+            }
+            ServiceState::ShutdownAlready => {
+                warn!(
+                    "the lite pull consumer [{}] has been shutdown, do nothing",
+                    self.consumer_config.consumer_group
+                );
+            }
+            ServiceState::StartFailed => {
+                warn!(
+                    "the lite pull consumer [{}] start failed, do nothing",
+                    self.consumer_config.consumer_group
+                );
+            }
+        }
+    }
+
+    fn check_config(&self) -> rocketmq_error::RocketMQResult<()> {
+        Validators::check_group(self.consumer_config.consumer_group.as_str())?;
+        if self.consumer_config.consumer_group == DEFAULT_CONSUMER_GROUP {
+            return mq_client_err!(format!(
+                "consumer_group can not equal {} please specify another one.{}",
+                DEFAULT_CONSUMER_GROUP,
+                FAQUrl::suggest_todo(FAQUrl::CLIENT_PARAMETER_CHECK_URL)
+            ));
+        }
+        if self
+            .consumer_config
+            .allocate_message_queue_strategy
+            .is_none()
+        {
+            return mq_client_err!(format!(
+                "allocate_message_queue_strategy is null{}",
+                FAQUrl::suggest_todo(FAQUrl::CLIENT_PARAMETER_CHECK_URL)
+            ));
+        }
+        if self.consumer_config.pull_batch_size < 1 || self.consumer_config.pull_batch_size > 1024 {
+            return mq_client_err!(format!(
+                "pullBatchSize Out of range [1, 1024]{}",
+                FAQUrl::suggest_todo(FAQUrl::CLIENT_PARAMETER_CHECK_URL)
+            ));
+        }
+        if self.consumer_config.consumer_timeout_millis_when_suspend
+            < self.consumer_config.broker_suspend_max_time_millis
+        {
+            return mq_client_err!(format!(
+                "Long polling mode, the consumer consumer_timeout_millis_when_suspend must \
+                 greater than broker_suspend_max_time_millis{}",
+                FAQUrl::suggest_todo(FAQUrl::CLIENT_PARAMETER_CHECK_URL)
+            ));
+        }
+        Ok(())
+    }
+
+    async fn operate_after_running(&mut self) -> rocketmq_error::RocketMQResult<()> {
+        let subscription_type = *self.subscription_type.lock();
+        match subscription_type {
+            SubscriptionType::Subscribe => {
+                self.update_topic_subscribe_info_when_subscription_changed()
+                    .await;
+            }
+            SubscriptionType::Assign => {
+                self.update_assign_pull_task(&self.assigned_message_queue.message_queues());
+            }
+            SubscriptionType::None => {}
+        }
+        let topics = self
+            .topic_message_queue_change_listener_map
+            .read()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        for topic in topics {
+            let message_queues = self.fetch_message_queues(&topic).await?;
+            self.message_queues_for_topic
+                .write()
+                .insert(topic, message_queues);
+        }
+        let client_instance = self.client_instance.as_mut().unwrap();
+        client_instance.check_client_in_broker().await?;
+        if subscription_type == SubscriptionType::Subscribe
+            && client_instance
+                .send_heartbeat_to_all_broker_with_lock()
+                .await
+        {
+            client_instance.re_balance_immediately();
+        }
+        Ok(())
+    }
+
+    fn start_schedule_task(&self) {
+        let this = self
+            .default_lite_pull_consumer_impl
+            .clone()
+            .expect("default_lite_pull_consumer_impl is None");
+        let interval =
+            Duration::from_millis(self.consumer_config.topic_metadata_check_interval_millis);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(SCHEDULE_TASK_INITIAL_DELAY_MILLIS)).await;
+            while this.is_running() {
+                this.fetch_topic_message_queues_and_compare().await;
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+
+    async fn fetch_topic_message_queues_and_compare(&self) {
+        let listeners = self
+            .topic_message_queue_change_listener_map
+            .read()
+            .iter()
+            .map(|(topic, listener)| (topic.clone(), listener.clone()))
+            .collect::<Vec<_>>();
+        for (topic, listener) in listeners {
+            match self.fetch_message_queues(&topic).await {
+                Ok(message_queues) => {
+                    let changed = {
+                        let mut message_queues_for_topic = self.message_queues_for_topic.write();
+                        if message_queues_for_topic.get(&topic) != Some(&message_queues) {
+                            message_queues_for_topic.insert(topic.clone(), message_queues.clone());
+                            true
+                        } else {
+                            false
+                        }
+                    };
+                    if changed {
+                        listener.on_changed(topic.as_str(), message_queues);
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "ScheduledTask fetchMessageQueuesAndCompare exception, topic={}, {}",
+                        topic, e
+                    );
+                }
+            }
+        }
+    }
+
+    async fn update_topic_subscribe_info_when_subscription_changed(&self) {
+        let sub_table = self.rebalance_impl.get_subscription_inner();
+        let topics = sub_table.read().await.keys().cloned().collect::<Vec<_>>();
+        let client = self.client_instance.as_ref().unwrap().mut_from_ref();
+        for topic in topics {
+            client
+                .update_topic_route_info_from_name_server_topic(&topic)
+                .await;
+        }
+    }
+
+    fn make_sure_state_ok(&self) -> rocketmq_error::RocketMQResult<()> {
+        if *self.service_state != ServiceState::Running {
+            return mq_client_err!(format!(
+                "The consumer service state not OK, {},{}",
+                *self.service_state,
+                FAQUrl::suggest_todo(FAQUrl::CLIENT_SERVICE_NOT_OK)
+            ));
+        }
+        Ok(())
+    }
+
+    fn set_subscription_type(
+        &self,
+        subscription_type: SubscriptionType,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let mut current = self.subscription_type.lock();
+        if *current == SubscriptionType::None {
+            *current = subscription_type;
+        } else if *current != subscription_type {
+            return mq_client_err!(SUBSCRIPTION_CONFLICT_EXCEPTION_MESSAGE);
+        }
+        Ok(())
+    }
+}
+
+impl DefaultLitePullConsumerImpl {
+    pub async fn subscribe(
+        &self,
+        topic: CheetahString,
+        sub_expression: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        if topic.is_empty() {
+            return mq_client_err!("Topic can not be null or empty.");
+        }
+        self.set_subscription_type(SubscriptionType::Subscribe)?;
+        let subscription_data = match FilterAPI::build_subscription_data(&topic, &sub_expression) {
+            Ok(subscription_data) => subscription_data,
+            Err(e) => return mq_client_err!(format!("buildSubscriptionData exception, {}", e)),
+        };
+        self.rebalance_impl
+            .put_subscription_data(topic, subscription_data)
+            .await;
+        self.after_subscription_changed().await;
+        Ok(())
+    }
+
+    pub async fn subscribe_with_selector(
+        &self,
+        topic: CheetahString,
+        message_selector: Option<MessageSelector>,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let Some(message_selector) = message_selector else {
+            return self
+                .subscribe(
+                    topic,
+                    CheetahString::from_static_str(SubscriptionData::SUB_ALL),
+                )
+                .await;
+        };
+        if topic.is_empty() {
+            return mq_client_err!("Topic can not be null or empty.");
+        }
+        self.set_subscription_type(SubscriptionType::Subscribe)?;
+        let subscription_data = match FilterAPI::build(
+            &topic,
+            &CheetahString::from_slice(message_selector.get_expression()),
+            Some(CheetahString::from_slice(
+                message_selector.get_expression_type(),
+            )),
+        ) {
+            Ok(subscription_data) => subscription_data,
+            Err(e) => return mq_client_err!(format!("subscription exception, {}", e)),
+        };
+        self.rebalance_impl
+            .put_subscription_data(topic, subscription_data)
+            .await;
+        self.after_subscription_changed().await;
+        Ok(())
+    }
+
+    async fn after_subscription_changed(&self) {
+        if self.is_running() {
+            let client_instance = self.client_instance.as_ref().unwrap().mut_from_ref();
+            client_instance
+                .send_heartbeat_to_all_broker_with_lock()
+                .await;
+            self.update_topic_subscribe_info_when_subscription_changed()
+                .await;
+            client_instance.re_balance_immediately();
+        }
+    }
+
+    pub async fn unsubscribe(&self, topic: CheetahString) {
+        self.rebalance_impl.remove_subscription_data(&topic).await;
+        self.remove_pull_task(&topic);
+        self.assigned_message_queue.remove_assign(&topic);
+    }
+
+    pub fn assignment(&self) -> rocketmq_error::RocketMQResult<HashSet<MessageQueue>> {
+        self.make_sure_state_ok()?;
+        Ok(self.assigned_message_queue.message_queues())
+    }
+
+    pub async fn assign(
+        &self,
+        message_queues: Vec<MessageQueue>,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        if message_queues.is_empty() {
+            return mq_client_err!("Message queues can not be null or empty.");
+        }
+        self.set_subscription_type(SubscriptionType::Assign)?;
+        let message_queues = message_queues.into_iter().collect::<HashSet<_>>();
+        self.assigned_message_queue
+            .update_assign_all(&message_queues);
+        if self.is_running() {
+            self.update_assign_pull_task(&message_queues);
+        }
+        Ok(())
+    }
+
+    pub fn set_sub_expression_for_assign(
+        &self,
+        topic: CheetahString,
+        sub_expression: CheetahString,
+    ) {
+        if !sub_expression.trim().is_empty() {
+            self.topic_to_sub_expression
+                .write()
+                .insert(topic, sub_expression);
+        }
+    }
+
+    pub fn pause(&self, message_queues: &[MessageQueue]) {
+        self.assigned_message_queue.pause(message_queues);
+    }
+
+    pub fn resume(&self, message_queues: &[MessageQueue]) {
+        self.assigned_message_queue.resume(message_queues);
+    }
+
+    /// Returns the messages of the next cached pull result, waiting at most `timeout` millis for
+    /// one to arrive.
+    pub async fn poll(&self, timeout: u64) -> rocketmq_error::RocketMQResult<Vec<MessageExt>> {
+        self.make_sure_state_ok()?;
+        if self.consumer_config.auto_commit {
+            self.maybe_auto_commit().await;
+        }
+        let deadline = Instant::now() + Duration::from_millis(timeout);
+        loop {
+            {
+                let _lock = self.obj_lock.lock().await;
+                let consume_request = self.consume_request_cache.lock().pop_front();
+                if let Some(consume_request) = consume_request {
+                    if consume_request.process_queue.is_dropped() {
+                        continue;
+                    }
+                    let offset = consume_request
+                        .process_queue
+                        .remove_message(&consume_request.messages)
+                        .await;
+                    self.assigned_message_queue
+                        .update_consume_offset(&consume_request.message_queue, offset);
+                    return Ok(consume_request
+                        .messages
+                        .iter()
+                        .map(|message| message.as_ref().clone())
+                        .collect());
+                }
+            }
+            if tokio::time::timeout_at(deadline, self.consume_request_notify.notified())
+                .await
+                .is_err()
+            {
+                return Ok(vec![]);
+            }
+        }
+    }
+
+    async fn maybe_auto_commit(&self) {
+        let now = get_current_millis();
+        if now >= self.next_auto_commit_deadline.load(Ordering::Acquire) {
+            self.commit_all(false).await;
+            self.next_auto_commit_deadline.store(
+                now + self.consumer_config.auto_commit_interval_millis,
+                Ordering::Release,
+            );
+        }
+    }
+
+    pub async fn seek(
+        &self,
+        message_queue: &MessageQueue,
+        offset: i64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        self.make_sure_state_ok()?;
+        if !self.assigned_message_queue.contains(message_queue) {
+            if *self.subscription_type.lock() == SubscriptionType::Subscribe {
+                return mq_client_err!(format!(
+                    "The message queue is not in assigned list, may be rebalancing, message \
+                     queue: {}",
+                    message_queue
+                ));
+            }
+            return mq_client_err!(format!(
+                "The message queue is not in assigned list, message queue: {}",
+                message_queue
+            ));
+        }
+        let min_offset = self.min_offset(message_queue).await?;
+        let max_offset = self.max_offset(message_queue).await?;
+        if offset < min_offset || offset > max_offset {
+            return mq_client_err!(format!(
+                "Seek offset illegal, seek offset = {}, min offset = {}, max offset = {}",
+                offset, min_offset, max_offset
+            ));
+        }
+        let _lock = self.obj_lock.lock().await;
+        self.clear_message_queue_in_cache(message_queue).await;
+        self.assigned_message_queue
+            .set_seek_offset(message_queue, offset);
+        Ok(())
+    }
+
+    pub async fn seek_to_begin(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        self.make_sure_state_ok()?;
+        let begin = self.min_offset(message_queue).await?;
+        self.seek(message_queue, begin).await
+    }
+
+    pub async fn seek_to_end(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        self.make_sure_state_ok()?;
+        let end = self.max_offset(message_queue).await?;
+        self.seek(message_queue, end).await
+    }
+
+    async fn clear_message_queue_in_cache(&self, message_queue: &MessageQueue) {
+        if let Some(process_queue) = self.assigned_message_queue.get_process_queue(message_queue) {
+            process_queue.clear().await;
+        }
+        self.consume_request_cache
+            .lock()
+            .retain(|consume_request| consume_request.message_queue != *message_queue);
+    }
+
+    async fn min_offset(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_admin_impl
+            .mut_from_ref()
+            .min_offset(message_queue)
+            .await
+    }
+
+    async fn max_offset(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_admin_impl
+            .mut_from_ref()
+            .max_offset(message_queue)
+            .await
+    }
+
+    pub async fn search_offset(
+        &self,
+        message_queue: &MessageQueue,
+        timestamp: u64,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        self.make_sure_state_ok()?;
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_admin_impl
+            .mut_from_ref()
+            .search_offset(message_queue, timestamp)
+            .await
+    }
+
+    pub async fn fetch_message_queues(
+        &self,
+        topic: &str,
+    ) -> rocketmq_error::RocketMQResult<HashSet<MessageQueue>> {
+        self.make_sure_state_ok()?;
+        self.client_instance
+            .as_ref()
+            .unwrap()
+            .mq_admin_impl
+            .mut_from_ref()
+            .fetch_subscribe_message_queues(topic)
+            .await
+    }
+
+    pub async fn committed(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        self.make_sure_state_ok()?;
+        let offset = self
+            .offset_store
+            .as_ref()
+            .unwrap()
+            .read_offset(message_queue, ReadOffsetType::MemoryFirstThenStore)
+            .await;
+        if offset == -2 {
+            return mq_client_err!("Fetch consume offset from broker exception");
+        }
+        Ok(offset)
+    }
+
+    /// Writes the consume offset of every assigned queue into the offset store. The offsets are
+    /// only sent to the broker right away when `persist` is set or in broadcasting mode.
+    pub async fn commit_all(&self, persist: bool) {
+        if let Err(e) = self.make_sure_state_ok() {
+            error!("commit offsets failed: {}", e);
+            return;
+        }
+        let message_queues = self.assigned_message_queue.message_queues();
+        for message_queue in &message_queues {
+            let consume_offset = self
+                .assigned_message_queue
+                .get_consume_offset(message_queue);
+            if consume_offset != -1 {
+                self.update_consume_offset(message_queue, consume_offset)
+                    .await;
+            }
+        }
+        if persist || self.consumer_config.message_model == MessageModel::Broadcasting {
+            self.persist_offsets(&message_queues).await;
+        }
+    }
+
+    pub async fn commit_with_map(&self, offset_map: HashMap<MessageQueue, i64>, persist: bool) {
+        if let Err(e) = self.make_sure_state_ok() {
+            error!("commit offsets failed: {}", e);
+            return;
+        }
+        for (message_queue, offset) in &offset_map {
+            if *offset != -1 {
+                self.update_consume_offset(message_queue, *offset).await;
+            } else {
+                error!("consumerOffset is -1 in messageQueue [{}].", message_queue);
+            }
+        }
+        if persist {
+            self.persist_offsets(&offset_map.into_keys().collect())
+                .await;
+        }
+    }
+
+    pub async fn commit_with_set(&self, message_queues: HashSet<MessageQueue>, persist: bool) {
+        if let Err(e) = self.make_sure_state_ok() {
+            error!("commit offsets failed: {}", e);
+            return;
+        }
+        for message_queue in &message_queues {
+            let consume_offset = self
+                .assigned_message_queue
+                .get_consume_offset(message_queue);
+            if consume_offset != -1 {
+                self.update_consume_offset(message_queue, consume_offset)
+                    .await;
+            }
+        }
+        if persist {
+            self.persist_offsets(&message_queues).await;
+        }
+    }
+
+    async fn update_consume_offset(&self, message_queue: &MessageQueue, offset: i64) {
+        if let Some(process_queue) = self.assigned_message_queue.get_process_queue(message_queue) {
+            if !process_queue.is_dropped() {
+                self.offset_store
+                    .as_ref()
+                    .unwrap()
+                    .update_offset(message_queue, offset, false)
+                    .await;
+            }
+        }
+    }
+
+    async fn persist_offsets(&self, message_queues: &HashSet<MessageQueue>) {
+        self.offset_store
+            .as_ref()
+            .unwrap()
+            .mut_from_ref()
+            .persist_all(message_queues)
+            .await;
+    }
+
+    pub async fn register_topic_message_queue_change_listener(
+        &self,
+        topic: CheetahString,
+        listener: Arc<dyn TopicMessageQueueChangeListener>,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        if topic.is_empty() {
+            return mq_client_err!("Topic can not be null or empty.");
+        }
+        let previous = self
+            .topic_message_queue_change_listener_map
+            .write()
+            .insert(topic.clone(), listener);
+        if previous.is_some() {
+            warn!(
+                "Topic {} had been registered, new listener will overwrite the old one",
+                topic
+            );
+        }
+        if self.is_running() {
+            let message_queues = self.fetch_message_queues(&topic).await?;
+            self.message_queues_for_topic
+                .write()
+                .insert(topic, message_queues);
+        }
+        Ok(())
+    }
+
+    pub async fn update_name_server_address(&mut self, name_server_address: &str) {
+        self.client_config.namesrv_addr = Some(CheetahString::from_slice(name_server_address));
+        if let Some(ref client_instance) = self.client_instance {
+            if let Some(ref mq_client_api_impl) = client_instance.mq_client_api_impl {
+                mq_client_api_impl
+                    .update_name_server_address_list(name_server_address)
+                    .await;
+            }
+        }
+    }
+}
+
+impl DefaultLitePullConsumerImpl {
+    /// Called by the rebalance whenever the queues allocated for `topic` change.
+    pub(crate) fn update_assign_queue_and_start_pull_task(
+        &self,
+        topic: &str,
+        mq_all: &HashSet<MessageQueue>,
+        mq_divided: &HashSet<MessageQueue>,
+        process_queues: &HashMap<MessageQueue, Arc<ProcessQueue>>,
+    ) {
+        let assigned = match self.consumer_config.message_model {
+            MessageModel::Broadcasting => mq_all,
+            MessageModel::Clustering => mq_divided,
+        };
+        self.assigned_message_queue
+            .update_assign(topic, assigned, process_queues);
+        self.update_pull_task(topic, assigned);
+    }
+
+    fn update_pull_task(&self, topic: &str, message_queues: &HashSet<MessageQueue>) {
+        self.task_table.lock().retain(|message_queue, cancelled| {
+            if message_queue.get_topic() == topic && !message_queues.contains(message_queue) {
+                cancelled.store(true, Ordering::Release);
+                false
+            } else {
+                true
+            }
+        });
+        self.start_pull_task(message_queues);
+    }
+
+    fn update_assign_pull_task(&self, message_queues: &HashSet<MessageQueue>) {
+        self.task_table.lock().retain(|message_queue, cancelled| {
+            if message_queues.contains(message_queue) {
+                true
+            } else {
+                cancelled.store(true, Ordering::Release);
+                false
+            }
+        });
+        self.start_pull_task(message_queues);
+    }
+
+    fn remove_pull_task(&self, topic: &str) {
+        self.task_table.lock().retain(|message_queue, cancelled| {
+            if message_queue.get_topic() == topic {
+                cancelled.store(true, Ordering::Release);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    fn start_pull_task(&self, message_queues: &HashSet<MessageQueue>) {
+        let mut task_table = self.task_table.lock();
+        for message_queue in message_queues {
+            if task_table.contains_key(message_queue) {
+                continue;
+            }
+            let cancelled = Arc::new(AtomicBool::new(false));
+            task_table.insert(message_queue.clone(), cancelled.clone());
+            let this = self
+                .default_lite_pull_consumer_impl
+                .clone()
+                .expect("default_lite_pull_consumer_impl is None");
+            let message_queue = message_queue.clone();
+            tokio::spawn(async move {
+                while !cancelled.load(Ordering::Acquire) {
+                    match this.pull_once(&message_queue).await {
+                        Some(0) => {}
+                        Some(delay) => tokio::time::sleep(Duration::from_millis(delay)).await,
+                        None => break,
+                    }
+                }
+                this.task_table
+                    .lock()
+                    .retain(|key, value| key != &message_queue || !Arc::ptr_eq(value, &cancelled));
+            });
+        }
+    }
+
+    /// Runs one pull of `message_queue`. Returns the delay before the next pull, or `None` when
+    /// the queue is no longer pulled by this consumer.
+    async fn pull_once(&self, message_queue: &MessageQueue) -> Option<u64> {
+        if !self.is_running() {
+            return None;
+        }
+        if self.assigned_message_queue.is_paused(message_queue) {
+            debug!("Message Queue: {} has been paused!", message_queue);
+            return Some(PULL_TIME_DELAY_MILLS_WHEN_PAUSE);
+        }
+        let process_queue = self
+            .assigned_message_queue
+            .get_process_queue(message_queue)?;
+        if process_queue.is_dropped() {
+            info!(
+                "The message queue not be able to poll, because it's dropped. {}",
+                message_queue
+            );
+            return None;
+        }
+        process_queue.set_last_pull_timestamp(get_current_millis());
+
+        let config = &self.consumer_config;
+        let cached_request_count = self.consume_request_cache.lock().len() as u64;
+        if cached_request_count * config.pull_batch_size as u64 > config.pull_threshold_for_all {
+            warn!(
+                "The consume request count exceeds threshold {}, so do flow control, consume \
+                 request count={}",
+                config.pull_threshold_for_all, cached_request_count
+            );
+            return Some(PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL);
+        }
+        let cached_message_count = process_queue.msg_count();
+        let cached_message_size_in_mib = process_queue.msg_size() / _1MB;
+        if cached_message_count > config.pull_threshold_for_queue as u64 {
+            warn!(
+                "The cached message count exceeds the threshold {}, so do flow control, count={}, \
+                 size={} MiB, mq={}",
+                config.pull_threshold_for_queue,
+                cached_message_count,
+                cached_message_size_in_mib,
+                message_queue
+            );
+            return Some(PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL);
+        }
+        if cached_message_size_in_mib > config.pull_threshold_size_for_queue as u64 {
+            warn!(
+                "The cached message size exceeds the threshold {} MiB, so do flow control, \
+                 count={}, size={} MiB, mq={}",
+                config.pull_threshold_size_for_queue,
+                cached_message_count,
+                cached_message_size_in_mib,
+                message_queue
+            );
+            return Some(PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL);
+        }
+        let max_span = process_queue.get_max_span().await;
+        if max_span > config.consume_max_span as u64 {
+            warn!(
+                "The queue's messages, span too long, so do flow control, maxSpan={}, mq={}",
+                max_span, message_queue
+            );
+            return Some(PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL);
+        }
+
+        let result = match self.next_pull_offset(message_queue).await {
+            Ok(offset) => self.pull(message_queue, offset, &process_queue).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => Some(0),
+            Err(e) => {
+                error!(
+                    "An error occurred in pull message process. mq={}, {}",
+                    message_queue, e
+                );
+                Some(config.pull_time_delay_mills_when_exception)
+            }
+        }
+    }
+
+    async fn next_pull_offset(
+        &self,
+        message_queue: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        {
+            let _lock = self.obj_lock.lock().await;
+            let seek_offset = self.assigned_message_queue.get_seek_offset(message_queue);
+            if seek_offset != -1 {
+                self.assigned_message_queue
+                    .update_consume_offset(message_queue, seek_offset);
+                self.assigned_message_queue
+                    .set_seek_offset(message_queue, -1);
+                return Ok(seek_offset);
+            }
+        }
+        let offset = self.assigned_message_queue.get_pull_offset(message_queue);
+        if offset != -1 {
+            return Ok(offset);
+        }
+        self.rebalance_impl
+            .mut_from_ref()
+            .compute_pull_from_where_with_exception(message_queue)
+            .await
+    }
+
+    async fn subscription_data(
+        &self,
+        topic: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<SubscriptionData> {
+        let subscription_type = *self.subscription_type.lock();
+        if subscription_type == SubscriptionType::Subscribe {
+            let subscription_inner = self.rebalance_impl.get_subscription_inner();
+            let subscription_data = subscription_inner.read().await.get(topic).cloned();
+            if let Some(subscription_data) = subscription_data {
+                return Ok(subscription_data);
+            }
+        }
+        let sub_expression = self
+            .topic_to_sub_expression
+            .read()
+            .get(topic)
+            .cloned()
+            .unwrap_or_else(|| CheetahString::from_static_str(SubscriptionData::SUB_ALL));
+        match FilterAPI::build_subscription_data(topic, &sub_expression) {
+            Ok(subscription_data) => Ok(subscription_data),
+            Err(e) => mq_client_err!(format!("buildSubscriptionData exception, {}", e)),
+        }
+    }
+
+    async fn pull(
+        &self,
+        message_queue: &MessageQueue,
+        offset: i64,
+        process_queue: &Arc<ProcessQueue>,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let subscription_data = self.subscription_data(message_queue.get_topic_cs()).await?;
+        let sys_flag = PullSysFlag::build_sys_flag_with_lite_pull(false, true, true, false, true);
+        let pull_api_wrapper = self.pull_api_wrapper.as_ref().unwrap().mut_from_ref();
+        let pull_result = pull_api_wrapper
+            .pull_kernel_impl(
+                message_queue,
+                subscription_data.sub_string.clone(),
+                subscription_data.expression_type.clone(),
+                subscription_data.sub_version,
+                offset,
+                self.consumer_config.pull_batch_size as i32,
+                i32::MAX,
+                sys_flag as i32,
+                0,
+                self.consumer_config.broker_suspend_max_time_millis,
+                self.consumer_config.consumer_timeout_millis_when_suspend,
+                CommunicationMode::Sync,
+                SyncPullCallback,
+            )
+            .await?;
+        let Some(mut pull_result) = pull_result else {
+            return mq_client_err!("The pull result of a sync pull is empty");
+        };
+        pull_api_wrapper.process_pull_result(message_queue, &mut pull_result, &subscription_data);
+
+        let _lock = self.obj_lock.lock().await;
+        if self.assigned_message_queue.get_seek_offset(message_queue) != -1 {
+            // a seek happened during the pull, so the result is stale
+            return Ok(());
+        }
+        let pull_result = pull_result.pull_result;
+        match pull_result.pull_status {
+            PullStatus::Found => {
+                if let Some(messages) = pull_result.msg_found_list {
+                    if !messages.is_empty() {
+                        process_queue.put_message(messages.clone()).await;
+                        self.consume_request_cache.lock().push_back(ConsumeRequest {
+                            messages,
+                            message_queue: message_queue.clone(),
+                            process_queue: process_queue.clone(),
+                        });
+                        self.consume_request_notify.notify_one();
+                    }
+                }
+            }
+            PullStatus::OffsetIllegal => {
+                warn!(
+                    "The pull request offset illegal, mq={}, offset={}, next begin offset={}",
+                    message_queue, offset, pull_result.next_begin_offset
+                );
+            }
+            _ => {}
+        }
+        self.assigned_message_queue.update_pull_offset(
+            message_queue,
+            pull_result.next_begin_offset as i64,
+            process_queue,
+        );
+        Ok(())
+    }
+}
+
+impl MQConsumerInner for DefaultLitePullConsumerImpl {
+    fn group_name(&self) -> CheetahString {
+        self.consumer_config.consumer_group().clone()
+    }
+
+    fn message_model(&self) -> MessageModel {
+        self.consumer_config.message_model
+    }
+
+    fn consume_type(&self) -> ConsumeType {
+        ConsumeType::ConsumeActively
+    }
+
+    fn consume_from_where(&self) -> ConsumeFromWhere {
+        self.consumer_config.consume_from_where
+    }
+
+    fn subscriptions(&self) -> HashSet<SubscriptionData> {
+        let inner = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .subscription_inner
+            .clone();
+
+        let handle = Handle::current();
+        thread::spawn(move || {
+            handle.block_on(async move {
+                let inner = inner.read().await;
+                inner.values().cloned().collect()
+            })
+        })
+        .join()
+        .unwrap()
+    }
+
+    fn do_rebalance(&self) {
+        let rebalance_impl = self.rebalance_impl.clone();
+        tokio::spawn(async move {
+            rebalance_impl.mut_from_ref().do_rebalance(false).await;
+        });
+    }
+
+    async fn try_rebalance(&self) -> rocketmq_error::RocketMQResult<bool> {
+        Ok(self.rebalance_impl.mut_from_ref().do_rebalance(false).await)
+    }
+
+    async fn persist_consumer_offset(&self) {
+        if let Err(err) = self.make_sure_state_ok() {
+            error!(
+                "group: {} persistConsumerOffset exception:{}",
+                self.consumer_config.consumer_group, err
+            );
+            return;
+        }
+        let subscription_type = *self.subscription_type.lock();
+        let message_queues = match subscription_type {
+            SubscriptionType::Subscribe => self
+                .rebalance_impl
+                .rebalance_impl_inner
+                .process_queue_table
+                .read()
+                .await
+                .keys()
+                .cloned()
+                .collect::<HashSet<_>>(),
+            SubscriptionType::Assign => self.assigned_message_queue.message_queues(),
+            SubscriptionType::None => HashSet::new(),
+        };
+        self.persist_offsets(&message_queues).await;
+    }
+
+    async fn update_topic_subscribe_info(
+        &self,
+        topic: CheetahString,
+        info: &HashSet<MessageQueue>,
+    ) {
+        let sub_table = self.rebalance_impl.get_subscription_inner();
+        let sub_table_inner = sub_table.read().await;
+        if sub_table_inner.contains_key(&topic) {
+            let mut guard = self
+                .rebalance_impl
+                .rebalance_impl_inner
+                .topic_subscribe_info_table
+                .write()
+                .await;
+            guard.insert(topic, info.clone());
+        }
+    }
+
+    async fn is_subscribe_topic_need_update(&self, topic: &str) -> bool {
+        let sub_table = self.rebalance_impl.get_subscription_inner();
+        let sub_table_inner = sub_table.read().await;
+        if sub_table_inner.contains_key(topic) {
+            drop(sub_table_inner);
+            let guard = self
+                .rebalance_impl
+                .rebalance_impl_inner
+                .topic_subscribe_info_table
+                .read()
+                .await;
+            return !guard.contains_key(topic);
+        }
+        false
+    }
+
+    fn is_unit_mode(&self) -> bool {
+        self.consumer_config.unit_mode
+    }
+
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo {
+        let mut info = ConsumerRunningInfo::new();
+        let consumer_config = &self.consumer_config;
+        let properties = [
+            ("consumerGroup", consumer_config.consumer_group.to_string()),
+            ("messageModel", consumer_config.message_model.to_string()),
+            ("autoCommit", consumer_config.auto_commit.to_string()),
+            (
+                "autoCommitIntervalMillis",
+                consumer_config.auto_commit_interval_millis.to_string(),
+            ),
+            ("pullBatchSize", consumer_config.pull_batch_size.to_string()),
+            (
+                "pullThresholdForQueue",
+                consumer_config.pull_threshold_for_queue.to_string(),
+            ),
+            (
+                "pullThresholdSizeForQueue",
+                consumer_config.pull_threshold_size_for_queue.to_string(),
+            ),
+            (
+                "pollTimeoutMillis",
+                consumer_config.poll_timeout_millis.to_string(),
+            ),
+            (
+                ConsumerRunningInfo::PROP_CONSUMER_START_TIMESTAMP,
+                self.consumer_start_timestamp.to_string(),
+            ),
+        ];
+        for (key, value) in properties {
+            info.set_property(key, value);
+        }
+
+        info.subscription_set = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .subscription_inner
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect();
+
+        let process_queue_table = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .process_queue_table
+            .read()
+            .await
+            .clone();
+        for (mq, pq) in process_queue_table {
+            let mut pq_info = ProcessQueueInfo::default();
+            if let Some(ref offset_store) = self.offset_store {
+                pq_info.commit_offset = offset_store
+                    .read_offset(&mq, ReadOffsetType::MemoryFirstThenStore)
+                    .await
+                    .max(0) as u64;
+            }
+            pq.fill_process_queue_info(&mut pq_info).await;
+            info.mq_table.insert(mq, pq_info);
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_consumer_impl() -> ArcMut<DefaultLitePullConsumerImpl> {
+        let mut consumer_impl = ArcMut::new(DefaultLitePullConsumerImpl::new(
+            ClientConfig::default(),
+            ArcMut::new(LitePullConsumerConfig::default()),
+            None,
+        ));
+        let wrapper = consumer_impl.clone();
+        consumer_impl.set_default_lite_pull_consumer_impl(wrapper);
+        consumer_impl
+    }
+
+    #[tokio::test]
+    async fn subscribe_and_assign_are_mutually_exclusive() {
+        let consumer_impl = new_consumer_impl();
+        consumer_impl
+            .assign(vec![MessageQueue::from_parts("topic", "broker-a", 0)])
+            .await
+            .unwrap();
+        let result = consumer_impl
+            .subscribe(
+                CheetahString::from_static_str("topic"),
+                CheetahString::from_static_str("*"),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn assign_rejects_empty_queues() {
+        let consumer_impl = new_consumer_impl();
+        assert!(consumer_impl.assign(vec![]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn poll_requires_running_consumer() {
+        let consumer_impl = new_consumer_impl();
+        assert!(consumer_impl.poll(10).await.is_err());
+    }
+}
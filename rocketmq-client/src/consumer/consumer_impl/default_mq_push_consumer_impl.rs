@@ -42,6 +42,8 @@ use rocketmq_error::mq_client_err;
 use rocketmq_error::ClientErr;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
 use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::pop_process_queue_info::PopProcessQueueInfo;
+use rocketmq_remoting::protocol::body::process_queue_info::ProcessQueueInfo;
 use rocketmq_remoting::protocol::filter::filter_api::FilterAPI;
 use rocketmq_remoting::protocol::header::ack_message_request_header::AckMessageRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_request_header::ChangeInvisibleTimeRequestHeader;
@@ -77,6 +79,7 @@ use crate::consumer::consumer_impl::re_balance::rebalance_push_impl::RebalancePu
 use crate::consumer::consumer_impl::re_balance::Rebalance;
 use crate::consumer::default_mq_push_consumer::ConsumerConfig;
 use crate::consumer::listener::message_listener::MessageListener;
+use crate::consumer::message_selector::MessageSelector;
 use crate::consumer::mq_consumer_inner::MQConsumerInner;
 use crate::consumer::mq_consumer_inner::MQConsumerInnerImpl;
 use crate::consumer::pop_callback::DefaultPopCallback;
@@ -95,6 +98,7 @@ use crate::hook::filter_message_hook::FilterMessageHook;
 use crate::implementation::communication_mode::CommunicationMode;
 use crate::implementation::mq_client_manager::MQClientManager;
 use crate::producer::mq_producer::MQProducer;
+use crate::stat::consumer_stats_manager::ConsumerStatsManager;
 
 const PULL_TIME_DELAY_MILLS_WHEN_CACHE_FLOW_CONTROL: u64 = 50;
 pub(crate) const PULL_TIME_DELAY_MILLS_WHEN_BROKER_FLOW_CONTROL: u64 = 20;
@@ -143,6 +147,7 @@ pub struct DefaultMQPushConsumerImpl {
     queue_max_span_flow_control_times: u64,
     pub(crate) pop_delay_level: Arc<[i32; 16]>,
     default_mqpush_consumer_impl: Option<ArcMut<DefaultMQPushConsumerImpl>>,
+    consumer_start_timestamp: u64,
 }
 
 impl DefaultMQPushConsumerImpl {
@@ -175,6 +180,7 @@ impl DefaultMQPushConsumerImpl {
                 10, 30, 60, 120, 180, 240, 300, 360, 420, 480, 540, 600, 1200, 1800, 3600, 7200,
             ]),
             default_mqpush_consumer_impl: None,
+            consumer_start_timestamp: get_current_millis(),
         };
         let wrapper = ArcMut::downgrade(&this.rebalance_impl);
         this.rebalance_impl.set_rebalance_impl(wrapper);
@@ -223,7 +229,7 @@ impl DefaultMQPushConsumerImpl {
                     .get_or_create_mq_client_instance(
                         self.client_config.as_ref().clone(),
                         self.rpc_hook.clone(),
-                    );
+                    )?;
                 self.client_instance = Some(client_instance.clone());
                 self.rebalance_impl
                     .set_consumer_group(self.consumer_config.consumer_group.clone());
@@ -355,10 +361,12 @@ impl DefaultMQPushConsumerImpl {
                     .register_consumer(
                         self.consumer_config.consumer_group.as_ref(),
                         MQConsumerInnerImpl {
-                            default_mqpush_consumer_impl: self
-                                .default_mqpush_consumer_impl
-                                .clone()
-                                .expect("default_mqpush_consumer_impl is None"),
+                            default_mqpush_consumer_impl: Some(
+                                self.default_mqpush_consumer_impl
+                                    .clone()
+                                    .expect("default_mqpush_consumer_impl is None"),
+                            ),
+                            default_lite_pull_consumer_impl: None,
                         },
                     )
                     .await;
@@ -673,8 +681,37 @@ impl DefaultMQPushConsumerImpl {
         Ok(())
     }
 
-    pub fn register_consume_message_hook(&mut self, hook: impl ConsumeMessageHook) {
-        unimplemented!("registerConsumeMessageHook");
+    pub fn suspend(&self) {
+        self.pause.store(true, Ordering::Release);
+        info!(
+            "suspend this consumer, {}",
+            self.consumer_config.consumer_group
+        );
+    }
+
+    pub async fn resume(&self) {
+        self.pause.store(false, Ordering::Release);
+        if let Err(err) = self.try_rebalance().await {
+            warn!(
+                "resume consumer {} rebalance failed: {}",
+                self.consumer_config.consumer_group, err
+            );
+        }
+        info!(
+            "resume this consumer, {}",
+            self.consumer_config.consumer_group
+        );
+    }
+
+    #[inline]
+    pub fn is_pause(&self) -> bool {
+        self.pause.load(Ordering::Acquire)
+    }
+
+    pub fn register_consume_message_hook(&mut self, hook: impl ConsumeMessageHook + 'static) {
+        info!("register consumeMessageHook Hook, {}", hook.hook_name());
+        self.consume_message_hook_list
+            .push(Arc::new(Box::new(hook)));
     }
 
     pub fn register_message_listener(&mut self, message_listener: Option<ArcMut<MessageListener>>) {
@@ -702,6 +739,40 @@ impl DefaultMQPushConsumerImpl {
         Ok(())
     }
 
+    pub async fn subscribe_with_selector(
+        &mut self,
+        topic: CheetahString,
+        message_selector: Option<MessageSelector>,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let Some(message_selector) = message_selector else {
+            return self
+                .subscribe(
+                    topic,
+                    CheetahString::from_static_str(SubscriptionData::SUB_ALL),
+                )
+                .await;
+        };
+        let subscription_data = FilterAPI::build(
+            &topic,
+            &CheetahString::from_slice(message_selector.get_expression()),
+            Some(CheetahString::from_slice(
+                message_selector.get_expression_type(),
+            )),
+        );
+        if let Err(e) = subscription_data {
+            return mq_client_err!(format!("subscription exception, {}", e));
+        }
+        self.rebalance_impl
+            .put_subscription_data(topic, subscription_data.unwrap())
+            .await;
+        if let Some(ref mut client_instance) = self.client_instance {
+            client_instance
+                .send_heartbeat_to_all_broker_with_lock()
+                .await;
+        }
+        Ok(())
+    }
+
     pub async fn execute_pull_request_immediately(&mut self, pull_request: PullRequest) {
         self.client_instance
             .as_mut()
@@ -875,6 +946,19 @@ impl DefaultMQPushConsumerImpl {
             return;
         }
         let cached_message_count = pull_request.process_queue.msg_count();
+        #[cfg(feature = "metrics")]
+        if let Some(client_metrics_manager) = self
+            .client_instance
+            .as_ref()
+            .and_then(|client_instance| client_instance.client_metrics_manager())
+        {
+            client_metrics_manager.set_cached_messages(
+                pull_request.consumer_group.as_str(),
+                pull_request.message_queue.get_topic(),
+                pull_request.message_queue.get_queue_id(),
+                cached_message_count,
+            );
+        }
         let cached_message_size_in_mib = pull_request.process_queue.msg_size() / _1MB;
         if cached_message_count > self.consumer_config.pull_threshold_for_queue as u64 {
             if self.queue_flow_control_times % 1000 == 0 {
@@ -1093,6 +1177,7 @@ impl DefaultMQPushConsumerImpl {
                     message_queue_inner: Some(message_queue_inner),
                     subscription_data: Some(subscription_data),
                     pull_request: Some(pull_request.clone()),
+                    begin_timestamp,
                 },
             )
             .await;
@@ -1229,6 +1314,12 @@ impl DefaultMQPushConsumerImpl {
     }
 
     #[inline]
+    pub(crate) fn consumer_stats_manager(&self) -> Option<&ConsumerStatsManager> {
+        self.client_instance
+            .as_ref()
+            .map(|client_instance| client_instance.consumer_stats_manager())
+    }
+
     pub fn has_hook(&self) -> bool {
         !self.consume_message_hook_list.is_empty()
     }
@@ -1663,7 +1754,123 @@ impl MQConsumerInner for DefaultMQPushConsumerImpl {
         self.consumer_config.unit_mode
     }
 
-    fn consumer_running_info(&self) -> ConsumerRunningInfo {
-        todo!()
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo {
+        let mut info = ConsumerRunningInfo::new();
+        let consumer_config = &self.consumer_config;
+        let properties = [
+            ("consumerGroup", consumer_config.consumer_group.to_string()),
+            ("messageModel", consumer_config.message_model.to_string()),
+            (
+                "consumeFromWhere",
+                format!("{:?}", consumer_config.consume_from_where),
+            ),
+            (
+                "consumeThreadMin",
+                consumer_config.consume_thread_min.to_string(),
+            ),
+            (
+                "consumeThreadMax",
+                consumer_config.consume_thread_max.to_string(),
+            ),
+            (
+                "consumeConcurrentlyMaxSpan",
+                consumer_config.consume_concurrently_max_span.to_string(),
+            ),
+            (
+                "pullThresholdForQueue",
+                consumer_config.pull_threshold_for_queue.to_string(),
+            ),
+            (
+                "pullThresholdSizeForQueue",
+                consumer_config.pull_threshold_size_for_queue.to_string(),
+            ),
+            ("pullInterval", consumer_config.pull_interval.to_string()),
+            (
+                "consumeMessageBatchMaxSize",
+                consumer_config.consume_message_batch_max_size.to_string(),
+            ),
+            ("pullBatchSize", consumer_config.pull_batch_size.to_string()),
+            (
+                "maxReconsumeTimes",
+                consumer_config.max_reconsume_times.to_string(),
+            ),
+            (
+                "consumeTimeout",
+                consumer_config.consume_timeout.to_string(),
+            ),
+            ("unitMode", consumer_config.unit_mode.to_string()),
+            (
+                ConsumerRunningInfo::PROP_CONSUME_ORDERLY,
+                self.consume_orderly.to_string(),
+            ),
+            (
+                ConsumerRunningInfo::PROP_THREADPOOL_CORE_SIZE,
+                consumer_config.consume_thread_min.to_string(),
+            ),
+            (
+                ConsumerRunningInfo::PROP_CONSUMER_START_TIMESTAMP,
+                self.consumer_start_timestamp.to_string(),
+            ),
+        ];
+        for (key, value) in properties {
+            info.set_property(key, value);
+        }
+
+        let subscriptions = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .subscription_inner
+            .read()
+            .await
+            .values()
+            .cloned()
+            .collect::<HashSet<_>>();
+
+        let process_queue_table = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .process_queue_table
+            .read()
+            .await
+            .clone();
+        for (mq, pq) in process_queue_table {
+            let mut pq_info = ProcessQueueInfo::default();
+            if let Some(ref offset_store) = self.offset_store {
+                pq_info.commit_offset = offset_store
+                    .read_offset(&mq, ReadOffsetType::MemoryFirstThenStore)
+                    .await
+                    .max(0) as u64;
+            }
+            pq.fill_process_queue_info(&mut pq_info).await;
+            info.mq_table.insert(mq, pq_info);
+        }
+
+        let pop_process_queue_table = self
+            .rebalance_impl
+            .rebalance_impl_inner
+            .pop_process_queue_table
+            .read()
+            .await;
+        for (mq, pq) in pop_process_queue_table.iter() {
+            let mut pq_info = PopProcessQueueInfo::default();
+            pq.fill_pop_process_queue_info(&mut pq_info);
+            info.mq_pop_table.insert(mq.clone(), pq_info);
+        }
+        drop(pop_process_queue_table);
+
+        if let Some(ref client_instance) = self.client_instance {
+            let consumer_stats_manager = client_instance.consumer_stats_manager();
+            for subscription in &subscriptions {
+                info.status_table.insert(
+                    subscription.topic.clone(),
+                    consumer_stats_manager.consume_status(
+                        self.consumer_config.consumer_group.as_str(),
+                        subscription.topic.as_str(),
+                    ),
+                );
+            }
+        }
+        info.subscription_set = subscriptions;
+        info
     }
 }
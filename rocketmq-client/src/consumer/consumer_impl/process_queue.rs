@@ -216,7 +216,7 @@ impl ProcessQueue {
                     std::sync::atomic::Ordering::Release,
                 );
                 self.msg_size.fetch_add(
-                    message.body().as_ref().unwrap().len() as u64,
+                    message.body().as_ref().map_or(0, |body| body.len()) as u64,
                     Ordering::AcqRel,
                 );
             }
@@ -256,17 +256,17 @@ impl ProcessQueue {
             if let Some(prev) = prev {
                 removed_cnt += 1;
                 self.msg_size.fetch_sub(
-                    message.body().as_ref().unwrap().len() as u64,
+                    message.body().as_ref().map_or(0, |body| body.len()) as u64,
                     Ordering::AcqRel,
                 );
             }
-            self.msg_count.fetch_sub(removed_cnt, Ordering::AcqRel);
-            if self.msg_count.load(Ordering::Acquire) == 0 {
-                self.msg_size.store(0, Ordering::Release);
-            }
-            if !msg_tree_map.is_empty() {
-                result = *msg_tree_map.first_key_value().unwrap().0;
-            }
+        }
+        self.msg_count.fetch_sub(removed_cnt, Ordering::AcqRel);
+        if self.msg_count.load(Ordering::Acquire) == 0 {
+            self.msg_size.store(0, Ordering::Release);
+        }
+        if !msg_tree_map.is_empty() {
+            result = *msg_tree_map.first_key_value().unwrap().0;
         }
         result
     }
@@ -346,8 +346,39 @@ impl ProcessQueue {
         drop(lock);
     }
 
-    pub(crate) fn fill_process_queue_info(&self, info: ProcessQueueInfo) {
-        unimplemented!("fill_process_queue_info")
+    pub(crate) async fn fill_process_queue_info(&self, info: &mut ProcessQueueInfo) {
+        let lock = self.tree_map_lock.read().await;
+        let msg_tree_map = self.msg_tree_map.read().await;
+        if let (Some((first, _)), Some((last, _))) = (
+            msg_tree_map.first_key_value(),
+            msg_tree_map.last_key_value(),
+        ) {
+            info.cached_msg_min_offset = *first as u64;
+            info.cached_msg_max_offset = *last as u64;
+            info.cached_msg_count = msg_tree_map.len() as u32;
+        }
+        drop(msg_tree_map);
+        info.cached_msg_size_in_mib =
+            (self.msg_size.load(Ordering::Acquire) / (1024 * 1024)) as u32;
+
+        let consuming_msg_orderly_tree_map = self.consuming_msg_orderly_tree_map.read().await;
+        if let (Some((first, _)), Some((last, _))) = (
+            consuming_msg_orderly_tree_map.first_key_value(),
+            consuming_msg_orderly_tree_map.last_key_value(),
+        ) {
+            info.transaction_msg_min_offset = *first as u64;
+            info.transaction_msg_max_offset = *last as u64;
+            info.transaction_msg_count = consuming_msg_orderly_tree_map.len() as u32;
+        }
+        drop(consuming_msg_orderly_tree_map);
+        drop(lock);
+
+        info.locked = self.locked.load(Ordering::Acquire);
+        info.try_unlock_times = self.try_unlock_times.load(Ordering::Acquire) as u64;
+        info.last_lock_timestamp = self.last_lock_timestamp.load(Ordering::Acquire);
+        info.droped = self.is_dropped();
+        info.last_pull_timestamp = self.last_pull_timestamp.load(Ordering::Acquire);
+        info.last_consume_timestamp = self.last_consume_timestamp.load(Ordering::Acquire);
     }
 
     pub(crate) fn set_last_pull_timestamp(&self, last_pull_timestamp: u64) {
@@ -372,3 +403,54 @@ impl ProcessQueue {
         self.locked.load(std::sync::atomic::Ordering::Acquire)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+
+    use super::*;
+
+    fn message(queue_offset: i64, body: &'static [u8]) -> ArcMut<MessageExt> {
+        let mut message = MessageExt::default();
+        message.set_queue_offset(queue_offset);
+        message.set_body(Bytes::from_static(body));
+        ArcMut::new(message)
+    }
+
+    #[tokio::test]
+    async fn remove_message_updates_count_and_returns_next_offset() {
+        let queue = ProcessQueue::new();
+        let messages = (0..4).map(|i| message(i, b"abc")).collect::<Vec<_>>();
+        queue.put_message(messages.clone()).await;
+        assert_eq!(queue.msg_count(), 4);
+        assert_eq!(queue.msg_size(), 12);
+
+        let offset = queue.remove_message(&messages[..3]).await;
+        assert_eq!(offset, 3);
+        assert_eq!(queue.msg_count(), 1);
+        assert_eq!(queue.msg_size(), 3);
+
+        let offset = queue.remove_message(&messages[3..]).await;
+        assert_eq!(offset, 4);
+        assert_eq!(queue.msg_count(), 0);
+        assert_eq!(queue.msg_size(), 0);
+    }
+
+    #[tokio::test]
+    async fn fill_process_queue_info_reports_cached_messages() {
+        let queue = ProcessQueue::new();
+        queue
+            .put_message((5..8).map(|i| message(i, b"abc")).collect())
+            .await;
+        queue.set_locked(true);
+
+        let mut info = ProcessQueueInfo::default();
+        queue.fill_process_queue_info(&mut info).await;
+        assert_eq!(info.cached_msg_min_offset, 5);
+        assert_eq!(info.cached_msg_max_offset, 7);
+        assert_eq!(info.cached_msg_count, 3);
+        assert_eq!(info.transaction_msg_count, 0);
+        assert!(info.locked);
+        assert!(!info.droped);
+    }
+}
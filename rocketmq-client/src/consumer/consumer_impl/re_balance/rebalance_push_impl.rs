@@ -329,7 +329,7 @@ impl Rebalance for RebalancePushImpl {
                         )
                         .unwrap()
                         .and_utc()
-                        .timestamp();
+                        .timestamp_millis();
                         self.rebalance_impl_inner
                             .client_instance
                             .as_mut()
@@ -0,0 +1,309 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::consumer::consume_from_where::ConsumeFromWhere;
+use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
+use rocketmq_remoting::runtime::RPCHook;
+
+use crate::base::client_config::ClientConfig;
+use crate::consumer::allocate_message_queue_strategy::AllocateMessageQueueStrategy;
+use crate::consumer::default_lite_pull_consumer::DefaultLitePullConsumer;
+use crate::consumer::default_lite_pull_consumer::LitePullConsumerConfig;
+use crate::consumer::message_queue_listener::MessageQueueListener;
+
+pub struct DefaultLitePullConsumerBuilder {
+    client_config: Option<ClientConfig>,
+    consumer_group: Option<CheetahString>,
+    message_model: Option<MessageModel>,
+    message_queue_listener: Option<Arc<Box<dyn MessageQueueListener>>>,
+    consume_from_where: Option<ConsumeFromWhere>,
+    consume_timestamp: Option<CheetahString>,
+    allocate_message_queue_strategy: Option<Arc<dyn AllocateMessageQueueStrategy>>,
+    unit_mode: Option<bool>,
+    auto_commit: Option<bool>,
+    auto_commit_interval_millis: Option<u64>,
+    pull_batch_size: Option<u32>,
+    consume_max_span: Option<u32>,
+    pull_threshold_for_all: Option<u64>,
+    pull_threshold_for_queue: Option<u32>,
+    pull_threshold_size_for_queue: Option<u32>,
+    poll_timeout_millis: Option<u64>,
+    topic_metadata_check_interval_millis: Option<u64>,
+    pull_time_delay_mills_when_exception: Option<u64>,
+    broker_suspend_max_time_millis: Option<u64>,
+    consumer_timeout_millis_when_suspend: Option<u64>,
+    rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+}
+
+impl Default for DefaultLitePullConsumerBuilder {
+    fn default() -> Self {
+        Self {
+            client_config: Some(Default::default()),
+            consumer_group: None,
+            message_model: None,
+            message_queue_listener: None,
+            consume_from_where: None,
+            consume_timestamp: None,
+            allocate_message_queue_strategy: None,
+            unit_mode: None,
+            auto_commit: None,
+            auto_commit_interval_millis: None,
+            pull_batch_size: None,
+            consume_max_span: None,
+            pull_threshold_for_all: None,
+            pull_threshold_for_queue: None,
+            pull_threshold_size_for_queue: None,
+            poll_timeout_millis: None,
+            topic_metadata_check_interval_millis: None,
+            pull_time_delay_mills_when_exception: None,
+            broker_suspend_max_time_millis: None,
+            consumer_timeout_millis_when_suspend: None,
+            rpc_hook: None,
+        }
+    }
+}
+
+impl DefaultLitePullConsumerBuilder {
+    pub fn name_server_addr(mut self, name_server_addr: impl Into<CheetahString>) -> Self {
+        if let Some(client_config) = self.client_config.as_mut() {
+            client_config.namesrv_addr = Some(name_server_addr.into());
+            client_config
+                .namespace_initialized
+                .store(false, std::sync::atomic::Ordering::Release);
+        }
+        self
+    }
+
+    pub fn client_config(mut self, client_config: ClientConfig) -> Self {
+        self.client_config = Some(client_config);
+        self
+    }
+
+    pub fn consumer_group(mut self, consumer_group: impl Into<CheetahString>) -> Self {
+        self.consumer_group = Some(consumer_group.into());
+        self
+    }
+
+    pub fn message_model(mut self, message_model: MessageModel) -> Self {
+        self.message_model = Some(message_model);
+        self
+    }
+
+    pub fn message_queue_listener(
+        mut self,
+        message_queue_listener: Option<Arc<Box<dyn MessageQueueListener>>>,
+    ) -> Self {
+        self.message_queue_listener = message_queue_listener;
+        self
+    }
+
+    pub fn consume_from_where(mut self, consume_from_where: ConsumeFromWhere) -> Self {
+        self.consume_from_where = Some(consume_from_where);
+        self
+    }
+
+    pub fn consume_timestamp(mut self, consume_timestamp: impl Into<CheetahString>) -> Self {
+        self.consume_timestamp = Some(consume_timestamp.into());
+        self
+    }
+
+    pub fn allocate_message_queue_strategy(
+        mut self,
+        allocate_message_queue_strategy: Arc<dyn AllocateMessageQueueStrategy>,
+    ) -> Self {
+        self.allocate_message_queue_strategy = Some(allocate_message_queue_strategy);
+        self
+    }
+
+    pub fn unit_mode(mut self, unit_mode: bool) -> Self {
+        self.unit_mode = Some(unit_mode);
+        self
+    }
+
+    pub fn auto_commit(mut self, auto_commit: bool) -> Self {
+        self.auto_commit = Some(auto_commit);
+        self
+    }
+
+    pub fn auto_commit_interval_millis(mut self, auto_commit_interval_millis: u64) -> Self {
+        self.auto_commit_interval_millis = Some(auto_commit_interval_millis);
+        self
+    }
+
+    pub fn pull_batch_size(mut self, pull_batch_size: u32) -> Self {
+        self.pull_batch_size = Some(pull_batch_size);
+        self
+    }
+
+    pub fn consume_max_span(mut self, consume_max_span: u32) -> Self {
+        self.consume_max_span = Some(consume_max_span);
+        self
+    }
+
+    pub fn pull_threshold_for_all(mut self, pull_threshold_for_all: u64) -> Self {
+        self.pull_threshold_for_all = Some(pull_threshold_for_all);
+        self
+    }
+
+    pub fn pull_threshold_for_queue(mut self, pull_threshold_for_queue: u32) -> Self {
+        self.pull_threshold_for_queue = Some(pull_threshold_for_queue);
+        self
+    }
+
+    pub fn pull_threshold_size_for_queue(mut self, pull_threshold_size_for_queue: u32) -> Self {
+        self.pull_threshold_size_for_queue = Some(pull_threshold_size_for_queue);
+        self
+    }
+
+    pub fn poll_timeout_millis(mut self, poll_timeout_millis: u64) -> Self {
+        self.poll_timeout_millis = Some(poll_timeout_millis);
+        self
+    }
+
+    pub fn topic_metadata_check_interval_millis(
+        mut self,
+        topic_metadata_check_interval_millis: u64,
+    ) -> Self {
+        self.topic_metadata_check_interval_millis = Some(topic_metadata_check_interval_millis);
+        self
+    }
+
+    pub fn pull_time_delay_mills_when_exception(
+        mut self,
+        pull_time_delay_mills_when_exception: u64,
+    ) -> Self {
+        self.pull_time_delay_mills_when_exception = Some(pull_time_delay_mills_when_exception);
+        self
+    }
+
+    pub fn broker_suspend_max_time_millis(mut self, broker_suspend_max_time_millis: u64) -> Self {
+        self.broker_suspend_max_time_millis = Some(broker_suspend_max_time_millis);
+        self
+    }
+
+    pub fn consumer_timeout_millis_when_suspend(
+        mut self,
+        consumer_timeout_millis_when_suspend: u64,
+    ) -> Self {
+        self.consumer_timeout_millis_when_suspend = Some(consumer_timeout_millis_when_suspend);
+        self
+    }
+
+    pub fn rpc_hook(mut self, rpc_hook: Option<Arc<Box<dyn RPCHook>>>) -> Self {
+        self.rpc_hook = rpc_hook;
+        self
+    }
+
+    pub fn build(mut self) -> DefaultLitePullConsumer {
+        let mut consumer_config = LitePullConsumerConfig::default();
+        if let Some(consumer_group) = self.consumer_group.take() {
+            consumer_config.consumer_group = consumer_group;
+        }
+        if let Some(message_model) = self.message_model {
+            consumer_config.message_model = message_model;
+        }
+        consumer_config.message_queue_listener = self.message_queue_listener.take();
+        if let Some(consume_from_where) = self.consume_from_where {
+            consumer_config.consume_from_where = consume_from_where;
+        }
+        if let Some(consume_timestamp) = self.consume_timestamp.take() {
+            consumer_config.consume_timestamp = Some(consume_timestamp);
+        }
+        if let Some(allocate_message_queue_strategy) = self.allocate_message_queue_strategy.take() {
+            consumer_config.allocate_message_queue_strategy = Some(allocate_message_queue_strategy);
+        }
+        if let Some(unit_mode) = self.unit_mode {
+            consumer_config.unit_mode = unit_mode;
+        }
+        if let Some(auto_commit) = self.auto_commit {
+            consumer_config.auto_commit = auto_commit;
+        }
+        if let Some(auto_commit_interval_millis) = self.auto_commit_interval_millis {
+            consumer_config.auto_commit_interval_millis = auto_commit_interval_millis;
+        }
+        if let Some(pull_batch_size) = self.pull_batch_size {
+            consumer_config.pull_batch_size = pull_batch_size;
+        }
+        if let Some(consume_max_span) = self.consume_max_span {
+            consumer_config.consume_max_span = consume_max_span;
+        }
+        if let Some(pull_threshold_for_all) = self.pull_threshold_for_all {
+            consumer_config.pull_threshold_for_all = pull_threshold_for_all;
+        }
+        if let Some(pull_threshold_for_queue) = self.pull_threshold_for_queue {
+            consumer_config.pull_threshold_for_queue = pull_threshold_for_queue;
+        }
+        if let Some(pull_threshold_size_for_queue) = self.pull_threshold_size_for_queue {
+            consumer_config.pull_threshold_size_for_queue = pull_threshold_size_for_queue;
+        }
+        if let Some(poll_timeout_millis) = self.poll_timeout_millis {
+            consumer_config.poll_timeout_millis = poll_timeout_millis;
+        }
+        if let Some(topic_metadata_check_interval_millis) =
+            self.topic_metadata_check_interval_millis
+        {
+            consumer_config.topic_metadata_check_interval_millis =
+                topic_metadata_check_interval_millis;
+        }
+        if let Some(pull_time_delay_mills_when_exception) =
+            self.pull_time_delay_mills_when_exception
+        {
+            consumer_config.pull_time_delay_mills_when_exception =
+                pull_time_delay_mills_when_exception;
+        }
+        if let Some(broker_suspend_max_time_millis) = self.broker_suspend_max_time_millis {
+            consumer_config.broker_suspend_max_time_millis = broker_suspend_max_time_millis;
+        }
+        if let Some(consumer_timeout_millis_when_suspend) =
+            self.consumer_timeout_millis_when_suspend
+        {
+            consumer_config.consumer_timeout_millis_when_suspend =
+                consumer_timeout_millis_when_suspend;
+        }
+        consumer_config.rpc_hook = self.rpc_hook.take();
+
+        DefaultLitePullConsumer::new(
+            self.client_config.take().unwrap_or_default(),
+            consumer_config,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_applies_configured_values() {
+        let consumer = DefaultLitePullConsumerBuilder::default()
+            .consumer_group("lite_pull_group")
+            .message_model(MessageModel::Broadcasting)
+            .auto_commit(false)
+            .pull_batch_size(32)
+            .poll_timeout_millis(1000)
+            .build();
+        let config = consumer.consumer_config();
+        assert_eq!(config.consumer_group().as_str(), "lite_pull_group");
+        assert_eq!(config.message_model(), MessageModel::Broadcasting);
+        assert!(!config.auto_commit());
+        assert_eq!(config.pull_batch_size(), 32);
+        assert_eq!(config.poll_timeout_millis(), 1000);
+        assert_eq!(config.auto_commit_interval_millis(), 5000);
+    }
+}
@@ -30,6 +30,7 @@ use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
 use tokio::runtime::Handle;
+use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
 use crate::base::mq_admin::MQAdmin;
@@ -531,11 +532,13 @@ impl MQPushConsumer for DefaultMQPushConsumer {
             let mut dispatcher = AsyncTraceDispatcher::new(
                 self.consumer_config.consumer_group.as_str(),
                 Type::Consume,
-                self.client_config.trace_topic.clone().unwrap().as_str(),
+                self.client_config
+                    .trace_topic
+                    .clone()
+                    .unwrap_or_default()
+                    .as_str(),
                 self.consumer_config.rpc_hook.clone(),
             );
-            dispatcher
-                .set_host_consumer(self.default_mqpush_consumer_impl.as_ref().unwrap().clone());
             dispatcher.set_namespace_v2(self.client_config.namespace_v2.clone());
             let dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>> =
                 Arc::new(Box::new(dispatcher));
@@ -546,15 +549,27 @@ impl MQPushConsumer for DefaultMQPushConsumer {
             );
         }
 
-        if let Some(ref rpc_hook) = self.consumer_config.trace_dispatcher {
-            unimplemented!("trace hook");
+        if let Some(ref trace_dispatcher) = self.consumer_config.trace_dispatcher {
+            let namesrv_addr = self.client_config.get_namesrv_addr().unwrap_or_default();
+            if let Err(e) =
+                trace_dispatcher.start(namesrv_addr.as_str(), self.client_config.access_channel)
+            {
+                warn!("trace dispatcher start failed: {}", e);
+            }
         }
 
         Ok(())
     }
 
     async fn shutdown(&mut self) {
-        todo!()
+        if let Some(ref mut default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            default_mqpush_consumer_impl
+                .shutdown(self.consumer_config.await_termination_millis_when_shutdown)
+                .await;
+        }
+        if let Some(ref trace_dispatcher) = self.consumer_config.trace_dispatcher {
+            trace_dispatcher.shutdown();
+        }
     }
 
     fn register_message_listener_concurrently_fn<MLCFN>(&mut self, message_listener: MLCFN)
@@ -643,7 +658,11 @@ impl MQPushConsumer for DefaultMQPushConsumer {
         topic: &str,
         selector: Option<MessageSelector>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqpush_consumer_impl
+            .as_mut()
+            .unwrap()
+            .subscribe_with_selector(CheetahString::from_slice(topic), selector)
+            .await
     }
 
     async fn unsubscribe(&mut self, topic: &str) {
@@ -651,11 +670,15 @@ impl MQPushConsumer for DefaultMQPushConsumer {
     }
 
     async fn suspend(&mut self) {
-        todo!()
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            default_mqpush_consumer_impl.suspend();
+        }
     }
 
     async fn resume(&mut self) {
-        todo!()
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            default_mqpush_consumer_impl.resume().await;
+        }
     }
 }
 
@@ -88,7 +88,7 @@ pub trait LitePullConsumerLocal: Sync {
         listener: MQL,
     ) -> rocketmq_error::RocketMQResult<()>
     where
-        MQL: MessageQueueListener;
+        MQL: MessageQueueListener + 'static;
 
     /// Subscribes to a topic with a message selector.
     ///
@@ -285,7 +285,7 @@ pub trait LitePullConsumerLocal: Sync {
         listener: TL,
     ) -> rocketmq_error::RocketMQResult<()>
     where
-        TL: TopicMessageQueueChangeListener;
+        TL: TopicMessageQueueChangeListener + 'static;
 
     /// Updates the name server address.
     ///
@@ -28,6 +28,7 @@ use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_rust::ArcMut;
 
+use crate::consumer::consumer_impl::default_lite_pull_consumer_impl::DefaultLitePullConsumerImpl;
 use crate::consumer::consumer_impl::default_mq_push_consumer_impl::DefaultMQPushConsumerImpl;
 use crate::consumer::consumer_impl::pop_request::PopRequest;
 use crate::consumer::consumer_impl::pull_request::PullRequest;
@@ -86,7 +87,7 @@ pub trait MQConsumerInnerLocal: MQConsumerInnerAny + Sync + 'static {
     fn is_unit_mode(&self) -> bool;
 
     /// Returns the running information of the consumer.
-    fn consumer_running_info(&self) -> ConsumerRunningInfo;
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo;
 }
 
 pub trait MQConsumerInnerAny: std::any::Any {
@@ -107,20 +108,23 @@ impl<T: MQConsumerInner> MQConsumerInnerAny for T {
 
 #[derive(Clone)]
 pub struct MQConsumerInnerImpl {
-    pub(crate) default_mqpush_consumer_impl: ArcMut<DefaultMQPushConsumerImpl>,
+    pub(crate) default_mqpush_consumer_impl: Option<ArcMut<DefaultMQPushConsumerImpl>>,
+    pub(crate) default_lite_pull_consumer_impl: Option<ArcMut<DefaultLitePullConsumerImpl>>,
 }
 
 impl MQConsumerInnerImpl {
     pub(crate) async fn pop_message(&mut self, pop_request: PopRequest) {
-        self.default_mqpush_consumer_impl
-            .pop_message(pop_request)
-            .await;
+        if let Some(ref mut default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            default_mqpush_consumer_impl.pop_message(pop_request).await;
+        }
     }
 
     pub(crate) async fn pull_message(&mut self, pull_request: PullRequest) {
-        self.default_mqpush_consumer_impl
-            .pull_message(pull_request)
-            .await;
+        if let Some(ref mut default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            default_mqpush_consumer_impl
+                .pull_message(pull_request)
+                .await;
+        }
     }
 
     pub(crate) async fn consume_message_directly(
@@ -128,51 +132,106 @@ impl MQConsumerInnerImpl {
         msg: MessageExt,
         broker_name: Option<CheetahString>,
     ) -> Option<ConsumeMessageDirectlyResult> {
-        self.default_mqpush_consumer_impl
-            .consume_message_directly(msg, broker_name)
-            .await
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return default_mqpush_consumer_impl
+                .consume_message_directly(msg, broker_name)
+                .await;
+        }
+        None
     }
 }
 
 impl MQConsumerInner for MQConsumerInnerImpl {
     #[inline]
     fn group_name(&self) -> CheetahString {
-        MQConsumerInner::group_name(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::group_name(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::group_name(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn message_model(&self) -> MessageModel {
-        MQConsumerInner::message_model(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::message_model(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::message_model(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn consume_type(&self) -> ConsumeType {
-        MQConsumerInner::consume_type(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::consume_type(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::consume_type(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn consume_from_where(&self) -> ConsumeFromWhere {
-        MQConsumerInner::consume_from_where(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::consume_from_where(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::consume_from_where(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn subscriptions(&self) -> HashSet<SubscriptionData> {
-        MQConsumerInner::subscriptions(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::subscriptions(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::subscriptions(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn do_rebalance(&self) {
-        MQConsumerInner::do_rebalance(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::do_rebalance(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::do_rebalance(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     async fn try_rebalance(&self) -> rocketmq_error::RocketMQResult<bool> {
-        MQConsumerInner::try_rebalance(self.default_mqpush_consumer_impl.as_ref()).await
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::try_rebalance(default_mqpush_consumer_impl.as_ref()).await;
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::try_rebalance(default_lite_pull_consumer_impl.as_ref()).await;
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     async fn persist_consumer_offset(&self) {
-        MQConsumerInner::persist_consumer_offset(self.default_mqpush_consumer_impl.as_ref()).await
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::persist_consumer_offset(default_mqpush_consumer_impl.as_ref())
+                .await;
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::persist_consumer_offset(
+                default_lite_pull_consumer_impl.as_ref(),
+            )
+            .await;
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
@@ -181,30 +240,67 @@ impl MQConsumerInner for MQConsumerInnerImpl {
         topic: CheetahString,
         info: &HashSet<MessageQueue>,
     ) {
-        MQConsumerInner::update_topic_subscribe_info(
-            self.default_mqpush_consumer_impl.mut_from_ref(),
-            topic,
-            info,
-        )
-        .await
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::update_topic_subscribe_info(
+                default_mqpush_consumer_impl.mut_from_ref(),
+                topic,
+                info,
+            )
+            .await;
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::update_topic_subscribe_info(
+                default_lite_pull_consumer_impl.mut_from_ref(),
+                topic,
+                info,
+            )
+            .await;
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     async fn is_subscribe_topic_need_update(&self, topic: &str) -> bool {
-        MQConsumerInner::is_subscribe_topic_need_update(
-            self.default_mqpush_consumer_impl.as_ref(),
-            topic,
-        )
-        .await
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::is_subscribe_topic_need_update(
+                default_mqpush_consumer_impl.as_ref(),
+                topic,
+            )
+            .await;
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::is_subscribe_topic_need_update(
+                default_lite_pull_consumer_impl.as_ref(),
+                topic,
+            )
+            .await;
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
     fn is_unit_mode(&self) -> bool {
-        MQConsumerInner::is_unit_mode(self.default_mqpush_consumer_impl.as_ref())
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::is_unit_mode(default_mqpush_consumer_impl.as_ref());
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::is_unit_mode(default_lite_pull_consumer_impl.as_ref());
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 
     #[inline]
-    fn consumer_running_info(&self) -> ConsumerRunningInfo {
-        MQConsumerInner::consumer_running_info(self.default_mqpush_consumer_impl.as_ref())
+    async fn consumer_running_info(&self) -> ConsumerRunningInfo {
+        if let Some(ref default_mqpush_consumer_impl) = self.default_mqpush_consumer_impl {
+            return MQConsumerInner::consumer_running_info(default_mqpush_consumer_impl.as_ref())
+                .await;
+        }
+        if let Some(ref default_lite_pull_consumer_impl) = self.default_lite_pull_consumer_impl {
+            return MQConsumerInner::consumer_running_info(
+                default_lite_pull_consumer_impl.as_ref(),
+            )
+            .await;
+        }
+        unreachable!("MQConsumerInnerImpl holds no consumer implementation")
     }
 }
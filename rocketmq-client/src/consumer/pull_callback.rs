@@ -16,6 +16,7 @@
  */
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_common::common::mix_all;
@@ -46,6 +47,7 @@ pub(crate) struct DefaultPullCallback {
     pub(crate) message_queue_inner: Option<MessageQueue>,
     pub(crate) subscription_data: Option<SubscriptionData>,
     pub(crate) pull_request: Option<PullRequest>,
+    pub(crate) begin_timestamp: Instant,
 }
 
 impl PullCallback for DefaultPullCallback {
@@ -74,8 +76,21 @@ impl PullCallback for DefaultPullCallback {
             PullStatus::Found => {
                 let prev_request_offset = pull_request.next_offset;
                 pull_request.set_next_offset(pull_result_ext.pull_result.next_begin_offset as i64);
-                /*let pull_rt = get_current_millis() - begin_timestamp.elapsed().as_millis() as u64;
-                self.client_instance.as_mut().unwrap().*/
+                let pull_rt = self.begin_timestamp.elapsed().as_millis() as u64;
+                if let Some(stats) = push_consumer_impl.consumer_stats_manager() {
+                    let group = push_consumer_impl.consumer_config.consumer_group.as_str();
+                    let topic = pull_request.get_message_queue().get_topic();
+                    stats.inc_pull_rt(group, topic, pull_rt);
+                    stats.inc_pull_tps(
+                        group,
+                        topic,
+                        pull_result_ext
+                            .pull_result
+                            .msg_found_list
+                            .as_ref()
+                            .map_or(0, |msgs| msgs.len() as u64),
+                    );
+                }
                 let mut first_msg_offset = i64::MAX;
                 if pull_result_ext
                     .pull_result
@@ -31,10 +31,12 @@ use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
 use rocketmq_common::common::message::message_queue_assignment::MessageQueueAssignment;
 use rocketmq_common::common::mix_all;
+use rocketmq_common::common::mq_version::RocketMqVersion;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_error::mq_client_err;
 use rocketmq_remoting::base::connection_net_event::ConnectionNetEvent;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
 use rocketmq_remoting::protocol::heartbeat::consumer_data::ConsumerData;
 use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
 use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
@@ -56,6 +58,7 @@ use crate::admin::mq_admin_ext_async_inner::MQAdminExtInnerImpl;
 use crate::base::client_config::ClientConfig;
 use crate::consumer::consumer_impl::pull_message_service::PullMessageService;
 use crate::consumer::consumer_impl::re_balance::rebalance_service::RebalanceService;
+use crate::consumer::consumer_impl::re_balance::Rebalance;
 use crate::consumer::mq_consumer_inner::MQConsumerInner;
 use crate::consumer::mq_consumer_inner::MQConsumerInnerImpl;
 use crate::implementation::client_remoting_processor::ClientRemotingProcessor;
@@ -66,6 +69,9 @@ use crate::producer::default_mq_producer::DefaultMQProducer;
 use crate::producer::default_mq_producer::ProducerConfig;
 use crate::producer::producer_impl::mq_producer_inner::MQProducerInnerImpl;
 use crate::producer::producer_impl::topic_publish_info::TopicPublishInfo;
+#[cfg(feature = "metrics")]
+use crate::stat::client_metrics_manager::ClientMetricsManager;
+use crate::stat::consumer_stats_manager::ConsumerStatsManager;
 
 const LOCK_TIMEOUT_MILLIS: u64 = 3000;
 
@@ -117,6 +123,9 @@ pub struct MQClientInstance {
         >,
     >,
     send_heartbeat_times_total: Arc<AtomicI64>,
+    consumer_stats_manager: ConsumerStatsManager,
+    #[cfg(feature = "metrics")]
+    client_metrics_manager: Option<Arc<ClientMetricsManager>>,
 }
 
 impl MQClientInstance {
@@ -130,7 +139,10 @@ impl MQClientInstance {
         let (tx, _) = tokio::sync::broadcast::channel::<ConnectionNetEvent>(16);
         let rx = tx.subscribe();
         let mq_client_api_impl = ArcMut::new(MQClientAPIImpl::new(
-            Arc::new(TokioClientConfig::default()),
+            Arc::new(TokioClientConfig {
+                use_tls: client_config.use_tls,
+                ..TokioClientConfig::default()
+            }),
             ClientRemotingProcessor::new(),
             rpc_hook,
             client_config.clone(),
@@ -190,11 +202,23 @@ impl MQClientInstance {
         instance_index: i32,
         client_id: impl Into<CheetahString>,
         rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
-    ) -> ArcMut<MQClientInstance> {
+    ) -> rocketmq_error::RocketMQResult<ArcMut<MQClientInstance>> {
         let broker_addr_table = Arc::new(Default::default());
+        let client_id = client_id.into();
+        #[allow(unused_mut)]
+        let mut consumer_stats_manager = ConsumerStatsManager::new();
+        #[cfg(feature = "metrics")]
+        let client_metrics_manager = client_config
+            .metrics_exporter_type
+            .is_enable()
+            .then(|| Arc::new(ClientMetricsManager::new(client_id.clone())));
+        #[cfg(feature = "metrics")]
+        if let Some(client_metrics_manager) = client_metrics_manager.as_ref() {
+            consumer_stats_manager.set_metrics_manager(client_metrics_manager.clone());
+        }
         let mut instance = ArcMut::new(MQClientInstance {
             client_config: ArcMut::new(client_config.clone()),
-            client_id: client_id.into(),
+            client_id,
             boot_timestamp: get_current_millis(),
             producer_table: Arc::new(RwLock::new(HashMap::new())),
             consumer_table: Arc::new(Default::default()),
@@ -221,6 +245,9 @@ impl MQClientInstance {
             broker_addr_table,
             broker_version_table: Arc::new(Default::default()),
             send_heartbeat_times_total: Arc::new(AtomicI64::new(0)),
+            consumer_stats_manager,
+            #[cfg(feature = "metrics")]
+            client_metrics_manager,
         });
         let instance_clone = instance.clone();
         instance.mq_admin_impl.set_client(instance_clone);
@@ -228,12 +255,16 @@ impl MQClientInstance {
         let (tx, mut rx) = tokio::sync::broadcast::channel::<ConnectionNetEvent>(16);
 
         let mq_client_api_impl = ArcMut::new(MQClientAPIImpl::new(
-            Arc::new(TokioClientConfig::default()),
+            Arc::new(TokioClientConfig {
+                use_tls: client_config.use_tls,
+                tls_config: client_config.tls_config(),
+                ..TokioClientConfig::default()
+            }),
             ClientRemotingProcessor::new(instance.clone()),
             rpc_hook,
             client_config.clone(),
             Some(tx),
-        ));
+        )?);
         instance.mq_client_api_impl = Some(mq_client_api_impl.clone());
         if let Some(namesrv_addr) = client_config.namesrv_addr.as_deref() {
             let handle = Handle::current();
@@ -273,7 +304,7 @@ impl MQClientInstance {
             }
             warn!("ConnectionNetEvent recv error");
         });
-        instance
+        Ok(instance)
     }
 
     pub fn re_balance_immediately(&self) {
@@ -317,6 +348,9 @@ impl MQClientInstance {
                 self.pull_message_service.start(instance).await;
                 // Start rebalance service
                 self.rebalance_service.start(this).await;
+                self.consumer_stats_manager.start();
+                #[cfg(feature = "metrics")]
+                self.start_metrics_exporter().await;
                 // Start push service
                 self.default_producer
                     .default_mqproducer_impl
@@ -339,7 +373,35 @@ impl MQClientInstance {
         Ok(())
     }
 
-    pub async fn shutdown(&mut self) {}
+    pub async fn shutdown(&mut self) {
+        #[cfg(feature = "metrics")]
+        if let Some(client_metrics_manager) = self.client_metrics_manager.as_ref() {
+            client_metrics_manager.shutdown();
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    async fn start_metrics_exporter(&self) {
+        let Some(client_metrics_manager) = self.client_metrics_manager.as_ref() else {
+            return;
+        };
+        let port = self.client_config.metrics_prom_exporter_port;
+        if let Err(e) = client_metrics_manager
+            .start(self.client_config.metrics_prom_exporter_host.as_str(), port)
+            .await
+        {
+            error!(
+                "Start client metrics exporter on port {} failed: {}",
+                port, e
+            );
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn client_metrics_manager(&self) -> Option<&Arc<ClientMetricsManager>> {
+        self.client_metrics_manager.as_ref()
+    }
 
     pub async fn register_producer(&mut self, group: &str, producer: MQProducerInnerImpl) -> bool {
         if group.is_empty() {
@@ -1231,15 +1293,123 @@ impl MQClientInstance {
         consumer_group: &CheetahString,
         broker_name: Option<CheetahString>,
     ) -> Option<ConsumeMessageDirectlyResult> {
-        let consumer_table = self.consumer_table.read().await;
-        let consumer_inner = consumer_table.get(consumer_group);
-        if let Some(consumer) = consumer_inner {
-            consumer
-                .consume_message_directly(message, broker_name)
-                .await;
+        let consumer = self.select_consumer(consumer_group).await?;
+        consumer
+            .consume_message_directly(message, broker_name)
+            .await
+    }
+
+    #[inline]
+    pub fn consumer_stats_manager(&self) -> &ConsumerStatsManager {
+        &self.consumer_stats_manager
+    }
+
+    /// Resets the consume offsets of a push consumer. Pulling is suspended while the process
+    /// queues of the affected message queues are dropped and rebuilt from the new offsets.
+    pub async fn reset_offset(
+        &self,
+        topic: &CheetahString,
+        group: &CheetahString,
+        offset_table: HashMap<MessageQueue, i64>,
+    ) {
+        let Some(consumer) = self
+            .select_consumer(group)
+            .await
+            .and_then(|consumer| consumer.default_mqpush_consumer_impl)
+        else {
+            info!("[reset-offset] consumer does not exist. group={}", group);
+            return;
+        };
+        consumer.suspend();
+
+        let process_queue_table = consumer
+            .rebalance_impl
+            .rebalance_impl_inner
+            .process_queue_table
+            .clone();
+        for (mq, pq) in process_queue_table.read().await.iter() {
+            if mq.get_topic() == topic && offset_table.contains_key(mq) {
+                pq.set_dropped(true);
+                pq.clear().await;
+            }
         }
 
-        None
+        tokio::time::sleep(Duration::from_secs(10)).await;
+
+        let mut process_queue_table = process_queue_table.write().await;
+        let reset_queues = process_queue_table
+            .keys()
+            .filter(|mq| mq.get_topic() == topic)
+            .filter_map(|mq| offset_table.get(mq).map(|offset| (mq.clone(), *offset)))
+            .collect::<Vec<_>>();
+        for (mq, offset) in reset_queues {
+            if let Some(ref offset_store) = consumer.offset_store {
+                offset_store.update_offset(&mq, offset, false).await;
+            }
+            if let Some(pq) = process_queue_table.remove(&mq) {
+                consumer
+                    .rebalance_impl
+                    .mut_from_ref()
+                    .remove_unnecessary_message_queue(&mq, pq.as_ref())
+                    .await;
+            }
+            info!(
+                "[reset-offset] reset offset of {} to {}. group={}",
+                mq, offset, group
+            );
+        }
+        drop(process_queue_table);
+        consumer.resume().await;
+    }
+
+    /// Returns the consume offsets the consumer `group` holds for `topic`.
+    pub async fn get_consumer_status(
+        &self,
+        topic: &CheetahString,
+        group: &CheetahString,
+    ) -> HashMap<MessageQueue, i64> {
+        let Some(consumer) = self.select_consumer(group).await else {
+            return HashMap::new();
+        };
+        let offset_store = if let Some(ref push) = consumer.default_mqpush_consumer_impl {
+            push.offset_store.clone()
+        } else if let Some(ref lite_pull) = consumer.default_lite_pull_consumer_impl {
+            lite_pull.offset_store.clone()
+        } else {
+            None
+        };
+        match offset_store {
+            Some(offset_store) => offset_store.clone_offset_table(topic).await,
+            None => HashMap::new(),
+        }
+    }
+
+    pub async fn consumer_running_info(
+        &self,
+        consumer_group: &CheetahString,
+    ) -> Option<ConsumerRunningInfo> {
+        let consumer = self.select_consumer(consumer_group).await?;
+        let mut consumer_running_info = consumer.consumer_running_info().await;
+        let ns_addr = self
+            .mq_client_api_impl
+            .as_ref()
+            .map(|api| {
+                api.get_name_server_address_list()
+                    .iter()
+                    .map(|addr| format!("{addr};"))
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
+        consumer_running_info.set_property(ConsumerRunningInfo::PROP_NAMESERVER_ADDR, ns_addr);
+        consumer_running_info.set_property(
+            ConsumerRunningInfo::PROP_CONSUME_TYPE,
+            consumer.consume_type().name(),
+        );
+        consumer_running_info.set_property(
+            ConsumerRunningInfo::PROP_CLIENT_VERSION,
+            RocketMqVersion::CURRENT_VERSION.to_string(),
+        );
+        Some(consumer_running_info)
     }
 }
 
@@ -16,7 +16,7 @@
  */
 use crate::hook::consume_message_context::ConsumeMessageContext;
 
-pub trait ConsumeMessageHook {
+pub trait ConsumeMessageHook: Send + Sync {
     fn hook_name(&self) -> &str;
 
     fn consume_message_before(&self, context: Option<&mut ConsumeMessageContext>);
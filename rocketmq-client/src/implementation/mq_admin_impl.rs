@@ -14,12 +14,25 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashSet;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::boundary_type::BoundaryType;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::MessageDecoder;
 use rocketmq_error::mq_client_err;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::header::query_message_request_header::QueryMessageRequestHeader;
 use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
 use rocketmq_rust::ArcMut;
+use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
+use crate::base::query_result::QueryResult;
 use crate::factory::mq_client_instance;
 use crate::factory::mq_client_instance::MQClientInstance;
 use crate::implementation::mq_client_api_impl::MQClientAPIImpl;
@@ -92,7 +105,64 @@ impl MQAdminImpl {
         ))
     }
 
+    pub async fn fetch_subscribe_message_queues(
+        &mut self,
+        topic: &str,
+    ) -> rocketmq_error::RocketMQResult<HashSet<MessageQueue>> {
+        let topic_route_data = self
+            .client
+            .as_mut()
+            .expect("client is None")
+            .mq_client_api_impl
+            .as_mut()
+            .expect("mq_client_api_impl is None")
+            .get_topic_route_info_from_name_server_detail(topic, self.timeout_millis, true)
+            .await?;
+        if let Some(topic_route_data) = topic_route_data {
+            let message_queues =
+                mq_client_instance::topic_route_data2topic_subscribe_info(topic, &topic_route_data);
+            if !message_queues.is_empty() {
+                return Ok(message_queues);
+            }
+            return mq_client_err!(format!(
+                "Can not find Message Queue for this topic, {} Namesrv return empty",
+                topic
+            ));
+        }
+        mq_client_err!(format!(
+            "Can not find Message Queue for this topic, {}",
+            topic
+        ))
+    }
+
     pub async fn max_offset(&mut self, mq: &MessageQueue) -> rocketmq_error::RocketMQResult<i64> {
+        let broker_addr = self.find_broker_addr_for_offset(mq).await?;
+        self.client
+            .as_mut()
+            .expect("client is None")
+            .mq_client_api_impl
+            .as_mut()
+            .expect("mq_client_api_impl is None")
+            .get_max_offset(&broker_addr, mq, self.timeout_millis)
+            .await
+    }
+
+    pub async fn min_offset(&mut self, mq: &MessageQueue) -> rocketmq_error::RocketMQResult<i64> {
+        let broker_addr = self.find_broker_addr_for_offset(mq).await?;
+        self.client
+            .as_mut()
+            .expect("client is None")
+            .mq_client_api_impl
+            .as_mut()
+            .expect("mq_client_api_impl is None")
+            .get_min_offset(&broker_addr, mq, self.timeout_millis)
+            .await
+    }
+
+    async fn find_broker_addr_for_offset(
+        &mut self,
+        mq: &MessageQueue,
+    ) -> rocketmq_error::RocketMQResult<CheetahString> {
         let client = self.client.as_mut().expect("client is None");
         let broker_name = client.get_broker_name_from_message_queue(mq).await;
         let mut broker_addr = client
@@ -107,23 +177,184 @@ impl MQAdminImpl {
                 .find_broker_address_in_publish(broker_name.as_ref())
                 .await;
         }
-        if let Some(ref broker_addr) = broker_addr {
-            let offset = client
-                .mq_client_api_impl
-                .as_mut()
-                .expect("mq_client_api_impl is None")
-                .get_max_offset(broker_addr, mq, self.timeout_millis)
-                .await?;
-            return Ok(offset);
+        match broker_addr {
+            Some(broker_addr) => Ok(broker_addr),
+            None => mq_client_err!(format!("The broker[{}] not exist", mq.get_broker_name())),
+        }
+    }
+
+    /// Looks a message up by its offset message id, which encodes the store host and the commit
+    /// log offset.
+    pub async fn view_message(
+        &mut self,
+        topic: &CheetahString,
+        msg_id: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<MessageExt> {
+        if !is_offset_msg_id(msg_id) {
+            return mq_client_err!(
+                ResponseCode::NoMessage,
+                format!("The message id {} is not an offset message id", msg_id)
+            );
+        }
+        let message_id = MessageDecoder::decode_message_id(msg_id);
+        let client = self.client.as_ref().expect("client is None");
+        client
+            .mq_client_api_impl
+            .as_ref()
+            .expect("mq_client_api_impl is None")
+            .view_message(
+                &CheetahString::from_string(message_id.address.to_string()),
+                topic.clone(),
+                message_id.offset,
+                self.timeout_millis,
+            )
+            .await
+    }
+
+    /// Queries the index of every broker serving `topic` and merges the results. With
+    /// `is_unique_key` the key is matched against the client generated message id, otherwise
+    /// against the message keys.
+    pub async fn query_message(
+        &mut self,
+        topic: &CheetahString,
+        key: &CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+        is_unique_key: bool,
+    ) -> rocketmq_error::RocketMQResult<QueryResult> {
+        let client = self.client.as_ref().expect("client is None");
+        let mq_client_api_impl = client
+            .mq_client_api_impl
+            .as_ref()
+            .expect("mq_client_api_impl is None");
+        let Some(topic_route_data) = mq_client_api_impl
+            .get_topic_route_info_from_name_server(topic, self.timeout_millis)
+            .await?
+        else {
+            return mq_client_err!(
+                ResponseCode::TopicNotExist,
+                format!("The topic[{}] not matched route info", topic)
+            );
+        };
+
+        let mut index_last_update_timestamp = 0;
+        let mut message_list = Vec::new();
+        let mut last_error = None;
+        for broker_data in topic_route_data.broker_datas.iter() {
+            let Some(addr) = broker_data.select_broker_addr() else {
+                continue;
+            };
+            let request_header = QueryMessageRequestHeader {
+                topic: topic.clone(),
+                key: key.clone(),
+                max_num,
+                begin_timestamp: begin,
+                end_timestamp: end,
+                topic_request_header: None,
+            };
+            match mq_client_api_impl
+                .query_message(&addr, request_header, self.timeout_millis, is_unique_key)
+                .await
+            {
+                Ok(query_result) => {
+                    index_last_update_timestamp =
+                        index_last_update_timestamp.max(query_result.index_last_update_timestamp());
+                    message_list.extend(query_result.message_list().iter().cloned());
+                }
+                Err(e) => {
+                    warn!("queryMessage from broker {} failed: {}", addr, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let message_list: Vec<MessageExt> = message_list
+            .into_iter()
+            .filter(|message| {
+                if is_unique_key {
+                    MessageClientIDSetter::get_uniq_id(message).as_ref() == Some(key)
+                } else {
+                    message.get_keys().is_some_and(|keys| {
+                        keys.split(MessageConst::KEY_SEPARATOR)
+                            .any(|k| k == key.as_str())
+                    })
+                }
+            })
+            .collect();
+        if message_list.is_empty() {
+            if let Some(e) = last_error {
+                return Err(e);
+            }
+            return mq_client_err!(
+                ResponseCode::NoMessage,
+                "query message by key finished, but no message."
+            );
         }
+        Ok(QueryResult::new(index_last_update_timestamp, message_list))
+    }
 
-        unimplemented!("max_offset")
+    pub async fn query_message_by_uniq_key(
+        &mut self,
+        topic: &CheetahString,
+        uniq_key: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<MessageExt> {
+        let query_result = self
+            .query_message(topic, uniq_key, 32, 0, i64::MAX, true)
+            .await?;
+        query_result
+            .message_list()
+            .iter()
+            .min_by_key(|message| message.store_timestamp())
+            .cloned()
+            .map_or_else(
+                || {
+                    mq_client_err!(
+                        ResponseCode::NoMessage,
+                        format!("The message {} not found", uniq_key)
+                    )
+                },
+                Ok,
+            )
     }
+
     pub async fn search_offset(
         &mut self,
         mq: &MessageQueue,
         timestamp: u64,
     ) -> rocketmq_error::RocketMQResult<i64> {
-        unimplemented!("max_offset")
+        let broker_addr = self.find_broker_addr_for_offset(mq).await?;
+        self.client
+            .as_mut()
+            .expect("client is None")
+            .mq_client_api_impl
+            .as_mut()
+            .expect("mq_client_api_impl is None")
+            .search_offset(
+                &broker_addr,
+                mq,
+                timestamp,
+                BoundaryType::Lower,
+                self.timeout_millis,
+            )
+            .await
+    }
+}
+
+/// Offset message ids are the hex encoding of the store host (IPv4 or IPv6 address and port)
+/// followed by the commit log offset.
+fn is_offset_msg_id(msg_id: &str) -> bool {
+    (msg_id.len() == 32 || msg_id.len() == 56) && msg_id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_msg_id_detection() {
+        assert!(is_offset_msg_id("0A0A0A0A00002A9F0000000000000064"));
+        assert!(!is_offset_msg_id("0A0A0A0A00002A9F"));
+        assert!(!is_offset_msg_id("0A0A0A0A00002A9F00000000000000XY"));
     }
 }
@@ -22,6 +22,10 @@ use std::time::Instant;
 
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
+use rocketmq_common::common::attribute::attribute_parser::AttributeParser;
+use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
+use rocketmq_common::common::boundary_type::BoundaryType;
+use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_batch::MessageBatch;
 use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
@@ -45,47 +49,108 @@ use rocketmq_error::RocketmqError::MQClientBrokerError;
 use rocketmq_remoting::base::connection_net_event::ConnectionNetEvent;
 use rocketmq_remoting::clients::rocketmq_default_impl::RocketmqDefaultClient;
 use rocketmq_remoting::clients::RemotingClient;
+use rocketmq_remoting::code::request_code::ControllerRequestCode;
 use rocketmq_remoting::code::request_code::RequestCode;
 use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::consume_stats_list::ConsumeStatsList;
+use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
 use rocketmq_remoting::protocol::body::batch_ack_message_request_body::BatchAckMessageRequestBody;
+use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
 use rocketmq_remoting::protocol::body::check_client_request_body::CheckClientRequestBody;
+use rocketmq_remoting::protocol::body::check_rocksdb_cqwrite_progress_response_body::CheckRocksdbCqWriteProgressResponseBody;
+use rocketmq_remoting::protocol::body::cluster_acl_version_info::ClusterAclVersionInfo;
+use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
+use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::elect_master_response_body::ElectMasterResponseBody;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
 use rocketmq_remoting::protocol::body::get_consumer_listby_group_response_body::GetConsumerListByGroupResponseBody;
+use rocketmq_remoting::protocol::body::group_list::GroupList;
+use rocketmq_remoting::protocol::body::kv_table::KVTable;
+use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
 use rocketmq_remoting::protocol::body::query_assignment_request_body::QueryAssignmentRequestBody;
 use rocketmq_remoting::protocol::body::query_assignment_response_body::QueryAssignmentResponseBody;
 use rocketmq_remoting::protocol::body::request::lock_batch_request_body::LockBatchRequestBody;
+use rocketmq_remoting::protocol::body::reset_offset_body::ResetOffsetBody;
 use rocketmq_remoting::protocol::body::response::lock_batch_response_body::LockBatchResponseBody;
 use rocketmq_remoting::protocol::body::set_message_request_mode_request_body::SetMessageRequestModeRequestBody;
+use rocketmq_remoting::protocol::body::subscription_group_list::SubscriptionGroupList;
+use rocketmq_remoting::protocol::body::subscription_group_wrapper::SubscriptionGroupWrapper;
+use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::unlock_batch_request_body::UnlockBatchRequestBody;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
 use rocketmq_remoting::protocol::header::ack_message_request_header::AckMessageRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::CreateAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::DeleteAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::GetAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::ListAclsRequestHeader;
+use rocketmq_remoting::protocol::header::auth::acl_request_header::UpdateAclRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::CreateUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::DeleteUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::GetUserRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::ListUsersRequestHeader;
+use rocketmq_remoting::protocol::header::auth::user_request_header::UpdateUserRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_request_header::ChangeInvisibleTimeRequestHeader;
 use rocketmq_remoting::protocol::header::change_invisible_time_response_header::ChangeInvisibleTimeResponseHeader;
+use rocketmq_remoting::protocol::header::check_rocksdb_cq_write_progress_request_header::CheckRocksdbCqWriteProgressRequestHeader;
 use rocketmq_remoting::protocol::header::client_request_header::GetRouteInfoRequestHeader;
 use rocketmq_remoting::protocol::header::consumer_send_msg_back_request_header::ConsumerSendMsgBackRequestHeader;
+use rocketmq_remoting::protocol::header::controller::elect_master_request_header::ElectMasterRequestHeader;
+use rocketmq_remoting::protocol::header::create_access_config_request_header::CreateAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::create_topic_request_header::CreateTopicRequestHeader;
+use rocketmq_remoting::protocol::header::delete_access_config_request_header::DeleteAccessConfigRequestHeader;
+use rocketmq_remoting::protocol::header::delete_subscription_group_request_header::DeleteSubscriptionGroupRequestHeader;
+use rocketmq_remoting::protocol::header::delete_topic_request_header::DeleteTopicRequestHeader;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
 use rocketmq_remoting::protocol::header::end_transaction_request_header::EndTransactionRequestHeader;
 use rocketmq_remoting::protocol::header::extra_info_util::ExtraInfoUtil;
+use rocketmq_remoting::protocol::header::get_consume_stats_in_broker_header::GetConsumeStatsInBrokerHeader;
+use rocketmq_remoting::protocol::header::get_consume_stats_request_header::GetConsumeStatsRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_connection_list_request_header::GetConsumerConnectionListRequestHeader;
 use rocketmq_remoting::protocol::header::get_consumer_listby_group_request_header::GetConsumerListByGroupRequestHeader;
+use rocketmq_remoting::protocol::header::get_consumer_running_info_request_header::GetConsumerRunningInfoRequestHeader;
 use rocketmq_remoting::protocol::header::get_max_offset_request_header::GetMaxOffsetRequestHeader;
 use rocketmq_remoting::protocol::header::get_max_offset_response_header::GetMaxOffsetResponseHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
+use rocketmq_remoting::protocol::header::get_min_offset_request_header::GetMinOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::get_min_offset_response_header::GetMinOffsetResponseHeader;
+use rocketmq_remoting::protocol::header::get_producer_connection_list_request_header::GetProducerConnectionListRequestHeader;
+use rocketmq_remoting::protocol::header::get_topic_stats_info_request_header::GetTopicStatsInfoRequestHeader;
 use rocketmq_remoting::protocol::header::heartbeat_request_header::HeartbeatRequestHeader;
 use rocketmq_remoting::protocol::header::lock_batch_mq_request_header::LockBatchMqRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header::SendMessageRequestHeader;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_request_header_v2::SendMessageRequestHeaderV2;
 use rocketmq_remoting::protocol::header::message_operation_header::send_message_response_header::SendMessageResponseHeader;
+use rocketmq_remoting::protocol::header::namesrv::topic_operation_header::DeleteTopicFromNamesrvRequestHeader;
 use rocketmq_remoting::protocol::header::pop_message_request_header::PopMessageRequestHeader;
 use rocketmq_remoting::protocol::header::pop_message_response_header::PopMessageResponseHeader;
 use rocketmq_remoting::protocol::header::pull_message_request_header::PullMessageRequestHeader;
 use rocketmq_remoting::protocol::header::pull_message_response_header::PullMessageResponseHeader;
 use rocketmq_remoting::protocol::header::query_consumer_offset_request_header::QueryConsumerOffsetRequestHeader;
 use rocketmq_remoting::protocol::header::query_consumer_offset_response_header::QueryConsumerOffsetResponseHeader;
+use rocketmq_remoting::protocol::header::query_message_request_header::QueryMessageRequestHeader;
+use rocketmq_remoting::protocol::header::query_message_response_header::QueryMessageResponseHeader;
+use rocketmq_remoting::protocol::header::query_topic_consume_by_who_request_header::QueryTopicConsumeByWhoRequestHeader;
+use rocketmq_remoting::protocol::header::reset_offset_request_header::ResetOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::search_offset_request_header::SearchOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::search_offset_response_header::SearchOffsetResponseHeader;
 use rocketmq_remoting::protocol::header::unlock_batch_mq_request_header::UnlockBatchMqRequestHeader;
 use rocketmq_remoting::protocol::header::unregister_client_request_header::UnregisterClientRequestHeader;
 use rocketmq_remoting::protocol::header::update_consumer_offset_header::UpdateConsumerOffsetRequestHeader;
+use rocketmq_remoting::protocol::header::update_global_white_addrs_config_request_header::UpdateGlobalWhiteAddrsConfigRequestHeader;
+use rocketmq_remoting::protocol::header::view_broker_stats_data_request_header::ViewBrokerStatsDataRequestHeader;
+use rocketmq_remoting::protocol::header::view_message_request_header::ViewMessageRequestHeader;
 use rocketmq_remoting::protocol::heartbeat::heartbeat_data::HeartbeatData;
 use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
 use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_remoting::protocol::subscription::broker_stats_data::BrokerStatsData;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 use rocketmq_remoting::protocol::RemotingDeserializable;
 use rocketmq_remoting::protocol::RemotingSerializable;
 use rocketmq_remoting::remoting::RemotingService;
@@ -98,6 +163,7 @@ use tracing::error;
 use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
+use crate::base::query_result::QueryResult;
 use crate::consumer::ack_callback::AckCallback;
 use crate::consumer::ack_result::AckResult;
 use crate::consumer::ack_status::AckStatus;
@@ -146,14 +212,14 @@ impl MQClientAPIImpl {
         rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
         client_config: ClientConfig,
         tx: Option<tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
-    ) -> Self {
+    ) -> rocketmq_error::RocketMQResult<Self> {
         let mut default_client =
-            RocketmqDefaultClient::new_with_cl(tokio_client_config, client_remoting_processor, tx);
+            RocketmqDefaultClient::new_with_cl(tokio_client_config, client_remoting_processor, tx)?;
         if let Some(hook) = rpc_hook {
             default_client.register_rpc_hook(hook);
         }
 
-        MQClientAPIImpl {
+        Ok(MQClientAPIImpl {
             remoting_client: ArcMut::new(default_client),
             top_addressing: Box::new(DefaultTopAddressing::new(
                 mix_all::get_ws_addr().into(),
@@ -162,7 +228,7 @@ impl MQClientAPIImpl {
             //client_remoting_processor,
             name_srv_addr: None,
             client_config,
-        }
+        })
     }
 
     pub async fn start(&self) {
@@ -1252,6 +1318,102 @@ impl MQClientAPIImpl {
         )
     }
 
+    pub async fn get_min_offset(
+        &mut self,
+        addr: &str,
+        message_queue: &MessageQueue,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        let request_header = GetMinOffsetRequestHeader {
+            topic: CheetahString::from_slice(message_queue.get_topic()),
+            queue_id: message_queue.get_queue_id(),
+            topic_request_header: Some(TopicRequestHeader {
+                rpc_request_header: Some(RpcRequestHeader {
+                    broker_name: Some(CheetahString::from_slice(message_queue.get_broker_name())),
+                    ..Default::default()
+                }),
+                lo: None,
+            }),
+        };
+
+        let request =
+            RemotingCommand::create_request_command(RequestCode::GetMinOffset, request_header);
+
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let response_header = response
+                .decode_command_custom_header::<GetMinOffsetResponseHeader>()
+                .expect("decode error");
+            return Ok(response_header.offset);
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn search_offset(
+        &mut self,
+        addr: &str,
+        message_queue: &MessageQueue,
+        timestamp: u64,
+        boundary_type: BoundaryType,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<i64> {
+        let request_header = SearchOffsetRequestHeader {
+            topic: CheetahString::from_slice(message_queue.get_topic()),
+            queue_id: message_queue.get_queue_id(),
+            timestamp: timestamp as i64,
+            boundary_type: Some(CheetahString::from_static_str(boundary_type.get_name())),
+            topic_request_header: Some(TopicRequestHeader {
+                rpc_request_header: Some(RpcRequestHeader {
+                    broker_name: Some(CheetahString::from_slice(message_queue.get_broker_name())),
+                    ..Default::default()
+                }),
+                lo: None,
+            }),
+        };
+
+        let request = RemotingCommand::create_request_command(
+            RequestCode::SearchOffsetByTimestamp,
+            request_header,
+        );
+
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let response_header = response
+                .decode_command_custom_header::<SearchOffsetResponseHeader>()
+                .expect("decode error");
+            return Ok(response_header.offset);
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
     pub async fn set_message_request_mode(
         &mut self,
         broker_addr: &CheetahString,
@@ -1741,6 +1903,1109 @@ impl MQClientAPIImpl {
         }
         Ok(())
     }
+
+    pub async fn get_controller_meta_data(
+        &self,
+        controller_addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<GetMetaDataResponseHeader> {
+        let request = RemotingCommand::create_remoting_command(
+            ControllerRequestCode::ControllerGetMetadataInfo,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(controller_addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return response.decode_command_custom_header::<GetMetaDataResponseHeader>();
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    /// The controller requests except the metadata query must be sent to the leader.
+    async fn get_controller_leader_address(
+        &self,
+        controller_addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<CheetahString> {
+        let meta_data = self
+            .get_controller_meta_data(controller_addr, timeout_millis)
+            .await?;
+        match meta_data.controller_leader_address {
+            Some(leader_address) if !leader_address.is_empty() => Ok(leader_address),
+            _ => Ok(controller_addr.clone()),
+        }
+    }
+
+    pub async fn get_in_sync_state_data(
+        &self,
+        controller_addr: &CheetahString,
+        brokers: Vec<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<BrokerReplicasInfo> {
+        let leader_address = self
+            .get_controller_leader_address(controller_addr, timeout_millis)
+            .await?;
+        let request = RemotingCommand::create_remoting_command(
+            ControllerRequestCode::ControllerGetSyncStateData,
+        )
+        .set_body(brokers.encode()?);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(&leader_address), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return BrokerReplicasInfo::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn check_rocksdb_cq_write_progress(
+        &self,
+        broker_addr: &CheetahString,
+        topic: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<CheckRocksdbCqWriteProgressResponseBody> {
+        let request_header = CheckRocksdbCqWriteProgressRequestHeader {
+            topic,
+            check_store_time: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::CheckRocksdbCqWriteProgress,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(broker_addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return CheckRocksdbCqWriteProgressResponseBody::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn get_consume_stats(
+        &self,
+        addr: &CheetahString,
+        consumer_group: CheetahString,
+        topic: Option<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumeStats> {
+        let request_header = GetConsumeStatsRequestHeader {
+            consumer_group,
+            topic: topic.unwrap_or_default(),
+            topic_request_header: None,
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::GetConsumeStats, request_header);
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ConsumeStats::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn get_consumer_connection_list(
+        &self,
+        addr: &CheetahString,
+        consumer_group: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumerConnection> {
+        let request_header = GetConsumerConnectionListRequestHeader {
+            consumer_group,
+            rpc_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetConsumerConnectionList,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ConsumerConnection::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn get_consumer_running_info(
+        &self,
+        addr: &CheetahString,
+        consumer_group: CheetahString,
+        client_id: CheetahString,
+        jstack: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumerRunningInfo> {
+        let request_header = GetConsumerRunningInfoRequestHeader {
+            consumer_group,
+            client_id,
+            jstack_enable: jstack,
+            rpc_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetConsumerRunningInfo,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ConsumerRunningInfo::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn invoke_broker_to_reset_offset(
+        &self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        group: CheetahString,
+        timestamp: u64,
+        is_force: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<HashMap<MessageQueue, i64>> {
+        let request_header = ResetOffsetRequestHeader {
+            topic,
+            group,
+            timestamp: timestamp as i64,
+            is_force,
+            ..Default::default()
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::InvokeBrokerToResetOffset,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return match response.body() {
+                Some(body) => Ok(ResetOffsetBody::decode(body)?.offset_table),
+                None => Ok(HashMap::new()),
+            };
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn query_topic_consume_by_who(
+        &self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<GroupList> {
+        let request_header = QueryTopicConsumeByWhoRequestHeader {
+            topic,
+            topic_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::QueryTopicConsumeByWho,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return GroupList::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn get_broker_cluster_info(
+        &self,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ClusterInfo> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerClusterInfo);
+        let response = self
+            .remoting_client
+            .invoke_async(None, request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ClusterInfo::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string())
+        )
+    }
+
+    pub async fn get_topic_list_from_name_server(
+        &self,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<TopicList> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::GetAllTopicListFromNameserver);
+        let response = self
+            .remoting_client
+            .invoke_async(None, request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return TopicList::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string())
+        )
+    }
+
+    pub async fn get_topic_stats_info(
+        &self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<TopicStatsTable> {
+        let request_header = GetTopicStatsInfoRequestHeader {
+            topic,
+            topic_request_header: None,
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::GetTopicStatsInfo, request_header);
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return TopicStatsTable::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn get_broker_runtime_info(
+        &self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<KVTable> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerRuntimeInfo);
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return KVTable::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn view_message(
+        &self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        phy_offset: i64,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<MessageExt> {
+        let request_header = ViewMessageRequestHeader {
+            topic: topic.clone(),
+            offset: phy_offset,
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::ViewMessageById, request_header);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                let mut body = body.clone();
+                if let Some(message) =
+                    MessageDecoder::decode(&mut body, true, true, false, false, false)
+                {
+                    if message.get_topic() != &topic {
+                        return mq_client_err!(
+                            ResponseCode::NoMessage,
+                            format!("The message {} not belong to topic {}", phy_offset, topic)
+                        );
+                    }
+                    return Ok(message);
+                }
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn query_message(
+        &self,
+        addr: &CheetahString,
+        request_header: QueryMessageRequestHeader,
+        timeout_millis: u64,
+        is_unique_key: bool,
+    ) -> rocketmq_error::RocketMQResult<QueryResult> {
+        let mut request =
+            RemotingCommand::create_request_command(RequestCode::QueryMessage, request_header);
+        request.add_ext_field(mix_all::UNIQUE_MSG_QUERY_FLAG, is_unique_key.to_string());
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        match ResponseCode::from(response.code()) {
+            ResponseCode::Success => {
+                let response_header =
+                    response.decode_command_custom_header::<QueryMessageResponseHeader>()?;
+                let message_list = response.body().as_ref().map_or_else(Vec::new, |body| {
+                    MessageDecoder::decodes_batch(&mut body.clone(), true, true)
+                });
+                Ok(QueryResult::new(
+                    response_header.index_last_update_timestamp as u64,
+                    message_list,
+                ))
+            }
+            ResponseCode::QueryNotFound => Ok(QueryResult::default()),
+            _ => client_broker_err!(
+                response.code(),
+                response.remark().map_or("".to_string(), |s| s.to_string()),
+                addr.to_string()
+            ),
+        }
+    }
+
+    pub async fn create_plain_access_config(
+        &self,
+        addr: &CheetahString,
+        config: PlainAccessConfig,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let join_perms = |perms: Vec<CheetahString>| {
+            (!perms.is_empty()).then(|| {
+                CheetahString::from_string(
+                    perms
+                        .iter()
+                        .map(CheetahString::as_str)
+                        .collect::<Vec<_>>()
+                        .join(","),
+                )
+            })
+        };
+        let request_header = CreateAccessConfigRequestHeader {
+            access_key: config.access_key.unwrap_or_default(),
+            secret_key: config.secret_key,
+            white_remote_address: config.white_remote_address,
+            default_topic_perm: config.default_topic_perm,
+            default_group_perm: config.default_group_perm,
+            admin: config.admin,
+            topic_perms: join_perms(config.topic_perms),
+            group_perms: join_perms(config.group_perms),
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::UpdateAndCreateAclConfig,
+            request_header,
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_access_config(
+        &self,
+        addr: &CheetahString,
+        access_key: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteAclConfig,
+            DeleteAccessConfigRequestHeader { access_key },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn update_global_white_addrs_config(
+        &self,
+        addr: &CheetahString,
+        global_white_addrs: CheetahString,
+        acl_file_full_path: Option<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::UpdateGlobalWhiteAddrsConfig,
+            UpdateGlobalWhiteAddrsConfigRequestHeader {
+                global_white_addrs,
+                acl_file_full_path,
+            },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn get_broker_cluster_acl_info(
+        &self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ClusterAclVersionInfo> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::GetBrokerClusterAclInfo);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ClusterAclVersionInfo::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn create_topic(
+        &self,
+        addr: &CheetahString,
+        default_topic: CheetahString,
+        topic_config: &TopicConfig,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let attributes = topic_config
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<String, String>>();
+        let request_header = CreateTopicRequestHeader {
+            topic: topic_config.topic_name.clone().unwrap_or_default(),
+            default_topic,
+            read_queue_nums: topic_config.read_queue_nums as i32,
+            write_queue_nums: topic_config.write_queue_nums as i32,
+            perm: topic_config.perm as i32,
+            topic_filter_type: topic_config.topic_filter_type.to_string().into(),
+            topic_sys_flag: Some(topic_config.topic_sys_flag as i32),
+            order: topic_config.order,
+            attributes: (!attributes.is_empty())
+                .then(|| AttributeParser::parse_to_string(&attributes).into()),
+            force: None,
+            topic_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::UpdateAndCreateTopic,
+            request_header,
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_topic_in_broker(
+        &self,
+        addr: &CheetahString,
+        topic: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteTopicInBroker,
+            DeleteTopicRequestHeader {
+                topic,
+                topic_request_header: None,
+            },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_topic_in_name_server(
+        &self,
+        addr: &CheetahString,
+        cluster_name: Option<CheetahString>,
+        topic: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteTopicInNamesrv,
+            DeleteTopicFromNamesrvRequestHeader::new(topic, cluster_name),
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn update_broker_config(
+        &self,
+        addr: &CheetahString,
+        properties: &HashMap<CheetahString, CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let body = properties
+            .iter()
+            .map(|(key, value)| format!("{}={}\n", key, value))
+            .collect::<String>();
+        let request = RemotingCommand::create_remoting_command(RequestCode::UpdateBrokerConfig)
+            .set_body(body);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn get_broker_config(
+        &self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<HashMap<CheetahString, CheetahString>> {
+        let request = RemotingCommand::create_remoting_command(RequestCode::GetBrokerConfig);
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let Some(body) = response.body() else {
+                return Ok(HashMap::new());
+            };
+            if let Some(properties) = std::str::from_utf8(body)
+                .ok()
+                .and_then(mix_all::string_to_properties)
+            {
+                return Ok(properties);
+            }
+            return mq_client_err!(format!("Invalid broker config returned by {}", addr));
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn get_producer_connection_list(
+        &self,
+        addr: &CheetahString,
+        producer_group: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ProducerConnection> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetProducerConnectionList,
+            GetProducerConnectionListRequestHeader {
+                producer_group,
+                rpc_request_header: None,
+            },
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(
+                Some(&mix_all::broker_vip_channel(
+                    self.client_config.vip_channel_enabled,
+                    addr,
+                )),
+                request,
+                timeout_millis,
+            )
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ProducerConnection::decode(body);
+            }
+        }
+        client_broker_err!(
+            response.code(),
+            response.remark().map_or("".to_string(), |s| s.to_string()),
+            addr.to_string()
+        )
+    }
+
+    pub async fn create_subscription_group(
+        &self,
+        addr: &CheetahString,
+        config: &SubscriptionGroupConfig,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::UpdateAndCreateSubscriptionGroup)
+                .set_body(config.encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn create_subscription_group_list(
+        &self,
+        addr: &CheetahString,
+        configs: Vec<SubscriptionGroupConfig>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_remoting_command(
+            RequestCode::UpdateAndCreateSubscriptionGroupList,
+        )
+        .set_body(SubscriptionGroupList::new(configs).encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_subscription_group(
+        &self,
+        addr: &CheetahString,
+        group_name: CheetahString,
+        clean_offset: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::DeleteSubscriptionGroup,
+            DeleteSubscriptionGroupRequestHeader {
+                group_name,
+                clean_offset,
+                rpc_request_header: None,
+            },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn get_all_subscription_group(
+        &self,
+        addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<SubscriptionGroupWrapper> {
+        let request =
+            RemotingCommand::create_remoting_command(RequestCode::GetAllSubscriptionGroupConfig);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return SubscriptionGroupWrapper::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn view_broker_stats_data(
+        &self,
+        addr: &CheetahString,
+        stats_name: CheetahString,
+        stats_key: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<BrokerStatsData> {
+        let request_header = ViewBrokerStatsDataRequestHeader {
+            stats_name,
+            stats_key,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::ViewBrokerStatsData,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return BrokerStatsData::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn fetch_consume_stats_in_broker(
+        &self,
+        addr: &CheetahString,
+        is_order: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumeStatsList> {
+        let request_header = GetConsumeStatsInBrokerHeader {
+            is_order,
+            rpc_request_header: None,
+        };
+        let request = RemotingCommand::create_request_command(
+            RequestCode::GetBrokerConsumeStats,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return ConsumeStatsList::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn create_user(
+        &self,
+        addr: &CheetahString,
+        user_info: UserInfo,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request_header = CreateUserRequestHeader {
+            username: user_info.username.clone().unwrap_or_default(),
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::AuthCreateUser, request_header)
+                .set_body(user_info.encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn update_user(
+        &self,
+        addr: &CheetahString,
+        user_info: UserInfo,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request_header = UpdateUserRequestHeader {
+            username: user_info.username.clone().unwrap_or_default(),
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::AuthUpdateUser, request_header)
+                .set_body(user_info.encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_user(
+        &self,
+        addr: &CheetahString,
+        username: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthDeleteUser,
+            DeleteUserRequestHeader { username },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn get_user(
+        &self,
+        addr: &CheetahString,
+        username: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<UserInfo> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthGetUser,
+            GetUserRequestHeader { username },
+        );
+        self.invoke_admin_query(addr, request, timeout_millis).await
+    }
+
+    pub async fn list_users(
+        &self,
+        addr: &CheetahString,
+        filter: Option<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<Vec<UserInfo>> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthListUser,
+            ListUsersRequestHeader { filter },
+        );
+        self.invoke_admin_query(addr, request, timeout_millis).await
+    }
+
+    pub async fn create_acl(
+        &self,
+        addr: &CheetahString,
+        acl_info: AclInfo,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request_header = CreateAclRequestHeader {
+            subject: acl_info.subject.clone().unwrap_or_default(),
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::AuthCreateAcl, request_header)
+                .set_body(acl_info.encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn update_acl(
+        &self,
+        addr: &CheetahString,
+        acl_info: AclInfo,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request_header = UpdateAclRequestHeader {
+            subject: acl_info.subject.clone().unwrap_or_default(),
+        };
+        let request =
+            RemotingCommand::create_request_command(RequestCode::AuthUpdateAcl, request_header)
+                .set_body(acl_info.encode()?);
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn delete_acl(
+        &self,
+        addr: &CheetahString,
+        subject: CheetahString,
+        policy_type: Option<CheetahString>,
+        resource: Option<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthDeleteAcl,
+            DeleteAclRequestHeader {
+                subject,
+                policy_type,
+                resource,
+            },
+        );
+        self.invoke_admin_request(addr, request, timeout_millis)
+            .await
+    }
+
+    pub async fn get_acl(
+        &self,
+        addr: &CheetahString,
+        subject: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<AclInfo> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthGetAcl,
+            GetAclRequestHeader { subject },
+        );
+        self.invoke_admin_query(addr, request, timeout_millis).await
+    }
+
+    pub async fn list_acl(
+        &self,
+        addr: &CheetahString,
+        subject_filter: Option<CheetahString>,
+        resource_filter: Option<CheetahString>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<Vec<AclInfo>> {
+        let request = RemotingCommand::create_request_command(
+            RequestCode::AuthListAcl,
+            ListAclsRequestHeader {
+                subject_filter,
+                resource_filter,
+            },
+        );
+        self.invoke_admin_query(addr, request, timeout_millis).await
+    }
+
+    async fn invoke_admin_query<T: RemotingDeserializable<Output = T>>(
+        &self,
+        addr: &CheetahString,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<T> {
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return T::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    async fn invoke_admin_request(
+        &self,
+        addr: &CheetahString,
+        request: RemotingCommand,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let response = self
+            .remoting_client
+            .invoke_async(Some(addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            return Ok(());
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    pub async fn get_broker_epoch_cache(
+        &self,
+        broker_addr: &CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<EpochEntryCache> {
+        let request =
+            RemotingCommand::create_remoting_command(ControllerRequestCode::GetBrokerEpochCache);
+        let response = self
+            .remoting_client
+            .invoke_async(Some(broker_addr), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            if let Some(body) = response.body() {
+                return EpochEntryCache::decode(body);
+            }
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
+
+    /// Asks the controller to elect a new master, `broker_id` designates the new master.
+    pub async fn elect_master(
+        &self,
+        controller_addr: &CheetahString,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+        broker_id: Option<i64>,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, BrokerMemberGroup)> {
+        let leader_address = self
+            .get_controller_leader_address(controller_addr, timeout_millis)
+            .await?;
+        let request_header = match broker_id {
+            Some(broker_id) => ElectMasterRequestHeader::of_admin_trigger(
+                cluster_name.clone(),
+                broker_name.clone(),
+                broker_id,
+            ),
+            None => ElectMasterRequestHeader {
+                cluster_name: cluster_name.clone(),
+                ..ElectMasterRequestHeader::of_controller_trigger(broker_name.clone())
+            },
+        };
+        let request = RemotingCommand::create_request_command(
+            ControllerRequestCode::ControllerElectMaster,
+            request_header,
+        );
+        let response = self
+            .remoting_client
+            .invoke_async(Some(&leader_address), request, timeout_millis)
+            .await?;
+        if ResponseCode::from(response.code()) == ResponseCode::Success {
+            let response_header =
+                response.decode_command_custom_header::<ElectMasterResponseHeader>()?;
+            let broker_member_group = match response.body() {
+                Some(body) => ElectMasterResponseBody::decode(body)?
+                    .broker_member_group
+                    .unwrap_or_default(),
+                None => BrokerMemberGroup::default(),
+            };
+            return Ok((response_header, broker_member_group));
+        }
+        mq_client_err!(
+            response.code(),
+            response.remark().cloned().unwrap_or_default().to_string()
+        )
+    }
 }
 
 fn build_queue_offset_sorted_map(
@@ -60,12 +60,12 @@ impl MQClientManager {
         &self,
         client_config: ClientConfig,
         rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
-    ) -> ArcMut<MQClientInstance> {
+    ) -> rocketmq_error::RocketMQResult<ArcMut<MQClientInstance>> {
         let client_id = CheetahString::from_string(client_config.build_mq_client_id());
         let mut factory_table = self.factory_table.write();
 
         if let Some(instance) = factory_table.get(&client_id) {
-            return instance.clone();
+            return Ok(instance.clone());
         }
 
         let instance = MQClientInstance::new_arc(
@@ -73,10 +73,10 @@ impl MQClientManager {
             self.factory_index_generator.fetch_add(1, Ordering::SeqCst),
             client_id.clone(),
             rpc_hook,
-        );
+        )?;
         info!("Created new MQClientInstance for clientId: [{}]", client_id);
         factory_table.insert(client_id, instance.clone());
-        instance
+        Ok(instance)
     }
 
     pub fn get_or_create_produce_accumulator(
@@ -31,7 +31,8 @@ mod hook;
 pub mod implementation;
 mod latency;
 pub mod producer;
-mod trace;
+pub mod stat;
+pub mod trace;
 pub mod utils;
 
 pub use crate::consumer::consumer_impl::pull_request_ext::PullResultExt;
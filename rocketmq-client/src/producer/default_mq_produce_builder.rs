@@ -46,6 +46,9 @@ pub struct DefaultMQProducerBuilder {
     trace_dispatcher: Option<Arc<Box<dyn TraceDispatcher + Send + Sync>>>,
     auto_batch: Option<bool>,
     produce_accumulator: Option<ProduceAccumulator>,
+    batch_max_delay_ms: Option<u32>,
+    batch_max_bytes: Option<usize>,
+    total_batch_max_bytes: Option<usize>,
     enable_backpressure_for_async_mode: Option<bool>,
     back_pressure_for_async_send_num: Option<u32>,
     back_pressure_for_async_send_size: Option<u32>,
@@ -74,6 +77,9 @@ impl DefaultMQProducerBuilder {
             trace_dispatcher: None,
             auto_batch: None,
             produce_accumulator: None,
+            batch_max_delay_ms: None,
+            batch_max_bytes: None,
+            total_batch_max_bytes: None,
             enable_backpressure_for_async_mode: None,
             back_pressure_for_async_send_num: None,
             back_pressure_for_async_send_size: None,
@@ -203,6 +209,24 @@ impl DefaultMQProducerBuilder {
         self
     }
 
+    #[inline]
+    pub fn batch_max_delay_ms(mut self, batch_max_delay_ms: u32) -> Self {
+        self.batch_max_delay_ms = Some(batch_max_delay_ms);
+        self
+    }
+
+    #[inline]
+    pub fn batch_max_bytes(mut self, batch_max_bytes: usize) -> Self {
+        self.batch_max_bytes = Some(batch_max_bytes);
+        self
+    }
+
+    #[inline]
+    pub fn total_batch_max_bytes(mut self, total_batch_max_bytes: usize) -> Self {
+        self.total_batch_max_bytes = Some(total_batch_max_bytes);
+        self
+    }
+
     #[inline]
     pub fn enable_backpressure_for_async_mode(
         mut self,
@@ -298,11 +322,20 @@ impl DefaultMQProducerBuilder {
         }
 
         mq_producer.set_trace_dispatcher(self.trace_dispatcher);
+        if let Some(produce_accumulator) = self.produce_accumulator {
+            mq_producer.set_produce_accumulator(Some(produce_accumulator));
+        }
         if let Some(auto_batch) = self.auto_batch {
             mq_producer.set_auto_batch(auto_batch);
         }
-        if let Some(produce_accumulator) = self.produce_accumulator {
-            mq_producer.set_produce_accumulator(Some(produce_accumulator));
+        if let Some(batch_max_delay_ms) = self.batch_max_delay_ms {
+            mq_producer.set_batch_max_delay_ms(batch_max_delay_ms);
+        }
+        if let Some(batch_max_bytes) = self.batch_max_bytes {
+            mq_producer.set_batch_max_bytes(batch_max_bytes);
+        }
+        if let Some(total_batch_max_bytes) = self.total_batch_max_bytes {
+            mq_producer.set_total_batch_max_bytes(total_batch_max_bytes);
         }
 
         if let Some(enable_backpressure_for_async_mode) = self.enable_backpressure_for_async_mode {
@@ -37,9 +37,11 @@ use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
 use tracing::error;
+use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
 use crate::base::validators::Validators;
+use crate::implementation::mq_client_manager::MQClientManager;
 use crate::producer::default_mq_produce_builder::DefaultMQProducerBuilder;
 use crate::producer::mq_producer::MQProducer;
 use crate::producer::produce_accumulator::ProduceAccumulator;
@@ -420,6 +422,12 @@ impl DefaultMQProducer {
 
     pub fn set_auto_batch(&mut self, auto_batch: bool) {
         self.producer_config.auto_batch = auto_batch;
+        if auto_batch && self.producer_config.produce_accumulator.is_none() {
+            self.producer_config.produce_accumulator = Some(
+                MQClientManager::get_instance()
+                    .get_or_create_produce_accumulator(self.client_config.clone()),
+            );
+        }
     }
 
     pub fn set_produce_accumulator(&mut self, produce_accumulator: Option<ProduceAccumulator>) {
@@ -428,6 +436,30 @@ impl DefaultMQProducer {
         }
     }
 
+    /// Sets how long the produce accumulator holds a batch before sending it. Has no effect
+    /// unless auto batch is enabled.
+    pub fn set_batch_max_delay_ms(&mut self, hold_ms: u32) {
+        if let Some(ref mut produce_accumulator) = self.producer_config.produce_accumulator {
+            produce_accumulator.batch_max_delay_ms_mut(hold_ms);
+        }
+    }
+
+    /// Sets the body size at which the produce accumulator sends a batch immediately. Has no
+    /// effect unless auto batch is enabled.
+    pub fn set_batch_max_bytes(&mut self, hold_size: usize) {
+        if let Some(ref mut produce_accumulator) = self.producer_config.produce_accumulator {
+            produce_accumulator.batch_max_bytes_mut(hold_size);
+        }
+    }
+
+    /// Sets the total body size the produce accumulator may hold. Messages exceeding it are
+    /// sent directly. Has no effect unless auto batch is enabled.
+    pub fn set_total_batch_max_bytes(&mut self, total_hold_size: usize) {
+        if let Some(ref mut produce_accumulator) = self.producer_config.produce_accumulator {
+            produce_accumulator.total_batch_max_bytes_mut(total_hold_size);
+        }
+    }
+
     pub fn set_enable_backpressure_for_async_mode(
         &mut self,
         enable_backpressure_for_async_mode: bool,
@@ -548,10 +580,12 @@ impl DefaultMQProducer {
     where
         M: MessageTrait + Send + std::clone::Clone + std::marker::Sync + 'static,
     {
-        if !self.can_batch(&msg) {
+        // validates before reserving room in the accumulator, a rejected message would never
+        // release it
+        Validators::check_message(Some(&msg), self.producer_config())?;
+        if !self.can_batch(&msg).await {
             self.send_direct(msg, mq, send_callback).await
         } else {
-            Validators::check_message(Some(&msg), self.producer_config())?;
             MessageClientIDSetter::set_uniq_id(&mut msg);
             if send_callback.is_none() {
                 let mq_producer = self.clone();
@@ -574,20 +608,10 @@ impl DefaultMQProducer {
         }
     }
 
-    fn can_batch<M>(&self, msg: &M) -> bool
+    async fn can_batch<M>(&self, msg: &M) -> bool
     where
         M: MessageTrait,
     {
-        // produceAccumulator is full
-        if !self
-            .producer_config
-            .produce_accumulator
-            .as_ref()
-            .unwrap()
-            .try_add_message(msg)
-        {
-            return false;
-        }
         // delay message do not support batch processing
         if msg.get_delay_time_level() > 0
             || msg.get_delay_time_ms() > 0
@@ -610,7 +634,13 @@ impl DefaultMQProducer {
         {
             return false;
         }
-        true
+        // the message does not fit in produceAccumulator at all
+        self.producer_config
+            .produce_accumulator
+            .as_ref()
+            .unwrap()
+            .try_add_message(msg)
+            .await
     }
 }
 
@@ -638,10 +668,13 @@ impl MQProducer for DefaultMQProducer {
             let mut dispatcher = AsyncTraceDispatcher::new(
                 self.producer_config.producer_group.as_str(),
                 Type::Produce,
-                self.client_config.trace_topic.clone().unwrap().as_str(),
+                self.client_config
+                    .trace_topic
+                    .clone()
+                    .unwrap_or_default()
+                    .as_str(),
                 self.producer_config.rpc_hook.clone(),
             );
-            dispatcher.set_host_producer(self.default_mqproducer_impl.as_ref().unwrap().clone());
             dispatcher.set_namespace_v2(self.client_config.namespace_v2.clone());
             let dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>> =
                 Arc::new(Box::new(dispatcher));
@@ -653,8 +686,13 @@ impl MQProducer for DefaultMQProducer {
                 .register_end_transaction_hook(EndTransactionTraceHookImpl::new(dispatcher))
         }
 
-        if let Some(ref mut trace_dispatcher) = self.producer_config.trace_dispatcher {
-            //TODO: trace
+        if let Some(ref trace_dispatcher) = self.producer_config.trace_dispatcher {
+            let namesrv_addr = self.client_config.get_namesrv_addr().unwrap_or_default();
+            if let Err(e) =
+                trace_dispatcher.start(namesrv_addr.as_str(), self.client_config.access_channel)
+            {
+                warn!("trace dispatcher start failed: {}", e);
+            }
         }
         Ok(())
     }
@@ -15,39 +15,51 @@
  * limitations under the License.
  */
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::hash::Hash;
 use std::hash::Hasher;
-use std::sync::atomic::AtomicBool;
-use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::thread::Builder;
+use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_batch::MessageBatch;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::message_single::Message;
 use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::TimeUtils::get_current_millis;
-use rocketmq_rust::ArcMut;
-use tokio::sync::Mutex;
+use rocketmq_error::mq_client_err;
+use tokio::sync::oneshot;
+use tokio::sync::Notify;
+use tracing::info;
+use tracing::warn;
 
 use crate::producer::default_mq_producer::DefaultMQProducer;
 use crate::producer::send_callback::SendMessageCallback;
 use crate::producer::send_result::SendResult;
 
+type BatchTable = Arc<parking_lot::Mutex<HashMap<AggregateKey, MessageAccumulation>>>;
+
+/// Accumulates messages sent through a [`DefaultMQProducer`] with auto batch enabled and
+/// sends them as [`MessageBatch`]es.
+///
+/// Messages sharing an [`AggregateKey`] are held until either `hold_size` bytes have been
+/// collected or `hold_ms` has elapsed since the batch was created. Every caller is resolved
+/// individually once its batch has been sent. The bytes held across all batches are limited
+/// by `total_hold_size`; once the limit is reached callers wait until sent batches release
+/// enough room.
 #[derive(Default)]
 pub struct ProduceAccumulator {
     total_hold_size: usize,
     hold_size: usize,
     hold_ms: u32,
-    guard_thread_for_sync_send: GuardForSyncSendService,
-    guard_thread_for_async_send: GuardForAsyncSendService,
-    currently_hold_size: AtomicU64,
+    guard_thread_for_sync_send: GuardForSendService,
+    guard_thread_for_async_send: GuardForSendService,
+    currently_hold_size: Arc<HoldSize>,
     instance_name: String,
-    currently_hold_size_lock: Arc<parking_lot::Mutex<()>>,
-    sync_send_batchs: Arc<Mutex<HashMap<AggregateKey, ArcMut<MessageAccumulation>>>>,
-    async_send_batchs: Arc<Mutex<HashMap<AggregateKey, ArcMut<MessageAccumulation>>>>,
+    sync_send_batchs: BatchTable,
+    async_send_batchs: BatchTable,
 }
 
 impl ProduceAccumulator {
@@ -57,12 +69,12 @@ impl ProduceAccumulator {
             hold_size: 1024 * 32,
             hold_ms: 10,
             instance_name: instance_name.to_string(),
-            guard_thread_for_async_send: GuardForAsyncSendService {
-                service_name: instance_name.to_string(),
-            },
-            guard_thread_for_sync_send: GuardForSyncSendService {
-                service_name: instance_name.to_string(),
-            },
+            guard_thread_for_async_send: GuardForSendService::new(&format!(
+                "Client_{instance_name}-GuardForAsyncSendService"
+            )),
+            guard_thread_for_sync_send: GuardForSendService::new(&format!(
+                "Client_{instance_name}-GuardForSyncSendService"
+            )),
             ..Default::default()
         }
     }
@@ -70,23 +82,76 @@ impl ProduceAccumulator {
 
 impl ProduceAccumulator {
     pub fn start(&mut self) {
-        self.guard_thread_for_sync_send.start();
-        self.guard_thread_for_async_send.start();
+        self.guard_thread_for_sync_send.start(
+            self.sync_send_batchs.clone(),
+            self.hold_ms,
+            self.currently_hold_size.clone(),
+        );
+        self.guard_thread_for_async_send.start(
+            self.async_send_batchs.clone(),
+            self.hold_ms,
+            self.currently_hold_size.clone(),
+        );
     }
+
     pub fn shutdown(&mut self) {
         self.guard_thread_for_sync_send.shutdown();
         self.guard_thread_for_async_send.shutdown();
     }
 
-    pub(crate) fn try_add_message<T: MessageTrait>(&self, message: &T) -> bool {
-        let lock = self.currently_hold_size_lock.lock();
-        if self.currently_hold_size.load(Ordering::Acquire) as usize > self.total_hold_size {
-            drop(lock);
+    #[inline]
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    #[inline]
+    pub fn batch_max_delay_ms(&self) -> u32 {
+        self.hold_ms
+    }
+
+    #[inline]
+    pub fn batch_max_bytes(&self) -> usize {
+        self.hold_size
+    }
+
+    #[inline]
+    pub fn total_batch_max_bytes(&self) -> usize {
+        self.total_hold_size
+    }
+
+    #[inline]
+    pub fn currently_hold_size(&self) -> u64 {
+        self.currently_hold_size.get()
+    }
+
+    /// Sets the maximum time a batch is held before it is sent. Takes effect on the next
+    /// `start`.
+    #[inline]
+    pub fn batch_max_delay_ms_mut(&mut self, hold_ms: u32) {
+        self.hold_ms = hold_ms;
+    }
+
+    #[inline]
+    pub fn batch_max_bytes_mut(&mut self, hold_size: usize) {
+        self.hold_size = hold_size;
+    }
+
+    #[inline]
+    pub fn total_batch_max_bytes_mut(&mut self, total_hold_size: usize) {
+        self.total_hold_size = total_hold_size;
+    }
+
+    /// Reserves room for `message` in the global hold size, waiting while the held batches
+    /// leave no room for it. Returns `false` when the message is larger than the total hold
+    /// size and has to be sent directly.
+    pub(crate) async fn try_add_message<T: MessageTrait>(&self, message: &T) -> bool {
+        let size = message_size(message);
+        if size > self.total_hold_size as u64 {
             return false;
         }
         self.currently_hold_size
-            .fetch_add(message.get_body().unwrap().len() as u64, Ordering::AcqRel);
-        drop(lock);
+            .acquire(size, self.total_hold_size as u64)
+            .await;
         true
     }
 
@@ -96,7 +161,22 @@ impl ProduceAccumulator {
         mq: Option<MessageQueue>,
         default_mq_producer: DefaultMQProducer,
     ) -> rocketmq_error::RocketMQResult<Option<SendResult>> {
-        unimplemented!("send")
+        let partition_key = AggregateKey::new_from_message_queue(&message, mq);
+        let (tx, rx) = oneshot::channel();
+        let batch = self.add_to_batch(
+            &self.sync_send_batchs,
+            partition_key,
+            to_message(&message),
+            SendResultHandler::Sync(tx),
+            default_mq_producer,
+        );
+        if let Some(batch) = batch {
+            tokio::spawn(batch.send(self.currently_hold_size.clone()));
+        }
+        match rx.await {
+            Ok(result) => result.map(Some),
+            Err(_) => mq_client_err!("The batch holding the message was dropped before sending"),
+        }
     }
 
     pub(crate) async fn send_callback<M: MessageTrait + Send + Sync + 'static + Clone>(
@@ -107,23 +187,97 @@ impl ProduceAccumulator {
         default_mq_producer: DefaultMQProducer,
     ) -> rocketmq_error::RocketMQResult<()> {
         let partition_key = AggregateKey::new_from_message_queue(&message, mq);
-        loop {
-            let batch =
-                self.get_or_create_async_send_batch(partition_key.clone(), &default_mq_producer);
-            /*if batch.add(message.clone(), send_callback.clone()) {
-                self.async_send_batchs.lock().await.remove(&partition_key);
-            } else {
-                return Ok(());
-            }*/
+        let batch = self.add_to_batch(
+            &self.async_send_batchs,
+            partition_key,
+            to_message(&message),
+            SendResultHandler::Async(send_callback),
+            default_mq_producer,
+        );
+        if let Some(batch) = batch {
+            tokio::spawn(batch.send(self.currently_hold_size.clone()));
         }
+        Ok(())
     }
 
-    fn get_or_create_async_send_batch(
-        &mut self,
+    /// Adds the message to the batch of `aggregate_key`, creating it if needed. The batch is
+    /// taken out of `batches` and returned once it has reached the hold size.
+    fn add_to_batch(
+        &self,
+        batches: &BatchTable,
         aggregate_key: AggregateKey,
-        default_mq_producer: &DefaultMQProducer,
-    ) -> ArcMut<MessageAccumulation> {
-        unimplemented!("getOrCreateAsyncSendBatch")
+        message: Message,
+        handler: SendResultHandler,
+        default_mq_producer: DefaultMQProducer,
+    ) -> Option<MessageAccumulation> {
+        let mut batches = batches.lock();
+        let batch = batches.entry(aggregate_key.clone()).or_insert_with(|| {
+            MessageAccumulation::new(aggregate_key.clone(), default_mq_producer)
+        });
+        batch.add(message, handler);
+        if batch.messages_size >= self.hold_size {
+            batches.remove(&aggregate_key)
+        } else {
+            None
+        }
+    }
+}
+
+/// The bytes held across all batches of an accumulator.
+#[derive(Default)]
+struct HoldSize {
+    size: AtomicU64,
+    released: Notify,
+}
+
+impl HoldSize {
+    #[inline]
+    fn get(&self) -> u64 {
+        self.size.load(Ordering::Acquire)
+    }
+
+    /// Waits until `size` more bytes fit under `limit` and adds them.
+    async fn acquire(&self, size: u64, limit: u64) {
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            // registers the waiter before checking, a release in between is not missed
+            released.as_mut().enable();
+            if self
+                .size
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                    (current + size <= limit).then_some(current + size)
+                })
+                .is_ok()
+            {
+                return;
+            }
+            released.await;
+        }
+    }
+
+    fn release(&self, size: u64) {
+        self.size.fetch_sub(size, Ordering::AcqRel);
+        self.released.notify_waiters();
+    }
+}
+
+#[inline]
+fn message_size<T: MessageTrait>(message: &T) -> u64 {
+    message.get_body().map_or(0, |body| body.len() as u64)
+}
+
+fn to_message<M: MessageTrait>(message: &M) -> Message {
+    if let Some(message) = message.as_any().downcast_ref::<Message>() {
+        return message.clone();
+    }
+    Message {
+        topic: message.get_topic().clone(),
+        flag: message.get_flag(),
+        properties: message.get_properties().clone(),
+        body: message.get_body().cloned(),
+        compressed_body: None,
+        transaction_id: message.get_transaction_id().cloned(),
     }
 }
 
@@ -189,159 +343,354 @@ impl Hash for AggregateKey {
     }
 }
 
+/// How the result of a single accumulated message is handed back to its caller.
+enum SendResultHandler {
+    Sync(oneshot::Sender<rocketmq_error::RocketMQResult<SendResult>>),
+    Async(Option<SendMessageCallback>),
+}
+
+impl SendResultHandler {
+    fn on_success(self, send_result: SendResult) {
+        match self {
+            SendResultHandler::Sync(tx) => {
+                let _ = tx.send(Ok(send_result));
+            }
+            SendResultHandler::Async(Some(send_callback)) => {
+                send_callback(Some(&send_result), None);
+            }
+            SendResultHandler::Async(None) => {}
+        }
+    }
+
+    fn on_exception(self, error: &rocketmq_error::RocketmqError) {
+        match self {
+            SendResultHandler::Sync(tx) => {
+                let _ = tx.send(mq_client_err!(format!(
+                    "send batch message failed: {error}"
+                )));
+            }
+            SendResultHandler::Async(Some(send_callback)) => {
+                send_callback(None, Some(error));
+            }
+            SendResultHandler::Async(None) => {}
+        }
+    }
+}
+
 struct MessageAccumulation {
-    default_mq_producer: ArcMut<DefaultMQProducer>,
-    messages: Vec<Box<dyn MessageTrait + Send + Sync + 'static>>,
-    send_callbacks: Vec<SendMessageCallback>,
-    keys: HashSet<String>,
-    closed: Arc<AtomicBool>,
-    send_results: Option<Vec<SendResult>>,
+    default_mq_producer: DefaultMQProducer,
+    messages: Vec<Message>,
+    handlers: Vec<SendResultHandler>,
     aggregate_key: AggregateKey,
-    messages_size: Arc<AtomicI32>,
-    count: usize,
+    messages_size: usize,
     create_time: u64,
 }
 
 impl MessageAccumulation {
-    pub fn new(
-        aggregate_key: AggregateKey,
-        default_mq_producer: ArcMut<DefaultMQProducer>,
-    ) -> Self {
+    pub fn new(aggregate_key: AggregateKey, default_mq_producer: DefaultMQProducer) -> Self {
         Self {
             default_mq_producer,
             messages: vec![],
-            send_callbacks: vec![],
-            keys: HashSet::new(),
-            closed: Arc::new(AtomicBool::new(false)),
-            send_results: None,
+            handlers: vec![],
             aggregate_key,
-            messages_size: Arc::new(AtomicI32::new(0)),
-            count: 0,
+            messages_size: 0,
             create_time: get_current_millis(),
         }
     }
 
-    pub fn add<M: MessageTrait + Send + Sync + 'static>(
-        &mut self,
-        msg: M,
-        send_callback: Option<SendMessageCallback>,
-    ) -> rocketmq_error::RocketMQResult<bool> {
-        unimplemented!()
-    }
-
-    /*    fn ready_to_send(&self, hold_size: i32, hold_ms: u128) -> bool {
-        self.messages_size.load(Ordering::SeqCst) > hold_size
-            || SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("Time went backwards")
-                .as_millis()
-                >= self.create_time + hold_ms
+    fn add(&mut self, msg: Message, handler: SendResultHandler) {
+        self.messages_size += message_size(&msg) as usize;
+        self.messages.push(msg);
+        self.handlers.push(handler);
     }
 
-
-
-    pub fn add_with_callback(
-        &mut self,
-        msg: Message,
-        send_callback: SendCallback,
-    ) -> rocketmq_error::RocketMQResult<bool, ()> {
-        if self.closed.load(Ordering::SeqCst) {
-            return Err(());
-        }
-        self.count += 1;
-        self.messages.push_back(msg);
-        self.send_callbacks.push_back(send_callback);
-        self.messages_size
-            .fetch_add(msg.get_body().len() as i32, Ordering::SeqCst);
-
-        Ok(true)
+    #[inline]
+    fn ready_to_send(&self, hold_size: usize, hold_ms: u32, now: u64) -> bool {
+        self.messages_size >= hold_size || now >= self.create_time + hold_ms as u64
     }
 
-    fn batch(&self) -> MessageBatch {
-        // Implementation for creating a message batch from the accumulated messages
-        MessageBatch {}
+    fn batch(&self) -> rocketmq_error::RocketMQResult<MessageBatch> {
+        let mut batch = MessageBatch::generate_from_vec(self.messages.clone())?;
+        MessageClientIDSetter::set_uniq_id(&mut batch.final_message);
+        batch.set_body(batch.encode());
+        Ok(batch)
     }
 
-    fn split_send_results(&mut self, send_result: SendResult) {
-        // Implementation for splitting send results
+    /// Splits the result of the whole batch into one result per accumulated message.
+    fn split_send_results(&self, send_result: &SendResult) -> Vec<SendResult> {
+        let offset_msg_ids = send_result
+            .offset_msg_id
+            .as_ref()
+            .map(|ids| ids.split(',').map(str::to_string).collect::<Vec<_>>())
+            .filter(|ids| ids.len() == self.messages.len());
+        self.messages
+            .iter()
+            .enumerate()
+            .map(|(index, message)| SendResult {
+                send_status: send_result.send_status,
+                msg_id: MessageClientIDSetter::get_uniq_id(message),
+                message_queue: send_result.message_queue.clone(),
+                queue_offset: send_result.queue_offset + index as u64,
+                transaction_id: send_result.transaction_id.clone(),
+                offset_msg_id: offset_msg_ids.as_ref().map(|ids| ids[index].clone()),
+                region_id: send_result.region_id.clone(),
+                trace_on: send_result.trace_on,
+                raw_resp_body: None,
+            })
+            .collect()
     }
 
-    pub fn send(&mut self) -> rocketmq_error::RocketMQResult<(), ()> {
-        if self.closed.swap(true, Ordering::SeqCst) {
-            return Ok(());
+    async fn send(mut self, currently_hold_size: Arc<HoldSize>) {
+        let result = match self.batch() {
+            Ok(batch) => {
+                let mq = self.aggregate_key.mq.clone();
+                self.default_mq_producer.send_direct(batch, mq, None).await
+            }
+            Err(err) => Err(err),
+        };
+        currently_hold_size.release(self.messages_size as u64);
+
+        let handlers = std::mem::take(&mut self.handlers);
+        match result {
+            Ok(Some(send_result)) => {
+                let send_results = self.split_send_results(&send_result);
+                for (handler, send_result) in handlers.into_iter().zip(send_results) {
+                    handler.on_success(send_result);
+                }
+            }
+            Ok(None) => {
+                let err = rocketmq_error::RocketmqError::MQClientErr(
+                    rocketmq_error::ClientErr::new("send batch message returned no result"),
+                );
+                handlers
+                    .into_iter()
+                    .for_each(|handler| handler.on_exception(&err));
+            }
+            Err(err) => {
+                warn!(
+                    "send batch message of topic {} failed: {}",
+                    self.aggregate_key.topic, err
+                );
+                handlers
+                    .into_iter()
+                    .for_each(|handler| handler.on_exception(&err));
+            }
         }
-        let message_batch = self.batch();
-        let send_result = self
-            .default_mq_producer
-            .send_direct(message_batch, None, None)?;
-        self.split_send_results(send_result);
-        Ok(())
     }
-
-    pub fn send_with_callback(&mut self, send_callback: SendCallback) -> rocketmq_error::RocketMQResult<(), ()> {
-        if self.closed.swap(true, Ordering::SeqCst) {
-            return Ok(());
-        }
-        let message_batch = self.batch();
-        let size = self.messages_size.load(Ordering::SeqCst);
-
-        self.default_mq_producer
-            .send_direct(message_batch, None, Some(send_callback))?;
-
-        Ok(())
-    }*/
 }
 
+/// Sends the batches of one [`BatchTable`] whose hold time has expired.
 #[derive(Default)]
-struct GuardForSyncSendService {
+struct GuardForSendService {
     service_name: String,
+    shutdown: Arc<Notify>,
+    running: bool,
 }
 
-impl GuardForSyncSendService {
+impl GuardForSendService {
     pub fn new(service_name: &str) -> Self {
         Self {
             service_name: service_name.to_string(),
+            ..Default::default()
         }
     }
 
-    pub fn start(&mut self) {
+    pub fn start(&mut self, batches: BatchTable, hold_ms: u32, currently_hold_size: Arc<HoldSize>) {
+        if self.running {
+            return;
+        }
+        self.running = true;
         let service_name = self.service_name.clone();
-        Builder::new()
-            .name(service_name)
-            .spawn(|| {
-                // Implementation for starting the guard thread
-            })
-            .expect("Failed to start guard thread");
+        let shutdown = self.shutdown.clone();
+        let interval = Duration::from_millis((hold_ms / 2).max(1) as u64);
+        tokio::spawn(async move {
+            info!("{} service started", service_name);
+            loop {
+                tokio::select! {
+                    _ = shutdown.notified() => {
+                        Self::flush(&batches, &currently_hold_size, |_| true);
+                        break;
+                    }
+                    _ = tokio::time::sleep(interval) => {
+                        let now = get_current_millis();
+                        Self::flush(&batches, &currently_hold_size, |batch| {
+                            batch.ready_to_send(usize::MAX, hold_ms, now)
+                        });
+                    }
+                }
+            }
+            info!("{} service end", service_name);
+        });
     }
 
     pub fn shutdown(&mut self) {
-        // Implementation for shutting down the guard thread
+        if self.running {
+            self.running = false;
+            self.shutdown.notify_one();
+        }
     }
-}
 
-#[derive(Default)]
-struct GuardForAsyncSendService {
-    service_name: String,
+    fn flush(
+        batches: &BatchTable,
+        currently_hold_size: &Arc<HoldSize>,
+        ready: impl Fn(&MessageAccumulation) -> bool,
+    ) {
+        let ready_batches = {
+            let mut batches = batches.lock();
+            let ready_keys = batches
+                .iter()
+                .filter(|(_, batch)| ready(batch))
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            ready_keys
+                .iter()
+                .filter_map(|key| batches.remove(key))
+                .collect::<Vec<_>>()
+        };
+        for batch in ready_batches {
+            tokio::spawn(batch.send(currently_hold_size.clone()));
+        }
+    }
 }
 
-impl GuardForAsyncSendService {
-    pub fn new(service_name: &str) -> Self {
-        Self {
-            service_name: service_name.to_string(),
-        }
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_common::common::message::message_queue::MessageQueue;
+
+    use super::*;
+    use crate::producer::send_status::SendStatus;
+
+    fn message(topic: &str, body: &'static [u8]) -> Message {
+        let mut message = Message::with_tags(topic, "TagA", body);
+        MessageClientIDSetter::set_uniq_id(&mut message);
+        message
     }
 
-    pub fn start(&mut self) {
-        let service_name = self.service_name.clone();
-        Builder::new()
-            .name(service_name)
-            .spawn(|| {
-                // Implementation for starting the guard thread
-            })
-            .expect("Failed to start guard thread");
+    #[tokio::test]
+    async fn try_add_message_rejects_message_larger_than_total_hold_size() {
+        let mut accumulator = ProduceAccumulator::new("test");
+        accumulator.total_batch_max_bytes_mut(4);
+        let msg = message("TopicTest", b"hello");
+        assert!(!accumulator.try_add_message(&msg).await);
+        assert_eq!(accumulator.currently_hold_size(), 0);
     }
 
-    pub fn shutdown(&mut self) {
-        // Implementation for shutting down the guard thread
+    #[tokio::test]
+    async fn try_add_message_waits_for_room() {
+        let mut accumulator = ProduceAccumulator::new("test");
+        accumulator.total_batch_max_bytes_mut(8);
+        let accumulator = Arc::new(accumulator);
+        let msg = message("TopicTest", b"hello");
+        assert!(accumulator.try_add_message(&msg).await);
+        assert_eq!(accumulator.currently_hold_size(), 5);
+
+        let waiting = tokio::spawn({
+            let accumulator = accumulator.clone();
+            let msg = msg.clone();
+            async move { accumulator.try_add_message(&msg).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+        assert_eq!(accumulator.currently_hold_size(), 5);
+
+        accumulator.currently_hold_size.release(5);
+        assert!(waiting.await.unwrap());
+        assert_eq!(accumulator.currently_hold_size(), 5);
+    }
+
+    #[test]
+    fn aggregate_key_distinguishes_message_queue() {
+        let msg = message("TopicTest", b"hello");
+        let mq = MessageQueue::from_parts("TopicTest", "broker-a", 0);
+        let mut keys = HashMap::new();
+        keys.insert(AggregateKey::new_from_message(&msg), 1);
+        keys.insert(
+            AggregateKey::new_from_message_queue(&msg, Some(mq.clone())),
+            2,
+        );
+        keys.insert(AggregateKey::new_from_message_queue(&msg, None), 3);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(
+            keys[&AggregateKey::new_from_message_queue(&msg, Some(mq))],
+            2
+        );
+    }
+
+    #[test]
+    fn message_accumulation_ready_to_send() {
+        let msg = message("TopicTest", b"hello");
+        let mut batch = MessageAccumulation::new(
+            AggregateKey::new_from_message(&msg),
+            DefaultMQProducer::default(),
+        );
+        let create_time = batch.create_time;
+        batch.add(msg, SendResultHandler::Async(None));
+        assert!(!batch.ready_to_send(6, 10, create_time));
+        assert!(batch.ready_to_send(5, 10, create_time));
+        assert!(batch.ready_to_send(6, 10, create_time + 10));
+    }
+
+    #[test]
+    fn split_send_results_per_message() {
+        let first = message("TopicTest", b"a");
+        let second = message("TopicTest", b"b");
+        let mut batch = MessageAccumulation::new(
+            AggregateKey::new_from_message(&first),
+            DefaultMQProducer::default(),
+        );
+        batch.add(first.clone(), SendResultHandler::Async(None));
+        batch.add(second.clone(), SendResultHandler::Async(None));
+        let mq = MessageQueue::from_parts("TopicTest", "broker-a", 1);
+        let send_result = SendResult::new(
+            SendStatus::SendOk,
+            None,
+            Some("offset-1,offset-2".to_string()),
+            Some(mq.clone()),
+            100,
+        );
+        let results = batch.split_send_results(&send_result);
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].msg_id,
+            MessageClientIDSetter::get_uniq_id(&first)
+        );
+        assert_eq!(
+            results[1].msg_id,
+            MessageClientIDSetter::get_uniq_id(&second)
+        );
+        assert_eq!(results[0].offset_msg_id.as_deref(), Some("offset-1"));
+        assert_eq!(results[1].offset_msg_id.as_deref(), Some("offset-2"));
+        assert_eq!(results[0].queue_offset, 100);
+        assert_eq!(results[1].queue_offset, 101);
+        assert_eq!(results[1].message_queue, Some(mq));
+    }
+
+    #[tokio::test]
+    async fn batch_is_taken_once_hold_size_is_reached() {
+        let mut accumulator = ProduceAccumulator::new("test");
+        accumulator.batch_max_bytes_mut(2);
+        let first = message("TopicTest", b"a");
+        let key = AggregateKey::new_from_message(&first);
+        let taken = accumulator.add_to_batch(
+            &accumulator.async_send_batchs,
+            key.clone(),
+            first,
+            SendResultHandler::Async(None),
+            DefaultMQProducer::default(),
+        );
+        assert!(taken.is_none());
+        assert!(accumulator.async_send_batchs.lock().contains_key(&key));
+
+        let taken = accumulator.add_to_batch(
+            &accumulator.async_send_batchs,
+            key.clone(),
+            message("TopicTest", b"b"),
+            SendResultHandler::Async(None),
+            DefaultMQProducer::default(),
+        );
+        assert_eq!(taken.map(|batch| batch.messages.len()), Some(2));
+        assert!(accumulator.async_send_batchs.lock().is_empty());
     }
 }
@@ -59,6 +59,7 @@ use rocketmq_rust::ArcMut;
 use tokio::runtime::Handle;
 use tokio::sync::RwLock;
 use tokio::sync::Semaphore;
+use tracing::info;
 use tracing::warn;
 
 use crate::base::client_config::ClientConfig;
@@ -90,13 +91,19 @@ use crate::producer::send_result::SendResult;
 use crate::producer::send_status::SendStatus;
 use crate::producer::transaction_listener::TransactionListener;
 use crate::producer::transaction_send_result::TransactionSendResult;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::context::FutureExt;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::message_tracer::MessageTracer;
+#[cfg(feature = "opentelemetry")]
+use crate::trace::otel::span::SpanStatus;
 
 pub struct DefaultMQProducerImpl {
     client_config: ClientConfig,
     producer_config: Arc<ProducerConfig>,
     topic_publish_info_table: Arc<RwLock<HashMap<CheetahString /* topic */, TopicPublishInfo>>>,
     send_message_hook_list: ArcMut<Vec<Box<dyn SendMessageHook>>>,
-    end_transaction_hook_list: Arc<Vec<Box<dyn EndTransactionHook>>>,
+    end_transaction_hook_list: ArcMut<Vec<Box<dyn EndTransactionHook>>>,
     check_forbidden_hook_list: Vec<Arc<Box<dyn CheckForbiddenHook>>>,
     rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
     service_state: ServiceState,
@@ -132,7 +139,7 @@ impl DefaultMQProducerImpl {
             producer_config: Arc::new(producer_config),
             topic_publish_info_table,
             send_message_hook_list: ArcMut::new(vec![]),
-            end_transaction_hook_list: Arc::new(vec![]),
+            end_transaction_hook_list: ArcMut::new(vec![]),
             check_forbidden_hook_list: vec![],
             rpc_hook: None,
             service_state: ServiceState::CreateJust,
@@ -672,6 +679,32 @@ impl DefaultMQProducerImpl {
         send_callback: Option<SendMessageCallback>,
         timeout: u64,
     ) -> rocketmq_error::RocketMQResult<Option<SendResult>>
+    where
+        T: MessageTrait + Clone + Send + Sync,
+    {
+        // one span covers every attempt, the retries run inside its context
+        #[cfg(feature = "opentelemetry")]
+        if let Some(otel_cx) = crate::trace::otel::global::tracer().and_then(|tracer| {
+            tracer.start_send_span(self.producer_config.producer_group().as_str(), msg)
+        }) {
+            let result = self
+                .send_with_retry(msg, communication_mode, send_callback, timeout)
+                .with_context(otel_cx.clone())
+                .await;
+            MessageTracer::end_span(&otel_cx, otel_send_status(&result));
+            return result;
+        }
+        self.send_with_retry(msg, communication_mode, send_callback, timeout)
+            .await
+    }
+
+    async fn send_with_retry<T>(
+        &mut self,
+        msg: &mut T,
+        communication_mode: CommunicationMode,
+        send_callback: Option<SendMessageCallback>,
+        timeout: u64,
+    ) -> rocketmq_error::RocketMQResult<Option<SendResult>>
     where
         T: MessageTrait + Clone + Send + Sync,
     {
@@ -714,6 +747,13 @@ impl DefaultMQProducerImpl {
                             mq.as_ref().unwrap().get_broker_name().to_string();
                         begin_timestamp_prev = Instant::now();
                         if times > 0 {
+                            #[cfg(feature = "opentelemetry")]
+                            if let Some(tracer) = crate::trace::otel::global::tracer() {
+                                tracer.record_send_retry(
+                                    times + 1,
+                                    mq.as_ref().unwrap().get_broker_name(),
+                                );
+                            }
                             //Reset topic with namespace during resend.
                             let namespace = self.client_config.get_namespace().unwrap_or_default();
                             msg.set_topic(CheetahString::from_string(
@@ -738,6 +778,18 @@ impl DefaultMQProducerImpl {
                                 timeout - cost_time,
                             )
                             .await;
+                        #[cfg(feature = "metrics")]
+                        if let Some(client_metrics_manager) = self
+                            .client_instance
+                            .as_ref()
+                            .and_then(|client_instance| client_instance.client_metrics_manager())
+                        {
+                            client_metrics_manager.record_send_cost_time(
+                                topic.as_str(),
+                                begin_timestamp_prev.elapsed(),
+                                result_inner.is_ok(),
+                            );
+                        }
 
                         match result_inner {
                             Ok(result) => {
@@ -1052,13 +1104,19 @@ impl DefaultMQProducerImpl {
             if msg_type_flag {
                 send_message_context.msg_type = Some(MessageType::DelayMsg);
             }
-            let send_message_context = Some(send_message_context);
-            self.execute_send_message_hook_before(&send_message_context);
+            let mut send_message_context = Some(send_message_context);
+            self.execute_send_message_hook_before(&mut send_message_context);
             send_message_context
         } else {
             None
         };
 
+        // sends that did not go through `send_default_impl` get their span here
+        #[cfg(feature = "opentelemetry")]
+        let otel_cx = crate::trace::otel::global::tracer().and_then(|tracer| {
+            tracer.start_send_span(self.producer_config.producer_group().as_str(), msg)
+        });
+
         //build send message request header
         let mut request_header = SendMessageRequestHeader {
             producer_group: CheetahString::from_string(
@@ -1165,6 +1223,11 @@ impl DefaultMQProducerImpl {
             }
         };
 
+        #[cfg(feature = "opentelemetry")]
+        if let Some(otel_cx) = otel_cx {
+            MessageTracer::end_span(&otel_cx, otel_send_status(&send_result));
+        }
+
         match send_result {
             Ok(result) => {
                 if self.has_send_message_hook() {
@@ -1185,7 +1248,10 @@ impl DefaultMQProducerImpl {
         }
     }
 
-    pub fn execute_send_message_hook_before(&mut self, context: &Option<SendMessageContext<'_>>) {
+    pub fn execute_send_message_hook_before(
+        &mut self,
+        context: &mut Option<SendMessageContext<'_>>,
+    ) {
         if self.has_send_message_hook() {
             for hook in self.send_message_hook_list.iter() {
                 hook.send_message_before(context);
@@ -2203,7 +2269,7 @@ impl DefaultMQProducerImpl {
                     .get_or_create_mq_client_instance(
                         self.client_config.clone(),
                         self.rpc_hook.clone(),
-                    );
+                    )?;
 
                 let service_detector = DefaultServiceDetector {
                     client_instance: client_instance.clone(),
@@ -2264,12 +2330,14 @@ impl DefaultMQProducerImpl {
         Ok(())
     }
 
-    pub fn register_end_transaction_hook(&mut self, hook: impl EndTransactionHook) {
-        todo!()
+    pub fn register_end_transaction_hook(&mut self, hook: impl EndTransactionHook + 'static) {
+        info!("register end transaction Hook, {}", hook.hook_name());
+        self.end_transaction_hook_list.push(Box::new(hook));
     }
 
-    pub fn register_send_message_hook(&mut self, hook: impl SendMessageHook) {
-        todo!()
+    pub fn register_send_message_hook(&mut self, hook: impl SendMessageHook + 'static) {
+        info!("register sendMessage Hook, {}", hook.hook_name());
+        self.send_message_hook_list.push(Box::new(hook));
     }
 
     #[inline]
@@ -2334,3 +2402,16 @@ impl Resolver for DefaultResolver {
             .await
     }
 }
+
+#[cfg(feature = "opentelemetry")]
+fn otel_send_status(
+    send_result: &rocketmq_error::RocketMQResult<Option<SendResult>>,
+) -> SpanStatus {
+    match send_result {
+        Ok(Some(result)) if result.send_status != SendStatus::SendOk => {
+            SpanStatus::Error(format!("{:?}", result.send_status))
+        }
+        Ok(_) => SpanStatus::Ok,
+        Err(err) => SpanStatus::Error(err.to_string()),
+    }
+}
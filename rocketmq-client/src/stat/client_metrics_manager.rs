@@ -0,0 +1,165 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::metrics::instruments::GaugeVec;
+use rocketmq_common::common::metrics::instruments::HistogramVec;
+use rocketmq_common::common::metrics::instruments::LATENCY_MILLIS_BUCKETS;
+use rocketmq_common::common::metrics::metric_registry::MetricRegistry;
+use rocketmq_common::common::metrics::prometheus_exporter::PrometheusExporter;
+use rocketmq_common::common::metrics::MetricsLabels;
+use tracing::info;
+
+const INVOCATION_STATUS: &str = "invocation_status";
+const INVOCATION_STATUS_SUCCESS: &str = "success";
+const INVOCATION_STATUS_FAILURE: &str = "failure";
+
+/// Client side producer and consumer metrics, served in the Prometheus text format.
+pub struct ClientMetricsManager {
+    client_id: CheetahString,
+    registry: Arc<MetricRegistry>,
+    send_cost_time: Arc<HistogramVec>,
+    process_time: Arc<HistogramVec>,
+    cached_messages: Arc<GaugeVec>,
+    exporter: Mutex<Option<PrometheusExporter>>,
+}
+
+impl ClientMetricsManager {
+    pub fn new(client_id: CheetahString) -> Self {
+        let registry = Arc::new(MetricRegistry::new());
+        let send_cost_time = registry.histogram_vec(
+            "rocketmq_send_cost_time",
+            "Send message latency in milliseconds.",
+            &[
+                MetricsLabels::CLIENT_ID,
+                MetricsLabels::TOPIC,
+                INVOCATION_STATUS,
+            ],
+            LATENCY_MILLIS_BUCKETS,
+        );
+        let process_time = registry.histogram_vec(
+            "rocketmq_process_time",
+            "Consume message latency in milliseconds.",
+            &[
+                MetricsLabels::CLIENT_ID,
+                MetricsLabels::CONSUMER_GROUP,
+                MetricsLabels::TOPIC,
+            ],
+            LATENCY_MILLIS_BUCKETS,
+        );
+        let cached_messages = registry.gauge_vec(
+            "rocketmq_consumer_cached_messages",
+            "Number of messages cached in the process queue.",
+            &[
+                MetricsLabels::CLIENT_ID,
+                MetricsLabels::CONSUMER_GROUP,
+                MetricsLabels::TOPIC,
+                MetricsLabels::QUEUE_ID,
+            ],
+        );
+        ClientMetricsManager {
+            client_id,
+            registry,
+            send_cost_time,
+            process_time,
+            cached_messages,
+            exporter: Mutex::new(None),
+        }
+    }
+
+    pub fn record_send_cost_time(&self, topic: &str, cost_time: Duration, success: bool) {
+        let status = if success {
+            INVOCATION_STATUS_SUCCESS
+        } else {
+            INVOCATION_STATUS_FAILURE
+        };
+        self.send_cost_time.observe(
+            &[self.client_id.as_str(), topic, status],
+            cost_time.as_secs_f64() * 1000.0,
+        );
+    }
+
+    pub fn record_process_time(&self, group: &str, topic: &str, rt_millis: u64) {
+        self.process_time
+            .observe(&[self.client_id.as_str(), group, topic], rt_millis as f64);
+    }
+
+    pub fn set_cached_messages(&self, group: &str, topic: &str, queue_id: i32, count: u64) {
+        self.cached_messages.set(
+            &[self.client_id.as_str(), group, topic, &queue_id.to_string()],
+            count as f64,
+        );
+    }
+
+    pub fn registry(&self) -> &Arc<MetricRegistry> {
+        &self.registry
+    }
+
+    pub async fn start(&self, host: &str, port: u16) -> io::Result<()> {
+        let exporter = PrometheusExporter::start(host, port, self.registry.clone()).await?;
+        if let Some(previous) = self.exporter.lock().replace(exporter) {
+            previous.shutdown();
+        }
+        Ok(())
+    }
+
+    pub fn shutdown(&self) {
+        if let Some(exporter) = self.exporter.lock().take() {
+            exporter.shutdown();
+            info!("Client metrics exporter shutdown");
+        }
+    }
+}
+
+impl fmt::Debug for ClientMetricsManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientMetricsManager")
+            .field("client_id", &self.client_id)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_client_metrics() {
+        let manager = ClientMetricsManager::new(CheetahString::from_static_str("client"));
+        manager.record_send_cost_time("topic", Duration::from_millis(3), true);
+        manager.record_process_time("group", "topic", 12);
+        manager.set_cached_messages("group", "topic", 1, 7);
+        let text = manager.registry().gather();
+        assert!(text.contains(
+            "rocketmq_send_cost_time_count{client_id=\"client\",topic=\"topic\",\
+             invocation_status=\"success\"} 1"
+        ));
+        assert!(text.contains(
+            "rocketmq_process_time_sum{client_id=\"client\",consumer_group=\"group\",topic=\"\
+             topic\"} 12"
+        ));
+        assert!(text.contains(
+            "rocketmq_consumer_cached_messages{client_id=\"client\",consumer_group=\"group\",\
+             topic=\"topic\",queue_id=\"1\"} 7"
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use rocketmq_common::common::stats::stats_item_set::StatsItemSet;
+use rocketmq_common::common::stats::stats_snapshot::StatsSnapshot;
+use rocketmq_remoting::protocol::body::consume_status::ConsumeStatus;
+
+#[cfg(feature = "metrics")]
+use crate::stat::client_metrics_manager::ClientMetricsManager;
+
+const TOPIC_AND_GROUP_CONSUME_OK_TPS: &str = "CONSUME_OK_TPS";
+const TOPIC_AND_GROUP_CONSUME_FAILED_TPS: &str = "CONSUME_FAILED_TPS";
+const TOPIC_AND_GROUP_CONSUME_RT: &str = "CONSUME_RT";
+const TOPIC_AND_GROUP_PULL_TPS: &str = "PULL_TPS";
+const TOPIC_AND_GROUP_PULL_RT: &str = "PULL_RT";
+
+/// Per topic and consumer group pull and consume statistics, reported to the broker in
+/// `ConsumerRunningInfo`.
+#[derive(Debug, Clone)]
+pub struct ConsumerStatsManager {
+    topic_and_group_consume_ok_tps: StatsItemSet,
+    topic_and_group_consume_rt: StatsItemSet,
+    topic_and_group_consume_failed_tps: StatsItemSet,
+    topic_and_group_pull_tps: StatsItemSet,
+    topic_and_group_pull_rt: StatsItemSet,
+    #[cfg(feature = "metrics")]
+    metrics_manager: Option<std::sync::Arc<ClientMetricsManager>>,
+}
+
+impl Default for ConsumerStatsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConsumerStatsManager {
+    pub fn new() -> Self {
+        Self {
+            topic_and_group_consume_ok_tps: StatsItemSet::new(
+                TOPIC_AND_GROUP_CONSUME_OK_TPS.to_string(),
+            ),
+            topic_and_group_consume_rt: StatsItemSet::new(TOPIC_AND_GROUP_CONSUME_RT.to_string()),
+            topic_and_group_consume_failed_tps: StatsItemSet::new(
+                TOPIC_AND_GROUP_CONSUME_FAILED_TPS.to_string(),
+            ),
+            topic_and_group_pull_tps: StatsItemSet::new(TOPIC_AND_GROUP_PULL_TPS.to_string()),
+            topic_and_group_pull_rt: StatsItemSet::new(TOPIC_AND_GROUP_PULL_RT.to_string()),
+            #[cfg(feature = "metrics")]
+            metrics_manager: None,
+        }
+    }
+
+    /// Mirrors the consume RT into the `rocketmq_process_time` histogram.
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics_manager(&mut self, metrics_manager: std::sync::Arc<ClientMetricsManager>) {
+        self.metrics_manager = Some(metrics_manager);
+    }
+
+    pub fn start(&self) {
+        self.topic_and_group_consume_ok_tps.init();
+        self.topic_and_group_consume_rt.init();
+        self.topic_and_group_consume_failed_tps.init();
+        self.topic_and_group_pull_tps.init();
+        self.topic_and_group_pull_rt.init();
+    }
+
+    pub fn shutdown(&self) {}
+
+    #[inline]
+    fn stats_key(group: &str, topic: &str) -> String {
+        format!("{topic}@{group}")
+    }
+
+    pub fn inc_pull_rt(&self, group: &str, topic: &str, rt: u64) {
+        self.topic_and_group_pull_rt
+            .add_value(&Self::stats_key(group, topic), rt, 1);
+    }
+
+    pub fn inc_pull_tps(&self, group: &str, topic: &str, msgs: u64) {
+        self.topic_and_group_pull_tps
+            .add_value(&Self::stats_key(group, topic), msgs, 1);
+    }
+
+    pub fn inc_consume_rt(&self, group: &str, topic: &str, rt: u64) {
+        self.topic_and_group_consume_rt
+            .add_value(&Self::stats_key(group, topic), rt, 1);
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_manager) = self.metrics_manager.as_ref() {
+            metrics_manager.record_process_time(group, topic, rt);
+        }
+    }
+
+    pub fn inc_consume_ok_tps(&self, group: &str, topic: &str, msgs: u64) {
+        self.topic_and_group_consume_ok_tps
+            .add_value(&Self::stats_key(group, topic), msgs, 1);
+    }
+
+    pub fn inc_consume_failed_tps(&self, group: &str, topic: &str, msgs: u64) {
+        self.topic_and_group_consume_failed_tps
+            .add_value(&Self::stats_key(group, topic), msgs, 1);
+    }
+
+    pub fn consume_status(&self, group: &str, topic: &str) -> ConsumeStatus {
+        let key = Self::stats_key(group, topic);
+        ConsumeStatus {
+            pull_rt: self.get_pull_rt(&key).get_avgpt(),
+            pull_tps: self
+                .topic_and_group_pull_tps
+                .get_stats_data_in_minute(&key)
+                .get_tps(),
+            consume_rt: self.get_consume_rt(&key).get_avgpt(),
+            consume_ok_tps: self
+                .topic_and_group_consume_ok_tps
+                .get_stats_data_in_minute(&key)
+                .get_tps(),
+            consume_failed_tps: self
+                .topic_and_group_consume_failed_tps
+                .get_stats_data_in_minute(&key)
+                .get_tps(),
+            consume_failed_msgs: self
+                .topic_and_group_consume_failed_tps
+                .get_stats_data_in_hour(&key)
+                .get_sum() as i64,
+        }
+    }
+
+    fn get_pull_rt(&self, key: &str) -> StatsSnapshot {
+        self.topic_and_group_pull_rt.get_stats_data_in_minute(key)
+    }
+
+    fn get_consume_rt(&self, key: &str) -> StatsSnapshot {
+        let stats_data = self
+            .topic_and_group_consume_rt
+            .get_stats_data_in_minute(key);
+        if stats_data.get_sum() == 0 {
+            return self.topic_and_group_consume_rt.get_stats_data_in_hour(key);
+        }
+        stats_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consume_status_reports_sampled_values() {
+        let manager = ConsumerStatsManager::new();
+        let key = ConsumerStatsManager::stats_key("group", "topic");
+        let item = manager
+            .topic_and_group_consume_rt
+            .get_and_create_stats_item(&key);
+        item.sample_in_seconds();
+        manager.inc_consume_rt("group", "topic", 20);
+        manager.inc_consume_rt("group", "topic", 40);
+        item.sample_in_seconds();
+
+        let status = manager.consume_status("group", "topic");
+        assert_eq!(status.consume_rt, 30.0);
+        assert_eq!(status.pull_tps, 0.0);
+        assert_eq!(status.consume_failed_msgs, 0);
+    }
+}
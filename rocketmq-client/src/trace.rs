@@ -14,11 +14,15 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-pub mod async_trace_dispatcher;
-pub mod hook;
+pub(crate) mod async_trace_dispatcher;
+pub(crate) mod hook;
+#[cfg(feature = "opentelemetry")]
+pub mod otel;
 pub mod trace_bean;
 pub mod trace_constants;
 pub mod trace_context;
+pub mod trace_data_encoder;
 pub mod trace_dispatcher;
+pub mod trace_transfer_bean;
 pub mod trace_type;
 pub mod trace_view;
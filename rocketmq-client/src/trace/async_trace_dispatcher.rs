@@ -15,19 +15,73 @@
  * limitations under the License.
  */
 use std::any::Any;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::message::message_single::Message;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_error::RequestTimeoutErr;
 use rocketmq_remoting::runtime::RPCHook;
-use rocketmq_rust::ArcMut;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tracing::info;
+use tracing::warn;
 
 use crate::base::access_channel::AccessChannel;
-use crate::consumer::consumer_impl::default_mq_push_consumer_impl::DefaultMQPushConsumerImpl;
+use crate::base::client_config::ClientConfig;
+use crate::producer::default_mq_producer::DefaultMQProducer;
+use crate::producer::mq_producer::MQProducer;
 use crate::producer::producer_impl::default_mq_producer_impl::DefaultMQProducerImpl;
+use crate::trace::trace_constants::TraceConstants;
+use crate::trace::trace_context::TraceContext;
+use crate::trace::trace_data_encoder::TraceDataEncoder;
 use crate::trace::trace_dispatcher::TraceDispatcher;
 use crate::trace::trace_dispatcher::Type;
+use crate::trace::trace_transfer_bean::TraceTransferBean;
 
-pub struct AsyncTraceDispatcher;
+/// The max number of trace contexts waiting to be sent, the contexts appended when it is
+/// reached are discarded.
+const QUEUE_SIZE: usize = 2048;
+/// The max number of trace contexts sent in one trace message.
+const BATCH_SIZE: usize = 100;
+/// The max size of the body of a trace message.
+const MAX_MSG_SIZE: usize = 128000;
+const POLLING_TIME_MILLIS: u64 = 100;
+/// How long a trace context may wait for its batch to fill up.
+const WAIT_TIME_THRESHOLD_MILLIS: u64 = 500;
+const TRACE_SEND_TIMEOUT_MILLIS: u32 = 5000;
+/// How long a flush waits for the worker to send the trace data appended before it.
+const FLUSH_TIMEOUT_MILLIS: u64 = 5000;
+
+static TRACE_PRODUCER_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Batches the trace contexts appended by the trace hooks and sends them through an internal
+/// producer to the trace topic, `RMQ_SYS_TRACE_TOPIC` unless a custom one is configured.
+pub struct AsyncTraceDispatcher {
+    group: CheetahString,
+    type_: Type,
+    trace_topic_name: CheetahString,
+    rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
+    trace_context_tx: mpsc::Sender<TraceContext>,
+    trace_context_rx: Mutex<Option<mpsc::Receiver<TraceContext>>>,
+    discard_count: AtomicU64,
+    flush_tx: mpsc::UnboundedSender<oneshot::Sender<()>>,
+    flush_rx: Mutex<Option<mpsc::UnboundedReceiver<oneshot::Sender<()>>>>,
+    shutdown_tx: watch::Sender<bool>,
+    started: AtomicBool,
+    namespace_v2: Option<CheetahString>,
+}
 
 impl AsyncTraceDispatcher {
     pub fn new(
@@ -36,7 +90,82 @@ impl AsyncTraceDispatcher {
         trace_topic_name: &str,
         rpc_hook: Option<Arc<Box<dyn RPCHook>>>,
     ) -> Self {
-        AsyncTraceDispatcher
+        let trace_topic_name = if trace_topic_name.is_empty() {
+            CheetahString::from_static_str(TopicValidator::RMQ_SYS_TRACE_TOPIC)
+        } else {
+            CheetahString::from(trace_topic_name)
+        };
+        let (trace_context_tx, trace_context_rx) = mpsc::channel(QUEUE_SIZE);
+        let (flush_tx, flush_rx) = mpsc::unbounded_channel();
+        AsyncTraceDispatcher {
+            group: CheetahString::from(group),
+            type_,
+            trace_topic_name,
+            rpc_hook,
+            trace_context_tx,
+            trace_context_rx: Mutex::new(Some(trace_context_rx)),
+            discard_count: AtomicU64::new(0),
+            flush_tx,
+            flush_rx: Mutex::new(Some(flush_rx)),
+            shutdown_tx: watch::channel(false).0,
+            started: AtomicBool::new(false),
+            namespace_v2: None,
+        }
+    }
+
+    pub fn trace_topic_name(&self) -> &CheetahString {
+        &self.trace_topic_name
+    }
+
+    /// The number of trace contexts discarded because the queue was full.
+    pub fn discard_count(&self) -> u64 {
+        self.discard_count.load(Ordering::Relaxed)
+    }
+
+    fn build_trace_producer(
+        &self,
+        name_srv_addr: &str,
+        access_channel: AccessChannel,
+    ) -> DefaultMQProducer {
+        let type_name = match self.type_ {
+            Type::Produce => "PRODUCE",
+            Type::Consume => "CONSUME",
+        };
+        let producer_group = format!(
+            "{}-{}-{}-{}",
+            TraceConstants::GROUP_NAME_PREFIX,
+            self.group,
+            type_name,
+            TRACE_PRODUCER_COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut client_config = ClientConfig::new();
+        if !name_srv_addr.is_empty() {
+            client_config.namesrv_addr = Some(CheetahString::from(name_srv_addr));
+        }
+        client_config.instance_name = CheetahString::from_string(format!(
+            "{}_{}",
+            TraceConstants::TRACE_INSTANCE_NAME,
+            name_srv_addr
+        ));
+        client_config.namespace_v2 = self.namespace_v2.clone();
+        client_config.access_channel = access_channel;
+        client_config.enable_trace = false;
+        let mut producer = DefaultMQProducer::builder()
+            .client_config(client_config)
+            .producer_group(producer_group)
+            .send_msg_timeout(TRACE_SEND_TIMEOUT_MILLIS)
+            .max_message_size(MAX_MSG_SIZE as u32)
+            .build();
+        if self.rpc_hook.is_some() {
+            producer.set_rpc_hook(self.rpc_hook.clone());
+            let producer_impl = DefaultMQProducerImpl::new(
+                producer.client_config().clone(),
+                producer.producer_config().clone(),
+                self.rpc_hook.clone(),
+            );
+            producer.set_default_mqproducer_impl(producer_impl);
+        }
+        producer
     }
 }
 
@@ -46,19 +175,76 @@ impl TraceDispatcher for AsyncTraceDispatcher {
         name_srv_addr: &str,
         access_channel: AccessChannel,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        if self.started.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let (Some(trace_context_rx), Some(flush_rx)) = (
+            self.trace_context_rx.lock().take(),
+            self.flush_rx.lock().take(),
+        ) else {
+            return Ok(());
+        };
+        let worker = TraceWorker {
+            producer: self.build_trace_producer(name_srv_addr, access_channel),
+            trace_topic_name: self.trace_topic_name.clone(),
+            access_channel,
+            segments: HashMap::new(),
+            trace_context_rx,
+            flush_rx,
+            shutdown_rx: self.shutdown_tx.subscribe(),
+        };
+        tokio::spawn(worker.run());
+        Ok(())
     }
 
     fn append(&self, ctx: &dyn Any) -> bool {
-        todo!()
+        let Some(ctx) = ctx.downcast_ref::<TraceContext>() else {
+            return false;
+        };
+        if self.trace_context_tx.try_send(ctx.clone()).is_ok() {
+            return true;
+        }
+        let discard_count = self.discard_count.fetch_add(1, Ordering::Relaxed) + 1;
+        if discard_count % 100 == 1 {
+            warn!(
+                "trace context queue is full, {} trace contexts discarded, group {}",
+                discard_count, self.group
+            );
+        }
+        false
     }
 
+    /// Sends the trace data appended so far right away instead of waiting for the batches to
+    /// fill up, and blocks until the worker confirms it is sent or the flush times out.
     fn flush(&self) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        if !self.started.load(Ordering::Acquire) {
+            return Ok(());
+        }
+        let (done_tx, done_rx) = oneshot::channel();
+        if self.flush_tx.send(done_tx).is_err() {
+            // the worker is stopped, it sent everything before stopping
+            return Ok(());
+        }
+        let flushed = tokio::task::block_in_place(move || {
+            Handle::current().block_on(tokio::time::timeout(
+                Duration::from_millis(FLUSH_TIMEOUT_MILLIS),
+                done_rx,
+            ))
+        });
+        match flushed {
+            Ok(_) => Ok(()),
+            Err(_) => Err(rocketmq_error::RocketmqError::RequestTimeoutError(
+                RequestTimeoutErr::new(format!(
+                    "flush trace data timeout, {}ms elapsed",
+                    FLUSH_TIMEOUT_MILLIS
+                )),
+            )),
+        }
     }
 
+    /// Stops the worker once the trace contexts appended so far are sent.
     fn shutdown(&self) {
-        todo!()
+        let _ = self.shutdown_tx.send(true);
     }
 
     fn as_any(&self) -> &dyn Any {
@@ -71,8 +257,234 @@ impl TraceDispatcher for AsyncTraceDispatcher {
 }
 
 impl AsyncTraceDispatcher {
-    pub fn set_host_producer(&mut self, host_producer: ArcMut<DefaultMQProducerImpl>) {}
-    pub fn set_host_consumer(&mut self, host_producer: ArcMut<DefaultMQPushConsumerImpl>) {}
+    pub fn set_namespace_v2(&mut self, namespace_v2: Option<CheetahString>) {
+        self.namespace_v2 = namespace_v2;
+    }
+}
+
+/// The trace data waiting to be sent to one trace topic.
+#[derive(Default)]
+struct TraceDataSegment {
+    first_bean_add_time: u64,
+    current_msg_size: usize,
+    trace_transfer_beans: Vec<TraceTransferBean>,
+}
+
+impl TraceDataSegment {
+    fn add(&mut self, trace_transfer_bean: TraceTransferBean) {
+        if self.trace_transfer_beans.is_empty() {
+            self.first_bean_add_time = get_current_millis();
+        }
+        self.current_msg_size += trace_transfer_bean.trans_data.len();
+        self.trace_transfer_beans.push(trace_transfer_bean);
+    }
 
-    pub fn set_namespace_v2(&mut self, namespace_v2: Option<CheetahString>) {}
+    fn is_full(&self) -> bool {
+        self.trace_transfer_beans.len() >= BATCH_SIZE
+            || self.current_msg_size >= MAX_MSG_SIZE - 10 * 1000
+    }
+
+    fn is_expired(&self, now: u64) -> bool {
+        !self.trace_transfer_beans.is_empty()
+            && now.saturating_sub(self.first_bean_add_time) >= WAIT_TIME_THRESHOLD_MILLIS
+    }
+
+    /// Joins the batched trace data into the body and the keys of one trace message.
+    fn take_message(&mut self, topic: &CheetahString) -> Message {
+        let mut data = String::with_capacity(self.current_msg_size);
+        let mut keys = HashSet::new();
+        for bean in self.trace_transfer_beans.drain(..) {
+            data.push_str(&bean.trans_data);
+            keys.extend(bean.trans_key);
+        }
+        self.current_msg_size = 0;
+        let mut message = Message::new(topic.clone(), data.as_bytes());
+        let keys = keys
+            .iter()
+            .map(|key| key.as_str())
+            .collect::<Vec<_>>()
+            .join(MessageConst::KEY_SEPARATOR);
+        message.set_keys(CheetahString::from_string(keys));
+        message
+    }
+}
+
+struct TraceWorker {
+    producer: DefaultMQProducer,
+    trace_topic_name: CheetahString,
+    access_channel: AccessChannel,
+    segments: HashMap<CheetahString, TraceDataSegment>,
+    trace_context_rx: mpsc::Receiver<TraceContext>,
+    flush_rx: mpsc::UnboundedReceiver<oneshot::Sender<()>>,
+    shutdown_rx: watch::Receiver<bool>,
+}
+
+impl TraceWorker {
+    async fn run(mut self) {
+        if let Err(err) = self.producer.start().await {
+            warn!("start trace producer failed: {}", err);
+        }
+        info!(
+            "trace dispatcher started, trace topic {}",
+            self.trace_topic_name
+        );
+        loop {
+            tokio::select! {
+                ctx = self.trace_context_rx.recv() => match ctx {
+                    Some(ctx) => self.add(ctx).await,
+                    None => break,
+                },
+                _ = tokio::time::sleep(Duration::from_millis(POLLING_TIME_MILLIS)) => {}
+                Some(done_tx) = self.flush_rx.recv() => {
+                    self.drain().await;
+                    let _ = done_tx.send(());
+                }
+                _ = self.shutdown_rx.changed() => break,
+            }
+            self.send_segments(false).await;
+        }
+        self.drain().await;
+        self.producer.shutdown().await;
+        info!(
+            "trace dispatcher stopped, trace topic {}",
+            self.trace_topic_name
+        );
+    }
+
+    /// Sends the trace contexts waiting in the queue and the batched trace data.
+    async fn drain(&mut self) {
+        while let Ok(ctx) = self.trace_context_rx.try_recv() {
+            self.add(ctx).await;
+        }
+        self.send_segments(true).await;
+    }
+
+    async fn add(&mut self, ctx: TraceContext) {
+        let Some(trace_transfer_bean) = TraceDataEncoder::encoder_from_context_bean(&ctx) else {
+            return;
+        };
+        // the cloud access channel keeps the traces of each region in its own topic
+        let topic = if self.access_channel == AccessChannel::Cloud {
+            CheetahString::from_string(format!(
+                "{}{}",
+                TraceConstants::TRACE_TOPIC_PREFIX,
+                ctx.region_id
+            ))
+        } else {
+            self.trace_topic_name.clone()
+        };
+        let segment = self.segments.entry(topic.clone()).or_default();
+        segment.add(trace_transfer_bean);
+        if segment.is_full() {
+            let message = segment.take_message(&topic);
+            self.send(message).await;
+        }
+    }
+
+    async fn send_segments(&mut self, force: bool) {
+        let now = get_current_millis();
+        let mut messages = Vec::new();
+        for (topic, segment) in self.segments.iter_mut() {
+            if (force && !segment.trace_transfer_beans.is_empty()) || segment.is_expired(now) {
+                messages.push(segment.take_message(topic));
+            }
+        }
+        for message in messages {
+            self.send(message).await;
+        }
+    }
+
+    async fn send(&mut self, message: Message) {
+        let result = self
+            .producer
+            .send_with_callback(message, |_, err| {
+                if let Some(err) = err {
+                    warn!("send trace data failed: {}", err);
+                }
+            })
+            .await;
+        if let Err(err) = result {
+            warn!("send trace data failed: {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::message::MessageTrait;
+
+    use super::*;
+    use crate::trace::trace_bean::TraceBean;
+    use crate::trace::trace_type::TraceType;
+
+    #[test]
+    fn append_discards_when_queue_is_full() {
+        let dispatcher = AsyncTraceDispatcher::new("group", Type::Produce, "", None);
+        assert_eq!(
+            dispatcher.trace_topic_name().as_str(),
+            TopicValidator::RMQ_SYS_TRACE_TOPIC
+        );
+        let ctx = TraceContext {
+            trace_type: Some(TraceType::Pub),
+            trace_beans: Some(vec![TraceBean::default()]),
+            ..TraceContext::default()
+        };
+        for _ in 0..QUEUE_SIZE {
+            assert!(dispatcher.append(&ctx));
+        }
+        assert!(!dispatcher.append(&ctx));
+        assert!(!dispatcher.append(&"not a trace context"));
+        assert_eq!(dispatcher.discard_count(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn flush_waits_for_queued_contexts() {
+        let dispatcher = AsyncTraceDispatcher::new("group", Type::Produce, "", None);
+        assert!(dispatcher.flush().is_ok());
+        dispatcher
+            .start("127.0.0.1:9876", AccessChannel::Local)
+            .unwrap();
+        let ctx = TraceContext {
+            trace_type: Some(TraceType::Pub),
+            trace_beans: Some(vec![TraceBean::default()]),
+            ..TraceContext::default()
+        };
+        for _ in 0..10 {
+            assert!(dispatcher.append(&ctx));
+        }
+        assert!(dispatcher.flush().is_ok());
+        assert_eq!(dispatcher.trace_context_tx.capacity(), QUEUE_SIZE);
+
+        dispatcher.shutdown();
+    }
+
+    #[test]
+    fn segment_batches_trace_data() {
+        let mut segment = TraceDataSegment::default();
+        for index in 0..BATCH_SIZE {
+            assert!(!segment.is_full());
+            segment.add(TraceTransferBean {
+                trans_data: format!("data{}", index),
+                trans_key: HashSet::from([CheetahString::from(format!("key{}", index))]),
+            });
+        }
+        assert!(segment.is_full());
+        assert!(!segment.is_expired(segment.first_bean_add_time));
+        assert!(segment.is_expired(segment.first_bean_add_time + WAIT_TIME_THRESHOLD_MILLIS));
+
+        let topic = CheetahString::from_static_str(TopicValidator::RMQ_SYS_TRACE_TOPIC);
+        let message = segment.take_message(&topic);
+        assert_eq!(message.topic, topic);
+        assert!(message.body.as_ref().unwrap().starts_with(b"data0data1"));
+        assert_eq!(
+            message
+                .get_keys()
+                .unwrap()
+                .split(MessageConst::KEY_SEPARATOR)
+                .count(),
+            BATCH_SIZE
+        );
+        assert!(segment.trace_transfer_beans.is_empty());
+        assert_eq!(segment.current_msg_size, 0);
+    }
 }
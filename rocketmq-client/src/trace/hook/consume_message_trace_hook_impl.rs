@@ -16,9 +16,20 @@
  */
 use std::sync::Arc;
 
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
+
+use crate::consumer::listener::consume_return_type::ConsumeReturnType;
 use crate::hook::consume_message_context::ConsumeMessageContext;
 use crate::hook::consume_message_hook::ConsumeMessageHook;
+use crate::trace::trace_bean::TraceBean;
+use crate::trace::trace_context::TraceContext;
 use crate::trace::trace_dispatcher::TraceDispatcher;
+use crate::trace::trace_type::TraceType;
 
 pub struct ConsumeMessageTraceHookImpl {
     trace_dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>>,
@@ -32,14 +43,98 @@ impl ConsumeMessageTraceHookImpl {
 
 impl ConsumeMessageHook for ConsumeMessageTraceHookImpl {
     fn hook_name(&self) -> &str {
-        todo!()
+        "ConsumeMessageTraceHook"
     }
 
     fn consume_message_before(&self, context: Option<&mut ConsumeMessageContext>) {
-        todo!()
+        let Some(context) = context else {
+            return;
+        };
+        if context.msg_list.is_empty() {
+            return;
+        }
+        let mut trace_context = TraceContext {
+            trace_type: Some(TraceType::SubBefore),
+            group_name: CheetahString::from_string(NamespaceUtil::without_namespace(
+                context.consumer_group.as_str(),
+            )),
+            ..TraceContext::new()
+        };
+        let mut trace_beans = Vec::with_capacity(context.msg_list.len());
+        for msg in context.msg_list.iter() {
+            let trace_on = msg.get_property(&CheetahString::from_static_str(
+                MessageConst::PROPERTY_TRACE_SWITCH,
+            ));
+            if trace_on.as_deref() == Some("false") {
+                continue;
+            }
+            if let Some(region_id) = msg.get_property(&CheetahString::from_static_str(
+                MessageConst::PROPERTY_MSG_REGION,
+            )) {
+                trace_context.region_id = region_id;
+            }
+            trace_beans.push(TraceBean {
+                topic: CheetahString::from_string(NamespaceUtil::without_namespace(
+                    msg.get_topic(),
+                )),
+                msg_id: msg.msg_id().clone(),
+                tags: msg.get_tags().unwrap_or_default(),
+                keys: msg.get_keys().unwrap_or_default(),
+                store_time: msg.store_timestamp(),
+                body_length: msg.store_size(),
+                retry_times: msg.reconsume_times(),
+                ..TraceBean::default()
+            });
+        }
+        if trace_beans.is_empty() {
+            return;
+        }
+        trace_context.trace_beans = Some(trace_beans);
+        trace_context.time_stamp = get_current_millis();
+        self.trace_dispatcher.append(&trace_context);
+        context.mq_trace_context = Some(Arc::new(Box::new(trace_context)));
     }
 
     fn consume_message_after(&self, context: Option<&mut ConsumeMessageContext>) {
-        todo!()
+        let Some(context) = context else {
+            return;
+        };
+        if context.msg_list.is_empty() {
+            return;
+        }
+        let Some(sub_before_context) = context
+            .mq_trace_context
+            .as_ref()
+            .and_then(|trace_context| trace_context.downcast_ref::<TraceContext>())
+        else {
+            return;
+        };
+        if sub_before_context
+            .trace_beans
+            .as_ref()
+            .map_or(true, |trace_beans| trace_beans.is_empty())
+        {
+            return;
+        }
+        let cost_time = (get_current_millis().saturating_sub(sub_before_context.time_stamp)
+            / context.msg_list.len() as u64) as i32;
+        let context_code = context
+            .props
+            .get(mix_all::CONSUME_CONTEXT_TYPE)
+            .and_then(|context_type| context_type.parse::<ConsumeReturnType>().ok())
+            .map_or(0, i32::from);
+        let sub_after_context = TraceContext {
+            trace_type: Some(TraceType::SubAfter),
+            region_id: sub_before_context.region_id.clone(),
+            group_name: sub_before_context.group_name.clone(),
+            request_id: sub_before_context.request_id.clone(),
+            access_channel: context.access_channel,
+            is_success: context.success,
+            cost_time,
+            context_code,
+            trace_beans: sub_before_context.trace_beans.clone(),
+            ..TraceContext::new()
+        };
+        self.trace_dispatcher.append(&sub_after_context);
     }
 }
@@ -16,9 +16,19 @@
  */
 use std::sync::Arc;
 
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_enum::MessageType;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
+
 use crate::hook::end_transaction_context::EndTransactionContext;
 use crate::hook::end_transaction_hook::EndTransactionHook;
+use crate::trace::trace_bean::TraceBean;
+use crate::trace::trace_context::TraceContext;
 use crate::trace::trace_dispatcher::TraceDispatcher;
+use crate::trace::trace_type::TraceType;
 
 pub struct EndTransactionTraceHookImpl {
     trace_dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>>,
@@ -36,6 +46,36 @@ impl EndTransactionHook for EndTransactionTraceHookImpl {
     }
 
     fn end_transaction(&self, context: &EndTransactionContext) {
-        todo!()
+        let message = context.message;
+        let trace_bean = TraceBean {
+            topic: CheetahString::from_string(NamespaceUtil::without_namespace(
+                message.get_topic(),
+            )),
+            tags: message.get_tags().unwrap_or_default(),
+            keys: message.get_keys().unwrap_or_default(),
+            store_host: context.broker_addr.clone(),
+            msg_type: Some(MessageType::TransMsgCommit),
+            msg_id: context.msg_id.clone(),
+            transaction_state: Some(context.transaction_state),
+            transaction_id: Some(context.transaction_id.clone()),
+            from_transaction_check: context.from_transaction_check,
+            ..TraceBean::default()
+        };
+        let region_id = message
+            .get_property(&CheetahString::from_static_str(
+                MessageConst::PROPERTY_MSG_REGION,
+            ))
+            .filter(|region_id| !region_id.is_empty())
+            .unwrap_or_else(|| CheetahString::from_static_str(mix_all::DEFAULT_TRACE_REGION_ID));
+        let trace_context = TraceContext {
+            trace_type: Some(TraceType::EndTransaction),
+            group_name: CheetahString::from_string(NamespaceUtil::without_namespace(
+                context.producer_group.as_str(),
+            )),
+            region_id,
+            trace_beans: Some(vec![trace_bean]),
+            ..TraceContext::new()
+        };
+        self.trace_dispatcher.append(&trace_context);
     }
 }
@@ -16,9 +16,17 @@
  */
 use std::sync::Arc;
 
+use cheetah_string::CheetahString;
+use rocketmq_remoting::protocol::namespace_util::NamespaceUtil;
+
 use crate::hook::send_message_context::SendMessageContext;
 use crate::hook::send_message_hook::SendMessageHook;
+use crate::producer::send_status::SendStatus;
+use crate::trace::async_trace_dispatcher::AsyncTraceDispatcher;
+use crate::trace::trace_bean::TraceBean;
+use crate::trace::trace_context::TraceContext;
 use crate::trace::trace_dispatcher::TraceDispatcher;
+use crate::trace::trace_type::TraceType;
 
 pub struct SendMessageTraceHookImpl {
     trace_dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>>,
@@ -28,17 +36,91 @@ impl SendMessageTraceHookImpl {
     pub fn new(trace_dispatcher: Arc<Box<dyn TraceDispatcher + Send + Sync>>) -> Self {
         Self { trace_dispatcher }
     }
+
+    /// The messages sent to the trace topic are not traced themselves.
+    fn is_trace_message(&self, topic: &str) -> bool {
+        self.trace_dispatcher
+            .as_any()
+            .downcast_ref::<AsyncTraceDispatcher>()
+            .is_some_and(|dispatcher| topic.starts_with(dispatcher.trace_topic_name().as_str()))
+    }
 }
+
 impl SendMessageHook for SendMessageTraceHookImpl {
     fn hook_name(&self) -> &str {
-        todo!()
+        "SendMessageTraceHook"
     }
 
-    fn send_message_before(&self, context: &Option<SendMessageContext<'_>>) {
-        todo!()
+    fn send_message_before(&self, context: &mut Option<SendMessageContext<'_>>) {
+        let Some(context) = context.as_mut() else {
+            return;
+        };
+        let Some(message) = context.message.as_ref() else {
+            return;
+        };
+        if self.is_trace_message(message.get_topic()) {
+            return;
+        }
+        let trace_bean = TraceBean {
+            topic: CheetahString::from_string(NamespaceUtil::without_namespace(
+                message.get_topic(),
+            )),
+            tags: message.get_tags().unwrap_or_default(),
+            keys: message.get_keys().unwrap_or_default(),
+            store_host: context.broker_addr.clone().unwrap_or_default(),
+            body_length: message.get_body().map_or(0, |body| body.len() as i32),
+            msg_type: context.msg_type,
+            ..TraceBean::default()
+        };
+        let trace_context = TraceContext {
+            trace_type: Some(TraceType::Pub),
+            group_name: CheetahString::from_string(NamespaceUtil::without_namespace(
+                context.producer_group.as_deref().unwrap_or_default(),
+            )),
+            trace_beans: Some(vec![trace_bean]),
+            ..TraceContext::new()
+        };
+        context.mq_trace_context = Some(Arc::new(Box::new(trace_context)));
     }
 
     fn send_message_after(&self, context: &Option<SendMessageContext<'_>>) {
-        todo!()
+        let Some(context) = context.as_ref() else {
+            return;
+        };
+        let Some(trace_context) = context
+            .mq_trace_context
+            .as_ref()
+            .and_then(|trace_context| trace_context.downcast_ref::<TraceContext>())
+        else {
+            return;
+        };
+        let Some(send_result) = context.send_result.as_ref() else {
+            return;
+        };
+        let Some(region_id) = send_result.region_id.as_ref() else {
+            return;
+        };
+        if !send_result.trace_on {
+            return;
+        }
+        let mut trace_context = trace_context.clone();
+        let Some(trace_beans) = trace_context.trace_beans.as_mut() else {
+            return;
+        };
+        let cost_time = (rocketmq_common::TimeUtils::get_current_millis()
+            .saturating_sub(trace_context.time_stamp)
+            / trace_beans.len() as u64) as i32;
+        let trace_bean = &mut trace_beans[0];
+        trace_bean.msg_id = send_result.msg_id.clone().unwrap_or_default();
+        trace_bean.offset_msg_id = send_result
+            .offset_msg_id
+            .as_deref()
+            .map(CheetahString::from)
+            .unwrap_or_default();
+        trace_bean.store_time = trace_context.time_stamp as i64 + cost_time as i64 / 2;
+        trace_context.cost_time = cost_time;
+        trace_context.is_success = send_result.send_status == SendStatus::SendOk;
+        trace_context.region_id = CheetahString::from(region_id.as_str());
+        self.trace_dispatcher.append(&trace_context);
     }
 }
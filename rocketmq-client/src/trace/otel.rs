@@ -0,0 +1,36 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! OpenTelemetry style tracing across RocketMQ hops.
+//!
+//! The API mirrors the `opentelemetry` crate: a process wide tracer and
+//! [`TextMapPropagator`](propagation::TextMapPropagator) installed through [`global`], and an
+//! active [`Context`](context::Context) carrying the current span. The producer records one
+//! `publish` span per send, child of the active span, and injects it into the message
+//! properties; retries are recorded as events of that span. The push consumer extracts the
+//! propagated context, records a `process` span and attaches its context around the
+//! listener, so messages sent from the listener continue the trace. Spans are handed to a
+//! [`SpanExporter`](span_exporter::SpanExporter), usually the
+//! [`OtlpHttpSpanExporter`](otlp_http_exporter::OtlpHttpSpanExporter).
+
+pub mod context;
+pub mod global;
+pub mod message_tracer;
+pub mod otlp_http_exporter;
+pub mod propagation;
+pub mod span;
+pub mod span_exporter;
+pub mod trace_parent;
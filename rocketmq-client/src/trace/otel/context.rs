@@ -0,0 +1,163 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::cell::RefCell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Poll;
+
+use crate::trace::otel::span::Span;
+use crate::trace::otel::trace_parent::SpanContext;
+
+thread_local! {
+    static CURRENT_CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+/// The execution scoped context carrying the active span, mirroring the OpenTelemetry
+/// `Context`. Spans started by the tracer take the active span as their parent.
+#[derive(Clone, Default)]
+pub struct Context {
+    span: Option<Arc<Span>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The context attached to the current thread.
+    pub fn current() -> Self {
+        CURRENT_CONTEXT.with(|current| current.borrow().clone())
+    }
+
+    pub fn current_with_span(span: Span) -> Self {
+        Self::current().with_span(span)
+    }
+
+    /// A copy of this context with `span` as the active span.
+    pub fn with_span(&self, span: Span) -> Self {
+        Context {
+            span: Some(Arc::new(span)),
+        }
+    }
+
+    /// A copy of this context whose active span is the propagated `span_context`.
+    pub fn with_remote_span_context(&self, span_context: SpanContext) -> Self {
+        self.with_span(Span::non_recording(span_context))
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_deref()
+    }
+
+    pub fn has_active_span(&self) -> bool {
+        self.span.is_some()
+    }
+
+    /// Makes this context the current one until the returned guard is dropped.
+    pub fn attach(self) -> ContextGuard {
+        let previous = CURRENT_CONTEXT.with(|current| current.replace(self));
+        ContextGuard {
+            previous: Some(previous),
+            _not_send: PhantomData,
+        }
+    }
+}
+
+/// Restores the previous context when dropped.
+pub struct ContextGuard {
+    previous: Option<Context>,
+    // the guard restores the context of the thread that attached it
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CURRENT_CONTEXT.with(|current| current.replace(previous));
+        }
+    }
+}
+
+/// Attaches a context to a future, it is current whenever the future is polled.
+pub trait FutureExt: Future + Sized {
+    fn with_context(self, cx: Context) -> WithContext<Self> {
+        WithContext {
+            inner: Box::pin(self),
+            cx,
+        }
+    }
+}
+
+impl<F: Future> FutureExt for F {}
+
+pub struct WithContext<F> {
+    inner: Pin<Box<F>>,
+    cx: Context,
+}
+
+impl<F: Future> Future for WithContext<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, task_cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let _guard = this.cx.clone().attach();
+        this.inner.as_mut().poll(task_cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attach_restores_previous_context() {
+        let outer = SpanContext::new_root();
+        let inner = outer.new_child();
+        assert!(!Context::current().has_active_span());
+        {
+            let _outer = Context::new()
+                .with_remote_span_context(outer.clone())
+                .attach();
+            {
+                let _inner = Context::current()
+                    .with_remote_span_context(inner.clone())
+                    .attach();
+                assert_eq!(Context::current().span().unwrap().span_context(), &inner);
+            }
+            assert_eq!(Context::current().span().unwrap().span_context(), &outer);
+        }
+        assert!(!Context::current().has_active_span());
+    }
+
+    #[tokio::test]
+    async fn future_sees_attached_context() {
+        let span_context = SpanContext::new_root();
+        let cx = Context::new().with_remote_span_context(span_context.clone());
+        let seen = async {
+            tokio::task::yield_now().await;
+            Context::current()
+                .span()
+                .map(|span| span.span_context().clone())
+        }
+        .with_context(cx)
+        .await;
+        assert_eq!(seen, Some(span_context));
+        assert!(!Context::current().has_active_span());
+    }
+}
@@ -0,0 +1,70 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Process wide tracer and propagator, mirroring `opentelemetry::global`.
+
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use crate::trace::otel::message_tracer::MessageTracer;
+use crate::trace::otel::propagation::TextMapPropagator;
+use crate::trace::otel::propagation::TraceContextPropagator;
+
+static TRACER: RwLock<Option<Arc<MessageTracer>>> = RwLock::new(None);
+static TEXT_MAP_PROPAGATOR: RwLock<Option<Arc<dyn TextMapPropagator>>> = RwLock::new(None);
+
+/// Installs the tracer used by every producer and push consumer of this process.
+pub fn set_tracer(tracer: MessageTracer) {
+    let previous = TRACER
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(Arc::new(tracer));
+    if let Some(previous) = previous {
+        previous.shutdown();
+    }
+}
+
+pub fn tracer() -> Option<Arc<MessageTracer>> {
+    TRACER.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Removes the installed tracer and flushes its exporter.
+pub fn shutdown_tracer() {
+    let tracer = TRACER.write().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(tracer) = tracer {
+        tracer.shutdown();
+    }
+}
+
+/// Installs the propagator carrying the trace context in message properties, the W3C
+/// [`TraceContextPropagator`] unless set.
+pub fn set_text_map_propagator(propagator: impl TextMapPropagator + 'static) {
+    TEXT_MAP_PROPAGATOR
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(Arc::new(propagator));
+}
+
+pub fn get_text_map_propagator<T>(f: impl FnOnce(&dyn TextMapPropagator) -> T) -> T {
+    let propagator = TEXT_MAP_PROPAGATOR
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    match propagator {
+        Some(propagator) => f(propagator.as_ref()),
+        None => f(&TraceContextPropagator),
+    }
+}
@@ -0,0 +1,389 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use rocketmq_common::common::message::message_batch::MessageBatch;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
+
+use crate::trace::otel::context::Context;
+use crate::trace::otel::global;
+use crate::trace::otel::propagation::MessageInjector;
+use crate::trace::otel::span::Span;
+use crate::trace::otel::span::SpanKind;
+use crate::trace::otel::span::SpanStatus;
+use crate::trace::otel::span_exporter::SpanExporter;
+use crate::trace::otel::trace_parent::SpanContext;
+
+const MESSAGING_SYSTEM: &str = "messaging.system";
+const MESSAGING_SYSTEM_ROCKETMQ: &str = "rocketmq";
+const MESSAGING_OPERATION: &str = "messaging.operation";
+const MESSAGING_DESTINATION_NAME: &str = "messaging.destination.name";
+const MESSAGING_MESSAGE_ID: &str = "messaging.message.id";
+const MESSAGING_BATCH_MESSAGE_COUNT: &str = "messaging.batch.message_count";
+const MESSAGING_ROCKETMQ_CLIENT_GROUP: &str = "messaging.rocketmq.client_group";
+const MESSAGING_ROCKETMQ_MESSAGE_TAG: &str = "messaging.rocketmq.message.tag";
+const MESSAGING_ROCKETMQ_MESSAGE_KEYS: &str = "messaging.rocketmq.message.keys";
+const MESSAGING_ROCKETMQ_SEND_ATTEMPT: &str = "messaging.rocketmq.send.attempt";
+const MESSAGING_ROCKETMQ_BROKER_NAME: &str = "messaging.rocketmq.broker_name";
+
+const OPERATION_PUBLISH: &str = "publish";
+const OPERATION_PROCESS: &str = "process";
+const EVENT_SEND_RETRY: &str = "send retry";
+
+/// Creates the producer and consumer spans of RocketMQ messages, following the
+/// OpenTelemetry messaging semantic conventions.
+pub struct MessageTracer {
+    exporter: Arc<dyn SpanExporter>,
+}
+
+impl MessageTracer {
+    pub fn new(exporter: Arc<dyn SpanExporter>) -> Self {
+        MessageTracer { exporter }
+    }
+
+    /// Starts the `publish` span of `msg` as a child of the active span and injects it into
+    /// the message properties, into every message of a [`MessageBatch`].
+    ///
+    /// Returns `None` when the active span is already the one propagated in `msg`: the
+    /// producer retries a send inside the context of its span, which covers every attempt.
+    pub fn start_send_span<T: MessageTrait + ?Sized>(
+        &self,
+        producer_group: &str,
+        msg: &mut T,
+    ) -> Option<Context> {
+        let parent_cx = Context::current();
+        let parent = parent_cx.span().map(|span| span.span_context().clone());
+        if parent.is_some() {
+            let propagated = global::get_text_map_propagator(|propagator| {
+                propagator.extract_with_context(&Context::new(), msg.get_properties())
+            });
+            if propagated.span().map(Span::span_context) == parent.as_ref() {
+                return None;
+            }
+        }
+        let span_context = parent
+            .as_ref()
+            .map(SpanContext::new_child)
+            .unwrap_or_else(SpanContext::new_root);
+
+        let topic = msg.get_topic().to_string();
+        let span = Span::start(
+            format!("{} {}", topic, OPERATION_PUBLISH),
+            SpanKind::Producer,
+            span_context,
+            parent.as_ref(),
+            self.exporter.clone(),
+        );
+        span.set_attribute(MESSAGING_SYSTEM, MESSAGING_SYSTEM_ROCKETMQ);
+        span.set_attribute(MESSAGING_OPERATION, OPERATION_PUBLISH);
+        span.set_attribute(MESSAGING_DESTINATION_NAME, topic);
+        span.set_attribute(MESSAGING_ROCKETMQ_CLIENT_GROUP, producer_group);
+        if let Some(tags) = msg.get_tags() {
+            span.set_attribute(MESSAGING_ROCKETMQ_MESSAGE_TAG, tags.as_str());
+        }
+        if let Some(keys) = msg.get_keys() {
+            span.set_attribute(MESSAGING_ROCKETMQ_MESSAGE_KEYS, keys.as_str());
+        }
+
+        let cx = parent_cx.with_span(span);
+        global::get_text_map_propagator(|propagator| {
+            propagator.inject_context(&cx, &mut MessageInjector(&mut *msg));
+            if let Some(batch) = msg.as_any_mut().downcast_mut::<MessageBatch>() {
+                let messages = batch.messages.as_deref_mut().unwrap_or_default();
+                for message in messages.iter_mut() {
+                    propagator.inject_context(&cx, &mut MessageInjector(message));
+                }
+                if let Some(span) = cx.span() {
+                    span.set_attribute(MESSAGING_BATCH_MESSAGE_COUNT, messages.len().to_string());
+                }
+                // the batch body was encoded before the context was injected
+                batch.set_body(batch.encode());
+            }
+        });
+        Some(cx)
+    }
+
+    /// Records a retry of the send whose span is active.
+    pub fn record_send_retry(&self, attempt: u32, broker_name: &str) {
+        if let Some(span) = Context::current().span() {
+            span.add_event(
+                EVENT_SEND_RETRY,
+                vec![
+                    (MESSAGING_ROCKETMQ_SEND_ATTEMPT, attempt.to_string()),
+                    (MESSAGING_ROCKETMQ_BROKER_NAME, broker_name.to_string()),
+                ],
+            );
+        }
+    }
+
+    /// Starts the `process` span of the messages handed to one listener call, to be attached
+    /// around the listener. A single message continues the trace propagated by its producer,
+    /// a batch links to the producer span of every message instead.
+    pub fn start_process_span(&self, consumer_group: &str, msgs: &[&MessageExt]) -> Context {
+        let Some(first) = msgs.first() else {
+            return Context::current();
+        };
+        let propagated = msgs
+            .iter()
+            .map(|msg| {
+                global::get_text_map_propagator(|propagator| {
+                    propagator.extract_with_context(&Context::current(), msg.get_properties())
+                })
+            })
+            .collect::<Vec<_>>();
+        let parent_cx = if msgs.len() == 1 {
+            propagated[0].clone()
+        } else {
+            Context::current()
+        };
+        let parent = parent_cx.span().map(|span| span.span_context().clone());
+        let span_context = parent
+            .as_ref()
+            .map(SpanContext::new_child)
+            .unwrap_or_else(SpanContext::new_root);
+
+        let topic = first.get_topic().to_string();
+        let span = Span::start(
+            format!("{} {}", topic, OPERATION_PROCESS),
+            SpanKind::Consumer,
+            span_context,
+            parent.as_ref(),
+            self.exporter.clone(),
+        );
+        span.set_attribute(MESSAGING_SYSTEM, MESSAGING_SYSTEM_ROCKETMQ);
+        span.set_attribute(MESSAGING_OPERATION, OPERATION_PROCESS);
+        span.set_attribute(MESSAGING_DESTINATION_NAME, topic);
+        span.set_attribute(MESSAGING_ROCKETMQ_CLIENT_GROUP, consumer_group);
+        if msgs.len() == 1 {
+            span.set_attribute(MESSAGING_MESSAGE_ID, first.msg_id().as_str());
+            if let Some(tags) = first.get_tags() {
+                span.set_attribute(MESSAGING_ROCKETMQ_MESSAGE_TAG, tags.as_str());
+            }
+        } else {
+            span.set_attribute(MESSAGING_BATCH_MESSAGE_COUNT, msgs.len().to_string());
+            for producer_cx in &propagated {
+                if let Some(producer_span) = producer_cx.span() {
+                    if Some(producer_span.span_context()) != parent.as_ref() {
+                        span.add_link(producer_span.span_context().clone());
+                    }
+                }
+            }
+        }
+        parent_cx.with_span(span)
+    }
+
+    /// Sets the status of the span active in `cx` and ends it.
+    pub fn end_span(cx: &Context, status: SpanStatus) {
+        if let Some(span) = cx.span() {
+            span.set_status(status);
+            span.end();
+        }
+    }
+
+    pub fn shutdown(&self) {
+        self.exporter.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cheetah_string::CheetahString;
+    use rocketmq_common::common::message::message_single::Message;
+
+    use super::*;
+    use crate::trace::otel::context::FutureExt;
+    use crate::trace::otel::propagation::TextMapPropagator;
+    use crate::trace::otel::propagation::TraceContextPropagator;
+    use crate::trace::otel::span_exporter::InMemorySpanExporter;
+
+    fn new_tracer() -> (MessageTracer, InMemorySpanExporter) {
+        let exporter = InMemorySpanExporter::new();
+        (MessageTracer::new(Arc::new(exporter.clone())), exporter)
+    }
+
+    fn propagated_span_context(msg: &Message) -> Option<SpanContext> {
+        TraceContextPropagator::new()
+            .extract_with_context(&Context::new(), msg.get_properties())
+            .span()
+            .map(|span| span.span_context().clone())
+    }
+
+    fn to_message_ext(msg: &Message) -> MessageExt {
+        let mut msg_ext = MessageExt::default();
+        msg_ext.set_message_inner(msg.clone());
+        msg_ext
+    }
+
+    #[test]
+    fn consumer_span_continues_producer_trace() {
+        let (tracer, exporter) = new_tracer();
+
+        let mut msg = Message::with_tags("TopicTest", "TagA", b"hello");
+        let send_cx = tracer.start_send_span("producer_group", &mut msg).unwrap();
+        MessageTracer::end_span(&send_cx, SpanStatus::Ok);
+
+        let msg_ext = to_message_ext(&msg);
+        let process_cx = tracer.start_process_span("consumer_group", &[&msg_ext]);
+        {
+            // a message sent by the listener continues the consumer trace
+            let _guard = process_cx.clone().attach();
+            let mut reply = Message::with_tags("TopicReply", "TagA", b"world");
+            let reply_cx = tracer
+                .start_send_span("producer_group", &mut reply)
+                .unwrap();
+            MessageTracer::end_span(&reply_cx, SpanStatus::Ok);
+        }
+        MessageTracer::end_span(&process_cx, SpanStatus::Ok);
+
+        let spans = exporter.get_finished_spans();
+        assert_eq!(spans.len(), 3);
+        let (send, reply, process) = (&spans[0], &spans[1], &spans[2]);
+        assert_eq!(send.name, "TopicTest publish");
+        assert_eq!(send.kind, SpanKind::Producer);
+        assert_eq!(send.status, SpanStatus::Ok);
+        assert!(send.parent_span_id.is_none());
+        assert_eq!(process.name, "TopicTest process");
+        assert_eq!(process.kind, SpanKind::Consumer);
+        assert_eq!(
+            process.span_context.trace_id(),
+            send.span_context.trace_id()
+        );
+        assert_eq!(
+            process.parent_span_id.as_ref(),
+            Some(send.span_context.span_id())
+        );
+        assert_eq!(reply.name, "TopicReply publish");
+        assert_eq!(
+            reply.parent_span_id.as_ref(),
+            Some(process.span_context.span_id())
+        );
+    }
+
+    #[test]
+    fn send_span_is_child_of_active_span() {
+        let (tracer, exporter) = new_tracer();
+        let parent = SpanContext::new_root();
+        let _guard = Context::new()
+            .with_remote_span_context(parent.clone())
+            .attach();
+
+        let mut msg = Message::with_tags("TopicTest", "TagA", b"hello");
+        drop(tracer.start_send_span("producer_group", &mut msg));
+
+        let spans = exporter.get_finished_spans();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].span_context.trace_id(), parent.trace_id());
+        assert_eq!(spans[0].parent_span_id.as_ref(), Some(parent.span_id()));
+        assert_eq!(
+            propagated_span_context(&msg).as_ref(),
+            Some(&spans[0].span_context)
+        );
+    }
+
+    #[tokio::test]
+    async fn retried_send_keeps_one_span() {
+        let (tracer, exporter) = new_tracer();
+        let mut msg = Message::with_tags("TopicTest", "TagA", b"hello");
+        let send_cx = tracer.start_send_span("producer_group", &mut msg).unwrap();
+        let propagated = propagated_span_context(&msg);
+
+        async {
+            for attempt in 1..=3 {
+                if attempt > 1 {
+                    tracer.record_send_retry(attempt, "broker-a");
+                }
+                // every attempt goes through the kernel send, which must not start a span
+                assert!(tracer.start_send_span("producer_group", &mut msg).is_none());
+                tokio::task::yield_now().await;
+            }
+        }
+        .with_context(send_cx.clone())
+        .await;
+        MessageTracer::end_span(&send_cx, SpanStatus::Ok);
+
+        let spans = exporter.get_finished_spans();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(propagated_span_context(&msg), propagated);
+        let events = &spans[0].events;
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].name, "send retry");
+        assert_eq!(
+            events[1].attributes[0],
+            (MESSAGING_ROCKETMQ_SEND_ATTEMPT, "3".to_string())
+        );
+    }
+
+    #[test]
+    fn batch_send_propagates_to_every_message() {
+        let (tracer, exporter) = new_tracer();
+        let mut batch = MessageBatch::generate_from_vec(vec![
+            Message::with_tags("TopicTest", "TagA", b"first"),
+            Message::with_tags("TopicTest", "TagA", b"second"),
+        ])
+        .unwrap();
+        batch.set_body(batch.encode());
+
+        let send_cx = tracer
+            .start_send_span("producer_group", &mut batch)
+            .unwrap();
+        MessageTracer::end_span(&send_cx, SpanStatus::Ok);
+
+        let spans = exporter.get_finished_spans();
+        assert_eq!(spans.len(), 1);
+        assert!(spans[0]
+            .attributes
+            .contains(&(MESSAGING_BATCH_MESSAGE_COUNT, "2".to_string())));
+        for msg in batch.messages.as_ref().unwrap() {
+            assert_eq!(
+                propagated_span_context(msg).as_ref(),
+                Some(&spans[0].span_context)
+            );
+        }
+        assert_eq!(batch.get_body(), Some(&batch.encode()));
+        assert!(batch
+            .get_property(&CheetahString::from_static_str(
+                TraceContextPropagator::TRACEPARENT
+            ))
+            .is_some());
+    }
+
+    #[test]
+    fn batch_process_span_links_producer_spans() {
+        let (tracer, exporter) = new_tracer();
+        let mut first = Message::with_tags("TopicTest", "TagA", b"first");
+        let mut second = Message::with_tags("TopicTest", "TagA", b"second");
+        drop(tracer.start_send_span("producer_group", &mut first));
+        drop(tracer.start_send_span("producer_group", &mut second));
+
+        let (first, second) = (to_message_ext(&first), to_message_ext(&second));
+        let process_cx = tracer.start_process_span("consumer_group", &[&first, &second]);
+        MessageTracer::end_span(&process_cx, SpanStatus::Ok);
+
+        let spans = exporter.get_finished_spans();
+        assert_eq!(spans.len(), 3);
+        let process = &spans[2];
+        assert!(process.parent_span_id.is_none());
+        assert_eq!(
+            process.links,
+            vec![spans[0].span_context.clone(), spans[1].span_context.clone()]
+        );
+        assert!(process
+            .attributes
+            .contains(&(MESSAGING_BATCH_MESSAGE_COUNT, "2".to_string())));
+    }
+}
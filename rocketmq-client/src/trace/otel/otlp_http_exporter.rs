@@ -0,0 +1,241 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::time::Duration;
+
+use serde_json::json;
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::trace::otel::span::SpanData;
+use crate::trace::otel::span::SpanStatus;
+use crate::trace::otel::span_exporter::SpanExporter;
+use crate::trace::otel::trace_parent::encode_hex;
+
+const TRACES_PATH: &str = "/v1/traces";
+const SCOPE_NAME: &str = "rocketmq-client";
+const MAX_EXPORT_BATCH_SIZE: usize = 512;
+const SCHEDULED_DELAY: Duration = Duration::from_secs(5);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum ExportCommand {
+    Span(Box<SpanData>),
+    Shutdown,
+}
+
+/// Batches spans and posts them to an OpenTelemetry collector with OTLP/HTTP using the
+/// JSON encoding.
+pub struct OtlpHttpSpanExporter {
+    sender: mpsc::UnboundedSender<ExportCommand>,
+}
+
+impl OtlpHttpSpanExporter {
+    /// Starts the background export task, it must be called inside a tokio runtime.
+    /// `endpoint` is the collector base URL, e.g. `http://localhost:4318`.
+    pub fn new(endpoint: impl Into<String>, service_name: impl Into<String>) -> Self {
+        let url = format!("{}{}", endpoint.into().trim_end_matches('/'), TRACES_PATH);
+        let service_name = service_name.into();
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            let mut batch = Vec::with_capacity(MAX_EXPORT_BATCH_SIZE);
+            let mut interval = tokio::time::interval(SCHEDULED_DELAY);
+            loop {
+                tokio::select! {
+                    command = receiver.recv() => match command {
+                        Some(ExportCommand::Span(span)) => {
+                            batch.push(*span);
+                            if batch.len() >= MAX_EXPORT_BATCH_SIZE {
+                                post_spans(&client, &url, &service_name, &mut batch).await;
+                            }
+                        }
+                        Some(ExportCommand::Shutdown) | None => {
+                            post_spans(&client, &url, &service_name, &mut batch).await;
+                            break;
+                        }
+                    },
+                    _ = interval.tick() => {
+                        post_spans(&client, &url, &service_name, &mut batch).await;
+                    }
+                }
+            }
+        });
+        OtlpHttpSpanExporter { sender }
+    }
+}
+
+impl SpanExporter for OtlpHttpSpanExporter {
+    fn export(&self, span: SpanData) {
+        let _ = self.sender.send(ExportCommand::Span(Box::new(span)));
+    }
+
+    fn shutdown(&self) {
+        let _ = self.sender.send(ExportCommand::Shutdown);
+    }
+}
+
+async fn post_spans(
+    client: &reqwest::Client,
+    url: &str,
+    service_name: &str,
+    batch: &mut Vec<SpanData>,
+) {
+    if batch.is_empty() {
+        return;
+    }
+    let body = encode_spans(service_name, batch).to_string();
+    batch.clear();
+    let result = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .timeout(EXPORT_TIMEOUT)
+        .body(body)
+        .send()
+        .await;
+    match result {
+        Ok(response) if !response.status().is_success() => {
+            warn!(
+                "Export spans to {} failed, status {}",
+                url,
+                response.status()
+            );
+        }
+        Err(e) => warn!("Export spans to {} failed: {}", url, e),
+        Ok(_) => {}
+    }
+}
+
+/// Builds an `ExportTraceServiceRequest` in the OTLP JSON encoding.
+pub(crate) fn encode_spans(service_name: &str, spans: &[SpanData]) -> Value {
+    let spans = spans.iter().map(encode_span).collect::<Vec<_>>();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [string_attribute("service.name", service_name)]
+            },
+            "scopeSpans": [{
+                "scope": { "name": SCOPE_NAME },
+                "spans": spans
+            }]
+        }]
+    })
+}
+
+fn encode_span(span: &SpanData) -> Value {
+    let mut value = json!({
+        "traceId": span.span_context.trace_id_hex(),
+        "spanId": span.span_context.span_id_hex(),
+        "name": span.name,
+        "kind": span.kind.otlp_value(),
+        "startTimeUnixNano": span.start_time_unix_nano.to_string(),
+        "endTimeUnixNano": span.end_time_unix_nano.to_string(),
+        "attributes": span
+            .attributes
+            .iter()
+            .map(|(key, value)| string_attribute(key, value))
+            .collect::<Vec<_>>(),
+        "events": span
+            .events
+            .iter()
+            .map(|event| json!({
+                "name": event.name,
+                "timeUnixNano": event.time_unix_nano.to_string(),
+                "attributes": event
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| string_attribute(key, value))
+                    .collect::<Vec<_>>(),
+            }))
+            .collect::<Vec<_>>(),
+        "links": span
+            .links
+            .iter()
+            .map(|link| json!({
+                "traceId": link.trace_id_hex(),
+                "spanId": link.span_id_hex(),
+            }))
+            .collect::<Vec<_>>(),
+        "status": match &span.status {
+            SpanStatus::Unset => json!({ "code": 0 }),
+            SpanStatus::Ok => json!({ "code": 1 }),
+            SpanStatus::Error(message) => json!({ "code": 2, "message": message }),
+        },
+    });
+    if let Some(parent_span_id) = span.parent_span_id.as_ref() {
+        value["parentSpanId"] = Value::String(encode_hex(parent_span_id));
+    }
+    if !span.span_context.trace_state().is_empty() {
+        value["traceState"] = Value::String(span.span_context.trace_state().to_string());
+    }
+    value
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::otel::span::SpanEvent;
+    use crate::trace::otel::span::SpanKind;
+    use crate::trace::otel::trace_parent::SpanContext;
+
+    #[test]
+    fn encode_spans_in_otlp_json() {
+        let parent = SpanContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            None,
+        )
+        .unwrap();
+        let span = SpanData {
+            name: "TopicTest process".to_string(),
+            kind: SpanKind::Consumer,
+            span_context: parent.new_child(),
+            parent_span_id: Some(*parent.span_id()),
+            start_time_unix_nano: 1,
+            end_time_unix_nano: 2,
+            attributes: vec![("messaging.system", "rocketmq".to_string())],
+            events: vec![SpanEvent {
+                name: "send retry".to_string(),
+                time_unix_nano: 1,
+                attributes: vec![("messaging.rocketmq.send.attempt", "2".to_string())],
+            }],
+            links: vec![parent.clone()],
+            status: SpanStatus::Error("ReconsumeLater".to_string()),
+        };
+        let value = encode_spans("demo", &[span]);
+        let resource_spans = &value["resourceSpans"][0];
+        assert_eq!(
+            resource_spans["resource"]["attributes"][0]["value"]["stringValue"],
+            "demo"
+        );
+        let span = &resource_spans["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["traceId"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(span["parentSpanId"], "00f067aa0ba902b7");
+        assert_eq!(span["kind"], 5);
+        assert_eq!(span["startTimeUnixNano"], "1");
+        assert_eq!(span["status"]["code"], 2);
+        assert_eq!(span["attributes"][0]["key"], "messaging.system");
+        assert_eq!(span["events"][0]["name"], "send retry");
+        assert_eq!(
+            span["events"][0]["attributes"][0]["value"]["stringValue"],
+            "2"
+        );
+        assert_eq!(span["links"][0]["spanId"], "00f067aa0ba902b7");
+    }
+}
@@ -0,0 +1,155 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::MessageTrait;
+
+use crate::trace::otel::context::Context;
+use crate::trace::otel::trace_parent::SpanContext;
+
+/// Writes propagation fields into a carrier.
+pub trait Injector {
+    fn set(&mut self, key: &str, value: String);
+}
+
+/// Reads propagation fields from a carrier.
+pub trait Extractor {
+    fn get(&self, key: &str) -> Option<&str>;
+}
+
+/// Carries a [`Context`] across process boundaries, mirroring the OpenTelemetry
+/// `TextMapPropagator`.
+pub trait TextMapPropagator: Send + Sync {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector);
+
+    /// Returns `cx` with the propagated span as its active span, or `cx` unchanged when
+    /// the carrier holds no valid context.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context;
+
+    fn inject(&self, injector: &mut dyn Injector) {
+        self.inject_context(&Context::current(), injector);
+    }
+
+    fn extract(&self, extractor: &dyn Extractor) -> Context {
+        self.extract_with_context(&Context::current(), extractor)
+    }
+}
+
+/// Propagates the W3C `traceparent`/`tracestate` headers, see
+/// <https://www.w3.org/TR/trace-context/>.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TraceContextPropagator;
+
+impl TraceContextPropagator {
+    pub const TRACEPARENT: &'static str = "traceparent";
+    pub const TRACESTATE: &'static str = "tracestate";
+
+    pub fn new() -> Self {
+        TraceContextPropagator
+    }
+}
+
+impl TextMapPropagator for TraceContextPropagator {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let Some(span) = cx.span() else {
+            return;
+        };
+        let span_context = span.span_context();
+        injector.set(Self::TRACEPARENT, span_context.to_traceparent());
+        if !span_context.trace_state().is_empty() {
+            injector.set(Self::TRACESTATE, span_context.trace_state().to_string());
+        }
+    }
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        extractor
+            .get(Self::TRACEPARENT)
+            .and_then(|traceparent| {
+                SpanContext::from_traceparent(traceparent, extractor.get(Self::TRACESTATE))
+            })
+            .map(|span_context| cx.with_remote_span_context(span_context))
+            .unwrap_or_else(|| cx.clone())
+    }
+}
+
+/// Injects into the properties of a message.
+pub struct MessageInjector<'a, T: ?Sized>(pub &'a mut T);
+
+impl<T: MessageTrait + ?Sized> Injector for MessageInjector<'_, T> {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.put_property(
+            CheetahString::from_slice(key),
+            CheetahString::from_string(value),
+        );
+    }
+}
+
+/// Extracts from message properties.
+impl Extractor for HashMap<CheetahString, CheetahString> {
+    fn get(&self, key: &str) -> Option<&str> {
+        HashMap::get(self, key).map(CheetahString::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::message::message_single::Message;
+
+    use super::*;
+
+    #[test]
+    fn trace_context_round_trip_through_message_properties() {
+        let span_context = SpanContext::from_traceparent(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            Some("congo=t61rcWkgMzE"),
+        )
+        .unwrap();
+        let cx = Context::new().with_remote_span_context(span_context.clone());
+        let propagator = TraceContextPropagator::new();
+
+        let mut msg = Message::default();
+        propagator.inject_context(&cx, &mut MessageInjector(&mut msg));
+        assert_eq!(
+            msg.get_property(&CheetahString::from_static_str(
+                TraceContextPropagator::TRACESTATE
+            )),
+            Some(CheetahString::from_static_str("congo=t61rcWkgMzE"))
+        );
+
+        let extracted = propagator.extract_with_context(&Context::new(), msg.get_properties());
+        assert_eq!(extracted.span().unwrap().span_context(), &span_context);
+        assert!(!extracted.span().unwrap().is_recording());
+    }
+
+    #[test]
+    fn missing_or_invalid_context_is_not_extracted() {
+        let propagator = TraceContextPropagator::new();
+        let mut msg = Message::default();
+        assert!(!propagator
+            .extract_with_context(&Context::new(), msg.get_properties())
+            .has_active_span());
+
+        msg.put_property(
+            CheetahString::from_static_str(TraceContextPropagator::TRACEPARENT),
+            CheetahString::from_static_str("00-invalid"),
+        );
+        assert!(!propagator
+            .extract_with_context(&Context::new(), msg.get_properties())
+            .has_active_span());
+    }
+}
@@ -0,0 +1,181 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use parking_lot::Mutex;
+
+use crate::trace::otel::span_exporter::SpanExporter;
+use crate::trace::otel::trace_parent::SpanContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Producer,
+    Consumer,
+}
+
+impl SpanKind {
+    /// The `SpanKind` value of the OTLP protocol.
+    pub fn otlp_value(&self) -> i32 {
+        match self {
+            SpanKind::Producer => 4,
+            SpanKind::Consumer => 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SpanStatus {
+    #[default]
+    Unset,
+    Ok,
+    Error(String),
+}
+
+/// Something that happened during a span, e.g. a send retry.
+#[derive(Debug, Clone)]
+pub struct SpanEvent {
+    pub name: String,
+    pub time_unix_nano: u64,
+    pub attributes: Vec<(&'static str, String)>,
+}
+
+/// A finished span, as handed to the exporter.
+#[derive(Debug, Clone)]
+pub struct SpanData {
+    pub name: String,
+    pub kind: SpanKind,
+    pub span_context: SpanContext,
+    pub parent_span_id: Option<[u8; 8]>,
+    pub start_time_unix_nano: u64,
+    pub end_time_unix_nano: u64,
+    pub attributes: Vec<(&'static str, String)>,
+    pub events: Vec<SpanEvent>,
+    /// Contexts of causally related spans that are not the parent, e.g. the producer spans
+    /// of a batch of consumed messages.
+    pub links: Vec<SpanContext>,
+    pub status: SpanStatus,
+}
+
+/// A span in progress, usually held by a [`Context`](crate::trace::otel::context::Context).
+/// It is exported when [`Span::end`] is called or when it is dropped.
+///
+/// A span built from a propagated [`SpanContext`] is not recording: it only serves as the
+/// parent of the spans started in its context.
+pub struct Span {
+    span_context: SpanContext,
+    data: Mutex<Option<SpanData>>,
+    exporter: Option<Arc<dyn SpanExporter>>,
+}
+
+impl Span {
+    pub(crate) fn start(
+        name: String,
+        kind: SpanKind,
+        span_context: SpanContext,
+        parent: Option<&SpanContext>,
+        exporter: Arc<dyn SpanExporter>,
+    ) -> Self {
+        Span {
+            data: Mutex::new(Some(SpanData {
+                name,
+                kind,
+                span_context: span_context.clone(),
+                parent_span_id: parent.map(|parent| *parent.span_id()),
+                start_time_unix_nano: now_unix_nano(),
+                end_time_unix_nano: 0,
+                attributes: Vec::new(),
+                events: Vec::new(),
+                links: Vec::new(),
+                status: SpanStatus::Unset,
+            })),
+            span_context,
+            exporter: Some(exporter),
+        }
+    }
+
+    pub fn non_recording(span_context: SpanContext) -> Self {
+        Span {
+            span_context,
+            data: Mutex::new(None),
+            exporter: None,
+        }
+    }
+
+    pub fn span_context(&self) -> &SpanContext {
+        &self.span_context
+    }
+
+    /// Whether the span still records, `false` once ended or when built from a propagated
+    /// context.
+    pub fn is_recording(&self) -> bool {
+        self.data.lock().is_some()
+    }
+
+    pub fn set_attribute(&self, key: &'static str, value: impl Into<String>) {
+        if let Some(data) = self.data.lock().as_mut() {
+            data.attributes.push((key, value.into()));
+        }
+    }
+
+    pub fn add_event(&self, name: impl Into<String>, attributes: Vec<(&'static str, String)>) {
+        if let Some(data) = self.data.lock().as_mut() {
+            data.events.push(SpanEvent {
+                name: name.into(),
+                time_unix_nano: now_unix_nano(),
+                attributes,
+            });
+        }
+    }
+
+    pub fn add_link(&self, span_context: SpanContext) {
+        if let Some(data) = self.data.lock().as_mut() {
+            data.links.push(span_context);
+        }
+    }
+
+    pub fn set_status(&self, status: SpanStatus) {
+        if let Some(data) = self.data.lock().as_mut() {
+            data.status = status;
+        }
+    }
+
+    /// Ends the span and hands it to the exporter, later calls do nothing.
+    pub fn end(&self) {
+        let data = self.data.lock().take();
+        if let (Some(mut data), Some(exporter)) = (data, self.exporter.as_ref()) {
+            data.end_time_unix_nano = now_unix_nano();
+            if data.span_context.is_sampled() {
+                exporter.export(data);
+            }
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        self.end();
+    }
+}
+
+fn now_unix_nano() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,56 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::trace::otel::span::SpanData;
+
+/// Receives finished spans. Implementations must not block, `export` is called on the
+/// send and consume paths.
+pub trait SpanExporter: Send + Sync {
+    fn export(&self, span: SpanData);
+
+    /// Flushes pending spans and releases resources.
+    fn shutdown(&self) {}
+}
+
+/// Keeps finished spans in memory, for tests.
+#[derive(Debug, Clone, Default)]
+pub struct InMemorySpanExporter {
+    spans: Arc<Mutex<Vec<SpanData>>>,
+}
+
+impl InMemorySpanExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_finished_spans(&self) -> Vec<SpanData> {
+        self.spans.lock().clone()
+    }
+
+    pub fn reset(&self) {
+        self.spans.lock().clear();
+    }
+}
+
+impl SpanExporter for InMemorySpanExporter {
+    fn export(&self, span: SpanData) {
+        self.spans.lock().push(span);
+    }
+}
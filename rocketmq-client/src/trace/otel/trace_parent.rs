@@ -0,0 +1,186 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt::Write;
+
+const SUPPORTED_VERSION: u8 = 0;
+const TRACEPARENT_LEN: usize = 55;
+
+/// The part of a span that crosses process boundaries, see
+/// <https://www.w3.org/TR/trace-context/>.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanContext {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    trace_flags: u8,
+    trace_state: String,
+}
+
+impl SpanContext {
+    pub const FLAG_SAMPLED: u8 = 0x01;
+
+    /// Starts a new sampled trace.
+    pub fn new_root() -> Self {
+        SpanContext {
+            trace_id: random_non_zero(),
+            span_id: random_non_zero(),
+            trace_flags: Self::FLAG_SAMPLED,
+            trace_state: String::new(),
+        }
+    }
+
+    /// Creates the context of a child span in the same trace.
+    pub fn new_child(&self) -> Self {
+        SpanContext {
+            trace_id: self.trace_id,
+            span_id: random_non_zero(),
+            trace_flags: self.trace_flags,
+            trace_state: self.trace_state.clone(),
+        }
+    }
+
+    pub fn trace_id(&self) -> &[u8; 16] {
+        &self.trace_id
+    }
+
+    pub fn span_id(&self) -> &[u8; 8] {
+        &self.span_id
+    }
+
+    pub fn trace_flags(&self) -> u8 {
+        self.trace_flags
+    }
+
+    pub fn trace_state(&self) -> &str {
+        &self.trace_state
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.trace_flags & Self::FLAG_SAMPLED != 0
+    }
+
+    pub fn trace_id_hex(&self) -> String {
+        encode_hex(&self.trace_id)
+    }
+
+    pub fn span_id_hex(&self) -> String {
+        encode_hex(&self.span_id)
+    }
+
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "{:02x}-{}-{}-{:02x}",
+            SUPPORTED_VERSION,
+            self.trace_id_hex(),
+            self.span_id_hex(),
+            self.trace_flags
+        )
+    }
+
+    /// Parses a `traceparent` header, returning `None` for anything the W3C spec asks
+    /// receivers to ignore.
+    pub fn from_traceparent(traceparent: &str, trace_state: Option<&str>) -> Option<Self> {
+        let traceparent = traceparent.trim();
+        let mut parts = traceparent.split('-');
+        let version = decode_hex::<1>(parts.next()?)?[0];
+        if version == 0xff
+            || (version == SUPPORTED_VERSION && traceparent.len() != TRACEPARENT_LEN)
+            || traceparent.len() < TRACEPARENT_LEN
+        {
+            return None;
+        }
+        let trace_id = decode_hex::<16>(parts.next()?)?;
+        let span_id = decode_hex::<8>(parts.next()?)?;
+        let trace_flags = decode_hex::<1>(parts.next()?)?[0];
+        if version == SUPPORTED_VERSION && parts.next().is_some() {
+            return None;
+        }
+        if trace_id.iter().all(|b| *b == 0) || span_id.iter().all(|b| *b == 0) {
+            return None;
+        }
+        Some(SpanContext {
+            trace_id,
+            span_id,
+            trace_flags,
+            trace_state: trace_state.unwrap_or_default().trim().to_string(),
+        })
+    }
+}
+
+fn random_non_zero<const N: usize>() -> [u8; N] {
+    loop {
+        let bytes: [u8; N] = std::array::from_fn(|_| rand::random::<u8>());
+        if bytes.iter().any(|b| *b != 0) {
+            return bytes;
+        }
+    }
+}
+
+pub(crate) fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut hex, b| {
+            let _ = write!(hex, "{:02x}", b);
+            hex
+        })
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
+    if hex.len() != N * 2
+        || !hex
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    {
+        return None;
+    }
+    let mut bytes = [0u8; N];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).ok()?;
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent_round_trip() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let context =
+            SpanContext::from_traceparent(traceparent, Some("congo=t61rcWkgMzE")).unwrap();
+        assert_eq!(context.trace_id_hex(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(context.span_id_hex(), "00f067aa0ba902b7");
+        assert!(context.is_sampled());
+        assert_eq!(context.trace_state(), "congo=t61rcWkgMzE");
+        assert_eq!(context.to_traceparent(), traceparent);
+    }
+
+    #[test]
+    fn invalid_traceparent_is_ignored() {
+        for traceparent in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+        ] {
+            assert!(SpanContext::from_traceparent(traceparent, None).is_none());
+        }
+    }
+}
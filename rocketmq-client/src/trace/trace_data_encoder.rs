@@ -0,0 +1,366 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashSet;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_enum::MessageType;
+use rocketmq_common::common::message::MessageConst;
+
+use crate::base::access_channel::AccessChannel;
+use crate::producer::local_transaction_state::LocalTransactionState;
+use crate::trace::trace_bean::TraceBean;
+use crate::trace::trace_constants::TraceConstants;
+use crate::trace::trace_context::TraceContext;
+use crate::trace::trace_transfer_bean::TraceTransferBean;
+use crate::trace::trace_type::TraceType;
+
+/// Encodes trace contexts into the trace data format shared with the other RocketMQ clients,
+/// and decodes the trace data back. The fields of a context are separated by
+/// `TraceConstants::CONTENT_SPLITOR`, the contexts by `TraceConstants::FIELD_SPLITOR`.
+pub struct TraceDataEncoder;
+
+impl TraceDataEncoder {
+    /// Decodes the trace contexts stored in the body of a trace message.
+    pub fn decoder_from_trace_data_string(trace_data: &str) -> Vec<TraceContext> {
+        let mut res_list = Vec::new();
+        for context in trace_data.split(TraceConstants::FIELD_SPLITOR) {
+            let line: Vec<&str> = context.split(TraceConstants::CONTENT_SPLITOR).collect();
+            let trace_type = match line[0] {
+                "Pub" => TraceType::Pub,
+                "SubBefore" => TraceType::SubBefore,
+                "SubAfter" => TraceType::SubAfter,
+                "EndTransaction" => TraceType::EndTransaction,
+                _ => continue,
+            };
+            let field = |index: usize| CheetahString::from(line.get(index).copied().unwrap_or(""));
+            let mut trace_context = TraceContext {
+                trace_type: Some(trace_type),
+                ..TraceContext::new()
+            };
+            let mut bean = TraceBean::default();
+            match trace_type {
+                TraceType::Pub => {
+                    trace_context.time_stamp = parse_field(&line, 1);
+                    trace_context.region_id = field(2);
+                    trace_context.group_name = field(3);
+                    bean.topic = field(4);
+                    bean.msg_id = field(5);
+                    bean.tags = field(6);
+                    bean.keys = field(7);
+                    bean.store_host = field(8);
+                    bean.body_length = parse_field(&line, 9);
+                    trace_context.cost_time = parse_field(&line, 10);
+                    bean.msg_type = Some(message_type_of(parse_field(&line, 11)));
+                    if line.len() == 13 {
+                        trace_context.is_success = line[12] == "true";
+                    } else if line.len() >= 14 {
+                        bean.offset_msg_id = field(12);
+                        trace_context.is_success = line[13] == "true";
+                    }
+                }
+                TraceType::SubBefore => {
+                    trace_context.time_stamp = parse_field(&line, 1);
+                    trace_context.region_id = field(2);
+                    trace_context.group_name = field(3);
+                    trace_context.request_id = field(4);
+                    bean.msg_id = field(5);
+                    bean.retry_times = parse_field(&line, 6);
+                    bean.keys = field(7);
+                }
+                TraceType::SubAfter => {
+                    trace_context.request_id = field(1);
+                    bean.msg_id = field(2);
+                    trace_context.cost_time = parse_field(&line, 3);
+                    trace_context.is_success = field(4) == "true";
+                    bean.keys = field(5);
+                    if line.len() >= 7 {
+                        trace_context.context_code = parse_field(&line, 6);
+                    }
+                    if line.len() >= 9 {
+                        trace_context.time_stamp = parse_field(&line, 7);
+                        trace_context.group_name = field(8);
+                    }
+                }
+                TraceType::EndTransaction => {
+                    trace_context.time_stamp = parse_field(&line, 1);
+                    trace_context.region_id = field(2);
+                    trace_context.group_name = field(3);
+                    bean.topic = field(4);
+                    bean.msg_id = field(5);
+                    bean.tags = field(6);
+                    bean.keys = field(7);
+                    bean.store_host = field(8);
+                    bean.msg_type = Some(message_type_of(parse_field(&line, 9)));
+                    bean.transaction_id = Some(field(10));
+                    bean.transaction_state = transaction_state_of(line.get(11).copied());
+                    bean.from_transaction_check = field(12) == "true";
+                    trace_context.is_success = true;
+                }
+            }
+            trace_context.trace_beans = Some(vec![bean]);
+            res_list.push(trace_context);
+        }
+        res_list
+    }
+
+    /// Encodes a trace context, returns `None` when the context has no trace bean.
+    pub fn encoder_from_context_bean(ctx: &TraceContext) -> Option<TraceTransferBean> {
+        let trace_beans = ctx.trace_beans.as_ref().filter(|beans| !beans.is_empty())?;
+        let trace_type = ctx.trace_type?;
+        let mut sb = String::with_capacity(256);
+        let mut append = |value: &dyn std::fmt::Display, splitor: char| {
+            sb.push_str(&value.to_string());
+            sb.push(splitor);
+        };
+        const C: char = TraceConstants::CONTENT_SPLITOR;
+        const F: char = TraceConstants::FIELD_SPLITOR;
+        match trace_type {
+            TraceType::Pub => {
+                let bean = &trace_beans[0];
+                append(&trace_type, C);
+                append(&ctx.time_stamp, C);
+                append(&ctx.region_id, C);
+                append(&ctx.group_name, C);
+                append(&bean.topic, C);
+                append(&bean.msg_id, C);
+                append(&bean.tags, C);
+                append(&bean.keys, C);
+                append(&bean.store_host, C);
+                append(&bean.body_length, C);
+                append(&ctx.cost_time, C);
+                append(&message_type_ordinal(bean.msg_type), C);
+                append(&bean.offset_msg_id, C);
+                append(&ctx.is_success, F);
+            }
+            TraceType::SubBefore => {
+                for bean in trace_beans {
+                    append(&trace_type, C);
+                    append(&ctx.time_stamp, C);
+                    append(&ctx.region_id, C);
+                    append(&ctx.group_name, C);
+                    append(&ctx.request_id, C);
+                    append(&bean.msg_id, C);
+                    append(&bean.retry_times, C);
+                    append(&bean.keys, F);
+                }
+            }
+            TraceType::SubAfter => {
+                for bean in trace_beans {
+                    append(&trace_type, C);
+                    append(&ctx.request_id, C);
+                    append(&bean.msg_id, C);
+                    append(&ctx.cost_time, C);
+                    append(&ctx.is_success, C);
+                    append(&bean.keys, C);
+                    if ctx.access_channel == Some(AccessChannel::Cloud) {
+                        append(&ctx.context_code, F);
+                    } else {
+                        append(&ctx.context_code, C);
+                        append(&ctx.time_stamp, C);
+                        append(&ctx.group_name, F);
+                    }
+                }
+            }
+            TraceType::EndTransaction => {
+                let bean = &trace_beans[0];
+                append(&trace_type, C);
+                append(&ctx.time_stamp, C);
+                append(&ctx.region_id, C);
+                append(&ctx.group_name, C);
+                append(&bean.topic, C);
+                append(&bean.msg_id, C);
+                append(&bean.tags, C);
+                append(&bean.keys, C);
+                append(&bean.store_host, C);
+                append(&message_type_ordinal(bean.msg_type), C);
+                append(&bean.transaction_id.clone().unwrap_or_default(), C);
+                append(&transaction_state_name(bean.transaction_state), C);
+                append(&bean.from_transaction_check, F);
+            }
+        }
+
+        let mut trans_key = HashSet::new();
+        for bean in trace_beans {
+            trans_key.insert(bean.msg_id.clone());
+            for key in bean.keys.split(MessageConst::KEY_SEPARATOR) {
+                if !key.is_empty() {
+                    trans_key.insert(CheetahString::from(key));
+                }
+            }
+        }
+        Some(TraceTransferBean {
+            trans_data: sb,
+            trans_key,
+        })
+    }
+}
+
+fn parse_field<T: std::str::FromStr + Default>(line: &[&str], index: usize) -> T {
+    line.get(index)
+        .and_then(|value| value.parse().ok())
+        .unwrap_or_default()
+}
+
+fn message_type_ordinal(msg_type: Option<MessageType>) -> i32 {
+    match msg_type.unwrap_or_default() {
+        MessageType::NormalMsg => 0,
+        MessageType::TransMsgHalf => 1,
+        MessageType::TransMsgCommit => 2,
+        MessageType::DelayMsg => 3,
+        MessageType::OrderMsg => 4,
+    }
+}
+
+fn message_type_of(ordinal: i32) -> MessageType {
+    match ordinal {
+        1 => MessageType::TransMsgHalf,
+        2 => MessageType::TransMsgCommit,
+        3 => MessageType::DelayMsg,
+        4 => MessageType::OrderMsg,
+        _ => MessageType::NormalMsg,
+    }
+}
+
+fn transaction_state_name(state: Option<LocalTransactionState>) -> &'static str {
+    match state.unwrap_or_default() {
+        LocalTransactionState::CommitMessage => "COMMIT_MESSAGE",
+        LocalTransactionState::RollbackMessage => "ROLLBACK_MESSAGE",
+        LocalTransactionState::Unknown => "UNKNOW",
+    }
+}
+
+fn transaction_state_of(name: Option<&str>) -> Option<LocalTransactionState> {
+    match name? {
+        "COMMIT_MESSAGE" => Some(LocalTransactionState::CommitMessage),
+        "ROLLBACK_MESSAGE" => Some(LocalTransactionState::RollbackMessage),
+        "UNKNOW" => Some(LocalTransactionState::Unknown),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bean() -> TraceBean {
+        TraceBean {
+            topic: CheetahString::from("TopicTest"),
+            msg_id: CheetahString::from("7F0000010001"),
+            offset_msg_id: CheetahString::from("0A0000010000"),
+            tags: CheetahString::from("TagA"),
+            keys: CheetahString::from("key1 key2"),
+            store_host: CheetahString::from("127.0.0.1:10911"),
+            body_length: 128,
+            retry_times: 2,
+            msg_type: Some(MessageType::NormalMsg),
+            ..TraceBean::default()
+        }
+    }
+
+    fn context(trace_type: TraceType) -> TraceContext {
+        TraceContext {
+            trace_type: Some(trace_type),
+            time_stamp: 1700000000000,
+            region_id: CheetahString::from("DefaultRegion"),
+            group_name: CheetahString::from("group"),
+            cost_time: 12,
+            is_success: true,
+            request_id: CheetahString::from("request"),
+            context_code: 1,
+            access_channel: Some(AccessChannel::Local),
+            trace_beans: Some(vec![bean()]),
+            ..TraceContext::default()
+        }
+    }
+
+    #[test]
+    fn encode_pub_context() {
+        let transfer_bean =
+            TraceDataEncoder::encoder_from_context_bean(&context(TraceType::Pub)).unwrap();
+        assert_eq!(
+            transfer_bean.trans_data,
+            concat!(
+                "Pub\u{1}1700000000000\u{1}DefaultRegion\u{1}group\u{1}TopicTest\u{1}",
+                "7F0000010001\u{1}TagA\u{1}key1 key2\u{1}127.0.0.1:10911\u{1}128\u{1}12\u{1}",
+                "0\u{1}0A0000010000\u{1}true\u{2}"
+            )
+        );
+        let keys: HashSet<CheetahString> = ["7F0000010001", "key1", "key2"]
+            .into_iter()
+            .map(CheetahString::from)
+            .collect();
+        assert_eq!(transfer_bean.trans_key, keys);
+        assert!(TraceDataEncoder::encoder_from_context_bean(&TraceContext::default()).is_none());
+    }
+
+    #[test]
+    fn decode_encoded_contexts() {
+        let mut end_transaction = context(TraceType::EndTransaction);
+        let bean = &mut end_transaction.trace_beans.as_mut().unwrap()[0];
+        bean.msg_type = Some(MessageType::TransMsgCommit);
+        bean.transaction_id = Some(CheetahString::from("transaction"));
+        bean.transaction_state = Some(LocalTransactionState::CommitMessage);
+        bean.from_transaction_check = true;
+
+        let trace_data: String = [
+            context(TraceType::Pub),
+            context(TraceType::SubBefore),
+            context(TraceType::SubAfter),
+            end_transaction,
+        ]
+        .iter()
+        .map(|ctx| {
+            TraceDataEncoder::encoder_from_context_bean(ctx)
+                .unwrap()
+                .trans_data
+        })
+        .collect();
+        let contexts = TraceDataEncoder::decoder_from_trace_data_string(&trace_data);
+        assert_eq!(contexts.len(), 4);
+
+        let publish = &contexts[0];
+        assert_eq!(publish.trace_type, Some(TraceType::Pub));
+        assert_eq!(publish.time_stamp, 1700000000000);
+        assert_eq!(publish.cost_time, 12);
+        assert!(publish.is_success);
+        let publish_bean = &publish.trace_beans.as_ref().unwrap()[0];
+        assert_eq!(publish_bean.topic, "TopicTest");
+        assert_eq!(publish_bean.offset_msg_id, "0A0000010000");
+        assert_eq!(publish_bean.body_length, 128);
+
+        let sub_before = &contexts[1];
+        assert_eq!(sub_before.request_id, "request");
+        assert_eq!(sub_before.trace_beans.as_ref().unwrap()[0].retry_times, 2);
+
+        let sub_after = &contexts[2];
+        assert_eq!(sub_after.trace_type, Some(TraceType::SubAfter));
+        assert_eq!(sub_after.context_code, 1);
+        assert_eq!(sub_after.group_name, "group");
+        assert_eq!(sub_after.trace_beans.as_ref().unwrap()[0].keys, "key1 key2");
+
+        let end_transaction_bean = &contexts[3].trace_beans.as_ref().unwrap()[0];
+        assert_eq!(
+            end_transaction_bean.msg_type,
+            Some(MessageType::TransMsgCommit)
+        );
+        assert_eq!(
+            end_transaction_bean.transaction_state,
+            Some(LocalTransactionState::CommitMessage)
+        );
+        assert!(end_transaction_bean.from_transaction_check);
+    }
+}
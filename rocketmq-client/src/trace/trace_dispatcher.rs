@@ -18,6 +18,7 @@ use std::any::Any;
 
 use crate::base::access_channel::AccessChannel;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Type {
     Produce,
     Consume,
@@ -18,8 +18,13 @@
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
 use rocketmq_common::common::message::message_enum::MessageType;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::utils::util_all;
 
+use crate::trace::trace_data_encoder::TraceDataEncoder;
+use crate::trace::trace_type::TraceType;
+
 lazy_static! {
     static ref LOCAL_ADDRESS: CheetahString = util_all::get_ip_str();
 }
@@ -39,6 +44,7 @@ pub struct TraceView {
     pub topic: CheetahString,
     pub group_name: CheetahString,
     pub status: CheetahString,
+    pub trace_type: Option<TraceType>,
 }
 
 impl Default for TraceView {
@@ -57,10 +63,52 @@ impl Default for TraceView {
             topic: CheetahString::default(),
             group_name: CheetahString::default(),
             status: CheetahString::default(),
+            trace_type: None,
         }
     }
 }
 
+impl TraceView {
+    /// Decodes the trace records carried by a message of the trace topic, keeping the ones that
+    /// belong to the message `key`.
+    pub fn decode_from_trace_trans_data(key: &str, message_ext: &MessageExt) -> Vec<TraceView> {
+        let Some(body) = message_ext.get_body().filter(|body| !body.is_empty()) else {
+            return Vec::new();
+        };
+        let message_body = String::from_utf8_lossy(body);
+        let client_host = CheetahString::from_string(message_ext.born_host().ip().to_string());
+        TraceDataEncoder::decoder_from_trace_data_string(&message_body)
+            .into_iter()
+            .filter_map(|context| {
+                let trace_bean = context.trace_beans.as_ref()?.first()?;
+                if trace_bean.msg_id != key {
+                    return None;
+                }
+                Some(TraceView {
+                    msg_id: trace_bean.msg_id.clone(),
+                    tags: trace_bean.tags.clone(),
+                    keys: trace_bean.keys.clone(),
+                    store_host: trace_bean.store_host.clone(),
+                    client_host: client_host.clone(),
+                    cost_time: context.cost_time as i64,
+                    msg_type: trace_bean.msg_type,
+                    offset_msg_id: trace_bean.offset_msg_id.clone(),
+                    time_stamp: context.time_stamp as i64,
+                    born_time: trace_bean.store_time,
+                    topic: trace_bean.topic.clone(),
+                    group_name: context.group_name.clone(),
+                    status: CheetahString::from_static_str(if context.is_success {
+                        "success"
+                    } else {
+                        "failed"
+                    }),
+                    trace_type: context.trace_type,
+                })
+            })
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use cheetah_string::CheetahString;
@@ -101,6 +149,7 @@ mod tests {
             topic: CheetahString::from("topic"),
             group_name: CheetahString::from("group"),
             status: CheetahString::from("status"),
+            trace_type: Some(TraceType::Pub),
         };
         assert_eq!(trace_view.msg_id, CheetahString::from("msg_id"));
         assert_eq!(trace_view.tags, CheetahString::from("tags"));
@@ -119,4 +168,51 @@ mod tests {
         assert_eq!(trace_view.group_name, CheetahString::from("group"));
         assert_eq!(trace_view.status, CheetahString::from("status"));
     }
+
+    #[test]
+    fn decode_trace_views_for_message() {
+        use crate::trace::trace_bean::TraceBean;
+        use crate::trace::trace_context::TraceContext;
+
+        let bean = |msg_id: &str| TraceBean {
+            topic: CheetahString::from("TopicTest"),
+            msg_id: CheetahString::from(msg_id),
+            msg_type: Some(MessageType::NormalMsg),
+            ..TraceBean::default()
+        };
+        let mut body = String::new();
+        for (trace_type, msg_id) in [
+            (TraceType::Pub, "msg-1"),
+            (TraceType::Pub, "msg-2"),
+            (TraceType::SubBefore, "msg-1"),
+            (TraceType::SubAfter, "msg-1"),
+        ] {
+            let context = TraceContext {
+                trace_type: Some(trace_type),
+                group_name: CheetahString::from("group"),
+                is_success: true,
+                trace_beans: Some(vec![bean(msg_id)]),
+                ..TraceContext::default()
+            };
+            body.push_str(
+                &TraceDataEncoder::encoder_from_context_bean(&context)
+                    .unwrap()
+                    .trans_data,
+            );
+        }
+        let mut message_ext = MessageExt::default();
+        message_ext.set_body(bytes::Bytes::from(body));
+
+        let views = TraceView::decode_from_trace_trans_data("msg-1", &message_ext);
+        let trace_types: Vec<_> = views.iter().map(|view| view.trace_type).collect();
+        assert_eq!(
+            trace_types,
+            vec![
+                Some(TraceType::Pub),
+                Some(TraceType::SubBefore),
+                Some(TraceType::SubAfter)
+            ]
+        );
+        assert!(views.iter().all(|view| view.status == "success"));
+    }
 }
@@ -37,6 +37,7 @@ pub mod config;
 pub mod config_manager;
 pub mod constant;
 pub mod consumer;
+pub mod controller;
 mod faq;
 pub mod filter;
 pub mod future;
@@ -44,6 +45,7 @@ pub mod hasher;
 pub mod key_builder;
 pub mod macros;
 pub mod message;
+pub mod metrics;
 pub mod mix_all;
 pub mod mq_version;
 pub mod namesrv;
@@ -0,0 +1,69 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+use cheetah_string::CheetahString;
+
+use crate::common::attribute::long_range_attribute::LongRangeAttribute;
+use crate::common::attribute::Attribute;
+
+/// Defines attributes and configurations for RocketMQ subscription groups
+pub struct SubscriptionGroupAttributes;
+
+impl SubscriptionGroupAttributes {
+    /// Priority attribute of the consumer group, used by priority aware dispatching
+    pub fn priority_attribute() -> &'static LongRangeAttribute {
+        static INSTANCE: OnceLock<LongRangeAttribute> = OnceLock::new();
+        INSTANCE
+            .get_or_init(|| LongRangeAttribute::new("priority".into(), true, 0, i32::MAX as i64, 0))
+    }
+
+    /// Returns all defined attributes in a HashMap
+    pub fn all() -> &'static HashMap<CheetahString, Arc<dyn Attribute>> {
+        static ALL: OnceLock<HashMap<CheetahString, Arc<dyn Attribute>>> = OnceLock::new();
+        ALL.get_or_init(|| {
+            let mut map = HashMap::new();
+            let priority = Self::priority_attribute();
+            map.insert(
+                priority.name().clone(),
+                Arc::new(priority.clone()) as Arc<dyn Attribute>,
+            );
+            map
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_attribute_range() {
+        let attribute = SubscriptionGroupAttributes::priority_attribute();
+        assert_eq!(attribute.default_value(), 0);
+        assert_eq!(attribute.min(), 0);
+        assert_eq!(attribute.max(), i32::MAX as i64);
+        assert!(attribute.is_changeable());
+    }
+
+    #[test]
+    fn all_attributes_contains_priority() {
+        assert!(SubscriptionGroupAttributes::all().contains_key("priority"));
+    }
+}
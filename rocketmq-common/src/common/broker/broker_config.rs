@@ -26,6 +26,7 @@ use serde::Serialize;
 use crate::common::broker::broker_role::BrokerRole;
 use crate::common::constant::PermName;
 use crate::common::message::message_enum::MessageRequestMode;
+use crate::common::metrics::metrics_exporter_type::MetricsExporterType;
 use crate::common::mix_all;
 use crate::common::mix_all::NAMESRV_ADDR_PROPERTY;
 use crate::common::server::config::ServerConfig;
@@ -176,6 +177,7 @@ pub struct BrokerConfig {
     pub transfer_msg_by_heap: bool,
     pub short_polling_time_mills: u64,
     pub long_polling_enable: bool,
+    pub enable_calc_filter_bit_map: bool,
     pub max_error_rate_of_bloom_filter: i32,
     pub expect_consumer_num_use_filter: i32,
     pub bit_map_length_consume_queue_ext: i32,
@@ -221,6 +223,34 @@ pub struct BrokerConfig {
     pub pop_ck_max_buffer_size: i64,
     pub pop_ck_offset_max_queue_size: u64,
     pub delay_offset_update_version_step: u64,
+    pub controller_addr: Option<CheetahString>,
+    pub fetch_controller_addr_by_dns_lookup: bool,
+    pub sync_broker_metadata_period: u64,
+    pub check_sync_state_set_period: u64,
+    pub sync_controller_metadata_period: u64,
+    pub controller_heart_beat_timeout_mills: u64,
+    pub broker_heartbeat_interval: u64,
+    pub broker_election_priority: i32,
+    #[serde(default)]
+    pub acl_enable: bool,
+    #[serde(default)]
+    pub authentication_enabled: bool,
+    #[serde(default)]
+    pub authorization_enabled: bool,
+    /// The super user created on start up, a json object like
+    /// `{"username":"rocketmq","password":"12345678"}`.
+    #[serde(default)]
+    pub init_authentication_user: CheetahString,
+    #[serde(default)]
+    pub metrics_exporter_type: MetricsExporterType,
+    /// Host the Prometheus exporter binds, loopback unless configured otherwise.
+    #[serde(default = "default_metrics_prom_exporter_host")]
+    pub metrics_prom_exporter_host: CheetahString,
+    #[serde(default = "default_metrics_prom_exporter_port")]
+    pub metrics_prom_exporter_port: u16,
+    /// Keys that can not be changed through `updateBrokerConfig`, separated by `;`.
+    #[serde(default = "default_config_black_list")]
+    pub config_black_list: CheetahString,
 }
 
 impl Default for BrokerConfig {
@@ -292,6 +322,7 @@ impl Default for BrokerConfig {
             transfer_msg_by_heap: true,
             short_polling_time_mills: 1000,
             long_polling_enable: true,
+            enable_calc_filter_bit_map: false,
             max_error_rate_of_bloom_filter: 20,
             expect_consumer_num_use_filter: 32,
             bit_map_length_consume_queue_ext: 64,
@@ -335,6 +366,22 @@ impl Default for BrokerConfig {
             pop_ck_max_buffer_size: 200_000,
             pop_ck_offset_max_queue_size: 20_000,
             delay_offset_update_version_step: 200,
+            controller_addr: None,
+            fetch_controller_addr_by_dns_lookup: false,
+            sync_broker_metadata_period: 5 * 1000,
+            check_sync_state_set_period: 5 * 1000,
+            sync_controller_metadata_period: 10 * 1000,
+            controller_heart_beat_timeout_mills: 10 * 1000,
+            broker_heartbeat_interval: 1000,
+            broker_election_priority: i32::MAX,
+            acl_enable: false,
+            authentication_enabled: false,
+            authorization_enabled: false,
+            init_authentication_user: CheetahString::empty(),
+            metrics_exporter_type: MetricsExporterType::Disable,
+            metrics_prom_exporter_host: default_metrics_prom_exporter_host(),
+            metrics_prom_exporter_port: default_metrics_prom_exporter_port(),
+            config_black_list: default_config_black_list(),
         }
     }
 }
@@ -598,6 +645,10 @@ impl BrokerConfig {
             "longPollingEnable".into(),
             self.long_polling_enable.to_string().into(),
         );
+        properties.insert(
+            "enableCalcFilterBitMap".into(),
+            self.enable_calc_filter_bit_map.to_string().into(),
+        );
         properties.insert(
             "maxErrorRateOfBloomFilter".into(),
             self.max_error_rate_of_bloom_filter.to_string().into(),
@@ -628,8 +679,136 @@ impl BrokerConfig {
             "forwardTimeout".into(),
             self.forward_timeout.to_string().into(),
         );
+        properties.insert(
+            "controllerAddr".into(),
+            self.controller_addr.clone().unwrap_or_default(),
+        );
+        properties.insert(
+            "syncBrokerMetadataPeriod".into(),
+            self.sync_broker_metadata_period.to_string().into(),
+        );
+        properties.insert(
+            "checkSyncStateSetPeriod".into(),
+            self.check_sync_state_set_period.to_string().into(),
+        );
+        properties.insert(
+            "syncControllerMetadataPeriod".into(),
+            self.sync_controller_metadata_period.to_string().into(),
+        );
+        properties.insert(
+            "controllerHeartBeatTimeoutMills".into(),
+            self.controller_heart_beat_timeout_mills.to_string().into(),
+        );
+        properties.insert(
+            "brokerHeartbeatInterval".into(),
+            self.broker_heartbeat_interval.to_string().into(),
+        );
+        properties.insert(
+            "brokerElectionPriority".into(),
+            self.broker_election_priority.to_string().into(),
+        );
+        properties.insert("aclEnable".into(), self.acl_enable.to_string().into());
+        properties.insert(
+            "authenticationEnabled".into(),
+            self.authentication_enabled.to_string().into(),
+        );
+        properties.insert(
+            "authorizationEnabled".into(),
+            self.authorization_enabled.to_string().into(),
+        );
+        properties.insert(
+            "initAuthenticationUser".into(),
+            self.init_authentication_user.clone(),
+        );
+        properties.insert(
+            "metricsExporterType".into(),
+            self.metrics_exporter_type.as_str().into(),
+        );
+        properties.insert(
+            "metricsPromExporterHost".into(),
+            self.metrics_prom_exporter_host.clone(),
+        );
+        properties.insert(
+            "metricsPromExporterPort".into(),
+            self.metrics_prom_exporter_port.to_string().into(),
+        );
+        properties.insert("configBlackList".into(), self.config_black_list.clone());
         properties
     }
+
+    pub fn get_config_blacklist(&self) -> Vec<CheetahString> {
+        self.config_black_list
+            .split(';')
+            .map(|s| CheetahString::from(s.trim()))
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Applies `updateBrokerConfig` properties to this config.
+    ///
+    /// Keys are the camelCase field names, including the fields of the nested config groups
+    /// such as `brokerClusterName` or `defaultTopicQueueNums`. Each value is parsed according to
+    /// the type of the current one. Keys that match no field are returned to the caller.
+    pub fn update(
+        &mut self,
+        properties: &HashMap<CheetahString, CheetahString>,
+    ) -> Result<Vec<CheetahString>, String> {
+        let mut config = serde_json::to_value(&*self).map_err(|e| e.to_string())?;
+        let root = config
+            .as_object_mut()
+            .ok_or_else(|| "broker config is not an object".to_string())?;
+        let mut unknown_keys = Vec::new();
+        for (key, value) in properties {
+            match property_slot(root, key.as_str()) {
+                Some(slot) => *slot = parse_property(key, value, slot)?,
+                None => unknown_keys.push(key.clone()),
+            }
+        }
+        *self = serde_json::from_value(config).map_err(|e| e.to_string())?;
+        Ok(unknown_keys)
+    }
+}
+
+fn property_slot<'a>(
+    root: &'a mut serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Option<&'a mut serde_json::Value> {
+    if root.contains_key(key) {
+        return root.get_mut(key);
+    }
+    root.values_mut()
+        .filter_map(serde_json::Value::as_object_mut)
+        .find_map(|group| group.get_mut(key))
+}
+
+fn parse_property(
+    key: &str,
+    value: &str,
+    current: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    use serde_json::Value;
+
+    let invalid = || format!("Invalid value '{}' for key '{}'", value, key);
+    match current {
+        Value::Bool(_) => value
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| invalid()),
+        Value::Number(number) if number.is_u64() => {
+            value.parse::<u64>().map(Value::from).map_err(|_| invalid())
+        }
+        Value::Number(number) if number.is_i64() => {
+            value.parse::<i64>().map(Value::from).map_err(|_| invalid())
+        }
+        Value::Number(_) => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(invalid),
+        Value::String(_) | Value::Null => Ok(Value::String(value.to_string())),
+        _ => Err(format!("Key '{}' can not be updated", key)),
+    }
 }
 
 pub fn default_broker_name() -> String {
@@ -638,6 +817,18 @@ pub fn default_broker_name() -> String {
         .unwrap_or_else(|| "DEFAULT_BROKER".to_string())
 }
 
+fn default_metrics_prom_exporter_host() -> CheetahString {
+    CheetahString::from_static_str("127.0.0.1")
+}
+
+fn default_metrics_prom_exporter_port() -> u16 {
+    5557
+}
+
+fn default_config_black_list() -> CheetahString {
+    CheetahString::from_static_str("configBlackList;brokerConfigPath")
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TopicQueueConfig {
@@ -657,3 +848,61 @@ impl Default for TopicQueueConfig {
 pub struct TimerWheelConfig {
     pub timer_wheel_enable: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(entries: &[(&str, &str)]) -> HashMap<CheetahString, CheetahString> {
+        entries
+            .iter()
+            .map(|(key, value)| (CheetahString::from(*key), CheetahString::from(*value)))
+            .collect()
+    }
+
+    #[test]
+    fn update_sets_top_level_and_nested_fields() {
+        let mut config = BrokerConfig::default();
+        let unknown = config
+            .update(&properties(&[
+                ("brokerPermission", "4"),
+                ("autoCreateTopicEnable", "false"),
+                ("brokerClusterName", "TestCluster"),
+                ("defaultTopicQueueNums", "16"),
+                ("noSuchKey", "1"),
+            ]))
+            .unwrap();
+        assert_eq!(config.broker_permission, 4);
+        assert!(!config.auto_create_topic_enable);
+        assert_eq!(
+            config.broker_identity.broker_cluster_name.as_str(),
+            "TestCluster"
+        );
+        assert_eq!(config.topic_queue_config.default_topic_queue_nums, 16);
+        assert_eq!(unknown, vec![CheetahString::from_static_str("noSuchKey")]);
+    }
+
+    #[test]
+    fn update_rejects_invalid_values() {
+        let mut config = BrokerConfig::default();
+        assert!(config
+            .update(&properties(&[("brokerPermission", "rw")]))
+            .is_err());
+        assert_eq!(
+            config.broker_permission,
+            BrokerConfig::default().broker_permission
+        );
+    }
+
+    #[test]
+    fn config_blacklist_is_split() {
+        let config = BrokerConfig::default();
+        assert_eq!(
+            config.get_config_blacklist(),
+            vec![
+                CheetahString::from_static_str("configBlackList"),
+                CheetahString::from_static_str("brokerConfigPath"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,197 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::env;
+
+use cheetah_string::CheetahString;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::common::mix_all::ROCKETMQ_HOME_ENV;
+use crate::common::mix_all::ROCKETMQ_HOME_PROPERTY;
+
+/// Configuration of the controller, which elects masters for brokers running in controller
+/// mode. The controller is either embedded in the name server or started standalone.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ControllerConfig {
+    #[serde(alias = "rocketmqHome")]
+    pub rocketmq_home: String,
+
+    #[serde(alias = "configStorePath")]
+    pub config_store_path: String,
+
+    /// Directory in which the replicas metadata is persisted.
+    #[serde(alias = "controllerStorePath")]
+    pub controller_store_path: String,
+
+    /// Interval to scan for brokers whose heartbeat timed out.
+    #[serde(alias = "scanNotActiveBrokerInterval")]
+    pub scan_not_active_broker_interval: u64,
+
+    /// Whether a replica which is not in the sync state set may be elected as master.
+    #[serde(alias = "enableElectUncleanMaster")]
+    pub enable_elect_unclean_master: bool,
+
+    /// A slave whose max offset lags behind the master by more than this many bytes is
+    /// removed from the sync state set.
+    #[serde(alias = "haMaxGapNotInSync")]
+    pub ha_max_gap_not_in_sync: i64,
+
+    #[serde(alias = "configBlackList")]
+    pub config_black_list: String,
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        let rocketmq_home = env::var(ROCKETMQ_HOME_PROPERTY)
+            .unwrap_or_else(|_| env::var(ROCKETMQ_HOME_ENV).unwrap_or_default());
+        let home = dirs::home_dir().unwrap();
+        let controller_store_path = format!(
+            "{}{}{}",
+            home.to_str().unwrap(),
+            std::path::MAIN_SEPARATOR,
+            "rocketmq-controller"
+        );
+        let config_store_path = format!(
+            "{}{}{}",
+            controller_store_path,
+            std::path::MAIN_SEPARATOR,
+            "rocketmq-controller.properties"
+        );
+        ControllerConfig {
+            rocketmq_home,
+            config_store_path,
+            controller_store_path,
+            scan_not_active_broker_interval: 5 * 1000,
+            enable_elect_unclean_master: false,
+            ha_max_gap_not_in_sync: 1024 * 1024 * 256,
+            config_black_list: "configBlackList;configStorePath;controllerStorePath".to_string(),
+        }
+    }
+}
+
+impl ControllerConfig {
+    pub fn new() -> ControllerConfig {
+        Self::default()
+    }
+
+    pub fn get_all_configs_format_string(&self) -> Result<String, String> {
+        let mut json_map = HashMap::new();
+        json_map.insert(
+            "rocketmqHome".to_string(),
+            Value::String(self.rocketmq_home.clone()),
+        );
+        json_map.insert(
+            "configStorePath".to_string(),
+            Value::String(self.config_store_path.clone()),
+        );
+        json_map.insert(
+            "controllerStorePath".to_string(),
+            Value::String(self.controller_store_path.clone()),
+        );
+        json_map.insert(
+            "scanNotActiveBrokerInterval".to_string(),
+            Value::Number(self.scan_not_active_broker_interval.into()),
+        );
+        json_map.insert(
+            "enableElectUncleanMaster".to_string(),
+            Value::Bool(self.enable_elect_unclean_master),
+        );
+        json_map.insert(
+            "haMaxGapNotInSync".to_string(),
+            Value::Number(self.ha_max_gap_not_in_sync.into()),
+        );
+        json_map.insert(
+            "configBlackList".to_string(),
+            Value::String(self.config_black_list.clone()),
+        );
+        serde_json::to_string_pretty(&json_map)
+            .map_err(|err| format!("Failed to serialize ControllerConfig: {}", err))
+    }
+
+    /// Splits the `config_black_list` into a `Vec<CheetahString>` for easier usage.
+    pub fn get_config_blacklist(&self) -> Vec<CheetahString> {
+        self.config_black_list
+            .split(';')
+            .map(|s| CheetahString::from(s.trim()))
+            .collect()
+    }
+
+    pub fn update(
+        &mut self,
+        properties: HashMap<CheetahString, CheetahString>,
+    ) -> Result<(), String> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "rocketmqHome" => self.rocketmq_home = value.to_string(),
+                "configStorePath" => self.config_store_path = value.to_string(),
+                "controllerStorePath" => self.controller_store_path = value.to_string(),
+                "scanNotActiveBrokerInterval" => {
+                    self.scan_not_active_broker_interval = value
+                        .parse()
+                        .map_err(|_| format!("Invalid integer value for key '{}'", key))?
+                }
+                "enableElectUncleanMaster" => {
+                    self.enable_elect_unclean_master = value
+                        .parse()
+                        .map_err(|_| format!("Invalid boolean value for key '{}'", key))?
+                }
+                "haMaxGapNotInSync" => {
+                    self.ha_max_gap_not_in_sync = value
+                        .parse()
+                        .map_err(|_| format!("Invalid integer value for key '{}'", key))?
+                }
+                "configBlackList" => self.config_black_list = value.to_string(),
+                _ => {
+                    return Err(format!("Unknown configuration key: '{}'", key));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_controller_config() {
+        let mut config = ControllerConfig::new();
+        assert!(!config.enable_elect_unclean_master);
+        let mut properties = HashMap::new();
+        properties.insert(
+            CheetahString::from_static_str("enableElectUncleanMaster"),
+            CheetahString::from_static_str("true"),
+        );
+        properties.insert(
+            CheetahString::from_static_str("scanNotActiveBrokerInterval"),
+            CheetahString::from_static_str("1000"),
+        );
+        config.update(properties).unwrap();
+        assert!(config.enable_elect_unclean_master);
+        assert_eq!(config.scan_not_active_broker_interval, 1000);
+
+        let mut unknown = HashMap::new();
+        unknown.insert(
+            CheetahString::from_static_str("unknownKey"),
+            CheetahString::from_static_str("1"),
+        );
+        assert!(config.update(unknown).is_err());
+    }
+}
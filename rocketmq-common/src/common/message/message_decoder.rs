@@ -227,29 +227,22 @@ pub fn decode(
     msg_ext.set_born_timestamp(born_time_stamp);
 
     // 10 BORNHOST
-    let (born_host_address, born_host_ip_length) =
-        if sys_flag & MessageSysFlag::BORNHOST_V6_FLAG != 0 {
-            let mut born_host = [0; 16];
-            byte_buffer.copy_to_slice(&mut born_host);
-            let port = byte_buffer.get_i32();
-            (
-                SocketAddr::V6(SocketAddrV6::new(
-                    Ipv6Addr::from(born_host),
-                    port as u16,
-                    0,
-                    0,
-                )),
-                16,
-            )
-        } else {
-            let mut born_host = [0; 4];
-            byte_buffer.copy_to_slice(&mut born_host);
-            let port = byte_buffer.get_i32();
-            (
-                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(born_host), port as u16)),
-                4,
-            )
-        };
+    let born_host_address = if sys_flag & MessageSysFlag::BORNHOST_V6_FLAG != 0 {
+        let mut born_host = [0; 16];
+        byte_buffer.copy_to_slice(&mut born_host);
+        let port = byte_buffer.get_i32();
+        SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::from(born_host),
+            port as u16,
+            0,
+            0,
+        ))
+    } else {
+        let mut born_host = [0; 4];
+        byte_buffer.copy_to_slice(&mut born_host);
+        let port = byte_buffer.get_i32();
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(born_host), port as u16))
+    };
     msg_ext.set_born_host(born_host_address);
 
     // 11 STORETIMESTAMP
@@ -257,29 +250,22 @@ pub fn decode(
     msg_ext.set_store_timestamp(store_timestamp);
 
     // 12 STOREHOST
-    let (store_host_address, store_host_ip_length) =
-        if sys_flag & MessageSysFlag::STOREHOSTADDRESS_V6_FLAG != 0 {
-            let mut store_host = [0; 16];
-            byte_buffer.copy_to_slice(&mut store_host);
-            let port = byte_buffer.get_i32();
-            (
-                SocketAddr::V6(SocketAddrV6::new(
-                    Ipv6Addr::from(store_host),
-                    port as u16,
-                    0,
-                    0,
-                )),
-                16,
-            )
-        } else {
-            let mut store_host = [0; 4];
-            byte_buffer.copy_to_slice(&mut store_host);
-            let port = byte_buffer.get_i32();
-            (
-                SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(store_host), port as u16)),
-                4,
-            )
-        };
+    let store_host_address = if sys_flag & MessageSysFlag::STOREHOSTADDRESS_V6_FLAG != 0 {
+        let mut store_host = [0; 16];
+        byte_buffer.copy_to_slice(&mut store_host);
+        let port = byte_buffer.get_i32();
+        SocketAddr::V6(SocketAddrV6::new(
+            Ipv6Addr::from(store_host),
+            port as u16,
+            0,
+            0,
+        ))
+    } else {
+        let mut store_host = [0; 4];
+        byte_buffer.copy_to_slice(&mut store_host);
+        let port = byte_buffer.get_i32();
+        SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::from(store_host), port as u16))
+    };
     msg_ext.set_store_host(store_host_address);
 
     // 13 RECONSUMETIMES
@@ -314,18 +300,7 @@ pub fn decode(
             }
             msg_ext.message.body = Some(body_bytes);
         } else {
-            let _ = byte_buffer.split_to(
-                BORN_TIMESTAMP_POSITION
-                    + born_host_ip_length
-                    + 4
-                    + 8
-                    + store_host_ip_length
-                    + 4
-                    + 4
-                    + 8
-                    + 4
-                    + body_len as usize,
-            );
+            byte_buffer.advance(body_len as usize);
         }
     }
 
@@ -955,6 +930,35 @@ mod tests {
         assert_eq!(message_id.offset, 860316681131967304);
     }
 
+    #[test]
+    fn decode_without_body_skips_only_the_body() {
+        let mut message_ext = MessageExt::default();
+        message_ext.set_topic(CheetahString::from_static_str("TopicTest"));
+        message_ext.set_body(Bytes::from("Hello, World!"));
+        message_ext.set_born_host("127.0.0.1:10911".parse().unwrap());
+        message_ext.set_store_host("127.0.0.1:10911".parse().unwrap());
+        message_ext.put_property(
+            CheetahString::from_static_str("KEYS"),
+            CheetahString::from_static_str("key-1"),
+        );
+        // encode always writes a two byte topic length, which is the V2 layout
+        let mut frame = BytesMut::from(encode(&message_ext, false).unwrap().as_ref());
+        frame[MESSAGE_MAGIC_CODE_POSITION..MESSAGE_MAGIC_CODE_POSITION + 4]
+            .copy_from_slice(&crate::common::message::MESSAGE_MAGIC_CODE_V2.to_be_bytes());
+        let mut bytes = frame.freeze();
+
+        let decoded = decode(&mut bytes, false, false, false, false, false).unwrap();
+        assert!(decoded.get_body().is_none());
+        assert_eq!(decoded.get_topic().as_str(), "TopicTest");
+        assert_eq!(
+            decoded
+                .get_property(&CheetahString::from_static_str("KEYS"))
+                .as_deref(),
+            Some("key-1")
+        );
+        assert!(bytes.is_empty());
+    }
+
     #[test]
     fn encode_with_compression() {
         let mut message_ext = MessageExt::default();
@@ -0,0 +1,39 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+pub mod metrics_exporter_type;
+
+#[cfg(feature = "metrics")]
+pub mod instruments;
+#[cfg(feature = "metrics")]
+pub mod metric_registry;
+#[cfg(feature = "metrics")]
+pub mod prometheus_exporter;
+#[cfg(feature = "metrics")]
+pub mod text_encoder;
+
+/// Label keys shared by the broker, name server and client metrics.
+pub struct MetricsLabels;
+
+impl MetricsLabels {
+    pub const CLUSTER_NAME: &'static str = "cluster";
+    pub const CONSUMER_GROUP: &'static str = "consumer_group";
+    pub const NODE_ID: &'static str = "node_id";
+    pub const NODE_TYPE: &'static str = "node_type";
+    pub const QUEUE_ID: &'static str = "queue_id";
+    pub const CLIENT_ID: &'static str = "client_id";
+    pub const TOPIC: &'static str = "topic";
+}
@@ -0,0 +1,267 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+
+use crate::common::metrics::metric_registry::MetricsCollector;
+use crate::common::metrics::text_encoder::format_value;
+use crate::common::metrics::text_encoder::MetricType;
+use crate::common::metrics::text_encoder::TextEncoder;
+
+/// Latency buckets in milliseconds, matching the Java client's send/process time views.
+pub const LATENCY_MILLIS_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 20.0, 50.0, 200.0, 500.0, 1000.0];
+
+/// Message size buckets in bytes, matching the Java broker's message size view.
+pub const MESSAGE_SIZE_BUCKETS: &[f64] = &[
+    1024.0,
+    4.0 * 1024.0,
+    16.0 * 1024.0,
+    64.0 * 1024.0,
+    256.0 * 1024.0,
+    1024.0 * 1024.0,
+    4.0 * 1024.0 * 1024.0,
+];
+
+struct MetricDesc {
+    name: String,
+    help: String,
+    label_names: Vec<&'static str>,
+}
+
+impl MetricDesc {
+    fn new(name: &str, help: &str, label_names: &[&'static str]) -> Self {
+        Self {
+            name: name.to_string(),
+            help: help.to_string(),
+            label_names: label_names.to_vec(),
+        }
+    }
+
+    fn key(&self, label_values: &[&str]) -> Vec<String> {
+        debug_assert_eq!(
+            self.label_names.len(),
+            label_values.len(),
+            "label values do not match the label names of {}",
+            self.name
+        );
+        label_values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn labels<'a>(&'a self, label_values: &'a [String]) -> Vec<(&'a str, &'a str)> {
+        self.label_names
+            .iter()
+            .copied()
+            .zip(label_values.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// A monotonically increasing counter, partitioned by label values.
+pub struct CounterVec {
+    desc: MetricDesc,
+    values: DashMap<Vec<String>, AtomicU64>,
+}
+
+impl CounterVec {
+    pub fn new(name: &str, help: &str, label_names: &[&'static str]) -> Self {
+        Self {
+            desc: MetricDesc::new(name, help, label_names),
+            values: DashMap::new(),
+        }
+    }
+
+    pub fn inc(&self, label_values: &[&str]) {
+        self.inc_by(label_values, 1);
+    }
+
+    pub fn inc_by(&self, label_values: &[&str], value: u64) {
+        self.values
+            .entry(self.desc.key(label_values))
+            .or_default()
+            .fetch_add(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self, label_values: &[&str]) -> u64 {
+        self.values
+            .get(&self.desc.key(label_values))
+            .map_or(0, |value| value.load(Ordering::Relaxed))
+    }
+}
+
+impl MetricsCollector for CounterVec {
+    fn collect(&self, encoder: &mut TextEncoder) {
+        encoder.write_family(&self.desc.name, &self.desc.help, MetricType::Counter);
+        for entry in self.values.iter() {
+            encoder.write_sample(
+                &self.desc.name,
+                &self.desc.labels(entry.key()),
+                entry.value().load(Ordering::Relaxed) as f64,
+            );
+        }
+    }
+}
+
+/// A value that can go up and down, partitioned by label values.
+pub struct GaugeVec {
+    desc: MetricDesc,
+    values: DashMap<Vec<String>, AtomicU64>,
+}
+
+impl GaugeVec {
+    pub fn new(name: &str, help: &str, label_names: &[&'static str]) -> Self {
+        Self {
+            desc: MetricDesc::new(name, help, label_names),
+            values: DashMap::new(),
+        }
+    }
+
+    pub fn set(&self, label_values: &[&str], value: f64) {
+        self.values
+            .entry(self.desc.key(label_values))
+            .or_default()
+            .store(value.to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn get(&self, label_values: &[&str]) -> f64 {
+        self.values
+            .get(&self.desc.key(label_values))
+            .map_or(0.0, |value| f64::from_bits(value.load(Ordering::Relaxed)))
+    }
+
+    pub fn remove(&self, label_values: &[&str]) {
+        self.values.remove(&self.desc.key(label_values));
+    }
+}
+
+impl MetricsCollector for GaugeVec {
+    fn collect(&self, encoder: &mut TextEncoder) {
+        encoder.write_family(&self.desc.name, &self.desc.help, MetricType::Gauge);
+        for entry in self.values.iter() {
+            encoder.write_sample(
+                &self.desc.name,
+                &self.desc.labels(entry.key()),
+                f64::from_bits(entry.value().load(Ordering::Relaxed)),
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct HistogramState {
+    bucket_counts: Vec<u64>,
+    count: u64,
+    sum: f64,
+}
+
+/// A histogram with cumulative buckets, partitioned by label values.
+pub struct HistogramVec {
+    desc: MetricDesc,
+    buckets: Vec<f64>,
+    values: DashMap<Vec<String>, Mutex<HistogramState>>,
+}
+
+impl HistogramVec {
+    pub fn new(name: &str, help: &str, label_names: &[&'static str], buckets: &[f64]) -> Self {
+        Self {
+            desc: MetricDesc::new(name, help, label_names),
+            buckets: buckets.to_vec(),
+            values: DashMap::new(),
+        }
+    }
+
+    pub fn observe(&self, label_values: &[&str], value: f64) {
+        let entry = self
+            .values
+            .entry(self.desc.key(label_values))
+            .or_insert_with(|| {
+                Mutex::new(HistogramState {
+                    bucket_counts: vec![0; self.buckets.len()],
+                    ..Default::default()
+                })
+            });
+        let mut state = entry.lock();
+        if let Some(index) = self.buckets.iter().position(|bound| value <= *bound) {
+            state.bucket_counts[index] += 1;
+        }
+        state.count += 1;
+        state.sum += value;
+    }
+}
+
+impl MetricsCollector for HistogramVec {
+    fn collect(&self, encoder: &mut TextEncoder) {
+        encoder.write_family(&self.desc.name, &self.desc.help, MetricType::Histogram);
+        let bucket_name = format!("{}_bucket", self.desc.name);
+        let sum_name = format!("{}_sum", self.desc.name);
+        let count_name = format!("{}_count", self.desc.name);
+        let bounds: Vec<String> = self
+            .buckets
+            .iter()
+            .map(|bound| format_value(*bound))
+            .collect();
+        for entry in self.values.iter() {
+            let state = entry.value().lock();
+            let mut labels = self.desc.labels(entry.key());
+            let mut cumulative = 0;
+            for (bound, bucket_count) in bounds.iter().zip(state.bucket_counts.iter()) {
+                cumulative += bucket_count;
+                labels.push(("le", bound.as_str()));
+                encoder.write_sample(&bucket_name, &labels, cumulative as f64);
+                labels.pop();
+            }
+            labels.push(("le", "+Inf"));
+            encoder.write_sample(&bucket_name, &labels, state.count as f64);
+            labels.pop();
+            encoder.write_sample(&sum_name, &labels, state.sum);
+            encoder.write_sample(&count_name, &labels, state.count as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_accumulates_per_label_set() {
+        let counter = CounterVec::new("rocketmq_messages_in_total", "Messages in.", &["topic"]);
+        counter.inc_by(&["TopicA"], 3);
+        counter.inc(&["TopicA"]);
+        counter.inc(&["TopicB"]);
+        assert_eq!(counter.get(&["TopicA"]), 4);
+        assert_eq!(counter.get(&["TopicB"]), 1);
+    }
+
+    #[test]
+    fn histogram_writes_cumulative_buckets() {
+        let histogram = HistogramVec::new("rocketmq_send_cost_time", "Send RT.", &[], &[1.0, 5.0]);
+        histogram.observe(&[], 0.5);
+        histogram.observe(&[], 3.0);
+        histogram.observe(&[], 9.0);
+        let mut encoder = TextEncoder::new();
+        histogram.collect(&mut encoder);
+        let text = encoder.finish();
+        assert!(text.contains("rocketmq_send_cost_time_bucket{le=\"1\"} 1\n"));
+        assert!(text.contains("rocketmq_send_cost_time_bucket{le=\"5\"} 2\n"));
+        assert!(text.contains("rocketmq_send_cost_time_bucket{le=\"+Inf\"} 3\n"));
+        assert!(text.contains("rocketmq_send_cost_time_sum 12.5\n"));
+        assert!(text.contains("rocketmq_send_cost_time_count 3\n"));
+    }
+}
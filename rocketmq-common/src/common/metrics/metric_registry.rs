@@ -0,0 +1,112 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use parking_lot::RwLock;
+
+use crate::common::metrics::instruments::CounterVec;
+use crate::common::metrics::instruments::GaugeVec;
+use crate::common::metrics::instruments::HistogramVec;
+use crate::common::metrics::text_encoder::TextEncoder;
+
+/// Something that writes one or more metric families when the registry is scraped.
+///
+/// Instruments record values as they happen, while scrape time collectors read the
+/// current state of existing stats (offsets, stats item sets, queue sizes).
+pub trait MetricsCollector: Send + Sync {
+    fn collect(&self, encoder: &mut TextEncoder);
+}
+
+impl<F> MetricsCollector for F
+where
+    F: Fn(&mut TextEncoder) + Send + Sync,
+{
+    fn collect(&self, encoder: &mut TextEncoder) {
+        self(encoder)
+    }
+}
+
+#[derive(Default)]
+pub struct MetricRegistry {
+    collectors: RwLock<Vec<Arc<dyn MetricsCollector>>>,
+}
+
+impl MetricRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&self, collector: Arc<dyn MetricsCollector>) {
+        self.collectors.write().push(collector);
+    }
+
+    pub fn counter_vec(
+        &self,
+        name: &str,
+        help: &str,
+        label_names: &[&'static str],
+    ) -> Arc<CounterVec> {
+        let counter = Arc::new(CounterVec::new(name, help, label_names));
+        self.register(counter.clone());
+        counter
+    }
+
+    pub fn gauge_vec(&self, name: &str, help: &str, label_names: &[&'static str]) -> Arc<GaugeVec> {
+        let gauge = Arc::new(GaugeVec::new(name, help, label_names));
+        self.register(gauge.clone());
+        gauge
+    }
+
+    pub fn histogram_vec(
+        &self,
+        name: &str,
+        help: &str,
+        label_names: &[&'static str],
+        buckets: &[f64],
+    ) -> Arc<HistogramVec> {
+        let histogram = Arc::new(HistogramVec::new(name, help, label_names, buckets));
+        self.register(histogram.clone());
+        histogram
+    }
+
+    /// Renders every registered collector in the Prometheus text format.
+    pub fn gather(&self) -> String {
+        let mut encoder = TextEncoder::new();
+        for collector in self.collectors.read().iter() {
+            collector.collect(&mut encoder);
+        }
+        encoder.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gather_includes_instruments_and_collectors() {
+        let registry = MetricRegistry::new();
+        let counter = registry.counter_vec("rocketmq_route_queries_total", "Queries.", &[]);
+        counter.inc(&[]);
+        registry.register(Arc::new(|encoder: &mut TextEncoder| {
+            encoder.write_gauge("rocketmq_topic_number", "Topic count.", &[], 2.0);
+        }));
+        let text = registry.gather();
+        assert!(text.contains("rocketmq_route_queries_total 1\n"));
+        assert!(text.contains("rocketmq_topic_number 2\n"));
+    }
+}
@@ -0,0 +1,70 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::str::FromStr;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How a server or client exposes its metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MetricsExporterType {
+    #[default]
+    Disable,
+    /// Serve the Prometheus text exposition format on a local `/metrics` endpoint.
+    Prom,
+}
+
+impl MetricsExporterType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricsExporterType::Disable => "DISABLE",
+            MetricsExporterType::Prom => "PROM",
+        }
+    }
+
+    #[inline]
+    pub fn is_enable(&self) -> bool {
+        *self != MetricsExporterType::Disable
+    }
+}
+
+impl FromStr for MetricsExporterType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "DISABLE" => Ok(MetricsExporterType::Disable),
+            "PROM" => Ok(MetricsExporterType::Prom),
+            _ => Err(format!("Unknown metrics exporter type: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exporter_type_uses_java_names() {
+        let json = serde_json::to_string(&MetricsExporterType::Prom).unwrap();
+        assert_eq!(json, r#""PROM""#);
+        let decoded: MetricsExporterType = serde_json::from_str(r#""DISABLE""#).unwrap();
+        assert!(!decoded.is_enable());
+        assert_eq!("prom".parse(), Ok(MetricsExporterType::Prom));
+    }
+}
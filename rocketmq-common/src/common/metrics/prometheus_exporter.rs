@@ -0,0 +1,179 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tracing::info;
+use tracing::warn;
+
+use crate::common::metrics::metric_registry::MetricRegistry;
+use crate::common::metrics::text_encoder::TextEncoder;
+
+const MAX_REQUEST_HEAD_SIZE: usize = 8 * 1024;
+
+/// Serves a [`MetricRegistry`] on `GET /metrics` for Prometheus to scrape.
+pub struct PrometheusExporter {
+    local_addr: SocketAddr,
+    shutdown: Arc<Notify>,
+}
+
+impl PrometheusExporter {
+    pub const METRICS_PATH: &'static str = "/metrics";
+
+    /// Binds `host:port` and starts serving in the background. An empty host binds the
+    /// loopback interface and port `0` picks a free port.
+    pub async fn start(
+        host: &str,
+        port: u16,
+        registry: Arc<MetricRegistry>,
+    ) -> io::Result<PrometheusExporter> {
+        let host = if host.is_empty() { "127.0.0.1" } else { host };
+        let listener = TcpListener::bind((host, port)).await?;
+        let local_addr = listener.local_addr()?;
+        let shutdown = Arc::new(Notify::new());
+        let shutdown_signal = shutdown.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => {
+                            let registry = registry.clone();
+                            tokio::spawn(async move {
+                                if let Err(e) = serve(stream, &registry).await {
+                                    warn!("Serve metrics request failed: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => warn!("Accept metrics connection failed: {}", e),
+                    },
+                    _ = shutdown_signal.notified() => break,
+                }
+            }
+        });
+        info!(
+            "Prometheus exporter started, listening on {}{}",
+            local_addr,
+            Self::METRICS_PATH
+        );
+        Ok(PrometheusExporter {
+            local_addr,
+            shutdown,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn shutdown(&self) {
+        self.shutdown.notify_one();
+    }
+}
+
+async fn serve(mut stream: TcpStream, registry: &MetricRegistry) -> io::Result<()> {
+    let mut head = Vec::with_capacity(1024);
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 || head.len() + read > MAX_REQUEST_HEAD_SIZE {
+            break;
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+
+    let request_line = head
+        .split(|byte| *byte == b'\r' || *byte == b'\n')
+        .next()
+        .map(String::from_utf8_lossy)
+        .unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts
+        .next()
+        .unwrap_or_default()
+        .split('?')
+        .next()
+        .unwrap_or_default();
+
+    let (status, content_type, body) =
+        if method == "GET" && path == PrometheusExporter::METRICS_PATH {
+            ("200 OK", TextEncoder::CONTENT_TYPE, registry.gather())
+        } else {
+            (
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                "Not Found\n".to_string(),
+            )
+        };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn http_get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn serves_registry_on_metrics_path() {
+        let registry = Arc::new(MetricRegistry::new());
+        registry
+            .counter_vec("rocketmq_messages_in_total", "Messages in.", &["topic"])
+            .inc(&["TopicA"]);
+        let exporter = PrometheusExporter::start("127.0.0.1", 0, registry)
+            .await
+            .unwrap();
+
+        let response = http_get(exporter.local_addr(), "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("rocketmq_messages_in_total{topic=\"TopicA\"} 1\n"));
+
+        let response = http_get(exporter.local_addr(), "/other").await;
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+        exporter.shutdown();
+    }
+
+    #[tokio::test]
+    async fn empty_host_binds_loopback() {
+        let exporter = PrometheusExporter::start("", 0, Arc::new(MetricRegistry::new()))
+            .await
+            .unwrap();
+        assert!(exporter.local_addr().ip().is_loopback());
+        exporter.shutdown();
+    }
+}
@@ -0,0 +1,120 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt::Write;
+
+/// The kind of a metric family, as written in the `# TYPE` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+            MetricType::Histogram => "histogram",
+        }
+    }
+}
+
+/// Writes metric families in the Prometheus text exposition format (version 0.0.4).
+#[derive(Debug, Default)]
+pub struct TextEncoder {
+    buf: String,
+}
+
+impl TextEncoder {
+    pub const CONTENT_TYPE: &'static str = "text/plain; version=0.0.4; charset=utf-8";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_family(&mut self, name: &str, help: &str, metric_type: MetricType) {
+        let _ = writeln!(self.buf, "# HELP {} {}", name, escape_help(help));
+        let _ = writeln!(self.buf, "# TYPE {} {}", name, metric_type.as_str());
+    }
+
+    pub fn write_sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.buf.push_str(name);
+        if !labels.is_empty() {
+            self.buf.push('{');
+            for (index, (key, value)) in labels.iter().enumerate() {
+                if index > 0 {
+                    self.buf.push(',');
+                }
+                let _ = write!(self.buf, "{}=\"{}\"", key, escape_label_value(value));
+            }
+            self.buf.push('}');
+        }
+        self.buf.push(' ');
+        self.buf.push_str(&format_value(value));
+        self.buf.push('\n');
+    }
+
+    /// Writes a family with a single sample, the common shape of scrape time gauges.
+    pub fn write_gauge(&mut self, name: &str, help: &str, labels: &[(&str, &str)], value: f64) {
+        self.write_family(name, help, MetricType::Gauge);
+        self.write_sample(name, labels, value);
+    }
+
+    pub fn finish(self) -> String {
+        self.buf
+    }
+}
+
+pub(crate) fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_family_and_escaped_labels() {
+        let mut encoder = TextEncoder::new();
+        encoder.write_family("rocketmq_topic_number", "Topic count.", MetricType::Gauge);
+        encoder.write_sample("rocketmq_topic_number", &[("cluster", "a\"b")], 3.0);
+        encoder.write_sample("rocketmq_up", &[], f64::INFINITY);
+        assert_eq!(
+            encoder.finish(),
+            "# HELP rocketmq_topic_number Topic count.\n# TYPE rocketmq_topic_number \
+             gauge\nrocketmq_topic_number{cluster=\"a\\\"b\"} 3\nrocketmq_up +Inf\n"
+        );
+    }
+}
@@ -22,6 +22,7 @@ use cheetah_string::CheetahString;
 use serde::Deserialize;
 use serde_json::Value;
 
+use crate::common::metrics::metrics_exporter_type::MetricsExporterType;
 use crate::common::mix_all::ROCKETMQ_HOME_ENV;
 use crate::common::mix_all::ROCKETMQ_HOME_PROPERTY;
 
@@ -92,6 +93,33 @@ pub struct NamesrvConfig {
 
     #[serde(alias = "configBlackList")]
     pub config_black_list: String,
+
+    #[serde(alias = "aclEnable", default)]
+    pub acl_enable: bool,
+
+    #[serde(alias = "metricsExporterType", default)]
+    pub metrics_exporter_type: MetricsExporterType,
+
+    /// Host the Prometheus exporter binds, loopback unless configured otherwise.
+    #[serde(
+        alias = "metricsPromExporterHost",
+        default = "default_metrics_prom_exporter_host"
+    )]
+    pub metrics_prom_exporter_host: String,
+
+    #[serde(
+        alias = "metricsPromExporterPort",
+        default = "default_metrics_prom_exporter_port"
+    )]
+    pub metrics_prom_exporter_port: u16,
+}
+
+fn default_metrics_prom_exporter_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_metrics_prom_exporter_port() -> u16 {
+    5558
 }
 
 impl Default for NamesrvConfig {
@@ -139,6 +167,10 @@ impl Default for NamesrvConfig {
             wait_seconds_for_service: 45,
             delete_topic_with_broker_registration: false,
             config_black_list: "configBlackList;configStorePath;kvConfigPath".to_string(),
+            acl_enable: false,
+            metrics_exporter_type: MetricsExporterType::Disable,
+            metrics_prom_exporter_host: default_metrics_prom_exporter_host(),
+            metrics_prom_exporter_port: default_metrics_prom_exporter_port(),
         }
     }
 }
@@ -236,6 +268,19 @@ impl NamesrvConfig {
             "configBlackList".to_string(),
             Value::String(self.config_black_list.clone()),
         );
+        json_map.insert("aclEnable".to_string(), Value::Bool(self.acl_enable));
+        json_map.insert(
+            "metricsExporterType".to_string(),
+            Value::String(self.metrics_exporter_type.as_str().to_string()),
+        );
+        json_map.insert(
+            "metricsPromExporterHost".to_string(),
+            Value::String(self.metrics_prom_exporter_host.clone()),
+        );
+        json_map.insert(
+            "metricsPromExporterPort".to_string(),
+            Value::Number(self.metrics_prom_exporter_port.into()),
+        );
 
         // Convert the HashMap to a JSON value
         match serde_json::to_string_pretty(&json_map) {
@@ -312,6 +357,18 @@ impl NamesrvConfig {
                         .parse()
                         .map_err(|_| format!("Invalid boolean value for key '{}'", key))?
                 }
+                "aclEnable" => {
+                    self.acl_enable = value
+                        .parse()
+                        .map_err(|_| format!("Invalid boolean value for key '{}'", key))?
+                }
+                "metricsExporterType" => self.metrics_exporter_type = value.parse()?,
+                "metricsPromExporterHost" => self.metrics_prom_exporter_host = value.to_string(),
+                "metricsPromExporterPort" => {
+                    self.metrics_prom_exporter_port = value
+                        .parse()
+                        .map_err(|_| format!("Invalid integer value for key '{}'", key))?
+                }
                 "enableTopicList" => {
                     self.enable_topic_list = value
                         .parse()
@@ -362,6 +419,7 @@ mod tests {
     use std::env;
 
     use super::*;
+    use crate::common::metrics::metrics_exporter_type::MetricsExporterType;
     use crate::common::mix_all::ROCKETMQ_HOME_ENV;
     use crate::common::mix_all::ROCKETMQ_HOME_PROPERTY;
 
@@ -407,6 +465,7 @@ mod tests {
         assert_eq!(config.enable_topic_list, true);
         assert_eq!(config.notify_min_broker_id_changed, false);
         assert_eq!(config.enable_controller_in_namesrv, false);
+        assert!(!config.acl_enable);
         assert_eq!(config.need_wait_for_service, false);
         assert_eq!(config.wait_seconds_for_service, 45);
         assert_eq!(config.delete_topic_with_broker_registration, false);
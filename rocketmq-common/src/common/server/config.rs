@@ -17,11 +17,15 @@
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::common::server::tls_config::TlsConfig;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerConfig {
     pub listen_port: u32,
     pub bind_address: String,
+    #[serde(default = "TlsConfig::from_env")]
+    pub tls_config: TlsConfig,
 }
 
 impl Default for ServerConfig {
@@ -29,6 +33,7 @@ impl Default for ServerConfig {
         ServerConfig {
             listen_port: 10911,
             bind_address: "0.0.0.0".to_string(),
+            tls_config: TlsConfig::from_env(),
         }
     }
 }
@@ -41,4 +46,8 @@ impl ServerConfig {
     pub fn listen_port(&self) -> u32 {
         self.listen_port
     }
+
+    pub fn tls_config(&self) -> &TlsConfig {
+        &self.tls_config
+    }
 }
@@ -0,0 +1,159 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How a server treats the connections it accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    /// Only plaintext connections are accepted.
+    #[default]
+    Disabled,
+    /// Both TLS and plaintext connections are accepted, told apart by their first byte.
+    Permissive,
+    /// Only TLS connections are accepted.
+    Enforcing,
+}
+
+impl TlsMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode.trim().to_ascii_lowercase().as_str() {
+            "disabled" => Some(TlsMode::Disabled),
+            "permissive" => Some(TlsMode::Permissive),
+            "enforcing" => Some(TlsMode::Enforcing),
+            _ => None,
+        }
+    }
+}
+
+/// Whether a TLS server asks its clients for a certificate.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsClientAuth {
+    #[default]
+    None,
+    /// Clients may present a certificate, it is verified when they do.
+    Optional,
+    /// Clients must present a certificate signed by the trusted CA.
+    Require,
+}
+
+impl TlsClientAuth {
+    pub fn parse(client_auth: &str) -> Option<Self> {
+        match client_auth.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(TlsClientAuth::None),
+            "optional" => Some(TlsClientAuth::Optional),
+            "require" => Some(TlsClientAuth::Require),
+            _ => None,
+        }
+    }
+}
+
+/// The TLS settings of the remoting transport. The server side is configured by `tls_mode` and
+/// the `tls_server_*` paths, the client side by `tls_enable` and the `tls_client_*` paths. The
+/// certificates and keys are PEM files.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TlsConfig {
+    pub tls_mode: TlsMode,
+    /// Whether clients connect with TLS.
+    pub tls_enable: bool,
+    /// Clients do not verify the certificate of the server, for test environments only.
+    pub tls_test_mode_enable: bool,
+    pub tls_server_need_client_auth: TlsClientAuth,
+    pub tls_server_cert_path: Option<String>,
+    pub tls_server_key_path: Option<String>,
+    /// The CA the certificates of the clients are verified with.
+    pub tls_server_trust_cert_path: Option<String>,
+    /// The certificate a client presents to servers requiring client auth.
+    pub tls_client_cert_path: Option<String>,
+    pub tls_client_key_path: Option<String>,
+    /// The CA the certificates of the servers are verified with.
+    pub tls_client_trust_cert_path: Option<String>,
+}
+
+impl TlsConfig {
+    /// Reads the settings from the environment variables the Java implementation honours, such
+    /// as `TLS_SERVER_MODE`, `TLS_ENABLE` or `TLS_SERVER_CERTPATH`.
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        let path = |name: &str| lookup(name).filter(|path| !path.trim().is_empty());
+        let flag = |name: &str| lookup(name).is_some_and(|flag| flag.trim() == "true");
+        TlsConfig {
+            tls_mode: lookup("TLS_SERVER_MODE")
+                .and_then(|mode| TlsMode::parse(&mode))
+                .unwrap_or_default(),
+            tls_enable: flag("TLS_ENABLE"),
+            tls_test_mode_enable: flag("TLS_TEST_MODE_ENABLE"),
+            tls_server_need_client_auth: lookup("TLS_SERVER_NEED_CLIENT_AUTH")
+                .and_then(|client_auth| TlsClientAuth::parse(&client_auth))
+                .unwrap_or_default(),
+            tls_server_cert_path: path("TLS_SERVER_CERTPATH"),
+            tls_server_key_path: path("TLS_SERVER_KEYPATH"),
+            tls_server_trust_cert_path: path("TLS_SERVER_TRUSTCERTPATH"),
+            tls_client_cert_path: path("TLS_CLIENT_CERTPATH"),
+            tls_client_key_path: path("TLS_CLIENT_KEYPATH"),
+            tls_client_trust_cert_path: path("TLS_CLIENT_TRUSTCERTPATH"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn tls_config_from_lookup() {
+        let env: HashMap<&str, &str> = [
+            ("TLS_SERVER_MODE", "Enforcing"),
+            ("TLS_ENABLE", "true"),
+            ("TLS_SERVER_NEED_CLIENT_AUTH", "require"),
+            ("TLS_SERVER_CERTPATH", "/etc/rocketmq/server.pem"),
+            ("TLS_SERVER_KEYPATH", " "),
+        ]
+        .into_iter()
+        .collect();
+        let config = TlsConfig::from_lookup(|name| env.get(name).map(|value| value.to_string()));
+        assert_eq!(config.tls_mode, TlsMode::Enforcing);
+        assert!(config.tls_enable);
+        assert!(!config.tls_test_mode_enable);
+        assert_eq!(config.tls_server_need_client_auth, TlsClientAuth::Require);
+        assert_eq!(
+            config.tls_server_cert_path.as_deref(),
+            Some("/etc/rocketmq/server.pem")
+        );
+        assert_eq!(config.tls_server_key_path, None);
+
+        assert_eq!(TlsConfig::from_lookup(|_| None), TlsConfig::default());
+    }
+
+    #[test]
+    fn tls_config_deserializes_with_defaults() {
+        let config: TlsConfig =
+            serde_json::from_str(r#"{"tlsMode":"permissive","tlsServerCertPath":"cert.pem"}"#)
+                .unwrap();
+        assert_eq!(config.tls_mode, TlsMode::Permissive);
+        assert_eq!(config.tls_server_cert_path.as_deref(), Some("cert.pem"));
+        assert_eq!(config.tls_server_need_client_auth, TlsClientAuth::None);
+    }
+}
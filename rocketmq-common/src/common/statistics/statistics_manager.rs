@@ -138,6 +138,15 @@ impl StatisticsManager {
         false
     }
 
+    /// The statistics items of every kind currently tracked.
+    pub fn statistics_items(&self) -> Vec<Arc<StatisticsItem>> {
+        self.stats_table
+            .read()
+            .values()
+            .flat_map(|item_map| item_map.values().cloned())
+            .collect()
+    }
+
     fn schedule_statistics_item(&self, item: Arc<StatisticsItem>) {
         let kind_meta_map = self.kind_meta_map.read();
         if let Some(kind_meta) = kind_meta_map.get(item.stat_kind()) {
@@ -20,12 +20,10 @@ use std::fmt;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
-use std::thread;
-use std::time::Duration;
 use std::time::SystemTime;
 
 use parking_lot::Mutex;
-use tracing::info;
+use tracing::debug;
 
 use crate::common::stats::call_snapshot::CallSnapshot;
 use crate::common::stats::stats_snapshot::StatsSnapshot;
@@ -60,7 +58,12 @@ impl StatsItem {
             let first = cs_list.front().unwrap();
             let last = cs_list.back().unwrap();
             let sum = last.get_value() - first.get_value();
-            let tps = (sum as f64 * 1000.0) / (last.get_timestamp() - first.get_timestamp()) as f64;
+            let elapsed = last.get_timestamp() - first.get_timestamp();
+            let tps = if elapsed > 0 {
+                (sum as f64 * 1000.0) / elapsed as f64
+            } else {
+                0.0
+            };
             let times_diff = last.get_times() - first.get_times();
             let avgpt = if times_diff > 0 {
                 sum as f64 / times_diff as f64
@@ -87,192 +90,99 @@ impl StatsItem {
         Self::compute_stats_data(Arc::clone(&self.cs_list_day))
     }
 
-    pub fn init(&self) {
-        let cs_list_minute = Arc::clone(&self.cs_list_minute);
-        let cs_list_minute_clone = Arc::clone(&self.cs_list_minute);
-        let cs_list_hour = Arc::clone(&self.cs_list_hour);
-        let cs_list_hour_clone = Arc::clone(&self.cs_list_hour);
-        let cs_list_day = Arc::clone(&self.cs_list_day);
-        let cs_list_day_clone = Arc::clone(&self.cs_list_day);
-        let stats_name = self.stats_name.clone();
-        let stats_key = self.stats_key.clone();
-
-        thread::spawn(move || loop {
-            thread::sleep(Duration::from_secs(10));
-            Self::sampling_in_seconds(cs_list_minute.clone());
-        });
-
-        thread::spawn(move || loop {
-            thread::sleep(Duration::from_secs(600));
-            Self::sampling_in_minutes(cs_list_hour.clone());
-        });
-
-        thread::spawn(move || loop {
-            thread::sleep(Duration::from_secs(3600));
-            Self::sampling_in_hour(cs_list_day.clone());
-        });
-
-        let stats_name_clone = stats_name.clone();
-        let stats_key_clone = stats_key.clone();
-        thread::spawn(move || loop {
-            let sleep_time = Self::compute_next_minutes_time_millis()
-                - SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64;
-            thread::sleep(Duration::from_millis(sleep_time));
-            Self::print_at_minutes(
-                &stats_name_clone,
-                &stats_key_clone,
-                cs_list_minute_clone.clone(),
-            );
-        });
-
-        let stats_name_clone = stats_name.clone();
-        let stats_key_clone = stats_key.clone();
-        thread::spawn(move || loop {
-            let sleep_time = Self::compute_next_hour_time_millis()
-                - SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64;
-            thread::sleep(Duration::from_millis(sleep_time));
-            Self::print_at_hour(
-                &stats_name_clone,
-                &stats_key_clone,
-                cs_list_hour_clone.clone(),
-            );
-        });
-
-        thread::spawn(move || loop {
-            let sleep_time = Self::compute_next_morning_time_millis()
-                - SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64
-                - 2000;
-            thread::sleep(Duration::from_millis(sleep_time));
-            Self::print_at_day(&stats_name, &stats_key, cs_list_day_clone.clone());
-        });
+    pub fn add_value(&self, inc_value: u64, inc_times: u64) {
+        self.value.fetch_add(inc_value, Ordering::Relaxed);
+        self.times.fetch_add(inc_times, Ordering::Relaxed);
     }
 
-    pub fn sampling_in_seconds(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
-        let mut cs_list = cs_list.lock();
-        if cs_list.is_empty() {
-            cs_list.push_back(CallSnapshot::new(
-                SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64
-                    - 10 * 1000,
-                0,
-                0,
-            ));
-        }
-        cs_list.push_back(CallSnapshot::new(
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            0,
-            0,
-        ));
-        if cs_list.len() > 7 {
-            cs_list.pop_front();
-        }
+    pub fn get_value(&self) -> &AtomicU64 {
+        &self.value
     }
 
-    pub fn sampling_in_minutes(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
-        let mut cs_list = cs_list.lock();
-        if cs_list.is_empty() {
-            cs_list.push_back(CallSnapshot::new(
-                SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64
-                    - 10 * 60 * 1000,
-                0,
-                0,
-            ));
-        }
-        cs_list.push_back(CallSnapshot::new(
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            0,
-            0,
-        ));
-        if cs_list.len() > 7 {
-            cs_list.pop_front();
-        }
+    pub fn get_times(&self) -> &AtomicU64 {
+        &self.times
     }
 
-    pub fn sampling_in_hour(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
+    pub fn get_stats_name(&self) -> &str {
+        &self.stats_name
+    }
+
+    pub fn get_stats_key(&self) -> &str {
+        &self.stats_key
+    }
+
+    pub fn sample_in_seconds(&self) {
+        self.sample(&self.cs_list_minute, 10 * 1000, 7);
+    }
+
+    pub fn sample_in_minutes(&self) {
+        self.sample(&self.cs_list_hour, 10 * 60 * 1000, 7);
+    }
+
+    pub fn sample_in_hour(&self) {
+        self.sample(&self.cs_list_day, 60 * 60 * 1000, 25);
+    }
+
+    fn sample(
+        &self,
+        cs_list: &Mutex<LinkedList<CallSnapshot>>,
+        interval_millis: u64,
+        max_len: usize,
+    ) {
+        Self::push_snapshot(
+            cs_list,
+            interval_millis,
+            max_len,
+            self.times.load(Ordering::Relaxed),
+            self.value.load(Ordering::Relaxed),
+        );
+    }
+
+    fn push_snapshot(
+        cs_list: &Mutex<LinkedList<CallSnapshot>>,
+        interval_millis: u64,
+        max_len: usize,
+        times: u64,
+        value: u64,
+    ) {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
         let mut cs_list = cs_list.lock();
         if cs_list.is_empty() {
-            cs_list.push_back(CallSnapshot::new(
-                SystemTime::now()
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .unwrap()
-                    .as_millis() as u64
-                    - 60 * 60 * 1000,
-                0,
-                0,
-            ));
+            cs_list.push_back(CallSnapshot::new(now - interval_millis, 0, 0));
         }
-        cs_list.push_back(CallSnapshot::new(
-            SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .unwrap()
-                .as_millis() as u64,
-            0,
-            0,
-        ));
-        if cs_list.len() > 25 {
+        cs_list.push_back(CallSnapshot::new(now, times, value));
+        if cs_list.len() > max_len {
             cs_list.pop_front();
         }
     }
 
-    pub fn print_at_minutes(
-        stats_name: &str,
-        stats_key: &str,
-        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
-    ) {
-        let ss = Self::compute_stats_data(cs_list);
-        info!(
+    pub fn log_at_minutes(&self) {
+        debug!(
             "[{}] [{}] Stats In One Minute, {}",
-            stats_name,
-            stats_key,
-            Self::stat_print_detail(ss)
+            self.stats_name,
+            self.stats_key,
+            Self::stat_print_detail(self.get_stats_data_in_minute())
         );
     }
 
-    pub fn print_at_hour(
-        stats_name: &str,
-        stats_key: &str,
-        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
-    ) {
-        let ss = Self::compute_stats_data(cs_list);
-        info!(
+    pub fn log_at_hour(&self) {
+        debug!(
             "[{}] [{}] Stats In One Hour, {}",
-            stats_name,
-            stats_key,
-            Self::stat_print_detail(ss)
+            self.stats_name,
+            self.stats_key,
+            Self::stat_print_detail(self.get_stats_data_in_hour())
         );
     }
 
-    pub fn print_at_day(
-        stats_name: &str,
-        stats_key: &str,
-        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
-    ) {
-        let ss = Self::compute_stats_data(cs_list);
-        info!(
+    pub fn log_at_day(&self) {
+        debug!(
             "[{}] [{}] Stats In One Day, {}",
-            stats_name,
-            stats_key,
-            Self::stat_print_detail(ss)
+            self.stats_name,
+            self.stats_key,
+            Self::stat_print_detail(self.get_stats_data_in_day())
         );
     }
 
@@ -314,6 +224,81 @@ impl StatsItem {
     }
 }
 
+/// The scheduling API from before the items were sampled by their [`StatsItemSet`], kept for
+/// compatibility.
+///
+/// [`StatsItemSet`]: crate::common::stats::stats_item_set::StatsItemSet
+impl StatsItem {
+    /// Does nothing, the [`StatsItemSet`] holding the item samples and logs it.
+    ///
+    /// [`StatsItemSet`]: crate::common::stats::stats_item_set::StatsItemSet
+    #[deprecated]
+    pub fn init(&self) {}
+
+    /// Appends an empty snapshot to `cs_list`, use [`StatsItem::sample_in_seconds`] to sample
+    /// the values of an item.
+    #[deprecated]
+    pub fn sampling_in_seconds(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
+        Self::push_snapshot(&cs_list, 10 * 1000, 7, 0, 0);
+    }
+
+    /// Appends an empty snapshot to `cs_list`, use [`StatsItem::sample_in_minutes`] to sample
+    /// the values of an item.
+    #[deprecated]
+    pub fn sampling_in_minutes(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
+        Self::push_snapshot(&cs_list, 10 * 60 * 1000, 7, 0, 0);
+    }
+
+    /// Appends an empty snapshot to `cs_list`, use [`StatsItem::sample_in_hour`] to sample the
+    /// values of an item.
+    #[deprecated]
+    pub fn sampling_in_hour(cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>) {
+        Self::push_snapshot(&cs_list, 60 * 60 * 1000, 25, 0, 0);
+    }
+
+    #[deprecated]
+    pub fn print_at_minutes(
+        stats_name: &str,
+        stats_key: &str,
+        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
+    ) {
+        debug!(
+            "[{}] [{}] Stats In One Minute, {}",
+            stats_name,
+            stats_key,
+            Self::stat_print_detail(Self::compute_stats_data(cs_list))
+        );
+    }
+
+    #[deprecated]
+    pub fn print_at_hour(
+        stats_name: &str,
+        stats_key: &str,
+        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
+    ) {
+        debug!(
+            "[{}] [{}] Stats In One Hour, {}",
+            stats_name,
+            stats_key,
+            Self::stat_print_detail(Self::compute_stats_data(cs_list))
+        );
+    }
+
+    #[deprecated]
+    pub fn print_at_day(
+        stats_name: &str,
+        stats_key: &str,
+        cs_list: Arc<Mutex<LinkedList<CallSnapshot>>>,
+    ) {
+        debug!(
+            "[{}] [{}] Stats In One Day, {}",
+            stats_name,
+            stats_key,
+            Self::stat_print_detail(Self::compute_stats_data(cs_list))
+        );
+    }
+}
+
 impl fmt::Debug for StatsItem {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("StatsItem")
@@ -355,6 +340,30 @@ mod tests {
         assert_eq!(snapshot.get_avgpt(), 10.0);
     }
 
+    #[test]
+    fn sampling_in_seconds_records_added_values() {
+        let stats_item = StatsItem::new("TestName", "TestKey");
+        stats_item.sample_in_seconds();
+        stats_item.add_value(30, 3);
+        stats_item.sample_in_seconds();
+        let snapshot = stats_item.get_stats_data_in_minute();
+        assert_eq!(snapshot.get_sum(), 30);
+        assert_eq!(snapshot.get_times(), 3);
+        assert_eq!(snapshot.get_avgpt(), 10.0);
+        assert!(snapshot.get_tps() > 0.0);
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_sampling_appends_empty_snapshots() {
+        let cs_list = Arc::new(Mutex::new(LinkedList::new()));
+        for _ in 0..10 {
+            StatsItem::sampling_in_seconds(cs_list.clone());
+        }
+        assert_eq!(cs_list.lock().len(), 7);
+        assert_eq!(StatsItem::compute_stats_data(cs_list).get_sum(), 0);
+    }
+
     #[test]
     fn get_stats_data_in_minute_returns_correct_snapshot() {
         let stats_item = StatsItem::new("TestName", "TestKey");
@@ -14,17 +14,257 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+use crate::common::stats::stats_item::StatsItem;
 use crate::common::stats::stats_snapshot::StatsSnapshot;
+use crate::TimeUtils::get_current_millis;
 
-#[derive(Debug)]
-pub struct StatsItemSet {}
+#[derive(Debug, Clone)]
+pub struct StatsItemSet {
+    stats_item_table: Arc<DashMap<String, Arc<StatsItem>>>,
+    stats_name: String,
+    scheduled_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
 
 impl StatsItemSet {
-    pub fn new(_stats_name: String) -> Self {
-        StatsItemSet {}
+    pub fn new(stats_name: String) -> Self {
+        StatsItemSet {
+            stats_item_table: Arc::new(DashMap::new()),
+            stats_name,
+            scheduled_tasks: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    pub fn get_stats_name(&self) -> &str {
+        &self.stats_name
+    }
+
+    pub fn get_stats_item_table(&self) -> Arc<DashMap<String, Arc<StatsItem>>> {
+        Arc::clone(&self.stats_item_table)
+    }
+
+    /// Starts the sampling and stats logging tasks on the current tokio runtime, they run until
+    /// [`StatsItemSet::shutdown`] is called. Without a runtime nothing is scheduled.
+    pub fn init(&self) {
+        let Ok(handle) = Handle::try_current() else {
+            warn!(
+                "[{}] no tokio runtime available, stats sampling is not scheduled",
+                self.stats_name
+            );
+            return;
+        };
+        let mut tasks = self.scheduled_tasks.lock();
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(&handle, 0, 10 * 1000, move || {
+            table.iter().for_each(|item| item.sample_in_seconds())
+        }));
+
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(&handle, 0, 10 * 60 * 1000, move || {
+            table.iter().for_each(|item| item.sample_in_minutes())
+        }));
+
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(&handle, 0, 60 * 60 * 1000, move || {
+            table.iter().for_each(|item| item.sample_in_hour())
+        }));
+
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(
+            &handle,
+            StatsItem::compute_next_minutes_time_millis().saturating_sub(get_current_millis()),
+            60 * 1000,
+            move || table.iter().for_each(|item| item.log_at_minutes()),
+        ));
+
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(
+            &handle,
+            StatsItem::compute_next_hour_time_millis().saturating_sub(get_current_millis()),
+            60 * 60 * 1000,
+            move || table.iter().for_each(|item| item.log_at_hour()),
+        ));
+
+        let table = Arc::clone(&self.stats_item_table);
+        tasks.push(Self::schedule(
+            &handle,
+            StatsItem::compute_next_morning_time_millis()
+                .saturating_sub(get_current_millis())
+                .saturating_sub(2000),
+            24 * 60 * 60 * 1000,
+            move || table.iter().for_each(|item| item.log_at_day()),
+        ));
+    }
+
+    /// Stops the tasks started by [`StatsItemSet::init`].
+    pub fn shutdown(&self) {
+        for task in self.scheduled_tasks.lock().drain(..) {
+            task.abort();
+        }
+    }
+
+    fn schedule<F>(
+        handle: &Handle,
+        initial_delay_millis: u64,
+        period_millis: u64,
+        task: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn() + Send + 'static,
+    {
+        handle.spawn(async move {
+            tokio::time::sleep(Duration::from_millis(initial_delay_millis)).await;
+            let mut interval = tokio::time::interval(Duration::from_millis(period_millis));
+            loop {
+                interval.tick().await;
+                task();
+            }
+        })
+    }
+
+    pub fn add_value(&self, stats_key: &str, inc_value: u64, inc_times: u64) {
+        self.get_and_create_stats_item(stats_key)
+            .add_value(inc_value, inc_times);
+    }
+
+    pub fn del_value(&self, stats_key: &str) {
+        self.stats_item_table.remove(stats_key);
+    }
+
+    pub fn del_value_by_prefix_key(&self, stats_key: &str, separator: &str) {
+        let prefix = format!("{}{}", stats_key, separator);
+        self.stats_item_table
+            .retain(|key, _| !key.starts_with(&prefix));
+    }
+
+    pub fn del_value_by_infix_key(&self, stats_key: &str, separator: &str) {
+        let infix = format!("{}{}{}", separator, stats_key, separator);
+        self.stats_item_table.retain(|key, _| !key.contains(&infix));
+    }
+
+    pub fn del_value_by_suffix_key(&self, stats_key: &str, separator: &str) {
+        let suffix = format!("{}{}", separator, stats_key);
+        self.stats_item_table
+            .retain(|key, _| !key.ends_with(&suffix));
+    }
+
+    pub fn get_stats_item(&self, stats_key: &str) -> Option<Arc<StatsItem>> {
+        self.stats_item_table
+            .get(stats_key)
+            .map(|item| Arc::clone(item.value()))
+    }
+
+    pub fn get_and_create_stats_item(&self, stats_key: &str) -> Arc<StatsItem> {
+        if let Some(item) = self.stats_item_table.get(stats_key) {
+            return Arc::clone(item.value());
+        }
+        Arc::clone(
+            self.stats_item_table
+                .entry(stats_key.to_string())
+                .or_insert_with(|| Arc::new(StatsItem::new(&self.stats_name, stats_key)))
+                .value(),
+        )
     }
 
     pub fn get_stats_data_in_minute(&self, stats_key: &str) -> StatsSnapshot {
-        StatsSnapshot::new()
+        self.get_stats_item(stats_key)
+            .map(|item| item.get_stats_data_in_minute())
+            .unwrap_or_default()
+    }
+
+    pub fn get_stats_data_in_hour(&self, stats_key: &str) -> StatsSnapshot {
+        self.get_stats_item(stats_key)
+            .map(|item| item.get_stats_data_in_hour())
+            .unwrap_or_default()
+    }
+
+    pub fn get_stats_data_in_day(&self, stats_key: &str) -> StatsSnapshot {
+        self.get_stats_item(stats_key)
+            .map(|item| item.get_stats_data_in_day())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[test]
+    fn add_value_creates_stats_item() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        stats_set.add_value("TestKey", 10, 2);
+        stats_set.add_value("TestKey", 5, 1);
+        let stats_item = stats_set.get_stats_item("TestKey").unwrap();
+        assert_eq!(stats_item.get_stats_name(), "TestName");
+        assert_eq!(stats_item.get_value().load(Ordering::Relaxed), 15);
+        assert_eq!(stats_item.get_times().load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn stats_data_of_unknown_key_is_empty() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        let snapshot = stats_set.get_stats_data_in_minute("Unknown");
+        assert_eq!(snapshot.get_sum(), 0);
+        assert_eq!(snapshot.get_tps(), 0.0);
+    }
+
+    #[test]
+    fn del_value_removes_stats_item() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        stats_set.add_value("TestKey", 1, 1);
+        stats_set.del_value("TestKey");
+        assert!(stats_set.get_stats_item("TestKey").is_none());
+    }
+
+    #[test]
+    fn del_value_by_key_parts_removes_matching_items() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        stats_set.add_value("0@TopicA@GroupA", 1, 1);
+        stats_set.add_value("TopicA@GroupB", 1, 1);
+        stats_set.add_value("TopicB@GroupA", 1, 1);
+
+        stats_set.del_value_by_prefix_key("TopicA", "@");
+        assert!(stats_set.get_stats_item("TopicA@GroupB").is_none());
+        assert!(stats_set.get_stats_item("0@TopicA@GroupA").is_some());
+
+        stats_set.del_value_by_infix_key("TopicA", "@");
+        assert!(stats_set.get_stats_item("0@TopicA@GroupA").is_none());
+
+        stats_set.del_value_by_suffix_key("GroupA", "@");
+        assert!(stats_set.get_stats_item_table().is_empty());
+    }
+
+    #[test]
+    fn init_without_runtime_schedules_nothing() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        stats_set.init();
+        assert!(stats_set.scheduled_tasks.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_scheduled_tasks() {
+        let stats_set = StatsItemSet::new("TestName".to_string());
+        stats_set.init();
+        let abort_handles: Vec<_> = stats_set
+            .scheduled_tasks
+            .lock()
+            .iter()
+            .map(|task| task.abort_handle())
+            .collect();
+        assert_eq!(abort_handles.len(), 6);
+        stats_set.shutdown();
+        assert!(stats_set.scheduled_tasks.lock().is_empty());
+        tokio::task::yield_now().await;
+        assert!(abort_handles.iter().all(|task| task.is_finished()));
     }
 }
@@ -38,6 +38,7 @@ use chrono::Timelike;
 use chrono::Utc;
 use local_ip_address::Error;
 use once_cell::sync::Lazy;
+use sysinfo::Disks;
 use tracing::error;
 use tracing::info;
 
@@ -169,34 +170,8 @@ pub fn get_disk_partition_space_used_percent(path: &str) -> f64 {
         return -1.0;
     }
 
-    match fs::metadata(path) {
-        Ok(metadata) => {
-            let total_space = metadata.len();
-            if total_space > 0 {
-                match (fs::metadata(path), fs::metadata(path)) {
-                    (Ok(metadata1), Ok(metadata2)) => {
-                        let free_space = metadata1.len();
-                        let usable_space = metadata2.len();
-                        let used_space = total_space.saturating_sub(free_space);
-                        let entire_space = used_space + usable_space;
-                        let round_num = if used_space * 100 % entire_space != 0 {
-                            1
-                        } else {
-                            0
-                        };
-                        let result = used_space * 100 / entire_space + round_num;
-                        return result as f64 / 100.0;
-                    }
-                    (Err(e), _) | (_, Err(e)) => {
-                        error!(
-                            "Error when measuring disk space usage, got exception: {:?}",
-                            e
-                        );
-                        return -1.0;
-                    }
-                }
-            }
-        }
+    let path = match path.canonicalize() {
+        Ok(path) => path,
         Err(e) => {
             error!(
                 "Error when measuring disk space usage, got exception: {:?}",
@@ -204,6 +179,34 @@ pub fn get_disk_partition_space_used_percent(path: &str) -> f64 {
             );
             return -1.0;
         }
+    };
+    // the partition of a path is the one mounted at its longest ancestor
+    let disks = Disks::new_with_refreshed_list();
+    let Some(disk) = disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+    else {
+        error!(
+            "Error when measuring disk space usage, no partition found for path: {}",
+            path.to_string_lossy()
+        );
+        return -1.0;
+    };
+
+    let total_space = disk.total_space();
+    if total_space > 0 {
+        let usable_space = disk.available_space();
+        let used_space = total_space.saturating_sub(usable_space);
+        let entire_space = used_space + usable_space;
+        let round_num = if used_space * 100 % entire_space != 0 {
+            1
+        } else {
+            0
+        };
+        let result = used_space * 100 / entire_space + round_num;
+        return result as f64 / 100.0;
     }
 
     -1.0
@@ -442,6 +445,18 @@ mod tests {
         assert_eq!(is_path_exists("./non_existing_path"), false);
     }
 
+    #[test]
+    fn get_disk_partition_space_used_percent_returns_ratio() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ratio = get_disk_partition_space_used_percent(temp_dir.path().to_str().unwrap());
+        assert!((0.0..=1.0).contains(&ratio) || ratio == -1.0);
+        assert_eq!(get_disk_partition_space_used_percent(""), -1.0);
+        assert_eq!(
+            get_disk_partition_space_used_percent("./non_existing_path"),
+            -1.0
+        );
+    }
+
     #[test]
     fn bytes_to_string_converts_correctly() {
         let bytes = [0x41, 0x42, 0x43];
@@ -135,6 +135,9 @@ pub enum RocketmqError {
 
     #[error("{0}")]
     ConfigError(String),
+
+    #[error("MQFilterException: {0}")]
+    MQFilterError(String),
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,629 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use cheetah_string::CheetahString;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+use crate::expression::evaluation_context::EvaluationContext;
+use crate::expression::Expression;
+
+/// A literal value that appears in a SQL92 selector.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Null,
+    Boolean(bool),
+    Long(i64),
+    Double(f64),
+    String(String),
+}
+
+/// Runtime value produced while evaluating an expression.
+///
+/// Strings borrow either from the compiled expression or from the evaluation context, so
+/// evaluating a selector against message properties does not copy them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Value<'a> {
+    Null,
+    Boolean(bool),
+    Long(i64),
+    Double(f64),
+    String(&'a str),
+}
+
+impl Value<'_> {
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    fn from_bool(value: Option<bool>) -> Self {
+        match value {
+            None => Value::Null,
+            Some(value) => Value::Boolean(value),
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComparisonOperator {
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+impl ComparisonOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ComparisonOperator::Equal => "=",
+            ComparisonOperator::NotEqual => "<>",
+            ComparisonOperator::GreaterThan => ">",
+            ComparisonOperator::GreaterThanOrEqual => ">=",
+            ComparisonOperator::LessThan => "<",
+            ComparisonOperator::LessThanOrEqual => "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringOperator {
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl StringOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            StringOperator::Contains => "CONTAINS",
+            StringOperator::StartsWith => "STARTSWITH",
+            StringOperator::EndsWith => "ENDSWITH",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LikeToken {
+    Char(char),
+    AnyOne,
+    AnyMany,
+}
+
+/// Compiled pattern of a `LIKE` expression.
+///
+/// `%` matches any sequence of characters and `_` matches exactly one character; an optional
+/// escape character makes the following wildcard literal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LikePattern {
+    pattern: String,
+    escape: Option<char>,
+    tokens: Vec<LikeToken>,
+}
+
+impl LikePattern {
+    pub fn new(pattern: String, escape: Option<char>) -> Self {
+        let mut tokens = Vec::with_capacity(pattern.len());
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            if Some(c) == escape {
+                match chars.next() {
+                    Some(next) => tokens.push(LikeToken::Char(next)),
+                    None => tokens.push(LikeToken::Char(c)),
+                }
+                continue;
+            }
+            match c {
+                '%' => {
+                    if tokens.last() != Some(&LikeToken::AnyMany) {
+                        tokens.push(LikeToken::AnyMany)
+                    }
+                }
+                '_' => tokens.push(LikeToken::AnyOne),
+                c => tokens.push(LikeToken::Char(c)),
+            }
+        }
+        Self {
+            pattern,
+            escape,
+            tokens,
+        }
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn escape(&self) -> Option<char> {
+        self.escape
+    }
+
+    pub fn matches(&self, text: &str) -> bool {
+        let text: Vec<char> = text.chars().collect();
+        let tokens = &self.tokens;
+        let (mut p, mut t) = (0usize, 0usize);
+        let mut backtrack: Option<(usize, usize)> = None;
+        while t < text.len() {
+            match tokens.get(p) {
+                Some(LikeToken::AnyOne) => {
+                    p += 1;
+                    t += 1;
+                }
+                Some(LikeToken::Char(c)) if *c == text[t] => {
+                    p += 1;
+                    t += 1;
+                }
+                Some(LikeToken::AnyMany) => {
+                    backtrack = Some((p, t));
+                    p += 1;
+                }
+                _ => match backtrack {
+                    Some((star, mark)) => {
+                        p = star + 1;
+                        t = mark + 1;
+                        backtrack = Some((star, mark + 1));
+                    }
+                    None => return false,
+                },
+            }
+        }
+        tokens[p..].iter().all(|token| *token == LikeToken::AnyMany)
+    }
+}
+
+/// Abstract syntax tree of a compiled SQL92 selector.
+///
+/// Evaluation follows SQL three-valued logic: comparisons involving a missing property yield
+/// `NULL`, and a selector only matches when it evaluates to `TRUE`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SqlExpression {
+    Constant(Literal),
+    Property(CheetahString),
+    Negate(Box<SqlExpression>),
+    Not(Box<SqlExpression>),
+    BooleanCast(Box<SqlExpression>),
+    And(Box<SqlExpression>, Box<SqlExpression>),
+    Or(Box<SqlExpression>, Box<SqlExpression>),
+    Comparison {
+        operator: ComparisonOperator,
+        left: Box<SqlExpression>,
+        right: Box<SqlExpression>,
+    },
+    IsNull {
+        operand: Box<SqlExpression>,
+        not: bool,
+    },
+    In {
+        operand: Box<SqlExpression>,
+        values: Vec<String>,
+        not: bool,
+    },
+    Like {
+        operand: Box<SqlExpression>,
+        pattern: LikePattern,
+        not: bool,
+    },
+    StringMatch {
+        operator: StringOperator,
+        operand: Box<SqlExpression>,
+        value: String,
+        not: bool,
+    },
+}
+
+impl SqlExpression {
+    /// Whether this expression always produces a boolean (or `NULL`) value.
+    pub fn is_boolean(&self) -> bool {
+        match self {
+            SqlExpression::Constant(Literal::Boolean(_)) => true,
+            SqlExpression::Constant(_) | SqlExpression::Property(_) | SqlExpression::Negate(_) => {
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Evaluates this expression to a [`Value`].
+    pub fn evaluate_value<'a>(
+        &'a self,
+        context: &'a dyn EvaluationContext,
+    ) -> RocketMQResult<Value<'a>> {
+        match self {
+            SqlExpression::Constant(literal) => Ok(match literal {
+                Literal::Null => Value::Null,
+                Literal::Boolean(value) => Value::Boolean(*value),
+                Literal::Long(value) => Value::Long(*value),
+                Literal::Double(value) => Value::Double(*value),
+                Literal::String(value) => Value::String(value.as_str()),
+            }),
+            SqlExpression::Property(name) => Ok(match context.get(name.as_str()) {
+                None => Value::Null,
+                Some(value) => Value::String(value.as_str()),
+            }),
+            SqlExpression::Negate(operand) => match operand.evaluate_value(context)? {
+                Value::Null => Ok(Value::Null),
+                Value::Long(value) => Ok(Value::Long(value.wrapping_neg())),
+                Value::Double(value) => Ok(Value::Double(-value)),
+                Value::String(value) => match parse_number(value)? {
+                    Value::Long(value) => Ok(Value::Long(value.wrapping_neg())),
+                    Value::Double(value) => Ok(Value::Double(-value)),
+                    _ => Ok(Value::Null),
+                },
+                Value::Boolean(_) => Err(filter_error(format!("Cannot negate {}", operand))),
+            },
+            SqlExpression::Not(operand) => {
+                let value = operand.evaluate_value(context)?.as_bool();
+                Ok(Value::from_bool(value.map(|value| !value)))
+            }
+            SqlExpression::BooleanCast(operand) => Ok(match operand.evaluate_value(context)? {
+                Value::Null => Value::Null,
+                Value::Boolean(value) => Value::Boolean(value),
+                Value::String(value) => Value::Boolean(value.eq_ignore_ascii_case("true")),
+                _ => Value::Boolean(false),
+            }),
+            SqlExpression::And(left, right) => {
+                let lv = left.evaluate_value(context)?.as_bool();
+                if lv == Some(false) {
+                    return Ok(Value::Boolean(false));
+                }
+                let rv = right.evaluate_value(context)?.as_bool();
+                Ok(match (lv, rv) {
+                    (_, Some(false)) => Value::Boolean(false),
+                    (Some(true), Some(true)) => Value::Boolean(true),
+                    _ => Value::Null,
+                })
+            }
+            SqlExpression::Or(left, right) => {
+                let lv = left.evaluate_value(context)?.as_bool();
+                if lv == Some(true) {
+                    return Ok(Value::Boolean(true));
+                }
+                let rv = right.evaluate_value(context)?.as_bool();
+                Ok(match (lv, rv) {
+                    (_, Some(true)) => Value::Boolean(true),
+                    (Some(false), Some(false)) => Value::Boolean(false),
+                    _ => Value::Null,
+                })
+            }
+            SqlExpression::Comparison {
+                operator,
+                left,
+                right,
+            } => {
+                let lv = left.evaluate_value(context)?;
+                let rv = right.evaluate_value(context)?;
+                evaluate_comparison(*operator, lv, rv)
+            }
+            SqlExpression::IsNull { operand, not } => {
+                let is_null = operand.evaluate_value(context)?.is_null();
+                Ok(Value::Boolean(is_null ^ not))
+            }
+            SqlExpression::In {
+                operand,
+                values,
+                not,
+            } => Ok(match operand.evaluate_value(context)? {
+                Value::Null => Value::Null,
+                Value::String(value) => {
+                    Value::Boolean(values.iter().any(|item| item == value) ^ not)
+                }
+                _ => Value::Null,
+            }),
+            SqlExpression::Like {
+                operand,
+                pattern,
+                not,
+            } => Ok(match operand.evaluate_value(context)? {
+                Value::Null => Value::Null,
+                Value::String(value) => Value::Boolean(pattern.matches(value) ^ not),
+                _ => Value::Boolean(false),
+            }),
+            SqlExpression::StringMatch {
+                operator,
+                operand,
+                value,
+                not,
+            } => Ok(match operand.evaluate_value(context)? {
+                Value::Null => Value::Null,
+                Value::String(text) => {
+                    let matched = match operator {
+                        StringOperator::Contains => text.contains(value.as_str()),
+                        StringOperator::StartsWith => text.starts_with(value.as_str()),
+                        StringOperator::EndsWith => text.ends_with(value.as_str()),
+                    };
+                    Value::Boolean(matched ^ not)
+                }
+                _ => Value::Boolean(false),
+            }),
+        }
+    }
+
+    /// Evaluates this expression as a selector, `NULL` is treated as not matched.
+    pub fn matches(&self, context: &dyn EvaluationContext) -> RocketMQResult<bool> {
+        Ok(self.evaluate_value(context)?.as_bool().unwrap_or(false))
+    }
+}
+
+impl Expression for SqlExpression {
+    fn evaluate(
+        &self,
+        context: &dyn EvaluationContext,
+    ) -> Result<Box<dyn std::any::Any>, Box<dyn Error>> {
+        Ok(Box::new(self.matches(context)?))
+    }
+}
+
+fn filter_error(message: impl Into<String>) -> RocketmqError {
+    RocketmqError::MQFilterError(message.into())
+}
+
+fn parse_number(value: &str) -> RocketMQResult<Value<'_>> {
+    let trimmed = value.trim();
+    if let Ok(value) = trimmed.parse::<i64>() {
+        return Ok(Value::Long(value));
+    }
+    match trimmed.parse::<f64>() {
+        Ok(value) => Ok(Value::Double(value)),
+        Err(_) => Err(filter_error(format!(
+            "Cannot convert value '{}' to a number",
+            value
+        ))),
+    }
+}
+
+fn compare_numbers(lv: Value<'_>, rv: Value<'_>) -> Option<Ordering> {
+    match (lv, rv) {
+        (Value::Long(l), Value::Long(r)) => Some(l.cmp(&r)),
+        (Value::Long(l), Value::Double(r)) => (l as f64).partial_cmp(&r),
+        (Value::Double(l), Value::Long(r)) => l.partial_cmp(&(r as f64)),
+        (Value::Double(l), Value::Double(r)) => l.partial_cmp(&r),
+        _ => None,
+    }
+}
+
+/// Compares two non-null values, converting a string operand to the type of the other one
+/// when their types differ.
+fn compare_values(lv: Value<'_>, rv: Value<'_>) -> RocketMQResult<Option<Ordering>> {
+    Ok(match (lv, rv) {
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (Value::Boolean(l), Value::Boolean(r)) => Some(l.cmp(&r)),
+        (Value::String(l), Value::Boolean(r)) => Some(l.eq_ignore_ascii_case("true").cmp(&r)),
+        (Value::Boolean(l), Value::String(r)) => Some(l.cmp(&r.eq_ignore_ascii_case("true"))),
+        (Value::String(l), number @ (Value::Long(_) | Value::Double(_))) => {
+            compare_numbers(parse_number(l)?, number)
+        }
+        (number @ (Value::Long(_) | Value::Double(_)), Value::String(r)) => {
+            compare_numbers(number, parse_number(r)?)
+        }
+        (l, r) => compare_numbers(l, r),
+    })
+}
+
+fn evaluate_comparison<'a>(
+    operator: ComparisonOperator,
+    lv: Value<'a>,
+    rv: Value<'a>,
+) -> RocketMQResult<Value<'a>> {
+    if lv.is_null() || rv.is_null() {
+        return Ok(Value::Null);
+    }
+    let is_equality = matches!(
+        operator,
+        ComparisonOperator::Equal | ComparisonOperator::NotEqual
+    );
+    // Ordering two strings is not supported, only numbers can be compared this way.
+    if !is_equality && matches!((lv, rv), (Value::String(_), Value::String(_))) {
+        return Ok(Value::Boolean(false));
+    }
+    let ordering = compare_values(lv, rv)?;
+    let result = match (operator, ordering) {
+        (ComparisonOperator::Equal, ordering) => ordering == Some(Ordering::Equal),
+        (ComparisonOperator::NotEqual, ordering) => ordering != Some(Ordering::Equal),
+        (_, None) => false,
+        (ComparisonOperator::GreaterThan, Some(ordering)) => ordering == Ordering::Greater,
+        (ComparisonOperator::GreaterThanOrEqual, Some(ordering)) => ordering != Ordering::Less,
+        (ComparisonOperator::LessThan, Some(ordering)) => ordering == Ordering::Less,
+        (ComparisonOperator::LessThanOrEqual, Some(ordering)) => ordering != Ordering::Greater,
+    };
+    Ok(Value::Boolean(result))
+}
+
+fn write_string_literal(f: &mut Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "'{}'", value.replace('\'', "''"))
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Null => write!(f, "NULL"),
+            Literal::Boolean(value) => write!(f, "{}", if *value { "TRUE" } else { "FALSE" }),
+            Literal::Long(value) => write!(f, "{}", value),
+            Literal::Double(value) => write!(f, "{:?}", value),
+            Literal::String(value) => write_string_literal(f, value),
+        }
+    }
+}
+
+impl Display for SqlExpression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let not = |not: &bool| if *not { "NOT " } else { "" };
+        match self {
+            SqlExpression::Constant(literal) => write!(f, "{}", literal),
+            SqlExpression::Property(name) => write!(f, "{}", name),
+            SqlExpression::Negate(operand) => write!(f, "(-{})", operand),
+            SqlExpression::Not(operand) => write!(f, "NOT ({})", operand),
+            SqlExpression::BooleanCast(operand) => write!(f, "{}", operand),
+            SqlExpression::And(left, right) => write!(f, "({} AND {})", left, right),
+            SqlExpression::Or(left, right) => write!(f, "({} OR {})", left, right),
+            SqlExpression::Comparison {
+                operator,
+                left,
+                right,
+            } => write!(f, "({} {} {})", left, operator.symbol(), right),
+            SqlExpression::IsNull { operand, not: n } => {
+                write!(f, "({} IS {}NULL)", operand, not(n))
+            }
+            SqlExpression::In {
+                operand,
+                values,
+                not: n,
+            } => {
+                write!(f, "({} {}IN (", operand, not(n))?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_string_literal(f, value)?;
+                }
+                write!(f, "))")
+            }
+            SqlExpression::Like {
+                operand,
+                pattern,
+                not: n,
+            } => {
+                write!(f, "({} {}LIKE ", operand, not(n))?;
+                write_string_literal(f, pattern.pattern())?;
+                if let Some(escape) = pattern.escape() {
+                    write!(f, " ESCAPE ")?;
+                    write_string_literal(f, escape.to_string().as_str())?;
+                }
+                write!(f, ")")
+            }
+            SqlExpression::StringMatch {
+                operator,
+                operand,
+                value,
+                not: n,
+            } => {
+                write!(f, "({} {}{} ", operand, not(n), operator.symbol())?;
+                write_string_literal(f, value)?;
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    struct MapContext(HashMap<CheetahString, CheetahString>);
+
+    impl EvaluationContext for MapContext {
+        fn get(&self, name: &str) -> Option<&CheetahString> {
+            self.0.get(name)
+        }
+
+        fn key_values(&self) -> Option<HashMap<CheetahString, CheetahString>> {
+            Some(self.0.clone())
+        }
+    }
+
+    fn context(pairs: &[(&str, &str)]) -> MapContext {
+        MapContext(
+            pairs
+                .iter()
+                .map(|(k, v)| (CheetahString::from(*k), CheetahString::from(*v)))
+                .collect(),
+        )
+    }
+
+    fn property(name: &str) -> Box<SqlExpression> {
+        Box::new(SqlExpression::Property(CheetahString::from(name)))
+    }
+
+    fn constant(literal: Literal) -> Box<SqlExpression> {
+        Box::new(SqlExpression::Constant(literal))
+    }
+
+    #[test]
+    fn like_pattern_matches_wildcards() {
+        let pattern = LikePattern::new("ab%c_".to_string(), None);
+        assert!(pattern.matches("abcd"));
+        assert!(pattern.matches("abxxxcd"));
+        assert!(!pattern.matches("abxxxc"));
+        assert!(!pattern.matches("xabcd"));
+    }
+
+    #[test]
+    fn like_pattern_honours_escape() {
+        let pattern = LikePattern::new("100!%".to_string(), Some('!'));
+        assert!(pattern.matches("100%"));
+        assert!(!pattern.matches("1000"));
+    }
+
+    #[test]
+    fn comparison_converts_string_property_to_number() {
+        let expression = SqlExpression::Comparison {
+            operator: ComparisonOperator::GreaterThan,
+            left: property("a"),
+            right: constant(Literal::Long(3)),
+        };
+        assert!(expression.matches(&context(&[("a", "4")])).unwrap());
+        assert!(!expression.matches(&context(&[("a", "3")])).unwrap());
+        assert!(!expression.matches(&context(&[])).unwrap());
+        assert!(expression.matches(&context(&[("a", "abc")])).is_err());
+    }
+
+    #[test]
+    fn comparison_of_two_strings_by_order_is_false() {
+        let expression = SqlExpression::Comparison {
+            operator: ComparisonOperator::LessThan,
+            left: property("a"),
+            right: constant(Literal::String("b".to_string())),
+        };
+        assert!(!expression.matches(&context(&[("a", "a")])).unwrap());
+    }
+
+    #[test]
+    fn not_of_unknown_is_unknown() {
+        let expression = SqlExpression::Not(Box::new(SqlExpression::Comparison {
+            operator: ComparisonOperator::Equal,
+            left: property("a"),
+            right: constant(Literal::String("x".to_string())),
+        }));
+        assert!(!expression.matches(&context(&[])).unwrap());
+        assert!(expression.matches(&context(&[("a", "y")])).unwrap());
+    }
+
+    #[test]
+    fn evaluate_returns_boxed_bool() {
+        let expression = SqlExpression::IsNull {
+            operand: property("a"),
+            not: false,
+        };
+        let value = expression.evaluate(&context(&[])).unwrap();
+        assert_eq!(value.downcast_ref::<bool>(), Some(&true));
+    }
+}
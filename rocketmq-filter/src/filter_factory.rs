@@ -0,0 +1,112 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+use crate::filter_spi::sql_filter::SqlFilter;
+use crate::filter_spi::FilterSpi;
+
+static INSTANCE: Lazy<FilterFactory> = Lazy::new(|| {
+    let factory = FilterFactory {
+        filter_spi_table: RwLock::new(HashMap::new()),
+    };
+    factory
+        .register(Arc::new(SqlFilter))
+        .expect("register SQL92 filter failed");
+    factory
+});
+
+/// Filter factory: support other filter to register.
+pub struct FilterFactory {
+    filter_spi_table: RwLock<HashMap<&'static str, Arc<dyn FilterSpi>>>,
+}
+
+impl FilterFactory {
+    /// The shared factory, with [`SqlFilter`] registered by default.
+    pub fn instance() -> &'static FilterFactory {
+        &INSTANCE
+    }
+
+    /// Register a filter.
+    ///
+    /// Returns an error if a filter of the same type has been registered already.
+    pub fn register(&self, filter_spi: Arc<dyn FilterSpi>) -> RocketMQResult<()> {
+        let mut table = self.filter_spi_table.write();
+        if table.contains_key(filter_spi.of_type()) {
+            return Err(RocketmqError::IllegalArgument(format!(
+                "Filter spi type({}) already exist!",
+                filter_spi.of_type()
+            )));
+        }
+        table.insert(filter_spi.of_type(), filter_spi);
+        Ok(())
+    }
+
+    /// Un register a filter.
+    pub fn un_register(&self, type_: &str) -> Option<Arc<dyn FilterSpi>> {
+        self.filter_spi_table.write().remove(type_)
+    }
+
+    /// Get a filter registered, `None` if none exist.
+    pub fn get(&self, type_: &str) -> Option<Arc<dyn FilterSpi>> {
+        self.filter_spi_table.read().get(type_).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expression::Expression;
+
+    struct NoopFilter;
+
+    impl FilterSpi for NoopFilter {
+        fn compile(
+            &self,
+            _expr: &str,
+        ) -> RocketMQResult<Box<dyn Expression + Send + Sync + 'static>> {
+            Err(RocketmqError::MQFilterError("noop".to_string()))
+        }
+
+        fn of_type(&self) -> &'static str {
+            "NOOP"
+        }
+    }
+
+    #[test]
+    fn instance_has_sql_filter_registered() {
+        let filter = FilterFactory::instance().get("SQL92").unwrap();
+        assert_eq!(filter.of_type(), "SQL92");
+        assert!(FilterFactory::instance().get("TAG").is_none());
+    }
+
+    #[test]
+    fn register_rejects_duplicate_type() {
+        let factory = FilterFactory {
+            filter_spi_table: RwLock::new(HashMap::new()),
+        };
+        assert!(factory.register(Arc::new(NoopFilter)).is_ok());
+        assert!(factory.register(Arc::new(NoopFilter)).is_err());
+        assert!(factory.un_register("NOOP").is_some());
+        assert!(factory.get("NOOP").is_none());
+    }
+}
@@ -0,0 +1,38 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+pub mod sql_filter;
+
+use rocketmq_error::RocketMQResult;
+
+use crate::expression::Expression;
+
+/// Filter spi interface.
+pub trait FilterSpi: Send + Sync {
+    /// Compile the expression to a filter expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `expr` - The expression to compile
+    ///
+    /// # Returns
+    ///
+    /// The compiled expression, or an `MQFilterError` if the expression is illegal
+    fn compile(&self, expr: &str) -> RocketMQResult<Box<dyn Expression + Send + Sync + 'static>>;
+
+    /// Which type of expression this filter supports.
+    fn of_type(&self) -> &'static str;
+}
@@ -0,0 +1,51 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use rocketmq_error::RocketMQResult;
+
+use crate::expression::Expression;
+use crate::filter_spi::FilterSpi;
+use crate::parser::selector_parser::SelectorParser;
+
+/// SQL92 Filter, just a wrapper of [`SelectorParser`].
+#[derive(Default)]
+pub struct SqlFilter;
+
+impl SqlFilter {
+    pub const SQL92: &'static str = "SQL92";
+}
+
+impl FilterSpi for SqlFilter {
+    fn compile(&self, expr: &str) -> RocketMQResult<Box<dyn Expression + Send + Sync + 'static>> {
+        Ok(Box::new(SelectorParser::parse(expr)?))
+    }
+
+    fn of_type(&self) -> &'static str {
+        Self::SQL92
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compile_returns_error_for_illegal_expression() {
+        assert!(SqlFilter.compile("a >").is_err());
+        assert!(SqlFilter.compile("a > 1").is_ok());
+        assert_eq!(SqlFilter.of_type(), "SQL92");
+    }
+}
@@ -16,4 +16,7 @@
  */
 
 pub mod expression;
+pub mod filter_factory;
+pub mod filter_spi;
+pub mod parser;
 pub mod utils;
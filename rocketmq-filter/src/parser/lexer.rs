@@ -0,0 +1,365 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Identifier(String),
+    StringLiteral(String),
+    LongLiteral(i64),
+    DoubleLiteral(f64),
+    And,
+    Or,
+    Not,
+    Between,
+    In,
+    Is,
+    Null,
+    Like,
+    Escape,
+    True,
+    False,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+    LeftParen,
+    RightParen,
+    Comma,
+    Plus,
+    Minus,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(name) => write!(f, "{}", name),
+            Token::StringLiteral(value) => write!(f, "'{}'", value),
+            Token::LongLiteral(value) => write!(f, "{}", value),
+            Token::DoubleLiteral(value) => write!(f, "{}", value),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Between => write!(f, "BETWEEN"),
+            Token::In => write!(f, "IN"),
+            Token::Is => write!(f, "IS"),
+            Token::Null => write!(f, "NULL"),
+            Token::Like => write!(f, "LIKE"),
+            Token::Escape => write!(f, "ESCAPE"),
+            Token::True => write!(f, "TRUE"),
+            Token::False => write!(f, "FALSE"),
+            Token::Contains => write!(f, "CONTAINS"),
+            Token::StartsWith => write!(f, "STARTSWITH"),
+            Token::EndsWith => write!(f, "ENDSWITH"),
+            Token::Equal => write!(f, "="),
+            Token::NotEqual => write!(f, "<>"),
+            Token::GreaterThan => write!(f, ">"),
+            Token::GreaterThanOrEqual => write!(f, ">="),
+            Token::LessThan => write!(f, "<"),
+            Token::LessThanOrEqual => write!(f, "<="),
+            Token::LeftParen => write!(f, "("),
+            Token::RightParen => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Plus => write!(f, "+"),
+            Token::Minus => write!(f, "-"),
+        }
+    }
+}
+
+/// A token together with the character position it starts at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub position: usize,
+}
+
+fn keyword(word: &str) -> Option<Token> {
+    let token = match word.to_ascii_uppercase().as_str() {
+        "AND" => Token::And,
+        "OR" => Token::Or,
+        "NOT" => Token::Not,
+        "BETWEEN" => Token::Between,
+        "IN" => Token::In,
+        "IS" => Token::Is,
+        "NULL" => Token::Null,
+        "LIKE" => Token::Like,
+        "ESCAPE" => Token::Escape,
+        "TRUE" => Token::True,
+        "FALSE" => Token::False,
+        "CONTAINS" => Token::Contains,
+        "STARTSWITH" => Token::StartsWith,
+        "ENDSWITH" => Token::EndsWith,
+        _ => return None,
+    };
+    Some(token)
+}
+
+#[inline]
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == '$'
+}
+
+#[inline]
+fn is_identifier_part(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$' || c == '.'
+}
+
+fn lex_error(message: String, position: usize) -> RocketmqError {
+    RocketmqError::MQFilterError(format!("{} at position {}", message, position))
+}
+
+/// Splits a SQL92 selector into tokens.
+pub fn tokenize(input: &str) -> RocketMQResult<Vec<SpannedToken>> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        let start = pos;
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+        let token = match c {
+            '(' => {
+                pos += 1;
+                Token::LeftParen
+            }
+            ')' => {
+                pos += 1;
+                Token::RightParen
+            }
+            ',' => {
+                pos += 1;
+                Token::Comma
+            }
+            '+' => {
+                pos += 1;
+                Token::Plus
+            }
+            '-' => {
+                pos += 1;
+                Token::Minus
+            }
+            '=' => {
+                pos += 1;
+                Token::Equal
+            }
+            '!' if chars.get(pos + 1) == Some(&'=') => {
+                pos += 2;
+                Token::NotEqual
+            }
+            '<' => match chars.get(pos + 1) {
+                Some('>') => {
+                    pos += 2;
+                    Token::NotEqual
+                }
+                Some('=') => {
+                    pos += 2;
+                    Token::LessThanOrEqual
+                }
+                _ => {
+                    pos += 1;
+                    Token::LessThan
+                }
+            },
+            '>' => {
+                if chars.get(pos + 1) == Some(&'=') {
+                    pos += 2;
+                    Token::GreaterThanOrEqual
+                } else {
+                    pos += 1;
+                    Token::GreaterThan
+                }
+            }
+            '\'' | '"' => {
+                // '' (or "") inside a quoted text stands for the quote character itself
+                let quote = c;
+                let mut value = String::new();
+                pos += 1;
+                loop {
+                    match chars.get(pos) {
+                        None => {
+                            return Err(lex_error("Unterminated quoted text".to_string(), start))
+                        }
+                        Some(&ch) if ch == quote => {
+                            if chars.get(pos + 1) == Some(&quote) {
+                                value.push(quote);
+                                pos += 2;
+                            } else {
+                                pos += 1;
+                                break;
+                            }
+                        }
+                        Some(&ch) => {
+                            value.push(ch);
+                            pos += 1;
+                        }
+                    }
+                }
+                if quote == '"' {
+                    Token::Identifier(value)
+                } else {
+                    Token::StringLiteral(value)
+                }
+            }
+            c if c.is_ascii_digit()
+                || (c == '.' && chars.get(pos + 1).is_some_and(|next| next.is_ascii_digit())) =>
+            {
+                let (token, next) = lex_number(&chars, pos)?;
+                pos = next;
+                token
+            }
+            c if is_identifier_start(c) => {
+                while pos < chars.len() && is_identifier_part(chars[pos]) {
+                    pos += 1;
+                }
+                let word: String = chars[start..pos].iter().collect();
+                keyword(word.as_str()).unwrap_or(Token::Identifier(word))
+            }
+            c => return Err(lex_error(format!("Unexpected character '{}'", c), start)),
+        };
+        tokens.push(SpannedToken {
+            token,
+            position: start,
+        });
+    }
+    Ok(tokens)
+}
+
+fn lex_number(chars: &[char], start: usize) -> RocketMQResult<(Token, usize)> {
+    let mut pos = start;
+    let mut is_floating = false;
+    while pos < chars.len() && chars[pos].is_ascii_digit() {
+        pos += 1;
+    }
+    if pos < chars.len() && chars[pos] == '.' {
+        is_floating = true;
+        pos += 1;
+        while pos < chars.len() && chars[pos].is_ascii_digit() {
+            pos += 1;
+        }
+    }
+    if pos < chars.len() && (chars[pos] == 'e' || chars[pos] == 'E') {
+        let mut exponent = pos + 1;
+        if exponent < chars.len() && (chars[exponent] == '+' || chars[exponent] == '-') {
+            exponent += 1;
+        }
+        if exponent < chars.len() && chars[exponent].is_ascii_digit() {
+            is_floating = true;
+            pos = exponent;
+            while pos < chars.len() && chars[pos].is_ascii_digit() {
+                pos += 1;
+            }
+        }
+    }
+    let text: String = chars[start..pos].iter().collect();
+    if is_floating {
+        if pos < chars.len() && matches!(chars[pos], 'd' | 'D' | 'f' | 'F') {
+            pos += 1;
+        }
+        let value = text
+            .parse::<f64>()
+            .map_err(|_| lex_error(format!("Invalid floating point literal {}", text), start))?;
+        return Ok((Token::DoubleLiteral(value), pos));
+    }
+    if pos < chars.len() && (chars[pos] == 'l' || chars[pos] == 'L') {
+        pos += 1;
+    }
+    let value = text
+        .parse::<i64>()
+        .map_err(|_| lex_error(format!("Invalid integer literal {}", text), start))?;
+    Ok((Token::LongLiteral(value), pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Token> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_recognizes_keywords_case_insensitively() {
+        assert_eq!(
+            tokens("a is not null and b Between 1 AND 2"),
+            vec![
+                Token::Identifier("a".to_string()),
+                Token::Is,
+                Token::Not,
+                Token::Null,
+                Token::And,
+                Token::Identifier("b".to_string()),
+                Token::Between,
+                Token::LongLiteral(1),
+                Token::And,
+                Token::LongLiteral(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_reads_literals() {
+        assert_eq!(
+            tokens("'it''s' 10L 1.5 .5e1 \"my key\""),
+            vec![
+                Token::StringLiteral("it's".to_string()),
+                Token::LongLiteral(10),
+                Token::DoubleLiteral(1.5),
+                Token::DoubleLiteral(5.0),
+                Token::Identifier("my key".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_reads_operators() {
+        assert_eq!(
+            tokens("<> != <= >= < > ="),
+            vec![
+                Token::NotEqual,
+                Token::NotEqual,
+                Token::LessThanOrEqual,
+                Token::GreaterThanOrEqual,
+                Token::LessThan,
+                Token::GreaterThan,
+                Token::Equal,
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_rejects_unterminated_string() {
+        assert!(tokenize("a = 'abc").is_err());
+        assert!(tokenize("a # 1").is_err());
+    }
+}
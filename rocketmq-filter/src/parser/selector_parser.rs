@@ -0,0 +1,547 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+use crate::expression::sql_expression::ComparisonOperator;
+use crate::expression::sql_expression::LikePattern;
+use crate::expression::sql_expression::Literal;
+use crate::expression::sql_expression::SqlExpression;
+use crate::expression::sql_expression::StringOperator;
+use crate::parser::lexer::tokenize;
+use crate::parser::lexer::SpannedToken;
+use crate::parser::lexer::Token;
+
+/// Recursive descent parser for SQL92 message selectors.
+///
+/// The grammar, from the lowest to the highest precedence:
+///
+/// ```text
+/// or_expr         := and_expr ( OR and_expr )*
+/// and_expr        := equality_expr ( AND equality_expr )*
+/// equality_expr   := comparison_expr ( ( '=' | '<>' ) comparison_expr | IS [NOT] NULL )*
+/// comparison_expr := unary_expr ( ( '>' | '>=' | '<' | '<=' ) unary_expr
+///                    | [NOT] LIKE string [ESCAPE string]
+///                    | [NOT] BETWEEN unary_expr AND unary_expr
+///                    | [NOT] IN '(' string ( ',' string )* ')'
+///                    | [NOT] ( CONTAINS | STARTSWITH | ENDSWITH ) string )*
+/// unary_expr      := '+' unary_expr | '-' unary_expr | NOT unary_expr | primary_expr
+/// primary_expr    := literal | identifier | '(' or_expr ')'
+/// ```
+pub struct SelectorParser {
+    tokens: Vec<SpannedToken>,
+    index: usize,
+    input_len: usize,
+}
+
+impl SelectorParser {
+    /// Parses `sql` into a boolean expression.
+    pub fn parse(sql: &str) -> RocketMQResult<SqlExpression> {
+        let mut parser = SelectorParser {
+            tokens: tokenize(sql)?,
+            index: 0,
+            input_len: sql.chars().count(),
+        };
+        if parser.tokens.is_empty() {
+            return Err(RocketmqError::MQFilterError(
+                "Expression can't be empty".to_string(),
+            ));
+        }
+        let expression = parser.or_expression()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(format!("Unexpected token {}", token)));
+        }
+        as_boolean_expression(expression)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index).map(|spanned| &spanned.token)
+    }
+
+    fn peek_next(&self) -> Option<&Token> {
+        self.tokens
+            .get(self.index + 1)
+            .map(|spanned| &spanned.token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.index)
+            .map(|spanned| spanned.token.clone());
+        if token.is_some() {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> RocketMQResult<()> {
+        if self.eat(&token) {
+            Ok(())
+        } else {
+            Err(self.unexpected(format!("{}", token).as_str()))
+        }
+    }
+
+    fn error_at(&self, message: String) -> RocketmqError {
+        let position = self
+            .tokens
+            .get(self.index)
+            .map_or(self.input_len, |spanned| spanned.position);
+        RocketmqError::MQFilterError(format!("{} at position {}", message, position))
+    }
+
+    fn unexpected(&self, expected: &str) -> RocketmqError {
+        match self.peek() {
+            None => self.error_at(format!("Expected {}, but reached the end", expected)),
+            Some(token) => self.error_at(format!("Expected {}, but found {}", expected, token)),
+        }
+    }
+
+    fn or_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        let mut left = self.and_expression()?;
+        while self.eat(&Token::Or) {
+            let right = self.and_expression()?;
+            left = SqlExpression::Or(
+                Box::new(as_boolean_expression(left)?),
+                Box::new(as_boolean_expression(right)?),
+            );
+        }
+        Ok(left)
+    }
+
+    fn and_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        let mut left = self.equality_expression()?;
+        while self.eat(&Token::And) {
+            let right = self.equality_expression()?;
+            left = SqlExpression::And(
+                Box::new(as_boolean_expression(left)?),
+                Box::new(as_boolean_expression(right)?),
+            );
+        }
+        Ok(left)
+    }
+
+    fn equality_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        let mut left = self.comparison_expression()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Equal) => ComparisonOperator::Equal,
+                Some(Token::NotEqual) => ComparisonOperator::NotEqual,
+                Some(Token::Is) => {
+                    self.index += 1;
+                    let not = self.eat(&Token::Not);
+                    self.expect(Token::Null)?;
+                    left = SqlExpression::IsNull {
+                        operand: Box::new(left),
+                        not,
+                    };
+                    continue;
+                }
+                _ => return Ok(left),
+            };
+            self.index += 1;
+            let right = self.comparison_expression()?;
+            check_equal_operand(&left)?;
+            check_equal_operand(&right)?;
+            left = SqlExpression::Comparison {
+                operator,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+    }
+
+    fn comparison_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        let mut left = self.unary_expression()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::GreaterThan) => Some(ComparisonOperator::GreaterThan),
+                Some(Token::GreaterThanOrEqual) => Some(ComparisonOperator::GreaterThanOrEqual),
+                Some(Token::LessThan) => Some(ComparisonOperator::LessThan),
+                Some(Token::LessThanOrEqual) => Some(ComparisonOperator::LessThanOrEqual),
+                _ => None,
+            };
+            if let Some(operator) = operator {
+                self.index += 1;
+                let right = self.unary_expression()?;
+                left = create_less_than_style(operator, left, right)?;
+                continue;
+            }
+
+            let not = match (self.peek(), self.peek_next()) {
+                (
+                    Some(Token::Not),
+                    Some(
+                        Token::Like
+                        | Token::Between
+                        | Token::In
+                        | Token::Contains
+                        | Token::StartsWith
+                        | Token::EndsWith,
+                    ),
+                ) => {
+                    self.index += 1;
+                    true
+                }
+                _ => false,
+            };
+            match self.peek() {
+                Some(Token::Like) => {
+                    self.index += 1;
+                    let pattern = self.string_literal()?;
+                    let escape = if self.eat(&Token::Escape) {
+                        let escape = self.string_literal()?;
+                        let mut chars = escape.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => Some(c),
+                            _ => {
+                                return Err(self.error_at(format!(
+                                    "ESCAPE string literal must be exactly one character: '{}'",
+                                    escape
+                                )))
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    check_property_operand(&left, "LIKE")?;
+                    left = SqlExpression::Like {
+                        operand: Box::new(left),
+                        pattern: LikePattern::new(pattern, escape),
+                        not,
+                    };
+                }
+                Some(Token::Between) => {
+                    self.index += 1;
+                    let low = self.unary_expression()?;
+                    self.expect(Token::And)?;
+                    let high = self.unary_expression()?;
+                    left = if not {
+                        SqlExpression::Or(
+                            Box::new(create_less_than_style(
+                                ComparisonOperator::LessThan,
+                                left.clone(),
+                                low,
+                            )?),
+                            Box::new(create_less_than_style(
+                                ComparisonOperator::GreaterThan,
+                                left,
+                                high,
+                            )?),
+                        )
+                    } else {
+                        SqlExpression::And(
+                            Box::new(create_less_than_style(
+                                ComparisonOperator::GreaterThanOrEqual,
+                                left.clone(),
+                                low,
+                            )?),
+                            Box::new(create_less_than_style(
+                                ComparisonOperator::LessThanOrEqual,
+                                left,
+                                high,
+                            )?),
+                        )
+                    };
+                }
+                Some(Token::In) => {
+                    self.index += 1;
+                    self.expect(Token::LeftParen)?;
+                    let mut values = vec![self.string_literal()?];
+                    while self.eat(&Token::Comma) {
+                        values.push(self.string_literal()?);
+                    }
+                    self.expect(Token::RightParen)?;
+                    check_property_operand(&left, "IN")?;
+                    left = SqlExpression::In {
+                        operand: Box::new(left),
+                        values,
+                        not,
+                    };
+                }
+                Some(Token::Contains | Token::StartsWith | Token::EndsWith) => {
+                    let operator = match self.next() {
+                        Some(Token::Contains) => StringOperator::Contains,
+                        Some(Token::StartsWith) => StringOperator::StartsWith,
+                        _ => StringOperator::EndsWith,
+                    };
+                    let value = self.string_literal()?;
+                    check_property_operand(&left, operator.symbol())?;
+                    left = SqlExpression::StringMatch {
+                        operator,
+                        operand: Box::new(left),
+                        value,
+                        not,
+                    };
+                }
+                _ => return Ok(left),
+            }
+        }
+    }
+
+    fn unary_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        match self.peek() {
+            Some(Token::Plus) => {
+                self.index += 1;
+                self.unary_expression()
+            }
+            Some(Token::Minus) => {
+                self.index += 1;
+                let operand = self.unary_expression()?;
+                match operand {
+                    SqlExpression::Constant(Literal::Long(value)) => {
+                        Ok(SqlExpression::Constant(Literal::Long(value.wrapping_neg())))
+                    }
+                    SqlExpression::Constant(Literal::Double(value)) => {
+                        Ok(SqlExpression::Constant(Literal::Double(-value)))
+                    }
+                    SqlExpression::Property(_) | SqlExpression::Negate(_) => {
+                        Ok(SqlExpression::Negate(Box::new(operand)))
+                    }
+                    other => Err(self.error_at(format!("Cannot negate {}", other))),
+                }
+            }
+            Some(Token::Not) => {
+                self.index += 1;
+                let operand = self.unary_expression()?;
+                Ok(SqlExpression::Not(Box::new(as_boolean_expression(
+                    operand,
+                )?)))
+            }
+            _ => self.primary_expression(),
+        }
+    }
+
+    fn primary_expression(&mut self) -> RocketMQResult<SqlExpression> {
+        let expression = match self.peek() {
+            Some(Token::LeftParen) => {
+                self.index += 1;
+                let expression = self.or_expression()?;
+                self.expect(Token::RightParen)?;
+                return Ok(expression);
+            }
+            Some(Token::StringLiteral(value)) => {
+                SqlExpression::Constant(Literal::String(value.clone()))
+            }
+            Some(Token::LongLiteral(value)) => SqlExpression::Constant(Literal::Long(*value)),
+            Some(Token::DoubleLiteral(value)) => SqlExpression::Constant(Literal::Double(*value)),
+            Some(Token::True) => SqlExpression::Constant(Literal::Boolean(true)),
+            Some(Token::False) => SqlExpression::Constant(Literal::Boolean(false)),
+            Some(Token::Null) => SqlExpression::Constant(Literal::Null),
+            Some(Token::Identifier(name)) => {
+                SqlExpression::Property(CheetahString::from_slice(name.as_str()))
+            }
+            _ => return Err(self.unexpected("a literal, property or '('")),
+        };
+        self.index += 1;
+        Ok(expression)
+    }
+
+    fn string_literal(&mut self) -> RocketMQResult<String> {
+        match self.peek() {
+            Some(Token::StringLiteral(value)) => {
+                let value = value.clone();
+                self.index += 1;
+                Ok(value)
+            }
+            _ => Err(self.unexpected("a string literal")),
+        }
+    }
+}
+
+fn as_boolean_expression(expression: SqlExpression) -> RocketMQResult<SqlExpression> {
+    match expression {
+        SqlExpression::Property(_) => Ok(SqlExpression::BooleanCast(Box::new(expression))),
+        expression if expression.is_boolean() => Ok(expression),
+        expression => Err(RocketmqError::MQFilterError(format!(
+            "Expression will not result in a boolean value: {}",
+            expression
+        ))),
+    }
+}
+
+fn check_property_operand(expression: &SqlExpression, operator: &str) -> RocketMQResult<()> {
+    match expression {
+        SqlExpression::Property(_) => Ok(()),
+        other => Err(RocketmqError::MQFilterError(format!(
+            "Expected a property for {} expression, but found {}",
+            operator, other
+        ))),
+    }
+}
+
+fn check_equal_operand(expression: &SqlExpression) -> RocketMQResult<()> {
+    match expression {
+        SqlExpression::Constant(Literal::Null) => Err(RocketmqError::MQFilterError(
+            "'NULL' cannot be compared, use IS NULL instead".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Only properties and numeric constants can be used with `>`, `>=`, `<` and `<=`.
+fn check_less_than_operand(expression: &SqlExpression) -> RocketMQResult<()> {
+    match expression {
+        SqlExpression::Constant(Literal::Long(_) | Literal::Double(_))
+        | SqlExpression::Property(_)
+        | SqlExpression::Negate(_) => Ok(()),
+        other => Err(RocketmqError::MQFilterError(format!(
+            "Value '{}' cannot be compared",
+            other
+        ))),
+    }
+}
+
+fn create_less_than_style(
+    operator: ComparisonOperator,
+    left: SqlExpression,
+    right: SqlExpression,
+) -> RocketMQResult<SqlExpression> {
+    check_less_than_operand(&left)?;
+    check_less_than_operand(&right)?;
+    Ok(SqlExpression::Comparison {
+        operator,
+        left: Box::new(left),
+        right: Box::new(right),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::expression::evaluation_context::EvaluationContext;
+
+    struct MapContext(HashMap<CheetahString, CheetahString>);
+
+    impl EvaluationContext for MapContext {
+        fn get(&self, name: &str) -> Option<&CheetahString> {
+            self.0.get(name)
+        }
+
+        fn key_values(&self) -> Option<HashMap<CheetahString, CheetahString>> {
+            Some(self.0.clone())
+        }
+    }
+
+    fn eval(sql: &str, pairs: &[(&str, &str)]) -> bool {
+        let context = MapContext(
+            pairs
+                .iter()
+                .map(|(k, v)| (CheetahString::from(*k), CheetahString::from(*v)))
+                .collect(),
+        );
+        SelectorParser::parse(sql)
+            .unwrap()
+            .matches(&context)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_comparisons() {
+        assert!(eval("a = 'x'", &[("a", "x")]));
+        assert!(eval("a <> 'x'", &[("a", "y")]));
+        assert!(eval(
+            "a > 1 AND a >= 2 AND a < 3.5 AND a <= 2",
+            &[("a", "2")]
+        ));
+        assert!(eval("a = -2", &[("a", "-2")]));
+        assert!(!eval("a = 'x'", &[]));
+    }
+
+    #[test]
+    fn parse_between() {
+        assert!(eval("a BETWEEN 1 AND 3", &[("a", "3")]));
+        assert!(!eval("a BETWEEN 1 AND 3", &[("a", "4")]));
+        assert!(eval("a NOT BETWEEN 1 AND 3", &[("a", "4")]));
+        assert!(!eval("a NOT BETWEEN 1 AND 3", &[]));
+    }
+
+    #[test]
+    fn parse_in() {
+        assert!(eval("TAGS IN ('a', 'b')", &[("TAGS", "b")]));
+        assert!(!eval("TAGS IN ('a', 'b')", &[("TAGS", "c")]));
+        assert!(eval("TAGS NOT IN ('a', 'b')", &[("TAGS", "c")]));
+        assert!(!eval("TAGS NOT IN ('a', 'b')", &[]));
+    }
+
+    #[test]
+    fn parse_null_checks() {
+        assert!(eval("a IS NULL", &[]));
+        assert!(!eval("a IS NULL", &[("a", "1")]));
+        assert!(eval("a IS NOT NULL", &[("a", "1")]));
+    }
+
+    #[test]
+    fn parse_like_and_string_functions() {
+        assert!(eval("a LIKE 'ab%'", &[("a", "abc")]));
+        assert!(eval("a NOT LIKE 'ab_'", &[("a", "abcd")]));
+        assert!(eval("a LIKE 'a\\_%' ESCAPE '\\'", &[("a", "a_b")]));
+        assert!(!eval("a LIKE 'a\\_%' ESCAPE '\\'", &[("a", "ab")]));
+        assert!(eval("a CONTAINS 'bc'", &[("a", "abcd")]));
+        assert!(eval(
+            "a STARTSWITH 'ab' AND a ENDSWITH 'cd'",
+            &[("a", "abcd")]
+        ));
+        assert!(eval("a NOT CONTAINS 'x'", &[("a", "abcd")]));
+    }
+
+    #[test]
+    fn parse_boolean_logic_and_precedence() {
+        assert!(eval("a = 1 OR b = 1 AND c = 1", &[("a", "1")]));
+        assert!(!eval("(a = 1 OR b = 1) AND c = 1", &[("a", "1")]));
+        assert!(eval("NOT (a = 1) AND TRUE", &[("a", "2")]));
+        assert!(eval("a IS NULL OR a > 10", &[]));
+        assert!(!eval("FALSE", &[]));
+        assert!(eval("flag", &[("flag", "true")]));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_expressions() {
+        assert!(SelectorParser::parse("").is_err());
+        assert!(SelectorParser::parse("a =").is_err());
+        assert!(SelectorParser::parse("a = 1 b").is_err());
+        assert!(SelectorParser::parse("(a = 1").is_err());
+        assert!(SelectorParser::parse("'abc'").is_err());
+        assert!(SelectorParser::parse("a > 'abc'").is_err());
+        assert!(SelectorParser::parse("a = NULL").is_err());
+        assert!(SelectorParser::parse("1 IN ('a')").is_err());
+        assert!(SelectorParser::parse("a IN (1, 2)").is_err());
+        assert!(SelectorParser::parse("a LIKE 'x' ESCAPE 'ab'").is_err());
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let expression =
+            SelectorParser::parse("a IN ('x', 'y''s') AND b NOT LIKE '%z' OR c IS NOT NULL")
+                .unwrap();
+        let reparsed = SelectorParser::parse(expression.to_string().as_str()).unwrap();
+        assert_eq!(expression, reparsed);
+    }
+}
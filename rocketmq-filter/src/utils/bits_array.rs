@@ -0,0 +1,129 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+/// A fixed length bit set backed by a byte array, bit `0` being the lowest bit of the first
+/// byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitsArray {
+    bytes: Vec<u8>,
+    bit_length: usize,
+}
+
+impl BitsArray {
+    /// Creates a zeroed bits array holding `bit_length` bits.
+    pub fn create(bit_length: usize) -> Self {
+        BitsArray {
+            bytes: vec![0; bit_length.div_ceil(8)],
+            bit_length,
+        }
+    }
+
+    /// Wraps `bytes`, every bit of them being addressable.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let bit_length = bytes.len() * 8;
+        BitsArray { bytes, bit_length }
+    }
+
+    pub fn bit_length(&self) -> usize {
+        self.bit_length
+    }
+
+    pub fn byte_length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn set_bit(&mut self, bit_pos: usize, set: bool) -> RocketMQResult<()> {
+        self.check_bit_position(bit_pos)?;
+        let mask = 1u8 << (bit_pos % 8);
+        if set {
+            self.bytes[bit_pos / 8] |= mask;
+        } else {
+            self.bytes[bit_pos / 8] &= !mask;
+        }
+        Ok(())
+    }
+
+    pub fn get_bit(&self, bit_pos: usize) -> RocketMQResult<bool> {
+        self.check_bit_position(bit_pos)?;
+        Ok(self.bytes[bit_pos / 8] & (1u8 << (bit_pos % 8)) != 0)
+    }
+
+    fn check_bit_position(&self, bit_pos: usize) -> RocketMQResult<()> {
+        if bit_pos >= self.bit_length {
+            return Err(RocketmqError::IllegalArgument(format!(
+                "BitPos {} is greater than or equal to bitLength {}",
+                bit_pos, self.bit_length
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Display for BitsArray {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, byte) in self.bytes.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:08b}", byte.reverse_bits())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_and_get_bit() {
+        let mut bits = BitsArray::create(12);
+        assert_eq!(bits.byte_length(), 2);
+        bits.set_bit(0, true).unwrap();
+        bits.set_bit(9, true).unwrap();
+        assert!(bits.get_bit(0).unwrap());
+        assert!(!bits.get_bit(1).unwrap());
+        assert!(bits.get_bit(9).unwrap());
+        assert_eq!(bits.bytes(), &[0b0000_0001, 0b0000_0010]);
+
+        bits.set_bit(9, false).unwrap();
+        assert!(!bits.get_bit(9).unwrap());
+        assert_eq!(bits.to_string(), "10000000 00000000");
+    }
+
+    #[test]
+    fn out_of_range_bit_is_rejected() {
+        let mut bits = BitsArray::create(12);
+        assert!(bits.set_bit(12, true).is_err());
+        assert!(bits.get_bit(16).is_err());
+        assert_eq!(BitsArray::from_bytes(vec![0; 8]).bit_length(), 64);
+    }
+}
@@ -14,6 +14,14 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+
+use crate::utils::bits_array::BitsArray;
 use crate::utils::bloom_filter_data::BloomFilterData;
 
 #[derive(Clone, Copy)]
@@ -48,8 +56,8 @@ impl BloomFilter {
         }
 
         let error_rate = f as f64 / 100.0;
-        let k =
-            (0.5f64.log2() * (n as f64 * error_rate.ln()).abs() / error_rate.ln()).ceil() as i32;
+        // k = log0.5(f), the optimal hash function num for the error rate
+        let k = (error_rate.ln() / 0.5f64.ln()).ceil() as i32;
 
         if k < 1 {
             return Err(
@@ -88,4 +96,171 @@ impl BloomFilter {
             None => false,
         }
     }
+
+    /// Calculates the `k` bit positions of `str`, using double hashing on top of murmur3.
+    pub fn calc_bit_positions(&self, str: &str) -> Vec<i32> {
+        let hash1 = murmur3_32(str.as_bytes(), 0) as i32;
+        let hash2 = murmur3_32(str.as_bytes(), hash1 as u32) as i32;
+        (1..=self.k)
+            .map(|i| {
+                let mut combined_hash = hash1.wrapping_add(i.wrapping_mul(hash2));
+                // flip all the bits if it's negative (guaranteed positive number)
+                if combined_hash < 0 {
+                    combined_hash = !combined_hash;
+                }
+                combined_hash % self.m
+            })
+            .collect()
+    }
+
+    /// Calculates the bit positions of `str` and wraps them together with `m`.
+    pub fn generate(&self, str: &str) -> BloomFilterData {
+        BloomFilterData::new(self.calc_bit_positions(str), self.m as u32)
+    }
+
+    /// Sets the bits of `filter_data` in `bits`.
+    pub fn hash_to(
+        &self,
+        filter_data: &BloomFilterData,
+        bits: &mut BitsArray,
+    ) -> RocketMQResult<()> {
+        self.check_filter_data(filter_data)?;
+        self.check(bits)?;
+        for bit_pos in filter_data.bit_pos() {
+            bits.set_bit(*bit_pos as usize, true)?;
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if all bits of `filter_data` are set in `bits`. A hit may be false
+    /// positive, a miss is always accurate.
+    pub fn is_hit(&self, filter_data: &BloomFilterData, bits: &BitsArray) -> RocketMQResult<bool> {
+        self.check_filter_data(filter_data)?;
+        self.check(bits)?;
+        for bit_pos in filter_data.bit_pos() {
+            if !bits.get_bit(*bit_pos as usize)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn check_filter_data(&self, filter_data: &BloomFilterData) -> RocketMQResult<()> {
+        if !self.is_valid(Some(filter_data)) {
+            return Err(RocketmqError::IllegalArgument(format!(
+                "Bloom filter data may not belong to this filter! {:?}, {}",
+                filter_data, self
+            )));
+        }
+        Ok(())
+    }
+
+    fn check(&self, bits: &BitsArray) -> RocketMQResult<()> {
+        if bits.bit_length() != self.m as usize {
+            return Err(RocketmqError::IllegalArgument(format!(
+                "Length({}) of bits in BitsArray is not equal to {}!",
+                bits.bit_length(),
+                self.m
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Display for BloomFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "f: {}, n: {}, k: {}, m: {}",
+            self.f, self.n, self.k, self.m
+        )
+    }
+}
+
+/// MurmurHash3 x86 32-bit variant.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mix_k1 = |mut k1: u32| {
+        k1 = k1.wrapping_mul(C1);
+        k1 = k1.rotate_left(15);
+        k1.wrapping_mul(C2)
+    };
+
+    let mut h1 = seed;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in chunks.by_ref() {
+        let k1 = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        h1 ^= mix_k1(k1);
+        h1 = h1.rotate_left(13);
+        h1 = h1.wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k1 = 0u32;
+        for (index, byte) in tail.iter().enumerate() {
+            k1 |= (*byte as u32) << (8 * index);
+        }
+        h1 ^= mix_k1(k1);
+    }
+
+    h1 ^= data.len() as u32;
+    h1 ^= h1 >> 16;
+    h1 = h1.wrapping_mul(0x85eb_ca6b);
+    h1 ^= h1 >> 13;
+    h1 = h1.wrapping_mul(0xc2b2_ae35);
+    h1 ^ (h1 >> 16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_calculates_k_and_m() {
+        let bloom_filter = BloomFilter::new(20, 32).unwrap();
+        assert_eq!(bloom_filter.k(), 3);
+        assert_eq!(bloom_filter.m(), 112);
+        assert!(BloomFilter::new(0, 32).is_err());
+        assert!(BloomFilter::new(20, 0).is_err());
+    }
+
+    #[test]
+    fn murmur3_matches_reference_values() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"", 1), 0x514e_28b7);
+        assert_eq!(murmur3_32(b"hello", 0), 0x248b_fa47);
+        assert_eq!(
+            murmur3_32(b"The quick brown fox jumps over the lazy dog", 0),
+            0x2e4f_f723
+        );
+    }
+
+    #[test]
+    fn generated_data_hits_its_own_bits() {
+        let bloom_filter = BloomFilter::new(20, 64).unwrap();
+        let data = bloom_filter.generate("CID_TEST#TopicTest");
+        assert!(bloom_filter.is_valid(Some(&data)));
+        assert!(data
+            .bit_pos()
+            .iter()
+            .all(|pos| *pos >= 0 && *pos < bloom_filter.m()));
+
+        let mut bits = BitsArray::create(bloom_filter.m() as usize);
+        assert!(!bloom_filter.is_hit(&data, &bits).unwrap());
+        bloom_filter.hash_to(&data, &mut bits).unwrap();
+        assert!(bloom_filter.is_hit(&data, &bits).unwrap());
+    }
+
+    #[test]
+    fn mismatched_length_is_rejected() {
+        let bloom_filter = BloomFilter::new(20, 64).unwrap();
+        let data = bloom_filter.generate("CID_TEST#TopicTest");
+        let mut bits = BitsArray::create(bloom_filter.m() as usize + 8);
+        assert!(bloom_filter.hash_to(&data, &mut bits).is_err());
+        assert!(bloom_filter
+            .is_hit(&BloomFilterData::new(vec![1], 8), &bits)
+            .is_err());
+    }
 }
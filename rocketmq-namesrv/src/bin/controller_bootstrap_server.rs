@@ -0,0 +1,118 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::path::PathBuf;
+use std::process::exit;
+
+use clap::Parser;
+use rocketmq_common::common::controller::controller_config::ControllerConfig;
+use rocketmq_common::common::mq_version::RocketMqVersion;
+use rocketmq_common::common::server::config::ServerConfig;
+use rocketmq_common::EnvUtils::EnvUtils;
+use rocketmq_common::ParseConfigFile;
+use rocketmq_error::RocketMQResult;
+use rocketmq_namesrv::controller::ControllerBootstrap;
+use rocketmq_remoting::protocol::remoting_command;
+use rocketmq_rust::rocketmq;
+use tracing::info;
+use tracing::warn;
+
+#[rocketmq::main]
+async fn main() -> RocketMQResult<()> {
+    // Initialize the logger
+    rocketmq_common::log::init_logger();
+    // parse command line arguments
+    let args = Args::parse();
+
+    EnvUtils::put_property(
+        remoting_command::REMOTING_VERSION_KEY,
+        RocketMqVersion::CURRENT_VERSION.to_string(),
+    );
+
+    let home = EnvUtils::get_rocketmq_home();
+    info!("Rocketmq(Rust) home: {}", home);
+
+    let controller_config = match args.config_file {
+        Some(config_file) if config_file.exists() && config_file.is_file() => {
+            let config = ParseConfigFile::parse_config_file::<ControllerConfig>(config_file)?;
+            info!("Parsed controller config: {:?}", config);
+            config
+        }
+        Some(config_file) => {
+            eprintln!("Config file not found: {:?}", config_file);
+            exit(1);
+        }
+        None => {
+            warn!("Config file not found, using default");
+            ControllerConfig::default()
+        }
+    };
+
+    info!(
+        "Rocketmq controller(Rust) running on: {}:{}",
+        args.ip, args.port
+    );
+    ControllerBootstrap::new(
+        controller_config,
+        ServerConfig {
+            listen_port: args.port,
+            bind_address: args.ip,
+            ..ServerConfig::default()
+        },
+    )
+    .boot()
+    .await;
+
+    Ok(())
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    author = "mxsm",
+    version = "0.1.0",
+    about = "RocketMQ Controller(Rust)"
+)]
+struct Args {
+    /// rocketmq controller port
+    #[arg(
+        short,
+        long,
+        value_name = "PORT",
+        default_missing_value = "9878",
+        default_value = "9878",
+        required = false
+    )]
+    port: u32,
+
+    /// rocketmq controller ip
+    #[arg(
+        short,
+        long,
+        value_name = "IP",
+        default_value = "0.0.0.0",
+        required = false
+    )]
+    ip: String,
+
+    /// Controller config properties file
+    #[arg(
+        short,
+        long,
+        value_name = "CONFIG FILE",
+        default_missing_value = "None"
+    )]
+    config_file: Option<PathBuf>,
+}
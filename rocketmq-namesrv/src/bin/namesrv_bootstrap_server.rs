@@ -19,6 +19,7 @@ use std::path::PathBuf;
 use std::process::exit;
 
 use clap::Parser;
+use rocketmq_common::common::controller::controller_config::ControllerConfig;
 use rocketmq_common::common::mq_version::RocketMqVersion;
 use rocketmq_common::common::namesrv::namesrv_config::NamesrvConfig;
 use rocketmq_common::common::server::config::ServerConfig;
@@ -57,13 +58,21 @@ async fn main() -> RocketMQResult<()> {
         None
     };
 
-    let namesrv_config = if let Some(config_file) = config_file {
-        let config = ParseConfigFile::parse_config_file::<NamesrvConfig>(config_file)?;
+    let (namesrv_config, controller_config) = if let Some(config_file) = config_file {
+        let config = ParseConfigFile::parse_config_file::<NamesrvConfig>(config_file.clone())?;
         info!("Parsed namesrv config: {:?}", config);
-        config
+        let controller_config = if config.enable_controller_in_namesrv {
+            let controller_config =
+                ParseConfigFile::parse_config_file::<ControllerConfig>(config_file)?;
+            info!("Parsed controller config: {:?}", controller_config);
+            controller_config
+        } else {
+            ControllerConfig::default()
+        };
+        (config, controller_config)
     } else {
         warn!("Config file not found, using default");
-        NamesrvConfig::default()
+        (NamesrvConfig::default(), ControllerConfig::default())
     };
 
     info!(
@@ -72,11 +81,13 @@ async fn main() -> RocketMQResult<()> {
     );
     Builder::new()
         .set_name_server_config(namesrv_config)
+        .set_controller_config(controller_config)
         .set_server_config(ServerConfig {
             listen_port: args.port,
             bind_address: args.ip,
+            ..ServerConfig::default()
         })
-        .build()
+        .build()?
         .boot()
         .await;
 
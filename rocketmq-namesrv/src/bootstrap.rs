@@ -15,13 +15,19 @@
  * limitations under the License.
  */
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use rocketmq_acl::acl_server_rpc_hook::AclServerRPCHook;
+use rocketmq_acl::plain::plain_access_validator::PlainAccessValidator;
+use rocketmq_acl::plain::plain_permission_manager::DEFAULT_PLAIN_ACL_FILE;
+use rocketmq_common::common::controller::controller_config::ControllerConfig;
 use rocketmq_common::common::namesrv::namesrv_config::NamesrvConfig;
 use rocketmq_common::common::server::config::ServerConfig;
 use rocketmq_common::utils::network_util::NetworkUtil;
+use rocketmq_error::RocketMQResult;
 use rocketmq_remoting::base::channel_event_listener::ChannelEventListener;
 use rocketmq_remoting::clients::rocketmq_default_impl::RocketmqDefaultClient;
 use rocketmq_remoting::clients::RemotingClient;
@@ -33,8 +39,13 @@ use rocketmq_runtime::RocketMQRuntime;
 use rocketmq_rust::wait_for_signal;
 use rocketmq_rust::ArcMut;
 use tokio::sync::broadcast;
+use tracing::error;
 use tracing::info;
 
+use crate::controller::ControllerManager;
+use crate::controller::ControllerRequestProcessor;
+#[cfg(feature = "metrics")]
+use crate::metrics::NamesrvMetricsManager;
 use crate::processor::ClientRequestProcessor;
 use crate::processor::NameServerRequestProcessor;
 use crate::route_info::broker_housekeeping_service::BrokerHousekeepingService;
@@ -48,6 +59,7 @@ pub struct NameServerBootstrap {
 pub struct Builder {
     name_server_config: Option<NamesrvConfig>,
     server_config: Option<ServerConfig>,
+    controller_config: Option<ControllerConfig>,
 }
 
 struct NameServerRuntime {
@@ -84,7 +96,25 @@ impl NameServerRuntime {
         let (notify_conn_disconnect, _) = broadcast::channel::<SocketAddr>(100);
         let receiver = notify_conn_disconnect.subscribe();
         let request_processor = self.init_processors(receiver);
-        let server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        let mut server = RocketMQServer::new(Arc::new(self.inner.server_config.clone()));
+        if self.inner.name_server_config.acl_enable {
+            let acl_file = PathBuf::from(&self.inner.name_server_config.rocketmq_home)
+                .join(DEFAULT_PLAIN_ACL_FILE);
+            match PlainAccessValidator::new(&acl_file) {
+                Ok(validator) => {
+                    info!(
+                        "The name server enables acl, acl file: {}",
+                        acl_file.display()
+                    );
+                    server.register_rpc_hook(Box::new(AclServerRPCHook::new(Arc::new(validator))));
+                }
+                Err(e) => panic!(
+                    "Failed to initialize acl from {}: {}",
+                    acl_file.display(),
+                    e
+                ),
+            }
+        }
         let channel_event_listener = self
             .inner
             .broker_housekeeping_service
@@ -104,6 +134,11 @@ impl NameServerRuntime {
             .update_name_server_address_list(vec![namesrv])
             .await;
         self.inner.remoting_client.start(weak_arc_mut).await;
+        if let Some(controller_manager) = self.inner.controller_manager.as_ref() {
+            controller_manager.start();
+        }
+        #[cfg(feature = "metrics")]
+        self.start_metrics_exporter().await;
         info!("Rocketmq NameServer(Rust) started");
 
         tokio::select! {
@@ -114,6 +149,24 @@ impl NameServerRuntime {
         }
     }
 
+    #[cfg(feature = "metrics")]
+    async fn start_metrics_exporter(&mut self) {
+        let host = self
+            .inner
+            .name_server_config
+            .metrics_prom_exporter_host
+            .clone();
+        let port = self.inner.name_server_config.metrics_prom_exporter_port;
+        if let Some(metrics_manager) = self.inner.metrics_manager.as_mut() {
+            if let Err(e) = metrics_manager.start(&host, port).await {
+                error!(
+                    "Start name server metrics exporter on port {} failed: {}",
+                    port, e
+                );
+            }
+        }
+    }
+
     #[inline]
     fn shutdown(&mut self) {
         if let Some(runtime) = self.name_server_runtime.take() {
@@ -123,6 +176,13 @@ impl NameServerRuntime {
             .route_info_manager_mut()
             .un_register_service
             .shutdown();
+        if let Some(controller_manager) = self.inner.controller_manager.as_ref() {
+            controller_manager.shutdown();
+        }
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_manager) = self.inner.metrics_manager.as_mut() {
+            metrics_manager.shutdown();
+        }
         info!("Rocketmq NameServer(Rust) gracefully shutdown completed");
     }
 
@@ -155,6 +215,11 @@ impl NameServerRuntime {
         NameServerRequestProcessor {
             client_request_processor: ArcMut::new(client_request_processor),
             default_request_processor: ArcMut::new(default_request_processor),
+            controller_request_processor: self
+                .inner
+                .controller_manager
+                .clone()
+                .map(|manager| ArcMut::new(ControllerRequestProcessor::new(manager))),
         }
     }
 }
@@ -181,6 +246,7 @@ impl Builder {
         Builder {
             name_server_config: None,
             server_config: None,
+            controller_config: None,
         }
     }
 
@@ -197,15 +263,43 @@ impl Builder {
     }
 
     #[inline]
-    pub fn build(self) -> NameServerBootstrap {
+    pub fn set_controller_config(mut self, controller_config: ControllerConfig) -> Self {
+        self.controller_config = Some(controller_config);
+        self
+    }
+
+    #[inline]
+    pub fn build(self) -> RocketMQResult<NameServerBootstrap> {
         let name_server_config = self.name_server_config.unwrap_or_default();
         let runtime = RocketMQRuntime::new_multi(10, "namesrv-thread");
-        let tokio_client_config = TokioClientConfig::default();
+        let server_config = self.server_config.unwrap_or_default();
+        let tls_config = server_config.tls_config().clone();
+        let tokio_client_config = TokioClientConfig {
+            use_tls: tls_config.tls_enable,
+            tls_config,
+            ..TokioClientConfig::default()
+        };
         let remoting_client = ArcMut::new(RocketmqDefaultClient::new(
             Arc::new(tokio_client_config.clone()),
             DefaultRemotingRequestProcessor,
-        ));
-        let server_config = self.server_config.unwrap_or_default();
+        )?);
+        let controller_manager = if name_server_config.enable_controller_in_namesrv {
+            let controller_address = CheetahString::from_string(format!(
+                "{}:{}",
+                NetworkUtil::get_local_address().unwrap(),
+                server_config.listen_port
+            ));
+            let controller_manager = Arc::new(ControllerManager::new(
+                self.controller_config.unwrap_or_default(),
+                controller_address,
+            ));
+            if !controller_manager.initialize() {
+                error!("controller manager initialize failed");
+            }
+            Some(controller_manager)
+        } else {
+            None
+        };
         let mut inner = ArcMut::new(NameServerRuntimeInner {
             name_server_config,
             tokio_client_config,
@@ -214,6 +308,9 @@ impl Builder {
             kvconfig_manager: None,
             remoting_client,
             broker_housekeeping_service: None,
+            controller_manager,
+            #[cfg(feature = "metrics")]
+            metrics_manager: None,
         });
 
         let route_info_manager = RouteInfoManager::new(inner.clone());
@@ -223,14 +320,23 @@ impl Builder {
         inner.route_info_manager = Some(route_info_manager);
         inner.broker_housekeeping_service =
             Some(Arc::new(BrokerHousekeepingService::new(inner.clone())));
+        #[cfg(feature = "metrics")]
+        if inner.name_server_config.metrics_exporter_type.is_enable() {
+            let node_id = format!(
+                "{}:{}",
+                NetworkUtil::get_local_address().unwrap_or_default(),
+                inner.server_config.listen_port
+            );
+            inner.metrics_manager = Some(NamesrvMetricsManager::new(&inner, node_id));
+        }
 
-        NameServerBootstrap {
+        Ok(NameServerBootstrap {
             name_server_runtime: NameServerRuntime {
                 name_server_runtime: Some(runtime),
                 inner,
                 shutdown_rx: None,
             },
-        }
+        })
     }
 }
 
@@ -242,6 +348,9 @@ pub(crate) struct NameServerRuntimeInner {
     kvconfig_manager: Option<KVConfigManager>,
     remoting_client: ArcMut<RocketmqDefaultClient>,
     broker_housekeeping_service: Option<Arc<BrokerHousekeepingService>>,
+    controller_manager: Option<Arc<ControllerManager>>,
+    #[cfg(feature = "metrics")]
+    metrics_manager: Option<NamesrvMetricsManager>,
 }
 
 impl NameServerRuntimeInner {
@@ -301,6 +410,12 @@ impl NameServerRuntimeInner {
             .expect("route_info_manager is None")
     }
 
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn metrics_manager(&self) -> Option<&NamesrvMetricsManager> {
+        self.metrics_manager.as_ref()
+    }
+
     #[inline]
     pub fn kvconfig_manager(&self) -> &KVConfigManager {
         self.kvconfig_manager
@@ -0,0 +1,77 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::controller::controller_config::ControllerConfig;
+use rocketmq_common::common::server::config::ServerConfig;
+use rocketmq_common::utils::network_util::NetworkUtil;
+use rocketmq_remoting::remoting_server::server::RocketMQServer;
+use tracing::error;
+use tracing::info;
+
+pub use self::controller_manager::ControllerManager;
+pub use self::controller_request_processor::ControllerRequestProcessor;
+
+mod broker_heartbeat_manager;
+mod controller_manager;
+mod controller_request_processor;
+mod controller_result;
+mod elect_policy;
+mod replicas_info_manager;
+
+/// Starts a standalone controller, which is not embedded in a name server.
+pub struct ControllerBootstrap {
+    controller_config: ControllerConfig,
+    server_config: ServerConfig,
+}
+
+impl ControllerBootstrap {
+    pub fn new(controller_config: ControllerConfig, server_config: ServerConfig) -> Self {
+        Self {
+            controller_config,
+            server_config,
+        }
+    }
+
+    pub async fn boot(self) {
+        let controller_address = CheetahString::from_string(format!(
+            "{}:{}",
+            NetworkUtil::get_local_address().unwrap(),
+            self.server_config.listen_port
+        ));
+        let controller_manager = Arc::new(ControllerManager::new(
+            self.controller_config,
+            controller_address,
+        ));
+        if !controller_manager.initialize() {
+            error!("controller manager initialize failed");
+            return;
+        }
+        controller_manager.start();
+        let server = RocketMQServer::new(Arc::new(self.server_config));
+        info!("Rocketmq Controller(Rust) started");
+        server
+            .run(
+                ControllerRequestProcessor::new(controller_manager.clone()),
+                None,
+            )
+            .await;
+        controller_manager.shutdown();
+        info!("Rocketmq Controller(Rust) shutdown");
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tracing::info;
+
+/// Identifies a broker replica registered to the controller.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct BrokerIdentityInfo {
+    pub(crate) cluster_name: CheetahString,
+    pub(crate) broker_name: CheetahString,
+    pub(crate) broker_id: i64,
+}
+
+impl BrokerIdentityInfo {
+    pub(crate) fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_id: i64,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            broker_id,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BrokerLiveInfo {
+    pub(crate) broker_addr: CheetahString,
+    pub(crate) heartbeat_timeout_millis: i64,
+    pub(crate) last_update_timestamp: i64,
+    pub(crate) epoch: i32,
+    pub(crate) max_offset: i64,
+    pub(crate) confirm_offset: i64,
+    pub(crate) election_priority: i32,
+}
+
+/// Tracks the heartbeats which brokers in controller mode send to the controller.
+pub(crate) struct BrokerHeartbeatManager {
+    broker_live_table: HashMap<BrokerIdentityInfo, BrokerLiveInfo>,
+}
+
+impl BrokerHeartbeatManager {
+    pub(crate) const DEFAULT_BROKER_CHANNEL_EXPIRED_TIME: i64 = 1000 * 10;
+
+    pub(crate) fn new() -> Self {
+        Self {
+            broker_live_table: HashMap::new(),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn on_broker_heartbeat(
+        &mut self,
+        identity: BrokerIdentityInfo,
+        broker_addr: CheetahString,
+        heartbeat_timeout_millis: Option<i64>,
+        epoch: Option<i32>,
+        max_offset: Option<i64>,
+        confirm_offset: Option<i64>,
+        election_priority: Option<i32>,
+    ) {
+        let now = get_current_millis() as i64;
+        let live_info = BrokerLiveInfo {
+            broker_addr,
+            heartbeat_timeout_millis: heartbeat_timeout_millis
+                .unwrap_or(Self::DEFAULT_BROKER_CHANNEL_EXPIRED_TIME),
+            last_update_timestamp: now,
+            epoch: epoch.unwrap_or(-1),
+            max_offset: max_offset.unwrap_or(-1),
+            confirm_offset: confirm_offset.unwrap_or(-1),
+            election_priority: election_priority.unwrap_or(i32::MAX),
+        };
+        if self
+            .broker_live_table
+            .insert(identity.clone(), live_info)
+            .is_none()
+        {
+            info!("new broker registered to controller, {:?}", identity);
+        }
+    }
+
+    pub(crate) fn is_broker_active(
+        &self,
+        cluster_name: &str,
+        broker_name: &str,
+        broker_id: i64,
+    ) -> bool {
+        self.get_broker_live_info(cluster_name, broker_name, broker_id)
+            .is_some_and(|info| {
+                info.last_update_timestamp + info.heartbeat_timeout_millis
+                    >= get_current_millis() as i64
+            })
+    }
+
+    pub(crate) fn get_broker_live_info(
+        &self,
+        cluster_name: &str,
+        broker_name: &str,
+        broker_id: i64,
+    ) -> Option<&BrokerLiveInfo> {
+        self.broker_live_table.get(&BrokerIdentityInfo::new(
+            cluster_name,
+            broker_name,
+            broker_id,
+        ))
+    }
+
+    /// Removes the brokers whose heartbeat timed out and returns them.
+    pub(crate) fn scan_not_active_broker(&mut self) -> Vec<BrokerIdentityInfo> {
+        let now = get_current_millis() as i64;
+        let expired = self
+            .broker_live_table
+            .iter()
+            .filter(|(_, info)| info.last_update_timestamp + info.heartbeat_timeout_millis < now)
+            .map(|(identity, _)| identity.clone())
+            .collect::<Vec<_>>();
+        for identity in &expired {
+            self.broker_live_table.remove(identity);
+            info!(
+                "the broker {:?} heartbeat timeout, remove it from controller",
+                identity
+            );
+        }
+        expired
+    }
+
+    pub(crate) fn remove_broker(&mut self, identity: &BrokerIdentityInfo) {
+        self.broker_live_table.remove(identity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_broker_is_not_active() {
+        let mut manager = BrokerHeartbeatManager::new();
+        manager.on_broker_heartbeat(
+            BrokerIdentityInfo::new("cluster", "broker-a", 1),
+            "127.0.0.1:10911".into(),
+            Some(-1),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(!manager.is_broker_active("cluster", "broker-a", 1));
+        assert_eq!(manager.scan_not_active_broker().len(), 1);
+        assert!(manager
+            .get_broker_live_info("cluster", "broker-a", 1)
+            .is_none());
+
+        manager.on_broker_heartbeat(
+            BrokerIdentityInfo::new("cluster", "broker-a", 2),
+            "127.0.0.1:10921".into(),
+            None,
+            Some(1),
+            Some(100),
+            None,
+            None,
+        );
+        assert!(manager.is_broker_active("cluster", "broker-a", 2));
+        assert!(manager.scan_not_active_broker().is_empty());
+    }
+}
@@ -0,0 +1,254 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::controller::controller_config::ControllerConfig;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
+use rocketmq_remoting::protocol::body::sync_state_set::SyncStateSet;
+use rocketmq_remoting::protocol::header::broker::broker_heartbeat_request_header::BrokerHeartbeatRequestHeader;
+use rocketmq_remoting::protocol::header::controller::alter_sync_state_set_header::AlterSyncStateSetRequestHeader;
+use rocketmq_remoting::protocol::header::controller::alter_sync_state_set_header::AlterSyncStateSetResponseHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdResponseHeader;
+use rocketmq_remoting::protocol::header::controller::clean_controller_broker_data_header::CleanControllerBrokerDataRequestHeader;
+use rocketmq_remoting::protocol::header::controller::elect_master_request_header::ElectMasterRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdResponseHeader;
+use rocketmq_remoting::protocol::header::controller::get_replica_info_header::GetReplicaInfoResponseHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerRequestHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerResponseHeader;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_rust::ArcMut;
+use tokio::sync::Notify;
+use tracing::info;
+use tracing::warn;
+
+use crate::controller::broker_heartbeat_manager::BrokerHeartbeatManager;
+use crate::controller::broker_heartbeat_manager::BrokerIdentityInfo;
+use crate::controller::controller_result::ControllerResult;
+use crate::controller::elect_policy::DefaultElectPolicy;
+use crate::controller::replicas_info_manager::ReplicasInfoManager;
+
+/// Manages the replicas of brokers running in controller mode: assigns broker ids, tracks
+/// broker heartbeats and sync state sets, and elects a new master when the old one is gone.
+///
+/// The controller runs as a single node, so it always considers itself the leader.
+pub struct ControllerManager {
+    controller_config: ArcMut<ControllerConfig>,
+    controller_address: CheetahString,
+    // Lock order: replicas_info_manager before heartbeat_manager.
+    replicas_info_manager: Mutex<ReplicasInfoManager>,
+    heartbeat_manager: Mutex<BrokerHeartbeatManager>,
+    elect_policy: DefaultElectPolicy,
+    shutdown: Notify,
+}
+
+impl ControllerManager {
+    pub fn new(controller_config: ControllerConfig, controller_address: CheetahString) -> Self {
+        let replicas_info_manager =
+            ReplicasInfoManager::new(Some(controller_config.controller_store_path.as_str()));
+        Self {
+            controller_config: ArcMut::new(controller_config),
+            controller_address,
+            replicas_info_manager: Mutex::new(replicas_info_manager),
+            heartbeat_manager: Mutex::new(BrokerHeartbeatManager::new()),
+            elect_policy: DefaultElectPolicy,
+            shutdown: Notify::new(),
+        }
+    }
+
+    pub fn initialize(&self) -> bool {
+        self.replicas_info_manager.lock().load()
+    }
+
+    pub fn start(self: &Arc<Self>) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            let period =
+                Duration::from_millis(this.controller_config.scan_not_active_broker_interval);
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => this.scan_not_active_broker(),
+                    _ = this.shutdown.notified() => break,
+                }
+            }
+            info!("controller scan service stopped");
+        });
+        info!(
+            "controller manager started, address: {}",
+            self.controller_address
+        );
+    }
+
+    pub fn shutdown(&self) {
+        self.shutdown.notify_waiters();
+    }
+
+    pub fn controller_config(&self) -> &ControllerConfig {
+        &self.controller_config
+    }
+
+    pub fn controller_config_mut(&self) -> &mut ControllerConfig {
+        self.controller_config.mut_from_ref()
+    }
+
+    pub fn controller_address(&self) -> &CheetahString {
+        &self.controller_address
+    }
+
+    /// Removes the brokers whose heartbeat timed out, elects new masters for the broker
+    /// groups without an alive master and refreshes the sync state sets.
+    pub(crate) fn scan_not_active_broker(&self) {
+        let mut replicas_info_manager = self.replicas_info_manager.lock();
+        let mut heartbeat_manager = self.heartbeat_manager.lock();
+        let inactive = heartbeat_manager.scan_not_active_broker();
+        if !inactive.is_empty() {
+            warn!("inactive brokers found by controller: {:?}", inactive);
+        }
+        for broker_name in replicas_info_manager.broker_sets_need_elect(&heartbeat_manager) {
+            let result = replicas_info_manager.elect_master(
+                &ElectMasterRequestHeader::of_controller_trigger(broker_name.clone()),
+                &self.elect_policy,
+                &heartbeat_manager,
+                self.controller_config.enable_elect_unclean_master,
+            );
+            info!(
+                "elect master for broker-set: {} triggered by controller, result: {:?}, new \
+                 master: {:?}",
+                broker_name, result.response_code, result.response.master_broker_id
+            );
+        }
+        replicas_info_manager.refresh_sync_state_sets(
+            &heartbeat_manager,
+            self.controller_config.ha_max_gap_not_in_sync,
+        );
+    }
+
+    pub(crate) fn on_broker_heartbeat(&self, request: BrokerHeartbeatRequestHeader) {
+        let Some(broker_id) = request.broker_id else {
+            return;
+        };
+        self.heartbeat_manager.lock().on_broker_heartbeat(
+            BrokerIdentityInfo::new(request.cluster_name, request.broker_name, broker_id),
+            request.broker_addr,
+            request.heartbeat_timeout_mills,
+            request.epoch,
+            request.max_offset,
+            request.confirm_offset,
+            request.election_priority,
+        );
+    }
+
+    pub(crate) fn get_next_broker_id(
+        &self,
+        request: &GetNextBrokerIdRequestHeader,
+    ) -> ControllerResult<GetNextBrokerIdResponseHeader> {
+        self.replicas_info_manager
+            .lock()
+            .get_next_broker_id(&request.cluster_name, &request.broker_name)
+    }
+
+    pub(crate) fn apply_broker_id(
+        &self,
+        request: &ApplyBrokerIdRequestHeader,
+    ) -> ControllerResult<ApplyBrokerIdResponseHeader> {
+        self.replicas_info_manager.lock().apply_broker_id(request)
+    }
+
+    pub(crate) fn register_broker(
+        &self,
+        request: &RegisterBrokerToControllerRequestHeader,
+    ) -> ControllerResult<RegisterBrokerToControllerResponseHeader> {
+        let mut replicas_info_manager = self.replicas_info_manager.lock();
+        let heartbeat_manager = self.heartbeat_manager.lock();
+        replicas_info_manager.register_broker(request, &heartbeat_manager)
+    }
+
+    pub(crate) fn elect_master(
+        &self,
+        request: &ElectMasterRequestHeader,
+    ) -> ControllerResult<ElectMasterResponseHeader> {
+        let mut replicas_info_manager = self.replicas_info_manager.lock();
+        let heartbeat_manager = self.heartbeat_manager.lock();
+        replicas_info_manager.elect_master(
+            request,
+            &self.elect_policy,
+            &heartbeat_manager,
+            self.controller_config.enable_elect_unclean_master,
+        )
+    }
+
+    pub(crate) fn alter_sync_state_set(
+        &self,
+        request: &AlterSyncStateSetRequestHeader,
+        sync_state_set: SyncStateSet,
+    ) -> ControllerResult<AlterSyncStateSetResponseHeader> {
+        let mut replicas_info_manager = self.replicas_info_manager.lock();
+        let heartbeat_manager = self.heartbeat_manager.lock();
+        replicas_info_manager.alter_sync_state_set(request, sync_state_set, &heartbeat_manager)
+    }
+
+    pub(crate) fn get_replica_info(
+        &self,
+        broker_name: &CheetahString,
+    ) -> ControllerResult<GetReplicaInfoResponseHeader> {
+        self.replicas_info_manager
+            .lock()
+            .get_replica_info(broker_name)
+    }
+
+    pub(crate) fn get_sync_state_data(&self, broker_names: &[CheetahString]) -> BrokerReplicasInfo {
+        let replicas_info_manager = self.replicas_info_manager.lock();
+        let heartbeat_manager = self.heartbeat_manager.lock();
+        replicas_info_manager.get_sync_state_data(broker_names, &heartbeat_manager)
+    }
+
+    pub(crate) fn clean_broker_data(
+        &self,
+        request: &CleanControllerBrokerDataRequestHeader,
+    ) -> ControllerResult<()> {
+        let mut replicas_info_manager = self.replicas_info_manager.lock();
+        let mut heartbeat_manager = self.heartbeat_manager.lock();
+        let result = replicas_info_manager.clean_broker_data(request, &heartbeat_manager);
+        if result.is_success() {
+            if let Some(cluster_name) = request.cluster_name.as_ref() {
+                let broker_ids = request
+                    .broker_controller_ids_to_clean
+                    .as_ref()
+                    .map(|ids| {
+                        ids.split(';')
+                            .filter_map(|id| id.trim().parse::<i64>().ok())
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                for broker_id in broker_ids {
+                    heartbeat_manager.remove_broker(&BrokerIdentityInfo::new(
+                        cluster_name.clone(),
+                        request.broker_name.clone(),
+                        broker_id,
+                    ));
+                }
+            }
+        }
+        result
+    }
+}
@@ -0,0 +1,268 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use core::str;
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::mix_all::string_to_properties;
+use rocketmq_remoting::code::request_code::ControllerRequestCode;
+use rocketmq_remoting::code::request_code::RequestCode;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::net::channel::Channel;
+use rocketmq_remoting::protocol::body::sync_state_set::SyncStateSet;
+use rocketmq_remoting::protocol::command_custom_header::CommandCustomHeader;
+use rocketmq_remoting::protocol::header::broker::broker_heartbeat_request_header::BrokerHeartbeatRequestHeader;
+use rocketmq_remoting::protocol::header::controller::alter_sync_state_set_header::AlterSyncStateSetRequestHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::clean_controller_broker_data_header::CleanControllerBrokerDataRequestHeader;
+use rocketmq_remoting::protocol::header::controller::elect_master_request_header::ElectMasterRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_replica_info_header::GetReplicaInfoRequestHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerRequestHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use rocketmq_remoting::runtime::connection_handler_context::ConnectionHandlerContext;
+use rocketmq_remoting::runtime::processor::RequestProcessor;
+use tracing::info;
+
+use crate::controller::controller_manager::ControllerManager;
+use crate::controller::controller_result::ControllerResult;
+
+/// Handles the requests sent to the controller by brokers and admin tools.
+#[derive(Clone)]
+pub struct ControllerRequestProcessor {
+    controller_manager: Arc<ControllerManager>,
+}
+
+impl ControllerRequestProcessor {
+    pub fn new(controller_manager: Arc<ControllerManager>) -> Self {
+        Self { controller_manager }
+    }
+
+    /// Whether the request should be handled by the controller.
+    pub fn is_controller_request(request_code: i32) -> bool {
+        ControllerRequestCode::value_of(request_code).is_some()
+            || request_code == RequestCode::BrokerHeartbeat.to_i32()
+    }
+
+    pub fn process_request(
+        &mut self,
+        _channel: Channel,
+        _ctx: ConnectionHandlerContext,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<Option<RemotingCommand>> {
+        if request.code() == RequestCode::BrokerHeartbeat.to_i32() {
+            self.on_broker_heartbeat(&request)?;
+            return Ok(Some(RemotingCommand::create_response_command()));
+        }
+        let Some(request_code) = ControllerRequestCode::value_of(request.code()) else {
+            return Ok(Some(
+                RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::RequestCodeNotSupported,
+                    format!(
+                        "request code {} not supported by controller",
+                        request.code()
+                    ),
+                ),
+            ));
+        };
+        let response = match request_code {
+            ControllerRequestCode::ControllerAlterSyncStateSet => {
+                self.alter_sync_state_set(request)?
+            }
+            ControllerRequestCode::ControllerElectMaster => {
+                let header = request.decode_command_custom_header::<ElectMasterRequestHeader>()?;
+                to_response(self.controller_manager.elect_master(&header))
+            }
+            ControllerRequestCode::ControllerRegisterBroker => {
+                let header = request
+                    .decode_command_custom_header::<RegisterBrokerToControllerRequestHeader>()?;
+                to_response(self.controller_manager.register_broker(&header))
+            }
+            ControllerRequestCode::ControllerGetReplicaInfo => {
+                let header =
+                    request.decode_command_custom_header::<GetReplicaInfoRequestHeader>()?;
+                to_response(
+                    self.controller_manager
+                        .get_replica_info(&header.broker_name),
+                )
+            }
+            ControllerRequestCode::ControllerGetMetadataInfo => self.get_controller_metadata(),
+            ControllerRequestCode::ControllerGetSyncStateData => {
+                self.get_sync_state_data(request)?
+            }
+            ControllerRequestCode::ControllerGetNextBrokerId => {
+                let header =
+                    request.decode_command_custom_header::<GetNextBrokerIdRequestHeader>()?;
+                to_response(self.controller_manager.get_next_broker_id(&header))
+            }
+            ControllerRequestCode::ControllerApplyBrokerId => {
+                let header =
+                    request.decode_command_custom_header::<ApplyBrokerIdRequestHeader>()?;
+                to_response(self.controller_manager.apply_broker_id(&header))
+            }
+            ControllerRequestCode::CleanBrokerData => {
+                let header = request
+                    .decode_command_custom_header::<CleanControllerBrokerDataRequestHeader>()?;
+                let result = self.controller_manager.clean_broker_data(&header);
+                RemotingCommand::create_response_command_with_code(result.response_code)
+                    .set_remark_option(result.remark)
+            }
+            ControllerRequestCode::UpdateControllerConfig => self.update_controller_config(request),
+            ControllerRequestCode::GetControllerConfig => self.get_controller_config(),
+            ControllerRequestCode::GetBrokerEpochCache
+            | ControllerRequestCode::NotifyBrokerRoleChanged => {
+                RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::RequestCodeNotSupported,
+                    format!(
+                        "request code {} not supported by controller",
+                        request.code()
+                    ),
+                )
+            }
+        };
+        Ok(Some(response))
+    }
+
+    /// Records a heartbeat of a broker running in controller mode.
+    pub fn on_broker_heartbeat(
+        &self,
+        request: &RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        let header = request.decode_command_custom_header::<BrokerHeartbeatRequestHeader>()?;
+        self.controller_manager.on_broker_heartbeat(header);
+        Ok(())
+    }
+
+    fn alter_sync_state_set(
+        &self,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<RemotingCommand> {
+        let header = request.decode_command_custom_header::<AlterSyncStateSetRequestHeader>()?;
+        let Some(body) = request.body() else {
+            return Ok(RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::ControllerInvalidRequest,
+                "The sync state set is required",
+            ));
+        };
+        let sync_state_set = SyncStateSet::decode(body)?;
+        Ok(to_response(
+            self.controller_manager
+                .alter_sync_state_set(&header, sync_state_set),
+        ))
+    }
+
+    fn get_controller_metadata(&self) -> RemotingCommand {
+        let address = self.controller_manager.controller_address().clone();
+        RemotingCommand::create_response_command_with_header(GetMetaDataResponseHeader {
+            group: None,
+            controller_leader_id: Some(CheetahString::from_static_str("0")),
+            controller_leader_address: Some(address.clone()),
+            is_leader: Some(true),
+            peers: Some(address),
+        })
+    }
+
+    fn get_sync_state_data(
+        &self,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<RemotingCommand> {
+        let broker_names = match request.body() {
+            Some(body) => Vec::<CheetahString>::decode(body)?,
+            None => Vec::new(),
+        };
+        let broker_replicas_info = self.controller_manager.get_sync_state_data(&broker_names);
+        Ok(RemotingCommand::create_response_command().set_body(broker_replicas_info.encode()?))
+    }
+
+    fn update_controller_config(&self, request: RemotingCommand) -> RemotingCommand {
+        let Some(body) = request.body() else {
+            return RemotingCommand::create_response_command();
+        };
+        let properties = match str::from_utf8(body).ok().and_then(string_to_properties) {
+            Some(properties) => properties,
+            None => {
+                return RemotingCommand::create_response_command_with_code_remark(
+                    ResponseCode::SystemError,
+                    "string_to_properties error",
+                );
+            }
+        };
+        let black_list = self
+            .controller_manager
+            .controller_config()
+            .get_config_blacklist();
+        if properties.keys().any(|key| black_list.contains(key)) {
+            return RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::NoPermission,
+                "Cannot update config in blacklist.",
+            );
+        }
+        info!("update controller config: {:?}", properties);
+        match self
+            .controller_manager
+            .controller_config_mut()
+            .update(properties)
+        {
+            Ok(_) => RemotingCommand::create_response_command(),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                format!("Update error {:?}", e),
+            ),
+        }
+    }
+
+    fn get_controller_config(&self) -> RemotingCommand {
+        match self
+            .controller_manager
+            .controller_config()
+            .get_all_configs_format_string()
+        {
+            Ok(content) => RemotingCommand::create_response_command().set_body(content),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                ResponseCode::SystemError,
+                e,
+            ),
+        }
+    }
+}
+
+impl RequestProcessor for ControllerRequestProcessor {
+    async fn process_request(
+        &mut self,
+        channel: Channel,
+        ctx: ConnectionHandlerContext,
+        request: RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<Option<RemotingCommand>> {
+        ControllerRequestProcessor::process_request(self, channel, ctx, request)
+    }
+}
+
+fn to_response<H>(result: ControllerResult<H>) -> RemotingCommand
+where
+    H: CommandCustomHeader + Default + Send + Sync + 'static,
+{
+    let response = RemotingCommand::create_response_command_with_header(result.response)
+        .set_code(result.response_code)
+        .set_remark_option(result.remark);
+    match result.body {
+        Some(body) => response.set_body(body),
+        None => response,
+    }
+}
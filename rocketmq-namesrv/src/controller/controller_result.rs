@@ -0,0 +1,65 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_remoting::code::response_code::ResponseCode;
+
+/// The outcome of a controller operation: the response header, an optional body and the
+/// response code which is sent back to the requester.
+#[derive(Debug)]
+pub(crate) struct ControllerResult<T> {
+    pub(crate) response_code: ResponseCode,
+    pub(crate) remark: Option<CheetahString>,
+    pub(crate) response: T,
+    pub(crate) body: Option<Vec<u8>>,
+}
+
+impl<T: Default> ControllerResult<T> {
+    pub(crate) fn of(response: T) -> Self {
+        Self {
+            response_code: ResponseCode::Success,
+            remark: None,
+            response,
+            body: None,
+        }
+    }
+
+    pub(crate) fn of_code(response_code: ResponseCode, remark: impl Into<CheetahString>) -> Self {
+        Self {
+            response_code,
+            remark: Some(remark.into()),
+            response: T::default(),
+            body: None,
+        }
+    }
+
+    pub(crate) fn set_code_and_remark(
+        &mut self,
+        response_code: ResponseCode,
+        remark: impl Into<CheetahString>,
+    ) {
+        self.response_code = response_code;
+        self.remark = Some(remark.into());
+    }
+
+    pub(crate) fn set_body(&mut self, body: Vec<u8>) {
+        self.body = Some(body);
+    }
+
+    pub(crate) fn is_success(&self) -> bool {
+        self.response_code == ResponseCode::Success
+    }
+}
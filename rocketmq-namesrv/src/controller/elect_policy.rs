@@ -0,0 +1,110 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::controller::broker_heartbeat_manager::BrokerHeartbeatManager;
+
+/// Chooses the new master of a broker group.
+///
+/// Candidates are the alive replicas in the sync state set. When none of them is alive and
+/// unclean election is enabled, any alive replica may be elected.
+#[derive(Default)]
+pub(crate) struct DefaultElectPolicy;
+
+impl DefaultElectPolicy {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn elect(
+        &self,
+        cluster_name: &str,
+        broker_name: &str,
+        sync_state_brokers: &HashSet<i64>,
+        all_replica_brokers: &HashSet<i64>,
+        old_master: Option<i64>,
+        prefer_broker_id: Option<i64>,
+        enable_elect_unclean_master: bool,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> Option<i64> {
+        let alive = |brokers: &HashSet<i64>| {
+            brokers
+                .iter()
+                .copied()
+                .filter(|id| heartbeat_manager.is_broker_active(cluster_name, broker_name, *id))
+                .collect::<Vec<_>>()
+        };
+        let new_master = self.try_elect(
+            cluster_name,
+            broker_name,
+            alive(sync_state_brokers),
+            old_master,
+            prefer_broker_id,
+            heartbeat_manager,
+        );
+        if new_master.is_some() || !enable_elect_unclean_master {
+            return new_master;
+        }
+        self.try_elect(
+            cluster_name,
+            broker_name,
+            alive(all_replica_brokers),
+            old_master,
+            prefer_broker_id,
+            heartbeat_manager,
+        )
+    }
+
+    fn try_elect(
+        &self,
+        cluster_name: &str,
+        broker_name: &str,
+        mut brokers: Vec<i64>,
+        old_master: Option<i64>,
+        prefer_broker_id: Option<i64>,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> Option<i64> {
+        if brokers.is_empty() {
+            return None;
+        }
+        if let Some(old_master) = old_master {
+            if brokers.contains(&old_master)
+                && (prefer_broker_id.is_none() || prefer_broker_id == Some(old_master))
+            {
+                return Some(old_master);
+            }
+        }
+        if let Some(prefer) = prefer_broker_id {
+            return brokers.contains(&prefer).then_some(prefer);
+        }
+        // Prefer the replica with the newest epoch, then the largest offset, then the
+        // highest election priority (a smaller value means a higher priority).
+        brokers.sort_by(|a, b| {
+            let a = heartbeat_manager.get_broker_live_info(cluster_name, broker_name, *a);
+            let b = heartbeat_manager.get_broker_live_info(cluster_name, broker_name, *b);
+            match (a, b) {
+                (Some(a), Some(b)) => b
+                    .epoch
+                    .cmp(&a.epoch)
+                    .then(b.max_offset.cmp(&a.max_offset))
+                    .then(a.election_priority.cmp(&b.election_priority)),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }
+        });
+        brokers.first().copied()
+    }
+}
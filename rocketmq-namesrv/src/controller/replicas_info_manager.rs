@@ -0,0 +1,994 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_common::FileUtils;
+use rocketmq_remoting::code::response_code::ResponseCode;
+use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
+use rocketmq_remoting::protocol::body::broker_replicas_info::ReplicaIdentity;
+use rocketmq_remoting::protocol::body::broker_replicas_info::ReplicasInfo;
+use rocketmq_remoting::protocol::body::elect_master_response_body::ElectMasterResponseBody;
+use rocketmq_remoting::protocol::body::sync_state_set::SyncStateSet;
+use rocketmq_remoting::protocol::header::controller::alter_sync_state_set_header::AlterSyncStateSetRequestHeader;
+use rocketmq_remoting::protocol::header::controller::alter_sync_state_set_header::AlterSyncStateSetResponseHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdRequestHeader;
+use rocketmq_remoting::protocol::header::controller::apply_broker_id_header::ApplyBrokerIdResponseHeader;
+use rocketmq_remoting::protocol::header::controller::clean_controller_broker_data_header::CleanControllerBrokerDataRequestHeader;
+use rocketmq_remoting::protocol::header::controller::elect_master_request_header::ElectMasterRequestHeader;
+use rocketmq_remoting::protocol::header::controller::get_next_broker_id_header::GetNextBrokerIdResponseHeader;
+use rocketmq_remoting::protocol::header::controller::get_replica_info_header::GetReplicaInfoResponseHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerRequestHeader;
+use rocketmq_remoting::protocol::header::controller::register_broker_to_controller_header::RegisterBrokerToControllerResponseHeader;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::error;
+use tracing::info;
+
+use crate::controller::broker_heartbeat_manager::BrokerHeartbeatManager;
+use crate::controller::controller_result::ControllerResult;
+use crate::controller::elect_policy::DefaultElectPolicy;
+
+const REPLICAS_INFO_FILE_NAME: &str = "replicasInfo.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrokerIdInfo {
+    broker_address: CheetahString,
+    register_check_code: CheetahString,
+}
+
+/// The replicas registered for one broker group and the id assigned to the next replica.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BrokerReplicaInfo {
+    cluster_name: CheetahString,
+    broker_name: CheetahString,
+    next_assign_broker_id: i64,
+    broker_id_info: HashMap<i64, BrokerIdInfo>,
+}
+
+impl BrokerReplicaInfo {
+    fn new(cluster_name: CheetahString, broker_name: CheetahString) -> Self {
+        Self {
+            cluster_name,
+            broker_name,
+            next_assign_broker_id: mix_all::FIRST_BROKER_CONTROLLER_ID as i64,
+            broker_id_info: HashMap::new(),
+        }
+    }
+
+    fn add_broker(
+        &mut self,
+        broker_id: i64,
+        broker_address: CheetahString,
+        register_check_code: CheetahString,
+    ) {
+        self.broker_id_info.insert(
+            broker_id,
+            BrokerIdInfo {
+                broker_address,
+                register_check_code,
+            },
+        );
+        self.next_assign_broker_id = self.next_assign_broker_id.max(broker_id + 1);
+    }
+
+    fn broker_address(&self, broker_id: i64) -> Option<&CheetahString> {
+        self.broker_id_info
+            .get(&broker_id)
+            .map(|info| &info.broker_address)
+    }
+
+    fn all_broker_ids(&self) -> HashSet<i64> {
+        self.broker_id_info.keys().copied().collect()
+    }
+}
+
+/// The master and the sync state set of one broker group.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncStateInfo {
+    cluster_name: CheetahString,
+    broker_name: CheetahString,
+    master_broker_id: Option<i64>,
+    master_epoch: i32,
+    sync_state_set: HashSet<i64>,
+    sync_state_set_epoch: i32,
+}
+
+impl SyncStateInfo {
+    fn new(cluster_name: CheetahString, broker_name: CheetahString) -> Self {
+        Self {
+            cluster_name,
+            broker_name,
+            ..Default::default()
+        }
+    }
+
+    fn update_master(&mut self, master_broker_id: Option<i64>) {
+        self.master_broker_id = master_broker_id;
+        self.master_epoch += 1;
+    }
+
+    fn update_sync_state_set(&mut self, sync_state_set: HashSet<i64>) {
+        self.sync_state_set = sync_state_set;
+        self.sync_state_set_epoch += 1;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ReplicasInfoSerializeWrapper {
+    replica_info_table: HashMap<CheetahString, BrokerReplicaInfo>,
+    sync_state_set_info_table: HashMap<CheetahString, SyncStateInfo>,
+}
+
+/// Holds the replicas metadata of all broker groups managed by the controller.
+///
+/// Every mutation bumps the corresponding epoch so that brokers holding stale metadata are
+/// fenced, and is persisted to `replicasInfo.json` under the controller store path.
+pub(crate) struct ReplicasInfoManager {
+    replica_info_table: HashMap<CheetahString, BrokerReplicaInfo>,
+    sync_state_set_info_table: HashMap<CheetahString, SyncStateInfo>,
+    store_path: Option<String>,
+}
+
+impl ReplicasInfoManager {
+    pub(crate) fn new(controller_store_path: Option<&str>) -> Self {
+        Self {
+            replica_info_table: HashMap::new(),
+            sync_state_set_info_table: HashMap::new(),
+            store_path: controller_store_path.map(|path| {
+                format!(
+                    "{}{}{}",
+                    path,
+                    std::path::MAIN_SEPARATOR,
+                    REPLICAS_INFO_FILE_NAME
+                )
+            }),
+        }
+    }
+
+    pub(crate) fn load(&mut self) -> bool {
+        let Some(store_path) = self.store_path.as_ref() else {
+            return true;
+        };
+        match FileUtils::file_to_string(store_path) {
+            Ok(content) if content.is_empty() => true,
+            Ok(content) => {
+                match SerdeJsonUtils::decode::<ReplicasInfoSerializeWrapper>(content.as_bytes()) {
+                    Ok(wrapper) => {
+                        self.replica_info_table = wrapper.replica_info_table;
+                        self.sync_state_set_info_table = wrapper.sync_state_set_info_table;
+                        info!("load controller replicas info success, {}", store_path);
+                        true
+                    }
+                    Err(e) => {
+                        error!("decode controller replicas info failed: {:?}", e);
+                        false
+                    }
+                }
+            }
+            Err(e) => {
+                error!("load controller replicas info failed: {:?}", e);
+                false
+            }
+        }
+    }
+
+    fn persist(&self) {
+        let Some(store_path) = self.store_path.as_ref() else {
+            return;
+        };
+        let wrapper = ReplicasInfoSerializeWrapper {
+            replica_info_table: self.replica_info_table.clone(),
+            sync_state_set_info_table: self.sync_state_set_info_table.clone(),
+        };
+        match wrapper.to_json_pretty() {
+            Ok(content) => {
+                if let Err(e) = FileUtils::string_to_file(content.as_str(), store_path) {
+                    error!("persist controller replicas info failed: {:?}", e);
+                }
+            }
+            Err(e) => error!("encode controller replicas info failed: {:?}", e),
+        }
+    }
+
+    pub(crate) fn get_next_broker_id(
+        &self,
+        cluster_name: &CheetahString,
+        broker_name: &CheetahString,
+    ) -> ControllerResult<GetNextBrokerIdResponseHeader> {
+        let next_broker_id = self
+            .replica_info_table
+            .get(broker_name)
+            .map_or(mix_all::FIRST_BROKER_CONTROLLER_ID as i64, |info| {
+                info.next_assign_broker_id
+            });
+        ControllerResult::of(GetNextBrokerIdResponseHeader {
+            cluster_name: Some(cluster_name.clone()),
+            broker_name: Some(broker_name.clone()),
+            next_broker_id: Some(next_broker_id),
+        })
+    }
+
+    pub(crate) fn apply_broker_id(
+        &mut self,
+        request: &ApplyBrokerIdRequestHeader,
+    ) -> ControllerResult<ApplyBrokerIdResponseHeader> {
+        let broker_name = &request.broker_name;
+        let broker_id = request.applied_broker_id;
+        match self.replica_info_table.get_mut(broker_name) {
+            None => {
+                if broker_id != mix_all::FIRST_BROKER_CONTROLLER_ID as i64 {
+                    return ControllerResult::of_code(
+                        ResponseCode::ControllerBrokerIdInvalid,
+                        format!(
+                            "Broker-set: {} hasn't been registered, the first broker id must be {}",
+                            broker_name,
+                            mix_all::FIRST_BROKER_CONTROLLER_ID
+                        ),
+                    );
+                }
+                let mut replica_info =
+                    BrokerReplicaInfo::new(request.cluster_name.clone(), broker_name.clone());
+                replica_info.add_broker(
+                    broker_id,
+                    CheetahString::empty(),
+                    request.register_check_code.clone(),
+                );
+                self.replica_info_table
+                    .insert(broker_name.clone(), replica_info);
+                self.sync_state_set_info_table.insert(
+                    broker_name.clone(),
+                    SyncStateInfo::new(request.cluster_name.clone(), broker_name.clone()),
+                );
+            }
+            Some(replica_info) => {
+                if let Some(exist) = replica_info.broker_id_info.get(&broker_id) {
+                    // A broker restarting with the same metadata may apply for its id again.
+                    if exist.register_check_code != request.register_check_code {
+                        return ControllerResult::of_code(
+                            ResponseCode::ControllerBrokerIdInvalid,
+                            format!(
+                                "Broker id: {} in broker-set: {} has been applied by another \
+                                 broker",
+                                broker_id, broker_name
+                            ),
+                        );
+                    }
+                } else if broker_id != replica_info.next_assign_broker_id {
+                    return ControllerResult::of_code(
+                        ResponseCode::ControllerBrokerIdInvalid,
+                        format!(
+                            "Broker id: {} is not the next broker id: {} of broker-set: {}",
+                            broker_id, replica_info.next_assign_broker_id, broker_name
+                        ),
+                    );
+                } else {
+                    replica_info.add_broker(
+                        broker_id,
+                        CheetahString::empty(),
+                        request.register_check_code.clone(),
+                    );
+                }
+            }
+        }
+        self.persist();
+        ControllerResult::of(ApplyBrokerIdResponseHeader {
+            cluster_name: Some(request.cluster_name.clone()),
+            broker_name: Some(broker_name.clone()),
+        })
+    }
+
+    pub(crate) fn register_broker(
+        &mut self,
+        request: &RegisterBrokerToControllerRequestHeader,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> ControllerResult<RegisterBrokerToControllerResponseHeader> {
+        let broker_name = &request.broker_name;
+        let Some(broker_id) = request.broker_id else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerInvalidRequest,
+                "Broker id is required when registering to controller",
+            );
+        };
+        let Some(replica_info) = self
+            .replica_info_table
+            .get_mut(broker_name)
+            .filter(|info| info.broker_id_info.contains_key(&broker_id))
+        else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerBrokerMetadataNotExist,
+                format!(
+                    "Broker-set: {} hasn't applied broker id: {}",
+                    broker_name, broker_id
+                ),
+            );
+        };
+        if let Some(broker_address) = request.broker_address.as_ref() {
+            let id_info = replica_info.broker_id_info.get_mut(&broker_id).unwrap();
+            if id_info.broker_address != *broker_address {
+                id_info.broker_address = broker_address.clone();
+                self.persist();
+            }
+        }
+
+        let mut response = RegisterBrokerToControllerResponseHeader {
+            cluster_name: Some(request.cluster_name.clone()),
+            broker_name: Some(broker_name.clone()),
+            ..Default::default()
+        };
+        let replica_info = &self.replica_info_table[broker_name];
+        if let Some(sync_state_info) = self.sync_state_set_info_table.get(broker_name) {
+            if let Some(master) = sync_state_info.master_broker_id.filter(|master| {
+                heartbeat_manager.is_broker_active(&replica_info.cluster_name, broker_name, *master)
+            }) {
+                response.master_broker_id = Some(master);
+                response.master_address = replica_info.broker_address(master).cloned();
+                response.master_epoch = Some(sync_state_info.master_epoch);
+                response.sync_state_set_epoch = Some(sync_state_info.sync_state_set_epoch);
+            }
+        }
+        let mut result = ControllerResult::of(response);
+        if let Some(sync_state_info) = self.sync_state_set_info_table.get(broker_name) {
+            if let Ok(body) = SyncStateSet::new(
+                sync_state_info.sync_state_set.clone(),
+                sync_state_info.sync_state_set_epoch,
+            )
+            .encode()
+            {
+                result.set_body(body);
+            }
+        }
+        result
+    }
+
+    pub(crate) fn elect_master(
+        &mut self,
+        request: &ElectMasterRequestHeader,
+        elect_policy: &DefaultElectPolicy,
+        heartbeat_manager: &BrokerHeartbeatManager,
+        enable_elect_unclean_master: bool,
+    ) -> ControllerResult<ElectMasterResponseHeader> {
+        let broker_name = &request.broker_name;
+        let (Some(replica_info), Some(sync_state_info)) = (
+            self.replica_info_table.get(broker_name),
+            self.sync_state_set_info_table.get_mut(broker_name),
+        ) else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerBrokerNeedToBeRegistered,
+                format!("Broker-set: {} hasn't been registered", broker_name),
+            );
+        };
+        let all_replica_brokers = replica_info.all_broker_ids();
+        let prefer_broker_id = if request.designate_elect {
+            match request.broker_id {
+                Some(broker_id) if all_replica_brokers.contains(&broker_id) => Some(broker_id),
+                _ => {
+                    return ControllerResult::of_code(
+                        ResponseCode::ControllerElectMasterFailed,
+                        format!(
+                            "The designated broker: {:?} doesn't belong to broker-set: {}",
+                            request.broker_id, broker_name
+                        ),
+                    );
+                }
+            }
+        } else {
+            None
+        };
+        let old_master = sync_state_info.master_broker_id;
+        let new_master = elect_policy.elect(
+            &replica_info.cluster_name,
+            broker_name,
+            &sync_state_info.sync_state_set,
+            &all_replica_brokers,
+            old_master,
+            prefer_broker_id,
+            enable_elect_unclean_master,
+            heartbeat_manager,
+        );
+
+        let (response_code, remark) = match new_master {
+            Some(new_master) if Some(new_master) == old_master => (
+                ResponseCode::ControllerMasterStillExist,
+                Some(format!(
+                    "The old master: {} of broker-set: {} is still alive",
+                    new_master, broker_name
+                )),
+            ),
+            Some(new_master) => {
+                info!(
+                    "elect new master: {} for broker-set: {}, old master: {:?}",
+                    new_master, broker_name, old_master
+                );
+                sync_state_info.update_master(Some(new_master));
+                sync_state_info.update_sync_state_set(HashSet::from([new_master]));
+                (ResponseCode::Success, None)
+            }
+            None => {
+                if old_master.is_some() {
+                    sync_state_info.update_master(None);
+                }
+                (
+                    ResponseCode::ControllerMasterNotAvailable,
+                    Some(format!(
+                        "Failed to elect a new master for broker-set: {}",
+                        broker_name
+                    )),
+                )
+            }
+        };
+        let master = sync_state_info.master_broker_id;
+        let response = ElectMasterResponseHeader {
+            master_broker_id: master,
+            master_address: master.and_then(|master| replica_info.broker_address(master).cloned()),
+            master_epoch: Some(sync_state_info.master_epoch),
+            sync_state_set_epoch: Some(sync_state_info.sync_state_set_epoch),
+        };
+        let mut broker_member_group =
+            BrokerMemberGroup::new(replica_info.cluster_name.clone(), broker_name.clone());
+        for (broker_id, id_info) in &replica_info.broker_id_info {
+            broker_member_group
+                .broker_addrs
+                .insert(*broker_id as u64, id_info.broker_address.clone());
+        }
+        let body = ElectMasterResponseBody {
+            broker_member_group: Some(broker_member_group),
+            sync_state_set: sync_state_info.sync_state_set.iter().copied().collect(),
+        };
+        if old_master != master {
+            self.persist();
+        }
+
+        let mut result = ControllerResult::of(response);
+        if let Some(remark) = remark {
+            result.set_code_and_remark(response_code, remark);
+        }
+        if let Ok(body) = body.encode() {
+            result.set_body(body);
+        }
+        result
+    }
+
+    pub(crate) fn alter_sync_state_set(
+        &mut self,
+        request: &AlterSyncStateSetRequestHeader,
+        sync_state_set: SyncStateSet,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> ControllerResult<AlterSyncStateSetResponseHeader> {
+        let broker_name = &request.broker_name;
+        let (Some(replica_info), Some(sync_state_info)) = (
+            self.replica_info_table.get(broker_name),
+            self.sync_state_set_info_table.get_mut(broker_name),
+        ) else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerAlterSyncStateSetFailed,
+                format!("Broker-set: {} hasn't been registered", broker_name),
+            );
+        };
+        if sync_state_info.master_broker_id != Some(request.master_broker_id) {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerInvalidMaster,
+                format!(
+                    "Broker: {} is not the master of broker-set: {}",
+                    request.master_broker_id, broker_name
+                ),
+            );
+        }
+        if sync_state_info.master_epoch != request.master_epoch {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerFencedMasterEpoch,
+                format!(
+                    "The master epoch: {} is fenced, current master epoch: {}",
+                    request.master_epoch, sync_state_info.master_epoch
+                ),
+            );
+        }
+        if sync_state_info.sync_state_set_epoch != sync_state_set.sync_state_set_epoch {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerFencedSyncStateSetEpoch,
+                format!(
+                    "The sync state set epoch: {} is fenced, current sync state set epoch: {}",
+                    sync_state_set.sync_state_set_epoch, sync_state_info.sync_state_set_epoch
+                ),
+            );
+        }
+        let new_sync_state_set = sync_state_set.sync_state_set;
+        if !new_sync_state_set.contains(&request.master_broker_id) {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerInvalidReplicas,
+                "The new sync state set doesn't contain the master",
+            );
+        }
+        for replica in &new_sync_state_set {
+            if !replica_info.broker_id_info.contains_key(replica) {
+                return ControllerResult::of_code(
+                    ResponseCode::ControllerInvalidReplicas,
+                    format!(
+                        "Replica: {} doesn't belong to broker-set: {}",
+                        replica, broker_name
+                    ),
+                );
+            }
+            if !sync_state_info.sync_state_set.contains(replica)
+                && !heartbeat_manager.is_broker_active(
+                    &replica_info.cluster_name,
+                    broker_name,
+                    *replica,
+                )
+            {
+                return ControllerResult::of_code(
+                    ResponseCode::ControllerBrokerNotAlive,
+                    format!("Replica: {} is not alive", replica),
+                );
+            }
+        }
+        if new_sync_state_set == sync_state_info.sync_state_set {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerAlterSyncStateSetFailed,
+                "The new sync state set is equal to the old one, no need to update",
+            );
+        }
+        sync_state_info.update_sync_state_set(new_sync_state_set);
+        let new_sync_state_set_epoch = sync_state_info.sync_state_set_epoch;
+        self.persist();
+        ControllerResult::of(AlterSyncStateSetResponseHeader {
+            new_sync_state_set_epoch,
+        })
+    }
+
+    pub(crate) fn get_replica_info(
+        &self,
+        broker_name: &CheetahString,
+    ) -> ControllerResult<GetReplicaInfoResponseHeader> {
+        let (Some(replica_info), Some(sync_state_info)) = (
+            self.replica_info_table.get(broker_name),
+            self.sync_state_set_info_table.get(broker_name),
+        ) else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerBrokerMetadataNotExist,
+                format!("Broker-set: {} hasn't been registered", broker_name),
+            );
+        };
+        let master = sync_state_info.master_broker_id;
+        let mut result = ControllerResult::of(GetReplicaInfoResponseHeader {
+            master_broker_id: master,
+            master_address: master.and_then(|master| replica_info.broker_address(master).cloned()),
+            master_epoch: Some(sync_state_info.master_epoch),
+        });
+        if let Ok(body) = SyncStateSet::new(
+            sync_state_info.sync_state_set.clone(),
+            sync_state_info.sync_state_set_epoch,
+        )
+        .encode()
+        {
+            result.set_body(body);
+        }
+        result
+    }
+
+    pub(crate) fn get_sync_state_data(
+        &self,
+        broker_names: &[CheetahString],
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> BrokerReplicasInfo {
+        let mut broker_replicas_info = BrokerReplicasInfo::new();
+        for broker_name in broker_names {
+            let (Some(replica_info), Some(sync_state_info)) = (
+                self.replica_info_table.get(broker_name),
+                self.sync_state_set_info_table.get(broker_name),
+            ) else {
+                continue;
+            };
+            let mut in_sync_replicas = Vec::new();
+            let mut not_in_sync_replicas = Vec::new();
+            for (broker_id, id_info) in &replica_info.broker_id_info {
+                let identity = ReplicaIdentity::new_with_alive(
+                    broker_name.clone(),
+                    *broker_id as u64,
+                    id_info.broker_address.clone(),
+                    heartbeat_manager.is_broker_active(
+                        &replica_info.cluster_name,
+                        broker_name,
+                        *broker_id,
+                    ),
+                );
+                if sync_state_info.sync_state_set.contains(broker_id) {
+                    in_sync_replicas.push(identity);
+                } else {
+                    not_in_sync_replicas.push(identity);
+                }
+            }
+            let master = sync_state_info.master_broker_id;
+            broker_replicas_info.add_replica_info(
+                broker_name.clone(),
+                ReplicasInfo::new(
+                    master.unwrap_or(mix_all::MASTER_ID as i64) as u64,
+                    master
+                        .and_then(|master| replica_info.broker_address(master).cloned())
+                        .unwrap_or_default(),
+                    sync_state_info.master_epoch,
+                    sync_state_info.sync_state_set_epoch,
+                    in_sync_replicas,
+                    not_in_sync_replicas,
+                ),
+            );
+        }
+        broker_replicas_info
+    }
+
+    pub(crate) fn clean_broker_data(
+        &mut self,
+        request: &CleanControllerBrokerDataRequestHeader,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> ControllerResult<()> {
+        let broker_name = &request.broker_name;
+        let Some(replica_info) = self.replica_info_table.get_mut(broker_name) else {
+            return ControllerResult::of_code(
+                ResponseCode::ControllerInvalidCleanBrokerMetadata,
+                format!("Broker-set: {} hasn't been registered", broker_name),
+            );
+        };
+        let broker_ids = match request.broker_controller_ids_to_clean.as_ref() {
+            Some(ids) if !ids.is_empty() => {
+                let mut broker_ids = HashSet::new();
+                for id in ids.split(';').filter(|id| !id.trim().is_empty()) {
+                    match id.trim().parse::<i64>() {
+                        Ok(id) => {
+                            broker_ids.insert(id);
+                        }
+                        Err(_) => {
+                            return ControllerResult::of_code(
+                                ResponseCode::ControllerInvalidCleanBrokerMetadata,
+                                format!("Invalid broker id: {}", id),
+                            );
+                        }
+                    }
+                }
+                broker_ids
+            }
+            _ => replica_info.all_broker_ids(),
+        };
+        if !request.is_clean_living_broker {
+            if let Some(alive) = broker_ids.iter().find(|id| {
+                heartbeat_manager.is_broker_active(&replica_info.cluster_name, broker_name, **id)
+            }) {
+                return ControllerResult::of_code(
+                    ResponseCode::ControllerInvalidCleanBrokerMetadata,
+                    format!(
+                        "Broker: {} of broker-set: {} is still alive",
+                        alive, broker_name
+                    ),
+                );
+            }
+        }
+        for broker_id in &broker_ids {
+            replica_info.broker_id_info.remove(broker_id);
+        }
+        if replica_info.broker_id_info.is_empty() {
+            self.replica_info_table.remove(broker_name);
+            self.sync_state_set_info_table.remove(broker_name);
+        } else if let Some(sync_state_info) = self.sync_state_set_info_table.get_mut(broker_name) {
+            if sync_state_info
+                .master_broker_id
+                .is_some_and(|master| broker_ids.contains(&master))
+            {
+                sync_state_info.update_master(None);
+            }
+            let sync_state_set = sync_state_info
+                .sync_state_set
+                .difference(&broker_ids)
+                .copied()
+                .collect::<HashSet<_>>();
+            if sync_state_set != sync_state_info.sync_state_set {
+                sync_state_info.update_sync_state_set(sync_state_set);
+            }
+        }
+        info!(
+            "clean broker data of broker-set: {}, broker ids: {:?}",
+            broker_name, broker_ids
+        );
+        self.persist();
+        ControllerResult::of(())
+    }
+
+    /// Returns the broker groups which have no alive master but at least one alive replica.
+    pub(crate) fn broker_sets_need_elect(
+        &self,
+        heartbeat_manager: &BrokerHeartbeatManager,
+    ) -> Vec<CheetahString> {
+        self.replica_info_table
+            .iter()
+            .filter(|(broker_name, replica_info)| {
+                let master_alive = self
+                    .sync_state_set_info_table
+                    .get(*broker_name)
+                    .and_then(|info| info.master_broker_id)
+                    .is_some_and(|master| {
+                        heartbeat_manager.is_broker_active(
+                            &replica_info.cluster_name,
+                            broker_name,
+                            master,
+                        )
+                    });
+                !master_alive
+                    && replica_info.broker_id_info.keys().any(|broker_id| {
+                        heartbeat_manager.is_broker_active(
+                            &replica_info.cluster_name,
+                            broker_name,
+                            *broker_id,
+                        )
+                    })
+            })
+            .map(|(broker_name, _)| broker_name.clone())
+            .collect()
+    }
+
+    /// Recomputes the sync state set of every broker group whose master is alive from the
+    /// offsets reported in the heartbeats: a slave is in sync when it lags behind the master
+    /// by at most `ha_max_gap_not_in_sync` bytes. Returns the groups whose set changed.
+    pub(crate) fn refresh_sync_state_sets(
+        &mut self,
+        heartbeat_manager: &BrokerHeartbeatManager,
+        ha_max_gap_not_in_sync: i64,
+    ) -> Vec<CheetahString> {
+        let mut changed = Vec::new();
+        for (broker_name, sync_state_info) in self.sync_state_set_info_table.iter_mut() {
+            let Some(replica_info) = self.replica_info_table.get(broker_name) else {
+                continue;
+            };
+            let cluster_name = &replica_info.cluster_name;
+            let Some(master) = sync_state_info.master_broker_id else {
+                continue;
+            };
+            let Some(master_info) =
+                heartbeat_manager.get_broker_live_info(cluster_name, broker_name, master)
+            else {
+                continue;
+            };
+            if !heartbeat_manager.is_broker_active(cluster_name, broker_name, master) {
+                continue;
+            }
+            let master_offset = master_info.max_offset;
+            let mut sync_state_set = HashSet::from([master]);
+            for broker_id in replica_info.broker_id_info.keys() {
+                if *broker_id == master
+                    || !heartbeat_manager.is_broker_active(cluster_name, broker_name, *broker_id)
+                {
+                    continue;
+                }
+                let in_sync = heartbeat_manager
+                    .get_broker_live_info(cluster_name, broker_name, *broker_id)
+                    .is_some_and(|info| {
+                        info.max_offset >= 0
+                            && master_offset - info.max_offset <= ha_max_gap_not_in_sync
+                    });
+                if in_sync {
+                    sync_state_set.insert(*broker_id);
+                }
+            }
+            if sync_state_set != sync_state_info.sync_state_set {
+                info!(
+                    "sync state set of broker-set: {} changed from {:?} to {:?}",
+                    broker_name, sync_state_info.sync_state_set, sync_state_set
+                );
+                sync_state_info.update_sync_state_set(sync_state_set);
+                changed.push(broker_name.clone());
+            }
+        }
+        if !changed.is_empty() {
+            self.persist();
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::broker_heartbeat_manager::BrokerIdentityInfo;
+
+    fn register(
+        manager: &mut ReplicasInfoManager,
+        heartbeat_manager: &mut BrokerHeartbeatManager,
+        broker_id: i64,
+        max_offset: i64,
+    ) {
+        let next = manager
+            .get_next_broker_id(&"cluster".into(), &"broker-a".into())
+            .response
+            .next_broker_id
+            .unwrap();
+        assert_eq!(next, broker_id);
+        let address = format!("127.0.0.1:{}", 10911 + broker_id);
+        let applied = manager.apply_broker_id(&ApplyBrokerIdRequestHeader::new(
+            "cluster",
+            "broker-a",
+            broker_id,
+            address.clone(),
+        ));
+        assert!(applied.is_success());
+        let registered = manager.register_broker(
+            &RegisterBrokerToControllerRequestHeader::new(
+                "cluster",
+                "broker-a",
+                broker_id,
+                address.clone(),
+            ),
+            heartbeat_manager,
+        );
+        assert!(registered.is_success());
+        heartbeat_manager.on_broker_heartbeat(
+            BrokerIdentityInfo::new("cluster", "broker-a", broker_id),
+            address.into(),
+            None,
+            Some(1),
+            Some(max_offset),
+            None,
+            None,
+        );
+    }
+
+    #[test]
+    fn apply_broker_id_must_follow_next_id() {
+        let mut manager = ReplicasInfoManager::new(None);
+        let result = manager.apply_broker_id(&ApplyBrokerIdRequestHeader::new(
+            "cluster", "broker-a", 2, "a",
+        ));
+        assert_eq!(
+            result.response_code,
+            ResponseCode::ControllerBrokerIdInvalid
+        );
+        assert!(manager
+            .apply_broker_id(&ApplyBrokerIdRequestHeader::new(
+                "cluster", "broker-a", 1, "a"
+            ))
+            .is_success());
+        // The same broker may apply for its id again, another broker may not.
+        assert!(manager
+            .apply_broker_id(&ApplyBrokerIdRequestHeader::new(
+                "cluster", "broker-a", 1, "a"
+            ))
+            .is_success());
+        let result = manager.apply_broker_id(&ApplyBrokerIdRequestHeader::new(
+            "cluster", "broker-a", 1, "b",
+        ));
+        assert_eq!(
+            result.response_code,
+            ResponseCode::ControllerBrokerIdInvalid
+        );
+    }
+
+    #[test]
+    fn elect_master_and_fence_stale_alter_requests() {
+        let mut manager = ReplicasInfoManager::new(None);
+        let mut heartbeat_manager = BrokerHeartbeatManager::new();
+        let policy = DefaultElectPolicy;
+        register(&mut manager, &mut heartbeat_manager, 1, 100);
+        register(&mut manager, &mut heartbeat_manager, 2, 200);
+
+        // No sync state set yet, so only an unclean election can succeed.
+        let result = manager.elect_master(
+            &ElectMasterRequestHeader::of_controller_trigger("broker-a"),
+            &policy,
+            &heartbeat_manager,
+            false,
+        );
+        assert_eq!(
+            result.response_code,
+            ResponseCode::ControllerMasterNotAvailable
+        );
+        let result = manager.elect_master(
+            &ElectMasterRequestHeader::of_controller_trigger("broker-a"),
+            &policy,
+            &heartbeat_manager,
+            true,
+        );
+        assert!(result.is_success());
+        assert_eq!(result.response.master_broker_id, Some(2));
+        assert_eq!(result.response.master_epoch, Some(1));
+        assert_eq!(
+            result.response.master_address.as_deref(),
+            Some("127.0.0.1:10913")
+        );
+
+        let again = manager.elect_master(
+            &ElectMasterRequestHeader::of_controller_trigger("broker-a"),
+            &policy,
+            &heartbeat_manager,
+            false,
+        );
+        assert_eq!(
+            again.response_code,
+            ResponseCode::ControllerMasterStillExist
+        );
+
+        let header = AlterSyncStateSetRequestHeader::new("broker-a", 2, 1);
+        let stale = manager.alter_sync_state_set(
+            &header,
+            SyncStateSet::new(HashSet::from([1, 2]), 0),
+            &heartbeat_manager,
+        );
+        assert_eq!(
+            stale.response_code,
+            ResponseCode::ControllerFencedSyncStateSetEpoch
+        );
+        let altered = manager.alter_sync_state_set(
+            &header,
+            SyncStateSet::new(HashSet::from([1, 2]), 1),
+            &heartbeat_manager,
+        );
+        assert!(altered.is_success());
+        assert_eq!(altered.response.new_sync_state_set_epoch, 2);
+
+        let fenced = manager.alter_sync_state_set(
+            &AlterSyncStateSetRequestHeader::new("broker-a", 2, 0),
+            SyncStateSet::new(HashSet::from([2]), 2),
+            &heartbeat_manager,
+        );
+        assert_eq!(
+            fenced.response_code,
+            ResponseCode::ControllerFencedMasterEpoch
+        );
+
+        // The designated replica is in the sync state set, so it can take over.
+        let designated = manager.elect_master(
+            &ElectMasterRequestHeader::of_admin_trigger("cluster", "broker-a", 1),
+            &policy,
+            &heartbeat_manager,
+            false,
+        );
+        assert!(designated.is_success());
+        assert_eq!(designated.response.master_broker_id, Some(1));
+        assert_eq!(designated.response.master_epoch, Some(2));
+    }
+
+    #[test]
+    fn refresh_sync_state_set_by_offset_gap() {
+        let mut manager = ReplicasInfoManager::new(None);
+        let mut heartbeat_manager = BrokerHeartbeatManager::new();
+        register(&mut manager, &mut heartbeat_manager, 1, 1000);
+        register(&mut manager, &mut heartbeat_manager, 2, 990);
+        register(&mut manager, &mut heartbeat_manager, 3, 100);
+        let result = manager.elect_master(
+            &ElectMasterRequestHeader::of_controller_trigger("broker-a"),
+            &DefaultElectPolicy,
+            &heartbeat_manager,
+            true,
+        );
+        assert_eq!(result.response.master_broker_id, Some(1));
+
+        let changed = manager.refresh_sync_state_sets(&heartbeat_manager, 100);
+        assert_eq!(changed, vec![CheetahString::from("broker-a")]);
+        let data = manager.get_sync_state_data(&["broker-a".into()], &heartbeat_manager);
+        let replicas = &data.get_replicas_info_table()["broker-a"];
+        assert_eq!(replicas.get_in_sync_replicas().len(), 2);
+        assert_eq!(replicas.get_not_in_sync_replicas().len(), 1);
+        assert!(manager
+            .refresh_sync_state_sets(&heartbeat_manager, 100)
+            .is_empty());
+    }
+}
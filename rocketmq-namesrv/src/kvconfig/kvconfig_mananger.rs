@@ -213,7 +213,7 @@ mod tests {
     use super::*;
 
     fn create_kv_config_manager() -> KVConfigManager {
-        Builder::new().build().name_server_runtime_inner.kvconfig_manager()
+        Builder::new().build().unwrap().name_server_runtime_inner.kvconfig_manager()
         KVConfigManager::new(namesrv_config)
     }
 
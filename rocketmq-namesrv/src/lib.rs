@@ -23,7 +23,10 @@ pub use self::namesrv_config_parse::parse_command_and_config_file;
 pub use self::route::route_info_manager::RouteInfoManager;
 
 pub mod bootstrap;
+pub mod controller;
 mod kvconfig;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod namesrv_config_parse;
 pub mod processor;
 mod route;
@@ -0,0 +1,102 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::io;
+use std::sync::Arc;
+
+use rocketmq_common::common::metrics::instruments::CounterVec;
+use rocketmq_common::common::metrics::metric_registry::MetricRegistry;
+use rocketmq_common::common::metrics::prometheus_exporter::PrometheusExporter;
+use rocketmq_common::common::metrics::text_encoder::TextEncoder;
+use rocketmq_common::common::metrics::MetricsLabels;
+use rocketmq_rust::ArcMut;
+use rocketmq_rust::WeakArcMut;
+use tracing::info;
+
+use crate::bootstrap::NameServerRuntimeInner;
+
+const NODE_TYPE_NAMESRV: &str = "namesrv";
+
+/// Exposes the name server route table and request counters in the Prometheus text
+/// format.
+pub(crate) struct NamesrvMetricsManager {
+    node_id: String,
+    registry: Arc<MetricRegistry>,
+    route_queries: Arc<CounterVec>,
+    exporter: Option<PrometheusExporter>,
+}
+
+impl NamesrvMetricsManager {
+    pub fn new(
+        name_server_runtime_inner: &ArcMut<NameServerRuntimeInner>,
+        node_id: String,
+    ) -> Self {
+        let registry = Arc::new(MetricRegistry::new());
+        let route_queries = registry.counter_vec(
+            "rocketmq_route_queries_total",
+            "Total number of topic route queries.",
+            &[MetricsLabels::NODE_TYPE, MetricsLabels::NODE_ID],
+        );
+        let inner = ArcMut::downgrade(name_server_runtime_inner);
+        let collector_node_id = node_id.clone();
+        registry.register(Arc::new(move |encoder: &mut TextEncoder| {
+            let Some(inner) = WeakArcMut::upgrade(&inner) else {
+                return;
+            };
+            let labels = [
+                (MetricsLabels::NODE_TYPE, NODE_TYPE_NAMESRV),
+                (MetricsLabels::NODE_ID, collector_node_id.as_str()),
+            ];
+            let (topic_num, broker_num) = inner.route_info_manager().topic_and_broker_num();
+            encoder.write_gauge(
+                "rocketmq_broker_number",
+                "Number of live brokers registered to the name server.",
+                &labels,
+                broker_num as f64,
+            );
+            encoder.write_gauge(
+                "rocketmq_topic_number",
+                "Number of topics known by the name server.",
+                &labels,
+                topic_num as f64,
+            );
+        }));
+        NamesrvMetricsManager {
+            node_id,
+            registry,
+            route_queries,
+            exporter: None,
+        }
+    }
+
+    pub fn inc_route_queries(&self) {
+        self.route_queries
+            .inc(&[NODE_TYPE_NAMESRV, self.node_id.as_str()]);
+    }
+
+    pub async fn start(&mut self, host: &str, port: u16) -> io::Result<()> {
+        let exporter = PrometheusExporter::start(host, port, self.registry.clone()).await?;
+        self.exporter = Some(exporter);
+        Ok(())
+    }
+
+    pub fn shutdown(&mut self) {
+        if let Some(exporter) = self.exporter.take() {
+            exporter.shutdown();
+            info!("Name server metrics exporter shutdown");
+        }
+    }
+}
@@ -24,6 +24,7 @@ use rocketmq_rust::ArcMut;
 use tracing::info;
 
 pub use self::client_request_processor::ClientRequestProcessor;
+use crate::controller::ControllerRequestProcessor;
 use crate::processor::default_request_processor::DefaultRequestProcessor;
 
 mod client_request_processor;
@@ -35,6 +36,7 @@ const NAMESPACE_ORDER_TOPIC_CONFIG: &str = "ORDER_TOPIC_CONFIG";
 pub struct NameServerRequestProcessor {
     pub(crate) client_request_processor: ArcMut<ClientRequestProcessor>,
     pub(crate) default_request_processor: ArcMut<DefaultRequestProcessor>,
+    pub(crate) controller_request_processor: Option<ArcMut<ControllerRequestProcessor>>,
 }
 
 impl RequestProcessor for NameServerRequestProcessor {
@@ -44,6 +46,15 @@ impl RequestProcessor for NameServerRequestProcessor {
         ctx: ConnectionHandlerContext,
         request: RemotingCommand,
     ) -> rocketmq_error::RocketMQResult<Option<RemotingCommand>> {
+        if let Some(controller_request_processor) = self.controller_request_processor.as_mut() {
+            if request.code() == RequestCode::BrokerHeartbeat.to_i32() {
+                // Brokers in controller mode report their replica state to the embedded
+                // controller, the route info still needs to be refreshed by the name server.
+                controller_request_processor.on_broker_heartbeat(&request)?;
+            } else if ControllerRequestProcessor::is_controller_request(request.code()) {
+                return controller_request_processor.process_request(channel, ctx, request);
+            }
+        }
         let request_code = RequestCode::from(request.code());
         info!("Name server Received request code: {:?}", request_code);
         match request_code {
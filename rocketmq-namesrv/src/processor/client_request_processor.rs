@@ -56,6 +56,10 @@ impl ClientRequestProcessor {
         request: RemotingCommand,
     ) -> rocketmq_error::RocketMQResult<Option<RemotingCommand>> {
         let request_header = request.decode_command_custom_header::<GetRouteInfoRequestHeader>()?;
+        #[cfg(feature = "metrics")]
+        if let Some(metrics_manager) = self.name_server_runtime_inner.metrics_manager() {
+            metrics_manager.inc_route_queries();
+        }
         let namesrv_ready = self.need_check_namesrv_ready.load(Ordering::Relaxed)
             && TimeUtils::get_current_millis() - self.startup_time_millis
                 >= Duration::from_secs(
@@ -536,6 +536,13 @@ impl RouteInfoManager {
 }
 
 impl RouteInfoManager {
+    /// Returns the number of topics and live brokers in the route table.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn topic_and_broker_num(&self) -> (usize, usize) {
+        let _read_guard = self.lock.read();
+        (self.topic_queue_table.len(), self.broker_live_table.len())
+    }
+
     fn topic_set_of_broker_name(&self, broker_name: &str) -> HashSet<String> {
         let mut topic_of_broker = HashSet::new();
         for (key, value) in self.topic_queue_table.iter() {
@@ -36,6 +36,7 @@ use crate::protocol::RemotingCommandType;
 use crate::runtime::connection_handler_context::ConnectionHandlerContext;
 use crate::runtime::connection_handler_context::ConnectionHandlerContextWrapper;
 use crate::runtime::processor::RequestProcessor;
+use crate::tls::tls_helper::TlsClient;
 
 #[derive(Clone)]
 pub struct Client {
@@ -132,6 +133,7 @@ impl ClientInner {
         addr: T,
         processor: PR,
         tx: Option<&tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
+        tls_client: Option<&TlsClient>,
     ) -> RocketMQResult<(tokio::sync::mpsc::Sender<SendMessage>, ArcMut<ClientInner>)>
     where
         T: tokio::net::ToSocketAddrs,
@@ -144,7 +146,10 @@ impl ClientInner {
         let stream = tcp_stream?;
         let local_addr = stream.local_addr()?;
         let remote_address = stream.peer_addr()?;
-        let connection = Connection::new(stream);
+        let connection = match tls_client {
+            Some(tls_client) => Connection::new(tls_client.connect(stream).await?),
+            None => Connection::new(stream),
+        };
         let response_table = ArcMut::new(HashMap::with_capacity(128));
         let channel_inner = ArcMut::new(ChannelInner::new(connection, response_table.clone()));
         let weak_channel = ArcMut::downgrade(&channel_inner);
@@ -216,12 +221,13 @@ impl Client {
         addr: T,
         processor: PR,
         tx: Option<&tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
+        tls_client: Option<&TlsClient>,
     ) -> RocketMQResult<Client>
     where
         T: tokio::net::ToSocketAddrs,
         PR: RequestProcessor + 'static,
     {
-        let (tx, inner) = ClientInner::connect(addr, processor, tx).await?;
+        let (tx, inner) = ClientInner::connect(addr, processor, tx, tls_client).await?;
         Ok(Client {
             //connection: inner.connection.clone(),
             inner,
@@ -229,6 +235,11 @@ impl Client {
         })
     }
 
+    /// The address of the remote peer this client is connected to.
+    pub fn remote_address(&self) -> std::net::SocketAddr {
+        self.inner.channel.1.remote_address()
+    }
+
     /// Invokes a remote operation with the given `RemotingCommand`.
     ///
     /// # Arguments
@@ -16,6 +16,7 @@
  */
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::atomic::AtomicI32;
 use std::sync::Arc;
 use std::time::Duration;
@@ -42,6 +43,7 @@ use crate::request_processor::default_request_processor::DefaultRemotingRequestP
 use crate::runtime::config::client_config::TokioClientConfig;
 use crate::runtime::processor::RequestProcessor;
 use crate::runtime::RPCHook;
+use crate::tls::tls_helper::TlsClient;
 
 const LOCK_TIMEOUT_MILLIS: u64 = 3000;
 
@@ -58,9 +60,16 @@ pub struct RocketmqDefaultClient<PR = DefaultRemotingRequestProcessor> {
     client_runtime: Option<RocketMQRuntime>,
     processor: PR,
     tx: Option<tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
+    rpc_hooks: Vec<Arc<Box<dyn RPCHook>>>,
+    tls_client: Option<TlsClient>,
 }
 impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
-    pub fn new(tokio_client_config: Arc<TokioClientConfig>, processor: PR) -> Self {
+    /// Creates a remoting client, fails when TLS is enabled and the TLS configuration can not
+    /// be loaded.
+    pub fn new(
+        tokio_client_config: Arc<TokioClientConfig>,
+        processor: PR,
+    ) -> rocketmq_error::RocketMQResult<Self> {
         Self::new_with_cl(tokio_client_config, processor, None)
     }
 
@@ -68,8 +77,13 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
         tokio_client_config: Arc<TokioClientConfig>,
         processor: PR,
         tx: Option<tokio::sync::broadcast::Sender<ConnectionNetEvent>>,
-    ) -> Self {
-        Self {
+    ) -> rocketmq_error::RocketMQResult<Self> {
+        let tls_client = if tokio_client_config.use_tls {
+            Some(TlsClient::new(&tokio_client_config.tls_config)?)
+        } else {
+            None
+        };
+        Ok(Self {
             tokio_client_config,
             connection_tables: Arc::new(Mutex::new(Default::default())),
             namesrv_addr_list: ArcMut::new(Default::default()),
@@ -79,11 +93,35 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
             client_runtime: Some(RocketMQRuntime::new_multi(10, "client-thread")),
             processor,
             tx,
-        }
+            rpc_hooks: Vec::new(),
+            tls_client,
+        })
     }
 }
 
 impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
+    fn do_before_rpc_hooks(
+        &self,
+        remote_addr: SocketAddr,
+        request: &mut RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_before_request(remote_addr, request)?;
+        }
+        Ok(())
+    }
+
+    fn do_after_rpc_hooks(
+        &self,
+        remote_addr: SocketAddr,
+        response: &mut RemotingCommand,
+    ) -> rocketmq_error::RocketMQResult<()> {
+        for hook in self.rpc_hooks.iter() {
+            hook.do_after_response(remote_addr, response)?;
+        }
+        Ok(())
+    }
+
     async fn get_and_create_nameserver_client(&self) -> Option<Client> {
         let mut addr = self.namesrv_addr_choosed.as_ref().clone();
         if let Some(ref addr) = addr {
@@ -181,7 +219,13 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RocketmqDefaultClient<PR> {
         let addr_inner = addr.to_string();
 
         match time::timeout(duration, async {
-            Client::connect(addr_inner, self.processor.clone(), self.tx.as_ref()).await
+            Client::connect(
+                addr_inner,
+                self.processor.clone(),
+                self.tx.as_ref(),
+                self.tls_client.as_ref(),
+            )
+            .await
         })
         .await
         {
@@ -270,11 +314,11 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingService for Rocketmq
     }
 
     fn register_rpc_hook(&mut self, hook: Arc<Box<dyn RPCHook>>) {
-        todo!()
+        self.rpc_hooks.push(hook);
     }
 
     fn clear_rpc_hook(&mut self) {
-        todo!()
+        self.rpc_hooks.clear();
     }
 }
 
@@ -352,6 +396,9 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
                 "get client failed".to_string(),
             )),
             Some(mut client) => {
+                let remote_addr = client.remote_address();
+                let mut request = request;
+                self.do_before_rpc_hooks(remote_addr, &mut request)?;
                 match self
                     .client_runtime
                     .as_ref()
@@ -367,7 +414,10 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
                 {
                     Ok(result) => match result {
                         Ok(response) => match response {
-                            Ok(value) => Ok(value),
+                            Ok(mut value) => {
+                                self.do_after_rpc_hooks(remote_addr, &mut value)?;
+                                Ok(value)
+                            }
                             Err(e) => {
                                 Err(rocketmq_error::RocketmqError::RemoteError(e.to_string()))
                             }
@@ -394,13 +444,17 @@ impl<PR: RequestProcessor + Sync + Clone + 'static> RemotingClient for RocketmqD
                 error!("get client failed");
             }
             Some(mut client) => {
+                let mut request = request;
+                if let Err(e) = self.do_before_rpc_hooks(client.remote_address(), &mut request) {
+                    error!("invoke oneway rpc hook failed: {}", e);
+                    return;
+                }
                 self.client_runtime
                     .as_ref()
                     .unwrap()
                     .get_handle()
                     .spawn(async move {
                         match time::timeout(Duration::from_millis(timeout_millis), async move {
-                            let mut request = request;
                             request.mark_oneway_rpc_ref();
                             client.send(request).await
                         })
@@ -433,3 +487,39 @@ fn init_value_index() -> i32 {
     let mut rng = rand::rng();
     rng.random_range(0..999)
 }
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::server::tls_config::TlsConfig;
+
+    use super::*;
+
+    fn new_client(
+        tls_config: TlsConfig,
+    ) -> rocketmq_error::RocketMQResult<RocketmqDefaultClient<DefaultRemotingRequestProcessor>>
+    {
+        RocketmqDefaultClient::new(
+            Arc::new(TokioClientConfig {
+                use_tls: true,
+                tls_config,
+                ..TokioClientConfig::default()
+            }),
+            DefaultRemotingRequestProcessor,
+        )
+    }
+
+    #[test]
+    fn invalid_tls_configuration_is_an_error() {
+        assert!(new_client(TlsConfig::default()).is_err());
+        assert!(new_client(TlsConfig {
+            tls_client_trust_cert_path: Some("/nonexistent/ca.pem".to_string()),
+            ..TlsConfig::default()
+        })
+        .is_err());
+        assert!(new_client(TlsConfig {
+            tls_test_mode_enable: true,
+            ..TlsConfig::default()
+        })
+        .is_ok());
+    }
+}
@@ -96,6 +96,7 @@ pub enum RequestCode {
     InvokeBrokerToGetConsumerStatus = 223,
     QueryTopicConsumeByWho = 300,
     GetTopicsByCluster = 224,
+    UpdateAndCreateSubscriptionGroupList = 225,
     QueryTopicsByConsumer = 343,
     QuerySubscriptionByConsumer = 345,
     RegisterFilterServer = 301,
@@ -128,6 +129,7 @@ pub enum RequestCode {
     GetTopicConfig = 351,
     GetSubscriptionGroupConfig = 352,
     UpdateAndGetGroupForbidden = 353,
+    CheckRocksdbCqWriteProgress = 354,
     LitePullMessage = 361,
     QueryAssignment = 400,
     SetMessageRequestMode = 401,
@@ -148,6 +150,17 @@ pub enum RequestCode {
     RemoveColdDataFlowCtrConfig = 2002,
     GetColdDataFlowCtrInfo = 2003,
     SetCommitlogReadMode = 2004,
+
+    AuthCreateUser = 3001,
+    AuthUpdateUser = 3002,
+    AuthDeleteUser = 3003,
+    AuthGetUser = 3004,
+    AuthListUser = 3005,
+    AuthCreateAcl = 3006,
+    AuthUpdateAcl = 3007,
+    AuthDeleteAcl = 3008,
+    AuthGetAcl = 3009,
+    AuthListAcl = 3010,
     Unknown = -9999999,
 }
 
@@ -239,6 +252,7 @@ impl From<i32> for RequestCode {
             223 => RequestCode::InvokeBrokerToGetConsumerStatus,
             300 => RequestCode::QueryTopicConsumeByWho,
             224 => RequestCode::GetTopicsByCluster,
+            225 => RequestCode::UpdateAndCreateSubscriptionGroupList,
             343 => RequestCode::QueryTopicsByConsumer,
             345 => RequestCode::QuerySubscriptionByConsumer,
             301 => RequestCode::RegisterFilterServer,
@@ -271,6 +285,7 @@ impl From<i32> for RequestCode {
             351 => RequestCode::GetTopicConfig,
             352 => RequestCode::GetSubscriptionGroupConfig,
             353 => RequestCode::UpdateAndGetGroupForbidden,
+            354 => RequestCode::CheckRocksdbCqWriteProgress,
             361 => RequestCode::LitePullMessage,
             400 => RequestCode::QueryAssignment,
             401 => RequestCode::SetMessageRequestMode,
@@ -290,6 +305,16 @@ impl From<i32> for RequestCode {
             2002 => RequestCode::RemoveColdDataFlowCtrConfig,
             2003 => RequestCode::GetColdDataFlowCtrInfo,
             2004 => RequestCode::SetCommitlogReadMode,
+            3001 => RequestCode::AuthCreateUser,
+            3002 => RequestCode::AuthUpdateUser,
+            3003 => RequestCode::AuthDeleteUser,
+            3004 => RequestCode::AuthGetUser,
+            3005 => RequestCode::AuthListUser,
+            3006 => RequestCode::AuthCreateAcl,
+            3007 => RequestCode::AuthUpdateAcl,
+            3008 => RequestCode::AuthDeleteAcl,
+            3009 => RequestCode::AuthGetAcl,
+            3010 => RequestCode::AuthListAcl,
             _ => RequestCode::Unknown,
         }
     }
@@ -321,3 +346,34 @@ pub enum ControllerRequestCode {
     ControllerGetNextBrokerId = 1012,
     ControllerApplyBrokerId = 1013,
 }
+
+impl From<ControllerRequestCode> for i32 {
+    fn from(value: ControllerRequestCode) -> Self {
+        value as i32
+    }
+}
+
+impl ControllerRequestCode {
+    pub fn to_i32(self) -> i32 {
+        self.into()
+    }
+
+    pub fn value_of(code: i32) -> Option<Self> {
+        match code {
+            1001 => Some(ControllerRequestCode::ControllerAlterSyncStateSet),
+            1002 => Some(ControllerRequestCode::ControllerElectMaster),
+            1003 => Some(ControllerRequestCode::ControllerRegisterBroker),
+            1004 => Some(ControllerRequestCode::ControllerGetReplicaInfo),
+            1005 => Some(ControllerRequestCode::ControllerGetMetadataInfo),
+            1006 => Some(ControllerRequestCode::ControllerGetSyncStateData),
+            1007 => Some(ControllerRequestCode::GetBrokerEpochCache),
+            1008 => Some(ControllerRequestCode::NotifyBrokerRoleChanged),
+            1009 => Some(ControllerRequestCode::UpdateControllerConfig),
+            1010 => Some(ControllerRequestCode::GetControllerConfig),
+            1011 => Some(ControllerRequestCode::CleanBrokerData),
+            1012 => Some(ControllerRequestCode::ControllerGetNextBrokerId),
+            1013 => Some(ControllerRequestCode::ControllerApplyBrokerId),
+            _ => None,
+        }
+    }
+}
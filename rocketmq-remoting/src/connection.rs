@@ -24,11 +24,11 @@ use futures_util::stream::SplitSink;
 use futures_util::stream::SplitStream;
 use futures_util::SinkExt;
 use futures_util::StreamExt;
-use tokio::net::TcpStream;
 use tokio_util::codec::Framed;
 
 use crate::codec::remoting_command_codec::CompositeCodec;
 use crate::protocol::remoting_command::RemotingCommand;
+use crate::tls::transport_stream::TransportStream;
 
 /// Send and receive `Frame` values from a remote peer.
 ///
@@ -46,8 +46,8 @@ pub struct Connection {
     /// The `Framed` instance used for reading from and writing to the TCP stream.
     /// It leverages the `RemotingCommandCodec` for encoding and decoding frames.
     //pub(crate) framed: Framed<TcpStream, RemotingCommandCodec>,
-    writer: SplitSink<Framed<TcpStream, CompositeCodec>, Bytes>,
-    reader: SplitStream<Framed<TcpStream, CompositeCodec>>,
+    writer: SplitSink<Framed<TransportStream, CompositeCodec>, Bytes>,
+    reader: SplitStream<Framed<TransportStream, CompositeCodec>>,
 
     /// A boolean flag indicating the current state of the connection.
     /// `true` means the connection is in a good state, while `false` indicates
@@ -64,10 +64,10 @@ impl Hash for Connection {
 
         // Use the addr: *const _ess of writer and reader to hash them (they serve as a unique
         // identifier for these components)
-        let writer_addr: *const SplitSink<Framed<TcpStream, CompositeCodec>, Bytes> =
-            &self.writer as *const SplitSink<Framed<TcpStream, CompositeCodec>, Bytes>;
-        let reader_addr: *const SplitStream<Framed<TcpStream, CompositeCodec>> =
-            &self.reader as *const SplitStream<Framed<TcpStream, CompositeCodec>>;
+        let writer_addr: *const SplitSink<Framed<TransportStream, CompositeCodec>, Bytes> =
+            &self.writer as *const SplitSink<Framed<TransportStream, CompositeCodec>, Bytes>;
+        let reader_addr: *const SplitStream<Framed<TransportStream, CompositeCodec>> =
+            &self.reader as *const SplitStream<Framed<TransportStream, CompositeCodec>>;
 
         writer_addr.hash(state);
         reader_addr.hash(state);
@@ -92,13 +92,13 @@ impl Connection {
     ///
     /// # Arguments
     ///
-    /// * `tcp_stream` - The `TcpStream` associated with the connection.
+    /// * `stream` - The `TcpStream`, or the TLS stream over it, associated with the connection.
     ///
     /// # Returns
     ///
     /// A new `Connection` instance.
-    pub fn new(tcp_stream: TcpStream) -> Connection {
-        let framed = Framed::with_capacity(tcp_stream, CompositeCodec::new(), 1024 * 4);
+    pub fn new(stream: impl Into<TransportStream>) -> Connection {
+        let framed = Framed::with_capacity(stream.into(), CompositeCodec::new(), 1024 * 4);
         let (writer, reader) = framed.split();
         Self {
             writer,
@@ -109,12 +109,12 @@ impl Connection {
     }
 
     #[inline]
-    pub fn reader(&self) -> &SplitStream<Framed<TcpStream, CompositeCodec>> {
+    pub fn reader(&self) -> &SplitStream<Framed<TransportStream, CompositeCodec>> {
         &self.reader
     }
 
     #[inline]
-    pub fn writer(&self) -> &SplitSink<Framed<TcpStream, CompositeCodec>, Bytes> {
+    pub fn writer(&self) -> &SplitSink<Framed<TransportStream, CompositeCodec>, Bytes> {
         &self.writer
     }
 
@@ -34,3 +34,4 @@ pub mod remoting_server;
 pub mod request_processor;
 pub mod rpc;
 pub mod runtime;
+pub mod tls;
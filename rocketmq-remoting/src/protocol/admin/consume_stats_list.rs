@@ -22,7 +22,7 @@ use serde::Serialize;
 
 use super::consume_stats::ConsumeStats;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct ConsumeStatsList {
     #[serde(rename = "consume_stats_list")]
     pub consume_stats_list: Vec<HashMap<CheetahString, Vec<ConsumeStats>>>,
@@ -19,6 +19,7 @@ pub mod broker_body;
 pub mod consumer_running_info;
 pub mod create_topic_list_request_body;
 pub mod get_consumer_listby_group_response_body;
+pub mod get_consumer_status_body;
 
 pub mod consumer_connection;
 
@@ -35,6 +36,8 @@ pub mod connection;
 pub mod consume_message_directly_result;
 pub mod consume_queue_data;
 pub mod consume_status;
+pub mod elect_master_response_body;
+pub mod epoch_entry_cache;
 pub mod group_list;
 pub mod ha_client_runtime_info;
 pub mod ha_connection_runtime_info;
@@ -50,8 +53,12 @@ pub mod query_assignment_response_body;
 pub mod query_consume_queue_response_body;
 pub mod queue_time_span;
 pub mod request;
+pub mod reset_offset_body;
 pub mod response;
 pub mod set_message_request_mode_request_body;
+pub mod subscription_group_list;
+pub mod subscription_group_wrapper;
+pub mod sync_state_set;
 pub mod topic;
 pub mod topic_info_wrapper;
 pub mod unlock_batch_request_body;
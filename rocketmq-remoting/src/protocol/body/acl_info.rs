@@ -18,7 +18,7 @@ use cheetah_string::CheetahString;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PolicyEntryInfo {
     pub resource: Option<CheetahString>,
@@ -27,14 +27,14 @@ pub struct PolicyEntryInfo {
     pub decision: Option<CheetahString>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PolicyInfo {
     pub policy_type: Option<CheetahString>,
     pub entries: Option<Vec<PolicyEntryInfo>>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AclInfo {
     pub subject: Option<CheetahString>,
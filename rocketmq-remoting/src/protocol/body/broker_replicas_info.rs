@@ -21,7 +21,7 @@ use cheetah_string::CheetahString;
 use serde::Deserialize;
 use serde::Serialize;
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct BrokerReplicasInfo {
     replicas_info_table: HashMap<CheetahString, ReplicasInfo>,
@@ -50,7 +50,7 @@ impl BrokerReplicasInfo {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ReplicasInfo {
     master_broker_id: u64,
@@ -165,7 +165,7 @@ impl ReplicasInfo {
     }
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ReplicaIdentity {
     broker_name: CheetahString,
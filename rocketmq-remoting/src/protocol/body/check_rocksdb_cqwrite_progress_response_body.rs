@@ -25,6 +25,9 @@ pub struct ClusterAclVersionInfo {
     pub diff_result: Option<CheetahString>,
 }
 
+/// Body of the `CheckRocksdbCqWriteProgress` response.
+pub type CheckRocksdbCqWriteProgressResponseBody = ClusterAclVersionInfo;
+
 #[cfg(test)]
 mod tests {
     use serde_json;
@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::sync::Arc;
 
@@ -23,6 +24,8 @@ use dashmap::DashMap;
 use parking_lot::RwLock;
 use rocketmq_common::common::consumer::consume_from_where::ConsumeFromWhere;
 use serde::ser::SerializeStruct;
+use serde::Deserialize;
+use serde::Deserializer;
 use serde::Serialize;
 use serde::Serializer;
 
@@ -67,6 +70,36 @@ impl Serialize for ConsumerConnection {
     }
 }
 
+impl<'de> Deserialize<'de> for ConsumerConnection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct ConsumerConnectionData {
+            #[serde(default, alias = "connectionSet")]
+            connection_set: HashSet<Connection>,
+            #[serde(default, alias = "subscriptionTable")]
+            subscription_table: HashMap<CheetahString, SubscriptionData>,
+            #[serde(default, alias = "consumeType")]
+            consume_type: ConsumeType,
+            #[serde(default, alias = "messageModel")]
+            message_model: MessageModel,
+            #[serde(default, alias = "consumeFromWhere")]
+            consume_from_where: ConsumeFromWhere,
+        }
+
+        let data = ConsumerConnectionData::deserialize(deserializer)?;
+        Ok(ConsumerConnection {
+            connection_set: data.connection_set,
+            subscription_table: Arc::new(data.subscription_table.into_iter().collect()),
+            consume_type: Arc::new(RwLock::new(data.consume_type)),
+            message_model: Arc::new(RwLock::new(data.message_model)),
+            consume_from_where: Arc::new(RwLock::new(data.consume_from_where)),
+        })
+    }
+}
+
 impl ConsumerConnection {
     pub fn get_connection_set(&self) -> HashSet<Connection> {
         self.connection_set.clone()
@@ -111,3 +144,26 @@ impl ConsumerConnection {
         *self.consume_from_where.write() = consume_from_where;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn consumer_connection_round_trip() {
+        let mut connection = Connection::new();
+        connection.set_client_id(CheetahString::from_static_str("client-1"));
+        let mut consumer_connection = ConsumerConnection::new();
+        consumer_connection.set_connection_set(HashSet::from([connection]));
+        consumer_connection.set_message_model(MessageModel::Broadcasting);
+        consumer_connection.set_consume_type(ConsumeType::ConsumePassively);
+
+        let decoded =
+            ConsumerConnection::decode(consumer_connection.to_json().unwrap().as_bytes()).unwrap();
+        assert_eq!(decoded.get_connection_set().len(), 1);
+        assert_eq!(decoded.get_message_model(), MessageModel::Broadcasting);
+        assert_eq!(decoded.get_consume_type(), ConsumeType::ConsumePassively);
+    }
+}
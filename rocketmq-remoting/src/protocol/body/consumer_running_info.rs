@@ -14,5 +14,109 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-#[derive(Clone)]
-pub struct ConsumerRunningInfo {}
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json_any_key::*;
+
+use crate::protocol::body::consume_status::ConsumeStatus;
+use crate::protocol::body::pop_process_queue_info::PopProcessQueueInfo;
+use crate::protocol::body::process_queue_info::ProcessQueueInfo;
+use crate::protocol::heartbeat::subscription_data::SubscriptionData;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumerRunningInfo {
+    pub properties: HashMap<CheetahString, CheetahString>,
+    pub subscription_set: HashSet<SubscriptionData>,
+    #[serde(with = "any_key_map")]
+    pub mq_table: HashMap<MessageQueue, ProcessQueueInfo>,
+    #[serde(with = "any_key_map")]
+    pub mq_pop_table: HashMap<MessageQueue, PopProcessQueueInfo>,
+    pub status_table: BTreeMap<CheetahString, ConsumeStatus>,
+    pub user_consumer_info: BTreeMap<CheetahString, CheetahString>,
+    pub jstack: Option<CheetahString>,
+}
+
+impl ConsumerRunningInfo {
+    pub const PROP_NAMESERVER_ADDR: &'static str = "PROP_NAMESERVER_ADDR";
+    pub const PROP_THREADPOOL_CORE_SIZE: &'static str = "PROP_THREADPOOL_CORE_SIZE";
+    pub const PROP_CONSUME_ORDERLY: &'static str = "PROP_CONSUMEORDERLY";
+    pub const PROP_CONSUME_TYPE: &'static str = "PROP_CONSUME_TYPE";
+    pub const PROP_CLIENT_VERSION: &'static str = "PROP_CLIENT_VERSION";
+    pub const PROP_CONSUMER_START_TIMESTAMP: &'static str = "PROP_CONSUMER_START_TIMESTAMP";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_property(&self, key: &str) -> Option<&CheetahString> {
+        self.properties.get(key)
+    }
+
+    pub fn set_property(&mut self, key: impl Into<CheetahString>, value: impl Into<CheetahString>) {
+        self.properties.insert(key.into(), value.into());
+    }
+
+    /// Whether the consumer consumes orderly, according to the reported properties.
+    pub fn is_consume_orderly(&self) -> bool {
+        self.get_property(Self::PROP_CONSUME_ORDERLY)
+            .is_some_and(|value| value.as_str() == "true")
+    }
+
+    /// Sum of the messages cached by all the process queues of the consumer.
+    pub fn total_cached_msg_count(&self) -> u64 {
+        self.mq_table
+            .values()
+            .map(|info| info.cached_msg_count as u64)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn consumer_running_info_round_trip() {
+        let mut info = ConsumerRunningInfo::new();
+        info.set_property(ConsumerRunningInfo::PROP_CONSUME_ORDERLY, "true");
+        let mq = MessageQueue::from_parts("TopicTest", "broker-a", 0);
+        info.mq_table.insert(
+            mq.clone(),
+            ProcessQueueInfo {
+                commit_offset: 10,
+                cached_msg_count: 3,
+                ..Default::default()
+            },
+        );
+        info.status_table.insert(
+            CheetahString::from_static_str("TopicTest"),
+            ConsumeStatus {
+                consume_ok_tps: 1.5,
+                ..Default::default()
+            },
+        );
+
+        let bytes = info.encode().unwrap();
+        let decoded = ConsumerRunningInfo::decode(&bytes).unwrap();
+        assert!(decoded.is_consume_orderly());
+        assert_eq!(decoded.total_cached_msg_count(), 3);
+        assert_eq!(decoded.mq_table.get(&mq).unwrap().commit_offset, 10);
+        assert_eq!(
+            decoded
+                .status_table
+                .get("TopicTest")
+                .unwrap()
+                .consume_ok_tps,
+            1.5
+        );
+    }
+}
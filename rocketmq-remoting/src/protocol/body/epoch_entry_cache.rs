@@ -0,0 +1,118 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fmt::Display;
+
+use cheetah_string::CheetahString;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A range of the commit log written while a broker was master in `epoch`.
+///
+/// `end_offset` is `i64::MAX` while the epoch is still the latest one.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochEntry {
+    pub epoch: i32,
+    pub start_offset: i64,
+    pub end_offset: i64,
+}
+
+impl EpochEntry {
+    pub fn new(epoch: i32, start_offset: i64) -> Self {
+        Self {
+            epoch,
+            start_offset,
+            end_offset: i64::MAX,
+        }
+    }
+}
+
+impl Default for EpochEntry {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
+impl Display for EpochEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EpochEntry{{epoch={}, startOffset={}, endOffset={}}}",
+            self.epoch, self.start_offset, self.end_offset
+        )
+    }
+}
+
+/// Body of `GET_BROKER_EPOCH_CACHE`, describing the epoch history of a broker.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EpochEntryCache {
+    pub cluster_name: CheetahString,
+    pub broker_name: CheetahString,
+    pub broker_id: i64,
+    pub epoch_list: Vec<EpochEntry>,
+    pub max_offset: i64,
+}
+
+impl EpochEntryCache {
+    pub fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_id: i64,
+        epoch_list: Vec<EpochEntry>,
+        max_offset: i64,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            broker_id,
+            epoch_list,
+            max_offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn epoch_entry_cache_json_round_trip() {
+        let cache = EpochEntryCache::new(
+            "cluster",
+            "broker-a",
+            1,
+            vec![
+                EpochEntry {
+                    epoch: 1,
+                    start_offset: 0,
+                    end_offset: 100,
+                },
+                EpochEntry::new(2, 100),
+            ],
+            200,
+        );
+        let json = cache.to_json().unwrap();
+        assert!(json.contains("\"epochList\""));
+        let decoded = EpochEntryCache::decode(json.as_bytes()).unwrap();
+        assert_eq!(decoded.broker_name, "broker-a");
+        assert_eq!(decoded.epoch_list, cache.epoch_list);
+        assert_eq!(decoded.max_offset, 200);
+    }
+}
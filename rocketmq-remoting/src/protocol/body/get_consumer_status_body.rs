@@ -0,0 +1,57 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::message::message_queue::MessageQueue;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json_any_key::*;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetConsumerStatusBody {
+    #[serde(with = "any_key_map")]
+    pub message_queue_table: HashMap<MessageQueue, i64>,
+    pub consumer_table: HashMap<CheetahString, MessageQueueOffsetTable>,
+}
+
+/// Consume offsets reported by a single client.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageQueueOffsetTable(#[serde(with = "any_key_map")] pub HashMap<MessageQueue, i64>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn get_consumer_status_body_round_trip() {
+        let mq = MessageQueue::from_parts("TopicTest", "broker-a", 0);
+        let mut body = GetConsumerStatusBody::default();
+        body.message_queue_table.insert(mq.clone(), 7);
+        body.consumer_table.insert(
+            CheetahString::from_static_str("client-1"),
+            MessageQueueOffsetTable(HashMap::from([(mq.clone(), 8)])),
+        );
+        let decoded = GetConsumerStatusBody::decode(&body.encode().unwrap()).unwrap();
+        assert_eq!(decoded.message_queue_table.get(&mq), Some(&7));
+        assert_eq!(decoded.consumer_table["client-1"].0.get(&mq), Some(&8));
+    }
+}
@@ -0,0 +1,51 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionGroupList {
+    pub group_config_list: Vec<SubscriptionGroupConfig>,
+}
+
+impl SubscriptionGroupList {
+    pub fn new(group_config_list: Vec<SubscriptionGroupConfig>) -> Self {
+        Self { group_config_list }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn subscription_group_list_round_trip() {
+        let list = SubscriptionGroupList::new(vec![
+            SubscriptionGroupConfig::new("group_a".into()),
+            SubscriptionGroupConfig::new("group_b".into()),
+        ]);
+        let bytes = list.encode().unwrap();
+        let decoded = SubscriptionGroupList::decode(&bytes).unwrap();
+        assert_eq!(decoded.group_config_list.len(), 2);
+        assert_eq!(decoded.group_config_list[1].group_name(), "group_b");
+    }
+}
@@ -0,0 +1,67 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+use crate::protocol::DataVersion;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionGroupWrapper {
+    pub subscription_group_table: HashMap<CheetahString, SubscriptionGroupConfig>,
+    #[serde(default)]
+    pub forbidden_table: HashMap<CheetahString, HashMap<CheetahString, i32>>,
+    pub data_version: DataVersion,
+}
+
+impl SubscriptionGroupWrapper {
+    pub fn subscription_group_table(&self) -> &HashMap<CheetahString, SubscriptionGroupConfig> {
+        &self.subscription_group_table
+    }
+
+    pub fn forbidden_table(&self) -> &HashMap<CheetahString, HashMap<CheetahString, i32>> {
+        &self.forbidden_table
+    }
+
+    pub fn data_version(&self) -> &DataVersion {
+        &self.data_version
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::RemotingDeserializable;
+    use crate::protocol::RemotingSerializable;
+
+    #[test]
+    fn subscription_group_wrapper_round_trip() {
+        let mut wrapper = SubscriptionGroupWrapper::default();
+        wrapper.subscription_group_table.insert(
+            "group_a".into(),
+            SubscriptionGroupConfig::new("group_a".into()),
+        );
+        let bytes = wrapper.encode().unwrap();
+        let decoded = SubscriptionGroupWrapper::decode(&bytes).unwrap();
+        assert!(decoded.subscription_group_table().contains_key("group_a"));
+        assert!(decoded.forbidden_table().is_empty());
+    }
+}
@@ -15,31 +15,39 @@
  * limitations under the License.
  */
 pub mod ack_message_request_header;
+pub mod auth;
 pub mod broker;
 pub mod change_invisible_time_request_header;
 pub mod change_invisible_time_response_header;
+pub mod check_rocksdb_cq_write_progress_request_header;
 pub mod check_transaction_state_request_header;
 pub mod client_request_header;
 pub mod consume_message_directly_result_request_header;
 pub mod consumer_send_msg_back_request_header;
+pub mod controller;
+pub mod create_access_config_request_header;
 pub mod create_topic_request_header;
+pub mod delete_access_config_request_header;
 pub mod delete_subscription_group_request_header;
 pub mod delete_topic_request_header;
 pub mod elect_master_response_header;
 pub mod end_transaction_request_header;
 pub mod extra_info_util;
 pub mod get_all_topic_config_response_header;
+pub mod get_consume_stats_in_broker_header;
 pub mod get_consume_stats_request_header;
 pub mod get_consumer_connection_list_request_header;
 pub mod get_consumer_listby_group_request_header;
 pub mod get_consumer_listby_group_response_header;
 pub mod get_consumer_running_info_request_header;
+pub mod get_consumer_status_request_header;
 pub mod get_earliest_msg_storetime_response_header;
 pub mod get_max_offset_request_header;
 pub mod get_max_offset_response_header;
 pub mod get_meta_data_response_header;
 pub mod get_min_offset_request_header;
 pub mod get_min_offset_response_header;
+pub mod get_producer_connection_list_request_header;
 pub mod get_topic_config_request_header;
 pub mod get_topic_stats_info_request_header;
 pub mod get_topic_stats_request_header;
@@ -63,9 +71,12 @@ pub mod query_topic_consume_by_who_request_header;
 pub mod query_topics_by_consumer_request_header;
 pub mod reply_message_request_header;
 pub mod reset_offset_request_header;
+pub mod search_offset_request_header;
 pub mod search_offset_response_header;
 pub mod unlock_batch_mq_request_header;
 pub mod unregister_client_request_header;
 pub mod update_consumer_offset_header;
+pub mod update_global_white_addrs_config_request_header;
+pub mod view_broker_stats_data_request_header;
 pub mod view_message_request_header;
 pub mod view_message_response_header;
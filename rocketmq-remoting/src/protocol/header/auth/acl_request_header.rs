@@ -0,0 +1,90 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The policies to create are carried by the `AclInfo` body.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAclRequestHeader {
+    #[required]
+    pub subject: CheetahString,
+}
+
+/// The policies to update are carried by the `AclInfo` body.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAclRequestHeader {
+    #[required]
+    pub subject: CheetahString,
+}
+
+/// Deletes the whole acl of the subject, or only the entries of `resource` when it is set.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAclRequestHeader {
+    #[required]
+    pub subject: CheetahString,
+
+    pub policy_type: Option<CheetahString>,
+
+    pub resource: Option<CheetahString>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetAclRequestHeader {
+    #[required]
+    pub subject: CheetahString,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct ListAclsRequestHeader {
+    pub subject_filter: Option<CheetahString>,
+
+    pub resource_filter: Option<CheetahString>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn delete_acl_request_header_round_trips_through_map() {
+        let header = DeleteAclRequestHeader {
+            subject: CheetahString::from_static_str("User:rocketmq"),
+            policy_type: None,
+            resource: Some(CheetahString::from_static_str("Topic:test")),
+        };
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("subject").unwrap(), "User:rocketmq");
+        assert!(!map.contains_key("policyType"));
+
+        let decoded = <DeleteAclRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.subject, "User:rocketmq");
+        assert_eq!(decoded.resource.as_deref(), Some("Topic:test"));
+
+        assert!(<DeleteAclRequestHeader as FromMap>::from(&HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The user to create is carried by the `UserInfo` body.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateUserRequestHeader {
+    #[required]
+    pub username: CheetahString,
+}
+
+/// The new state of the user is carried by the `UserInfo` body.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateUserRequestHeader {
+    #[required]
+    pub username: CheetahString,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteUserRequestHeader {
+    #[required]
+    pub username: CheetahString,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUserRequestHeader {
+    #[required]
+    pub username: CheetahString,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct ListUsersRequestHeader {
+    /// Only the users whose name contains the filter are listed.
+    pub filter: Option<CheetahString>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn create_user_request_header_round_trips_through_map() {
+        let header = CreateUserRequestHeader {
+            username: CheetahString::from_static_str("rocketmq"),
+        };
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("username").unwrap(), "rocketmq");
+        let decoded = <CreateUserRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.username, "rocketmq");
+
+        assert!(<CreateUserRequestHeader as FromMap>::from(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn list_users_request_header_filter_is_optional() {
+        let decoded = <ListUsersRequestHeader as FromMap>::from(&HashMap::new()).unwrap();
+        assert!(decoded.filter.is_none());
+    }
+}
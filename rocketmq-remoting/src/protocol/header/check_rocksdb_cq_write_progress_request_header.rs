@@ -0,0 +1,59 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckRocksdbCqWriteProgressRequestHeader {
+    #[required]
+    pub topic: CheetahString,
+
+    pub check_store_time: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn check_rocksdb_cq_write_progress_request_header_round_trips_through_map() {
+        let header = CheckRocksdbCqWriteProgressRequestHeader {
+            topic: CheetahString::from_static_str("test_topic"),
+            check_store_time: Some(1000),
+        };
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("topic").unwrap(), "test_topic");
+        assert_eq!(map.get("checkStoreTime").unwrap(), "1000");
+
+        let decoded = <CheckRocksdbCqWriteProgressRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.topic, "test_topic");
+        assert_eq!(decoded.check_store_time, Some(1000));
+    }
+
+    #[test]
+    fn check_rocksdb_cq_write_progress_request_header_requires_topic() {
+        let map = HashMap::new();
+        assert!(<CheckRocksdbCqWriteProgressRequestHeader as FromMap>::from(&map).is_err());
+    }
+}
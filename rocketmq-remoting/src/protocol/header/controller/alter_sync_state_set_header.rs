@@ -0,0 +1,85 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct AlterSyncStateSetRequestHeader {
+    #[required]
+    pub broker_name: CheetahString,
+
+    #[required]
+    pub master_broker_id: i64,
+
+    #[required]
+    pub master_epoch: i32,
+
+    pub invoke_time: i64,
+}
+
+impl AlterSyncStateSetRequestHeader {
+    pub fn new(
+        broker_name: impl Into<CheetahString>,
+        master_broker_id: i64,
+        master_epoch: i32,
+    ) -> Self {
+        Self {
+            broker_name: broker_name.into(),
+            master_broker_id,
+            master_epoch,
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct AlterSyncStateSetResponseHeader {
+    pub new_sync_state_set_epoch: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn alter_sync_state_set_request_header_round_trip() {
+        let header = AlterSyncStateSetRequestHeader::new("broker-a", 1, 3);
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("brokerName").unwrap(), "broker-a");
+        assert_eq!(map.get("masterBrokerId").unwrap(), "1");
+        assert_eq!(map.get("masterEpoch").unwrap(), "3");
+        let decoded = <AlterSyncStateSetRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.broker_name, "broker-a");
+        assert_eq!(decoded.master_broker_id, 1);
+        assert_eq!(decoded.master_epoch, 3);
+    }
+
+    #[test]
+    fn alter_sync_state_set_request_header_requires_broker_name() {
+        let map = HashMap::new();
+        assert!(<AlterSyncStateSetRequestHeader as FromMap>::from(&map).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyBrokerIdRequestHeader {
+    #[required]
+    pub cluster_name: CheetahString,
+
+    #[required]
+    pub broker_name: CheetahString,
+
+    #[required]
+    pub applied_broker_id: i64,
+
+    /// Identifies the broker process applying for the id, so that a broker restarting with
+    /// the same metadata file can apply for its id again.
+    #[required]
+    pub register_check_code: CheetahString,
+}
+
+impl ApplyBrokerIdRequestHeader {
+    pub fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        applied_broker_id: i64,
+        register_check_code: impl Into<CheetahString>,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            applied_broker_id,
+            register_check_code: register_check_code.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyBrokerIdResponseHeader {
+    pub cluster_name: Option<CheetahString>,
+    pub broker_name: Option<CheetahString>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn apply_broker_id_request_header_round_trip() {
+        let header = ApplyBrokerIdRequestHeader::new("cluster", "broker-a", 2, "addr;1");
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("appliedBrokerId").unwrap(), "2");
+        let decoded = <ApplyBrokerIdRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.applied_broker_id, 2);
+        assert_eq!(decoded.register_check_code, "addr;1");
+    }
+}
@@ -0,0 +1,80 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanControllerBrokerDataRequestHeader {
+    pub cluster_name: Option<CheetahString>,
+
+    #[required]
+    pub broker_name: CheetahString,
+
+    /// Broker ids separated by `;`. All brokers of the group are cleaned when absent.
+    pub broker_controller_ids_to_clean: Option<CheetahString>,
+
+    /// Whether brokers which are still alive may be cleaned as well.
+    pub is_clean_living_broker: bool,
+
+    pub invoke_time: i64,
+}
+
+impl CleanControllerBrokerDataRequestHeader {
+    pub fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_controller_ids_to_clean: Option<CheetahString>,
+        is_clean_living_broker: bool,
+    ) -> Self {
+        Self {
+            cluster_name: Some(cluster_name.into()),
+            broker_name: broker_name.into(),
+            broker_controller_ids_to_clean,
+            is_clean_living_broker,
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn clean_controller_broker_data_request_header_round_trip() {
+        let header = CleanControllerBrokerDataRequestHeader::new(
+            "cluster",
+            "broker-a",
+            Some("1;2".into()),
+            true,
+        );
+        let map = header.to_map().unwrap();
+        let decoded = <CleanControllerBrokerDataRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.cluster_name.as_deref(), Some("cluster"));
+        assert_eq!(
+            decoded.broker_controller_ids_to_clean.as_deref(),
+            Some("1;2")
+        );
+        assert!(decoded.is_clean_living_broker);
+    }
+}
@@ -0,0 +1,111 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectMasterRequestHeader {
+    #[required]
+    pub cluster_name: CheetahString,
+
+    #[required]
+    pub broker_name: CheetahString,
+
+    /// The broker expected to become master. `None` lets the controller choose.
+    pub broker_id: Option<i64>,
+
+    /// Whether the election is designated by an admin tool instead of a broker.
+    pub designate_elect: bool,
+
+    pub invoke_time: i64,
+}
+
+impl ElectMasterRequestHeader {
+    /// Election triggered by a broker which wants to become master itself.
+    pub fn of_broker_trigger(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_id: i64,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            broker_id: Some(broker_id),
+            designate_elect: false,
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+
+    /// Election triggered by the controller itself, e.g. when the master is inactive.
+    pub fn of_controller_trigger(broker_name: impl Into<CheetahString>) -> Self {
+        Self {
+            cluster_name: CheetahString::empty(),
+            broker_name: broker_name.into(),
+            broker_id: None,
+            designate_elect: false,
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+
+    /// Election designated by an admin, which may not be the best candidate.
+    pub fn of_admin_trigger(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_id: i64,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            broker_id: Some(broker_id),
+            designate_elect: true,
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn admin_trigger_is_designated() {
+        let header = ElectMasterRequestHeader::of_admin_trigger("cluster", "broker-a", 2);
+        assert!(header.designate_elect);
+        assert_eq!(header.broker_id, Some(2));
+        let controller = ElectMasterRequestHeader::of_controller_trigger("broker-a");
+        assert!(!controller.designate_elect);
+        assert!(controller.broker_id.is_none());
+    }
+
+    #[test]
+    fn elect_master_request_header_round_trip() {
+        let header = ElectMasterRequestHeader::of_broker_trigger("cluster", "broker-a", 3);
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("clusterName").unwrap(), "cluster");
+        assert_eq!(map.get("brokerId").unwrap(), "3");
+        let decoded = <ElectMasterRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.broker_name, "broker-a");
+        assert_eq!(decoded.broker_id, Some(3));
+        assert!(!decoded.designate_elect);
+    }
+}
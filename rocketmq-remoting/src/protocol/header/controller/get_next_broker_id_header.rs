@@ -0,0 +1,70 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNextBrokerIdRequestHeader {
+    #[required]
+    pub cluster_name: CheetahString,
+
+    #[required]
+    pub broker_name: CheetahString,
+}
+
+impl GetNextBrokerIdRequestHeader {
+    pub fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetNextBrokerIdResponseHeader {
+    pub cluster_name: Option<CheetahString>,
+    pub broker_name: Option<CheetahString>,
+    pub next_broker_id: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn get_next_broker_id_response_header_round_trip() {
+        let header = GetNextBrokerIdResponseHeader {
+            cluster_name: Some("cluster".into()),
+            broker_name: Some("broker-a".into()),
+            next_broker_id: Some(3),
+        };
+        let map = header.to_map().unwrap();
+        let decoded = <GetNextBrokerIdResponseHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.next_broker_id, Some(3));
+        assert_eq!(decoded.broker_name.as_deref(), Some("broker-a"));
+    }
+}
@@ -0,0 +1,64 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplicaInfoRequestHeader {
+    #[required]
+    pub broker_name: CheetahString,
+}
+
+impl GetReplicaInfoRequestHeader {
+    pub fn new(broker_name: impl Into<CheetahString>) -> Self {
+        Self {
+            broker_name: broker_name.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetReplicaInfoResponseHeader {
+    pub master_broker_id: Option<i64>,
+    pub master_address: Option<CheetahString>,
+    pub master_epoch: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn get_replica_info_response_header_round_trip() {
+        let header = GetReplicaInfoResponseHeader {
+            master_broker_id: Some(1),
+            master_address: Some("127.0.0.1:10911".into()),
+            master_epoch: Some(2),
+        };
+        let map = header.to_map().unwrap();
+        let decoded = <GetReplicaInfoResponseHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.master_broker_id, Some(1));
+        assert_eq!(decoded.master_address.as_deref(), Some("127.0.0.1:10911"));
+        assert_eq!(decoded.master_epoch, Some(2));
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterBrokerToControllerRequestHeader {
+    #[required]
+    pub cluster_name: CheetahString,
+
+    #[required]
+    pub broker_name: CheetahString,
+
+    pub broker_id: Option<i64>,
+
+    pub broker_address: Option<CheetahString>,
+
+    pub invoke_time: i64,
+}
+
+impl RegisterBrokerToControllerRequestHeader {
+    pub fn new(
+        cluster_name: impl Into<CheetahString>,
+        broker_name: impl Into<CheetahString>,
+        broker_id: i64,
+        broker_address: impl Into<CheetahString>,
+    ) -> Self {
+        Self {
+            cluster_name: cluster_name.into(),
+            broker_name: broker_name.into(),
+            broker_id: Some(broker_id),
+            broker_address: Some(broker_address.into()),
+            invoke_time: get_current_millis() as i64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterBrokerToControllerResponseHeader {
+    pub cluster_name: Option<CheetahString>,
+    pub broker_name: Option<CheetahString>,
+    pub master_broker_id: Option<i64>,
+    pub master_address: Option<CheetahString>,
+    pub master_epoch: Option<i32>,
+    pub sync_state_set_epoch: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn register_broker_to_controller_request_header_round_trip() {
+        let header = RegisterBrokerToControllerRequestHeader::new(
+            "cluster",
+            "broker-a",
+            2,
+            "127.0.0.1:10911",
+        );
+        let map = header.to_map().unwrap();
+        let decoded = <RegisterBrokerToControllerRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.cluster_name, "cluster");
+        assert_eq!(decoded.broker_name, "broker-a");
+        assert_eq!(decoded.broker_id, Some(2));
+        assert_eq!(decoded.broker_address.as_deref(), Some("127.0.0.1:10911"));
+    }
+}
@@ -0,0 +1,81 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAccessConfigRequestHeader {
+    #[required]
+    pub access_key: CheetahString,
+
+    pub secret_key: Option<CheetahString>,
+
+    pub white_remote_address: Option<CheetahString>,
+
+    pub default_topic_perm: Option<CheetahString>,
+
+    pub default_group_perm: Option<CheetahString>,
+
+    pub admin: bool,
+
+    /// Comma separated `resource=perm` pairs.
+    pub topic_perms: Option<CheetahString>,
+
+    /// Comma separated `resource=perm` pairs.
+    pub group_perms: Option<CheetahString>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn create_access_config_request_header_round_trips_through_map() {
+        let header = CreateAccessConfigRequestHeader {
+            access_key: CheetahString::from_static_str("rocketmq2"),
+            secret_key: Some(CheetahString::from_static_str("12345678")),
+            admin: true,
+            topic_perms: Some(CheetahString::from_static_str("topicA=PUB,topicB=SUB")),
+            ..Default::default()
+        };
+        let map = header.to_map().unwrap();
+        assert_eq!(map.get("accessKey").unwrap(), "rocketmq2");
+        assert_eq!(map.get("admin").unwrap(), "true");
+        assert!(!map.contains_key("groupPerms"));
+
+        let decoded = <CreateAccessConfigRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.secret_key.as_deref(), Some("12345678"));
+        assert!(decoded.admin);
+        assert_eq!(
+            decoded.topic_perms.as_deref(),
+            Some("topicA=PUB,topicB=SUB")
+        );
+    }
+
+    #[test]
+    fn create_access_config_request_header_requires_access_key() {
+        let map = HashMap::new();
+        assert!(<CreateAccessConfigRequestHeader as FromMap>::from(&map).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::rpc::rpc_request_header::RpcRequestHeader;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct GetProducerConnectionListRequestHeader {
+    #[required]
+    pub producer_group: CheetahString,
+
+    #[serde(flatten)]
+    pub rpc_request_header: Option<RpcRequestHeader>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn get_producer_connection_list_request_header_serializes_correctly() {
+        let header = GetProducerConnectionListRequestHeader {
+            producer_group: CheetahString::from_static_str("test_group"),
+            rpc_request_header: None,
+        };
+        let serialized = serde_json::to_string(&header).unwrap();
+        assert_eq!(serialized, r#"{"producerGroup":"test_group"}"#);
+    }
+
+    #[test]
+    fn get_producer_connection_list_request_header_map_round_trip() {
+        let header = GetProducerConnectionListRequestHeader {
+            producer_group: CheetahString::from_static_str("test_group"),
+            rpc_request_header: None,
+        };
+        let map = header.to_map().unwrap();
+        assert_eq!(
+            map.get(&CheetahString::from_static_str("producerGroup")),
+            Some(&CheetahString::from_static_str("test_group"))
+        );
+        let decoded = <GetProducerConnectionListRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.producer_group, header.producer_group);
+    }
+
+    #[test]
+    fn get_producer_connection_list_request_header_requires_group() {
+        let map: HashMap<CheetahString, CheetahString> = HashMap::new();
+        assert!(<GetProducerConnectionListRequestHeader as FromMap>::from(&map).is_err());
+    }
+}
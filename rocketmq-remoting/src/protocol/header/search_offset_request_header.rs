@@ -0,0 +1,71 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use cheetah_string::CheetahString;
+use rocketmq_macros::RequestHeaderCodec;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::rpc::topic_request_header::TopicRequestHeader;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, RequestHeaderCodec)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOffsetRequestHeader {
+    #[required]
+    pub topic: CheetahString,
+
+    #[required]
+    pub queue_id: i32,
+
+    #[required]
+    pub timestamp: i64,
+
+    pub boundary_type: Option<CheetahString>,
+
+    #[serde(flatten)]
+    pub topic_request_header: Option<TopicRequestHeader>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protocol::command_custom_header::CommandCustomHeader;
+    use crate::protocol::command_custom_header::FromMap;
+
+    #[test]
+    fn search_offset_request_header_map_round_trip() {
+        let header = SearchOffsetRequestHeader {
+            topic: CheetahString::from_static_str("test_topic"),
+            queue_id: 3,
+            timestamp: 1_700_000_000_000,
+            boundary_type: Some(CheetahString::from_static_str("lower")),
+            topic_request_header: None,
+        };
+        let map: HashMap<CheetahString, CheetahString> = header.to_map().unwrap();
+        assert_eq!(map.get("queueId").unwrap(), "3");
+        assert_eq!(map.get("timestamp").unwrap(), "1700000000000");
+        assert_eq!(map.get("boundaryType").unwrap(), "lower");
+
+        let decoded = <SearchOffsetRequestHeader as FromMap>::from(&map).unwrap();
+        assert_eq!(decoded.topic, "test_topic");
+        assert_eq!(decoded.queue_id, 3);
+        assert_eq!(decoded.timestamp, 1_700_000_000_000);
+        assert_eq!(decoded.boundary_type.as_deref(), Some("lower"));
+    }
+}
@@ -39,6 +39,15 @@ impl ConsumeType {
             ConsumeType::ConsumePop => "POP",
         }
     }
+
+    /// The name of the variant as used by the Java client, e.g. `CONSUME_PASSIVELY`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConsumeType::ConsumeActively => "CONSUME_ACTIVELY",
+            ConsumeType::ConsumePassively => "CONSUME_PASSIVELY",
+            ConsumeType::ConsumePop => "CONSUME_POP",
+        }
+    }
 }
 
 impl Serialize for ConsumeType {
@@ -46,12 +55,7 @@ impl Serialize for ConsumeType {
     where
         S: Serializer,
     {
-        let value = match self {
-            ConsumeType::ConsumeActively => "CONSUME_ACTIVELY",
-            ConsumeType::ConsumePassively => "CONSUME_PASSIVELY",
-            ConsumeType::ConsumePop => "CONSUME_POP",
-        };
-        serializer.serialize_str(value)
+        serializer.serialize_str(self.name())
     }
 }
 
@@ -709,9 +709,9 @@ impl RemotingCommand {
         key: impl Into<CheetahString>,
         value: impl Into<CheetahString>,
     ) -> &mut Self {
-        if let Some(ref mut ext) = self.ext_fields {
-            ext.insert(key.into(), value.into());
-        }
+        self.ext_fields
+            .get_or_insert_with(HashMap::new)
+            .insert(key.into(), value.into());
         self
     }
 
@@ -20,6 +20,7 @@ use serde::Serialize;
 use crate::protocol::subscription::retry_policy::RetryPolicy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CustomizedRetryPolicy {
     next: Vec<i64>,
 }
@@ -22,6 +22,7 @@ use serde::Serialize;
 use crate::protocol::subscription::retry_policy::RetryPolicy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ExponentialRetryPolicy {
     initial: u64,
     max: u64,
@@ -26,7 +26,7 @@ use crate::protocol::subscription::group_retry_policy::GroupRetryPolicy;
 use crate::protocol::subscription::simple_subscription_data::SimpleSubscriptionData;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", default)]
 pub struct SubscriptionGroupConfig {
     group_name: CheetahString,
 
@@ -251,6 +251,7 @@ impl SubscriptionGroupConfig {
 #[cfg(test)]
 mod subscription_group_config_tests {
     use super::*;
+    use crate::protocol::subscription::group_retry_policy_type::GroupRetryPolicyType;
     //use crate::protocol::subscription::group_retry_policy::RetryPolicy;
 
     #[test]
@@ -314,4 +315,31 @@ mod subscription_group_config_tests {
             &HashMap::from([("key".into(), "value".into())])
         );
     }
+
+    #[test]
+    fn decoding_partial_config_with_retry_policy() {
+        let json = r#"{
+            "groupName": "group_a",
+            "consumeEnable": false,
+            "retryMaxTimes": 3,
+            "groupRetryPolicy": {
+                "type": "EXPONENTIAL",
+                "exponentialRetryPolicy": {"initial": 1000, "max": 60000, "multiplier": 3}
+            }
+        }"#;
+        let config: SubscriptionGroupConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.group_name(), "group_a");
+        assert!(!config.consume_enable());
+        assert_eq!(config.retry_max_times(), 3);
+        assert_eq!(config.retry_queue_nums(), 1);
+        let policy = config.group_retry_policy();
+        assert_eq!(policy.type_(), GroupRetryPolicyType::Exponential);
+        let exponential = policy.exponential_retry_policy().as_ref().unwrap();
+        assert_eq!(exponential.initial(), 1000);
+        assert_eq!(exponential.multiplier(), 3);
+
+        let encoded = serde_json::to_string(&config).unwrap();
+        assert!(encoded.contains(r#""type":"EXPONENTIAL""#));
+        assert!(!encoded.contains("defaultRetryPolicy"));
+    }
 }
@@ -21,6 +21,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use rocketmq_common::common::server::config::ServerConfig;
+use rocketmq_common::common::server::tls_config::TlsMode;
 use rocketmq_error::RocketmqError;
 use rocketmq_rust::wait_for_signal;
 use rocketmq_rust::ArcMut;
@@ -48,6 +49,7 @@ use crate::runtime::connection_handler_context::ConnectionHandlerContext;
 use crate::runtime::connection_handler_context::ConnectionHandlerContextWrapper;
 use crate::runtime::processor::RequestProcessor;
 use crate::runtime::RPCHook;
+use crate::tls::tls_helper::TlsServer;
 
 /// Default limit the max number of connections.
 const DEFAULT_MAX_CONNECTIONS: usize = 1000;
@@ -294,6 +296,9 @@ struct ConnectionListener<RP> {
     rpc_hooks: Arc<Vec<Box<dyn RPCHook>>>,
 
     channel_event_listener: Option<Arc<dyn ChannelEventListener>>,
+
+    /// Performs the TLS handshake on the accepted connections according to the TLS mode.
+    tls_server: TlsServer,
 }
 
 impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
@@ -338,33 +343,51 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
             info!("Accepted connection, client ip:{}", remote_addr);
             socket.set_nodelay(true).expect("set nodelay failed");
             let local_addr = socket.local_addr()?;
-            let response_table = ArcMut::new(HashMap::with_capacity(128));
-            let channel_inner = ArcMut::new(ChannelInner::new(
-                Connection::new(socket),
-                response_table.clone(),
-            ));
-            //create per connection handler state
-            let weak_channel = ArcMut::downgrade(&channel_inner);
-            let channel = Channel::new(weak_channel, local_addr, remote_addr);
-            let _ = tx.send(TokioEvent::new(
-                ConnectionNetEvent::CONNECTED(remote_addr),
-                remote_addr,
-                channel.clone(),
-            ));
-            let mut handler = ConnectionHandler {
-                request_processor: self.request_processor.clone(),
-                connection_handler_context: ArcMut::new(ConnectionHandlerContextWrapper {
-                    channel: channel.clone(),
-                }),
-                channel_inner: (channel_inner, channel),
-                shutdown: Shutdown::new(self.notify_shutdown.subscribe()),
-                _shutdown_complete: self.shutdown_complete_tx.clone(),
-                conn_disconnect_notify: self.conn_disconnect_notify.clone(),
-                rpc_hooks: self.rpc_hooks.clone(),
-                response_table,
-            };
+            let tls_server = self.tls_server.clone();
+            let request_processor = self.request_processor.clone();
+            let shutdown = Shutdown::new(self.notify_shutdown.subscribe());
+            let shutdown_complete = self.shutdown_complete_tx.clone();
+            let conn_disconnect_notify = self.conn_disconnect_notify.clone();
+            let rpc_hooks = self.rpc_hooks.clone();
             let sender = tx.clone();
             tokio::spawn(async move {
+                // The TLS handshake runs on the connection task, a slow peer must not hold up
+                // accepting the other connections.
+                let stream = match tls_server.accept(socket).await {
+                    Ok(stream) => stream,
+                    Err(err) => {
+                        warn!(
+                            "TLS handshake with client[IP={}] failed: {}",
+                            remote_addr, err
+                        );
+                        return;
+                    }
+                };
+                let response_table = ArcMut::new(HashMap::with_capacity(128));
+                let channel_inner = ArcMut::new(ChannelInner::new(
+                    Connection::new(stream),
+                    response_table.clone(),
+                ));
+                //create per connection handler state
+                let weak_channel = ArcMut::downgrade(&channel_inner);
+                let channel = Channel::new(weak_channel, local_addr, remote_addr);
+                let _ = sender.send(TokioEvent::new(
+                    ConnectionNetEvent::CONNECTED(remote_addr),
+                    remote_addr,
+                    channel.clone(),
+                ));
+                let mut handler = ConnectionHandler {
+                    request_processor,
+                    connection_handler_context: ArcMut::new(ConnectionHandlerContextWrapper {
+                        channel: channel.clone(),
+                    }),
+                    channel_inner: (channel_inner, channel),
+                    shutdown,
+                    _shutdown_complete: shutdown_complete,
+                    conn_disconnect_notify,
+                    rpc_hooks,
+                    response_table,
+                };
                 if let Err(err) = handler.handle().await {
                     error!(cause = ?err, "connection error");
                 }
@@ -414,6 +437,7 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> ConnectionListener<RP> {
 
 pub struct RocketMQServer<RP> {
     config: Arc<ServerConfig>,
+    rpc_hooks: Vec<Box<dyn RPCHook>>,
     _phantom_data: std::marker::PhantomData<RP>,
 }
 
@@ -421,14 +445,21 @@ impl<RP> RocketMQServer<RP> {
     pub fn new(config: Arc<ServerConfig>) -> Self {
         Self {
             config,
+            rpc_hooks: Vec::new(),
             _phantom_data: std::marker::PhantomData,
         }
     }
+
+    /// Registers a hook run around every request the server handles, it must be registered
+    /// before the server runs.
+    pub fn register_rpc_hook(&mut self, hook: Box<dyn RPCHook>) {
+        self.rpc_hooks.push(hook);
+    }
 }
 
 impl<RP: RequestProcessor + Sync + 'static + Clone> RocketMQServer<RP> {
     pub async fn run(
-        &self,
+        self,
         request_processor: RP,
         channel_event_listener: Option<Arc<dyn ChannelEventListener>>,
     ) {
@@ -442,14 +473,28 @@ impl<RP: RequestProcessor + Sync + 'static + Clone> RocketMQServer<RP> {
             "Bind local address: {}",
             format!("{}:{}", self.config.bind_address, self.config.listen_port)
         );
+        let tls_server = match TlsServer::new(self.config.tls_config()) {
+            Ok(tls_server) => tls_server,
+            Err(e) => {
+                error!("Invalid TLS configuration of the remoting server: {}", e);
+                return;
+            }
+        };
+        if self.config.tls_config().tls_mode != TlsMode::Disabled {
+            info!(
+                "Remoting server TLS mode: {:?}",
+                self.config.tls_config().tls_mode
+            );
+        }
         let (notify_conn_disconnect, _) = broadcast::channel::<SocketAddr>(100);
         run(
             listener,
             wait_for_signal(),
             request_processor,
             Some(notify_conn_disconnect),
-            vec![],
+            self.rpc_hooks,
             channel_event_listener,
+            tls_server,
         )
         .await;
     }
@@ -462,6 +507,7 @@ pub async fn run<RP: RequestProcessor + Sync + 'static + Clone>(
     conn_disconnect_notify: Option<broadcast::Sender<SocketAddr>>,
     rpc_hooks: Vec<Box<dyn RPCHook>>,
     channel_event_listener: Option<Arc<dyn ChannelEventListener>>,
+    tls_server: TlsServer,
 ) {
     let (notify_shutdown, _) = broadcast::channel(1);
     let (shutdown_complete_tx, mut shutdown_complete_rx) = mpsc::channel(1);
@@ -475,6 +521,7 @@ pub async fn run<RP: RequestProcessor + Sync + 'static + Clone>(
         request_processor,
         rpc_hooks: Arc::new(rpc_hooks),
         channel_event_listener,
+        tls_server,
     };
 
     tokio::select! {
@@ -16,6 +16,7 @@
  */
 
 use lazy_static::lazy_static;
+use rocketmq_common::common::server::tls_config::TlsConfig;
 
 use crate::runtime::config::net_system_config::NetSystemConfig;
 
@@ -37,7 +38,9 @@ pub struct TokioClientConfig {
     pub client_socket_rcv_buf_size: i32,
     pub client_pooled_byte_buf_allocator_enable: bool,
     pub client_close_socket_if_timeout: bool,
-    //pub use_tls: bool,
+    pub use_tls: bool,
+    /// The certificates clients connecting with TLS use.
+    pub tls_config: TlsConfig,
     pub socks_proxy_config: String,
     pub write_buffer_high_water_mark: i32,
     pub write_buffer_low_water_mark: i32,
@@ -50,6 +53,7 @@ pub struct TokioClientConfig {
 
 impl Default for TokioClientConfig {
     fn default() -> Self {
+        let tls_config = TlsConfig::from_env();
         TokioClientConfig {
             client_worker_threads: NET_SYSTEM_CONFIG.client_worker_size,
             client_callback_executor_threads: num_cpus::get(),
@@ -62,7 +66,8 @@ impl Default for TokioClientConfig {
             client_socket_rcv_buf_size: NET_SYSTEM_CONFIG.socket_rcvbuf_size,
             client_pooled_byte_buf_allocator_enable: false,
             client_close_socket_if_timeout: NET_SYSTEM_CONFIG.client_close_socket_if_timeout,
-            //use_tls: TlsSystemConfig::is_tls_enabled(),
+            use_tls: tls_config.tls_enable,
+            tls_config,
             socks_proxy_config: "{}".to_string(),
             write_buffer_high_water_mark: NET_SYSTEM_CONFIG.write_buffer_high_water_mark_value,
             write_buffer_low_water_mark: NET_SYSTEM_CONFIG.write_buffer_low_water_mark,
@@ -0,0 +1,470 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::fs::File;
+use std::io;
+use std::io::BufReader;
+use std::sync::Arc;
+
+use rocketmq_common::common::server::tls_config::TlsClientAuth;
+use rocketmq_common::common::server::tls_config::TlsConfig;
+use rocketmq_common::common::server::tls_config::TlsMode;
+use rocketmq_error::RocketMQResult;
+use rocketmq_error::RocketmqError;
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::client::danger::HandshakeSignatureValid;
+use tokio_rustls::rustls::client::danger::ServerCertVerified;
+use tokio_rustls::rustls::client::danger::ServerCertVerifier;
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use tokio_rustls::rustls::pki_types::PrivateKeyDer;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::pki_types::UnixTime;
+use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::CertificateError;
+use tokio_rustls::rustls::DigitallySignedStruct;
+use tokio_rustls::rustls::RootCertStore;
+use tokio_rustls::rustls::SignatureScheme;
+use tokio_rustls::TlsAcceptor;
+use tokio_rustls::TlsConnector;
+use tokio_rustls::TlsStream;
+
+use crate::tls::transport_stream::TransportStream;
+
+/// The content type of the record a TLS handshake starts with.
+const TLS_HANDSHAKE_RECORD: u8 = 0x16;
+
+/// The server side of the TLS transport, it accepts the connections according to the TLS mode.
+#[derive(Clone)]
+pub struct TlsServer {
+    mode: TlsMode,
+    acceptor: Option<TlsAcceptor>,
+}
+
+impl TlsServer {
+    pub fn new(config: &TlsConfig) -> RocketMQResult<Self> {
+        let acceptor = match config.tls_mode {
+            TlsMode::Disabled => None,
+            TlsMode::Permissive | TlsMode::Enforcing => {
+                Some(TlsAcceptor::from(Arc::new(build_server_config(config)?)))
+            }
+        };
+        Ok(Self {
+            mode: config.tls_mode,
+            acceptor,
+        })
+    }
+
+    pub fn mode(&self) -> TlsMode {
+        self.mode
+    }
+
+    /// Performs the TLS handshake on an accepted connection. In permissive mode the connections
+    /// which do not start with a TLS handshake stay plaintext.
+    pub async fn accept(&self, stream: TcpStream) -> io::Result<TransportStream> {
+        let Some(acceptor) = self.acceptor.as_ref() else {
+            return Ok(stream.into());
+        };
+        if self.mode == TlsMode::Permissive {
+            let mut first_byte = [0u8; 1];
+            let read = stream.peek(&mut first_byte).await?;
+            if read == 0 || first_byte[0] != TLS_HANDSHAKE_RECORD {
+                return Ok(stream.into());
+            }
+        }
+        let stream = acceptor.accept(stream).await?;
+        Ok(TransportStream::Tls(Box::new(TlsStream::Server(stream))))
+    }
+}
+
+/// The client side of the TLS transport.
+#[derive(Clone)]
+pub struct TlsClient {
+    connector: TlsConnector,
+}
+
+impl TlsClient {
+    pub fn new(config: &TlsConfig) -> RocketMQResult<Self> {
+        Ok(Self {
+            connector: TlsConnector::from(Arc::new(build_client_config(config)?)),
+        })
+    }
+
+    /// Performs the TLS handshake on a connection to a server.
+    pub async fn connect(&self, stream: TcpStream) -> io::Result<TransportStream> {
+        let server_name = ServerName::IpAddress(stream.peer_addr()?.ip().into());
+        let stream = self.connector.connect(server_name, stream).await?;
+        Ok(TransportStream::Tls(Box::new(TlsStream::Client(stream))))
+    }
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+/// Builds the configuration of a TLS server, the server certificate and key are required, and
+/// so is the trusted CA when clients have to authenticate.
+pub fn build_server_config(config: &TlsConfig) -> RocketMQResult<rustls::ServerConfig> {
+    let provider = crypto_provider();
+    let cert_path = required_path(&config.tls_server_cert_path, "tlsServerCertPath")?;
+    let key_path = required_path(&config.tls_server_key_path, "tlsServerKeyPath")?;
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(tls_error)?;
+    let builder = match config.tls_server_need_client_auth {
+        TlsClientAuth::None => builder.with_no_client_auth(),
+        client_auth => {
+            let trust_path =
+                required_path(&config.tls_server_trust_cert_path, "tlsServerTrustCertPath")?;
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(load_roots(trust_path)?, provider);
+            let verifier = if client_auth == TlsClientAuth::Optional {
+                verifier.allow_unauthenticated()
+            } else {
+                verifier
+            };
+            builder.with_client_cert_verifier(verifier.build().map_err(tls_error)?)
+        }
+    };
+    builder
+        .with_single_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+        .map_err(tls_error)
+}
+
+/// Builds the configuration of a TLS client. The certificate of the server is verified with the
+/// trusted CA unless the test mode is enabled, and the client presents its own certificate when
+/// one is configured.
+pub fn build_client_config(config: &TlsConfig) -> RocketMQResult<rustls::ClientConfig> {
+    let provider = crypto_provider();
+    let verifier: Arc<dyn ServerCertVerifier> = if config.tls_test_mode_enable {
+        Arc::new(InsecureServerVerifier {
+            provider: provider.clone(),
+        })
+    } else {
+        let trust_path =
+            required_path(&config.tls_client_trust_cert_path, "tlsClientTrustCertPath")?;
+        let inner =
+            WebPkiServerVerifier::builder_with_provider(load_roots(trust_path)?, provider.clone())
+                .build()
+                .map_err(tls_error)?;
+        Arc::new(CertChainVerifier { inner })
+    };
+    let builder = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(tls_error)?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+    match (
+        config.tls_client_cert_path.as_deref(),
+        config.tls_client_key_path.as_deref(),
+    ) {
+        (Some(cert_path), Some(key_path)) => builder
+            .with_client_auth_cert(load_certs(cert_path)?, load_private_key(key_path)?)
+            .map_err(tls_error),
+        _ => Ok(builder.with_no_client_auth()),
+    }
+}
+
+fn required_path<'a>(path: &'a Option<String>, name: &str) -> RocketMQResult<&'a str> {
+    path.as_deref()
+        .ok_or_else(|| RocketmqError::ConfigError(format!("TLS requires {} to be set", name)))
+}
+
+fn load_certs(path: &str) -> RocketMQResult<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| RocketmqError::ConfigError(format!("read certificates {}: {}", path, e)))?;
+    if certs.is_empty() {
+        return Err(RocketmqError::ConfigError(format!(
+            "no certificate in {}",
+            path
+        )));
+    }
+    Ok(certs)
+}
+
+fn load_private_key(path: &str) -> RocketMQResult<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .map_err(|e| RocketmqError::ConfigError(format!("read private key {}: {}", path, e)))?
+        .ok_or_else(|| RocketmqError::ConfigError(format!("no private key in {}", path)))
+}
+
+fn load_roots(path: &str) -> RocketMQResult<Arc<RootCertStore>> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(path)? {
+        roots.add(cert).map_err(tls_error)?;
+    }
+    Ok(Arc::new(roots))
+}
+
+fn open(path: &str) -> RocketMQResult<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .map_err(|e| RocketmqError::ConfigError(format!("open {}: {}", path, e)))
+}
+
+fn tls_error(e: impl std::fmt::Display) -> RocketmqError {
+    RocketmqError::ConfigError(format!("TLS configuration error: {}", e))
+}
+
+/// Verifies that the certificate of the server is issued by the trusted CA. Like the Java
+/// implementation it does not check the host name, brokers are usually addressed by ip.
+#[derive(Debug)]
+struct CertChainVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+}
+
+impl ServerCertVerifier for CertChainVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        ) {
+            Err(rustls::Error::InvalidCertificate(CertificateError::NotValidForName)) => {
+                Ok(ServerCertVerified::assertion())
+            }
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Accepts any certificate of the server, used by the test mode.
+#[derive(Debug)]
+struct InsecureServerVerifier {
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for InsecureServerVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    fn cert_path(name: &str) -> Option<String> {
+        Some(format!("{}/tests/tls/{}", env!("CARGO_MANIFEST_DIR"), name))
+    }
+
+    fn server_config(mode: TlsMode, client_auth: TlsClientAuth) -> TlsConfig {
+        TlsConfig {
+            tls_mode: mode,
+            tls_server_need_client_auth: client_auth,
+            tls_server_cert_path: cert_path("server.pem"),
+            tls_server_key_path: cert_path("server.key"),
+            tls_server_trust_cert_path: cert_path("ca.pem"),
+            ..TlsConfig::default()
+        }
+    }
+
+    fn client_config(with_cert: bool) -> TlsConfig {
+        TlsConfig {
+            tls_enable: true,
+            tls_client_trust_cert_path: cert_path("ca.pem"),
+            tls_client_cert_path: with_cert.then(|| cert_path("client.pem")).flatten(),
+            tls_client_key_path: with_cert.then(|| cert_path("client.key")).flatten(),
+            ..TlsConfig::default()
+        }
+    }
+
+    /// Accepts one connection and echoes what it reads, returns whether it was TLS.
+    async fn echo_once(listener: TcpListener, server: TlsServer) -> io::Result<bool> {
+        let (stream, _) = listener.accept().await?;
+        let mut stream = server.accept(stream).await?;
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await?;
+        stream.write_all(&buf).await?;
+        stream.flush().await?;
+        Ok(stream.is_tls())
+    }
+
+    async fn round_trip(mut stream: TransportStream) -> io::Result<[u8; 4]> {
+        stream.write_all(b"ping").await?;
+        stream.flush().await?;
+        let mut buf = [0u8; 4];
+        stream.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+
+    async fn listen(config: &TlsConfig) -> (TcpListener, TlsServer) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        (listener, TlsServer::new(config).unwrap())
+    }
+
+    #[tokio::test]
+    async fn enforcing_server_accepts_tls_clients() {
+        let (listener, server) =
+            listen(&server_config(TlsMode::Enforcing, TlsClientAuth::None)).await;
+        let addr = listener.local_addr().unwrap();
+        let echo = tokio::spawn(echo_once(listener, server));
+
+        let client = TlsClient::new(&client_config(false)).unwrap();
+        let stream = client
+            .connect(TcpStream::connect(addr).await.unwrap())
+            .await
+            .unwrap();
+        assert!(stream.is_tls());
+        assert_eq!(&round_trip(stream).await.unwrap(), b"ping");
+        assert!(echo.await.unwrap().unwrap());
+    }
+
+    #[tokio::test]
+    async fn permissive_server_accepts_plaintext_and_tls() {
+        let config = server_config(TlsMode::Permissive, TlsClientAuth::None);
+        let (listener, server) = listen(&config).await;
+        let addr = listener.local_addr().unwrap();
+        let echo = tokio::spawn(echo_once(listener, server));
+        let stream = TcpStream::connect(addr).await.unwrap().into();
+        assert_eq!(&round_trip(stream).await.unwrap(), b"ping");
+        assert!(!echo.await.unwrap().unwrap());
+
+        let (listener, server) = listen(&config).await;
+        let addr = listener.local_addr().unwrap();
+        let echo = tokio::spawn(echo_once(listener, server));
+        let client = TlsClient::new(&client_config(false)).unwrap();
+        let stream = client
+            .connect(TcpStream::connect(addr).await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(&round_trip(stream).await.unwrap(), b"ping");
+        assert!(echo.await.unwrap().unwrap());
+    }
+
+    #[tokio::test]
+    async fn mutual_tls_requires_client_certificates() {
+        let config = server_config(TlsMode::Enforcing, TlsClientAuth::Require);
+        let (listener, server) = listen(&config).await;
+        let addr = listener.local_addr().unwrap();
+        let echo = tokio::spawn(echo_once(listener, server));
+        let client = TlsClient::new(&client_config(true)).unwrap();
+        let stream = client
+            .connect(TcpStream::connect(addr).await.unwrap())
+            .await
+            .unwrap();
+        assert_eq!(&round_trip(stream).await.unwrap(), b"ping");
+        assert!(echo.await.unwrap().unwrap());
+
+        let (listener, server) = listen(&config).await;
+        let addr = listener.local_addr().unwrap();
+        let echo = tokio::spawn(echo_once(listener, server));
+        let client = TlsClient::new(&client_config(false)).unwrap();
+        // the server rejects the handshake once the client sends its (empty) certificate
+        if let Ok(stream) = client
+            .connect(TcpStream::connect(addr).await.unwrap())
+            .await
+        {
+            assert!(round_trip(stream).await.is_err());
+        }
+        assert!(echo.await.unwrap().is_err());
+    }
+
+    #[test]
+    fn missing_settings_are_reported() {
+        let config = TlsConfig {
+            tls_mode: TlsMode::Enforcing,
+            ..TlsConfig::default()
+        };
+        assert!(TlsServer::new(&config).is_err());
+        assert!(TlsClient::new(&TlsConfig::default()).is_err());
+        assert!(TlsClient::new(&TlsConfig {
+            tls_test_mode_enable: true,
+            ..TlsConfig::default()
+        })
+        .is_ok());
+        assert!(TlsServer::new(&TlsConfig::default()).is_ok());
+    }
+}
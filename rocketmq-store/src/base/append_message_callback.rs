@@ -146,6 +146,8 @@ impl AppendMessageCallback for DefaultAppendMessageCallback {
 
         // Determines whether there is sufficient free space
         if (msg_len + END_FILE_MIN_BLANK_LENGTH) > max_blank {
+            // keep the encoded message for the retry on the next mapped file
+            msg_inner.encoded_buff = Some(pre_encode_buffer);
             let bytes = self.msg_store_item_memory.mut_from_ref();
             bytes.clear();
             bytes.put_i32(max_blank);
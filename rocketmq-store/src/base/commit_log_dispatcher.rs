@@ -18,5 +18,7 @@
 use crate::base::dispatch_request::DispatchRequest;
 
 pub trait CommitLogDispatcher: Send + Sync + 'static {
-    fn dispatch(&self, dispatch_request: &DispatchRequest);
+    /// Dispatches the request, a dispatcher may enrich the request (e.g. with the filter bit
+    /// map) for the ones after it.
+    fn dispatch(&self, dispatch_request: &mut DispatchRequest);
 }
@@ -22,8 +22,8 @@ pub trait CompactionAppendMsgCallback {
     ///
     /// # Arguments
     ///
-    /// * `bb_dest` - The destination buffer to append to
-    /// * `file_from_offset` - The offset of the file
+    /// * `bb_dest` - The destination buffer to append to, starting at the wrote position
+    /// * `wrote_offset` - The physical offset `bb_dest` starts at
     /// * `max_blank` - The maximum blank space
     /// * `bb_src` - The source buffer containing the message to be appended
     ///
@@ -32,8 +32,8 @@ pub trait CompactionAppendMsgCallback {
     /// The result of the append operation
     fn do_append(
         &self,
-        bb_dest: &mut bytes::Bytes,
-        file_from_offset: i64,
+        bb_dest: &mut [u8],
+        wrote_offset: i64,
         max_blank: i32,
         bb_src: &mut bytes::Bytes,
     ) -> AppendMessageResult;
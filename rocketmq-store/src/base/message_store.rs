@@ -301,7 +301,7 @@ pub trait MessageStoreInner: Sync + 'static {
     ) -> Option<Vec<SelectMappedBufferResult>>;
 
     /// Append data to commit log.
-    fn append_to_commit_log(
+    async fn append_to_commit_log(
         &self,
         start_offset: i64,
         data: &[u8],
@@ -353,7 +353,7 @@ pub trait MessageStoreInner: Sync + 'static {
     fn clean_unused_topic(&self, retain_topics: &HashSet<String>) -> i32;
 
     /// Clean expired consume queues.
-    fn clean_expired_consumer_queue(&self);
+    async fn clean_expired_consumer_queue(&self);
 
     /// Check if the given message is in the page cache.
     fn check_in_mem_by_consume_offset(
@@ -405,6 +405,9 @@ pub trait MessageStoreInner: Sync + 'static {
     /// Add dispatcher.
     fn add_dispatcher(&self, dispatcher: Arc<dyn CommitLogDispatcher>);
 
+    /// Add dispatcher at the head of the dispatcher list.
+    fn add_first_dispatcher(&self, dispatcher: Arc<dyn CommitLogDispatcher>);
+
     /// Get consume queue of the topic/queue. If not exist, returns None.
     fn get_consume_queue(&self, topic: &CheetahString, queue_id: i32) -> Option<ArcConsumeQueue>;
 
@@ -477,6 +480,10 @@ pub trait MessageStoreInner: Sync + 'static {
     /// Get the queue store
     fn get_queue_store(&self) -> &dyn Any;
 
+    /// Compare the file consume queues with the rocksdb ones built in double write mode, checks
+    /// all topics when `topic` is empty
+    fn check_rocksdb_cq_write_progress(&self, topic: &CheetahString) -> String;
+
     /// If 'sync disk flush' is configured in this message store
     fn is_sync_disk_flush(&self) -> bool;
 
@@ -21,6 +21,7 @@ use std::path::PathBuf;
 use cheetah_string::CheetahString;
 use lazy_static::lazy_static;
 use rocketmq_common::common::broker::broker_role::BrokerRole;
+use rocketmq_common::common::server::tls_config::TlsConfig;
 use serde::Deserialize;
 
 use crate::base::store_enum::StoreType;
@@ -116,6 +117,9 @@ pub struct MessageStoreConfig {
     pub ha_transfer_batch_size: usize,
     pub ha_master_address: Option<String>,
     pub ha_max_gap_not_in_sync: usize,
+    /// The TLS settings of the replication between master and slaves, disabled by default.
+    #[serde(default)]
+    pub ha_tls_config: TlsConfig,
     pub broker_role: BrokerRole,
     pub flush_disk_type: FlushDiskType,
     pub sync_flush_timeout: u64,
@@ -245,7 +249,7 @@ impl Default for MessageStoreConfig {
             timer_enable_disruptor: false,
             timer_enable_check_metrics: false,
             timer_intercept_delay_level: false,
-            timer_max_delay_sec: 0,
+            timer_max_delay_sec: 3 * 24 * 3600,
             timer_wheel_enable: true,
             disappear_time_after_start: -1,
             timer_stop_enqueue: false,
@@ -253,9 +257,9 @@ impl Default for MessageStoreConfig {
             timer_skip_unknown_error: false,
             timer_warm_enable: false,
             timer_stop_dequeue: false,
-            timer_congest_num_each_slot: 0,
-            timer_metric_small_threshold: 0,
-            timer_progress_log_interval_ms: 0,
+            timer_congest_num_each_slot: i32::MAX as usize,
+            timer_metric_small_threshold: 1000000,
+            timer_progress_log_interval_ms: 10 * 1000,
             store_type: Default::default(),
             mapped_file_size_consume_queue: 300000 * 20,
             enable_consume_queue_ext: false,
@@ -265,8 +269,8 @@ impl Default for MessageStoreConfig {
             flush_interval_commit_log: 500,
             commit_interval_commit_log: 200,
             max_recovery_commit_log_files: 0,
-            disk_space_warning_level_ratio: 0,
-            disk_space_clean_forcibly_ratio: 0,
+            disk_space_warning_level_ratio: 90,
+            disk_space_clean_forcibly_ratio: 85,
             use_reentrant_lock_when_put_message: false,
             flush_commit_log_timed: true,
             flush_interval_consume_queue: 1000,
@@ -277,8 +281,8 @@ impl Default for MessageStoreConfig {
             redelete_hanged_file_interval: 1000 * 120,
             delete_when: "04".to_string(),
             disk_max_used_space_ratio: 75,
-            file_reserved_time: 0,
-            delete_file_batch_max: 0,
+            file_reserved_time: 72,
+            delete_file_batch_max: 10,
             put_msg_index_hight_water: 0,
             max_message_size: 1024 * 1024 * 4,
             check_crc_on_recover: false,
@@ -299,21 +303,22 @@ impl Default for MessageStoreConfig {
             max_index_num: 5000000 * 4,
             max_msgs_num_batch: 64,
             message_index_safe: false,
-            ha_listen_port: 0,
-            ha_send_heartbeat_interval: 0,
-            ha_housekeeping_interval: 0,
-            ha_transfer_batch_size: 0,
+            ha_listen_port: 10912,
+            ha_send_heartbeat_interval: 1000 * 5,
+            ha_housekeeping_interval: 1000 * 20,
+            ha_transfer_batch_size: 1024 * 32,
             ha_master_address: None,
-            ha_max_gap_not_in_sync: 0,
+            ha_max_gap_not_in_sync: 1024 * 1024 * 256,
+            ha_tls_config: TlsConfig::default(),
             broker_role: Default::default(),
             flush_disk_type: FlushDiskType::SyncFlush,
             sync_flush_timeout: 1000 * 5,
             put_message_timeout: 0,
-            slave_timeout: 0,
+            slave_timeout: 3000,
             message_delay_level: "1s 5s 10s 30s 1m 2m 3m 4m 5m 6m 7m 8m 9m 10m 20m 30m 1h 2h"
                 .to_string(),
             flush_delay_offset_interval: 10_000,
-            clean_file_forcibly_enable: false,
+            clean_file_forcibly_enable: true,
             warm_mapped_file_enable: false,
             offset_check_in_slave: false,
             debug_lock_enable: false,
@@ -370,11 +375,11 @@ impl Default for MessageStoreConfig {
             all_ack_in_sync_state_set: false,
             enable_auto_in_sync_replicas: false,
             ha_flow_control_enable: false,
-            max_ha_transfer_byte_in_second: 0,
-            ha_max_time_slave_not_catchup: 0,
+            max_ha_transfer_byte_in_second: 100 * 1024 * 1024,
+            ha_max_time_slave_not_catchup: 1000 * 15,
             sync_master_flush_offset_when_startup: false,
             max_checksum_range: 0,
-            replicas_per_disk_partition: 0,
+            replicas_per_disk_partition: 1,
             logical_disk_space_clean_forcibly_threshold: 0.0,
             max_slave_resend_length: 0,
             sync_from_last_file: false,
@@ -414,6 +419,26 @@ impl MessageStoreConfig {
         self.store_path_commit_log.clone().unwrap().to_string()
     }
 
+    pub fn get_store_path_epoch_file(&self) -> String {
+        match self.store_path_epoch_file.as_ref() {
+            Some(path) => path.to_string(),
+            None => PathBuf::from(self.store_path_root_dir.to_string())
+                .join("epochFileCheckpoint")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    pub fn get_store_path_broker_identity(&self) -> String {
+        match self.store_path_broker_identity.as_ref() {
+            Some(path) => path.to_string(),
+            None => PathBuf::from(self.store_path_root_dir.to_string())
+                .join("brokerIdentity")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
     pub fn is_enable_rocksdb_store(&self) -> bool {
         self.store_type == StoreType::RocksDB
     }
@@ -15,12 +15,16 @@
  * limitations under the License.
  */
 
-const MIN_EXT_UNIT_SIZE: i16 = 2  // size, 32k max
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::BytesMut;
+
+pub(crate) const MIN_EXT_UNIT_SIZE: i16 = 2  // size, 32k max
  + 8 * 2 // msg time + tagCode
   + 2; // bitMapSize
-const MAX_EXT_UNIT_SIZE: i16 = i16::MAX;
+pub(crate) const MAX_EXT_UNIT_SIZE: i16 = i16::MAX;
 
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default)]
 pub struct CqExtUnit {
     size: i16,
     tags_code: i64,
@@ -61,4 +65,88 @@ impl CqExtUnit {
     pub fn filter_bit_map(&self) -> &Option<Vec<u8>> {
         &self.filter_bit_map
     }
+
+    /// Unit size calculated from the bit map, which may differ from `size` before the unit is
+    /// written.
+    pub fn calc_unit_size(&self) -> i32 {
+        MIN_EXT_UNIT_SIZE as i32
+            + self
+                .filter_bit_map
+                .as_ref()
+                .map_or(0, |val| val.len() as i32)
+    }
+
+    /// Serializes the unit, refreshing `size` and `bit_map_size` from the bit map.
+    pub fn write(&mut self) -> BytesMut {
+        self.bit_map_size = self
+            .filter_bit_map
+            .as_ref()
+            .map_or(0, |val| val.len() as i16);
+        self.size = MIN_EXT_UNIT_SIZE + self.bit_map_size;
+        let mut buffer = BytesMut::with_capacity(self.size as usize);
+        buffer.put_i16(self.size);
+        buffer.put_i64(self.tags_code);
+        buffer.put_i64(self.msg_store_time);
+        buffer.put_i16(self.bit_map_size);
+        if let Some(filter_bit_map) = self.filter_bit_map.as_ref() {
+            buffer.put_slice(filter_bit_map);
+        }
+        buffer
+    }
+
+    /// Reads a unit from the head of `buffer`, returns `false` if there is no unit there.
+    pub fn read(&mut self, mut buffer: &[u8]) -> bool {
+        if buffer.remaining() < 2 {
+            return false;
+        }
+        self.size = buffer.get_i16();
+        if self.size < 1 || buffer.remaining() < (self.size - 2) as usize {
+            return false;
+        }
+        self.tags_code = buffer.get_i64();
+        self.msg_store_time = buffer.get_i64();
+        self.bit_map_size = buffer.get_i16();
+        if self.bit_map_size < 1 {
+            self.filter_bit_map = None;
+            return true;
+        }
+        if buffer.remaining() < self.bit_map_size as usize {
+            return false;
+        }
+        self.filter_bit_map = Some(buffer[..self.bit_map_size as usize].to_vec());
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_and_read_unit() {
+        let mut unit = CqExtUnit::new(12, 1_700_000_000_000, Some(vec![1, 2, 3, 4]));
+        let bytes = unit.write();
+        assert_eq!(bytes.len() as i32, unit.calc_unit_size());
+        assert_eq!(unit.size(), MIN_EXT_UNIT_SIZE + 4);
+
+        let mut read = CqExtUnit::default();
+        assert!(read.read(&bytes));
+        assert_eq!(read.size(), unit.size());
+        assert_eq!(read.tags_code(), 12);
+        assert_eq!(read.msg_store_time(), 1_700_000_000_000);
+        assert_eq!(read.filter_bit_map(), &Some(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn read_without_bit_map_and_blank() {
+        let mut unit = CqExtUnit::new(7, 8, None);
+        let bytes = unit.write();
+        let mut read = CqExtUnit::default();
+        assert!(read.read(&bytes));
+        assert_eq!(read.bit_map_size(), 0);
+        assert!(read.filter_bit_map().is_none());
+
+        assert!(!read.read(&[0, 0, 0, 0]));
+        assert!(!read.read(&[0xff, 0xff]));
+    }
 }
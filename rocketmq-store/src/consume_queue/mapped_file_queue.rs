@@ -21,9 +21,13 @@ use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
+use bytes::Buf;
 use cheetah_string::CheetahString;
 use parking_lot::RwLock;
+use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_common::UtilAll::offset_to_file_name;
 use tracing::error;
 use tracing::info;
@@ -288,6 +292,7 @@ impl MappedFileQueue {
                 }
             }
         }
+        self.delete_expired_file(will_remove_files);
     }
 
     #[inline]
@@ -313,15 +318,123 @@ impl MappedFileQueue {
     }
 
     #[inline]
-    pub(crate) fn delete_expired_file(&mut self, files: Vec<Arc<DefaultMappedFile>>) {
-        let mut files = files;
-        let read_guard = self.mapped_files.read();
+    pub(crate) fn delete_expired_file(&self, files: Vec<Arc<DefaultMappedFile>>) {
         if !files.is_empty() {
-            files.retain(|mf| read_guard.contains(mf));
             self.mapped_files.write().retain(|mf| !files.contains(mf));
         }
     }
 
+    /// Destroys the files whose last modification is older than `expired_time` millis, from the
+    /// oldest one, stopping at the first file that is still alive. The last file is always kept.
+    pub fn delete_expired_file_by_time(
+        &self,
+        expired_time: i64,
+        delete_files_interval: i32,
+        interval_forcibly: i64,
+        clean_immediately: bool,
+        delete_file_batch_max: i32,
+    ) -> i32 {
+        let mapped_files = self.mapped_files.read().clone();
+        if mapped_files.is_empty() {
+            return 0;
+        }
+        let candidates = mapped_files.len() - 1;
+        let mut files = Vec::new();
+        for (index, mapped_file) in mapped_files.iter().take(candidates).enumerate() {
+            let live_max_timestamp =
+                mapped_file.get_last_modified_timestamp() as i64 + expired_time;
+            if get_current_millis() as i64 >= live_max_timestamp || clean_immediately {
+                if !mapped_file.destroy(interval_forcibly as u64) {
+                    break;
+                }
+                files.push(mapped_file.clone());
+                if files.len() >= delete_file_batch_max as usize {
+                    break;
+                }
+                if delete_files_interval > 0 && index + 1 < candidates {
+                    thread::sleep(Duration::from_millis(delete_files_interval as u64));
+                }
+            } else {
+                //avoid deleting files in the middle
+                break;
+            }
+        }
+        let delete_count = files.len() as i32;
+        self.delete_expired_file(files);
+        delete_count
+    }
+
+    /// Destroys the logic files whose last unit points below the physical `offset`, together
+    /// with files that are no longer available. The last file is always kept.
+    pub fn delete_expired_file_by_offset(&self, offset: i64, unit_size: i32) -> i32 {
+        let mapped_files = self.mapped_files.read().clone();
+        if mapped_files.is_empty() {
+            return 0;
+        }
+        let mut files = Vec::new();
+        for mapped_file in mapped_files.iter().take(mapped_files.len() - 1) {
+            let destroy = if mapped_file.is_available() {
+                let position = self.mapped_file_size as usize - unit_size as usize;
+                match mapped_file.get_bytes(position, 8) {
+                    Some(mut bytes) => {
+                        let max_offset_in_logic_queue = bytes.get_i64();
+                        if max_offset_in_logic_queue < offset {
+                            info!(
+                                "physic min offset {}, logics in current mappedFile max offset \
+                                 {}, delete it",
+                                offset, max_offset_in_logic_queue
+                            );
+                        }
+                        max_offset_in_logic_queue < offset
+                    }
+                    None => break,
+                }
+            } else {
+                // handle hanged file
+                warn!(
+                    "Found a hanged consume queue file, attempting to delete it, {}",
+                    mapped_file.get_file_name()
+                );
+                true
+            };
+            if destroy && mapped_file.destroy(1000 * 60) {
+                files.push(mapped_file.clone());
+            } else {
+                break;
+            }
+        }
+        let delete_count = files.len() as i32;
+        self.delete_expired_file(files);
+        delete_count
+    }
+
+    /// Retries destroying the first file if a previous deletion left it hanged.
+    pub fn retry_delete_first_file(&self, interval_forcibly: i64) -> bool {
+        if let Some(mapped_file) = self.get_first_mapped_file() {
+            if !mapped_file.is_available() {
+                warn!(
+                    "the mappedFile was destroyed once, but still alive, {}",
+                    mapped_file.get_file_name()
+                );
+                let result = mapped_file.destroy(interval_forcibly as u64);
+                if result {
+                    info!(
+                        "the mappedFile re delete OK, {}",
+                        mapped_file.get_file_name()
+                    );
+                    self.delete_expired_file(vec![mapped_file]);
+                } else {
+                    warn!(
+                        "the mappedFile re delete failed, {}",
+                        mapped_file.get_file_name()
+                    );
+                }
+                return result;
+            }
+        }
+        false
+    }
+
     #[inline]
     pub fn destroy(&mut self) {
         for mapped_file in self.mapped_files.read().iter() {
@@ -511,4 +624,73 @@ mod tests {
         assert!(queue.load());
         assert_eq!(queue.mapped_files.read().len(), 1);
     }
+
+    fn new_queue_with_files(dir: &Path, mapped_file_size: u64, count: u64) -> MappedFileQueue {
+        let mut queue =
+            MappedFileQueue::new(dir.to_string_lossy().into_owned(), mapped_file_size, None);
+        for index in 0..count {
+            queue.try_create_mapped_file(index * mapped_file_size);
+        }
+        queue
+    }
+
+    #[test]
+    fn delete_expired_file_by_time_keeps_last_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let queue = new_queue_with_files(temp_dir.path(), 1024, 3);
+
+        assert_eq!(
+            queue.delete_expired_file_by_time(60 * 60 * 1000, 0, 1000, false, 10),
+            0
+        );
+        assert_eq!(queue.get_mapped_files_size(), 3);
+
+        assert_eq!(
+            queue.delete_expired_file_by_time(60 * 60 * 1000, 0, 1000, true, 1),
+            1
+        );
+        assert_eq!(queue.get_mapped_files_size(), 2);
+        assert!(!temp_dir.path().join(offset_to_file_name(0)).exists());
+
+        assert_eq!(queue.delete_expired_file_by_time(0, 0, 1000, false, 10), 1);
+        assert_eq!(queue.get_mapped_files_size(), 1);
+        assert_eq!(
+            queue
+                .get_first_mapped_file()
+                .unwrap()
+                .get_file_from_offset(),
+            2048
+        );
+    }
+
+    #[test]
+    fn delete_expired_file_by_offset_stops_at_live_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let unit_size = 20;
+        let queue = new_queue_with_files(temp_dir.path(), (unit_size * 2) as u64, 3);
+        for (mapped_file, max_phy_offset) in queue.mapped_files.read().iter().zip([100i64, 300]) {
+            let mut unit = vec![0u8; (unit_size * 2) as usize];
+            unit[unit_size as usize..unit_size as usize + 8]
+                .copy_from_slice(&max_phy_offset.to_be_bytes());
+            assert!(mapped_file.append_message_bytes(&unit));
+        }
+
+        assert_eq!(queue.delete_expired_file_by_offset(200, unit_size), 1);
+        assert_eq!(queue.get_mapped_files_size(), 2);
+        assert_eq!(
+            queue
+                .get_first_mapped_file()
+                .unwrap()
+                .get_file_from_offset(),
+            (unit_size * 2) as u64
+        );
+    }
+
+    #[test]
+    fn retry_delete_first_file_ignores_available_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let queue = new_queue_with_files(temp_dir.path(), 1024, 2);
+        assert!(!queue.retry_delete_first_file(1000));
+        assert_eq!(queue.get_mapped_files_size(), 2);
+    }
 }
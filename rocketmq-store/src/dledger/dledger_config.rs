@@ -0,0 +1,171 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::dledger::dledger_mmap_file_store::INDEX_UNIT_SIZE;
+
+/// Settings of one member of a DLedger (Raft) group.
+#[derive(Debug, Clone)]
+pub struct DLedgerConfig {
+    pub group: String,
+    pub self_id: String,
+    /// Members of the group, formatted as `n0-127.0.0.1:40911;n1-127.0.0.1:40912`.
+    pub peers: String,
+    pub store_base_dir: String,
+    /// Overrides `{store_base_dir}/dledger-{self_id}/data` when set.
+    pub data_store_path: Option<String>,
+    pub mapped_file_size_for_entry_data: u64,
+    pub mapped_file_size_for_entry_index: u64,
+    pub heartbeat_interval_ms: u64,
+    pub max_heartbeat_leak: u64,
+    pub min_vote_interval_ms: u64,
+    pub max_vote_interval_ms: u64,
+    pub rpc_timeout_ms: u64,
+    pub max_wait_ack_time_ms: u64,
+    pub max_push_size: usize,
+    pub flush_file_interval_ms: u64,
+}
+
+impl Default for DLedgerConfig {
+    fn default() -> Self {
+        Self {
+            group: "default".to_string(),
+            self_id: "n0".to_string(),
+            peers: "n0-localhost:20911".to_string(),
+            store_base_dir: dirs::home_dir()
+                .unwrap_or_default()
+                .join("dledgerstore")
+                .to_string_lossy()
+                .to_string(),
+            data_store_path: None,
+            mapped_file_size_for_entry_data: 1024 * 1024 * 1024,
+            mapped_file_size_for_entry_index: INDEX_UNIT_SIZE as u64 * 5 * 1024 * 1024,
+            heartbeat_interval_ms: 2000,
+            max_heartbeat_leak: 3,
+            min_vote_interval_ms: 300,
+            max_vote_interval_ms: 1000,
+            rpc_timeout_ms: 3000,
+            max_wait_ack_time_ms: 2500,
+            max_push_size: 4 * 1024 * 1024,
+            flush_file_interval_ms: 10,
+        }
+    }
+}
+
+impl DLedgerConfig {
+    pub fn from_message_store_config(message_store_config: &MessageStoreConfig) -> Self {
+        let mut config = Self {
+            store_base_dir: message_store_config.store_path_root_dir.to_string(),
+            data_store_path: message_store_config
+                .store_path_dledger_commit_log
+                .as_ref()
+                .map(|path| path.to_string()),
+            mapped_file_size_for_entry_data: message_store_config.mapped_file_size_commit_log
+                as u64,
+            ..Default::default()
+        };
+        if let Some(group) = message_store_config.dledger_group.as_ref() {
+            config.group = group.clone();
+        }
+        if let Some(self_id) = message_store_config.dledger_self_id.as_ref() {
+            config.self_id = self_id.clone();
+        }
+        if let Some(peers) = message_store_config.dledger_peers.as_ref() {
+            config.peers = peers.clone();
+        }
+        config
+    }
+
+    /// Parses `peers` into a map of member id to address.
+    pub fn parse_peers(&self) -> BTreeMap<String, String> {
+        self.peers
+            .split(';')
+            .filter_map(|peer| {
+                let (id, addr) = peer.trim().split_once('-')?;
+                Some((id.to_string(), addr.to_string()))
+            })
+            .collect()
+    }
+
+    pub fn self_addr(&self) -> Option<String> {
+        self.parse_peers().remove(&self.self_id)
+    }
+
+    fn default_path(&self) -> PathBuf {
+        PathBuf::from(&self.store_base_dir).join(format!("dledger-{}", self.self_id))
+    }
+
+    pub fn get_data_store_path(&self) -> String {
+        match self.data_store_path.as_ref() {
+            Some(path) => path.clone(),
+            None => self
+                .default_path()
+                .join("data")
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+
+    pub fn get_index_store_path(&self) -> String {
+        self.default_path()
+            .join("index")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn get_member_state_path(&self) -> String {
+        self.default_path()
+            .join("currterm")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn get_checkpoint_path(&self) -> String {
+        self.default_path()
+            .join("checkpoint")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn get_applied_index_path(&self) -> String {
+        self.default_path()
+            .join("applied")
+            .to_string_lossy()
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_peers_splits_ids_and_addresses() {
+        let config = DLedgerConfig {
+            self_id: "n1".to_string(),
+            peers: "n0-127.0.0.1:40911;n1-127.0.0.1:40912; n2-127.0.0.1:40913".to_string(),
+            ..Default::default()
+        };
+        let peers = config.parse_peers();
+        assert_eq!(peers.len(), 3);
+        assert_eq!(peers.get("n2").unwrap(), "127.0.0.1:40913");
+        assert_eq!(config.self_addr().unwrap(), "127.0.0.1:40912");
+    }
+}
@@ -0,0 +1,134 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use rocketmq_common::CRC32Utils::crc32;
+
+pub const MAGIC: i32 = 0xCAFEDEAD_u32 as i32;
+pub const POS_OFFSET: usize = 4 + 4 + 8 + 8;
+pub const HEADER_SIZE: usize = POS_OFFSET + 8 + 4 + 4 + 4;
+pub const BODY_OFFSET: usize = HEADER_SIZE + 4;
+
+/// One record of the replicated log.
+///
+/// Layout: `magic | size | index | term | pos | channel | chain crc | body crc | body size | body`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DLedgerEntry {
+    pub index: i64,
+    pub term: i64,
+    /// Physical position of the entry in the local data files.
+    pub pos: i64,
+    pub channel: i32,
+    pub body: Bytes,
+}
+
+impl DLedgerEntry {
+    pub fn new(index: i64, term: i64, body: Bytes) -> Self {
+        Self {
+            index,
+            term,
+            pos: -1,
+            channel: 0,
+            body,
+        }
+    }
+
+    #[inline]
+    pub fn compute_size_in_bytes(&self) -> usize {
+        BODY_OFFSET + self.body.len()
+    }
+
+    pub fn encode(&self) -> Bytes {
+        let size = self.compute_size_in_bytes();
+        let mut buffer = BytesMut::with_capacity(size);
+        buffer.put_i32(MAGIC);
+        buffer.put_i32(size as i32);
+        buffer.put_i64(self.index);
+        buffer.put_i64(self.term);
+        buffer.put_i64(self.pos);
+        buffer.put_i32(self.channel);
+        buffer.put_i32(0);
+        buffer.put_i32(crc32(&self.body) as i32);
+        buffer.put_i32(self.body.len() as i32);
+        buffer.put_slice(&self.body);
+        buffer.freeze()
+    }
+
+    /// Decodes one entry from the front of `buffer`, returning `None` when the bytes do not
+    /// hold a complete and intact entry.
+    pub fn decode(buffer: &mut Bytes) -> Option<Self> {
+        if buffer.remaining() < BODY_OFFSET {
+            return None;
+        }
+        if buffer.get_i32() != MAGIC {
+            return None;
+        }
+        let size = buffer.get_i32() as usize;
+        if size < BODY_OFFSET || buffer.remaining() < size - 8 {
+            return None;
+        }
+        let index = buffer.get_i64();
+        let term = buffer.get_i64();
+        let pos = buffer.get_i64();
+        let channel = buffer.get_i32();
+        let _chain_crc = buffer.get_i32();
+        let body_crc = buffer.get_i32();
+        let body_size = buffer.get_i32() as usize;
+        if body_size != size - BODY_OFFSET {
+            return None;
+        }
+        let body = buffer.split_to(body_size);
+        if crc32(&body) as i32 != body_crc {
+            return None;
+        }
+        Some(Self {
+            index,
+            term,
+            pos,
+            channel,
+            body,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode_round_trip() {
+        let mut entry = DLedgerEntry::new(7, 3, Bytes::from_static(b"hello dledger"));
+        entry.pos = 1024;
+        let mut encoded = entry.encode();
+        assert_eq!(encoded.len(), entry.compute_size_in_bytes());
+        assert_eq!((&encoded[POS_OFFSET..POS_OFFSET + 8]).get_i64(), entry.pos);
+        assert_eq!(DLedgerEntry::decode(&mut encoded), Some(entry));
+        assert!(encoded.is_empty());
+    }
+
+    #[test]
+    fn decode_rejects_corrupted_body() {
+        let entry = DLedgerEntry::new(0, 1, Bytes::from_static(b"body"));
+        let mut encoded = BytesMut::from(&entry.encode()[..]);
+        let last = encoded.len() - 1;
+        encoded[last] ^= 0xFF;
+        assert_eq!(DLedgerEntry::decode(&mut encoded.freeze()), None);
+    }
+}
@@ -0,0 +1,356 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use tokio::sync::watch;
+use tracing::info;
+use tracing::warn;
+
+use crate::dledger::dledger_config::DLedgerConfig;
+use crate::dledger::dledger_entry::DLedgerEntry;
+use crate::dledger::dledger_mmap_file_store::DLedgerMmapFileStore;
+use crate::dledger::dledger_protocol::DLedgerResponseCode;
+use crate::dledger::dledger_protocol::PushEntryRequest;
+use crate::dledger::dledger_protocol::PushEntryResponse;
+use crate::dledger::dledger_rpc_service::DLedgerRpcService;
+use crate::dledger::member_state::DLedgerRole;
+use crate::dledger::member_state::MemberState;
+
+/// Highest index every peer is known to hold, valid for one leader term.
+struct PeerWaterMarks {
+    term: i64,
+    marks: HashMap<String, i64>,
+}
+
+/// Highest index of the follower log the leader of `term` has confirmed to match its own.
+struct FollowerProgress {
+    term: i64,
+    confirmed_index: i64,
+}
+
+/// Replicates the log from the leader to the followers and tracks the committed index.
+pub struct DLedgerEntryPusher {
+    config: Arc<DLedgerConfig>,
+    member_state: Arc<MemberState>,
+    store: Arc<DLedgerMmapFileStore>,
+    rpc_service: Arc<DLedgerRpcService>,
+    peer_water_marks: Mutex<PeerWaterMarks>,
+    follower_progress: Mutex<FollowerProgress>,
+    append_lock: Mutex<()>,
+    ledger_end_tx: watch::Sender<i64>,
+    committed_tx: watch::Sender<i64>,
+}
+
+impl DLedgerEntryPusher {
+    pub fn new(
+        config: Arc<DLedgerConfig>,
+        member_state: Arc<MemberState>,
+        store: Arc<DLedgerMmapFileStore>,
+        rpc_service: Arc<DLedgerRpcService>,
+    ) -> Self {
+        Self {
+            config,
+            member_state,
+            store,
+            rpc_service,
+            peer_water_marks: Mutex::new(PeerWaterMarks {
+                term: -1,
+                marks: HashMap::new(),
+            }),
+            follower_progress: Mutex::new(FollowerProgress {
+                term: -1,
+                confirmed_index: -1,
+            }),
+            append_lock: Mutex::new(()),
+            ledger_end_tx: watch::Sender::new(-1),
+            committed_tx: watch::Sender::new(-1),
+        }
+    }
+
+    /// Publishes the committed index recovered by the store.
+    pub fn load(&self) {
+        self.ledger_end_tx
+            .send_replace(self.store.get_ledger_end_index());
+        self.committed_tx
+            .send_replace(self.store.get_committed_index());
+    }
+
+    pub fn subscribe_committed_index(&self) -> watch::Receiver<i64> {
+        self.committed_tx.subscribe()
+    }
+
+    /// Takes over replication for a freshly elected leader of `term`.
+    ///
+    /// An empty entry is appended first: entries of former terms only become committed together
+    /// with an entry of the current term.
+    pub fn start_leader(self: &Arc<Self>, term: i64, shutdown_rx: watch::Receiver<bool>) {
+        {
+            let mut water_marks = self.peer_water_marks.lock();
+            water_marks.term = term;
+            water_marks.marks = self
+                .member_state
+                .remote_peer_ids()
+                .into_iter()
+                .map(|peer_id| (peer_id, -1))
+                .collect();
+        }
+        let Some(entry) = self.append_entry(term, Bytes::new()) else {
+            warn!("append the first entry of term {} failed", term);
+            self.member_state.change_to_candidate(term);
+            return;
+        };
+        for peer_id in self.member_state.remote_peer_ids() {
+            tokio::spawn(
+                self.clone()
+                    .push_loop(peer_id, term, entry.index, shutdown_rx.clone()),
+            );
+        }
+        self.try_commit(term);
+    }
+
+    /// Appends `body` on the leader and waits until a quorum stored it.
+    pub async fn append_as_leader(&self, body: Bytes) -> Result<DLedgerEntry, DLedgerResponseCode> {
+        let (role, term, _) = self.member_state.snapshot();
+        if role != DLedgerRole::Leader {
+            return Err(DLedgerResponseCode::NotLeader);
+        }
+        let entry = self
+            .append_entry(term, body)
+            .ok_or(DLedgerResponseCode::AppendFailed)?;
+        self.try_commit(term);
+
+        let mut committed_rx = self.committed_tx.subscribe();
+        let wait = tokio::time::timeout(
+            Duration::from_millis(self.config.max_wait_ack_time_ms),
+            committed_rx.wait_for(|committed_index| *committed_index >= entry.index),
+        )
+        .await;
+        match wait {
+            // a later leader may have replaced the entry before it got committed
+            Ok(Ok(_)) if self.store.get_entry_term(entry.index) == term => Ok(entry),
+            Ok(_) => Err(DLedgerResponseCode::NotLeader),
+            Err(_) => Err(DLedgerResponseCode::WaitQuorumAckTimeout),
+        }
+    }
+
+    fn append_entry(&self, term: i64, body: Bytes) -> Option<DLedgerEntry> {
+        let _lock = self.append_lock.lock();
+        let (role, current_term, _) = self.member_state.snapshot();
+        if role != DLedgerRole::Leader || current_term != term {
+            return None;
+        }
+        let entry = self.store.append_as_leader(term, body)?;
+        self.ledger_end_tx.send_replace(entry.index);
+        Some(entry)
+    }
+
+    async fn push_loop(
+        self: Arc<Self>,
+        peer_id: String,
+        term: i64,
+        mut next_index: i64,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        let mut ledger_end_rx = self.ledger_end_tx.subscribe();
+        let retry_interval = Duration::from_millis(self.config.heartbeat_interval_ms.min(1000));
+        info!(
+            "[{}] start pushing entries to {} from index {} in term {}",
+            self.member_state.self_id(),
+            peer_id,
+            next_index,
+            term
+        );
+        loop {
+            let (role, current_term, _) = self.member_state.snapshot();
+            if *shutdown_rx.borrow() || role != DLedgerRole::Leader || current_term != term {
+                break;
+            }
+            if next_index > self.store.get_ledger_end_index() {
+                tokio::select! {
+                    _ = ledger_end_rx.changed() => {}
+                    _ = tokio::time::sleep(retry_interval) => {}
+                    _ = shutdown_rx.changed() => break,
+                }
+                continue;
+            }
+            let request = self.build_push_request(&peer_id, term, next_index);
+            let last_index = match request.entries.last() {
+                Some(entry) => entry.index,
+                None => {
+                    tokio::time::sleep(retry_interval).await;
+                    continue;
+                }
+            };
+            match self.rpc_service.push(&peer_id, &request).await {
+                Ok(response) => match response.code {
+                    DLedgerResponseCode::Success => {
+                        next_index = last_index + 1;
+                        self.update_peer_water_mark(term, &peer_id, last_index);
+                    }
+                    DLedgerResponseCode::InconsistentState => {
+                        // walk back until both logs agree on the previous entry
+                        next_index = (next_index - 1).min(response.ledger_end_index + 1).max(0);
+                    }
+                    DLedgerResponseCode::ExpiredTerm if response.term > term => {
+                        self.member_state.change_to_candidate(response.term);
+                        break;
+                    }
+                    code => {
+                        warn!("push entries to {} failed, code {:?}", peer_id, code);
+                        tokio::time::sleep(retry_interval).await;
+                    }
+                },
+                Err(_) => {
+                    tokio::select! {
+                        _ = tokio::time::sleep(retry_interval) => {}
+                        _ = shutdown_rx.changed() => break,
+                    }
+                }
+            }
+        }
+        info!(
+            "[{}] stop pushing entries to {} in term {}",
+            self.member_state.self_id(),
+            peer_id,
+            term
+        );
+    }
+
+    fn build_push_request(&self, peer_id: &str, term: i64, next_index: i64) -> PushEntryRequest {
+        let prev_index = next_index - 1;
+        let mut entries = Vec::new();
+        let mut size = 0;
+        let mut index = next_index;
+        while index <= self.store.get_ledger_end_index() {
+            let Some(entry) = self.store.get(index) else {
+                break;
+            };
+            size += entry.compute_size_in_bytes();
+            if !entries.is_empty() && size > self.config.max_push_size {
+                break;
+            }
+            entries.push(entry);
+            index += 1;
+        }
+        PushEntryRequest {
+            group: self.member_state.group().to_string(),
+            remote_id: peer_id.to_string(),
+            leader_id: self.member_state.self_id().to_string(),
+            term,
+            prev_index,
+            prev_term: self.store.get_entry_term(prev_index),
+            commit_index: self.store.get_committed_index(),
+            entries,
+        }
+    }
+
+    fn update_peer_water_mark(&self, term: i64, peer_id: &str, index: i64) {
+        {
+            let mut water_marks = self.peer_water_marks.lock();
+            if water_marks.term != term {
+                return;
+            }
+            let mark = water_marks.marks.entry(peer_id.to_string()).or_insert(-1);
+            *mark = (*mark).max(index);
+        }
+        self.try_commit(term);
+    }
+
+    /// Commits the highest index stored by a quorum, if it belongs to the current term.
+    fn try_commit(&self, term: i64) {
+        let mut indexes: Vec<i64> = {
+            let water_marks = self.peer_water_marks.lock();
+            if water_marks.term != term {
+                return;
+            }
+            water_marks.marks.values().copied().collect()
+        };
+        indexes.push(self.store.get_ledger_end_index());
+        indexes.sort_unstable_by(|a, b| b.cmp(a));
+        let quorum_index = indexes[indexes.len() / 2];
+        if quorum_index > self.store.get_committed_index()
+            && self.store.get_entry_term(quorum_index) == term
+            && self.store.update_committed_index(quorum_index)
+        {
+            self.committed_tx
+                .send_replace(self.store.get_committed_index());
+        }
+    }
+
+    /// Stores the entries pushed by the leader of `request.term`, whose leadership was already
+    /// acknowledged. Entries conflicting with the leader's log are truncated.
+    pub fn handle_push(&self, request: PushEntryRequest) -> PushEntryResponse {
+        let _lock = self.append_lock.lock();
+        let response = |code| PushEntryResponse {
+            term: self.member_state.current_term(),
+            code,
+            ledger_end_index: self.store.get_ledger_end_index(),
+        };
+        if request.prev_index >= 0
+            && self.store.get_entry_term(request.prev_index) != request.prev_term
+        {
+            return response(DLedgerResponseCode::InconsistentState);
+        }
+        for entry in &request.entries {
+            if entry.index <= self.store.get_ledger_end_index() {
+                if self.store.get_entry_term(entry.index) == entry.term {
+                    continue;
+                }
+                self.store.truncate(entry.index);
+            }
+            if !self.store.append_as_follower(entry) {
+                return response(DLedgerResponseCode::InconsistentState);
+            }
+        }
+        self.ledger_end_tx
+            .send_replace(self.store.get_ledger_end_index());
+        let confirmed_index = request.prev_index + request.entries.len() as i64;
+        {
+            let mut progress = self.follower_progress.lock();
+            if progress.term != request.term {
+                progress.term = request.term;
+                progress.confirmed_index = -1;
+            }
+            progress.confirmed_index = progress.confirmed_index.max(confirmed_index);
+        }
+        self.update_follower_committed_index(request.term, request.commit_index);
+        response(DLedgerResponseCode::Success)
+    }
+
+    /// Follows the committed index of the leader, as far as the local log is known to match it.
+    pub fn update_follower_committed_index(&self, term: i64, leader_committed_index: i64) {
+        let confirmed_index = {
+            let progress = self.follower_progress.lock();
+            if progress.term != term {
+                return;
+            }
+            progress.confirmed_index
+        };
+        if self
+            .store
+            .update_committed_index(leader_committed_index.min(confirmed_index))
+        {
+            self.committed_tx
+                .send_replace(self.store.get_committed_index());
+        }
+    }
+}
@@ -0,0 +1,351 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use parking_lot::RwLock;
+use rand::Rng;
+use rocketmq_common::TimeUtils::get_current_millis;
+use tokio::sync::mpsc;
+use tokio::sync::watch;
+use tracing::info;
+
+use crate::dledger::dledger_config::DLedgerConfig;
+use crate::dledger::dledger_entry_pusher::DLedgerEntryPusher;
+use crate::dledger::dledger_mmap_file_store::DLedgerMmapFileStore;
+use crate::dledger::dledger_protocol::DLedgerResponseCode;
+use crate::dledger::dledger_protocol::HeartBeatRequest;
+use crate::dledger::dledger_protocol::HeartBeatResponse;
+use crate::dledger::dledger_protocol::VoteRequest;
+use crate::dledger::dledger_protocol::VoteResponse;
+use crate::dledger::dledger_protocol::VoteResult;
+use crate::dledger::dledger_rpc_service::DLedgerRpcService;
+use crate::dledger::member_state::DLedgerRole;
+use crate::dledger::member_state::MemberState;
+
+/// Called with the new role and term whenever the local member changes its role.
+pub type RoleChangeHandler = Arc<dyn Fn(DLedgerRole, i64) + Send + Sync>;
+
+const TICK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Drives the role of the local member: followers watch the heartbeats of their leader,
+/// candidates ask for votes and leaders keep their followers from starting an election.
+pub struct DLedgerLeaderElector {
+    config: Arc<DLedgerConfig>,
+    member_state: Arc<MemberState>,
+    store: Arc<DLedgerMmapFileStore>,
+    rpc_service: Arc<DLedgerRpcService>,
+    entry_pusher: Arc<DLedgerEntryPusher>,
+    last_leader_heartbeat_time: AtomicI64,
+    role_change_handlers: RwLock<Vec<RoleChangeHandler>>,
+}
+
+impl DLedgerLeaderElector {
+    pub fn new(
+        config: Arc<DLedgerConfig>,
+        member_state: Arc<MemberState>,
+        store: Arc<DLedgerMmapFileStore>,
+        rpc_service: Arc<DLedgerRpcService>,
+        entry_pusher: Arc<DLedgerEntryPusher>,
+    ) -> Self {
+        Self {
+            config,
+            member_state,
+            store,
+            rpc_service,
+            entry_pusher,
+            last_leader_heartbeat_time: AtomicI64::new(get_current_millis() as i64),
+            role_change_handlers: RwLock::new(Vec::new()),
+        }
+    }
+
+    pub fn add_role_change_handler(&self, handler: RoleChangeHandler) {
+        self.role_change_handlers.write().push(handler);
+    }
+
+    pub fn start(self: &Arc<Self>, shutdown_rx: watch::Receiver<bool>) {
+        tokio::spawn(self.clone().run(shutdown_rx));
+    }
+
+    async fn run(self: Arc<Self>, mut shutdown_rx: watch::Receiver<bool>) {
+        let heartbeat_interval = self.config.heartbeat_interval_ms as i64;
+        let max_heartbeat_silence = heartbeat_interval * self.config.max_heartbeat_leak as i64;
+        let mut observed: Option<(DLedgerRole, i64)> = None;
+        let mut next_vote_time = get_current_millis() as i64 + self.random_vote_interval();
+        let mut last_send_heartbeat_time = 0;
+        let mut last_success_heartbeat_time = 0;
+        loop {
+            if *shutdown_rx.borrow() {
+                break;
+            }
+            let (role, term, _) = self.member_state.snapshot();
+            let now = get_current_millis() as i64;
+            if observed != Some((role, term)) {
+                let role_changed = !matches!(observed, Some((last_role, _)) if last_role == role);
+                observed = Some((role, term));
+                match role {
+                    DLedgerRole::Leader => {
+                        last_send_heartbeat_time = 0;
+                        last_success_heartbeat_time = now;
+                        self.entry_pusher.start_leader(term, shutdown_rx.clone());
+                    }
+                    DLedgerRole::Candidate => next_vote_time = now + self.random_vote_interval(),
+                    DLedgerRole::Follower => {}
+                }
+                if role_changed || role == DLedgerRole::Leader {
+                    self.fire_role_change(role, term);
+                }
+            }
+            match role {
+                DLedgerRole::Leader => {
+                    if now - last_send_heartbeat_time >= heartbeat_interval {
+                        last_send_heartbeat_time = now;
+                        if self.send_heartbeats(term).await {
+                            last_success_heartbeat_time = now;
+                        } else if now - last_success_heartbeat_time > max_heartbeat_silence {
+                            info!(
+                                "[{}] lost the quorum of term {}, step down",
+                                self.member_state.self_id(),
+                                term
+                            );
+                            self.member_state.change_to_candidate(term);
+                        }
+                    }
+                }
+                DLedgerRole::Follower => {
+                    let last_heartbeat = self.last_leader_heartbeat_time.load(Ordering::Acquire);
+                    if now - last_heartbeat > max_heartbeat_silence {
+                        info!(
+                            "[{}] no heartbeat from the leader of term {} for {}ms, start an \
+                             election",
+                            self.member_state.self_id(),
+                            term,
+                            now - last_heartbeat
+                        );
+                        self.member_state.change_to_candidate(term);
+                    }
+                }
+                DLedgerRole::Candidate => {
+                    // granting a vote postpones the own candidacy as well
+                    let last_heartbeat = self.last_leader_heartbeat_time.load(Ordering::Acquire);
+                    if now >= next_vote_time
+                        && now - last_heartbeat >= self.config.min_vote_interval_ms as i64
+                    {
+                        self.vote_for_self().await;
+                        next_vote_time = get_current_millis() as i64 + self.random_vote_interval();
+                    }
+                }
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(TICK_INTERVAL) => {}
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+    }
+
+    fn random_vote_interval(&self) -> i64 {
+        let min = self.config.min_vote_interval_ms;
+        let max = self.config.max_vote_interval_ms.max(min + 1);
+        rand::rng().random_range(min..max) as i64
+    }
+
+    fn fire_role_change(&self, role: DLedgerRole, term: i64) {
+        info!(
+            "[{}] role changed to {} in term {}",
+            self.member_state.self_id(),
+            role,
+            term
+        );
+        for handler in self.role_change_handlers.read().iter() {
+            handler(role, term);
+        }
+    }
+
+    /// Sends a heartbeat to every peer, returns whether a quorum acknowledged the leadership.
+    async fn send_heartbeats(&self, term: i64) -> bool {
+        let peers = self.member_state.remote_peer_ids();
+        let (tx, mut rx) = mpsc::channel(peers.len().max(1));
+        for peer_id in peers.iter() {
+            let request = HeartBeatRequest {
+                group: self.member_state.group().to_string(),
+                remote_id: peer_id.clone(),
+                leader_id: self.member_state.self_id().to_string(),
+                term,
+                commit_index: self.store.get_committed_index(),
+            };
+            let rpc_service = self.rpc_service.clone();
+            let peer_id = peer_id.clone();
+            let tx = tx.clone();
+            // detached, an abandoned request would leave a broken frame on the connection
+            tokio::spawn(async move {
+                let _ = tx
+                    .send(rpc_service.heartbeat(&peer_id, &request).await)
+                    .await;
+            });
+        }
+        drop(tx);
+        let mut acknowledged = 1;
+        while !self.member_state.is_quorum(acknowledged) {
+            let Some(response) = rx.recv().await else {
+                return false;
+            };
+            match response {
+                Ok(response) if response.code == DLedgerResponseCode::Success => acknowledged += 1,
+                Ok(response) if response.term > term => {
+                    self.member_state.change_to_candidate(response.term);
+                    return false;
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+
+    async fn vote_for_self(&self) {
+        let term = self.member_state.next_term();
+        let request = VoteRequest {
+            group: self.member_state.group().to_string(),
+            remote_id: String::new(),
+            leader_id: self.member_state.self_id().to_string(),
+            term,
+            ledger_end_index: self.store.get_ledger_end_index(),
+            ledger_end_term: self.store.get_ledger_end_term(),
+        };
+        info!(
+            "[{}] start the election of term {}",
+            self.member_state.self_id(),
+            term
+        );
+        let peers = self.member_state.remote_peer_ids();
+        let (tx, mut rx) = mpsc::channel(peers.len().max(1));
+        for peer_id in peers.iter() {
+            let mut request = request.clone();
+            request.remote_id = peer_id.clone();
+            let rpc_service = self.rpc_service.clone();
+            let peer_id = peer_id.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let _ = tx.send(rpc_service.vote(&peer_id, &request).await).await;
+            });
+        }
+        drop(tx);
+        let mut accepted = 1;
+        while !self.member_state.is_quorum(accepted) {
+            let Some(response) = rx.recv().await else {
+                return;
+            };
+            match response {
+                Ok(response) if response.vote_result == VoteResult::Accept => accepted += 1,
+                Ok(response) if response.term > term => {
+                    self.member_state.change_to_candidate(response.term);
+                    return;
+                }
+                _ => {}
+            }
+        }
+        self.member_state.change_to_leader(term);
+    }
+
+    pub fn handle_vote(&self, request: VoteRequest) -> VoteResponse {
+        let respond = |vote_result| VoteResponse {
+            term: self.member_state.current_term(),
+            vote_result,
+        };
+        if request.group != self.member_state.group()
+            || !self
+                .member_state
+                .peer_map()
+                .contains_key(&request.leader_id)
+        {
+            return respond(VoteResult::RejectUnknownLeader);
+        }
+        let (_, term, leader_id) = self.member_state.snapshot();
+        if request.term < term {
+            return respond(VoteResult::RejectExpiredVoteTerm);
+        }
+        if request.term > term {
+            self.member_state.change_to_candidate(request.term);
+        } else if leader_id.is_some_and(|leader_id| leader_id != request.leader_id) {
+            return respond(VoteResult::RejectAlreadyHasLeader);
+        }
+        let ledger_end_term = self.store.get_ledger_end_term();
+        if request.ledger_end_term < ledger_end_term {
+            return respond(VoteResult::RejectExpiredLedgerTerm);
+        }
+        if request.ledger_end_term == ledger_end_term
+            && request.ledger_end_index < self.store.get_ledger_end_index()
+        {
+            return respond(VoteResult::RejectSmallLedgerEndIndex);
+        }
+        if !self.member_state.try_vote(request.term, &request.leader_id) {
+            return respond(VoteResult::RejectAlreadyVoted);
+        }
+        self.touch_leader_heartbeat();
+        respond(VoteResult::Accept)
+    }
+
+    pub fn handle_heartbeat(&self, request: HeartBeatRequest) -> HeartBeatResponse {
+        let code = match self.accept_leader(&request.group, request.term, &request.leader_id) {
+            Ok(()) => {
+                self.entry_pusher
+                    .update_follower_committed_index(request.term, request.commit_index);
+                DLedgerResponseCode::Success
+            }
+            Err(code) => code,
+        };
+        HeartBeatResponse {
+            term: self.member_state.current_term(),
+            code,
+        }
+    }
+
+    /// Checks a request sent by a leader, following it if its term is the newest one.
+    pub fn accept_leader(
+        &self,
+        group: &str,
+        term: i64,
+        leader_id: &str,
+    ) -> Result<(), DLedgerResponseCode> {
+        if group != self.member_state.group() {
+            return Err(DLedgerResponseCode::UnknownGroup);
+        }
+        let (role, current_term, current_leader) = self.member_state.snapshot();
+        if term < current_term {
+            return Err(DLedgerResponseCode::ExpiredTerm);
+        }
+        if term == current_term
+            && current_leader
+                .as_deref()
+                .is_some_and(|current_leader| current_leader != leader_id)
+        {
+            return Err(DLedgerResponseCode::InconsistentLeader);
+        }
+        if role != DLedgerRole::Follower || term > current_term || current_leader.is_none() {
+            self.member_state.change_to_follower(term, leader_id);
+        }
+        self.touch_leader_heartbeat();
+        Ok(())
+    }
+
+    fn touch_leader_heartbeat(&self) {
+        self.last_leader_heartbeat_time
+            .store(get_current_millis() as i64, Ordering::Release);
+    }
+}
@@ -0,0 +1,498 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use parking_lot::RwLock;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::consume_queue::mapped_file_queue::MappedFileQueue;
+use crate::dledger::dledger_config::DLedgerConfig;
+use crate::dledger::dledger_entry::DLedgerEntry;
+use crate::dledger::dledger_entry::MAGIC;
+use crate::log_file::mapped_file::MappedFile;
+
+/// Layout of an index unit: `magic | pos | size | index | term`.
+pub const INDEX_UNIT_SIZE: i32 = 4 + 8 + 4 + 8 + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IndexUnit {
+    pos: i64,
+    size: i32,
+    index: i64,
+    term: i64,
+}
+
+struct LedgerFiles {
+    data_file_queue: MappedFileQueue,
+    index_file_queue: MappedFileQueue,
+    ledger_end_index: i64,
+    ledger_end_term: i64,
+}
+
+/// Stores the entries of the replicated log in memory mapped files.
+///
+/// Entries are appended to the data files and located through fixed size units in the index
+/// files, the unit of entry `i` lives at offset `i * INDEX_UNIT_SIZE`.
+pub struct DLedgerMmapFileStore {
+    config: Arc<DLedgerConfig>,
+    index_file_size: u64,
+    files: RwLock<LedgerFiles>,
+    committed_index: AtomicI64,
+    checkpoint: IndexCheckpoint,
+}
+
+impl DLedgerMmapFileStore {
+    pub fn new(config: Arc<DLedgerConfig>) -> Self {
+        let index_file_size = (config.mapped_file_size_for_entry_index / INDEX_UNIT_SIZE as u64)
+            .max(1)
+            * INDEX_UNIT_SIZE as u64;
+        let files = LedgerFiles {
+            data_file_queue: MappedFileQueue::new(
+                config.get_data_store_path(),
+                config.mapped_file_size_for_entry_data,
+                None,
+            ),
+            index_file_queue: MappedFileQueue::new(
+                config.get_index_store_path(),
+                index_file_size,
+                None,
+            ),
+            ledger_end_index: -1,
+            ledger_end_term: -1,
+        };
+        let checkpoint = IndexCheckpoint::new(config.get_checkpoint_path());
+        Self {
+            config,
+            index_file_size,
+            files: RwLock::new(files),
+            committed_index: AtomicI64::new(-1),
+            checkpoint,
+        }
+    }
+
+    /// Loads the files and drops whatever was written after the last complete entry.
+    pub fn load(&self) -> bool {
+        let mut files = self.files.write();
+        if !files.data_file_queue.load() || !files.index_file_queue.load() {
+            return false;
+        }
+        let mut unit_count = 0;
+        if let Some(last) = files.index_file_queue.get_last_mapped_file() {
+            let mut position = 0;
+            while position + INDEX_UNIT_SIZE as u64 <= self.index_file_size {
+                let Some(data) = last.get_data(position as usize, INDEX_UNIT_SIZE as usize) else {
+                    break;
+                };
+                if Self::decode_unit(data).is_none() {
+                    break;
+                }
+                position += INDEX_UNIT_SIZE as u64;
+            }
+            unit_count = (last.get_file_from_offset() + position) as i64 / INDEX_UNIT_SIZE as i64;
+        }
+        files.ledger_end_index = -1;
+        files.ledger_end_term = -1;
+        let mut data_end = 0;
+        // the tail unit may point at data that never reached the disk
+        while unit_count > 0 {
+            let Some(unit) = Self::read_unit(&files, self.index_file_size, unit_count - 1) else {
+                break;
+            };
+            if Self::read_entry(&files, &unit).is_some() {
+                files.ledger_end_index = unit.index;
+                files.ledger_end_term = unit.term;
+                data_end = unit.pos + unit.size as i64;
+                break;
+            }
+            warn!("drop broken dledger entry {} on recover", unit.index);
+            unit_count -= 1;
+        }
+        Self::truncate_queue(
+            &mut files.index_file_queue,
+            unit_count * INDEX_UNIT_SIZE as i64,
+        );
+        Self::truncate_queue(&mut files.data_file_queue, data_end);
+        drop(files);
+
+        self.checkpoint.load();
+        self.committed_index.store(
+            self.checkpoint.index().min(self.get_ledger_end_index()),
+            Ordering::Release,
+        );
+        info!(
+            "load dledger store, ledger end index {}, ledger end term {}, committed index {}",
+            self.get_ledger_end_index(),
+            self.get_ledger_end_term(),
+            self.get_committed_index()
+        );
+        true
+    }
+
+    /// Appends `body` as the next entry of `term`, used by the leader.
+    pub fn append_as_leader(&self, term: i64, body: Bytes) -> Option<DLedgerEntry> {
+        let mut files = self.files.write();
+        let mut entry = DLedgerEntry::new(files.ledger_end_index + 1, term, body);
+        if self.do_append(&mut files, &mut entry) {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    /// Appends an entry replicated from the leader, it must directly follow the local log.
+    pub fn append_as_follower(&self, entry: &DLedgerEntry) -> bool {
+        let mut files = self.files.write();
+        if entry.index != files.ledger_end_index + 1 {
+            warn!(
+                "refuse dledger entry {}, the ledger end index is {}",
+                entry.index, files.ledger_end_index
+            );
+            return false;
+        }
+        let mut entry = entry.clone();
+        self.do_append(&mut files, &mut entry)
+    }
+
+    fn do_append(&self, files: &mut LedgerFiles, entry: &mut DLedgerEntry) -> bool {
+        let size = entry.compute_size_in_bytes() as u64;
+        if size > self.config.mapped_file_size_for_entry_data {
+            error!(
+                "dledger entry {} is larger than a data file, size {}",
+                entry.index, size
+            );
+            return false;
+        }
+        let Some(mut data_file) = files
+            .data_file_queue
+            .get_last_mapped_file_mut_start_offset(0, true)
+        else {
+            error!("create dledger data file failed");
+            return false;
+        };
+        if data_file.get_file_size() - (data_file.get_wrote_position() as u64) < size {
+            // entries never span two files, the rest of this one stays unused
+            data_file.set_wrote_position(data_file.get_file_size() as i32);
+            let Some(next) = files
+                .data_file_queue
+                .get_last_mapped_file_mut_start_offset(0, true)
+            else {
+                error!("create dledger data file failed");
+                return false;
+            };
+            data_file = next;
+        }
+        entry.pos = data_file.get_file_from_offset() as i64 + data_file.get_wrote_position() as i64;
+        if !data_file.append_message_bytes(&entry.encode()) {
+            error!("append dledger entry {} to data file failed", entry.index);
+            return false;
+        }
+
+        let Some(index_file) = files
+            .index_file_queue
+            .get_last_mapped_file_mut_start_offset(0, true)
+        else {
+            error!("create dledger index file failed");
+            return false;
+        };
+        let unit = IndexUnit {
+            pos: entry.pos,
+            size: size as i32,
+            index: entry.index,
+            term: entry.term,
+        };
+        if !index_file.append_message_bytes(&Self::encode_unit(&unit)) {
+            error!("append dledger index {} failed", entry.index);
+            return false;
+        }
+        files.ledger_end_index = entry.index;
+        files.ledger_end_term = entry.term;
+        true
+    }
+
+    pub fn get(&self, index: i64) -> Option<DLedgerEntry> {
+        let files = self.files.read();
+        if index < 0 || index > files.ledger_end_index {
+            return None;
+        }
+        let unit = Self::read_unit(&files, self.index_file_size, index)?;
+        Self::read_entry(&files, &unit)
+    }
+
+    /// Term of the entry at `index`, -1 when the log has no such entry.
+    pub fn get_entry_term(&self, index: i64) -> i64 {
+        let files = self.files.read();
+        if index < 0 || index > files.ledger_end_index {
+            return -1;
+        }
+        Self::read_unit(&files, self.index_file_size, index)
+            .map(|unit| unit.term)
+            .unwrap_or(-1)
+    }
+
+    /// Removes the entry at `from_index` and everything after it.
+    pub fn truncate(&self, from_index: i64) {
+        let mut files = self.files.write();
+        if from_index > files.ledger_end_index || from_index < 0 {
+            return;
+        }
+        let Some(unit) = Self::read_unit(&files, self.index_file_size, from_index) else {
+            return;
+        };
+        info!(
+            "truncate dledger store from index {}, the ledger end index was {}",
+            from_index, files.ledger_end_index
+        );
+        Self::truncate_queue(&mut files.data_file_queue, unit.pos);
+        let index_offset = from_index * INDEX_UNIT_SIZE as i64;
+        Self::truncate_queue(&mut files.index_file_queue, index_offset);
+        // recover scans the index file until the first broken unit, so wipe the dropped ones
+        if let Some(index_file) = files
+            .index_file_queue
+            .find_mapped_file_by_offset(index_offset, false)
+        {
+            let position = (index_offset as u64 - index_file.get_file_from_offset()) as usize;
+            let stale = index_file.get_file_size() as usize - position;
+            index_file.put_slice(&vec![0; stale], position);
+        }
+        files.ledger_end_index = from_index - 1;
+        files.ledger_end_term = match from_index {
+            0 => -1,
+            _ => Self::read_unit(&files, self.index_file_size, from_index - 1)
+                .map(|unit| unit.term)
+                .unwrap_or(-1),
+        };
+        let committed_index = self.committed_index.load(Ordering::Acquire);
+        if committed_index > files.ledger_end_index {
+            error!(
+                "[BUG] truncate committed dledger entries, committed index {}",
+                committed_index
+            );
+            self.committed_index
+                .store(files.ledger_end_index, Ordering::Release);
+        }
+    }
+
+    pub fn update_committed_index(&self, committed_index: i64) -> bool {
+        let committed_index = committed_index.min(self.get_ledger_end_index());
+        self.committed_index
+            .fetch_max(committed_index, Ordering::AcqRel)
+            < committed_index
+    }
+
+    pub fn flush(&self) {
+        let files = self.files.read();
+        while !files.data_file_queue.flush(0) {}
+        while !files.index_file_queue.flush(0) {}
+        drop(files);
+        let committed_index = self.get_committed_index();
+        if self.checkpoint.index() != committed_index {
+            self.checkpoint.set_index(committed_index);
+            self.checkpoint.persist();
+        }
+    }
+
+    #[inline]
+    pub fn get_ledger_end_index(&self) -> i64 {
+        self.files.read().ledger_end_index
+    }
+
+    #[inline]
+    pub fn get_ledger_end_term(&self) -> i64 {
+        self.files.read().ledger_end_term
+    }
+
+    #[inline]
+    pub fn get_committed_index(&self) -> i64 {
+        self.committed_index.load(Ordering::Acquire)
+    }
+
+    fn truncate_queue(queue: &mut MappedFileQueue, offset: i64) {
+        queue.truncate_dirty_files(offset);
+        if queue.get_flushed_where() > offset {
+            queue.set_flushed_where(offset);
+        }
+        if queue.get_committed_where() > offset {
+            queue.set_committed_where(offset);
+        }
+    }
+
+    fn read_unit(files: &LedgerFiles, index_file_size: u64, index: i64) -> Option<IndexUnit> {
+        let offset = index * INDEX_UNIT_SIZE as i64;
+        let index_file = files
+            .index_file_queue
+            .find_mapped_file_by_offset(offset, false)?;
+        let data = index_file.get_data(
+            (offset % index_file_size as i64) as usize,
+            INDEX_UNIT_SIZE as usize,
+        )?;
+        Self::decode_unit(data).filter(|unit| unit.index == index)
+    }
+
+    fn read_entry(files: &LedgerFiles, unit: &IndexUnit) -> Option<DLedgerEntry> {
+        let data_file = files
+            .data_file_queue
+            .find_mapped_file_by_offset(unit.pos, false)?;
+        let position = unit.pos - data_file.get_file_from_offset() as i64;
+        let mut data = data_file.get_data(position as usize, unit.size as usize)?;
+        DLedgerEntry::decode(&mut data).filter(|entry| entry.index == unit.index)
+    }
+
+    fn encode_unit(unit: &IndexUnit) -> Bytes {
+        let mut buffer = BytesMut::with_capacity(INDEX_UNIT_SIZE as usize);
+        buffer.put_i32(MAGIC);
+        buffer.put_i64(unit.pos);
+        buffer.put_i32(unit.size);
+        buffer.put_i64(unit.index);
+        buffer.put_i64(unit.term);
+        buffer.freeze()
+    }
+
+    fn decode_unit(mut data: Bytes) -> Option<IndexUnit> {
+        if data.get_i32() != MAGIC {
+            return None;
+        }
+        Some(IndexUnit {
+            pos: data.get_i64(),
+            size: data.get_i32(),
+            index: data.get_i64(),
+            term: data.get_i64(),
+        })
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexCheckpointSerializeWrapper {
+    index: i64,
+}
+
+/// Persists one index of the log, such as the committed index, across restarts.
+pub(crate) struct IndexCheckpoint {
+    config_path: String,
+    index: AtomicI64,
+}
+
+impl IndexCheckpoint {
+    pub(crate) fn new(config_path: String) -> Self {
+        Self {
+            config_path,
+            index: AtomicI64::new(-1),
+        }
+    }
+
+    pub(crate) fn index(&self) -> i64 {
+        self.index.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_index(&self, index: i64) {
+        self.index.store(index, Ordering::Release);
+    }
+}
+
+impl ConfigManager for IndexCheckpoint {
+    fn config_file_path(&self) -> String {
+        self.config_path.clone()
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let wrapper = IndexCheckpointSerializeWrapper {
+            index: self.index(),
+        };
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&wrapper).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&wrapper).expect("encode failed")
+        }
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        if let Ok(wrapper) =
+            SerdeJsonUtils::from_json_str::<IndexCheckpointSerializeWrapper>(json_string)
+        {
+            self.set_index(wrapper.index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_store(dir: &tempfile::TempDir) -> DLedgerMmapFileStore {
+        let config = DLedgerConfig {
+            store_base_dir: dir.path().to_string_lossy().to_string(),
+            mapped_file_size_for_entry_data: 256,
+            mapped_file_size_for_entry_index: INDEX_UNIT_SIZE as u64 * 4,
+            ..Default::default()
+        };
+        DLedgerMmapFileStore::new(Arc::new(config))
+    }
+
+    #[test]
+    fn append_truncate_and_recover() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = new_store(&dir);
+        assert!(store.load());
+        for i in 0..10 {
+            let body = Bytes::from(format!("entry-{i}-{}", "x".repeat(40)));
+            let entry = store.append_as_leader(1, body).unwrap();
+            assert_eq!(entry.index, i);
+        }
+        assert_eq!(store.get_ledger_end_index(), 9);
+        assert_eq!(
+            store.get(4).unwrap().body,
+            Bytes::from(format!("entry-4-{}", "x".repeat(40)))
+        );
+
+        store.truncate(6);
+        assert_eq!(store.get_ledger_end_index(), 5);
+        assert!(store.get(6).is_none());
+        let replicated = DLedgerEntry::new(6, 2, Bytes::from_static(b"from new leader"));
+        assert!(store.append_as_follower(&replicated));
+        assert!(!store.append_as_follower(&DLedgerEntry::new(9, 2, Bytes::new())));
+        assert_eq!(store.get_entry_term(6), 2);
+        assert!(store.update_committed_index(5));
+        store.flush();
+
+        let recovered = new_store(&dir);
+        assert!(recovered.load());
+        assert_eq!(recovered.get_ledger_end_index(), 6);
+        assert_eq!(recovered.get_ledger_end_term(), 2);
+        assert_eq!(recovered.get_committed_index(), 5);
+        assert_eq!(
+            recovered.get(6).unwrap().body,
+            Bytes::from_static(b"from new leader")
+        );
+        assert_eq!(recovered.get(0).unwrap().term, 1);
+    }
+}
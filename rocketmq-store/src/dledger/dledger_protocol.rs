@@ -0,0 +1,231 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingDeserializable;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::dledger::dledger_entry::DLedgerEntry;
+use crate::store_error::StoreError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum DLedgerRequestCode {
+    Vote = 51001,
+    HeartBeat = 51002,
+    Push = 51004,
+}
+
+impl From<DLedgerRequestCode> for i32 {
+    fn from(code: DLedgerRequestCode) -> Self {
+        code as i32
+    }
+}
+
+impl DLedgerRequestCode {
+    pub fn value_of(code: i32) -> Option<Self> {
+        match code {
+            51001 => Some(Self::Vote),
+            51002 => Some(Self::HeartBeat),
+            51004 => Some(Self::Push),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DLedgerResponseCode {
+    Success,
+    NotLeader,
+    ExpiredTerm,
+    InconsistentLeader,
+    InconsistentState,
+    UnknownGroup,
+    WaitQuorumAckTimeout,
+    AppendFailed,
+    NetworkError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum VoteResult {
+    Accept,
+    RejectUnknownLeader,
+    RejectExpiredVoteTerm,
+    RejectAlreadyVoted,
+    RejectAlreadyHasLeader,
+    RejectExpiredLedgerTerm,
+    RejectSmallLedgerEndIndex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteRequest {
+    pub group: String,
+    pub remote_id: String,
+    /// The candidate asking for the vote.
+    pub leader_id: String,
+    pub term: i64,
+    pub ledger_end_index: i64,
+    pub ledger_end_term: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteResponse {
+    pub term: i64,
+    pub vote_result: VoteResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartBeatRequest {
+    pub group: String,
+    pub remote_id: String,
+    pub leader_id: String,
+    pub term: i64,
+    pub commit_index: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartBeatResponse {
+    pub term: i64,
+    pub code: DLedgerResponseCode,
+}
+
+/// Replicates `entries` to a follower. `prev_index`/`prev_term` name the entry that must
+/// directly precede them in the follower's log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushEntryRequest {
+    pub group: String,
+    pub remote_id: String,
+    pub leader_id: String,
+    pub term: i64,
+    pub prev_index: i64,
+    pub prev_term: i64,
+    pub commit_index: i64,
+    #[serde(skip)]
+    pub entries: Vec<DLedgerEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushEntryResponse {
+    pub term: i64,
+    pub code: DLedgerResponseCode,
+    pub ledger_end_index: i64,
+}
+
+/// Builds a request whose body is the json of `body`.
+pub fn encode_request<T: Serialize>(
+    code: DLedgerRequestCode,
+    body: &T,
+) -> Result<RemotingCommand, StoreError> {
+    let body = body
+        .encode()
+        .map_err(|e| StoreError::General(e.to_string()))?;
+    Ok(RemotingCommand::create_remoting_command(code).set_body(body))
+}
+
+pub fn decode_body<T: DeserializeOwned>(command: &RemotingCommand) -> Result<T, StoreError> {
+    let body = command
+        .get_body()
+        .ok_or_else(|| StoreError::General("dledger command without body".to_string()))?;
+    T::decode(body).map_err(|e| StoreError::General(e.to_string()))
+}
+
+impl PushEntryRequest {
+    /// The body carries the length prefixed json of the request followed by the raw entries.
+    pub fn encode_command(&self) -> Result<RemotingCommand, StoreError> {
+        let json = self
+            .encode()
+            .map_err(|e| StoreError::General(e.to_string()))?;
+        let mut body = BytesMut::with_capacity(
+            4 + json.len()
+                + self
+                    .entries
+                    .iter()
+                    .map(DLedgerEntry::compute_size_in_bytes)
+                    .sum::<usize>(),
+        );
+        body.put_i32(json.len() as i32);
+        body.put_slice(&json);
+        for entry in &self.entries {
+            body.put_slice(&entry.encode());
+        }
+        Ok(RemotingCommand::create_remoting_command(DLedgerRequestCode::Push).set_body(body))
+    }
+
+    pub fn decode_command(command: &RemotingCommand) -> Result<Self, StoreError> {
+        let mut body: Bytes = command
+            .get_body()
+            .cloned()
+            .ok_or_else(|| StoreError::General("push request without body".to_string()))?;
+        if body.remaining() < 4 {
+            return Err(StoreError::General("truncated push request".to_string()));
+        }
+        let json_len = body.get_i32() as usize;
+        if body.remaining() < json_len {
+            return Err(StoreError::General("truncated push request".to_string()));
+        }
+        let json = body.split_to(json_len);
+        let mut request = Self::decode(&json).map_err(|e| StoreError::General(e.to_string()))?;
+        while body.has_remaining() {
+            let entry = DLedgerEntry::decode(&mut body)
+                .ok_or_else(|| StoreError::General("corrupted dledger entry".to_string()))?;
+            request.entries.push(entry);
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_request_round_trip() {
+        let request = PushEntryRequest {
+            group: "g".to_string(),
+            remote_id: "n1".to_string(),
+            leader_id: "n0".to_string(),
+            term: 3,
+            prev_index: 4,
+            prev_term: 2,
+            commit_index: 4,
+            entries: vec![
+                DLedgerEntry::new(5, 3, Bytes::from_static(b"a")),
+                DLedgerEntry::new(6, 3, Bytes::from_static(b"bc")),
+            ],
+        };
+        let command = request.encode_command().unwrap();
+        assert_eq!(command.code(), DLedgerRequestCode::Push as i32);
+        let decoded = PushEntryRequest::decode_command(&command).unwrap();
+        assert_eq!(decoded.prev_index, 4);
+        assert_eq!(decoded.entries, request.entries);
+    }
+}
@@ -0,0 +1,268 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rocketmq_remoting::code::response_code::RemotingSysResponseCode;
+use rocketmq_remoting::connection::Connection;
+use rocketmq_remoting::protocol::remoting_command::RemotingCommand;
+use rocketmq_remoting::protocol::RemotingSerializable;
+use serde::Serialize;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tracing::info;
+use tracing::warn;
+
+use crate::dledger::dledger_protocol::decode_body;
+use crate::dledger::dledger_protocol::encode_request;
+use crate::dledger::dledger_protocol::DLedgerRequestCode;
+use crate::dledger::dledger_protocol::HeartBeatRequest;
+use crate::dledger::dledger_protocol::HeartBeatResponse;
+use crate::dledger::dledger_protocol::PushEntryRequest;
+use crate::dledger::dledger_protocol::PushEntryResponse;
+use crate::dledger::dledger_protocol::VoteRequest;
+use crate::dledger::dledger_protocol::VoteResponse;
+use crate::dledger::member_state::MemberState;
+use crate::store_error::StoreError;
+
+/// Serves the requests other members send to the local one.
+pub trait DLedgerProtocolHandler: Send + Sync + 'static {
+    fn handle_vote(&self, request: VoteRequest) -> VoteResponse;
+
+    fn handle_heartbeat(&self, request: HeartBeatRequest) -> HeartBeatResponse;
+
+    fn handle_push(&self, request: PushEntryRequest) -> PushEntryResponse;
+}
+
+/// Transport between the members of a group, speaking the remoting protocol over one
+/// connection per peer.
+pub struct DLedgerRpcService {
+    member_state: Arc<MemberState>,
+    rpc_timeout: Duration,
+    connections: HashMap<String, tokio::sync::Mutex<Option<Connection>>>,
+    opaque: AtomicI32,
+}
+
+impl DLedgerRpcService {
+    pub fn new(member_state: Arc<MemberState>, rpc_timeout_ms: u64) -> Self {
+        let connections = member_state
+            .remote_peer_ids()
+            .into_iter()
+            .map(|peer_id| (peer_id, tokio::sync::Mutex::new(None)))
+            .collect();
+        Self {
+            member_state,
+            rpc_timeout: Duration::from_millis(rpc_timeout_ms),
+            connections,
+            opaque: AtomicI32::new(0),
+        }
+    }
+
+    /// Binds the address of the local member and serves requests until shutdown.
+    pub async fn start(
+        &self,
+        handler: Arc<dyn DLedgerProtocolHandler>,
+        shutdown_rx: watch::Receiver<bool>,
+    ) -> Result<(), StoreError> {
+        let addr = self.member_state.self_addr().cloned().ok_or_else(|| {
+            StoreError::General(format!(
+                "no address configured for dledger member {}",
+                self.member_state.self_id()
+            ))
+        })?;
+        let listener = TcpListener::bind(addr.as_str())
+            .await
+            .map_err(|e| StoreError::General(format!("bind {} failed: {}", addr, e)))?;
+        info!(
+            "dledger member {} listens on {}",
+            self.member_state.self_id(),
+            addr
+        );
+        tokio::spawn(Self::accept(listener, handler, shutdown_rx));
+        Ok(())
+    }
+
+    async fn accept(
+        listener: TcpListener,
+        handler: Arc<dyn DLedgerProtocolHandler>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    let Ok((stream, remote_addr)) = accepted else {
+                        continue;
+                    };
+                    let _ = stream.set_nodelay(true);
+                    tokio::spawn(Self::serve(
+                        Connection::new(stream),
+                        handler.clone(),
+                        shutdown_rx.clone(),
+                    ));
+                    info!("accept dledger connection from {}", remote_addr);
+                }
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+    }
+
+    async fn serve(
+        mut connection: Connection,
+        handler: Arc<dyn DLedgerProtocolHandler>,
+        mut shutdown_rx: watch::Receiver<bool>,
+    ) {
+        loop {
+            let request = tokio::select! {
+                request = connection.receive_command() => request,
+                _ = shutdown_rx.changed() => return,
+            };
+            let Some(Ok(request)) = request else {
+                return;
+            };
+            let opaque = request.opaque();
+            let response = Self::process(handler.as_ref(), request).set_opaque(opaque);
+            if connection.send_command(response).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn process(handler: &dyn DLedgerProtocolHandler, request: RemotingCommand) -> RemotingCommand {
+        let response = match DLedgerRequestCode::value_of(request.code()) {
+            Some(DLedgerRequestCode::Vote) => {
+                decode_body(&request).and_then(|req| Self::to_body(&handler.handle_vote(req)))
+            }
+            Some(DLedgerRequestCode::HeartBeat) => {
+                decode_body(&request).and_then(|req| Self::to_body(&handler.handle_heartbeat(req)))
+            }
+            Some(DLedgerRequestCode::Push) => PushEntryRequest::decode_command(&request)
+                .and_then(|req| Self::to_body(&handler.handle_push(req))),
+            None => Err(StoreError::General(format!(
+                "unknown dledger request code {}",
+                request.code()
+            ))),
+        };
+        match response {
+            Ok(body) => RemotingCommand::create_response_command().set_body(body),
+            Err(e) => RemotingCommand::create_response_command_with_code_remark(
+                RemotingSysResponseCode::SystemError,
+                e.to_string(),
+            ),
+        }
+    }
+
+    fn to_body<T: Serialize>(response: &T) -> Result<Vec<u8>, StoreError> {
+        response
+            .encode()
+            .map_err(|e| StoreError::General(e.to_string()))
+    }
+
+    pub async fn vote(
+        &self,
+        peer_id: &str,
+        request: &VoteRequest,
+    ) -> Result<VoteResponse, StoreError> {
+        let command = encode_request(DLedgerRequestCode::Vote, request)?;
+        decode_body(&self.invoke(peer_id, command).await?)
+    }
+
+    pub async fn heartbeat(
+        &self,
+        peer_id: &str,
+        request: &HeartBeatRequest,
+    ) -> Result<HeartBeatResponse, StoreError> {
+        let command = encode_request(DLedgerRequestCode::HeartBeat, request)?;
+        decode_body(&self.invoke(peer_id, command).await?)
+    }
+
+    pub async fn push(
+        &self,
+        peer_id: &str,
+        request: &PushEntryRequest,
+    ) -> Result<PushEntryResponse, StoreError> {
+        let command = request.encode_command()?;
+        decode_body(&self.invoke(peer_id, command).await?)
+    }
+
+    async fn invoke(
+        &self,
+        peer_id: &str,
+        request: RemotingCommand,
+    ) -> Result<RemotingCommand, StoreError> {
+        let (Some(addr), Some(slot)) = (
+            self.member_state.peer_map().get(peer_id),
+            self.connections.get(peer_id),
+        ) else {
+            return Err(StoreError::General(format!(
+                "unknown dledger peer {}",
+                peer_id
+            )));
+        };
+        let opaque = self.opaque.fetch_add(1, Ordering::Relaxed);
+        let mut connection = slot.lock().await;
+        let result = tokio::time::timeout(self.rpc_timeout, async {
+            if connection.is_none() {
+                let stream = TcpStream::connect(addr.as_str())
+                    .await
+                    .map_err(|e| StoreError::General(format!("connect {} failed: {}", addr, e)))?;
+                let _ = stream.set_nodelay(true);
+                *connection = Some(Connection::new(stream));
+            }
+            let channel = connection.as_mut().unwrap();
+            channel
+                .send_command(request.set_opaque(opaque))
+                .await
+                .map_err(|e| StoreError::General(e.to_string()))?;
+            loop {
+                match channel.receive_command().await {
+                    Some(Ok(response)) if response.opaque() == opaque => return Ok(response),
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => return Err(StoreError::General(e.to_string())),
+                    None => {
+                        return Err(StoreError::General(format!(
+                            "connection to {} closed",
+                            addr
+                        )))
+                    }
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| Err(StoreError::General(format!("request to {} timeout", addr))));
+        match result {
+            Ok(response) if response.code() == RemotingSysResponseCode::Success as i32 => {
+                Ok(response)
+            }
+            Ok(response) => Err(StoreError::General(format!(
+                "dledger request to {} failed: {:?}",
+                peer_id,
+                response.remark()
+            ))),
+            Err(e) => {
+                // the connection may hold a late response, start over with a new one
+                *connection = None;
+                warn!("dledger request to {} failed: {}", peer_id, e);
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,346 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use rocketmq_common::common::config_manager::ConfigManager;
+use tokio::sync::watch;
+use tracing::info;
+
+use crate::dledger::dledger_config::DLedgerConfig;
+use crate::dledger::dledger_entry::DLedgerEntry;
+use crate::dledger::dledger_entry_pusher::DLedgerEntryPusher;
+use crate::dledger::dledger_leader_elector::DLedgerLeaderElector;
+use crate::dledger::dledger_leader_elector::RoleChangeHandler;
+use crate::dledger::dledger_mmap_file_store::DLedgerMmapFileStore;
+use crate::dledger::dledger_protocol::DLedgerResponseCode;
+use crate::dledger::dledger_protocol::HeartBeatRequest;
+use crate::dledger::dledger_protocol::HeartBeatResponse;
+use crate::dledger::dledger_protocol::PushEntryRequest;
+use crate::dledger::dledger_protocol::PushEntryResponse;
+use crate::dledger::dledger_protocol::VoteRequest;
+use crate::dledger::dledger_protocol::VoteResponse;
+use crate::dledger::dledger_rpc_service::DLedgerProtocolHandler;
+use crate::dledger::dledger_rpc_service::DLedgerRpcService;
+use crate::dledger::member_state::MemberState;
+use crate::store_error::StoreError;
+
+/// One member of a DLedger group: a Raft replicated log with leader election.
+pub struct DLedgerServer {
+    config: Arc<DLedgerConfig>,
+    member_state: Arc<MemberState>,
+    store: Arc<DLedgerMmapFileStore>,
+    rpc_service: Arc<DLedgerRpcService>,
+    entry_pusher: Arc<DLedgerEntryPusher>,
+    leader_elector: Arc<DLedgerLeaderElector>,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+impl DLedgerServer {
+    pub fn new(config: DLedgerConfig) -> Self {
+        let config = Arc::new(config);
+        let member_state = Arc::new(MemberState::new(config.clone()));
+        let store = Arc::new(DLedgerMmapFileStore::new(config.clone()));
+        let rpc_service = Arc::new(DLedgerRpcService::new(
+            member_state.clone(),
+            config.rpc_timeout_ms,
+        ));
+        let entry_pusher = Arc::new(DLedgerEntryPusher::new(
+            config.clone(),
+            member_state.clone(),
+            store.clone(),
+            rpc_service.clone(),
+        ));
+        let leader_elector = Arc::new(DLedgerLeaderElector::new(
+            config.clone(),
+            member_state.clone(),
+            store.clone(),
+            rpc_service.clone(),
+            entry_pusher.clone(),
+        ));
+        Self {
+            config,
+            member_state,
+            store,
+            rpc_service,
+            entry_pusher,
+            leader_elector,
+            shutdown_tx: watch::Sender::new(false),
+        }
+    }
+
+    pub fn load(&self) -> bool {
+        // the term file is missing on the first start
+        self.member_state.load();
+        let result = self.store.load();
+        self.entry_pusher.load();
+        result
+    }
+
+    pub async fn start(self: &Arc<Self>) -> Result<(), StoreError> {
+        let handler: Arc<dyn DLedgerProtocolHandler> = self.clone();
+        self.rpc_service
+            .start(handler, self.shutdown_tx.subscribe())
+            .await?;
+        self.leader_elector.start(self.shutdown_tx.subscribe());
+
+        let store = self.store.clone();
+        let flush_interval = Duration::from_millis(self.config.flush_file_interval_ms.max(1));
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(flush_interval) => store.flush(),
+                    _ = shutdown_rx.changed() => break,
+                }
+            }
+        });
+        info!(
+            "dledger server {} of group {} started",
+            self.member_state.self_id(),
+            self.member_state.group()
+        );
+        Ok(())
+    }
+
+    pub fn shutdown(&self) {
+        if self.shutdown_tx.send_replace(true) {
+            return;
+        }
+        self.store.flush();
+        info!(
+            "dledger server {} of group {} shutdown",
+            self.member_state.self_id(),
+            self.member_state.group()
+        );
+    }
+
+    /// Appends `body` to the replicated log, returning once a quorum of the group stored it.
+    pub async fn append(&self, body: Bytes) -> Result<DLedgerEntry, DLedgerResponseCode> {
+        self.entry_pusher.append_as_leader(body).await
+    }
+
+    pub fn add_role_change_handler(&self, handler: RoleChangeHandler) {
+        self.leader_elector.add_role_change_handler(handler);
+    }
+
+    pub fn get_entry(&self, index: i64) -> Option<DLedgerEntry> {
+        self.store.get(index)
+    }
+
+    pub fn get_committed_index(&self) -> i64 {
+        self.store.get_committed_index()
+    }
+
+    pub fn get_ledger_end_index(&self) -> i64 {
+        self.store.get_ledger_end_index()
+    }
+
+    /// Receives the committed index every time it moves forward.
+    pub fn subscribe_committed_index(&self) -> watch::Receiver<i64> {
+        self.entry_pusher.subscribe_committed_index()
+    }
+
+    pub fn get_member_state(&self) -> &Arc<MemberState> {
+        &self.member_state
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.member_state.is_leader()
+    }
+
+    pub fn get_config(&self) -> &Arc<DLedgerConfig> {
+        &self.config
+    }
+}
+
+impl DLedgerProtocolHandler for DLedgerServer {
+    fn handle_vote(&self, request: VoteRequest) -> VoteResponse {
+        self.leader_elector.handle_vote(request)
+    }
+
+    fn handle_heartbeat(&self, request: HeartBeatRequest) -> HeartBeatResponse {
+        self.leader_elector.handle_heartbeat(request)
+    }
+
+    fn handle_push(&self, request: PushEntryRequest) -> PushEntryResponse {
+        if let Err(code) =
+            self.leader_elector
+                .accept_leader(&request.group, request.term, &request.leader_id)
+        {
+            return PushEntryResponse {
+                term: self.member_state.current_term(),
+                code,
+                ledger_end_index: self.store.get_ledger_end_index(),
+            };
+        }
+        self.entry_pusher.handle_push(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+    use crate::dledger::member_state::DLedgerRole;
+
+    fn free_port() -> u16 {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    }
+
+    async fn start_group(
+        dir: &tempfile::TempDir,
+        peers: &str,
+        ids: &[&str],
+    ) -> Vec<Arc<DLedgerServer>> {
+        let mut servers = Vec::new();
+        for id in ids {
+            let server = Arc::new(DLedgerServer::new(DLedgerConfig {
+                group: "test".to_string(),
+                self_id: id.to_string(),
+                peers: peers.to_string(),
+                store_base_dir: dir.path().to_string_lossy().to_string(),
+                mapped_file_size_for_entry_data: 64 * 1024,
+                mapped_file_size_for_entry_index: 32 * 1024,
+                heartbeat_interval_ms: 100,
+                min_vote_interval_ms: 150,
+                max_vote_interval_ms: 400,
+                rpc_timeout_ms: 300,
+                max_wait_ack_time_ms: 2000,
+                ..Default::default()
+            }));
+            assert!(server.load());
+            server.start().await.unwrap();
+            servers.push(server);
+        }
+        servers
+    }
+
+    async fn wait_for_leader(servers: &[Arc<DLedgerServer>], min_term: i64) -> Arc<DLedgerServer> {
+        let deadline = Instant::now() + Duration::from_secs(20);
+        loop {
+            let leaders: Vec<_> = servers
+                .iter()
+                .filter(|server| server.is_leader())
+                .filter(|server| server.member_state.current_term() >= min_term)
+                .collect();
+            let followers = servers
+                .iter()
+                .filter(|server| server.member_state.role() == DLedgerRole::Follower)
+                .count();
+            if leaders.len() == 1 && followers == servers.len() - 1 {
+                return leaders[0].clone();
+            }
+            assert!(Instant::now() < deadline, "no leader elected");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    async fn wait_for_committed(server: &DLedgerServer, index: i64) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while server.get_committed_index() < index {
+            assert!(
+                Instant::now() < deadline,
+                "entry {} is not committed",
+                index
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn three_members_elect_a_leader_and_survive_losing_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let peers = format!(
+            "n0-127.0.0.1:{};n1-127.0.0.1:{};n2-127.0.0.1:{}",
+            free_port(),
+            free_port(),
+            free_port()
+        );
+        let servers = start_group(&dir, &peers, &["n0", "n1", "n2"]).await;
+
+        let leader = wait_for_leader(&servers, 1).await;
+        let first_term = leader.member_state.current_term();
+        let mut last_index = -1;
+        for i in 0..10 {
+            let entry = leader
+                .append(Bytes::from(format!("message-{i}")))
+                .await
+                .unwrap();
+            last_index = entry.index;
+        }
+        for server in servers.iter() {
+            wait_for_committed(server, last_index).await;
+            assert_eq!(
+                server.get_entry(last_index).unwrap().body,
+                Bytes::from_static(b"message-9")
+            );
+        }
+
+        leader.shutdown();
+        let survivors: Vec<_> = servers
+            .iter()
+            .filter(|server| !Arc::ptr_eq(server, &leader))
+            .cloned()
+            .collect();
+        let new_leader = wait_for_leader(&survivors, first_term + 1).await;
+        let entry = new_leader
+            .append(Bytes::from_static(b"after failover"))
+            .await
+            .unwrap();
+        assert!(entry.index > last_index);
+        for server in survivors.iter() {
+            wait_for_committed(server, entry.index).await;
+            assert_eq!(
+                server.get_entry(entry.index).unwrap().body,
+                Bytes::from_static(b"after failover")
+            );
+            assert_eq!(
+                server.get_entry(last_index).unwrap().body,
+                Bytes::from_static(b"message-9")
+            );
+        }
+        for server in survivors.iter() {
+            server.shutdown();
+        }
+    }
+
+    #[tokio::test]
+    async fn followers_reject_appends() {
+        let dir = tempfile::tempdir().unwrap();
+        let peers = format!("n0-127.0.0.1:{};n1-127.0.0.1:{}", free_port(), free_port());
+        let servers = start_group(&dir, &peers, &["n0"]).await;
+        // the only reachable member can never win a majority of two
+        tokio::time::sleep(Duration::from_millis(600)).await;
+        assert!(!servers[0].is_leader());
+        assert_eq!(
+            servers[0]
+                .append(Bytes::from_static(b"lost"))
+                .await
+                .unwrap_err(),
+            DLedgerResponseCode::NotLeader
+        );
+        servers[0].shutdown();
+    }
+}
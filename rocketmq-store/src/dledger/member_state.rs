@@ -0,0 +1,285 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::info;
+
+use crate::dledger::dledger_config::DLedgerConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DLedgerRole {
+    Candidate,
+    Leader,
+    Follower,
+}
+
+impl fmt::Display for DLedgerRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DLedgerRole::Candidate => write!(f, "CANDIDATE"),
+            DLedgerRole::Leader => write!(f, "LEADER"),
+            DLedgerRole::Follower => write!(f, "FOLLOWER"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MemberStateInner {
+    role: DLedgerRole,
+    current_term: i64,
+    voted_for: Option<String>,
+    leader_id: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemberStateSerializeWrapper {
+    curr_term: i64,
+    vote_leader: Option<String>,
+}
+
+/// Role, term and vote of the local member. The term and the vote are persisted, a member must
+/// never vote twice in the same term across restarts.
+pub struct MemberState {
+    config: Arc<DLedgerConfig>,
+    peer_map: BTreeMap<String, String>,
+    inner: Mutex<MemberStateInner>,
+}
+
+impl MemberState {
+    pub fn new(config: Arc<DLedgerConfig>) -> Self {
+        let peer_map = config.parse_peers();
+        Self {
+            config,
+            peer_map,
+            inner: Mutex::new(MemberStateInner {
+                role: DLedgerRole::Candidate,
+                current_term: 0,
+                voted_for: None,
+                leader_id: None,
+            }),
+        }
+    }
+
+    #[inline]
+    pub fn self_id(&self) -> &str {
+        self.config.self_id.as_str()
+    }
+
+    #[inline]
+    pub fn group(&self) -> &str {
+        self.config.group.as_str()
+    }
+
+    pub fn self_addr(&self) -> Option<&String> {
+        self.peer_map.get(&self.config.self_id)
+    }
+
+    pub fn peer_map(&self) -> &BTreeMap<String, String> {
+        &self.peer_map
+    }
+
+    /// Ids of every member except the local one.
+    pub fn remote_peer_ids(&self) -> Vec<String> {
+        self.peer_map
+            .keys()
+            .filter(|id| id.as_str() != self.self_id())
+            .cloned()
+            .collect()
+    }
+
+    pub fn is_quorum(&self, num: usize) -> bool {
+        num > self.peer_map.len() / 2
+    }
+
+    pub fn role(&self) -> DLedgerRole {
+        self.inner.lock().role
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.role() == DLedgerRole::Leader
+    }
+
+    pub fn current_term(&self) -> i64 {
+        self.inner.lock().current_term
+    }
+
+    pub fn leader_id(&self) -> Option<String> {
+        self.inner.lock().leader_id.clone()
+    }
+
+    pub fn voted_for(&self) -> Option<String> {
+        self.inner.lock().voted_for.clone()
+    }
+
+    /// Role, term and leader read together.
+    pub fn snapshot(&self) -> (DLedgerRole, i64, Option<String>) {
+        let inner = self.inner.lock();
+        (inner.role, inner.current_term, inner.leader_id.clone())
+    }
+
+    /// Starts a new election: moves to the next term and votes for the local member.
+    pub fn next_term(&self) -> i64 {
+        let mut inner = self.inner.lock();
+        inner.current_term += 1;
+        inner.role = DLedgerRole::Candidate;
+        inner.voted_for = Some(self.config.self_id.clone());
+        inner.leader_id = None;
+        let term = inner.current_term;
+        drop(inner);
+        self.persist();
+        term
+    }
+
+    /// Grants the vote of `term` to `candidate`, returns false if it was given to someone else.
+    pub fn try_vote(&self, term: i64, candidate: &str) -> bool {
+        let mut inner = self.inner.lock();
+        if inner.current_term != term {
+            return false;
+        }
+        match inner.voted_for.as_deref() {
+            Some(voted_for) if voted_for != candidate => return false,
+            Some(_) => return true,
+            None => inner.voted_for = Some(candidate.to_string()),
+        }
+        drop(inner);
+        self.persist();
+        true
+    }
+
+    pub fn change_to_leader(&self, term: i64) -> bool {
+        let mut inner = self.inner.lock();
+        if inner.current_term != term || inner.role != DLedgerRole::Candidate {
+            return false;
+        }
+        inner.role = DLedgerRole::Leader;
+        inner.leader_id = Some(self.config.self_id.clone());
+        info!(
+            "[{}] change to leader of term {}",
+            self.config.self_id, term
+        );
+        true
+    }
+
+    pub fn change_to_follower(&self, term: i64, leader_id: &str) {
+        let mut inner = self.inner.lock();
+        let term_changed = Self::update_term(&mut inner, term);
+        inner.role = DLedgerRole::Follower;
+        inner.leader_id = Some(leader_id.to_string());
+        drop(inner);
+        if term_changed {
+            self.persist();
+        }
+        info!(
+            "[{}] change to follower of {} in term {}",
+            self.config.self_id, leader_id, term
+        );
+    }
+
+    /// Falls back to candidate, adopting `term` if it is larger than the current one.
+    pub fn change_to_candidate(&self, term: i64) {
+        let mut inner = self.inner.lock();
+        let term_changed = Self::update_term(&mut inner, term);
+        inner.role = DLedgerRole::Candidate;
+        inner.leader_id = None;
+        drop(inner);
+        if term_changed {
+            self.persist();
+        }
+    }
+
+    fn update_term(inner: &mut MemberStateInner, term: i64) -> bool {
+        if term > inner.current_term {
+            inner.current_term = term;
+            inner.voted_for = None;
+            return true;
+        }
+        false
+    }
+}
+
+impl ConfigManager for MemberState {
+    fn config_file_path(&self) -> String {
+        self.config.get_member_state_path()
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let inner = self.inner.lock();
+        let wrapper = MemberStateSerializeWrapper {
+            curr_term: inner.current_term,
+            vote_leader: inner.voted_for.clone(),
+        };
+        drop(inner);
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&wrapper).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&wrapper).expect("encode failed")
+        }
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        info!("decode MemberState from json string:{}", json_string);
+        let wrapper: MemberStateSerializeWrapper =
+            SerdeJsonUtils::from_json_str(json_string).expect("decode failed");
+        let mut inner = self.inner.lock();
+        inner.current_term = wrapper.curr_term;
+        inner.voted_for = wrapper.vote_leader;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn term_and_vote_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Arc::new(DLedgerConfig {
+            self_id: "n0".to_string(),
+            peers: "n0-127.0.0.1:1;n1-127.0.0.1:2;n2-127.0.0.1:3".to_string(),
+            store_base_dir: dir.path().to_string_lossy().to_string(),
+            ..Default::default()
+        });
+        let member_state = MemberState::new(config.clone());
+        assert_eq!(member_state.remote_peer_ids(), vec!["n1", "n2"]);
+        assert!(member_state.is_quorum(2));
+        assert!(!member_state.is_quorum(1));
+
+        member_state.change_to_candidate(4);
+        assert!(member_state.try_vote(4, "n1"));
+        assert!(!member_state.try_vote(4, "n2"));
+
+        let restarted = MemberState::new(config);
+        restarted.load();
+        assert_eq!(restarted.current_term(), 4);
+        assert_eq!(restarted.voted_for().as_deref(), Some("n1"));
+        assert_eq!(restarted.next_term(), 5);
+        assert!(restarted.change_to_leader(5));
+        assert!(restarted.is_leader());
+    }
+}
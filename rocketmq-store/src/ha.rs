@@ -15,8 +15,13 @@
  * limitations under the License.
  */
 
+pub(crate) mod default_ha_client;
+pub(crate) mod default_ha_connection;
 pub(crate) mod default_ha_service;
+pub mod epoch_file_cache;
+pub(crate) mod flow_monitor;
 pub(crate) mod general_ha_service;
+pub(crate) mod group_transfer_service;
 pub(crate) mod ha_client;
 pub(crate) mod ha_connection;
 pub(crate) mod ha_connection_state;
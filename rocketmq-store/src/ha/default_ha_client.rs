@@ -0,0 +1,426 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::time::Duration;
+
+use bytes::Buf;
+use bytes::BytesMut;
+use parking_lot::RwLock;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::tls::tls_helper::TlsClient;
+use rocketmq_remoting::tls::transport_stream::TransportStream;
+use rocketmq_rust::ArcMut;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::WriteHalf;
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio::sync::Notify;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::base::message_store::MessageStore;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::ha::default_ha_connection::TRANSFER_HEADER_SIZE;
+use crate::ha::flow_monitor::FlowMonitor;
+use crate::ha::ha_client::HAClient;
+use crate::ha::ha_connection_state::HAConnectionState;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+use crate::store_error::HAError;
+use crate::store_error::HAResult;
+
+const READ_MAX_BUFFER_SIZE: usize = 1024 * 1024 * 4;
+
+const CONNECT_TIMEOUT_MILLIS: u64 = 3000;
+
+const RECONNECT_INTERVAL_MILLIS: u64 = 5000;
+
+/// The slave side of the replication: connects to the master HA address, appends the pushed
+/// commit log data and reports the max offset it has stored.
+pub struct DefaultHAClient {
+    this: Weak<DefaultHAClient>,
+    message_store: ArcMut<LocalFileMessageStore>,
+    message_store_config: Arc<MessageStoreConfig>,
+    master_ha_address: RwLock<Option<String>>,
+    master_address: RwLock<Option<String>>,
+    current_reported_offset: AtomicI64,
+    last_read_timestamp: AtomicI64,
+    last_write_timestamp: AtomicI64,
+    current_state: RwLock<HAConnectionState>,
+    flow_monitor: FlowMonitor,
+    /// Connects to the master with TLS when `ha_tls_config` enables it
+    tls_client: Option<TlsClient>,
+    /// Bumped whenever the current master connection must be dropped
+    master_generation: AtomicU64,
+    wakeup: Notify,
+    started: AtomicBool,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+impl DefaultHAClient {
+    pub(crate) fn new(message_store: ArcMut<LocalFileMessageStore>) -> HAResult<Arc<Self>> {
+        let message_store_config = message_store.message_store_config();
+        let tls_config = &message_store_config.ha_tls_config;
+        let tls_client = if tls_config.tls_enable {
+            Some(TlsClient::new(tls_config).map_err(|e| HAError::Service(e.to_string()))?)
+        } else {
+            None
+        };
+        Ok(Arc::new_cyclic(|this| Self {
+            this: this.clone(),
+            message_store,
+            flow_monitor: FlowMonitor::new(message_store_config.clone()),
+            tls_client,
+            message_store_config,
+            master_ha_address: RwLock::new(None),
+            master_address: RwLock::new(None),
+            current_reported_offset: AtomicI64::new(0),
+            last_read_timestamp: AtomicI64::new(get_current_millis() as i64),
+            last_write_timestamp: AtomicI64::new(get_current_millis() as i64),
+            current_state: RwLock::new(HAConnectionState::Ready),
+            master_generation: AtomicU64::new(0),
+            wakeup: Notify::new(),
+            started: AtomicBool::new(false),
+            shutdown_tx: watch::channel(false).0,
+        }))
+    }
+
+    pub(crate) fn start_service(&self) {
+        if self.started.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Some(this) = self.this.upgrade() {
+            tokio::spawn(this.run());
+        }
+    }
+
+    pub(crate) fn shutdown_service(&self) {
+        *self.current_state.write() = HAConnectionState::Shutdown;
+        let _ = self.shutdown_tx.send(true);
+        self.close_master_connection();
+    }
+
+    pub(crate) fn wakeup_service(&self) {
+        self.wakeup.notify_one();
+    }
+
+    pub(crate) fn set_master_ha_address(&self, new_address: &str) {
+        let mut master_ha_address = self.master_ha_address.write();
+        if master_ha_address.as_deref() != Some(new_address) {
+            info!(
+                "update master ha address, OLD: {:?} NEW: {}",
+                master_ha_address, new_address
+            );
+            *master_ha_address = Some(new_address.to_string());
+            drop(master_ha_address);
+            self.close_master_connection();
+            self.wakeup_service();
+        }
+    }
+
+    pub(crate) fn set_master_address(&self, new_address: &str) {
+        let mut master_address = self.master_address.write();
+        if master_address.as_deref() != Some(new_address) {
+            info!(
+                "update master address, OLD: {:?} NEW: {}",
+                master_address, new_address
+            );
+            *master_address = Some(new_address.to_string());
+        }
+    }
+
+    fn close_master_connection(&self) {
+        self.master_generation.fetch_add(1, Ordering::AcqRel);
+        self.wakeup_service();
+    }
+
+    async fn run(self: Arc<Self>) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        info!("HAClient service started");
+        while !*shutdown_rx.borrow() {
+            let master_ha_address = self.master_ha_address.read().clone();
+            let stream = match master_ha_address {
+                Some(address) => match tokio::time::timeout(
+                    Duration::from_millis(CONNECT_TIMEOUT_MILLIS),
+                    self.connect(address.as_str()),
+                )
+                .await
+                {
+                    Ok(Ok(stream)) => {
+                        info!("HAClient connect to master {}", address);
+                        Some(stream)
+                    }
+                    Ok(Err(e)) => {
+                        warn!("HAClient connect to master {} failed: {}", address, e);
+                        None
+                    }
+                    Err(_) => {
+                        warn!("HAClient connect to master {} timeout", address);
+                        None
+                    }
+                },
+                None => None,
+            };
+            match stream {
+                Some(stream) => {
+                    self.transfer(stream, &mut shutdown_rx).await;
+                    if *self.current_state.read() != HAConnectionState::Shutdown {
+                        *self.current_state.write() = HAConnectionState::Ready;
+                    }
+                }
+                None => {
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => {}
+                        _ = self.wakeup.notified() => {}
+                        _ = tokio::time::sleep(Duration::from_millis(RECONNECT_INTERVAL_MILLIS)) => {}
+                    }
+                }
+            }
+        }
+        info!("HAClient service end");
+    }
+
+    async fn connect(&self, address: &str) -> std::io::Result<TransportStream> {
+        let stream = TcpStream::connect(address).await?;
+        match self.tls_client.as_ref() {
+            Some(tls_client) => tls_client.connect(stream).await,
+            None => Ok(stream.into()),
+        }
+    }
+
+    /// Keeps exchanging data with the master until the connection has to be closed.
+    async fn transfer(&self, stream: TransportStream, shutdown_rx: &mut watch::Receiver<bool>) {
+        let generation = self.master_generation.load(Ordering::Acquire);
+        let _ = stream.set_nodelay(true);
+        let (mut reader, mut writer) = tokio::io::split(stream);
+        self.current_reported_offset
+            .store(self.message_store.get_max_phy_offset(), Ordering::Release);
+        self.last_read_timestamp
+            .store(get_current_millis() as i64, Ordering::Release);
+        *self.current_state.write() = HAConnectionState::Transfer;
+        if !self
+            .report_slave_max_offset(
+                &mut writer,
+                self.current_reported_offset.load(Ordering::Acquire),
+            )
+            .await
+        {
+            return;
+        }
+
+        let heartbeat_interval = self.message_store_config.ha_send_heartbeat_interval as i64;
+        let housekeeping_interval = self.message_store_config.ha_housekeeping_interval as i64;
+        let mut buffer = BytesMut::with_capacity(READ_MAX_BUFFER_SIZE);
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                _ = self.wakeup.notified() => {}
+                _ = tokio::time::sleep(Duration::from_millis(1000)) => {}
+                read = reader.read_buf(&mut buffer) => {
+                    match read {
+                        Ok(0) => {
+                            warn!("HAClient, master closed the connection");
+                            break;
+                        }
+                        Ok(size) => {
+                            self.flow_monitor.add_byte_count_transferred(size as i64);
+                            self.last_read_timestamp
+                                .store(get_current_millis() as i64, Ordering::Release);
+                            if !self.dispatch_read_request(&mut buffer).await {
+                                break;
+                            }
+                            if !self.report_slave_max_offset_plus(&mut writer).await {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            warn!("HAClient, read from master failed: {}", e);
+                            break;
+                        }
+                    }
+                }
+            }
+            if generation != self.master_generation.load(Ordering::Acquire) {
+                break;
+            }
+            let now = get_current_millis() as i64;
+            if now - self.last_write_timestamp.load(Ordering::Acquire) >= heartbeat_interval
+                && !self
+                    .report_slave_max_offset(
+                        &mut writer,
+                        self.current_reported_offset.load(Ordering::Acquire),
+                    )
+                    .await
+            {
+                break;
+            }
+            if now - self.last_read_timestamp.load(Ordering::Acquire) > housekeeping_interval {
+                warn!(
+                    "AutoRecoverHAClient, housekeeping, found this connection[{:?}] expired, {}",
+                    self.master_ha_address.read(),
+                    now - self.last_read_timestamp.load(Ordering::Acquire)
+                );
+                break;
+            }
+        }
+        info!(
+            "HAClient close the connection to master {:?}",
+            self.master_ha_address.read()
+        );
+    }
+
+    /// Appends every complete packet in `buffer` to the commit log, leaving a partial packet
+    /// for the next read.
+    async fn dispatch_read_request(&self, buffer: &mut BytesMut) -> bool {
+        while buffer.len() >= TRANSFER_HEADER_SIZE {
+            let master_phy_offset = (&buffer[0..8]).get_i64();
+            let body_size = (&buffer[8..TRANSFER_HEADER_SIZE]).get_i32() as usize;
+            let slave_phy_offset = self.message_store.get_max_phy_offset();
+            if slave_phy_offset != 0 && slave_phy_offset != master_phy_offset {
+                error!(
+                    "master pushed offset not equal the max phy offset in slave, SLAVE: {} \
+                     MASTER: {}",
+                    slave_phy_offset, master_phy_offset
+                );
+                return false;
+            }
+            if buffer.len() < TRANSFER_HEADER_SIZE + body_size {
+                break;
+            }
+            if body_size > 0 {
+                let body = &buffer[TRANSFER_HEADER_SIZE..TRANSFER_HEADER_SIZE + body_size];
+                match self
+                    .message_store
+                    .append_to_commit_log(master_phy_offset, body, 0, body_size as i32)
+                    .await
+                {
+                    Ok(true) => {}
+                    Ok(false) => return false,
+                    Err(e) => {
+                        error!("HAClient append to commit log failed: {:?}", e);
+                        return false;
+                    }
+                }
+            }
+            buffer.advance(TRANSFER_HEADER_SIZE + body_size);
+        }
+        true
+    }
+
+    async fn report_slave_max_offset_plus(&self, writer: &mut WriteHalf<TransportStream>) -> bool {
+        let current_phy_offset = self.message_store.get_max_phy_offset();
+        if current_phy_offset > self.current_reported_offset.load(Ordering::Acquire) {
+            self.current_reported_offset
+                .store(current_phy_offset, Ordering::Release);
+            if !self
+                .report_slave_max_offset(writer, current_phy_offset)
+                .await
+            {
+                error!(
+                    "HAClient, reportSlaveMaxOffset error, {}",
+                    current_phy_offset
+                );
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn report_slave_max_offset(
+        &self,
+        writer: &mut WriteHalf<TransportStream>,
+        max_offset: i64,
+    ) -> bool {
+        match writer.write_all(&max_offset.to_be_bytes()).await {
+            Ok(_) => {
+                self.last_write_timestamp
+                    .store(get_current_millis() as i64, Ordering::Release);
+                true
+            }
+            Err(e) => {
+                warn!("HAClient, report slave max offset failed: {}", e);
+                false
+            }
+        }
+    }
+
+    pub(crate) fn get_current_reported_offset(&self) -> i64 {
+        self.current_reported_offset.load(Ordering::Acquire)
+    }
+}
+
+impl HAClient for DefaultHAClient {
+    async fn start(&self) {
+        self.start_service();
+    }
+
+    async fn shutdown(&self) {
+        self.shutdown_service();
+    }
+
+    async fn wakeup(&self) {
+        self.wakeup_service();
+    }
+
+    async fn update_master_address(&self, new_address: &str) {
+        self.set_master_address(new_address);
+    }
+
+    async fn update_ha_master_address(&self, new_address: &str) {
+        self.set_master_ha_address(new_address);
+    }
+
+    fn get_master_address(&self) -> String {
+        self.master_address.read().clone().unwrap_or_default()
+    }
+
+    fn get_ha_master_address(&self) -> String {
+        self.master_ha_address.read().clone().unwrap_or_default()
+    }
+
+    fn get_last_read_timestamp(&self) -> i64 {
+        self.last_read_timestamp.load(Ordering::Acquire)
+    }
+
+    fn get_last_write_timestamp(&self) -> i64 {
+        self.last_write_timestamp.load(Ordering::Acquire)
+    }
+
+    fn get_current_state(&self) -> HAConnectionState {
+        *self.current_state.read()
+    }
+
+    fn change_current_state(&self, ha_connection_state: HAConnectionState) {
+        info!("change state to {}", ha_connection_state);
+        *self.current_state.write() = ha_connection_state;
+    }
+
+    async fn close_master(&self) {
+        self.close_master_connection();
+    }
+
+    fn get_transferred_byte_in_second(&self) -> i64 {
+        self.flow_monitor.get_transferred_byte_in_second()
+    }
+}
@@ -0,0 +1,300 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::time::Duration;
+
+use bytes::BufMut;
+use bytes::BytesMut;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::tls::transport_stream::TransportStream;
+use rocketmq_rust::ArcMut;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::ReadHalf;
+use tokio::io::WriteHalf;
+use tokio::sync::watch;
+use tracing::info;
+use tracing::warn;
+
+use crate::base::message_store::MessageStore;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::ha::default_ha_service::DefaultHAService;
+use crate::ha::flow_monitor::FlowMonitor;
+use crate::ha::ha_connection::HAConnection;
+use crate::ha::ha_connection_state::HAConnectionState;
+use crate::ha::ha_service::HAService;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+
+/// Header of a transfer packet pushed by the master: physical offset(8) + body size(4).
+pub(crate) const TRANSFER_HEADER_SIZE: usize = 8 + 4;
+
+/// Size of the offset report sent back by the slave.
+pub(crate) const REPORT_HEADER_SIZE: usize = 8;
+
+/// The master side of a replication connection, one per connected slave.
+///
+/// A read task receives the max offset the slave has stored, a write task pushes the commit log
+/// data starting from the offset the slave requested first.
+pub struct DefaultHAConnection {
+    this: Weak<DefaultHAConnection>,
+    ha_service: DefaultHAService,
+    message_store: ArcMut<LocalFileMessageStore>,
+    message_store_config: Arc<MessageStoreConfig>,
+    client_address: SocketAddr,
+    socket: Mutex<Option<TransportStream>>,
+    current_state: RwLock<HAConnectionState>,
+    slave_request_offset: AtomicI64,
+    slave_ack_offset: AtomicI64,
+    transfer_from_where: AtomicI64,
+    flow_monitor: FlowMonitor,
+    stopped: AtomicBool,
+    shutdown_tx: watch::Sender<bool>,
+}
+
+impl DefaultHAConnection {
+    pub(crate) fn new(
+        ha_service: DefaultHAService,
+        message_store: ArcMut<LocalFileMessageStore>,
+        socket: TransportStream,
+        client_address: SocketAddr,
+    ) -> Arc<Self> {
+        let message_store_config = message_store.message_store_config();
+        let _ = socket.set_nodelay(true);
+        Arc::new_cyclic(|this| Self {
+            this: this.clone(),
+            ha_service,
+            message_store,
+            flow_monitor: FlowMonitor::new(message_store_config.clone()),
+            message_store_config,
+            client_address,
+            socket: Mutex::new(Some(socket)),
+            current_state: RwLock::new(HAConnectionState::Transfer),
+            slave_request_offset: AtomicI64::new(-1),
+            slave_ack_offset: AtomicI64::new(-1),
+            transfer_from_where: AtomicI64::new(-1),
+            stopped: AtomicBool::new(false),
+            shutdown_tx: watch::channel(false).0,
+        })
+    }
+
+    fn process_slave_ack(&self, offset: i64) {
+        self.slave_ack_offset.store(offset, Ordering::Release);
+        if self.slave_request_offset.load(Ordering::Acquire) < 0 {
+            self.slave_request_offset.store(offset, Ordering::Release);
+            info!("slave[{}] request offset {}", self.client_address, offset);
+        }
+        self.ha_service.notify_transfer_some(offset);
+    }
+
+    async fn read_loop(self: Arc<Self>, mut reader: ReadHalf<TransportStream>) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let housekeeping_interval =
+            Duration::from_millis(self.message_store_config.ha_housekeeping_interval as u64);
+        let mut report = [0u8; REPORT_HEADER_SIZE];
+        while !self.stopped.load(Ordering::Acquire) {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                result = tokio::time::timeout(housekeeping_interval, reader.read_exact(&mut report)) => {
+                    match result {
+                        Ok(Ok(_)) => self.process_slave_ack(i64::from_be_bytes(report)),
+                        Ok(Err(e)) => {
+                            warn!("ha connection {} read failed: {}", self.client_address, e);
+                            break;
+                        }
+                        Err(_) => {
+                            warn!(
+                                "ha housekeeping, found this connection[{}] expired, {:?}",
+                                self.client_address, housekeeping_interval
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        info!("{} read service end", self.client_address);
+        self.close();
+    }
+
+    async fn write_loop(self: Arc<Self>, mut writer: WriteHalf<TransportStream>) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let heartbeat_interval = self.message_store_config.ha_send_heartbeat_interval as u64;
+        let mut next_transfer_from_where = -1i64;
+        let mut last_write_timestamp = get_current_millis();
+        while !self.stopped.load(Ordering::Acquire) {
+            let slave_request_offset = self.slave_request_offset.load(Ordering::Acquire);
+            if slave_request_offset == -1 {
+                tokio::select! {
+                    _ = shutdown_rx.changed() => break,
+                    _ = tokio::time::sleep(Duration::from_millis(10)) => continue,
+                }
+            }
+
+            if next_transfer_from_where == -1 {
+                next_transfer_from_where = if slave_request_offset == 0 {
+                    let master_offset = self.message_store.get_max_phy_offset();
+                    let master_offset = master_offset
+                        - master_offset
+                            % self.message_store_config.mapped_file_size_commit_log as i64;
+                    master_offset.max(0)
+                } else {
+                    slave_request_offset
+                };
+                info!(
+                    "master transfer data from {} to slave[{}], and slave request {}",
+                    next_transfer_from_where, self.client_address, slave_request_offset
+                );
+            }
+
+            let now = get_current_millis();
+            if now - last_write_timestamp > heartbeat_interval {
+                // Build heartbeat packet
+                if let Err(e) =
+                    Self::transfer_data(&mut writer, next_transfer_from_where, &[]).await
+                {
+                    warn!(
+                        "ha connection {} heartbeat failed: {}",
+                        self.client_address, e
+                    );
+                    break;
+                }
+                last_write_timestamp = now;
+            }
+
+            let data = self
+                .message_store
+                .get_commit_log_data(next_transfer_from_where)
+                .and_then(|mut result| {
+                    let size = result
+                        .size
+                        .min(self.message_store_config.ha_transfer_batch_size as i32)
+                        .min(self.flow_monitor.can_transfer_max_byte_num());
+                    let body = result
+                        .get_bytes_ref()
+                        .filter(|_| size > 0)
+                        .map(|bytes| bytes[..size as usize].to_vec());
+                    result.release();
+                    body
+                });
+            match data {
+                Some(body) => {
+                    let this_offset = next_transfer_from_where;
+                    next_transfer_from_where += body.len() as i64;
+                    self.transfer_from_where
+                        .store(next_transfer_from_where, Ordering::Release);
+                    if let Err(e) = Self::transfer_data(&mut writer, this_offset, &body).await {
+                        warn!(
+                            "ha connection {} transfer failed: {}",
+                            self.client_address, e
+                        );
+                        break;
+                    }
+                    self.flow_monitor
+                        .add_byte_count_transferred(body.len() as i64);
+                    last_write_timestamp = get_current_millis();
+                }
+                None => {
+                    let wait_notify_object = self.ha_service.get_wait_notify_object();
+                    tokio::select! {
+                        _ = shutdown_rx.changed() => break,
+                        _ = wait_notify_object.wait_for_running(100) => {}
+                    }
+                }
+            }
+        }
+        info!("{} write service end", self.client_address);
+        self.close();
+    }
+
+    async fn transfer_data(
+        writer: &mut WriteHalf<TransportStream>,
+        offset: i64,
+        body: &[u8],
+    ) -> std::io::Result<()> {
+        let mut packet = BytesMut::with_capacity(TRANSFER_HEADER_SIZE + body.len());
+        packet.put_i64(offset);
+        packet.put_i32(body.len() as i32);
+        packet.put_slice(body);
+        writer.write_all(&packet).await
+    }
+
+    fn start_service(&self) {
+        let Some(socket) = self.socket.lock().take() else {
+            return;
+        };
+        let Some(this) = self.this.upgrade() else {
+            return;
+        };
+        let (reader, writer) = tokio::io::split(socket);
+        tokio::spawn(this.clone().read_loop(reader));
+        tokio::spawn(this.write_loop(writer));
+    }
+
+    pub(crate) fn close(&self) {
+        if self.stopped.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        *self.current_state.write() = HAConnectionState::Shutdown;
+        let _ = self.shutdown_tx.send(true);
+        if let Some(this) = self.this.upgrade() {
+            self.ha_service.remove_connection(&this);
+        }
+        info!("ha connection {} closed", self.client_address);
+    }
+}
+
+impl HAConnection for DefaultHAConnection {
+    async fn start(&self) {
+        self.start_service();
+    }
+
+    async fn shutdown(&self) {
+        self.close();
+    }
+
+    fn close(&self) {
+        DefaultHAConnection::close(self);
+    }
+
+    fn get_current_state(&self) -> HAConnectionState {
+        *self.current_state.read()
+    }
+
+    fn get_client_address(&self) -> SocketAddr {
+        self.client_address
+    }
+
+    fn get_transferred_byte_in_second(&self) -> i64 {
+        self.flow_monitor.get_transferred_byte_in_second()
+    }
+
+    fn get_transfer_from_where(&self) -> i64 {
+        self.transfer_from_where.load(Ordering::Acquire)
+    }
+
+    fn get_slave_ack_offset(&self) -> i64 {
+        self.slave_ack_offset.load(Ordering::Acquire)
+    }
+}
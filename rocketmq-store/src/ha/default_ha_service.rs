@@ -14,63 +14,309 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-/*
- * Licensed to the Apache Software Foundation (ASF) under one or more
- * contributor license agreements.  See the NOTICE file distributed with
- * this work for additional information regarding copyright ownership.
- * The ASF licenses this file to You under the Apache License, Version 2.0
- * (the "License"); you may not use this file except in compliance with
- * the License.  You may obtain a copy of the License at
- *
- *     http://www.apache.org/licenses/LICENSE-2.0
- *
- * Unless required by applicable law or agreed to in writing, software
- * distributed under the License is distributed on an "AS IS" BASIS,
- * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
- * See the License for the specific language governing permissions and
- * limitations under the License.
- */
 
+use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use parking_lot::Mutex;
+use rocketmq_common::common::broker::broker_role::BrokerRole;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::TimeUtils::get_current_nano;
+use rocketmq_remoting::protocol::body::ha_client_runtime_info::HAClientRuntimeInfo;
+use rocketmq_remoting::protocol::body::ha_connection_runtime_info::HAConnectionRuntimeInfo;
 use rocketmq_remoting::protocol::body::ha_runtime_info::HARuntimeInfo;
+use rocketmq_remoting::tls::tls_helper::TlsServer;
 use rocketmq_rust::ArcMut;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
 use tracing::error;
+use tracing::info;
+use tracing::warn;
 
+use crate::base::message_status_enum::PutMessageStatus;
 use crate::base::message_store::MessageStore;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::ha::default_ha_client::DefaultHAClient;
+use crate::ha::default_ha_connection::DefaultHAConnection;
+use crate::ha::epoch_file_cache::EpochFileCache;
+use crate::ha::group_transfer_service::GroupTransferService;
 use crate::ha::ha_client::HAClient;
 use crate::ha::ha_connection::HAConnection;
+use crate::ha::ha_connection_state::HAConnectionState;
 use crate::ha::ha_connection_state_notification_request::HAConnectionStateNotificationRequest;
 use crate::ha::ha_service::HAService;
 use crate::ha::wait_notify_object::WaitNotifyObject;
 use crate::log_file::flush_manager_impl::group_commit_request::GroupCommitRequest;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+use crate::store_error::HAError;
 use crate::store_error::HAResult;
 
-pub struct DefaultHAService;
+/// Master/slave replication: the master accepts slave connections and pushes the commit log,
+/// the slave runs a [`DefaultHAClient`] pulling from the master.
+///
+/// In controller mode the role is switched at runtime by `change_to_master`/`change_to_slave`,
+/// every new master epoch is recorded in the [`EpochFileCache`].
+#[derive(Clone)]
+pub struct DefaultHAService {
+    message_store_config: Arc<MessageStoreConfig>,
+    message_store: Option<ArcMut<LocalFileMessageStore>>,
+    connection_count: Arc<AtomicI32>,
+    connection_list: Arc<Mutex<Vec<Arc<DefaultHAConnection>>>>,
+    push_to_slave_max_offset: Arc<AtomicI64>,
+    wait_notify_object: Arc<WaitNotifyObject>,
+    group_transfer_service: Arc<GroupTransferService>,
+    ha_client: Arc<Mutex<Option<Arc<DefaultHAClient>>>>,
+    is_slave: Arc<AtomicBool>,
+    epoch_cache: Arc<EpochFileCache>,
+    local_address: Arc<Mutex<Option<SocketAddr>>>,
+    shutdown_tx: Arc<watch::Sender<bool>>,
+}
+
+impl DefaultHAService {
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        let is_slave = message_store_config.broker_role == BrokerRole::Slave;
+        let epoch_cache = EpochFileCache::new(message_store_config.get_store_path_epoch_file());
+        Self {
+            message_store_config,
+            message_store: None,
+            connection_count: Arc::new(AtomicI32::new(0)),
+            connection_list: Arc::new(Mutex::new(Vec::new())),
+            push_to_slave_max_offset: Arc::new(AtomicI64::new(0)),
+            wait_notify_object: Arc::new(WaitNotifyObject::default()),
+            group_transfer_service: Arc::new(GroupTransferService::default()),
+            ha_client: Arc::new(Mutex::new(None)),
+            is_slave: Arc::new(AtomicBool::new(is_slave)),
+            epoch_cache: Arc::new(epoch_cache),
+            local_address: Arc::new(Mutex::new(None)),
+            shutdown_tx: Arc::new(watch::channel(false).0),
+        }
+    }
+
+    /// Raises the max offset pushed to the slaves and wakes up the waiting producers.
+    pub(crate) fn notify_transfer_some(&self, offset: i64) {
+        let mut value = self.push_to_slave_max_offset.load(Ordering::Acquire);
+        while offset > value {
+            match self.push_to_slave_max_offset.compare_exchange(
+                value,
+                offset,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    self.group_transfer_service.notify_transfer_some();
+                    break;
+                }
+                Err(current) => value = current,
+            }
+        }
+    }
+
+    pub(crate) fn add_connection(&self, connection: Arc<DefaultHAConnection>) {
+        self.connection_list.lock().push(connection);
+        self.connection_count.fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub(crate) fn remove_connection(&self, connection: &Arc<DefaultHAConnection>) {
+        let mut connection_list = self.connection_list.lock();
+        let len = connection_list.len();
+        connection_list.retain(|item| !Arc::ptr_eq(item, connection));
+        if connection_list.len() < len {
+            self.connection_count.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    pub(crate) fn destroy_connections(&self) {
+        let connections = std::mem::take(&mut *self.connection_list.lock());
+        for connection in connections {
+            connection.close();
+        }
+    }
+
+    pub fn get_epoch_cache(&self) -> &Arc<EpochFileCache> {
+        &self.epoch_cache
+    }
+
+    /// Records `master_epoch` as starting at the current end of the commit log.
+    fn append_epoch_entry(&self, master_epoch: i32) -> bool {
+        if master_epoch <= self.epoch_cache.last_epoch() {
+            return true;
+        }
+        let start_offset = self
+            .message_store
+            .as_ref()
+            .map_or(0, |store| store.get_max_phy_offset());
+        self.epoch_cache.append_entry(master_epoch, start_offset)
+    }
+
+    /// The address the master listens on for slave connections, once started.
+    pub fn local_address(&self) -> Option<SocketAddr> {
+        *self.local_address.lock()
+    }
+
+    async fn accept_loop(self, listener: TcpListener, tls_server: TlsServer) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((socket, client_address)) => {
+                        info!("HAService receive new connection, {}", client_address);
+                        let Some(message_store) = self.message_store.clone() else {
+                            continue;
+                        };
+                        let service = self.clone();
+                        let tls_server = tls_server.clone();
+                        tokio::spawn(async move {
+                            let socket = match tls_server.accept(socket).await {
+                                Ok(socket) => socket,
+                                Err(e) => {
+                                    warn!("HAService TLS handshake with {} failed: {}", client_address, e);
+                                    return;
+                                }
+                            };
+                            let connection = DefaultHAConnection::new(
+                                service.clone(),
+                                message_store,
+                                socket,
+                                client_address,
+                            );
+                            service.add_connection(connection.clone());
+                            connection.start().await;
+                        });
+                    }
+                    Err(e) => error!("HAService accept connection failed: {}", e),
+                }
+            }
+        }
+        info!("AcceptSocketService service end");
+    }
+
+    async fn group_transfer_loop(self) {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let mut requests_read = Vec::new();
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() => break,
+                _ = self.group_transfer_service.wait_for_running(10) => {}
+            }
+            self.group_transfer_service
+                .swap_requests(&mut requests_read);
+            self.do_wait_transfer(&mut requests_read);
+        }
+        for mut request in requests_read {
+            request.wakeup_customer(PutMessageStatus::FlushSlaveTimeout);
+        }
+        info!("GroupTransferService service end");
+    }
+
+    fn do_wait_transfer(&self, requests_read: &mut Vec<GroupCommitRequest>) {
+        let now = get_current_nano();
+        requests_read.retain_mut(|request| {
+            let ack_nums = request.ack_nums.load(Ordering::Acquire);
+            let transfer_ok = if ack_nums <= 1 {
+                self.push_to_slave_max_offset.load(Ordering::Acquire) >= request.next_offset
+            } else {
+                let acked = self
+                    .connection_list
+                    .lock()
+                    .iter()
+                    .filter(|connection| connection.get_slave_ack_offset() >= request.next_offset)
+                    .count() as i32;
+                // the master itself counts as one replica
+                acked + 1 >= ack_nums
+            };
+            if transfer_ok {
+                request.wakeup_customer(PutMessageStatus::PutOk);
+                false
+            } else if now >= request.dead_line {
+                warn!(
+                    "transfer message to slave timeout, offset : {}",
+                    request.next_offset
+                );
+                request.wakeup_customer(PutMessageStatus::FlushSlaveTimeout);
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
 
 impl HAService for DefaultHAService {
-    fn init<MS: MessageStore>(&mut self, message_store: ArcMut<MS>) -> HAResult<()> {
-        error!("DefaultHAService init not implemented");
+    type Connection = DefaultHAConnection;
+
+    type Client = DefaultHAClient;
+
+    fn init(&mut self, message_store: ArcMut<LocalFileMessageStore>) -> HAResult<()> {
+        self.epoch_cache.load();
+        if self.is_slave.load(Ordering::Acquire) {
+            let ha_client = DefaultHAClient::new(message_store.clone())?;
+            if let Some(ha_master_address) = self.message_store_config.ha_master_address.as_ref() {
+                ha_client.set_master_ha_address(ha_master_address);
+            }
+            *self.ha_client.lock() = Some(ha_client);
+        }
+        self.message_store = Some(message_store);
         Ok(())
     }
 
     fn start(&mut self) -> HAResult<()> {
-        error!("DefaultHAService start not implemented");
+        let address = format!("0.0.0.0:{}", self.message_store_config.ha_listen_port);
+        let listener = std::net::TcpListener::bind(address.as_str())?;
+        listener.set_nonblocking(true)?;
+        *self.local_address.lock() = Some(listener.local_addr()?);
+        let listener = TcpListener::from_std(listener).map_err(|e| {
+            HAError::Service(format!("HAService listen on {} failed: {}", address, e))
+        })?;
+        let tls_server = TlsServer::new(&self.message_store_config.ha_tls_config)
+            .map_err(|e| HAError::Service(e.to_string()))?;
+        info!("HAService listen on {}", address);
+        tokio::spawn(self.clone().accept_loop(listener, tls_server));
+        tokio::spawn(self.clone().group_transfer_loop());
+        if let Some(ha_client) = self.get_ha_client() {
+            ha_client.start_service();
+        }
         Ok(())
     }
 
     fn shutdown(&self) {
-        todo!()
+        if let Some(ha_client) = self.ha_client.lock().take() {
+            ha_client.shutdown_service();
+        }
+        let _ = self.shutdown_tx.send(true);
+        self.destroy_connections();
     }
 
     async fn change_to_master(&self, master_epoch: i32) -> HAResult<bool> {
-        todo!()
+        if let Some(ha_client) = self.ha_client.lock().take() {
+            ha_client.shutdown_service();
+        }
+        self.is_slave.store(false, Ordering::Release);
+        // the connections of the previous term are stale
+        self.destroy_connections();
+        if !self.append_epoch_entry(master_epoch) {
+            return Ok(false);
+        }
+        info!(
+            "Change ha to master success, new master epoch {}",
+            master_epoch
+        );
+        Ok(true)
     }
 
     async fn change_to_master_when_last_role_is_master(&self, master_epoch: i32) -> HAResult<bool> {
-        todo!()
+        if !self.append_epoch_entry(master_epoch) {
+            return Ok(false);
+        }
+        info!(
+            "Change ha to master success, last role is master, new master epoch {}",
+            master_epoch
+        );
+        Ok(true)
     }
 
     async fn change_to_slave(
@@ -79,7 +325,22 @@ impl HAService for DefaultHAService {
         new_master_epoch: i32,
         slave_id: Option<i64>,
     ) -> HAResult<bool> {
-        todo!()
+        let Some(message_store) = self.message_store.clone() else {
+            return Err(HAError::Service("HAService is not initialized".to_string()));
+        };
+        self.is_slave.store(true, Ordering::Release);
+        self.destroy_connections();
+        let ha_client = DefaultHAClient::new(message_store)?;
+        ha_client.set_master_address(new_master_addr);
+        if let Some(old_ha_client) = self.ha_client.lock().replace(ha_client.clone()) {
+            old_ha_client.shutdown_service();
+        }
+        ha_client.start_service();
+        info!(
+            "Change ha to slave success, slave id {:?}, new master {}, new master epoch {}",
+            slave_id, new_master_addr, new_master_epoch
+        );
+        Ok(true)
     }
 
     async fn change_to_slave_when_master_not_change(
@@ -87,54 +348,381 @@ impl HAService for DefaultHAService {
         new_master_addr: &str,
         new_master_epoch: i32,
     ) -> HAResult<bool> {
-        todo!()
+        self.update_master_address(new_master_addr);
+        info!(
+            "Change ha to slave success, master not changed, master {} epoch {}",
+            new_master_addr, new_master_epoch
+        );
+        Ok(true)
     }
 
     fn update_master_address(&self, new_addr: &str) {
-        todo!()
+        if let Some(ha_client) = self.get_ha_client() {
+            ha_client.set_master_address(new_addr);
+        }
     }
 
     fn update_ha_master_address(&self, new_addr: &str) {
-        todo!()
+        if let Some(ha_client) = self.get_ha_client() {
+            ha_client.set_master_ha_address(new_addr);
+        }
     }
 
     fn in_sync_replicas_nums(&self, master_put_where: i64) -> i32 {
-        todo!()
+        let in_sync_slaves = self
+            .connection_list
+            .lock()
+            .iter()
+            .filter(|connection| {
+                master_put_where - connection.get_slave_ack_offset()
+                    < self.message_store_config.ha_max_gap_not_in_sync as i64
+            })
+            .count() as i32;
+        in_sync_slaves + 1
     }
 
     fn get_connection_count(&self) -> &AtomicI32 {
-        todo!()
+        &self.connection_count
     }
 
     fn put_request(&self, request: GroupCommitRequest) {
-        todo!()
+        self.group_transfer_service.put_request(request);
     }
 
     fn put_group_connection_state_request(&self, request: HAConnectionStateNotificationRequest) {
-        todo!()
+        warn!(
+            "DefaultHAService does not track connection state of {}",
+            request.remote_addr()
+        );
+        request.complete(false);
     }
 
-    fn get_connection_list<CN: HAConnection>(&self) -> Vec<Arc<CN>> {
-        todo!()
+    fn get_connection_list(&self) -> Vec<Arc<Self::Connection>> {
+        self.connection_list.lock().clone()
     }
 
-    fn get_ha_client<CL: HAClient>(&self) -> Arc<CL> {
-        todo!()
+    fn get_ha_client(&self) -> Option<Arc<Self::Client>> {
+        self.ha_client.lock().clone()
     }
 
     fn get_push_to_slave_max_offset(&self) -> &AtomicI64 {
-        todo!()
+        &self.push_to_slave_max_offset
     }
 
     fn get_runtime_info(&self, master_put_where: i64) -> HARuntimeInfo {
-        todo!()
+        let mut info = HARuntimeInfo {
+            master: !self.is_slave.load(Ordering::Acquire),
+            master_commit_log_max_offset: master_put_where.max(0) as u64,
+            ..HARuntimeInfo::default()
+        };
+        if info.master {
+            for connection in self.connection_list.lock().iter() {
+                let slave_ack_offset = connection.get_slave_ack_offset();
+                let diff = master_put_where - slave_ack_offset;
+                let in_sync = diff < self.message_store_config.ha_max_gap_not_in_sync as i64;
+                if in_sync {
+                    info.in_sync_slave_nums += 1;
+                }
+                info.ha_connection_info.push(HAConnectionRuntimeInfo {
+                    addr: connection.get_client_address().to_string(),
+                    slave_ack_offset: slave_ack_offset.max(0) as u64,
+                    diff,
+                    in_sync,
+                    transferred_byte_in_second: connection.get_transferred_byte_in_second().max(0)
+                        as u64,
+                    transfer_from_where: connection.get_transfer_from_where().max(0) as u64,
+                });
+            }
+        } else if let Some(ha_client) = self.get_ha_client() {
+            info.ha_client_runtime_info = HAClientRuntimeInfo {
+                master_addr: ha_client.get_ha_master_address(),
+                transferred_byte_in_second: ha_client.get_transferred_byte_in_second().max(0)
+                    as u64,
+                max_offset: self
+                    .message_store
+                    .as_ref()
+                    .map_or(0, |store| store.get_max_phy_offset().max(0) as u64),
+                last_read_timestamp: ha_client.get_last_read_timestamp().max(0) as u64,
+                last_write_timestamp: ha_client.get_last_write_timestamp().max(0) as u64,
+                master_flush_offset: self
+                    .message_store
+                    .as_ref()
+                    .map_or(0, |store| store.get_master_flushed_offset().max(0) as u64),
+                is_activated: ha_client.get_current_state() != HAConnectionState::Shutdown,
+            };
+        }
+        info
     }
 
     fn get_wait_notify_object(&self) -> Arc<WaitNotifyObject> {
-        todo!()
+        self.wait_notify_object.clone()
     }
 
     fn is_slave_ok(&self, master_put_where: i64) -> bool {
-        todo!()
+        self.connection_count.load(Ordering::Acquire) > 0
+            && master_put_where - self.push_to_slave_max_offset.load(Ordering::Acquire)
+                < self.message_store_config.ha_max_gap_not_in_sync as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::server::tls_config::TlsConfig;
+    use rocketmq_common::common::server::tls_config::TlsMode;
+    use tempfile::TempDir;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::test_utils;
+    use crate::test_utils::wait_until;
+
+    fn new_message_store(broker_role: BrokerRole) -> (ArcMut<LocalFileMessageStore>, TempDir) {
+        new_message_store_with_tls(broker_role, TlsConfig::default())
+    }
+
+    fn new_message_store_with_tls(
+        broker_role: BrokerRole,
+        ha_tls_config: TlsConfig,
+    ) -> (ArcMut<LocalFileMessageStore>, TempDir) {
+        test_utils::new_message_store(MessageStoreConfig {
+            mapped_file_size_commit_log: 1024 * 16,
+            ha_listen_port: 0,
+            broker_role,
+            ha_tls_config,
+            ..MessageStoreConfig::default()
+        })
+    }
+
+    /// Starts a master and a slave HA service, the slave connected to the master.
+    fn start_master_and_slave(
+        master_store: &ArcMut<LocalFileMessageStore>,
+        slave_store: &ArcMut<LocalFileMessageStore>,
+    ) -> (DefaultHAService, DefaultHAService) {
+        let mut master_ha_service = DefaultHAService::new(master_store.message_store_config());
+        master_ha_service.init(master_store.clone()).unwrap();
+        master_ha_service.start().unwrap();
+        let mut slave_ha_service = DefaultHAService::new(slave_store.message_store_config());
+        slave_ha_service.init(slave_store.clone()).unwrap();
+        slave_ha_service.start().unwrap();
+        slave_ha_service.update_ha_master_address(
+            format!(
+                "127.0.0.1:{}",
+                master_ha_service.local_address().unwrap().port()
+            )
+            .as_str(),
+        );
+        (master_ha_service, slave_ha_service)
+    }
+
+    #[tokio::test]
+    async fn slave_replicates_master_commit_log() {
+        let (master_store, _master_dir) = new_message_store(BrokerRole::SyncMaster);
+        let (slave_store, _slave_dir) = new_message_store(BrokerRole::Slave);
+
+        let (master_ha_service, slave_ha_service) =
+            start_master_and_slave(&master_store, &slave_store);
+
+        let data = vec![7u8; 1024];
+        assert!(master_store
+            .append_to_commit_log(0, &data, 0, data.len() as i32)
+            .await
+            .unwrap());
+        master_ha_service.get_wait_notify_object().wakeup_all();
+
+        assert!(wait_until(|| slave_store.get_max_phy_offset() == 1024).await);
+        assert!(
+            wait_until(|| {
+                master_ha_service
+                    .get_push_to_slave_max_offset()
+                    .load(Ordering::Acquire)
+                    == 1024
+            })
+            .await
+        );
+        assert_eq!(
+            master_ha_service
+                .get_connection_count()
+                .load(Ordering::Acquire),
+            1
+        );
+        assert_eq!(master_ha_service.in_sync_replicas_nums(1024), 2);
+        assert!(master_ha_service.is_slave_ok(1024));
+        let runtime_info = master_ha_service.get_runtime_info(1024);
+        assert!(runtime_info.master);
+        assert_eq!(runtime_info.in_sync_slave_nums, 1);
+        assert_eq!(runtime_info.ha_connection_info[0].slave_ack_offset, 1024);
+
+        let (tx, rx) = oneshot::channel();
+        master_ha_service.put_request(GroupCommitRequest::with_ack_nums(1024, 3000, 2, tx));
+        assert_eq!(rx.await.unwrap(), PutMessageStatus::PutOk);
+
+        let (tx, rx) = oneshot::channel();
+        master_ha_service.put_request(GroupCommitRequest::with_ack_nums(2048, 100, 2, tx));
+        assert_eq!(rx.await.unwrap(), PutMessageStatus::FlushSlaveTimeout);
+
+        slave_ha_service.shutdown();
+        master_ha_service.shutdown();
+    }
+
+    #[tokio::test]
+    async fn slave_replicates_over_tls() {
+        let cert_path = |name: &str| {
+            Some(format!(
+                "{}/../rocketmq-remoting/tests/tls/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                name
+            ))
+        };
+        let (master_store, _master_dir) = new_message_store_with_tls(
+            BrokerRole::SyncMaster,
+            TlsConfig {
+                tls_mode: TlsMode::Enforcing,
+                tls_server_cert_path: cert_path("server.pem"),
+                tls_server_key_path: cert_path("server.key"),
+                ..TlsConfig::default()
+            },
+        );
+        let (slave_store, _slave_dir) = new_message_store_with_tls(
+            BrokerRole::Slave,
+            TlsConfig {
+                tls_enable: true,
+                tls_client_trust_cert_path: cert_path("ca.pem"),
+                ..TlsConfig::default()
+            },
+        );
+
+        let (master_ha_service, slave_ha_service) =
+            start_master_and_slave(&master_store, &slave_store);
+
+        let data = vec![7u8; 1024];
+        assert!(master_store
+            .append_to_commit_log(0, &data, 0, data.len() as i32)
+            .await
+            .unwrap());
+        master_ha_service.get_wait_notify_object().wakeup_all();
+        assert!(wait_until(|| slave_store.get_max_phy_offset() == 1024).await);
+
+        slave_ha_service.shutdown();
+        master_ha_service.shutdown();
+    }
+
+    #[tokio::test]
+    async fn slave_appends_consecutive_pushes_and_acks_them() {
+        let (master_store, _master_dir) = new_message_store(BrokerRole::SyncMaster);
+        let (slave_store, _slave_dir) = new_message_store(BrokerRole::Slave);
+        let (master_ha_service, slave_ha_service) =
+            start_master_and_slave(&master_store, &slave_store);
+
+        for (start_offset, value) in [(0i64, 1u8), (1024, 2), (2048, 3)] {
+            let data = vec![value; 1024];
+            assert!(master_store
+                .append_to_commit_log(start_offset, &data, 0, data.len() as i32)
+                .await
+                .unwrap());
+            master_ha_service.get_wait_notify_object().wakeup_all();
+            let end_offset = start_offset + 1024;
+            assert!(wait_until(|| slave_store.get_max_phy_offset() == end_offset).await);
+            assert!(
+                wait_until(|| {
+                    master_ha_service
+                        .get_runtime_info(end_offset)
+                        .ha_connection_info
+                        .first()
+                        .is_some_and(|info| info.slave_ack_offset == end_offset as u64)
+                })
+                .await
+            );
+        }
+
+        let replicated = slave_store.get_commit_log_data(0).unwrap();
+        let replicated = replicated.bytes.unwrap();
+        assert_eq!(replicated.len(), 3072);
+        assert!(replicated[..1024].iter().all(|byte| *byte == 1));
+        assert!(replicated[1024..2048].iter().all(|byte| *byte == 2));
+        assert!(replicated[2048..].iter().all(|byte| *byte == 3));
+        assert_eq!(
+            master_ha_service
+                .get_push_to_slave_max_offset()
+                .load(Ordering::Acquire),
+            3072
+        );
+
+        slave_ha_service.shutdown();
+        master_ha_service.shutdown();
+    }
+
+    #[tokio::test]
+    async fn slave_falling_behind_is_out_of_sync() {
+        let (master_store, _master_dir) = test_utils::new_message_store(MessageStoreConfig {
+            mapped_file_size_commit_log: 1024 * 16,
+            ha_listen_port: 0,
+            ha_max_gap_not_in_sync: 512,
+            broker_role: BrokerRole::SyncMaster,
+            ..MessageStoreConfig::default()
+        });
+        let (slave_store, _slave_dir) = new_message_store(BrokerRole::Slave);
+        let (master_ha_service, slave_ha_service) =
+            start_master_and_slave(&master_store, &slave_store);
+
+        let data = vec![7u8; 1024];
+        assert!(master_store
+            .append_to_commit_log(0, &data, 0, data.len() as i32)
+            .await
+            .unwrap());
+        master_ha_service.get_wait_notify_object().wakeup_all();
+        assert!(
+            wait_until(|| {
+                master_ha_service
+                    .get_runtime_info(1024)
+                    .ha_connection_info
+                    .first()
+                    .is_some_and(|info| info.slave_ack_offset == 1024)
+            })
+            .await
+        );
+
+        // the slave is still in sync while it lags less than ha_max_gap_not_in_sync
+        assert_eq!(master_ha_service.in_sync_replicas_nums(1024 + 511), 2);
+        assert!(master_ha_service.is_slave_ok(1024 + 511));
+
+        let runtime_info = master_ha_service.get_runtime_info(1024 + 512);
+        assert_eq!(runtime_info.in_sync_slave_nums, 0);
+        assert!(!runtime_info.ha_connection_info[0].in_sync);
+        assert_eq!(runtime_info.ha_connection_info[0].diff, 512);
+        assert_eq!(master_ha_service.in_sync_replicas_nums(1024 + 512), 1);
+        assert!(!master_ha_service.is_slave_ok(1024 + 512));
+
+        slave_ha_service.shutdown();
+        master_ha_service.shutdown();
+    }
+
+    #[tokio::test]
+    async fn change_role_records_master_epoch() {
+        let (store, _dir) = new_message_store(BrokerRole::SyncMaster);
+        let mut ha_service = DefaultHAService::new(store.message_store_config());
+        ha_service.init(store.clone()).unwrap();
+
+        assert!(ha_service.change_to_master(1).await.unwrap());
+        let data = vec![7u8; 512];
+        assert!(store
+            .append_to_commit_log(0, &data, 0, data.len() as i32)
+            .await
+            .unwrap());
+        assert!(ha_service
+            .change_to_slave("127.0.0.1:10911", 2, Some(1))
+            .await
+            .unwrap());
+        assert!(!ha_service.get_runtime_info(512).master);
+        assert!(ha_service.get_ha_client().is_some());
+
+        assert!(ha_service.change_to_master(3).await.unwrap());
+        assert!(ha_service.get_ha_client().is_none());
+        assert!(ha_service.get_runtime_info(512).master);
+        let entries = ha_service.get_epoch_cache().get_all_entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].epoch, 1);
+        assert_eq!(entries[0].end_offset, 512);
+        assert_eq!(entries[1].start_offset, 512);
+        ha_service.shutdown();
     }
 }
@@ -0,0 +1,221 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+
+use parking_lot::RwLock;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntry;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct EpochFileSerializeWrapper {
+    epoch_entries: Vec<EpochEntry>,
+}
+
+/// The epoch history of the commit log in controller mode.
+///
+/// Every time a broker becomes master, it appends an entry with the new master epoch and the
+/// commit log offset from which that epoch starts. A slave compares its history with the
+/// master's to find the last offset on which both agree, and truncates everything after it.
+pub struct EpochFileCache {
+    file_path: String,
+    epoch_map: RwLock<BTreeMap<i32, EpochEntry>>,
+}
+
+impl EpochFileCache {
+    pub fn new(file_path: impl Into<String>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            epoch_map: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Appends a new epoch starting at `start_offset`, the previous epoch ends there.
+    ///
+    /// Returns `false` if the epoch or the offset goes backwards.
+    pub fn append_entry(&self, epoch: i32, start_offset: i64) -> bool {
+        {
+            let mut epoch_map = self.epoch_map.write();
+            if let Some((_, last)) = epoch_map.iter_mut().next_back() {
+                if last.epoch >= epoch || last.start_offset > start_offset {
+                    warn!(
+                        "append epoch entry failed, last {}, new epoch {} start offset {}",
+                        last, epoch, start_offset
+                    );
+                    return false;
+                }
+                last.end_offset = start_offset;
+            }
+            epoch_map.insert(epoch, EpochEntry::new(epoch, start_offset));
+        }
+        self.persist();
+        true
+    }
+
+    /// Replaces the whole history, e.g. with the history copied from the master.
+    pub fn set_epoch_entries(&self, entries: Vec<EpochEntry>) {
+        {
+            let mut epoch_map = self.epoch_map.write();
+            epoch_map.clear();
+            for entry in entries {
+                epoch_map.insert(entry.epoch, entry);
+            }
+            if let Some((_, last)) = epoch_map.iter_mut().next_back() {
+                last.end_offset = i64::MAX;
+            }
+        }
+        self.persist();
+    }
+
+    pub fn get_all_entries(&self) -> Vec<EpochEntry> {
+        self.epoch_map.read().values().copied().collect()
+    }
+
+    pub fn get_entry(&self, epoch: i32) -> Option<EpochEntry> {
+        self.epoch_map.read().get(&epoch).copied()
+    }
+
+    pub fn last_entry(&self) -> Option<EpochEntry> {
+        self.epoch_map.read().values().next_back().copied()
+    }
+
+    /// The latest epoch, `0` when the history is empty.
+    pub fn last_epoch(&self) -> i32 {
+        self.last_entry().map_or(0, |entry| entry.epoch)
+    }
+
+    /// Finds the largest offset up to which this history and `remote` agree.
+    ///
+    /// The latest epoch on each side ends at that side's max offset. Returns `-1` if the
+    /// histories share no epoch.
+    pub fn find_consistent_point(
+        &self,
+        local_max_offset: i64,
+        remote: &[EpochEntry],
+        remote_max_offset: i64,
+    ) -> i64 {
+        let bound = |entry: &EpochEntry, max_offset: i64| {
+            if entry.end_offset == i64::MAX {
+                max_offset
+            } else {
+                entry.end_offset
+            }
+        };
+        let epoch_map = self.epoch_map.read();
+        for local in epoch_map.values().rev() {
+            if let Some(remote_entry) = remote.iter().find(|entry| entry.epoch == local.epoch) {
+                if remote_entry.start_offset == local.start_offset {
+                    return bound(local, local_max_offset)
+                        .min(bound(remote_entry, remote_max_offset));
+                }
+            }
+        }
+        -1
+    }
+
+    /// Removes the epochs starting at or after `truncate_offset`.
+    pub fn truncate_suffix_by_offset(&self, truncate_offset: i64) {
+        {
+            let mut epoch_map = self.epoch_map.write();
+            epoch_map.retain(|_, entry| entry.start_offset < truncate_offset);
+            if let Some((_, last)) = epoch_map.iter_mut().next_back() {
+                last.end_offset = i64::MAX;
+            }
+        }
+        self.persist();
+    }
+}
+
+impl ConfigManager for EpochFileCache {
+    fn config_file_path(&self) -> String {
+        self.file_path.clone()
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let wrapper = EpochFileSerializeWrapper {
+            epoch_entries: self.get_all_entries(),
+        };
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&wrapper).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&wrapper).expect("encode failed")
+        }
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        if let Ok(wrapper) = SerdeJsonUtils::from_json_str::<EpochFileSerializeWrapper>(json_string)
+        {
+            let mut epoch_map = self.epoch_map.write();
+            epoch_map.clear();
+            for entry in wrapper.epoch_entries {
+                epoch_map.insert(entry.epoch, entry);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_consistent_point_with_master_history() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("epochFileCheckpoint");
+        let local = EpochFileCache::new(path.to_string_lossy().to_string());
+        assert!(local.append_entry(1, 0));
+        assert!(local.append_entry(2, 100));
+        assert!(!local.append_entry(2, 200));
+        // The local broker was master of epoch 3 but the write at 250..300 was not replicated.
+        assert!(local.append_entry(3, 250));
+
+        let remote = vec![
+            EpochEntry {
+                epoch: 1,
+                start_offset: 0,
+                end_offset: 100,
+            },
+            EpochEntry {
+                epoch: 2,
+                start_offset: 100,
+                end_offset: 260,
+            },
+            EpochEntry::new(4, 260),
+        ];
+        assert_eq!(local.find_consistent_point(300, &remote, 400), 250);
+
+        local.truncate_suffix_by_offset(250);
+        assert_eq!(local.last_epoch(), 2);
+        assert_eq!(local.last_entry().unwrap().end_offset, i64::MAX);
+
+        let reloaded = EpochFileCache::new(path.to_string_lossy().to_string());
+        assert!(reloaded.load());
+        assert_eq!(reloaded.get_all_entries(), local.get_all_entries());
+        assert_eq!(reloaded.find_consistent_point(250, &remote, 400), 250);
+
+        reloaded.set_epoch_entries(remote.clone());
+        assert_eq!(reloaded.last_epoch(), 4);
+        assert_eq!(reloaded.find_consistent_point(400, &remote, 400), 400);
+    }
+}
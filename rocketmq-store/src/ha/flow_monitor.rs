@@ -0,0 +1,113 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use rocketmq_common::TimeUtils::get_current_millis;
+
+use crate::config::message_store_config::MessageStoreConfig;
+
+/// Tracks how many bytes the HA connection transferred in the last second, and limits the
+/// transfer speed when `haFlowControlEnable` is on.
+pub(crate) struct FlowMonitor {
+    message_store_config: Arc<MessageStoreConfig>,
+    transferred_byte: AtomicI64,
+    transferred_byte_in_second: AtomicI64,
+    last_calculate_timestamp: AtomicI64,
+}
+
+impl FlowMonitor {
+    pub(crate) fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        Self {
+            message_store_config,
+            transferred_byte: AtomicI64::new(0),
+            transferred_byte_in_second: AtomicI64::new(0),
+            last_calculate_timestamp: AtomicI64::new(get_current_millis() as i64),
+        }
+    }
+
+    /// Rolls the counter over once a second has passed since the last calculation.
+    pub(crate) fn calculate_speed(&self) {
+        let now = get_current_millis() as i64;
+        let last = self.last_calculate_timestamp.load(Ordering::Acquire);
+        if now - last >= 1000
+            && self
+                .last_calculate_timestamp
+                .compare_exchange(last, now, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            self.transferred_byte_in_second.store(
+                self.transferred_byte.swap(0, Ordering::AcqRel),
+                Ordering::Release,
+            );
+        }
+    }
+
+    pub(crate) fn can_transfer_max_byte_num(&self) -> i32 {
+        self.calculate_speed();
+        if self.is_flow_control_enable() {
+            let remain =
+                self.max_transfer_byte_in_second() - self.transferred_byte.load(Ordering::Acquire);
+            remain.clamp(0, i32::MAX as i64) as i32
+        } else {
+            i32::MAX
+        }
+    }
+
+    pub(crate) fn add_byte_count_transferred(&self, count: i64) {
+        self.calculate_speed();
+        self.transferred_byte.fetch_add(count, Ordering::AcqRel);
+    }
+
+    pub(crate) fn get_transferred_byte_in_second(&self) -> i64 {
+        self.calculate_speed();
+        self.transferred_byte_in_second.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn is_flow_control_enable(&self) -> bool {
+        self.message_store_config.ha_flow_control_enable
+    }
+
+    pub(crate) fn max_transfer_byte_in_second(&self) -> i64 {
+        self.message_store_config.max_ha_transfer_byte_in_second as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_transfer_max_byte_num_respects_flow_control() {
+        let flow_monitor = FlowMonitor::new(Arc::new(MessageStoreConfig {
+            ha_flow_control_enable: true,
+            max_ha_transfer_byte_in_second: 1024,
+            ..MessageStoreConfig::default()
+        }));
+        assert_eq!(flow_monitor.can_transfer_max_byte_num(), 1024);
+        flow_monitor.add_byte_count_transferred(1000);
+        assert_eq!(flow_monitor.can_transfer_max_byte_num(), 24);
+        flow_monitor.add_byte_count_transferred(100);
+        assert_eq!(flow_monitor.can_transfer_max_byte_num(), 0);
+
+        let unlimited = FlowMonitor::new(Arc::new(MessageStoreConfig::default()));
+        unlimited.add_byte_count_transferred(1000);
+        assert_eq!(unlimited.can_transfer_max_byte_num(), i32::MAX);
+    }
+}
@@ -21,40 +21,60 @@ use std::sync::Arc;
 
 use rocketmq_remoting::protocol::body::ha_runtime_info::HARuntimeInfo;
 use rocketmq_rust::ArcMut;
-use tracing::error;
 
-use crate::base::message_store::MessageStore;
-use crate::ha::ha_client::HAClient;
-use crate::ha::ha_connection::HAConnection;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::ha::default_ha_client::DefaultHAClient;
+use crate::ha::default_ha_connection::DefaultHAConnection;
+use crate::ha::default_ha_service::DefaultHAService;
 use crate::ha::ha_connection_state_notification_request::HAConnectionStateNotificationRequest;
 use crate::ha::ha_service::HAService;
 use crate::ha::wait_notify_object::WaitNotifyObject;
 use crate::log_file::flush_manager_impl::group_commit_request::GroupCommitRequest;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
 use crate::store_error::HAResult;
 
-pub struct GeneralHAService;
+/// The HA service used by the message store, it delegates to the configured implementation.
+pub struct GeneralHAService {
+    default_ha_service: DefaultHAService,
+}
+
+impl GeneralHAService {
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        Self {
+            default_ha_service: DefaultHAService::new(message_store_config),
+        }
+    }
+
+    pub fn get_default_ha_service(&self) -> &DefaultHAService {
+        &self.default_ha_service
+    }
+}
 
 impl HAService for GeneralHAService {
-    fn init<MS: MessageStore>(&mut self, message_store: ArcMut<MS>) -> HAResult<()> {
-        error!("Initializing general HAService is not implemented");
-        Ok(())
+    type Connection = DefaultHAConnection;
+
+    type Client = DefaultHAClient;
+
+    fn init(&mut self, message_store: ArcMut<LocalFileMessageStore>) -> HAResult<()> {
+        self.default_ha_service.init(message_store)
     }
 
     fn start(&mut self) -> HAResult<()> {
-        error!("Starting general HAService is not implemented");
-        Ok(())
+        self.default_ha_service.start()
     }
 
     fn shutdown(&self) {
-        todo!()
+        self.default_ha_service.shutdown()
     }
 
     async fn change_to_master(&self, master_epoch: i32) -> HAResult<bool> {
-        todo!()
+        self.default_ha_service.change_to_master(master_epoch).await
     }
 
     async fn change_to_master_when_last_role_is_master(&self, master_epoch: i32) -> HAResult<bool> {
-        todo!()
+        self.default_ha_service
+            .change_to_master_when_last_role_is_master(master_epoch)
+            .await
     }
 
     async fn change_to_slave(
@@ -63,7 +83,9 @@ impl HAService for GeneralHAService {
         new_master_epoch: i32,
         slave_id: Option<i64>,
     ) -> HAResult<bool> {
-        todo!()
+        self.default_ha_service
+            .change_to_slave(new_master_addr, new_master_epoch, slave_id)
+            .await
     }
 
     async fn change_to_slave_when_master_not_change(
@@ -71,54 +93,58 @@ impl HAService for GeneralHAService {
         new_master_addr: &str,
         new_master_epoch: i32,
     ) -> HAResult<bool> {
-        todo!()
+        self.default_ha_service
+            .change_to_slave_when_master_not_change(new_master_addr, new_master_epoch)
+            .await
     }
 
     fn update_master_address(&self, new_addr: &str) {
-        todo!()
+        self.default_ha_service.update_master_address(new_addr)
     }
 
     fn update_ha_master_address(&self, new_addr: &str) {
-        todo!()
+        self.default_ha_service.update_ha_master_address(new_addr)
     }
 
     fn in_sync_replicas_nums(&self, master_put_where: i64) -> i32 {
-        todo!()
+        self.default_ha_service
+            .in_sync_replicas_nums(master_put_where)
     }
 
     fn get_connection_count(&self) -> &AtomicI32 {
-        todo!()
+        self.default_ha_service.get_connection_count()
     }
 
     fn put_request(&self, request: GroupCommitRequest) {
-        todo!()
+        self.default_ha_service.put_request(request)
     }
 
     fn put_group_connection_state_request(&self, request: HAConnectionStateNotificationRequest) {
-        todo!()
+        self.default_ha_service
+            .put_group_connection_state_request(request)
     }
 
-    fn get_connection_list<CN: HAConnection>(&self) -> Vec<Arc<CN>> {
-        todo!()
+    fn get_connection_list(&self) -> Vec<Arc<Self::Connection>> {
+        self.default_ha_service.get_connection_list()
     }
 
-    fn get_ha_client<CL: HAClient>(&self) -> Arc<CL> {
-        todo!()
+    fn get_ha_client(&self) -> Option<Arc<Self::Client>> {
+        self.default_ha_service.get_ha_client()
     }
 
     fn get_push_to_slave_max_offset(&self) -> &AtomicI64 {
-        todo!()
+        self.default_ha_service.get_push_to_slave_max_offset()
     }
 
     fn get_runtime_info(&self, master_put_where: i64) -> HARuntimeInfo {
-        todo!()
+        self.default_ha_service.get_runtime_info(master_put_where)
     }
 
     fn get_wait_notify_object(&self) -> Arc<WaitNotifyObject> {
-        todo!()
+        self.default_ha_service.get_wait_notify_object()
     }
 
     fn is_slave_ok(&self, master_put_where: i64) -> bool {
-        todo!()
+        self.default_ha_service.is_slave_ok(master_put_where)
     }
 }
@@ -0,0 +1,54 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+
+use crate::log_file::flush_manager_impl::group_commit_request::GroupCommitRequest;
+
+/// Holds the producer requests that wait for the slaves to acknowledge their data.
+#[derive(Default)]
+pub(crate) struct GroupTransferService {
+    requests_write: Mutex<Vec<GroupCommitRequest>>,
+    notify: Notify,
+}
+
+impl GroupTransferService {
+    pub(crate) fn put_request(&self, request: GroupCommitRequest) {
+        self.requests_write.lock().push(request);
+        self.notify.notify_one();
+    }
+
+    pub(crate) fn notify_transfer_some(&self) {
+        self.notify.notify_one();
+    }
+
+    /// Moves the requests put since the last call into `requests_read`.
+    pub(crate) fn swap_requests(&self, requests_read: &mut Vec<GroupCommitRequest>) {
+        requests_read.append(&mut self.requests_write.lock());
+    }
+
+    pub(crate) async fn wait_for_running(&self, interval_millis: u64) {
+        let _ = tokio::time::timeout(
+            Duration::from_millis(interval_millis),
+            self.notify.notified(),
+        )
+        .await;
+    }
+}
@@ -16,8 +16,6 @@
  */
 use std::net::SocketAddr;
 
-use tokio::net::TcpStream;
-
 use crate::ha::ha_connection_state::HAConnectionState;
 
 #[trait_variant::make(HAConnection: Send)]
@@ -37,12 +35,6 @@ pub trait RocketmqHAConnection: Sync {
     /// This forcibly closes the connection without waiting for pending operations.
     fn close(&self);
 
-    /// Get the underlying TCP stream
-    ///
-    /// # Returns
-    /// Reference to the TCP stream for this connection
-    fn get_socket(&self) -> &TcpStream;
-
     /// Get the current state of the connection
     ///
     /// # Returns
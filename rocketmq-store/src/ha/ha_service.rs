@@ -21,16 +21,22 @@ use std::sync::Arc;
 use rocketmq_remoting::protocol::body::ha_runtime_info::HARuntimeInfo;
 use rocketmq_rust::ArcMut;
 
-use crate::base::message_store::MessageStore;
 use crate::ha::ha_client::HAClient;
 use crate::ha::ha_connection::HAConnection;
 use crate::ha::ha_connection_state_notification_request::HAConnectionStateNotificationRequest;
 use crate::ha::wait_notify_object::WaitNotifyObject;
 use crate::log_file::flush_manager_impl::group_commit_request::GroupCommitRequest;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
 use crate::store_error::HAResult;
 
 #[trait_variant::make(HAService: Send)]
 pub trait RocketHAService: Sync {
+    /// The connection type the master keeps for every slave
+    type Connection: HAConnection;
+
+    /// The client type a slave uses to connect to its master
+    type Client: HAClient;
+
     /// Initialize the HA service
     ///
     /// This must be called before other methods.
@@ -40,7 +46,7 @@ pub trait RocketHAService: Sync {
     ///
     /// # Returns
     /// IO Result indicating success or failure
-    fn init<MS: MessageStore>(&mut self, message_store: ArcMut<MS>) -> HAResult<()>;
+    fn init(&mut self, message_store: ArcMut<LocalFileMessageStore>) -> HAResult<()>;
 
     /// Start the HA service
     ///
@@ -145,13 +151,13 @@ pub trait RocketHAService: Sync {
     ///
     /// # Returns
     /// List of HA connections
-    fn get_connection_list<CN: HAConnection>(&self) -> Vec<Arc<CN>>;
+    fn get_connection_list(&self) -> Vec<Arc<Self::Connection>>;
 
     /// Get the HA client
     ///
     /// # Returns
-    /// Reference to the HA client
-    fn get_ha_client<CL: HAClient>(&self) -> Arc<CL>;
+    /// Reference to the HA client, `None` before the service is initialized
+    fn get_ha_client(&self) -> Option<Arc<Self::Client>>;
 
     /// Get the maximum offset across all slaves
     ///
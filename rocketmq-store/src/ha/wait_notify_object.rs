@@ -15,4 +15,28 @@
  * limitations under the License.
  */
 
-pub(crate) struct WaitNotifyObject;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// Lets the HA transfer tasks sleep until new data arrives or the interval elapses.
+#[derive(Default)]
+pub(crate) struct WaitNotifyObject {
+    notify: Notify,
+}
+
+impl WaitNotifyObject {
+    /// Wakes up every task waiting in [`WaitNotifyObject::wait_for_running`].
+    pub(crate) fn wakeup_all(&self) {
+        self.notify.notify_waiters();
+    }
+
+    /// Waits until woken up or at most `interval_millis`.
+    pub(crate) async fn wait_for_running(&self, interval_millis: u64) {
+        let _ = tokio::time::timeout(
+            Duration::from_millis(interval_millis),
+            self.notify.notified(),
+        )
+        .await;
+    }
+}
@@ -15,5 +15,8 @@
  * limitations under the License.
  */
 
+pub(crate) mod compaction_log;
+pub(crate) mod compaction_position_mgr;
 pub(crate) mod compaction_service;
 pub(crate) mod compaction_store;
+pub(crate) mod kv_store;
@@ -0,0 +1,738 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use bytes::Buf;
+use bytes::Bytes;
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::MessageDecoder;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::base::compaction_append_msg_callback::CompactionAppendMsgCallback;
+use crate::base::get_message_result::GetMessageResult;
+use crate::base::message_result::AppendMessageResult;
+use crate::base::message_status_enum::AppendMessageStatus;
+use crate::base::message_status_enum::GetMessageStatus;
+use crate::base::message_store::MessageStore;
+use crate::base::select_result::SelectMappedBufferResult;
+use crate::consume_queue::mapped_file_queue::MappedFileQueue;
+use crate::log_file::commit_log::BLANK_MAGIC_CODE;
+use crate::log_file::mapped_file::MappedFile;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+
+/// Unit of the compacted consume queue: queue offset, commit log offset and size.
+pub(crate) const CQ_UNIT_SIZE: i32 = 8 + 8 + 4;
+
+const END_FILE_MIN_BLANK_LENGTH: i32 = 4 + 4;
+// offsets of the QUEUEOFFSET and PHYSICALOFFSET fields of a stored message
+const QUEUE_OFFSET_POSITION: usize = 4 + 4 + 4 + 4 + 4;
+const PHYSICAL_OFFSET_POSITION: usize = QUEUE_OFFSET_POSITION + 8;
+
+const COMPACTING_SUFFIX: &str = ".compacting";
+const DELETING_SUFFIX: &str = ".deleting";
+
+/// Copies a stored message to the compacted log, rewriting its physical offset.
+pub(crate) struct DefaultCompactionAppendMsgCallback;
+
+impl CompactionAppendMsgCallback for DefaultCompactionAppendMsgCallback {
+    fn do_append(
+        &self,
+        bb_dest: &mut [u8],
+        wrote_offset: i64,
+        max_blank: i32,
+        bb_src: &mut Bytes,
+    ) -> AppendMessageResult {
+        let msg_len = bb_src.len() as i32;
+        if msg_len + END_FILE_MIN_BLANK_LENGTH > max_blank {
+            bb_dest[0..4].copy_from_slice(&max_blank.to_be_bytes());
+            bb_dest[4..8].copy_from_slice(&BLANK_MAGIC_CODE.to_be_bytes());
+            return AppendMessageResult {
+                status: AppendMessageStatus::EndOfFile,
+                wrote_offset,
+                wrote_bytes: max_blank,
+                ..Default::default()
+            };
+        }
+        let queue_offset = (&bb_src[QUEUE_OFFSET_POSITION..PHYSICAL_OFFSET_POSITION]).get_i64();
+        bb_dest[..msg_len as usize].copy_from_slice(bb_src.as_ref());
+        bb_dest[PHYSICAL_OFFSET_POSITION..PHYSICAL_OFFSET_POSITION + 8]
+            .copy_from_slice(&wrote_offset.to_be_bytes());
+        AppendMessageResult {
+            status: AppendMessageStatus::PutOk,
+            wrote_offset,
+            wrote_bytes: msg_len,
+            logics_offset: queue_offset,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompactionCqUnit {
+    pub queue_offset: i64,
+    pub offset_py: i64,
+    pub size_py: i32,
+}
+
+/// One generation of compacted data, the compacted log and the consume queue pointing into it.
+struct CompactionFiles {
+    log: MappedFileQueue,
+    cq: MappedFileQueue,
+    cq_file_size: i64,
+    unit_count: i64,
+}
+
+impl CompactionFiles {
+    fn new(log_path: &str, cq_path: &str, log_file_size: u64, cq_file_size: u64) -> Self {
+        Self {
+            log: MappedFileQueue::new(log_path.to_string(), log_file_size, None),
+            cq: MappedFileQueue::new(cq_path.to_string(), cq_file_size, None),
+            cq_file_size: cq_file_size as i64,
+            unit_count: 0,
+        }
+    }
+
+    /// Loads the files of a finished generation, the last consume queue file is scanned to find
+    /// how many units it holds.
+    fn open(log_path: &str, cq_path: &str, log_file_size: u64, cq_file_size: u64) -> Option<Self> {
+        let mut files = Self::new(log_path, cq_path, log_file_size, cq_file_size);
+        if !files.log.load() || !files.cq.load() {
+            return None;
+        }
+        if let Some(last) = files.cq.get_last_mapped_file() {
+            let mut position = 0;
+            while position + CQ_UNIT_SIZE as i64 <= files.cq_file_size {
+                let Some(data) = last.get_data(position as usize, CQ_UNIT_SIZE as usize) else {
+                    break;
+                };
+                if Self::decode_unit(data).size_py <= 0 {
+                    break;
+                }
+                position += CQ_UNIT_SIZE as i64;
+            }
+            last.set_wrote_position(position as i32);
+            last.set_flushed_position(position as i32);
+            last.set_committed_position(position as i32);
+            files.unit_count =
+                (last.get_file_from_offset() as i64 + position) / CQ_UNIT_SIZE as i64;
+        }
+        Some(files)
+    }
+
+    fn decode_unit(mut data: Bytes) -> CompactionCqUnit {
+        CompactionCqUnit {
+            queue_offset: data.get_i64(),
+            offset_py: data.get_i64(),
+            size_py: data.get_i32(),
+        }
+    }
+
+    fn get_unit(&self, index: i64) -> Option<CompactionCqUnit> {
+        let offset = index * CQ_UNIT_SIZE as i64;
+        let mapped_file = self.cq.find_mapped_file_by_offset(offset, false)?;
+        let data =
+            mapped_file.get_data((offset % self.cq_file_size) as usize, CQ_UNIT_SIZE as usize)?;
+        Some(Self::decode_unit(data))
+    }
+
+    fn get_message(&self, unit: &CompactionCqUnit) -> Option<Bytes> {
+        let mapped_file = self.log.find_mapped_file_by_offset(unit.offset_py, false)?;
+        let position = unit.offset_py - mapped_file.get_file_from_offset() as i64;
+        mapped_file.get_data(position as usize, unit.size_py as usize)
+    }
+
+    /// Index of the first unit whose queue offset is not smaller than `queue_offset`.
+    fn lower_bound(&self, queue_offset: i64) -> i64 {
+        let (mut low, mut high) = (0, self.unit_count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.get_unit(mid) {
+                Some(unit) if unit.queue_offset < queue_offset => low = mid + 1,
+                _ => high = mid,
+            }
+        }
+        low
+    }
+
+    fn first_queue_offset(&self) -> i64 {
+        self.get_unit(0).map_or(-1, |unit| unit.queue_offset)
+    }
+
+    fn last_queue_offset(&self) -> i64 {
+        if self.unit_count == 0 {
+            return -1;
+        }
+        self.get_unit(self.unit_count - 1)
+            .map_or(-1, |unit| unit.queue_offset)
+    }
+
+    fn append(&mut self, mut message: Bytes, queue_offset: i64) -> bool {
+        let size_py = message.len() as i32;
+        let mut result = None;
+        // a message that does not fit ends the file, so it takes at most two attempts
+        for _ in 0..2 {
+            let Some(mapped_file) = self.log.get_last_mapped_file_mut_start_offset(0, true) else {
+                error!("create compaction log file failed");
+                return false;
+            };
+            let append_result = mapped_file
+                .append_message_compaction(&mut message, &DefaultCompactionAppendMsgCallback);
+            match append_result.status {
+                AppendMessageStatus::PutOk => {
+                    result = Some(append_result);
+                    break;
+                }
+                AppendMessageStatus::EndOfFile => continue,
+                status => {
+                    error!("append compaction log failed, status: {:?}", status);
+                    return false;
+                }
+            }
+        }
+        let Some(result) = result else {
+            error!(
+                "message of size {} is larger than a compaction log file",
+                size_py
+            );
+            return false;
+        };
+        let Some(cq_file) = self.cq.get_last_mapped_file_mut_start_offset(0, true) else {
+            error!("create compaction consume queue file failed");
+            return false;
+        };
+        let mut unit = [0u8; CQ_UNIT_SIZE as usize];
+        unit[0..8].copy_from_slice(&queue_offset.to_be_bytes());
+        unit[8..16].copy_from_slice(&result.wrote_offset.to_be_bytes());
+        unit[16..20].copy_from_slice(&size_py.to_be_bytes());
+        if !cq_file.append_message_bytes(&unit) {
+            error!("append compaction consume queue failed");
+            return false;
+        }
+        self.unit_count += 1;
+        true
+    }
+
+    fn flush(&self) {
+        for mapped_file in self.log.get_mapped_files().read().iter() {
+            mapped_file.flush(0);
+        }
+        for mapped_file in self.cq.get_mapped_files().read().iter() {
+            mapped_file.flush(0);
+        }
+    }
+}
+
+/// The compacted view of one queue of a compacted topic.
+///
+/// Each compaction merges the previously compacted messages with the messages appended to the
+/// queue since, keeps only the latest message of every `KEYS` value and writes the result to a
+/// new generation of files that replaces the previous one. Messages without keys can not be
+/// compacted and are not retained.
+pub(crate) struct CompactionLog {
+    topic: CheetahString,
+    queue_id: i32,
+    log_path: String,
+    cq_path: String,
+    log_file_size: u64,
+    cq_file_size: u64,
+    files: RwLock<CompactionFiles>,
+    compacting: Mutex<()>,
+}
+
+impl CompactionLog {
+    pub fn new(
+        topic: CheetahString,
+        queue_id: i32,
+        log_root: &str,
+        cq_root: &str,
+        log_file_size: u64,
+        cq_file_size: u64,
+    ) -> Self {
+        let log_path = PathBuf::from(log_root)
+            .join(topic.as_str())
+            .join(queue_id.to_string())
+            .to_string_lossy()
+            .into_owned();
+        let cq_path = PathBuf::from(cq_root)
+            .join(topic.as_str())
+            .join(queue_id.to_string())
+            .to_string_lossy()
+            .into_owned();
+        // the consume queue files must hold whole units
+        let cq_file_size = (cq_file_size / CQ_UNIT_SIZE as u64).max(1) * CQ_UNIT_SIZE as u64;
+        Self {
+            files: RwLock::new(CompactionFiles::new(
+                &log_path,
+                &cq_path,
+                log_file_size,
+                cq_file_size,
+            )),
+            topic,
+            queue_id,
+            log_path,
+            cq_path,
+            log_file_size,
+            cq_file_size,
+            compacting: Mutex::new(()),
+        }
+    }
+
+    pub fn load(&self) -> bool {
+        // a compaction interrupted before its swap is simply redone
+        Self::remove_dir(&format!("{}{}", self.log_path, COMPACTING_SUFFIX));
+        Self::remove_dir(&format!("{}{}", self.cq_path, COMPACTING_SUFFIX));
+        // a compaction interrupted during its swap leaves the previous generation aside
+        Self::restore_dir(&self.log_path);
+        Self::restore_dir(&self.cq_path);
+        match CompactionFiles::open(
+            &self.log_path,
+            &self.cq_path,
+            self.log_file_size,
+            self.cq_file_size,
+        ) {
+            Some(files) => {
+                info!(
+                    "load compaction log {}-{}, {} messages",
+                    self.topic, self.queue_id, files.unit_count
+                );
+                *self.files.write() = files;
+                true
+            }
+            None => {
+                error!(
+                    "load compaction log {}-{} failed",
+                    self.topic, self.queue_id
+                );
+                false
+            }
+        }
+    }
+
+    pub fn get_topic(&self) -> &CheetahString {
+        &self.topic
+    }
+
+    pub fn get_queue_id(&self) -> i32 {
+        self.queue_id
+    }
+
+    pub fn get_min_offset(&self) -> i64 {
+        self.files.read().first_queue_offset()
+    }
+
+    pub fn get_max_offset(&self) -> i64 {
+        self.files.read().last_queue_offset() + 1
+    }
+
+    pub fn get_unit_count(&self) -> i64 {
+        self.files.read().unit_count
+    }
+
+    pub fn get_message(
+        &self,
+        offset: i64,
+        max_msg_nums: i32,
+        max_total_msg_size: i32,
+    ) -> GetMessageResult {
+        let files = self.files.read();
+        let mut get_result = GetMessageResult::new();
+        let min_offset = files.first_queue_offset().max(0);
+        let max_offset = files.last_queue_offset() + 1;
+        let mut next_begin_offset = offset;
+        let status = if files.unit_count == 0 {
+            GetMessageStatus::NoMessageInQueue
+        } else if offset >= max_offset {
+            next_begin_offset = max_offset;
+            if offset == max_offset {
+                GetMessageStatus::OffsetOverflowOne
+            } else {
+                GetMessageStatus::OffsetOverflowBadly
+            }
+        } else {
+            let mut status = GetMessageStatus::NoMatchedMessage;
+            let mut index = files.lower_bound(offset);
+            while index < files.unit_count
+                && get_result.message_count() < max_msg_nums
+                && get_result.buffer_total_size() < max_total_msg_size
+            {
+                let Some(unit) = files.get_unit(index) else {
+                    break;
+                };
+                index += 1;
+                next_begin_offset = unit.queue_offset + 1;
+                let Some(message) = files.get_message(&unit) else {
+                    warn!(
+                        "compaction log {}-{} message at {} not found",
+                        self.topic, self.queue_id, unit.offset_py
+                    );
+                    continue;
+                };
+                get_result.add_message(
+                    SelectMappedBufferResult {
+                        start_offset: unit.offset_py as u64,
+                        size: unit.size_py,
+                        bytes: Some(message),
+                        ..Default::default()
+                    },
+                    unit.queue_offset as u64,
+                    1,
+                );
+                status = GetMessageStatus::Found;
+            }
+            status
+        };
+        get_result.set_status(Some(status));
+        get_result.set_next_begin_offset(next_begin_offset);
+        get_result.set_min_offset(min_offset);
+        get_result.set_max_offset(max_offset);
+        get_result
+    }
+
+    /// Compacts the messages of the queue from `position` on into a new generation and returns
+    /// the queue offset the next compaction starts at.
+    pub fn compact(&self, message_store: &LocalFileMessageStore, position: i64) -> i64 {
+        let _compacting = self.compacting.lock();
+        let Some(consume_queue) = message_store.find_consume_queue(&self.topic, self.queue_id)
+        else {
+            return position;
+        };
+        let files = self.files.read();
+        // messages already in the compacted log are never compacted twice
+        let mut start = position.max(files.last_queue_offset() + 1);
+        let min_offset = consume_queue.get_min_offset_in_queue();
+        if start < min_offset {
+            warn!(
+                "compaction of {}-{} starts at {}, but messages before {} were already deleted",
+                self.topic, self.queue_id, start, min_offset
+            );
+            start = min_offset;
+        }
+        let end = consume_queue.get_max_offset_in_queue();
+        if start >= end {
+            return position;
+        }
+
+        // the latest queue offset of every key
+        let mut offset_map: HashMap<CheetahString, i64> = HashMap::new();
+        for index in 0..files.unit_count {
+            if let Some(unit) = files.get_unit(index) {
+                if let Some(key) = files.get_message(&unit).and_then(Self::get_key) {
+                    offset_map.insert(key, unit.queue_offset);
+                }
+            }
+        }
+        let read_new_message = |visit: &mut dyn FnMut(i64, Bytes)| {
+            let Some(mut iterator) = consume_queue.iterate_from(start) else {
+                return;
+            };
+            for cq_unit in iterator.by_ref() {
+                if cq_unit.queue_offset >= end {
+                    break;
+                }
+                if let Some(message) = message_store
+                    .select_one_message_by_offset_with_size(cq_unit.pos, cq_unit.size)
+                    .and_then(|result| result.get_bytes())
+                {
+                    visit(cq_unit.queue_offset, message);
+                }
+            }
+            iterator.release();
+        };
+        read_new_message(&mut |queue_offset, message| {
+            if let Some(key) = Self::get_key(message) {
+                offset_map.insert(key, queue_offset);
+            }
+        });
+        let is_latest = |queue_offset: i64, message: &Bytes| {
+            Self::get_key(message.clone())
+                .is_some_and(|key| offset_map.get(&key) == Some(&queue_offset))
+        };
+
+        let compacting_log_path = format!("{}{}", self.log_path, COMPACTING_SUFFIX);
+        let compacting_cq_path = format!("{}{}", self.cq_path, COMPACTING_SUFFIX);
+        Self::remove_dir(&compacting_log_path);
+        Self::remove_dir(&compacting_cq_path);
+        let mut compacted = CompactionFiles::new(
+            &compacting_log_path,
+            &compacting_cq_path,
+            self.log_file_size,
+            self.cq_file_size,
+        );
+        let mut success = true;
+        for index in 0..files.unit_count {
+            let Some(unit) = files.get_unit(index) else {
+                continue;
+            };
+            if let Some(message) = files.get_message(&unit) {
+                if is_latest(unit.queue_offset, &message) {
+                    success &= compacted.append(message, unit.queue_offset);
+                }
+            }
+        }
+        read_new_message(&mut |queue_offset, message| {
+            if is_latest(queue_offset, &message) {
+                success &= compacted.append(message, queue_offset);
+            }
+        });
+        if !success {
+            error!("compaction of {}-{} failed", self.topic, self.queue_id);
+            drop(compacted);
+            Self::remove_dir(&compacting_log_path);
+            Self::remove_dir(&compacting_cq_path);
+            return position;
+        }
+        compacted.flush();
+        let unit_count = compacted.unit_count;
+        drop(compacted);
+        drop(files);
+
+        // swap the generations, readers wait for the new one
+        let mut files = self.files.write();
+        *files = CompactionFiles::new(
+            &self.log_path,
+            &self.cq_path,
+            self.log_file_size,
+            self.cq_file_size,
+        );
+        if !Self::swap_dir(&self.log_path, &compacting_log_path)
+            || !Self::swap_dir(&self.cq_path, &compacting_cq_path)
+        {
+            error!(
+                "swap compaction log {}-{} failed",
+                self.topic, self.queue_id
+            );
+        }
+        match CompactionFiles::open(
+            &self.log_path,
+            &self.cq_path,
+            self.log_file_size,
+            self.cq_file_size,
+        ) {
+            Some(new_files) => *files = new_files,
+            None => error!(
+                "reload compaction log {}-{} failed",
+                self.topic, self.queue_id
+            ),
+        }
+        info!(
+            "compaction of {}-{} done, queue offset {} to {}, {} messages retained",
+            self.topic, self.queue_id, start, end, unit_count
+        );
+        end
+    }
+
+    pub fn flush(&self) {
+        self.files.read().flush();
+    }
+
+    fn get_key(mut message: Bytes) -> Option<CheetahString> {
+        MessageDecoder::decode(&mut message, false, false, false, false, false)
+            .and_then(|msg| msg.get_keys())
+            .filter(|keys| !keys.is_empty())
+    }
+
+    fn swap_dir(path: &str, new_path: &str) -> bool {
+        let deleting_path = format!("{}{}", path, DELETING_SUFFIX);
+        Self::remove_dir(&deleting_path);
+        if Path::new(path).exists() {
+            if let Err(e) = fs::rename(path, &deleting_path) {
+                error!("rename {} failed: {}", path, e);
+                return false;
+            }
+        }
+        if Path::new(new_path).exists() {
+            if let Err(e) = fs::rename(new_path, path) {
+                error!("rename {} failed: {}", new_path, e);
+                return false;
+            }
+        }
+        Self::remove_dir(&deleting_path);
+        true
+    }
+
+    fn restore_dir(path: &str) {
+        let deleting_path = format!("{}{}", path, DELETING_SUFFIX);
+        if !Path::new(&deleting_path).exists() {
+            return;
+        }
+        if Path::new(path).exists() {
+            Self::remove_dir(&deleting_path);
+        } else if let Err(e) = fs::rename(&deleting_path, path) {
+            error!("restore {} failed: {}", deleting_path, e);
+        }
+    }
+
+    fn remove_dir(path: &str) {
+        if Path::new(path).exists() {
+            if let Err(e) = fs::remove_dir_all(path) {
+                warn!("remove {} failed: {}", path, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BufMut;
+    use bytes::BytesMut;
+
+    use super::*;
+
+    const TOPIC: &str = "CompactionLogTest";
+    const MESSAGE_SIZE: usize = 48;
+    // two messages per log file, two units per consume queue file
+    const LOG_FILE_SIZE: u64 = 128;
+    const CQ_FILE_SIZE: u64 = CQ_UNIT_SIZE as u64 * 2;
+
+    /// A stored message stand-in, only the length, queue offset and physical offset fields
+    /// are meaningful.
+    fn new_message(queue_offset: i64) -> Bytes {
+        let mut message = BytesMut::with_capacity(MESSAGE_SIZE);
+        message.put_i32(MESSAGE_SIZE as i32);
+        message.put_bytes(0, QUEUE_OFFSET_POSITION - 4);
+        message.put_i64(queue_offset);
+        message.put_i64(-1);
+        message.put_bytes(
+            queue_offset as u8,
+            MESSAGE_SIZE - PHYSICAL_OFFSET_POSITION - 8,
+        );
+        message.freeze()
+    }
+
+    fn new_compaction_log(root: &Path) -> CompactionLog {
+        CompactionLog::new(
+            CheetahString::from_static_str(TOPIC),
+            0,
+            root.join("compactionLog").to_str().unwrap(),
+            root.join("compactionCq").to_str().unwrap(),
+            LOG_FILE_SIZE,
+            CQ_FILE_SIZE,
+        )
+    }
+
+    fn append_messages(compaction_log: &CompactionLog, queue_offsets: &[i64]) {
+        let mut files = compaction_log.files.write();
+        for queue_offset in queue_offsets {
+            assert!(files.append(new_message(*queue_offset), *queue_offset));
+        }
+        files.flush();
+    }
+
+    #[test]
+    fn units_map_queue_offsets_to_rewritten_log_positions() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let compaction_log = new_compaction_log(temp_dir.path());
+        append_messages(&compaction_log, &[3, 7, 8, 20, 21]);
+
+        let files = compaction_log.files.read();
+        assert_eq!(files.unit_count, 5);
+        assert_eq!(files.first_queue_offset(), 3);
+        assert_eq!(files.last_queue_offset(), 21);
+        for (index, queue_offset) in [3i64, 7, 8, 20, 21].into_iter().enumerate() {
+            let unit = files.get_unit(index as i64).unwrap();
+            assert_eq!(unit.queue_offset, queue_offset);
+            assert_eq!(unit.size_py, MESSAGE_SIZE as i32);
+            // every log file holds two messages followed by the end of file blank
+            let expected_offset_py = (index as i64 / 2) * LOG_FILE_SIZE as i64
+                + (index as i64 % 2) * MESSAGE_SIZE as i64;
+            assert_eq!(unit.offset_py, expected_offset_py);
+            let message = files.get_message(&unit).unwrap();
+            assert_eq!(
+                (&message[QUEUE_OFFSET_POSITION..PHYSICAL_OFFSET_POSITION]).get_i64(),
+                queue_offset
+            );
+            assert_eq!(
+                (&message[PHYSICAL_OFFSET_POSITION..PHYSICAL_OFFSET_POSITION + 8]).get_i64(),
+                expected_offset_py
+            );
+        }
+        assert_eq!(files.lower_bound(0), 0);
+        assert_eq!(files.lower_bound(4), 1);
+        assert_eq!(files.lower_bound(8), 2);
+        assert_eq!(files.lower_bound(9), 3);
+        assert_eq!(files.lower_bound(22), 5);
+    }
+
+    #[test]
+    fn get_message_resolves_offsets_inside_gaps() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let compaction_log = new_compaction_log(temp_dir.path());
+        let result = compaction_log.get_message(0, 32, 1024 * 1024);
+        assert_eq!(result.status(), Some(GetMessageStatus::NoMessageInQueue));
+
+        append_messages(&compaction_log, &[3, 7, 8, 20, 21]);
+        assert_eq!(compaction_log.get_min_offset(), 3);
+        assert_eq!(compaction_log.get_max_offset(), 22);
+
+        let result = compaction_log.get_message(4, 2, 1024 * 1024);
+        assert_eq!(result.status(), Some(GetMessageStatus::Found));
+        assert_eq!(result.message_queue_offset(), &vec![7, 8]);
+        assert_eq!(result.next_begin_offset(), 9);
+
+        let result = compaction_log.get_message(9, 32, 1024 * 1024);
+        assert_eq!(result.message_queue_offset(), &vec![20, 21]);
+        assert_eq!(result.next_begin_offset(), 22);
+
+        let result = compaction_log.get_message(22, 32, 1024 * 1024);
+        assert_eq!(result.status(), Some(GetMessageStatus::OffsetOverflowOne));
+        assert_eq!(result.next_begin_offset(), 22);
+        let result = compaction_log.get_message(30, 32, 1024 * 1024);
+        assert_eq!(result.status(), Some(GetMessageStatus::OffsetOverflowBadly));
+        assert_eq!(result.next_begin_offset(), 22);
+    }
+
+    #[test]
+    fn load_replays_the_last_complete_generation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let compaction_log = new_compaction_log(temp_dir.path());
+        append_messages(&compaction_log, &[3, 7, 8]);
+        let log_path = compaction_log.log_path.clone();
+        let cq_path = compaction_log.cq_path.clone();
+        drop(compaction_log);
+
+        // interrupted in the middle of a swap: the previous generation was moved aside and a
+        // half written generation is left behind
+        fs::rename(&log_path, format!("{}{}", log_path, DELETING_SUFFIX)).unwrap();
+        fs::rename(&cq_path, format!("{}{}", cq_path, DELETING_SUFFIX)).unwrap();
+        fs::create_dir_all(format!("{}{}", log_path, COMPACTING_SUFFIX)).unwrap();
+        fs::create_dir_all(format!("{}{}", cq_path, COMPACTING_SUFFIX)).unwrap();
+
+        let reloaded = new_compaction_log(temp_dir.path());
+        assert!(reloaded.load());
+        assert_eq!(reloaded.get_unit_count(), 3);
+        assert_eq!(reloaded.get_min_offset(), 3);
+        assert_eq!(reloaded.get_max_offset(), 9);
+        for path in [&log_path, &cq_path] {
+            assert!(!Path::new(&format!("{}{}", path, DELETING_SUFFIX)).exists());
+            assert!(!Path::new(&format!("{}{}", path, COMPACTING_SUFFIX)).exists());
+        }
+        let result = reloaded.get_message(0, 32, 1024 * 1024);
+        assert_eq!(result.message_queue_offset(), &vec![3, 7, 8]);
+
+        // appending continues after the reloaded units
+        append_messages(&reloaded, &[12]);
+        assert_eq!(reloaded.get_max_offset(), 13);
+    }
+}
@@ -0,0 +1,94 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::info;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompactionPositionSerializeWrapper {
+    queue_offset_map: HashMap<String, i64>,
+}
+
+/// Remembers, for every compacted queue, the consume queue offset compaction has reached.
+pub(crate) struct CompactionPositionMgr {
+    config_path: String,
+    queue_offset_map: Mutex<HashMap<String, i64>>,
+}
+
+impl CompactionPositionMgr {
+    pub fn new(config_path: impl Into<String>) -> Self {
+        Self {
+            config_path: config_path.into(),
+            queue_offset_map: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn set_offset(&self, topic: &CheetahString, queue_id: i32, offset: i64) {
+        self.queue_offset_map
+            .lock()
+            .insert(Self::build_key(topic, queue_id), offset);
+    }
+
+    pub fn get_offset(&self, topic: &CheetahString, queue_id: i32) -> i64 {
+        self.queue_offset_map
+            .lock()
+            .get(&Self::build_key(topic, queue_id))
+            .copied()
+            .unwrap_or(-1)
+    }
+
+    fn build_key(topic: &CheetahString, queue_id: i32) -> String {
+        format!("{}_{}", topic, queue_id)
+    }
+}
+
+impl ConfigManager for CompactionPositionMgr {
+    fn config_file_path(&self) -> String {
+        self.config_path.clone()
+    }
+
+    fn encode_pretty(&self, pretty_format: bool) -> String {
+        let wrapper = CompactionPositionSerializeWrapper {
+            queue_offset_map: self.queue_offset_map.lock().clone(),
+        };
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&wrapper).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&wrapper).expect("encode failed")
+        }
+    }
+
+    fn decode(&self, json_string: &str) {
+        if json_string.is_empty() {
+            return;
+        }
+        info!(
+            "decode CompactionPositionMgr from json string:{}",
+            json_string
+        );
+        let wrapper: CompactionPositionSerializeWrapper =
+            SerdeJsonUtils::from_json_str(json_string).expect("decode failed");
+        *self.queue_offset_map.lock() = wrapper.queue_offset_map;
+    }
+}
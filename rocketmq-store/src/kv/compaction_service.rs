@@ -14,21 +14,251 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::attribute::cleanup_policy::CleanupPolicy;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_common::CleanupPolicyUtils::get_delete_policy;
+use rocketmq_rust::ArcMut;
+use tokio::sync::Notify;
 use tracing::error;
+use tracing::info;
+
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::kv::compaction_store::CompactionStore;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
 
-#[derive(Default, Clone)]
-pub struct CompactionService {}
+/// Periodically compacts every queue of the topics whose cleanup policy is
+/// [`CleanupPolicy::COMPACTION`].
+#[derive(Clone)]
+pub struct CompactionService {
+    message_store_config: Arc<MessageStoreConfig>,
+    topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
+    compaction_store: Arc<CompactionStore>,
+    running: Arc<AtomicBool>,
+    shutdown_notify: Arc<Notify>,
+}
 
 impl CompactionService {
+    pub fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
+        compaction_store: Arc<CompactionStore>,
+    ) -> Self {
+        Self {
+            message_store_config,
+            topic_config_table,
+            compaction_store,
+            running: Arc::new(AtomicBool::new(false)),
+            shutdown_notify: Arc::new(Notify::new()),
+        }
+    }
+
     pub fn load(&mut self, exit_ok: bool) -> bool {
-        error!(
-            "[unimplemented]load compaction service, exit ok: {}",
-            exit_ok
-        );
-        true
+        self.compaction_store.load(exit_ok)
+    }
+
+    pub fn start(&self, message_store: ArcMut<LocalFileMessageStore>) {
+        if self.running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let service = self.clone();
+        tokio::spawn(async move {
+            let interval = Duration::from_millis(
+                service.message_store_config.compaction_schedule_internal as u64,
+            );
+            info!("compaction service started, interval: {:?}", interval);
+            while service.running.load(Ordering::Acquire) {
+                tokio::select! {
+                    _ = service.shutdown_notify.notified() => {}
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                if !service.running.load(Ordering::Acquire) {
+                    break;
+                }
+                let this = service.clone();
+                let message_store = message_store.clone();
+                if let Err(e) =
+                    tokio::task::spawn_blocking(move || this.do_compaction(&message_store)).await
+                {
+                    error!("compaction task failed: {}", e);
+                }
+            }
+            info!("compaction service stopped");
+        });
+    }
+
+    /// Compacts every queue of the compacted topics once.
+    pub fn do_compaction(&self, message_store: &LocalFileMessageStore) {
+        let topics: Vec<(CheetahString, i32)> = self
+            .topic_config_table
+            .lock()
+            .values()
+            .filter(|topic_config| {
+                get_delete_policy(Some(topic_config)) == CleanupPolicy::COMPACTION
+            })
+            .filter_map(|topic_config| {
+                let topic = topic_config.topic_name.clone()?;
+                Some((
+                    topic,
+                    topic_config
+                        .read_queue_nums
+                        .max(topic_config.write_queue_nums) as i32,
+                ))
+            })
+            .collect();
+        for (topic, queue_nums) in topics {
+            for queue_id in 0..queue_nums {
+                self.compaction_store
+                    .compact(&topic, queue_id, message_store);
+            }
+        }
+    }
+
+    pub fn get_compaction_store(&self) -> &Arc<CompactionStore> {
+        &self.compaction_store
     }
 
     pub fn shutdown(&self) {
-        error!("[unimplemented]shutdown compaction service");
+        self.running.store(false, Ordering::Release);
+        self.shutdown_notify.notify_waiters();
+        self.compaction_store.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use rocketmq_common::common::attribute::topic_attributes::TopicAttributes;
+    use rocketmq_common::common::attribute::Attribute;
+    use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+    use rocketmq_common::common::message::MessageConst;
+    use rocketmq_common::common::message::MessageTrait;
+    use rocketmq_common::MessageAccessor::MessageAccessor;
+    use rocketmq_common::MessageDecoder;
+
+    use super::*;
+    use crate::base::message_status_enum::GetMessageStatus;
+    use crate::base::message_status_enum::PutMessageStatus;
+    use crate::base::message_store::MessageStore;
+    use crate::config::flush_disk_type::FlushDiskType;
+    use crate::test_utils::new_message_store_with_topics;
+    use crate::test_utils::wait_until;
+
+    const TOPIC: &str = "CompactionTopicTest";
+
+    async fn put_message(message_store: &ArcMut<LocalFileMessageStore>, key: Option<&str>) {
+        let mut msg = MessageExtBrokerInner::default();
+        msg.set_topic(CheetahString::from_static_str(TOPIC));
+        msg.set_body(Bytes::from_static(b"compaction message"));
+        if let Some(key) = key {
+            MessageAccessor::put_property(
+                &mut msg,
+                CheetahString::from_static_str(MessageConst::PROPERTY_KEYS),
+                CheetahString::from(key),
+            );
+        }
+        msg.properties_string = MessageDecoder::message_properties_to_string(msg.get_properties());
+        let result = message_store.mut_from_ref().put_message(msg).await;
+        assert_eq!(result.put_message_status(), PutMessageStatus::PutOk);
+    }
+
+    fn compacted_keys(compaction_store: &CompactionStore) -> Vec<(u64, String)> {
+        let get_result = compaction_store
+            .get_message(
+                &CheetahString::from_static_str("group"),
+                &CheetahString::from_static_str(TOPIC),
+                0,
+                0,
+                32,
+                1024 * 1024,
+            )
+            .unwrap();
+        assert_eq!(get_result.status(), Some(GetMessageStatus::Found));
+        get_result
+            .message_mapped_list()
+            .iter()
+            .zip(get_result.message_queue_offset())
+            .map(|(select_result, queue_offset)| {
+                let mut bytes = select_result.get_bytes().unwrap();
+                let msg =
+                    MessageDecoder::decode(&mut bytes, true, false, false, false, false).unwrap();
+                (*queue_offset, msg.get_keys().unwrap().to_string())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn keeps_latest_message_of_every_key() {
+        let mut topic_config = TopicConfig::new(TOPIC);
+        topic_config.read_queue_nums = 1;
+        topic_config.write_queue_nums = 1;
+        topic_config.attributes.insert(
+            TopicAttributes::cleanup_policy_attribute().name().into(),
+            CleanupPolicy::COMPACTION.to_string().into(),
+        );
+        let topic_config_table = Arc::new(parking_lot::Mutex::new(HashMap::from([(
+            CheetahString::from_static_str(TOPIC),
+            topic_config,
+        )])));
+        let (mut message_store, _temp_dir) = new_message_store_with_topics(
+            MessageStoreConfig {
+                mapped_file_size_commit_log: 1024 * 64,
+                compaction_mapped_file_size: 1024,
+                compaction_cq_mapped_file_size: 100,
+                enable_compaction: true,
+                flush_disk_type: FlushDiskType::AsyncFlush,
+                ..MessageStoreConfig::default()
+            },
+            topic_config_table.clone(),
+        );
+        let message_store_config = message_store.message_store_config();
+        assert!(message_store.load().await);
+        message_store.start().unwrap();
+
+        for _ in 0..3 {
+            for key in ["k0", "k1", "k2", "k3", "k4"] {
+                put_message(&message_store, Some(key)).await;
+            }
+        }
+        put_message(&message_store, None).await;
+        let topic = CheetahString::from_static_str(TOPIC);
+        assert!(wait_until(|| message_store.get_max_offset_in_queue(&topic, 0) == 16).await);
+
+        let compaction_store = Arc::new(CompactionStore::new(message_store_config.clone()));
+        let compaction_service = CompactionService::new(
+            message_store_config.clone(),
+            topic_config_table.clone(),
+            compaction_store.clone(),
+        );
+        compaction_service.do_compaction(&message_store);
+        assert_eq!(compaction_store.get_compaction_position(&topic, 0), 16);
+        let expected: Vec<(u64, String)> = (10..15)
+            .map(|queue_offset| (queue_offset, format!("k{}", queue_offset - 10)))
+            .collect();
+        assert_eq!(compacted_keys(&compaction_store), expected);
+
+        put_message(&message_store, Some("k0")).await;
+        assert!(wait_until(|| message_store.get_max_offset_in_queue(&topic, 0) == 17).await);
+        compaction_service.do_compaction(&message_store);
+        compaction_service.shutdown();
+        let mut expected: Vec<(u64, String)> = (11..15)
+            .map(|queue_offset| (queue_offset, format!("k{}", queue_offset - 10)))
+            .collect();
+        expected.push((16, "k0".to_string()));
+        assert_eq!(compacted_keys(&compaction_store), expected);
+
+        // the compacted view survives a restart
+        let reloaded = CompactionStore::new(message_store_config);
+        assert!(reloaded.load(true));
+        assert_eq!(reloaded.get_compaction_position(&topic, 0), 17);
+        assert_eq!(compacted_keys(&reloaded), expected);
+
+        message_store.shutdown();
     }
 }
@@ -14,29 +14,145 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fs;
+use std::sync::Arc;
+
 use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::config_manager::ConfigManager;
+use tracing::info;
 
 use crate::base::get_message_result::GetMessageResult;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::kv::compaction_log::CompactionLog;
+use crate::kv::compaction_position_mgr::CompactionPositionMgr;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+use crate::store_path_config_helper::get_compaction_position_path;
+use crate::store_path_config_helper::get_store_path_compaction_consume_queue;
+use crate::store_path_config_helper::get_store_path_compaction_log;
 
-pub struct CompactionStore;
+/// Holds the compacted view of every queue of the compacted topics.
+pub struct CompactionStore {
+    message_store_config: Arc<MessageStoreConfig>,
+    compaction_log_path: String,
+    compaction_cq_path: String,
+    compaction_log_table: Mutex<HashMap<(CheetahString, i32), Arc<CompactionLog>>>,
+    position_mgr: CompactionPositionMgr,
+}
 
 impl CompactionStore {
-    pub fn new() -> Self {
-        CompactionStore
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        let root_dir = message_store_config.store_path_root_dir.as_str();
+        Self {
+            compaction_log_path: get_store_path_compaction_log(root_dir),
+            compaction_cq_path: get_store_path_compaction_consume_queue(root_dir),
+            position_mgr: CompactionPositionMgr::new(get_compaction_position_path(root_dir)),
+            compaction_log_table: Mutex::new(HashMap::new()),
+            message_store_config,
+        }
+    }
+
+    /// Loads the compaction logs found under the compaction log directory.
+    pub fn load(&self, exit_ok: bool) -> bool {
+        // the position file does not exist until the first compaction
+        self.position_mgr.load();
+        let Ok(topic_dirs) = fs::read_dir(&self.compaction_log_path) else {
+            return true;
+        };
+        let mut result = true;
+        for topic_dir in topic_dirs.filter_map(Result::ok) {
+            let topic = topic_dir.file_name().to_string_lossy().to_string();
+            let Ok(queue_dirs) = fs::read_dir(topic_dir.path()) else {
+                continue;
+            };
+            // leftovers of an interrupted compaction are handled by the log itself
+            let queue_ids: HashSet<i32> = queue_dirs
+                .filter_map(Result::ok)
+                .filter_map(|queue_dir| {
+                    let name = queue_dir.file_name().to_string_lossy().to_string();
+                    name.split('.').next()?.parse::<i32>().ok()
+                })
+                .collect();
+            for queue_id in queue_ids {
+                let compaction_log =
+                    self.get_or_create_log(&CheetahString::from(topic.as_str()), queue_id);
+                result &= compaction_log.load();
+            }
+        }
+        info!(
+            "load compaction store, exit ok: {}, {} compaction logs",
+            exit_ok,
+            self.compaction_log_table.lock().len()
+        );
+        result
     }
-}
 
-#[allow(unused_variables)]
-impl CompactionStore {
     pub fn get_message(
         &self,
-        group: &CheetahString,
+        _group: &CheetahString,
         topic: &CheetahString,
         queue_id: i32,
         offset: i64,
         max_msg_nums: i32,
         max_total_msg_size: i32,
     ) -> Option<GetMessageResult> {
-        None
+        let compaction_log = self
+            .compaction_log_table
+            .lock()
+            .get(&(topic.clone(), queue_id))
+            .cloned()?;
+        Some(compaction_log.get_message(offset, max_msg_nums, max_total_msg_size))
+    }
+
+    /// Compacts the messages appended to the queue since its last compaction.
+    pub fn compact(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+        message_store: &LocalFileMessageStore,
+    ) {
+        let compaction_log = self.get_or_create_log(topic, queue_id);
+        let position = self.position_mgr.get_offset(topic, queue_id);
+        let next_position = compaction_log.compact(message_store, position);
+        if next_position != position {
+            self.position_mgr.set_offset(topic, queue_id, next_position);
+            self.position_mgr.persist();
+        }
+    }
+
+    /// The queue offset the next compaction of the queue starts at, `-1` if it was never
+    /// compacted.
+    pub fn get_compaction_position(&self, topic: &CheetahString, queue_id: i32) -> i64 {
+        self.position_mgr.get_offset(topic, queue_id)
+    }
+
+    pub fn flush(&self) {
+        for compaction_log in self.compaction_log_table.lock().values() {
+            compaction_log.flush();
+        }
+        self.position_mgr.persist();
+    }
+
+    pub fn shutdown(&self) {
+        self.flush();
+    }
+
+    fn get_or_create_log(&self, topic: &CheetahString, queue_id: i32) -> Arc<CompactionLog> {
+        self.compaction_log_table
+            .lock()
+            .entry((topic.clone(), queue_id))
+            .or_insert_with(|| {
+                Arc::new(CompactionLog::new(
+                    topic.clone(),
+                    queue_id,
+                    &self.compaction_log_path,
+                    &self.compaction_cq_path,
+                    self.message_store_config.compaction_mapped_file_size as u64,
+                    self.message_store_config.compaction_cq_mapped_file_size as u64,
+                ))
+            })
+            .clone()
     }
 }
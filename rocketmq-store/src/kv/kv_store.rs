@@ -0,0 +1,574 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::ops::Bound;
+use std::path::Path;
+use std::path::PathBuf;
+
+use parking_lot::Mutex;
+use parking_lot::RwLock;
+use rocketmq_common::utils::crc32_utils::crc32;
+use tracing::info;
+use tracing::warn;
+
+use crate::store_error::StoreError;
+
+const DATA_FILE_NAME: &str = "data.log";
+const COMPACT_FILE_NAME: &str = "data.log.compacting";
+/// Size of the `length + crc` header in front of every write batch record.
+const RECORD_HEADER_SIZE: usize = 8;
+/// The data log is rewritten once it grows past this size and holds more garbage than live data.
+const COMPACT_THRESHOLD_BYTES: u64 = 64 * 1024 * 1024;
+
+const OP_PUT: u8 = 1;
+const OP_DELETE: u8 = 2;
+const OP_DELETE_RANGE: u8 = 3;
+
+enum WriteOp {
+    Put(Vec<u8>, Vec<u8>),
+    Delete(Vec<u8>),
+    /// Deletes every key in `[start, end)`.
+    DeleteRange(Vec<u8>, Vec<u8>),
+}
+
+/// A group of mutations applied to a [`KvStore`] atomically.
+#[derive(Default)]
+pub(crate) struct WriteBatch {
+    ops: Vec<WriteOp>,
+}
+
+impl WriteBatch {
+    pub fn put(&mut self, key: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) {
+        self.ops.push(WriteOp::Put(key.into(), value.into()));
+    }
+
+    pub fn delete(&mut self, key: impl Into<Vec<u8>>) {
+        self.ops.push(WriteOp::Delete(key.into()));
+    }
+
+    pub fn delete_range(&mut self, start: impl Into<Vec<u8>>, end: impl Into<Vec<u8>>) {
+        self.ops
+            .push(WriteOp::DeleteRange(start.into(), end.into()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut payload = Vec::new();
+        for op in &self.ops {
+            match op {
+                WriteOp::Put(key, value) => {
+                    payload.push(OP_PUT);
+                    put_slice(&mut payload, key);
+                    put_slice(&mut payload, value);
+                }
+                WriteOp::Delete(key) => {
+                    payload.push(OP_DELETE);
+                    put_slice(&mut payload, key);
+                }
+                WriteOp::DeleteRange(start, end) => {
+                    payload.push(OP_DELETE_RANGE);
+                    put_slice(&mut payload, start);
+                    put_slice(&mut payload, end);
+                }
+            }
+        }
+        payload
+    }
+
+    fn decode(mut payload: &[u8]) -> Option<Self> {
+        let mut batch = WriteBatch::default();
+        while let Some((&op, rest)) = payload.split_first() {
+            payload = rest;
+            let first = take_slice(&mut payload)?;
+            match op {
+                OP_PUT => batch.put(first, take_slice(&mut payload)?),
+                OP_DELETE => batch.delete(first),
+                OP_DELETE_RANGE => batch.delete_range(first, take_slice(&mut payload)?),
+                _ => return None,
+            }
+        }
+        Some(batch)
+    }
+}
+
+fn put_slice(buf: &mut Vec<u8>, data: &[u8]) {
+    buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    buf.extend_from_slice(data);
+}
+
+fn take_slice(buf: &mut &[u8]) -> Option<Vec<u8>> {
+    if buf.len() < 4 {
+        return None;
+    }
+    let len = u32::from_be_bytes(buf[..4].try_into().ok()?) as usize;
+    if buf.len() < 4 + len {
+        return None;
+    }
+    let data = buf[4..4 + len].to_vec();
+    *buf = &buf[4 + len..];
+    Some(data)
+}
+
+type KvTable = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Applies `op` to `table`, returning the change of the live data size.
+fn apply_op(table: &mut KvTable, op: WriteOp) -> i64 {
+    match op {
+        WriteOp::Put(key, value) => {
+            let key_len = key.len() as i64;
+            let mut delta = key_len + value.len() as i64;
+            if let Some(old) = table.insert(key, value) {
+                delta -= key_len + old.len() as i64;
+            }
+            delta
+        }
+        WriteOp::Delete(key) => table
+            .remove(&key)
+            .map_or(0, |old| -((key.len() + old.len()) as i64)),
+        WriteOp::DeleteRange(start, end) => {
+            if start >= end {
+                return 0;
+            }
+            let mut removed = table.split_off(&start);
+            let mut rest = removed.split_off(&end);
+            table.append(&mut rest);
+            -removed
+                .iter()
+                .map(|(key, value)| (key.len() + value.len()) as i64)
+                .sum::<i64>()
+        }
+    }
+}
+
+fn encode_record(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    record.extend_from_slice(&crc32(payload).to_be_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+/// The append side of the data log.
+#[derive(Default)]
+struct DataLog {
+    file: Option<File>,
+    log_size: u64,
+    live_size: u64,
+    /// Whether records were appended since the last sync.
+    dirty: bool,
+}
+
+/// A compacted copy of the table, written to [`COMPACT_FILE_NAME`] while writes go on.
+struct Snapshot {
+    /// Size of the data log the snapshot is consistent with.
+    log_size: u64,
+    compacted_size: u64,
+}
+
+/// An embedded, ordered key-value store.
+///
+/// Every write batch is appended to a single data log as one checksummed record, and the live
+/// key space is indexed in memory, so thousands of logical tables can share one file. The log is
+/// replayed on load, a torn tail record is dropped, and the log is rewritten once it is mostly
+/// garbage.
+///
+/// Records go to the page cache as they are written, like the mapped consume queue files, and
+/// only [`KvStore::flush`] syncs them to disk. Readers only contend with the in-memory apply of a
+/// batch; the log append is serialized separately, and compaction rewrites a snapshot of the
+/// table without blocking either.
+pub(crate) struct KvStore {
+    dir: PathBuf,
+    table: RwLock<KvTable>,
+    log: Mutex<DataLog>,
+    /// Held for the whole of a compaction or destroy.
+    maintenance: Mutex<()>,
+}
+
+impl KvStore {
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            table: RwLock::new(BTreeMap::new()),
+            log: Mutex::new(DataLog::default()),
+            maintenance: Mutex::new(()),
+        }
+    }
+
+    /// Replays the data log and opens it for appending.
+    pub fn load(&self) -> Result<(), StoreError> {
+        fs::create_dir_all(&self.dir).map_err(io_error)?;
+        let data_file = self.dir.join(DATA_FILE_NAME);
+        let (table, valid_size) = Self::replay(&data_file)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&data_file)
+            .map_err(io_error)?;
+        if file.metadata().map_err(io_error)?.len() > valid_size {
+            warn!(
+                "Truncate torn tail of kv data log {}, valid size {}",
+                data_file.display(),
+                valid_size
+            );
+            file.set_len(valid_size).map_err(io_error)?;
+        }
+        let live_size = table
+            .iter()
+            .map(|(key, value)| (key.len() + value.len()) as u64)
+            .sum();
+        info!(
+            "Load kv store {}, {} keys, log size {}",
+            self.dir.display(),
+            table.len(),
+            valid_size
+        );
+        let mut log = self.log.lock();
+        *self.table.write() = table;
+        *log = DataLog {
+            file: Some(file),
+            log_size: valid_size,
+            live_size,
+            dirty: false,
+        };
+        Ok(())
+    }
+
+    fn replay(data_file: &Path) -> Result<(KvTable, u64), StoreError> {
+        let mut table = BTreeMap::new();
+        let file = match File::open(data_file) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((table, 0)),
+            Err(e) => return Err(io_error(e)),
+        };
+        let mut reader = BufReader::new(file);
+        let mut valid_size = 0u64;
+        let mut header = [0u8; RECORD_HEADER_SIZE];
+        loop {
+            if reader.read_exact(&mut header).is_err() {
+                break;
+            }
+            let len = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+            let crc = u32::from_be_bytes(header[4..].try_into().unwrap());
+            let mut payload = vec![0u8; len];
+            if reader.read_exact(&mut payload).is_err() || crc32(&payload) != crc {
+                break;
+            }
+            let Some(batch) = WriteBatch::decode(&payload) else {
+                break;
+            };
+            for op in batch.ops {
+                apply_op(&mut table, op);
+            }
+            valid_size += (RECORD_HEADER_SIZE + len) as u64;
+        }
+        Ok((table, valid_size))
+    }
+
+    pub fn write(&self, batch: WriteBatch) -> Result<(), StoreError> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let record = encode_record(&batch.encode());
+        let need_compact = {
+            let mut log = self.log.lock();
+            let Some(file) = log.file.as_mut() else {
+                return Err(StoreError::NotStarted);
+            };
+            file.write_all(&record).map_err(io_error)?;
+            log.log_size += record.len() as u64;
+            log.dirty = true;
+            let mut table = self.table.write();
+            for op in batch.ops {
+                let delta = apply_op(&mut table, op);
+                log.live_size = log.live_size.saturating_add_signed(delta);
+            }
+            log.log_size > COMPACT_THRESHOLD_BYTES && log.log_size > 2 * log.live_size
+        };
+        if need_compact {
+            // a compaction already running will pick this garbage up next time
+            if let Some(_maintenance) = self.maintenance.try_lock() {
+                if let Err(e) = self.compact_unguarded() {
+                    warn!("Compact kv store {} failed: {}", self.dir.display(), e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn put(
+        &self,
+        key: impl Into<Vec<u8>>,
+        value: impl Into<Vec<u8>>,
+    ) -> Result<(), StoreError> {
+        let mut batch = WriteBatch::default();
+        batch.put(key, value);
+        self.write(batch)
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.table.read().get(key).cloned()
+    }
+
+    /// Returns at most `limit` entries of `[start, end)` in key order.
+    pub fn scan(&self, start: &[u8], end: &[u8], limit: usize) -> Vec<(Vec<u8>, Vec<u8>)> {
+        if start >= end {
+            return Vec::new();
+        }
+        self.table
+            .read()
+            .range::<[u8], _>((Bound::Included(start), Bound::Excluded(end)))
+            .take(limit)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    /// The first entry of `[start, end)`.
+    pub fn first(&self, start: &[u8], end: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.scan(start, end, 1).pop()
+    }
+
+    /// The last entry of `[start, end)`.
+    pub fn last(&self, start: &[u8], end: &[u8]) -> Option<(Vec<u8>, Vec<u8>)> {
+        if start >= end {
+            return None;
+        }
+        self.table
+            .read()
+            .range::<[u8], _>((Bound::Included(start), Bound::Excluded(end)))
+            .next_back()
+            .map(|(key, value)| (key.clone(), value.clone()))
+    }
+
+    /// Syncs the data log to disk, a no-op when nothing was written since the last sync.
+    pub fn flush(&self) -> Result<(), StoreError> {
+        let file = {
+            let mut log = self.log.lock();
+            if !log.dirty {
+                return Ok(());
+            }
+            let Some(file) = log.file.as_ref() else {
+                return Ok(());
+            };
+            let file = file.try_clone().map_err(io_error)?;
+            log.dirty = false;
+            file
+        };
+        // sync outside the lock so that writers are not held up by the disk
+        file.sync_data().map_err(|e| {
+            self.log.lock().dirty = true;
+            io_error(e)
+        })
+    }
+
+    /// Rewrites the data log so that it only holds the live entries.
+    pub fn compact(&self) -> Result<(), StoreError> {
+        let _maintenance = self.maintenance.lock();
+        self.compact_unguarded()
+    }
+
+    /// Must be called with `maintenance` held.
+    fn compact_unguarded(&self) -> Result<(), StoreError> {
+        let snapshot = self.write_snapshot()?;
+        self.install_snapshot(snapshot)
+    }
+
+    /// Writes the live entries to the compact file. Only the table copy is taken under the log
+    /// lock, so writes go on while the file is written.
+    fn write_snapshot(&self) -> Result<Snapshot, StoreError> {
+        let (table, log_size) = {
+            let log = self.log.lock();
+            if log.file.is_none() {
+                return Err(StoreError::NotStarted);
+            }
+            // the table matches the log exactly while the log lock is held
+            (self.table.read().clone(), log.log_size)
+        };
+        let compact_file = self.dir.join(COMPACT_FILE_NAME);
+        let mut writer = BufWriter::new(File::create(&compact_file).map_err(io_error)?);
+        let mut compacted_size = 0u64;
+        let mut batch = WriteBatch::default();
+        let mut flush_batch = |batch: &mut WriteBatch, writer: &mut BufWriter<File>| {
+            let record = encode_record(&batch.encode());
+            batch.ops.clear();
+            writer.write_all(&record)?;
+            compacted_size += record.len() as u64;
+            std::io::Result::Ok(())
+        };
+        for (key, value) in table {
+            batch.put(key, value);
+            if batch.ops.len() >= 4096 {
+                flush_batch(&mut batch, &mut writer).map_err(io_error)?;
+            }
+        }
+        if !batch.is_empty() {
+            flush_batch(&mut batch, &mut writer).map_err(io_error)?;
+        }
+        writer.flush().map_err(io_error)?;
+        Ok(Snapshot {
+            log_size,
+            compacted_size,
+        })
+    }
+
+    /// Appends the records written since `snapshot` was taken to the compact file and swaps it
+    /// in as the data log.
+    fn install_snapshot(&self, snapshot: Snapshot) -> Result<(), StoreError> {
+        let compact_file = self.dir.join(COMPACT_FILE_NAME);
+        let data_file = self.dir.join(DATA_FILE_NAME);
+        let mut log = self.log.lock();
+        if log.file.is_none() {
+            return Err(StoreError::NotStarted);
+        }
+        let mut tail = Vec::with_capacity((log.log_size - snapshot.log_size) as usize);
+        let mut reader = File::open(&data_file).map_err(io_error)?;
+        reader
+            .seek(SeekFrom::Start(snapshot.log_size))
+            .map_err(io_error)?;
+        reader.read_to_end(&mut tail).map_err(io_error)?;
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&compact_file)
+            .map_err(io_error)?;
+        file.write_all(&tail).map_err(io_error)?;
+        file.sync_data().map_err(io_error)?;
+        fs::rename(&compact_file, &data_file).map_err(io_error)?;
+        let log_size = snapshot.compacted_size + tail.len() as u64;
+        info!(
+            "Compact kv store {}, log size {} -> {}",
+            self.dir.display(),
+            log.log_size,
+            log_size
+        );
+        log.file = Some(file);
+        log.log_size = log_size;
+        log.dirty = false;
+        Ok(())
+    }
+
+    /// Removes every entry together with the data log.
+    pub fn destroy(&self) -> Result<(), StoreError> {
+        let _maintenance = self.maintenance.lock();
+        let mut log = self.log.lock();
+        self.table.write().clear();
+        log.live_size = 0;
+        log.log_size = 0;
+        log.dirty = false;
+        match log.file.as_ref() {
+            Some(file) => file.set_len(0).map_err(io_error),
+            None => Ok(()),
+        }
+    }
+}
+
+fn io_error(e: std::io::Error) -> StoreError {
+    StoreError::General(format!("kv store io error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_store(dir: &Path) -> KvStore {
+        let store = KvStore::new(dir);
+        store.load().unwrap();
+        store
+    }
+
+    #[test]
+    fn kv_store_replays_batches_and_drops_torn_tail() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        {
+            let store = open_store(temp_dir.path());
+            let mut batch = WriteBatch::default();
+            for i in 0u8..10 {
+                batch.put(vec![b'k', i], vec![i]);
+            }
+            store.write(batch).unwrap();
+            let mut batch = WriteBatch::default();
+            batch.delete_range(vec![b'k', 2], vec![b'k', 5]);
+            batch.delete(vec![b'k', 9]);
+            store.write(batch).unwrap();
+            store.flush().unwrap();
+        }
+        // a half written record at the tail is ignored on open
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(temp_dir.path().join(DATA_FILE_NAME))
+            .unwrap();
+        file.write_all(&[0, 0, 0, 100, 1, 2]).unwrap();
+        drop(file);
+
+        let store = open_store(temp_dir.path());
+        let keys: Vec<u8> = store
+            .scan(b"k", b"l", usize::MAX)
+            .into_iter()
+            .map(|(key, _)| key[1])
+            .collect();
+        assert_eq!(keys, vec![0, 1, 5, 6, 7, 8]);
+        assert_eq!(store.last(b"k", b"l").unwrap().1, vec![8]);
+
+        store.compact().unwrap();
+        store.put(vec![b'k', 20], vec![20]).unwrap();
+        drop(store);
+        let store = open_store(temp_dir.path());
+        assert_eq!(store.scan(b"k", b"l", usize::MAX).len(), 7);
+        assert_eq!(store.first(b"k", b"l").unwrap().0, vec![b'k', 0]);
+    }
+
+    #[test]
+    fn kv_store_keeps_writes_made_during_compaction() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store = open_store(temp_dir.path());
+        for i in 0u8..10 {
+            store.put(vec![b'k', i], vec![i]).unwrap();
+        }
+        let mut batch = WriteBatch::default();
+        batch.delete_range(vec![b'k', 0], vec![b'k', 8]);
+        store.write(batch).unwrap();
+
+        let snapshot = store.write_snapshot().unwrap();
+        // written after the snapshot, so only present in the tail of the old log
+        store.put(vec![b'k', 20], vec![20]).unwrap();
+        let mut batch = WriteBatch::default();
+        batch.delete(vec![b'k', 8]);
+        store.write(batch).unwrap();
+        store.install_snapshot(snapshot).unwrap();
+        store.put(vec![b'k', 21], vec![21]).unwrap();
+        store.flush().unwrap();
+        drop(store);
+
+        let store = open_store(temp_dir.path());
+        let keys: Vec<u8> = store
+            .scan(b"k", b"l", usize::MAX)
+            .into_iter()
+            .map(|(key, _)| key[1])
+            .collect();
+        assert_eq!(keys, vec![9, 20, 21]);
+    }
+}
@@ -21,6 +21,7 @@
 pub mod base;
 pub mod config;
 pub mod consume_queue;
+pub mod dledger;
 pub mod filter;
 pub mod ha;
 pub mod hook;
@@ -36,5 +37,7 @@ pub mod stats;
 pub mod store;
 pub mod store_error;
 pub mod store_path_config_helper;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod timer;
 pub mod utils;
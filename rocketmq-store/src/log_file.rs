@@ -17,6 +17,7 @@
 
 pub(crate) mod cold_data_check_service;
 pub mod commit_log;
+pub mod dledger_commit_log;
 pub mod flush_manager_impl;
 pub mod mapped_file;
 
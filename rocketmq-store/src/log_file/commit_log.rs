@@ -22,6 +22,7 @@ use std::collections::HashMap;
 use std::mem;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::time::Duration;
 
 use bytes::Buf;
 use bytes::Bytes;
@@ -47,6 +48,7 @@ use rocketmq_common::MessageDecoder;
 use rocketmq_common::MessageDecoder::string_to_message_properties;
 use rocketmq_common::MessageDecoder::MESSAGE_MAGIC_CODE_POSITION;
 use rocketmq_common::MessageDecoder::MESSAGE_MAGIC_CODE_V2;
+use rocketmq_common::MessageDecoder::MESSAGE_PHYSIC_OFFSET_POSITION;
 use rocketmq_common::MessageDecoder::SYSFLAG_POSITION;
 use rocketmq_common::TimeUtils::get_current_millis;
 use rocketmq_common::UtilAll::time_millis_to_human_string;
@@ -71,8 +73,10 @@ use crate::base::swappable::Swappable;
 use crate::base::topic_queue_lock::TopicQueueLock;
 use crate::config::message_store_config::MessageStoreConfig;
 use crate::consume_queue::mapped_file_queue::MappedFileQueue;
+use crate::ha::ha_service::HAService;
 use crate::log_file::cold_data_check_service::ColdDataCheckService;
 use crate::log_file::flush_manager_impl::defalut_flush_manager::DefaultFlushManager;
+use crate::log_file::flush_manager_impl::group_commit_request::GroupCommitRequest;
 use crate::log_file::mapped_file::default_mapped_file_impl::DefaultMappedFile;
 use crate::log_file::mapped_file::MappedFile;
 use crate::message_encoder::message_ext_encoder::MessageExtEncoder;
@@ -348,10 +352,16 @@ impl CommitLog {
         } else {
             0
         };
-        let need_ack_nums = self.message_store_config.in_sync_replicas;
+        let mut need_ack_nums = self.message_store_config.in_sync_replicas;
         let need_handle_ha = self.need_handle_ha(&msg_batch.message_ext_broker_inner);
         if need_handle_ha && self.broker_config.enable_controller_mode {
-            unimplemented!("controller mode not support HA")
+            let in_sync_replicas_nums = self.in_sync_replicas_nums(curr_offset as i64);
+            if in_sync_replicas_nums < self.message_store_config.min_in_sync_replicas as i32 {
+                return PutMessageResult::new_default(PutMessageStatus::InSyncReplicasNotEnough);
+            }
+            if self.message_store_config.all_ack_in_sync_state_set {
+                need_ack_nums = in_sync_replicas_nums as u32;
+            }
         } else if need_handle_ha && self.broker_config.enable_slave_acting_master {
             unimplemented!("slave acting master not support HA")
         }
@@ -555,10 +565,16 @@ impl CommitLog {
         } else {
             0
         };
-        let need_ack_nums = self.message_store_config.in_sync_replicas;
+        let mut need_ack_nums = self.message_store_config.in_sync_replicas;
         let need_handle_ha = self.need_handle_ha(&msg);
         if need_handle_ha && self.broker_config.enable_controller_mode {
-            unimplemented!("controller mode not support HA")
+            let in_sync_replicas_nums = self.in_sync_replicas_nums(curr_offset as i64);
+            if in_sync_replicas_nums < self.message_store_config.min_in_sync_replicas as i32 {
+                return PutMessageResult::new_default(PutMessageStatus::InSyncReplicasNotEnough);
+            }
+            if self.message_store_config.all_ack_in_sync_state_set {
+                need_ack_nums = in_sync_replicas_nums as u32;
+            }
         } else if need_handle_ha && self.broker_config.enable_slave_acting_master {
             unimplemented!("slave acting master not support HA")
         }
@@ -761,6 +777,24 @@ impl CommitLog {
         put_message_result
     }
 
+    /// The number of replicas, the master included, that are in sync with `master_put_where`.
+    fn in_sync_replicas_nums(&self, master_put_where: i64) -> i32 {
+        self.local_file_message_store
+            .as_ref()
+            .and_then(|store| store.get_ha_service())
+            .map_or(1, |ha_service| {
+                ha_service.in_sync_replicas_nums(master_put_where)
+            })
+    }
+
+    /// Drops the data after `phy_offset`, used when a slave truncates to the consistent point
+    /// with its new master.
+    pub fn truncate_dirty_files(&mut self, phy_offset: i64) {
+        self.mapped_file_queue.set_flushed_where(phy_offset);
+        self.mapped_file_queue.set_committed_where(phy_offset);
+        self.mapped_file_queue.truncate_dirty_files(phy_offset);
+    }
+
     async fn handle_ha(
         &self,
         put_message_result: &AppendMessageResult,
@@ -770,9 +804,40 @@ impl CommitLog {
             return PutMessageStatus::PutOk;
         }
 
-        //HA service to do unimplemented
-
-        PutMessageStatus::PutOk
+        let Some(ha_service) = self
+            .local_file_message_store
+            .as_ref()
+            .and_then(|store| store.get_ha_service())
+        else {
+            return PutMessageStatus::PutOk;
+        };
+        let next_offset = put_message_result.wrote_offset + put_message_result.wrote_bytes as i64;
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let request = GroupCommitRequest::with_ack_nums(
+            next_offset,
+            self.message_store_config.slave_timeout as u64,
+            need_ack_nums as i32,
+            tx,
+        );
+        ha_service.put_request(request);
+        ha_service.get_wait_notify_object().wakeup_all();
+        match tokio::time::timeout(
+            Duration::from_millis(self.message_store_config.slave_timeout as u64),
+            rx,
+        )
+        .await
+        {
+            Ok(Ok(status)) => {
+                if status != PutMessageStatus::PutOk {
+                    error!(
+                        "do sync transfer other node, wait return, but failed, offset: {}",
+                        next_offset
+                    );
+                }
+                status
+            }
+            _ => PutMessageStatus::FlushSlaveTimeout,
+        }
     }
 
     async fn handle_disk_flush(
@@ -808,7 +873,7 @@ impl CommitLog {
 
     fn on_commit_log_dispatch(
         &mut self,
-        request: &DispatchRequest,
+        request: &mut DispatchRequest,
         do_dispatch: bool,
         is_recover: bool,
         is_file_end: bool,
@@ -862,7 +927,7 @@ impl CommitLog {
                     break;
                 }
                 let mut msg_bytes = msg.unwrap();
-                let dispatch_request = check_message_and_return_size(
+                let mut dispatch_request = check_message_and_return_size(
                     &mut msg_bytes,
                     check_crc_on_recover,
                     check_dup_info,
@@ -881,12 +946,12 @@ impl CommitLog {
                 if dispatch_request.success && dispatch_request.msg_size > 0 {
                     last_valid_msg_phy_offset = process_offset + mapped_file_offset;
                     mapped_file_offset += dispatch_request.msg_size as u64;
-                    self.on_commit_log_dispatch(&dispatch_request, do_dispatch, true, false);
+                    self.on_commit_log_dispatch(&mut dispatch_request, do_dispatch, true, false);
                 } else if dispatch_request.success && dispatch_request.msg_size == 0 {
                     // Come the end of the file, switch to the next file Since the
                     // return 0 representatives met last hole,
                     // this can not be included in truncate offset
-                    self.on_commit_log_dispatch(&dispatch_request, do_dispatch, true, true);
+                    self.on_commit_log_dispatch(&mut dispatch_request, do_dispatch, true, true);
                     index += 1;
                     if index >= mapped_files_inner.len() {
                         info!(
@@ -913,9 +978,8 @@ impl CommitLog {
                 }
             }
             process_offset += mapped_file_offset;
-            if broker_config.enable_controller_mode {
-                unimplemented!();
-            } else {
+            // in controller mode the confirm offset follows the max offset, see get_confirm_offset
+            if !broker_config.enable_controller_mode {
                 self.set_confirm_offset(last_valid_msg_phy_offset as i64);
             }
 
@@ -969,9 +1033,7 @@ impl CommitLog {
 
     //Fetch and compute the newest confirmOffset.
     pub fn get_confirm_offset(&self) -> i64 {
-        if self.broker_config.enable_controller_mode {
-            unimplemented!()
-        } else if self.broker_config.duplication_enable {
+        if self.broker_config.duplication_enable {
             return self.confirm_offset;
         }
         self.get_max_offset()
@@ -998,6 +1060,7 @@ impl CommitLog {
                     &self.message_store_config,
                     mapped_file,
                     &self.store_checkpoint,
+                    max_phy_offset_of_consume_queue,
                 ) {
                     break;
                 }
@@ -1024,7 +1087,7 @@ impl CommitLog {
                     break;
                 }
                 let mut msg_bytes = msg.unwrap();
-                let dispatch_request = check_message_and_return_size(
+                let mut dispatch_request = check_message_and_return_size(
                     &mut msg_bytes,
                     check_crc_on_recover,
                     check_dup_info,
@@ -1051,7 +1114,7 @@ impl CommitLog {
                             <= self.get_confirm_offset()
                         {
                             self.on_commit_log_dispatch(
-                                &dispatch_request,
+                                &mut dispatch_request,
                                 do_dispatch,
                                 true,
                                 false,
@@ -1060,13 +1123,18 @@ impl CommitLog {
                                 dispatch_request.commit_log_offset as u64 + size as u64;
                         }
                     } else {
-                        self.on_commit_log_dispatch(&dispatch_request, do_dispatch, true, false);
+                        self.on_commit_log_dispatch(
+                            &mut dispatch_request,
+                            do_dispatch,
+                            true,
+                            false,
+                        );
                     }
                 } else if dispatch_request.success && dispatch_request.msg_size == 0 {
                     // Come the end of the file, switch to the next file Since the
                     // return 0 representatives met last hole,
                     // this can not be included in truncate offset
-                    self.on_commit_log_dispatch(&dispatch_request, do_dispatch, true, true);
+                    self.on_commit_log_dispatch(&mut dispatch_request, do_dispatch, true, true);
                     index += 1;
                     if index >= mapped_files_inner.len() {
                         info!(
@@ -1097,12 +1165,12 @@ impl CommitLog {
             // this.getMessageStore().finishCommitLogDispatch();
 
             process_offset += mapped_file_offset;
+            // in controller mode the confirm offset follows the max offset, see get_confirm_offset
             if broker_config.enable_controller_mode {
-                println!(
-                    "TODO: finishCommitLogDispatch:{}",
+                info!(
+                    "recover abnormally in controller mode, last confirm valid msg phy offset {}",
                     last_confirm_valid_msg_phy_offset
                 );
-                unimplemented!();
             } else {
                 self.set_confirm_offset(last_valid_msg_phy_offset as i64);
             }
@@ -1189,6 +1257,28 @@ impl CommitLog {
         self.mapped_file_queue.check_self();
     }
 
+    pub fn delete_expired_file(
+        &self,
+        expired_time: i64,
+        delete_files_interval: i32,
+        interval_forcibly: i64,
+        clean_immediately: bool,
+        delete_file_batch_max: i32,
+    ) -> i32 {
+        self.mapped_file_queue.delete_expired_file_by_time(
+            expired_time,
+            delete_files_interval,
+            interval_forcibly,
+            clean_immediately,
+            delete_file_batch_max,
+        )
+    }
+
+    pub fn retry_delete_first_file(&self, interval_forcibly: i64) -> bool {
+        self.mapped_file_queue
+            .retry_delete_first_file(interval_forcibly)
+    }
+
     pub fn lock_time_mills(&self) -> i64 {
         let begin = self
             .begin_time_in_lock
@@ -1232,14 +1322,38 @@ impl CommitLog {
         }
     }
 
-    pub fn append_data(
+    pub async fn append_data(
         &self,
         start_offset: i64,
         data: &[u8],
         data_start: i32,
         data_length: i32,
     ) -> Result<bool, StoreError> {
-        unimplemented!("append_data not implemented")
+        let _lock = self.put_message_lock.lock().await;
+        let mapped_file = self
+            .mapped_file_queue
+            .mut_from_ref()
+            .get_last_mapped_file_mut_start_offset(start_offset as u64, true);
+        let Some(mapped_file) = mapped_file else {
+            error!(
+                "appendData getLastMappedFile error, start offset: {}",
+                start_offset
+            );
+            return Ok(false);
+        };
+        let remaining = mapped_file.get_file_size() as i32 - mapped_file.get_wrote_position();
+        if remaining < data_length {
+            error!(
+                "appendData, the mapped file remaining {} is less than data length {}",
+                remaining, data_length
+            );
+            return Ok(false);
+        }
+        Ok(mapped_file.append_message_offset_length(
+            data,
+            data_start as usize,
+            data_length as usize,
+        ))
     }
 
     pub fn set_local_file_message_store(
@@ -1495,6 +1609,7 @@ fn is_mapped_file_matched_recover(
     message_store_config: &Arc<MessageStoreConfig>,
     mapped_file: &DefaultMappedFile,
     store_checkpoint: &StoreCheckpoint,
+    max_phy_offset_of_consume_queue: i64,
 ) -> bool {
     let magic_code = mapped_file
         .get_bytes(MESSAGE_MAGIC_CODE_POSITION, mem::size_of::<i32>())
@@ -1506,7 +1621,19 @@ fn is_mapped_file_matched_recover(
         return false;
     }
     if message_store_config.is_enable_rocksdb_store() {
-        unimplemented!()
+        // the kv consume queues are not rebuilt from the checkpoint, so recover from the first
+        // file they have not fully dispatched
+        let phy_offset = mapped_file
+            .get_bytes(MESSAGE_PHYSIC_OFFSET_POSITION, mem::size_of::<i64>())
+            .unwrap_or(Bytes::from([0u8; mem::size_of::<i64>()].as_ref()))
+            .get_i64();
+        if phy_offset <= max_phy_offset_of_consume_queue {
+            info!(
+                "find check. beginPhyOffset: {}, maxPhyOffsetInConsumeQueue: {}",
+                phy_offset, max_phy_offset_of_consume_queue
+            );
+            return true;
+        }
     } else {
         let sys_flag = mapped_file
             .get_bytes(SYSFLAG_POSITION, mem::size_of::<i32>())
@@ -0,0 +1,419 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use cheetah_string::CheetahString;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::common::message::message_batch::MessageExtBatch;
+use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::MessageDecoder::string_to_message_properties;
+use rocketmq_rust::ArcMut;
+use tokio::sync::watch;
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use crate::base::message_result::PutMessageResult;
+use crate::base::message_status_enum::PutMessageStatus;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::dledger::dledger_config::DLedgerConfig;
+use crate::dledger::dledger_leader_elector::RoleChangeHandler;
+use crate::dledger::dledger_mmap_file_store::IndexCheckpoint;
+use crate::dledger::dledger_protocol::DLedgerResponseCode;
+use crate::dledger::dledger_server::DLedgerServer;
+use crate::log_file::commit_log::CommitLog;
+
+const ENTRY_KIND_MESSAGE: u8 = 1;
+const ENTRY_KIND_BATCH: u8 = 2;
+
+/// Results kept for leaders waiting on their own appends, the oldest are dropped first.
+const MAX_APPLIED_RESULTS: usize = 10000;
+
+/// Commit log mode where every put goes through a DLedger (Raft) group first.
+///
+/// The leader appends the message to the replicated log, and once a quorum stored it, every
+/// member of the group applies the committed entries to its local commit log in log order.
+/// Replaying the same entries in the same order keeps physical and queue offsets identical on all
+/// members, so consume queues, indexes and consumers work the same after a failover.
+pub struct DLedgerCommitLog {
+    dledger_server: Arc<DLedgerServer>,
+    message_store_config: Arc<MessageStoreConfig>,
+    applied_checkpoint: Arc<IndexCheckpoint>,
+    applied_results: Arc<parking_lot::Mutex<BTreeMap<i64, PutMessageResult>>>,
+    applied_tx: Arc<watch::Sender<i64>>,
+}
+
+impl DLedgerCommitLog {
+    pub fn new(message_store_config: Arc<MessageStoreConfig>) -> Self {
+        let config = DLedgerConfig::from_message_store_config(&message_store_config);
+        let applied_checkpoint = Arc::new(IndexCheckpoint::new(config.get_applied_index_path()));
+        Self {
+            dledger_server: Arc::new(DLedgerServer::new(config)),
+            message_store_config,
+            applied_checkpoint,
+            applied_results: Arc::new(parking_lot::Mutex::new(BTreeMap::new())),
+            applied_tx: Arc::new(watch::Sender::new(-1)),
+        }
+    }
+
+    pub fn load(&self) -> bool {
+        let result = self.dledger_server.load();
+        // the applied index file is missing on the first start
+        self.applied_checkpoint.load();
+        self.applied_tx
+            .send_replace(self.applied_checkpoint.index());
+        result
+    }
+
+    /// Starts the DLedger server and applies committed entries to `commit_log` in the background.
+    pub fn start(&self, commit_log: ArcMut<CommitLog>) {
+        let dledger_server = self.dledger_server.clone();
+        tokio::spawn(async move {
+            if let Err(e) = dledger_server.start().await {
+                error!("dledger server start failed: {:?}", e);
+            }
+        });
+
+        let dledger_server = self.dledger_server.clone();
+        let applied_checkpoint = self.applied_checkpoint.clone();
+        let applied_results = self.applied_results.clone();
+        let applied_tx = self.applied_tx.clone();
+        let mut committed_rx = self.dledger_server.subscribe_committed_index();
+        tokio::spawn(async move {
+            loop {
+                let committed_index = *committed_rx.borrow_and_update();
+                let mut applied_index = applied_checkpoint.index();
+                while applied_index < committed_index {
+                    let index = applied_index + 1;
+                    let Some(entry) = dledger_server.get_entry(index) else {
+                        error!("dledger entry {} is committed but missing", index);
+                        break;
+                    };
+                    if let Some(result) = apply_entry(&commit_log, entry.body).await {
+                        if dledger_server.is_leader() {
+                            let mut results = applied_results.lock();
+                            results.insert(index, result);
+                            while results.len() > MAX_APPLIED_RESULTS {
+                                results.pop_first();
+                            }
+                        }
+                    }
+                    applied_index = index;
+                    applied_checkpoint.set_index(applied_index);
+                    applied_tx.send_replace(applied_index);
+                }
+                applied_checkpoint.persist();
+                if committed_rx.changed().await.is_err() {
+                    break;
+                }
+            }
+        });
+        info!("dledger commit log started");
+    }
+
+    pub fn shutdown(&self) {
+        self.dledger_server.shutdown();
+        self.applied_checkpoint.persist();
+    }
+
+    pub async fn put_message(&self, msg: MessageExtBrokerInner) -> PutMessageResult {
+        self.append_and_wait(encode_message(&msg)).await
+    }
+
+    pub async fn put_messages(&self, msg_batch: MessageExtBatch) -> PutMessageResult {
+        self.append_and_wait(encode_batch(&msg_batch)).await
+    }
+
+    async fn append_and_wait(&self, body: Bytes) -> PutMessageResult {
+        if !self.dledger_server.is_leader() {
+            return PutMessageResult::new_default(PutMessageStatus::ServiceNotAvailable);
+        }
+        let index = match self.dledger_server.append(body).await {
+            Ok(entry) => entry.index,
+            Err(code) => {
+                warn!("dledger append failed: {:?}", code);
+                return PutMessageResult::new_default(match code {
+                    DLedgerResponseCode::NotLeader => PutMessageStatus::ServiceNotAvailable,
+                    DLedgerResponseCode::WaitQuorumAckTimeout => {
+                        PutMessageStatus::FlushSlaveTimeout
+                    }
+                    _ => PutMessageStatus::UnknownError,
+                });
+            }
+        };
+
+        let mut applied_rx = self.applied_tx.subscribe();
+        let timeout = Duration::from_millis(self.message_store_config.slave_timeout.max(1) as u64);
+        if !matches!(
+            tokio::time::timeout(timeout, applied_rx.wait_for(|applied| *applied >= index)).await,
+            Ok(Ok(_))
+        ) {
+            return PutMessageResult::new_default(PutMessageStatus::FlushSlaveTimeout);
+        }
+        self.applied_results
+            .lock()
+            .remove(&index)
+            .unwrap_or_else(|| PutMessageResult::new_default(PutMessageStatus::UnknownError))
+    }
+
+    pub fn add_role_change_handler(&self, handler: RoleChangeHandler) {
+        self.dledger_server.add_role_change_handler(handler);
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.dledger_server.is_leader()
+    }
+
+    pub fn get_applied_index(&self) -> i64 {
+        self.applied_checkpoint.index()
+    }
+
+    pub fn get_dledger_server(&self) -> &Arc<DLedgerServer> {
+        &self.dledger_server
+    }
+}
+
+/// Writes one committed entry to the local commit log, the empty entries a new leader appends
+/// carry no message.
+async fn apply_entry(commit_log: &ArcMut<CommitLog>, mut body: Bytes) -> Option<PutMessageResult> {
+    if body.is_empty() {
+        return None;
+    }
+    let kind = body.get_u8();
+    let result = match kind {
+        ENTRY_KIND_MESSAGE => {
+            let msg = decode_message(&mut body);
+            commit_log
+                .mut_from_ref()
+                .put_message(msg, commit_log.clone())
+                .await
+        }
+        ENTRY_KIND_BATCH => {
+            let is_inner_batch = body.get_u8() == 1;
+            let msg_batch = MessageExtBatch {
+                message_ext_broker_inner: decode_message(&mut body),
+                is_inner_batch,
+                encoded_buff: None,
+            };
+            commit_log
+                .mut_from_ref()
+                .put_messages(msg_batch, commit_log.clone())
+                .await
+        }
+        _ => {
+            error!("unknown dledger entry kind {}", kind);
+            return None;
+        }
+    };
+    if !result.is_ok() {
+        warn!(
+            "apply dledger entry to commit log failed: {:?}",
+            result.put_message_status()
+        );
+    }
+    Some(result)
+}
+
+fn encode_message(msg: &MessageExtBrokerInner) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u8(ENTRY_KIND_MESSAGE);
+    put_message_fields(&mut buf, msg);
+    buf.freeze()
+}
+
+fn encode_batch(msg_batch: &MessageExtBatch) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u8(ENTRY_KIND_BATCH);
+    buf.put_u8(msg_batch.is_inner_batch as u8);
+    put_message_fields(&mut buf, &msg_batch.message_ext_broker_inner);
+    buf.freeze()
+}
+
+fn put_message_fields(buf: &mut BytesMut, msg: &MessageExtBrokerInner) {
+    let inner = &msg.message_ext_inner;
+    buf.put_i64(msg.tags_code);
+    buf.put_i32(inner.queue_id);
+    buf.put_i32(inner.message.flag);
+    buf.put_i32(inner.sys_flag);
+    buf.put_i64(inner.born_timestamp);
+    put_string(buf, &inner.born_host.to_string());
+    put_string(buf, &inner.store_host.to_string());
+    buf.put_i32(inner.reconsume_times);
+    buf.put_i64(inner.prepared_transaction_offset);
+    put_string(buf, inner.message.topic.as_str());
+    put_string(buf, msg.properties_string.as_str());
+    let body = inner
+        .message
+        .body
+        .as_ref()
+        .map(Bytes::as_ref)
+        .unwrap_or(&[]);
+    buf.put_i32(body.len() as i32);
+    buf.put_slice(body);
+}
+
+fn decode_message(buf: &mut Bytes) -> MessageExtBrokerInner {
+    let mut msg = MessageExtBrokerInner {
+        tags_code: buf.get_i64(),
+        ..Default::default()
+    };
+    let inner = &mut msg.message_ext_inner;
+    inner.queue_id = buf.get_i32();
+    inner.message.flag = buf.get_i32();
+    inner.sys_flag = buf.get_i32();
+    inner.born_timestamp = buf.get_i64();
+    inner.born_host = get_socket_addr(buf);
+    inner.store_host = get_socket_addr(buf);
+    inner.reconsume_times = buf.get_i32();
+    inner.prepared_transaction_offset = buf.get_i64();
+    inner.message.topic = CheetahString::from_string(get_string(buf));
+    let properties_string = CheetahString::from_string(get_string(buf));
+    let body_len = buf.get_i32() as usize;
+    let body = buf.split_to(body_len);
+    if !body.is_empty() {
+        inner.message.body = Some(body);
+    }
+    msg.set_properties(string_to_message_properties(Some(&properties_string)));
+    msg.properties_string = properties_string;
+    msg
+}
+
+fn put_string(buf: &mut BytesMut, value: &str) {
+    buf.put_i32(value.len() as i32);
+    buf.put_slice(value.as_bytes());
+}
+
+fn get_string(buf: &mut Bytes) -> String {
+    let len = buf.get_i32() as usize;
+    String::from_utf8_lossy(&buf.split_to(len)).into_owned()
+}
+
+fn get_socket_addr(buf: &mut Bytes) -> SocketAddr {
+    get_string(buf)
+        .parse()
+        .unwrap_or_else(|_| SocketAddr::from(([127, 0, 0, 1], 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use rocketmq_common::common::message::MessageConst;
+    use rocketmq_common::MessageAccessor::MessageAccessor;
+    use rocketmq_common::MessageDecoder;
+
+    use super::*;
+    use crate::base::message_status_enum::GetMessageStatus;
+    use crate::base::message_store::MessageStore;
+    use crate::config::flush_disk_type::FlushDiskType;
+    use crate::test_utils::new_message_store;
+    use crate::test_utils::wait_until;
+
+    const TOPIC: &str = "DLedgerTopicTest";
+
+    fn new_message(key: &str) -> MessageExtBrokerInner {
+        let mut msg = MessageExtBrokerInner::default();
+        msg.set_topic(CheetahString::from_static_str(TOPIC));
+        msg.set_body(Bytes::from_static(b"dledger message"));
+        msg.message_ext_inner.queue_id = 1;
+        msg.message_ext_inner.born_timestamp = 42;
+        MessageAccessor::put_property(
+            &mut msg,
+            CheetahString::from_static_str(MessageConst::PROPERTY_KEYS),
+            CheetahString::from(key),
+        );
+        msg.properties_string = MessageDecoder::message_properties_to_string(msg.get_properties());
+        msg
+    }
+
+    #[test]
+    fn message_entry_round_trip() {
+        let msg = new_message("k0");
+        let mut body = encode_message(&msg);
+        assert_eq!(body.get_u8(), ENTRY_KIND_MESSAGE);
+        let decoded = decode_message(&mut body);
+        assert!(body.is_empty());
+        assert_eq!(decoded.topic(), msg.topic());
+        assert_eq!(decoded.message_ext_inner.queue_id, 1);
+        assert_eq!(decoded.message_ext_inner.born_timestamp, 42);
+        assert_eq!(
+            decoded.message_ext_inner.born_host,
+            msg.message_ext_inner.born_host
+        );
+        assert_eq!(decoded.properties_string, msg.properties_string);
+        assert_eq!(
+            decoded.get_keys(),
+            Some(CheetahString::from_static_str("k0"))
+        );
+        assert_eq!(decoded.get_body(), msg.get_body());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn single_member_group_applies_puts_to_commit_log() {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let (mut message_store, _temp_dir) = new_message_store(MessageStoreConfig {
+            mapped_file_size_commit_log: 1024 * 64,
+            flush_disk_type: FlushDiskType::AsyncFlush,
+            enable_dledger_commit_log: true,
+            dledger_group: Some("test".to_string()),
+            dledger_self_id: Some("n0".to_string()),
+            dledger_peers: Some(format!("n0-127.0.0.1:{}", port)),
+            ..MessageStoreConfig::default()
+        });
+        assert!(message_store.load().await);
+        message_store.start().unwrap();
+
+        let dledger_commit_log = message_store.get_dledger_commit_log().unwrap().clone();
+        assert!(wait_until(|| dledger_commit_log.is_leader()).await);
+
+        for key in ["k0", "k1", "k2"] {
+            let result = message_store
+                .mut_from_ref()
+                .put_message(new_message(key))
+                .await;
+            assert_eq!(result.put_message_status(), PutMessageStatus::PutOk);
+        }
+
+        let topic = CheetahString::from_static_str(TOPIC);
+        assert!(wait_until(|| message_store.get_max_offset_in_queue(&topic, 1) == 3).await);
+        let get_result = message_store
+            .get_message(
+                &CheetahString::from_static_str("group"),
+                &topic,
+                1,
+                0,
+                32,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(get_result.status(), Some(GetMessageStatus::Found));
+        assert_eq!(get_result.message_count(), 3);
+
+        message_store.shutdown();
+    }
+}
@@ -17,6 +17,7 @@
 use std::sync::atomic::AtomicI32;
 
 use rocketmq_common::TimeUtils::get_current_nano;
+use tokio::sync::oneshot;
 
 use crate::base::message_status_enum::PutMessageStatus;
 
@@ -26,6 +27,7 @@ pub(crate) struct GroupCommitRequest {
     pub(crate) flush_ok: Option<PutMessageStatus>,
     pub(crate) ack_nums: AtomicI32,
     pub(crate) dead_line: u64,
+    pub(crate) response: Option<oneshot::Sender<PutMessageStatus>>,
 }
 
 impl Default for GroupCommitRequest {
@@ -35,6 +37,7 @@ impl Default for GroupCommitRequest {
             flush_ok: None,
             ack_nums: AtomicI32::new(1),
             dead_line: 0,
+            response: None,
         }
     }
 }
@@ -48,4 +51,25 @@ impl GroupCommitRequest {
             ..Self::default()
         }
     }
+
+    pub(crate) fn with_ack_nums(
+        next_offset: i64,
+        timeout_millis: u64,
+        ack_nums: i32,
+        response: oneshot::Sender<PutMessageStatus>,
+    ) -> Self {
+        Self {
+            ack_nums: AtomicI32::new(ack_nums),
+            response: Some(response),
+            ..Self::new(next_offset, timeout_millis)
+        }
+    }
+
+    /// Completes the request, the waiting producer receives `status`.
+    pub(crate) fn wakeup_customer(&mut self, status: PutMessageStatus) {
+        self.flush_ok = Some(status);
+        if let Some(response) = self.response.take() {
+            let _ = response.send(status);
+        }
+    }
 }
@@ -140,7 +140,7 @@ pub trait MappedFile {
     /// # Returns
     /// An `AppendMessageResult` indicating the result of the append operation.
     fn append_message_compaction(
-        &mut self,
+        &self,
         byte_buffer_msg: &mut bytes::Bytes,
         cb: &dyn CompactionAppendMsgCallback,
     ) -> AppendMessageResult;
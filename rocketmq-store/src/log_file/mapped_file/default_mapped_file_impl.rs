@@ -26,6 +26,7 @@ use std::sync::atomic::AtomicI32;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::AtomicU64;
 use std::sync::atomic::Ordering;
+use std::time::UNIX_EPOCH;
 
 use bytes::Bytes;
 use bytes::BytesMut;
@@ -363,13 +364,33 @@ impl MappedFile for DefaultMappedFile {
         result
     }
 
-    #[inline]
     fn append_message_compaction(
-        &mut self,
+        &self,
         byte_buffer_msg: &mut Bytes,
         cb: &dyn CompactionAppendMsgCallback,
     ) -> AppendMessageResult {
-        unimplemented!("append_message_compaction not implemented")
+        let current_pos = self.wrote_position.load(Ordering::Acquire) as u64;
+        if current_pos >= self.file_size {
+            error!(
+                "MappedFile.appendMessage return null, wrotePosition: {} fileSize: {}",
+                current_pos, self.file_size
+            );
+            return AppendMessageResult {
+                status: AppendMessageStatus::UnknownError,
+                ..Default::default()
+            };
+        }
+        let result = cb.do_append(
+            &mut self.get_mapped_file_mut()[current_pos as usize..self.file_size as usize],
+            (self.file_from_offset + current_pos) as i64,
+            (self.file_size - current_pos) as i32,
+            byte_buffer_msg,
+        );
+        self.wrote_position
+            .fetch_add(result.wrote_bytes, Ordering::AcqRel);
+        self.store_timestamp
+            .store(result.store_timestamp as u64, Ordering::Release);
+        result
     }
 
     #[inline]
@@ -571,12 +592,10 @@ impl MappedFile for DefaultMappedFile {
     fn get_last_modified_timestamp(&self) -> u64 {
         self.file
             .metadata()
-            .unwrap()
-            .modified()
-            .unwrap()
-            .elapsed()
-            .unwrap()
-            .as_millis() as u64
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_millis() as u64)
     }
 
     fn get_data(&self, pos: usize, size: usize) -> Option<bytes::Bytes> {
@@ -42,6 +42,7 @@ use bytes::Bytes;
 use bytes::BytesMut;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::attribute::cleanup_policy::CleanupPolicy;
+use rocketmq_common::common::attribute::cq_type::CQType;
 use rocketmq_common::common::boundary_type::BoundaryType;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
 use rocketmq_common::common::broker::broker_role::BrokerRole;
@@ -58,6 +59,7 @@ use rocketmq_common::common::mix_all::RETRY_GROUP_TOPIC_PREFIX;
 use rocketmq_common::common::running::running_stats::RunningStats;
 use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
 use rocketmq_common::common::system_clock::SystemClock;
+use rocketmq_common::common::topic::TopicValidator;
 use rocketmq_common::utils::queue_type_utils::QueueTypeUtils;
 use rocketmq_common::utils::util_all;
 use rocketmq_common::CleanupPolicyUtils::get_delete_policy;
@@ -88,10 +90,12 @@ use crate::base::select_result::SelectMappedBufferResult;
 use crate::base::store_checkpoint::StoreCheckpoint;
 use crate::base::store_stats_service::StoreStatsService;
 use crate::base::transient_store_pool::TransientStorePool;
+use crate::config::flush_disk_type::FlushDiskType;
 use crate::config::message_store_config::MessageStoreConfig;
 use crate::config::store_path_config_helper::get_store_path_batch_consume_queue;
 use crate::config::store_path_config_helper::get_store_path_consume_queue_ext;
 use crate::filter::MessageFilter;
+use crate::ha::epoch_file_cache::EpochFileCache;
 use crate::ha::general_ha_service::GeneralHAService;
 use crate::ha::ha_service::HAService;
 use crate::hook::put_message_hook::BoxedPutMessageHook;
@@ -103,12 +107,17 @@ use crate::kv::compaction_service::CompactionService;
 use crate::kv::compaction_store::CompactionStore;
 use crate::log_file::commit_log;
 use crate::log_file::commit_log::CommitLog;
+use crate::log_file::dledger_commit_log::DLedgerCommitLog;
 use crate::log_file::mapped_file::MappedFile;
 use crate::log_file::MAX_PULL_MSG_SIZE;
 use crate::queue::build_consume_queue::CommitLogDispatcherBuildConsumeQueue;
+use crate::queue::build_consume_queue::CommitLogDispatcherBuildKvConsumeQueue;
+use crate::queue::consume_queue::ConsumeQueueTrait;
 use crate::queue::consume_queue_store::ConsumeQueueStoreTrait;
+use crate::queue::kv_consume_queue_store::KvConsumeQueueStore;
 use crate::queue::local_file_consume_queue_store::ConsumeQueueStore;
 use crate::queue::ArcConsumeQueue;
+use crate::queue::CqUnit;
 use crate::stats::broker_stats_manager::BrokerStatsManager;
 use crate::store::running_flags::RunningFlags;
 use crate::store_error::StoreError;
@@ -125,12 +134,14 @@ pub struct LocalFileMessageStore {
     put_message_hook_list: Arc<parking_lot::RwLock<Vec<BoxedPutMessageHook>>>,
     topic_config_table: Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>,
     commit_log: ArcMut<CommitLog>,
+    dledger_commit_log: Option<Arc<DLedgerCommitLog>>,
     compaction_service: Option<CompactionService>,
     store_checkpoint: Option<Arc<StoreCheckpoint>>,
     master_flushed_offset: Arc<AtomicI64>,
     index_service: IndexService,
     allocate_mapped_file_service: Arc<AllocateMappedFileService>,
     consume_queue_store: ConsumeQueueStore,
+    kv_consume_queue_store: Option<KvConsumeQueueStore>,
     dispatcher: CommitLogDispatcherDefault,
     broker_init_max_offset: Arc<AtomicI64>,
     state_machine_version: Arc<AtomicI64>,
@@ -186,7 +197,23 @@ impl LocalFileMessageStore {
             CommitLogDispatcherBuildConsumeQueue::new(consume_queue_store.clone());
 
         let dispatcher = CommitLogDispatcherDefault {
-            dispatcher_vec: Arc::new(vec![Box::new(build_consume_queue), Box::new(build_index)]),
+            dispatcher_vec: Arc::new(parking_lot::RwLock::new(vec![
+                Arc::new(build_consume_queue),
+                Arc::new(build_index),
+            ])),
+        };
+        // with the RocksDB store type the kv consume queues are the primary ones already
+        let kv_consume_queue_store = if message_store_config.rocksdb_cq_double_write_enable
+            && !message_store_config.is_enable_rocksdb_store()
+        {
+            let kv_consume_queue_store =
+                KvConsumeQueueStore::new(message_store_config.clone(), broker_config.clone());
+            dispatcher.dispatcher_vec.write().push(Arc::new(
+                CommitLogDispatcherBuildKvConsumeQueue::new(kv_consume_queue_store.clone()),
+            ));
+            Some(kv_consume_queue_store)
+        } else {
+            None
         };
 
         let commit_log = ArcMut::new(CommitLog::new(
@@ -202,6 +229,52 @@ impl LocalFileMessageStore {
         ensure_dir_ok(Self::get_store_path_physic(&message_store_config).as_str());
         ensure_dir_ok(Self::get_store_path_logic(&message_store_config).as_str());
 
+        let clean_commit_log_service = Arc::new(CleanCommitLogService::new(
+            message_store_config.clone(),
+            commit_log.clone(),
+            running_flags.clone(),
+        ));
+        let correct_logic_offset_service = Arc::new(CorrectLogicOffsetService::new(
+            message_store_config.clone(),
+            commit_log.clone(),
+            consume_queue_store.clone(),
+        ));
+        let clean_consume_queue_service = Arc::new(CleanConsumeQueueService::new(
+            message_store_config.clone(),
+            commit_log.clone(),
+            consume_queue_store.clone(),
+            index_service.clone(),
+        ));
+
+        let dledger_commit_log = if message_store_config.enable_dledger_commit_log {
+            Some(Arc::new(DLedgerCommitLog::new(
+                message_store_config.clone(),
+            )))
+        } else {
+            None
+        };
+
+        let ha_service = if !message_store_config.enable_dledger_commit_log
+            && !message_store_config.duplication_enable
+        {
+            Some(ArcMut::new(GeneralHAService::new(
+                message_store_config.clone(),
+            )))
+        } else {
+            None
+        };
+
+        let compaction_store = Arc::new(CompactionStore::new(message_store_config.clone()));
+        let compaction_service = if message_store_config.enable_compaction {
+            Some(CompactionService::new(
+                message_store_config.clone(),
+                topic_config_table.clone(),
+                compaction_store.clone(),
+            ))
+        } else {
+            None
+        };
+
         let identity = broker_config.broker_identity.clone();
         let transient_store_pool = TransientStorePool::new(
             message_store_config.transient_store_pool_size,
@@ -214,12 +287,14 @@ impl LocalFileMessageStore {
             topic_config_table,
             // message_store_runtime: Some(RocketMQRuntime::new_multi(10, "message-store-thread")),
             commit_log,
-            compaction_service: Default::default(),
+            dledger_commit_log,
+            compaction_service,
             store_checkpoint: Some(store_checkpoint),
             master_flushed_offset: Arc::new(AtomicI64::new(-1)),
             index_service,
             allocate_mapped_file_service: Arc::new(AllocateMappedFileService::new()),
             consume_queue_store,
+            kv_consume_queue_store,
             dispatcher,
             broker_init_max_offset: Arc::new(AtomicI64::new(-1)),
             state_machine_version: Arc::new(AtomicI64::new(0)),
@@ -231,18 +306,18 @@ impl LocalFileMessageStore {
                 message_store_config,
                 inner: None,
             },
-            clean_commit_log_service: Arc::new(CleanCommitLogService {}),
-            correct_logic_offset_service: Arc::new(CorrectLogicOffsetService {}),
-            clean_consume_queue_service: Arc::new(CleanConsumeQueueService {}),
+            clean_commit_log_service,
+            correct_logic_offset_service,
+            clean_consume_queue_service,
             broker_stats_manager,
             message_arriving_listener: None,
             notify_message_arrive_in_batch,
             store_stats_service: Arc::new(StoreStatsService::new(Some(identity))),
-            compaction_store: Arc::new(CompactionStore),
+            compaction_store,
             timer_message_store: None,
             transient_store_pool,
             message_store_arc: None,
-            ha_service: None,
+            ha_service,
             flush_consume_queue_service: FlushConsumeQueueService,
             delay_level_table: ArcMut::new(delay_level_table),
             max_delay_level,
@@ -250,12 +325,8 @@ impl LocalFileMessageStore {
     }
 
     pub fn get_store_path_physic(message_store_config: &Arc<MessageStoreConfig>) -> String {
-        match message_store_config.enable_dledger_commit_log {
-            true => {
-                unimplemented!("dledger commit log is not supported yet")
-            }
-            false => message_store_config.get_store_path_commit_log(),
-        }
+        // the replicated log lives apart, committed entries are applied to the normal commit log
+        message_store_config.get_store_path_commit_log()
     }
 
     pub fn get_store_path_logic(message_store_config: &Arc<MessageStoreConfig>) -> String {
@@ -266,6 +337,57 @@ impl LocalFileMessageStore {
         self.message_store_config.clone()
     }
 
+    pub fn get_ha_service(&self) -> Option<&ArcMut<GeneralHAService>> {
+        self.ha_service.as_ref()
+    }
+
+    /// The epoch history of the commit log, only maintained with the HA service.
+    pub fn get_epoch_cache(&self) -> Option<Arc<EpochFileCache>> {
+        self.ha_service.as_ref().map(|ha_service| {
+            ha_service
+                .get_default_ha_service()
+                .get_epoch_cache()
+                .clone()
+        })
+    }
+
+    /// Makes this store the master of `master_epoch`, used in controller mode.
+    pub async fn change_to_master(&self, master_epoch: i32) -> bool {
+        let Some(ha_service) = self.ha_service.as_ref() else {
+            return false;
+        };
+        ha_service
+            .change_to_master(master_epoch)
+            .await
+            .unwrap_or_else(|e| {
+                error!("change to master failed: {:?}", e);
+                false
+            })
+    }
+
+    /// Makes this store a slave replicating from `new_master_addr`, used in controller mode.
+    pub async fn change_to_slave(
+        &self,
+        new_master_addr: &str,
+        new_master_epoch: i32,
+        slave_id: i64,
+    ) -> bool {
+        let Some(ha_service) = self.ha_service.as_ref() else {
+            return false;
+        };
+        ha_service
+            .change_to_slave(new_master_addr, new_master_epoch, Some(slave_id))
+            .await
+            .unwrap_or_else(|e| {
+                error!("change to slave failed: {:?}", e);
+                false
+            })
+    }
+
+    pub fn get_dledger_commit_log(&self) -> Option<&Arc<DLedgerCommitLog>> {
+        self.dledger_commit_log.as_ref()
+    }
+
     pub fn is_transient_store_pool_enable(&self) -> bool {
         self.message_store_config.transient_store_pool_enable
             && (self.broker_config.enable_controller_mode
@@ -405,7 +527,7 @@ impl LocalFileMessageStore {
 
     pub fn on_commit_log_dispatch(
         &mut self,
-        dispatch_request: &DispatchRequest,
+        dispatch_request: &mut DispatchRequest,
         do_dispatch: bool,
         is_recover: bool,
         _is_file_end: bool,
@@ -415,12 +537,74 @@ impl LocalFileMessageStore {
         }
     }
 
-    pub fn do_dispatch(&mut self, dispatch_request: &DispatchRequest) {
+    pub fn do_dispatch(&mut self, dispatch_request: &mut DispatchRequest) {
         self.dispatcher.dispatch(dispatch_request)
     }
 
     pub fn truncate_dirty_logic_files(&mut self, phy_offset: i64) {
         self.consume_queue_store.truncate_dirty(phy_offset);
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+            kv_consume_queue_store.truncate_dirty(phy_offset);
+        }
+    }
+
+    /// Append the first difference between the file and the kv consume queues of `topic`
+    /// to `diff_result`, along with the queue boundaries unless all topics are being checked.
+    fn check_rocksdb_cq_write_progress_of_topic(
+        kv_consume_queue_store: &KvConsumeQueueStore,
+        topic: &CheetahString,
+        queue_table: &HashMap<i32, ArcConsumeQueue>,
+        check_all: bool,
+        diff_result: &mut String,
+    ) {
+        let unit_to_string =
+            |cq_unit: Option<CqUnit>| cq_unit.map_or("null".to_string(), |unit| unit.to_string());
+        let mut queue_ids = queue_table.keys().copied().collect::<Vec<_>>();
+        queue_ids.sort_unstable();
+        for queue_id in queue_ids {
+            let file_cq = &queue_table[&queue_id];
+            let kv_cq = kv_consume_queue_store.find_or_create_consume_queue(topic, queue_id);
+            if !check_all {
+                diff_result.push_str(&format!(
+                    "\n[topic: {}, queue: {}] \n  kvEarliest : {} |  kvLatest : {} \n \
+                     fileEarliest: {} | fileLatest: {} \n",
+                    topic,
+                    queue_id,
+                    unit_to_string(kv_cq.get_earliest_unit()),
+                    unit_to_string(kv_cq.get_latest_unit()),
+                    unit_to_string(file_cq.get_earliest_unit()),
+                    unit_to_string(file_cq.get_latest_unit()),
+                ));
+            }
+            let max_file_offset_in_queue = file_cq.get_max_offset_in_queue();
+            for offset in kv_cq.get_min_offset_in_queue()..max_file_offset_in_queue {
+                let file_cq_unit = file_cq.get(offset);
+                let kv_cq_unit = kv_cq.get(offset);
+                let same = match (&file_cq_unit, &kv_cq_unit) {
+                    (Some(file_cq_unit), Some(kv_cq_unit)) => {
+                        file_cq_unit.queue_offset == kv_cq_unit.queue_offset
+                            && file_cq_unit.size == kv_cq_unit.size
+                            && file_cq_unit.pos == kv_cq_unit.pos
+                            && file_cq_unit.batch_num == kv_cq_unit.batch_num
+                            && file_cq_unit.tags_code == kv_cq_unit.tags_code
+                    }
+                    _ => false,
+                };
+                if !same {
+                    let diff_info = format!(
+                        "[topic: {}, queue: {}, offset: {}] \n kv   : {}  \n file : {}  \n",
+                        topic,
+                        queue_id,
+                        offset,
+                        unit_to_string(kv_cq_unit),
+                        unit_to_string(file_cq_unit),
+                    );
+                    error!("{}", diff_info);
+                    diff_result.push_str(&diff_info);
+                    return;
+                }
+            }
+        }
     }
 
     pub fn consume_queue_store_mut(&mut self) -> &mut ConsumeQueueStore {
@@ -448,7 +632,8 @@ impl LocalFileMessageStore {
             let mut interval =
                 tokio::time::interval(Duration::from_millis(clean_resource_interval));
             loop {
-                clean_commit_log_service_arc.run();
+                let clean_commit_log_service = clean_commit_log_service_arc.clone();
+                let _ = tokio::task::spawn_blocking(move || clean_commit_log_service.run()).await;
                 interval.tick().await;
             }
         });
@@ -484,8 +669,13 @@ impl LocalFileMessageStore {
             let mut interval =
                 tokio::time::interval(Duration::from_millis(clean_resource_interval));
             loop {
-                correct_logic_offset_service_arc.run();
-                clean_consume_queue_service_arc.run();
+                let correct_logic_offset_service = correct_logic_offset_service_arc.clone();
+                let clean_consume_queue_service = clean_consume_queue_service_arc.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    correct_logic_offset_service.run();
+                    clean_consume_queue_service.run();
+                })
+                .await;
                 interval.tick().await;
             }
         });
@@ -496,6 +686,41 @@ impl LocalFileMessageStore {
         self.consume_queue_store.check_self();
     }
 
+    /// Destroys all consume queues of the topic, returns false if the topic has none.
+    fn destroy_topic(&self, topic: &CheetahString) -> bool {
+        let Some(queue_table) = self.consume_queue_store.find_consume_queue_map(topic) else {
+            return false;
+        };
+        let mut consume_queue_store = self.consume_queue_store.clone();
+        for (queue_id, consume_queue) in queue_table {
+            consume_queue_store.destroy_queue(consume_queue.as_ref().deref());
+            consume_queue_store.remove_topic_queue_table(topic, queue_id);
+        }
+        // remove topic from cq table
+        let consume_queue_table = self.consume_queue_store.get_consume_queue_table();
+        consume_queue_table.lock().remove(topic);
+
+        if self.broker_config.auto_delete_unused_stats {
+            if let Some(broker_stats_manager) = self.broker_stats_manager.as_ref() {
+                broker_stats_manager.on_topic_deleted(topic);
+            }
+        }
+
+        let root_dir = self.message_store_config.store_path_root_dir.as_str();
+        let consume_queue_dir =
+            PathBuf::from(get_store_path_consume_queue(root_dir)).join(topic.as_str());
+        let consume_queue_ext_dir =
+            PathBuf::from(get_store_path_consume_queue_ext(root_dir)).join(topic.as_str());
+        let batch_consume_queue_dir =
+            PathBuf::from(get_store_path_batch_consume_queue(root_dir)).join(topic.as_str());
+
+        util_all::delete_empty_directory(consume_queue_dir);
+        util_all::delete_empty_directory(consume_queue_ext_dir);
+        util_all::delete_empty_directory(batch_consume_queue_dir);
+        info!("DeleteTopic: Topic has been destroyed, topic={}", topic);
+        true
+    }
+
     pub fn next_offset_correction(&self, old_offset: i64, new_offset: i64) -> i64 {
         let mut next_offset = old_offset;
         if self.message_store_config.broker_role != BrokerRole::Slave
@@ -598,11 +823,20 @@ impl MessageStore for LocalFileMessageStore {
         if !result {
             return result;
         }
+        if let Some(dledger_commit_log) = self.dledger_commit_log.as_ref() {
+            result &= dledger_commit_log.load();
+            if !result {
+                return result;
+            }
+        }
         // load Consume Queue-- init Consume log mapped file queue
         result &= self.consume_queue_store.load();
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_mut() {
+            result &= kv_consume_queue_store.load();
+        }
 
-        if self.message_store_config.enable_compaction {
-            result &= self.compaction_service.as_mut().unwrap().load(last_exit_ok);
+        if let Some(compaction_service) = self.compaction_service.as_mut() {
+            result &= compaction_service.load(last_exit_ok);
             if !result {
                 return result;
             }
@@ -634,7 +868,7 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn start(&mut self) -> Result<(), StoreError> {
-        if !self.message_store_config.enable_dleger_commit_log
+        if !self.message_store_config.enable_dledger_commit_log
             && !self.message_store_config.duplication_enable
         {
             if let Some(ha_service) = self.ha_service.as_mut() {
@@ -667,9 +901,16 @@ impl MessageStore for LocalFileMessageStore {
         self.do_recheck_reput_offset_from_cq();
         self.flush_consume_queue_service.start();
         self.commit_log.start();
+        if let Some(dledger_commit_log) = self.dledger_commit_log.as_ref() {
+            dledger_commit_log.start(self.commit_log.clone());
+        }
         self.consume_queue_store.start();
         self.store_stats_service.start();
 
+        if let Some(compaction_service) = self.compaction_service.as_ref() {
+            compaction_service.start(self.message_store_arc.clone().unwrap());
+        }
+
         if let Some(ha_service) = self.ha_service.as_mut() {
             ha_service.start().map_err(|e| {
                 error!("HA service start failed: {:?}", e);
@@ -692,6 +933,9 @@ impl MessageStore for LocalFileMessageStore {
             }
 
             self.store_stats_service.shutdown();
+            if let Some(dledger_commit_log) = self.dledger_commit_log.as_ref() {
+                dledger_commit_log.shutdown();
+            }
             self.commit_log.shutdown();
 
             self.reput_message_service.shutdown();
@@ -704,8 +948,8 @@ impl MessageStore for LocalFileMessageStore {
                 compaction_service.shutdown();
             }
 
-            if self.message_store_config.rocksdb_cq_double_write_enable {
-                // this.rocksDBMessageStore.consumeQueueStore.shutdown();
+            if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+                kv_consume_queue_store.shutdown();
             }
             self.flush_consume_queue_service.shutdown();
             self.allocate_mapped_file_service.shutdown();
@@ -725,6 +969,9 @@ impl MessageStore for LocalFileMessageStore {
 
     fn destroy(&mut self) {
         self.consume_queue_store.destroy();
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+            kv_consume_queue_store.destroy();
+        }
         self.commit_log.destroy();
         self.index_service.destroy();
         self.delete_file(get_abort_file(
@@ -777,8 +1024,12 @@ impl MessageStore for LocalFileMessageStore {
         }
         let begin_time = Instant::now();
         //put message to commit log
-        let commit_log_this = self.commit_log.clone();
-        let result = self.commit_log.put_message(msg, commit_log_this).await;
+        let result = if let Some(dledger_commit_log) = self.dledger_commit_log.as_ref() {
+            dledger_commit_log.put_message(msg).await
+        } else {
+            let commit_log_this = self.commit_log.clone();
+            self.commit_log.put_message(msg, commit_log_this).await
+        };
         let elapsed_time = begin_time.elapsed().as_millis();
         if elapsed_time > 500 {
             warn!(
@@ -807,11 +1058,14 @@ impl MessageStore for LocalFileMessageStore {
 
         let begin_time = Instant::now();
         //put message to commit log
-        let commit_log_this = self.commit_log.clone();
-        let result = self
-            .commit_log
-            .put_messages(message_ext_batch, commit_log_this)
-            .await;
+        let result = if let Some(dledger_commit_log) = self.dledger_commit_log.as_ref() {
+            dledger_commit_log.put_messages(message_ext_batch).await
+        } else {
+            let commit_log_this = self.commit_log.clone();
+            self.commit_log
+                .put_messages(message_ext_batch, commit_log_this)
+                .await
+        };
         let elapsed_time = begin_time.elapsed().as_millis();
         if elapsed_time > 500 {
             warn!("not in lock eclipse time(ms) {}ms", elapsed_time,);
@@ -883,16 +1137,31 @@ impl MessageStore for LocalFileMessageStore {
         }
         let topic_config = self.get_topic_config(topic);
         let policy = get_delete_policy(topic_config.as_ref());
-        if policy == CleanupPolicy::COMPACTION && self.message_store_config.enable_compaction {
-            //not implemented will be implemented in the future
-            return self.compaction_store.get_message(
+        // compacted messages are served from the compacted view once the original ones are
+        // deleted
+        if policy == CleanupPolicy::COMPACTION
+            && self.message_store_config.enable_compaction
+            && offset < self.get_min_offset_in_queue(topic, queue_id)
+        {
+            if let Some(mut get_result) = self.compaction_store.get_message(
                 group,
                 topic,
                 queue_id,
                 offset,
                 max_msg_nums,
                 max_total_msg_size,
-            );
+            ) {
+                get_result.set_max_offset(self.get_max_offset_in_queue(topic, queue_id));
+                if get_result.status() != Some(GetMessageStatus::Found) {
+                    // nothing compacted left, continue with the messages still stored
+                    get_result.set_status(Some(GetMessageStatus::OffsetTooSmall));
+                    get_result.set_next_begin_offset(self.next_offset_correction(
+                        offset,
+                        self.get_min_offset_in_queue(topic, queue_id),
+                    ));
+                }
+                return Some(get_result);
+            }
         }
         let begin_time = Instant::now();
 
@@ -1404,7 +1673,7 @@ impl MessageStore for LocalFileMessageStore {
         self.commit_log.get_bulk_data(offset, size)
     }
 
-    fn append_to_commit_log(
+    async fn append_to_commit_log(
         &self,
         start_offset: i64,
         data: &[u8],
@@ -1418,7 +1687,8 @@ impl MessageStore for LocalFileMessageStore {
 
         let result = self
             .commit_log
-            .append_data(start_offset, data, data_start, data_length)?;
+            .append_data(start_offset, data, data_start, data_length)
+            .await?;
         if result {
             // weak up to do commit log flush TODO
         } else {
@@ -1499,15 +1769,30 @@ impl MessageStore for LocalFileMessageStore {
     }*/
 
     fn update_ha_master_address(&self, new_addr: &CheetahString) {
-        todo!()
+        if let Some(ha_service) = self.ha_service.as_ref() {
+            ha_service.update_ha_master_address(new_addr.as_str());
+        }
     }
 
     fn update_master_address(&self, new_addr: &CheetahString) {
-        todo!()
+        if let Some(ha_service) = self.ha_service.as_ref() {
+            ha_service.update_master_address(new_addr.as_str());
+        }
     }
 
     fn slave_fall_behind_much(&self) -> i64 {
-        todo!()
+        match self.ha_service.as_ref() {
+            Some(ha_service) => {
+                self.commit_log.get_max_offset()
+                    - ha_service
+                        .get_push_to_slave_max_offset()
+                        .load(Ordering::Acquire)
+            }
+            None => {
+                warn!("haServer is null, no need to compare offset");
+                -1
+            }
+        }
     }
 
     fn delete_topics(&mut self, delete_topics: Vec<&CheetahString>) -> i32 {
@@ -1516,52 +1801,46 @@ impl MessageStore for LocalFileMessageStore {
         }
         let mut delete_count = 0;
         for topic in delete_topics {
-            let queue_table = self.consume_queue_store.find_consume_queue_map(topic);
-            if queue_table.is_none() {
-                continue;
-            }
-            let queue_table = queue_table.unwrap();
-            for (queue_id, consume_queue) in queue_table {
-                self.consume_queue_store
-                    .destroy_queue(consume_queue.as_ref().deref());
-                self.consume_queue_store
-                    .remove_topic_queue_table(topic, queue_id);
+            if self.destroy_topic(topic) {
+                delete_count += 1;
             }
-            // remove topic from cq table
-            let consume_queue_table = self.consume_queue_store.get_consume_queue_table();
-            consume_queue_table.lock().remove(topic);
-
-            if self.broker_config.auto_delete_unused_stats {
-                self.broker_stats_manager
-                    .as_ref()
-                    .unwrap()
-                    .on_topic_deleted(topic);
-            }
-
-            let root_dir = self.message_store_config.store_path_root_dir.as_str();
-            let consume_queue_dir =
-                PathBuf::from(get_store_path_consume_queue(root_dir)).join(topic.as_str());
-            let consume_queue_ext_dir =
-                PathBuf::from(get_store_path_consume_queue_ext(root_dir)).join(topic.as_str());
-            let batch_consume_queue_dir =
-                PathBuf::from(get_store_path_batch_consume_queue(root_dir)).join(topic.as_str());
-
-            util_all::delete_empty_directory(consume_queue_dir);
-            util_all::delete_empty_directory(consume_queue_ext_dir);
-            util_all::delete_empty_directory(batch_consume_queue_dir);
-            info!("DeleteTopic: Topic has been destroyed, topic={}", topic);
-            delete_count += 1;
         }
-
         delete_count
     }
 
     fn clean_unused_topic(&self, retain_topics: &HashSet<String>) -> i32 {
-        todo!()
+        let consume_queue_topics: Vec<CheetahString> = self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .keys()
+            .cloned()
+            .collect();
+        let mut delete_count = 0;
+        for topic in consume_queue_topics {
+            if retain_topics.contains(topic.as_str())
+                || TopicValidator::is_system_topic(topic.as_str())
+                || is_lmq(Some(topic.as_str()))
+            {
+                continue;
+            }
+            if self.destroy_topic(&topic) {
+                delete_count += 1;
+            }
+        }
+        delete_count
     }
 
-    fn clean_expired_consumer_queue(&self) {
-        todo!()
+    async fn clean_expired_consumer_queue(&self) {
+        let min_commit_log_offset = self.commit_log.get_min_offset();
+        self.consume_queue_store
+            .clean_expired(min_commit_log_offset)
+            .await;
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+            kv_consume_queue_store
+                .clean_expired(min_commit_log_offset)
+                .await;
+        }
     }
 
     fn check_in_mem_by_consume_offset(
@@ -1622,7 +1901,7 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn get_confirm_offset(&self) -> i64 {
-        todo!()
+        self.commit_log.get_confirm_offset()
     }
 
     fn set_confirm_offset(&mut self, phy_offset: i64) {
@@ -1645,11 +1924,15 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn get_dispatcher_list(&self) -> Vec<Arc<dyn CommitLogDispatcher>> {
-        todo!()
+        self.dispatcher.dispatcher_vec.read().clone()
     }
 
     fn add_dispatcher(&self, dispatcher: Arc<dyn CommitLogDispatcher>) {
-        todo!()
+        self.dispatcher.dispatcher_vec.write().push(dispatcher);
+    }
+
+    fn add_first_dispatcher(&self, dispatcher: Arc<dyn CommitLogDispatcher>) {
+        self.dispatcher.dispatcher_vec.write().insert(0, dispatcher);
     }
 
     fn get_consume_queue(&self, topic: &CheetahString, queue_id: i32) -> Option<ArcConsumeQueue> {
@@ -1699,7 +1982,7 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn get_store_stats_service(&self) -> Arc<StoreStatsService> {
-        todo!()
+        self.store_stats_service.clone()
     }
 
     fn get_store_checkpoint(&self) -> &StoreCheckpoint {
@@ -1735,7 +2018,11 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn truncate_dirty_logic_files(&self, phy_offset: i64) -> Result<(), StoreError> {
-        todo!()
+        self.consume_queue_store.truncate_dirty(phy_offset);
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+            kv_consume_queue_store.truncate_dirty(phy_offset);
+        }
+        Ok(())
     }
 
     fn unlock_mapped_file<MF: MappedFile>(&self, unlock_mapped_file: &MF) {
@@ -1746,17 +2033,56 @@ impl MessageStore for LocalFileMessageStore {
         self.consume_queue_store.as_any()
     }
 
+    fn check_rocksdb_cq_write_progress(&self, topic: &CheetahString) -> String {
+        if self.message_store_config.is_enable_rocksdb_store() {
+            return "storeType is RocksDB, no need check".to_string();
+        }
+        let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() else {
+            return "rocksdbCQWriteEnable is false, checkRocksdbCqWriteProgressCommand is invalid"
+                .to_string();
+        };
+        let consume_queue_table = self.consume_queue_store.get_consume_queue_table();
+        let consume_queue_table = consume_queue_table.lock().clone();
+        let mut diff_result = String::new();
+        if !topic.is_empty() {
+            if let Some(queue_table) = consume_queue_table.get(topic) {
+                Self::check_rocksdb_cq_write_progress_of_topic(
+                    kv_consume_queue_store,
+                    topic,
+                    queue_table,
+                    false,
+                    &mut diff_result,
+                );
+            }
+            return diff_result;
+        }
+        for (topic, queue_table) in consume_queue_table.iter() {
+            Self::check_rocksdb_cq_write_progress_of_topic(
+                kv_consume_queue_store,
+                topic,
+                queue_table,
+                true,
+                &mut diff_result,
+            );
+        }
+        diff_result.push_str(&format!(
+            "check all topic successful, size:{}",
+            consume_queue_table.len()
+        ));
+        diff_result
+    }
+
     /*fn get_queue_store(&self) -> &Box<dyn ConsumeQueueStoreTrait> {
         /*&self.consume_queue_store as &Box<dyn ConsumeQueueStoreTrait>*/
         unimplemented!("get_queue_store")
     }*/
 
     fn is_sync_disk_flush(&self) -> bool {
-        todo!()
+        self.message_store_config.flush_disk_type == FlushDiskType::SyncFlush
     }
 
     fn is_sync_master(&self) -> bool {
-        todo!()
+        self.message_store_config.broker_role == BrokerRole::SyncMaster
     }
 
     fn assign_offset(&self, msg: &mut MessageExtBrokerInner) -> Result<(), StoreError> {
@@ -1788,19 +2114,29 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn wakeup_ha_client(&self) {
-        todo!()
+        if let Some(ha_client) = self
+            .ha_service
+            .as_ref()
+            .and_then(|ha_service| ha_service.get_ha_client())
+        {
+            ha_client.wakeup_service();
+        }
     }
 
     fn get_master_flushed_offset(&self) -> i64 {
-        todo!()
+        self.master_flushed_offset.load(Ordering::Acquire)
     }
 
     fn get_broker_init_max_offset(&self) -> i64 {
-        todo!()
+        self.broker_init_max_offset.load(Ordering::Acquire)
     }
 
     fn set_master_flushed_offset(&self, master_flushed_offset: i64) {
-        todo!()
+        self.master_flushed_offset
+            .store(master_flushed_offset, Ordering::Release);
+        if let Some(store_checkpoint) = self.store_checkpoint.as_ref() {
+            store_checkpoint.set_master_flushed_offset(master_flushed_offset as u64);
+        }
     }
 
     fn set_broker_init_max_offset(&mut self, broker_init_max_offset: i64) {
@@ -1813,7 +2149,33 @@ impl MessageStore for LocalFileMessageStore {
     }
 
     fn truncate_files(&self, offset_to_truncate: i64) -> Result<bool, StoreError> {
-        todo!()
+        let max_phy_offset = self.get_max_phy_offset();
+        if offset_to_truncate >= max_phy_offset {
+            info!(
+                "no need to truncate files, truncate offset {}, max physical offset {}",
+                offset_to_truncate, max_phy_offset
+            );
+            return Ok(false);
+        }
+        self.consume_queue_store.truncate_dirty(offset_to_truncate);
+        if let Some(kv_consume_queue_store) = self.kv_consume_queue_store.as_ref() {
+            kv_consume_queue_store.truncate_dirty(offset_to_truncate);
+        }
+        self.commit_log
+            .mut_from_ref()
+            .truncate_dirty_files(offset_to_truncate);
+        if let Some(message_store) = self.message_store_arc.as_ref() {
+            message_store.mut_from_ref().recover_topic_queue_table();
+        }
+        // the messages after the truncate offset are gone, dispatch the new ones from there
+        if let Some(reput_from_offset) = self.reput_message_service.reput_from_offset.as_ref() {
+            reput_from_offset.fetch_min(offset_to_truncate, Ordering::AcqRel);
+        }
+        info!(
+            "truncate files to {}, max physical offset was {}",
+            offset_to_truncate, max_phy_offset
+        );
+        Ok(true)
     }
 
     fn is_offset_aligned(&self, offset: i64) -> bool {
@@ -1925,14 +2287,14 @@ impl MessageStore for LocalFileMessageStore {
 pub struct CommitLogDispatcherDefault {
     /*build_index: CommitLogDispatcherBuildIndex,
     build_consume_queue: CommitLogDispatcherBuildConsumeQueue,*/
-    dispatcher_vec: Arc<Vec<Box<dyn CommitLogDispatcher>>>,
+    dispatcher_vec: Arc<parking_lot::RwLock<Vec<Arc<dyn CommitLogDispatcher>>>>,
 }
 
 impl CommitLogDispatcher for CommitLogDispatcherDefault {
-    fn dispatch(&self, dispatch_request: &DispatchRequest) {
+    fn dispatch(&self, dispatch_request: &mut DispatchRequest) {
         /*self.build_index.dispatch(dispatch_request);
         self.build_consume_queue.dispatch(dispatch_request);*/
-        for dispatcher in self.dispatcher_vec.iter() {
+        for dispatcher in self.dispatcher_vec.read().iter() {
             dispatcher.dispatch(dispatch_request);
         }
     }
@@ -2175,7 +2537,7 @@ impl ReputMessageServiceInner {
                 if dispatch_request.success {
                     match dispatch_request.msg_size.cmp(&0) {
                         std::cmp::Ordering::Greater => {
-                            self.dispatcher.dispatch(&dispatch_request);
+                            self.dispatcher.dispatch(&mut dispatch_request);
                             if !self.notify_message_arrive_in_batch {
                                 self.message_store
                                     .notify_message_arrive_if_necessary(&mut dispatch_request);
@@ -2183,11 +2545,6 @@ impl ReputMessageServiceInner {
                             self.reput_from_offset
                                 .fetch_add(size as i64, Ordering::AcqRel);
                             read_size += size;
-                            if !self.message_store_config.duplication_enable
-                                && self.message_store_config.broker_role == BrokerRole::Slave
-                            {
-                                unimplemented!()
-                            }
                         }
                         std::cmp::Ordering::Equal => {
                             self.reput_from_offset.store(
@@ -2208,9 +2565,6 @@ impl ReputMessageServiceInner {
                         .fetch_add(size as i64, Ordering::SeqCst);
                 } else {
                     do_next = false;
-                    if self.message_store_config.enable_dledger_commit_log {
-                        unimplemented!()
-                    }
                 }
             }
             result.release();
@@ -2238,31 +2592,376 @@ impl ReputMessageServiceInner {
     }
 }
 
-struct CleanCommitLogService {}
+const MAX_MANUAL_DELETE_FILE_TIMES: i32 = 20;
+
+/// Reclaims commit log files that are out of the reserved time, or all of the old ones when the
+/// disk is about to be full.
+struct CleanCommitLogService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: ArcMut<CommitLog>,
+    running_flags: Arc<RunningFlags>,
+    manual_delete_file_several_times: AtomicI32,
+    clean_immediately: AtomicBool,
+    last_redelete_timestamp: AtomicI64,
+}
 
 impl CleanCommitLogService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: ArcMut<CommitLog>,
+        running_flags: Arc<RunningFlags>,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            running_flags,
+            manual_delete_file_several_times: AtomicI32::new(0),
+            clean_immediately: AtomicBool::new(false),
+            last_redelete_timestamp: AtomicI64::new(0),
+        }
+    }
+
     fn run(&self) {
-        error!("clean commit log service run unimplemented!")
+        self.delete_expired_files();
+        self.re_delete_hanged_file();
     }
 
     fn execute_delete_files_manually(&self) {
-        error!("execute delete files manually unimplemented!")
+        self.manual_delete_file_several_times
+            .store(MAX_MANUAL_DELETE_FILE_TIMES, Ordering::SeqCst);
+        info!("executeDeleteFilesManually was invoked");
+    }
+
+    fn delete_expired_files(&self) {
+        let file_reserved_time = self.message_store_config.file_reserved_time as i64;
+        let delete_physic_files_interval =
+            self.message_store_config.delete_commit_log_files_interval as i32;
+        let destroy_mapped_file_interval_forcibly =
+            self.message_store_config
+                .destroy_mapped_file_interval_forcibly as i64;
+        let delete_file_batch_max = self.message_store_config.delete_file_batch_max as i32;
+
+        let is_time_up = self.is_time_to_delete();
+        let is_usage_exceeds_threshold = self.is_space_to_delete();
+        let is_manual_delete = self
+            .manual_delete_file_several_times
+            .load(Ordering::Acquire)
+            > 0;
+
+        if is_time_up || is_usage_exceeds_threshold || is_manual_delete {
+            if is_manual_delete {
+                self.manual_delete_file_several_times
+                    .fetch_sub(1, Ordering::SeqCst);
+            }
+            let clean_at_once = self.message_store_config.clean_file_forcibly_enable
+                && self.clean_immediately.load(Ordering::Acquire);
+            info!(
+                "begin to delete before {} hours file. isTimeUp: {} isUsageExceedsThreshold: {} \
+                 manualDeleteFileSeveralTimes: {} cleanAtOnce: {} deleteFileBatchMax: {}",
+                file_reserved_time,
+                is_time_up,
+                is_usage_exceeds_threshold,
+                self.manual_delete_file_several_times
+                    .load(Ordering::Acquire),
+                clean_at_once,
+                delete_file_batch_max
+            );
+            let delete_count = self.commit_log.delete_expired_file(
+                file_reserved_time * 60 * 60 * 1000,
+                delete_physic_files_interval,
+                destroy_mapped_file_interval_forcibly,
+                clean_at_once,
+                delete_file_batch_max,
+            );
+            if delete_count == 0 && is_usage_exceeds_threshold {
+                warn!("disk space will be full soon, but delete file failed.");
+            }
+        }
+    }
+
+    fn re_delete_hanged_file(&self) {
+        let interval = self.message_store_config.redelete_hanged_file_interval as i64;
+        let current_timestamp = get_current_millis() as i64;
+        if current_timestamp - self.last_redelete_timestamp.load(Ordering::Acquire) > interval {
+            self.last_redelete_timestamp
+                .store(current_timestamp, Ordering::Release);
+            let destroy_mapped_file_interval_forcibly =
+                self.message_store_config
+                    .destroy_mapped_file_interval_forcibly as i64;
+            self.commit_log
+                .retry_delete_first_file(destroy_mapped_file_interval_forcibly);
+        }
+    }
+
+    fn is_time_to_delete(&self) -> bool {
+        let when = self.message_store_config.delete_when.as_str();
+        if util_all::is_it_time_to_do(when) {
+            info!("it's time to reclaim disk space, {}", when);
+            return true;
+        }
+        false
+    }
+
+    fn is_space_to_delete(&self) -> bool {
+        self.clean_immediately.store(false, Ordering::Release);
+        let disk_space_warning_level_ratio = self.get_disk_space_warning_level_ratio();
+        let disk_space_clean_forcibly_ratio = self.get_disk_space_clean_forcibly_ratio();
+
+        let commit_log_store_path =
+            LocalFileMessageStore::get_store_path_physic(&self.message_store_config);
+        let mut min_physic_ratio = 100f64;
+        let mut min_store_path = "";
+        for store_path_physic in commit_log_store_path
+            .trim()
+            .split(mix_all::MULTI_PATH_SPLITTER.as_str())
+        {
+            let physic_ratio = util_all::get_disk_partition_space_used_percent(store_path_physic);
+            if min_physic_ratio > physic_ratio {
+                min_physic_ratio = physic_ratio;
+                min_store_path = store_path_physic;
+            }
+        }
+        if min_physic_ratio > disk_space_warning_level_ratio {
+            if self.running_flags.get_and_make_disk_full() {
+                error!(
+                    "physic disk maybe full soon {}, so mark disk full, storePathPhysic={}",
+                    min_physic_ratio, min_store_path
+                );
+            }
+            self.clean_immediately.store(true, Ordering::Release);
+            return true;
+        } else if min_physic_ratio > disk_space_clean_forcibly_ratio {
+            self.clean_immediately.store(true, Ordering::Release);
+            return true;
+        } else if !self.running_flags.get_and_make_disk_ok() {
+            info!(
+                "physic disk space OK {}, so mark disk ok, storePathPhysic={}",
+                min_physic_ratio, min_store_path
+            );
+        }
+
+        let store_path_logics =
+            LocalFileMessageStore::get_store_path_logic(&self.message_store_config);
+        let logics_ratio = util_all::get_disk_partition_space_used_percent(&store_path_logics);
+        if logics_ratio > disk_space_warning_level_ratio {
+            if self.running_flags.get_and_make_logic_disk_full() {
+                error!(
+                    "logics disk maybe full soon {}, so mark disk full",
+                    logics_ratio
+                );
+            }
+            self.clean_immediately.store(true, Ordering::Release);
+            return true;
+        } else if logics_ratio > disk_space_clean_forcibly_ratio {
+            self.clean_immediately.store(true, Ordering::Release);
+            return true;
+        } else if !self.running_flags.get_and_make_logic_disk_ok() {
+            info!("logics disk space OK {}, so mark disk ok", logics_ratio);
+        }
+
+        let ratio = self.message_store_config.disk_max_used_space_ratio as f64 / 100.0;
+        if min_physic_ratio < 0.0 || min_physic_ratio > ratio {
+            info!(
+                "commitLog disk maybe full soon, so reclaim space, {}",
+                min_physic_ratio
+            );
+            return true;
+        }
+        if logics_ratio < 0.0 || logics_ratio > ratio {
+            info!(
+                "consumeQueue disk maybe full soon, so reclaim space, {}",
+                logics_ratio
+            );
+            return true;
+        }
+        false
+    }
+
+    fn get_disk_space_warning_level_ratio(&self) -> f64 {
+        (self.message_store_config.disk_space_warning_level_ratio as f64 / 100.0).clamp(0.35, 0.90)
+    }
+
+    fn get_disk_space_clean_forcibly_ratio(&self) -> f64 {
+        (self.message_store_config.disk_space_clean_forcibly_ratio as f64 / 100.0).clamp(0.30, 0.85)
     }
 }
 
-struct CleanConsumeQueueService {}
+/// Removes consume queue and index files which only refer to commit log data that has already
+/// been deleted.
+struct CleanConsumeQueueService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: ArcMut<CommitLog>,
+    consume_queue_store: ConsumeQueueStore,
+    index_service: IndexService,
+    last_physical_min_offset: AtomicI64,
+}
 
 impl CleanConsumeQueueService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: ArcMut<CommitLog>,
+        consume_queue_store: ConsumeQueueStore,
+        index_service: IndexService,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            consume_queue_store,
+            index_service,
+            last_physical_min_offset: AtomicI64::new(0),
+        }
+    }
+
     fn run(&self) {
-        error!("clean consume queue service run unimplemented!")
+        self.delete_expired_files();
+    }
+
+    fn delete_expired_files(&self) {
+        let delete_logics_files_interval = self
+            .message_store_config
+            .delete_consume_queue_files_interval as u64;
+        let min_offset = self.commit_log.get_min_offset();
+        if min_offset <= self.last_physical_min_offset.load(Ordering::Acquire) {
+            return;
+        }
+        self.last_physical_min_offset
+            .store(min_offset, Ordering::Release);
+        let tables = self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .clone();
+        for queue_table in tables.values() {
+            for logic in queue_table.values() {
+                let delete_count = self
+                    .consume_queue_store
+                    .delete_expired_file(logic.as_ref().as_ref(), min_offset);
+                if delete_count > 0 && delete_logics_files_interval > 0 {
+                    thread::sleep(Duration::from_millis(delete_logics_files_interval));
+                }
+            }
+        }
+        self.index_service.delete_expired_file(min_offset as u64);
     }
 }
 
-struct CorrectLogicOffsetService {}
+/// Corrects the min logic offset of the consume queues whose head still points to commit log
+/// data that has been deleted.
+struct CorrectLogicOffsetService {
+    message_store_config: Arc<MessageStoreConfig>,
+    commit_log: ArcMut<CommitLog>,
+    consume_queue_store: ConsumeQueueStore,
+    last_force_correct_time: AtomicI64,
+}
 
 impl CorrectLogicOffsetService {
+    fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        commit_log: ArcMut<CommitLog>,
+        consume_queue_store: ConsumeQueueStore,
+    ) -> Self {
+        Self {
+            message_store_config,
+            commit_log,
+            consume_queue_store,
+            last_force_correct_time: AtomicI64::new(-1),
+        }
+    }
+
     fn run(&self) {
-        error!("correct logic offset service run unimplemented!")
+        self.correct_logic_min_offset();
+    }
+
+    fn correct_logic_min_offset(&self) {
+        let last_force_correct_time_cur_run = self.last_force_correct_time.load(Ordering::Acquire);
+        let min_phy_offset = self.commit_log.get_min_offset();
+        let tables = self
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .clone();
+        for queue_table in tables.values() {
+            for logic in queue_table.values() {
+                // simple consume queues are corrected when their expired files are deleted
+                if logic.get_cq_type() == CQType::SimpleCQ {
+                    continue;
+                }
+                if self.need_correct(
+                    logic.as_ref().as_ref(),
+                    min_phy_offset,
+                    last_force_correct_time_cur_run,
+                ) {
+                    self.do_correct(logic.as_ref().as_ref(), min_phy_offset);
+                }
+            }
+        }
+    }
+
+    fn need_correct(
+        &self,
+        logic: &dyn ConsumeQueueTrait,
+        min_phy_offset: i64,
+        last_force_correct_time_cur_run: i64,
+    ) -> bool {
+        // If first exist and not available, it means first file may destroy failed, delete it.
+        if self.consume_queue_store.is_first_file_exist(logic)
+            && !self.consume_queue_store.is_first_file_available(logic)
+        {
+            error!(
+                "CorrectLogicOffsetService.needCorrect. first file not available, trigger \
+                 correct. topic:{}, queue:{}, maxPhyOffset in queue:{}, minPhyOffset in commit \
+                 log:{}, minOffset in queue:{}, maxOffset in queue:{}, cqType:{:?}",
+                logic.get_topic(),
+                logic.get_queue_id(),
+                logic.get_max_physic_offset(),
+                min_phy_offset,
+                logic.get_min_offset_in_queue(),
+                logic.get_max_offset_in_queue(),
+                logic.get_cq_type()
+            );
+            return true;
+        }
+        // If first file does not exist, or the queue is empty, there is nothing to correct.
+        if !self.consume_queue_store.is_first_file_exist(logic)
+            || logic.get_min_offset_in_queue() == logic.get_max_offset_in_queue()
+        {
+            return false;
+        }
+        let Some(earliest_unit) = logic.get_earliest_unit() else {
+            return false;
+        };
+        if earliest_unit.pos < min_phy_offset {
+            info!(
+                "CorrectLogicOffsetService.needCorrect. earliest unit is expired, trigger \
+                 correct. topic:{}, queue:{}, minPhyOffset in queue:{}, minPhyOffset in commit \
+                 log:{}",
+                logic.get_topic(),
+                logic.get_queue_id(),
+                earliest_unit.pos,
+                min_phy_offset
+            );
+            return true;
+        }
+        let force_correct_interval = self
+            .message_store_config
+            .correct_logic_min_offset_force_interval as i64;
+        let now = get_current_millis() as i64;
+        if now - last_force_correct_time_cur_run > force_correct_interval {
+            self.last_force_correct_time.store(now, Ordering::Release);
+            return true;
+        }
+        false
+    }
+
+    fn do_correct(&self, logic: &dyn ConsumeQueueTrait, min_phy_offset: i64) {
+        self.consume_queue_store
+            .delete_expired_file(logic, min_phy_offset);
+        let sleep_interval = self
+            .message_store_config
+            .correct_logic_min_offset_sleep_interval as u64;
+        if sleep_interval > 0 {
+            thread::sleep(Duration::from_millis(sleep_interval));
+        }
     }
 }
 
@@ -2317,3 +3016,254 @@ pub fn parse_delay_level(level_string: &str) -> (BTreeMap<i32, i64>, i32) {
 
     (delay_level_table, max_delay_level)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::base::store_enum::StoreType;
+    use crate::queue::batch_consume_queue::BatchConsumeQueue;
+    use crate::queue::batch_consume_queue::CQ_STORE_UNIT_SIZE as BATCH_CQ_STORE_UNIT_SIZE;
+    use crate::queue::single_consume_queue::CQ_STORE_UNIT_SIZE;
+    use crate::test_utils::new_message_store;
+
+    const TOPIC: &str = "CleanServiceTest";
+
+    /// Creates a store whose commit log consists of three 4K files, which are not loaded yet.
+    fn new_message_store_with_commit_log_files(
+        message_store_config: MessageStoreConfig,
+    ) -> (ArcMut<LocalFileMessageStore>, TempDir) {
+        let (message_store, temp_dir) = new_message_store(MessageStoreConfig {
+            mapped_file_size_commit_log: 4096,
+            ..message_store_config
+        });
+        let commit_log_dir = PathBuf::from(
+            message_store
+                .message_store_config
+                .get_store_path_commit_log(),
+        );
+        fs::create_dir_all(&commit_log_dir).unwrap();
+        for index in 0..3u64 {
+            fs::write(
+                commit_log_dir.join(util_all::offset_to_file_name(index * 4096)),
+                vec![0u8; 4096],
+            )
+            .unwrap();
+        }
+        (message_store, temp_dir)
+    }
+
+    fn commit_log_file_count(message_store: &LocalFileMessageStore) -> usize {
+        fs::read_dir(
+            message_store
+                .message_store_config
+                .get_store_path_commit_log(),
+        )
+        .unwrap()
+        .count()
+    }
+
+    fn consume_queue_file_count(message_store: &LocalFileMessageStore, queue_id: i32) -> usize {
+        let consume_queue_dir = PathBuf::from(LocalFileMessageStore::get_store_path_logic(
+            &message_store.message_store_config,
+        ))
+        .join(TOPIC)
+        .join(queue_id.to_string());
+        fs::read_dir(consume_queue_dir).unwrap().count()
+    }
+
+    fn delete_commit_log_files_manually(message_store: &LocalFileMessageStore) {
+        message_store.execute_delete_files_manually();
+        message_store.clean_commit_log_service.run();
+    }
+
+    #[test]
+    fn clean_commit_log_service_deletes_files_manually() {
+        let (message_store, _temp_dir) =
+            new_message_store_with_commit_log_files(MessageStoreConfig {
+                file_reserved_time: 0,
+                ..MessageStoreConfig::default()
+            });
+        assert!(message_store.mut_from_ref().commit_log.load());
+
+        delete_commit_log_files_manually(&message_store);
+
+        assert_eq!(commit_log_file_count(&message_store), 1);
+        assert_eq!(message_store.commit_log.get_min_offset(), 8192);
+    }
+
+    #[test]
+    fn clean_commit_log_service_keeps_files_within_reserved_time() {
+        let (message_store, _temp_dir) =
+            new_message_store_with_commit_log_files(MessageStoreConfig {
+                file_reserved_time: 72,
+                clean_file_forcibly_enable: false,
+                ..MessageStoreConfig::default()
+            });
+        assert!(message_store.mut_from_ref().commit_log.load());
+
+        delete_commit_log_files_manually(&message_store);
+
+        assert_eq!(commit_log_file_count(&message_store), 3);
+        assert_eq!(message_store.commit_log.get_min_offset(), 0);
+    }
+
+    #[test]
+    fn clean_consume_queue_service_deletes_files_below_commit_log_min_offset() {
+        let (message_store, _temp_dir) =
+            new_message_store_with_commit_log_files(MessageStoreConfig {
+                mapped_file_size_consume_queue: CQ_STORE_UNIT_SIZE as usize * 10,
+                file_reserved_time: 0,
+                delete_consume_queue_files_interval: 0,
+                ..MessageStoreConfig::default()
+            });
+        assert!(message_store.mut_from_ref().commit_log.load());
+        let topic = CheetahString::from_static_str(TOPIC);
+        // 30 units of 400 bytes each, 10 units per consume queue file
+        for queue_offset in 0..30i64 {
+            message_store
+                .consume_queue_store
+                .put_message_position_info_wrapper(&DispatchRequest {
+                    topic: topic.clone(),
+                    commit_log_offset: queue_offset * 400,
+                    msg_size: 400,
+                    consume_queue_offset: queue_offset,
+                    ..DispatchRequest::default()
+                });
+        }
+        assert_eq!(consume_queue_file_count(&message_store, 0), 3);
+
+        // nothing to clean while the commit log still starts at 0
+        message_store.clean_consume_queue_service.run();
+        assert_eq!(consume_queue_file_count(&message_store, 0), 3);
+        assert_eq!(message_store.get_min_offset_in_queue(&topic, 0), 0);
+
+        delete_commit_log_files_manually(&message_store);
+        assert_eq!(message_store.commit_log.get_min_offset(), 8192);
+        message_store.clean_consume_queue_service.run();
+
+        assert_eq!(consume_queue_file_count(&message_store, 0), 1);
+        // the first unit at or above the commit log min offset 8192 is at 8400
+        assert_eq!(message_store.get_min_offset_in_queue(&topic, 0), 21);
+        assert_eq!(message_store.get_max_offset_in_queue(&topic, 0), 30);
+    }
+
+    #[test]
+    fn correct_logic_offset_service_corrects_expired_batch_queue_head() {
+        let (message_store, _temp_dir) =
+            new_message_store_with_commit_log_files(MessageStoreConfig {
+                mapper_file_size_batch_consume_queue: BATCH_CQ_STORE_UNIT_SIZE as usize * 2,
+                file_reserved_time: 0,
+                correct_logic_min_offset_force_interval: 1000 * 60,
+                ..MessageStoreConfig::default()
+            });
+        assert!(message_store.mut_from_ref().commit_log.load());
+        let topic = CheetahString::from_static_str(TOPIC);
+        let mut batch_consume_queue = BatchConsumeQueue::new(
+            topic.clone(),
+            0,
+            CheetahString::from_string(get_store_path_batch_consume_queue(
+                message_store
+                    .message_store_config
+                    .store_path_root_dir
+                    .as_str(),
+            )),
+            message_store
+                .message_store_config
+                .mapper_file_size_batch_consume_queue,
+            None,
+            message_store.message_store_config.clone(),
+        );
+        // batches of 10 messages at these commit log positions, two batches per file
+        for (index, pos) in [0i64, 1000, 5000, 9000, 9500].into_iter().enumerate() {
+            assert!(batch_consume_queue.put_batch_message_position_info(
+                pos,
+                400,
+                0,
+                0,
+                index as i64 * 10,
+                10,
+            ));
+        }
+        let consume_queue: ArcConsumeQueue = ArcMut::new(Box::new(batch_consume_queue));
+        message_store
+            .consume_queue_store
+            .get_consume_queue_table()
+            .lock()
+            .entry(topic.clone())
+            .or_default()
+            .insert(0, consume_queue.clone());
+        let logic = consume_queue.as_ref().as_ref();
+        let service = &message_store.correct_logic_offset_service;
+        let now = get_current_millis() as i64;
+        assert!(!service.need_correct(logic, 0, now));
+
+        delete_commit_log_files_manually(&message_store);
+        let min_phy_offset = message_store.commit_log.get_min_offset();
+        assert_eq!(min_phy_offset, 8192);
+        assert!(service.need_correct(logic, min_phy_offset, now));
+
+        service.run();
+
+        assert_eq!(logic.get_min_offset_in_queue(), 30);
+        assert_eq!(logic.get_earliest_unit().unwrap().pos, 9000);
+        assert!(!service.need_correct(logic, min_phy_offset, now));
+    }
+
+    #[test]
+    fn rocksdb_store_type_keeps_consume_queues_in_kv_table() {
+        let (message_store, _temp_dir) =
+            new_message_store_with_commit_log_files(MessageStoreConfig {
+                store_type: StoreType::RocksDB,
+                rocksdb_cq_double_write_enable: true,
+                file_reserved_time: 0,
+                delete_consume_queue_files_interval: 0,
+                ..MessageStoreConfig::default()
+            });
+        assert!(message_store.kv_consume_queue_store.is_none());
+        assert!(message_store.mut_from_ref().commit_log.load());
+        assert!(message_store.mut_from_ref().consume_queue_store.load());
+        let topic = CheetahString::from_static_str(TOPIC);
+        for queue_offset in 0..30i64 {
+            message_store
+                .consume_queue_store
+                .put_message_position_info_wrapper(&DispatchRequest {
+                    topic: topic.clone(),
+                    commit_log_offset: queue_offset * 400,
+                    msg_size: 400,
+                    consume_queue_offset: queue_offset,
+                    ..DispatchRequest::default()
+                });
+        }
+        let consume_queue = message_store
+            .consume_queue_store
+            .find_or_create_consume_queue(&topic, 0);
+        assert_eq!(consume_queue.get_cq_type(), CQType::RocksDBCQ);
+        assert!(!Path::new(&LocalFileMessageStore::get_store_path_logic(
+            &message_store.message_store_config
+        ))
+        .join(TOPIC)
+        .exists());
+        assert_eq!(message_store.get_max_offset_in_queue(&topic, 0), 30);
+        assert_eq!(consume_queue.get(12).unwrap().pos, 4800);
+
+        delete_commit_log_files_manually(&message_store);
+        message_store.clean_consume_queue_service.run();
+        assert_eq!(message_store.get_min_offset_in_queue(&topic, 0), 21);
+        assert!(message_store.consume_queue_store.shutdown());
+
+        // a fresh store finds the queue again in the kv table
+        let mut consume_queue_store = ConsumeQueueStore::new(
+            message_store.message_store_config.clone(),
+            message_store.broker_config.clone(),
+        );
+        consume_queue_store.set_message_store(message_store.clone());
+        assert!(consume_queue_store.load());
+        let queue_table = consume_queue_store.find_consume_queue_map(&topic).unwrap();
+        assert_eq!(queue_table[&0].get_min_offset_in_queue(), 21);
+        assert_eq!(queue_table[&0].get_max_offset_in_queue(), 30);
+    }
+}
@@ -24,12 +24,15 @@ use crate::queue::consume_queue::ConsumeQueueTrait;
 use crate::queue::consume_queue_ext::ConsumeQueueExt;
 use crate::queue::file_queue_life_cycle::FileQueueLifeCycle;
 
-mod batch_consume_queue;
+pub(crate) mod batch_consume_queue;
 pub mod build_consume_queue;
 pub mod consume_queue;
 mod consume_queue_ext;
 pub mod consume_queue_store;
 mod file_queue_life_cycle;
+pub mod kv_consume_queue;
+pub mod kv_consume_queue_store;
+mod kv_consume_queue_table;
 pub mod local_file_consume_queue_store;
 mod queue_offset_operator;
 pub mod referred_iterator;
@@ -65,6 +68,16 @@ impl Default for CqUnit {
     }
 }
 
+impl std::fmt::Display for CqUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CqUnit{{queueOffset={}, size={}, pos={}, batchNum={}, compactedOffset={}}}",
+            self.queue_offset, self.size, self.pos, self.batch_num, self.compacted_offset
+        )
+    }
+}
+
 impl CqUnit {
     pub fn get_valid_tags_code_as_long(&self) -> Option<i64> {
         if !self.is_tags_code_valid() {
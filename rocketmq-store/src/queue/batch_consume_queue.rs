@@ -14,31 +14,43 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
 use cheetah_string::CheetahString;
 use rocketmq_common::common::attribute::cq_type::CQType;
 use rocketmq_common::common::boundary_type::BoundaryType;
 use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
+use rocketmq_common::MessageAccessor::MessageAccessor;
+use rocketmq_common::MessageDecoder;
+use tracing::error;
 use tracing::info;
+use tracing::warn;
 
 use crate::base::dispatch_request::DispatchRequest;
+use crate::base::select_result::SelectMappedBufferResult;
 use crate::base::swappable::Swappable;
 use crate::config::message_store_config::MessageStoreConfig;
 use crate::consume_queue::mapped_file_queue::MappedFileQueue;
 use crate::filter::MessageFilter;
 use crate::log_file::mapped_file::default_mapped_file_impl::DefaultMappedFile;
+use crate::log_file::mapped_file::MappedFile;
 use crate::queue::consume_queue::ConsumeQueueTrait;
 use crate::queue::queue_offset_operator::QueueOffsetOperator;
 use crate::queue::referred_iterator::ReferredIterator;
 use crate::queue::CqUnit;
 use crate::queue::FileQueueLifeCycle;
 
-const CQ_STORE_UNIT_SIZE: i32 = 46;
+pub(crate) const CQ_STORE_UNIT_SIZE: i32 = 46;
 const MSG_TAG_OFFSET_INDEX: i32 = 12;
 const MSG_STORE_TIME_OFFSET_INDEX: i32 = 20;
 const MSG_BASE_OFFSET_INDEX: i32 = 28;
@@ -74,8 +86,17 @@ pub struct BatchConsumeQueue {
     max_offset_in_queue: Arc<AtomicI64>,
     min_offset_in_queue: Arc<AtomicI64>,
     commit_log_size: i32,
-    offset_cache: Arc<parking_lot::RwLock<BTreeMap<i64, DefaultMappedFile>>>,
-    time_cache: Arc<parking_lot::RwLock<BTreeMap<i64, DefaultMappedFile>>>,
+    offset_cache: Arc<parking_lot::RwLock<BTreeMap<i64, Arc<DefaultMappedFile>>>>,
+    time_cache: Arc<parking_lot::RwLock<BTreeMap<i64, Arc<DefaultMappedFile>>>>,
+}
+
+/// Location of a single store unit inside a batch consume queue file.
+struct BatchOffsetIndex {
+    mapped_file: Arc<DefaultMappedFile>,
+    index_pos: i32,
+    msg_offset: i64,
+    batch_size: i16,
+    store_timestamp: i64,
 }
 
 impl BatchConsumeQueue {
@@ -111,7 +132,7 @@ impl BatchConsumeQueue {
             )
         };
 
-        let byte_buffer_item = vec![0u8; CQ_STORE_UNIT_SIZE as usize];
+        let byte_buffer_item = Vec::with_capacity(CQ_STORE_UNIT_SIZE as usize);
 
         BatchConsumeQueue {
             message_store_config,
@@ -132,7 +153,318 @@ impl BatchConsumeQueue {
     }
 }
 
-#[allow(unused_variables)]
+impl BatchConsumeQueue {
+    #[inline]
+    fn topic_queue_key(msg: &MessageExtBrokerInner) -> CheetahString {
+        CheetahString::from_string(format!("{}-{}", msg.topic(), msg.queue_id()))
+    }
+
+    #[inline]
+    fn read_i64(mapped_file: &DefaultMappedFile, pos: i32) -> Option<i64> {
+        mapped_file
+            .get_bytes(pos as usize, 8)
+            .map(|mut bytes| bytes.get_i64())
+    }
+
+    /// Reads the store unit at `index_pos` of `mapped_file`.
+    fn get_batch_offset_index(
+        mapped_file: &Arc<DefaultMappedFile>,
+        index_pos: i32,
+    ) -> Option<BatchOffsetIndex> {
+        let mut bytes = mapped_file.get_bytes(index_pos as usize, CQ_STORE_UNIT_SIZE as usize)?;
+        bytes.advance(MSG_STORE_TIME_OFFSET_INDEX as usize);
+        let store_timestamp = bytes.get_i64();
+        let msg_offset = bytes.get_i64();
+        let batch_size = bytes.get_i16();
+        Some(BatchOffsetIndex {
+            mapped_file: mapped_file.clone(),
+            index_pos,
+            msg_offset,
+            batch_size,
+            store_timestamp,
+        })
+    }
+
+    /// The first store unit of `mapped_file`, or `None` if the file holds no unit yet.
+    fn get_min_msg_offset(mapped_file: &Arc<DefaultMappedFile>) -> Option<BatchOffsetIndex> {
+        if mapped_file.get_read_position() < CQ_STORE_UNIT_SIZE {
+            return None;
+        }
+        Self::get_batch_offset_index(mapped_file, 0)
+    }
+
+    /// The last store unit of `mapped_file`, or `None` if the file holds no unit yet.
+    fn get_max_msg_offset(mapped_file: &Arc<DefaultMappedFile>) -> Option<BatchOffsetIndex> {
+        let read_position = mapped_file.get_read_position();
+        if read_position < CQ_STORE_UNIT_SIZE {
+            return None;
+        }
+        let index_pos =
+            read_position / CQ_STORE_UNIT_SIZE * CQ_STORE_UNIT_SIZE - CQ_STORE_UNIT_SIZE;
+        Self::get_batch_offset_index(mapped_file, index_pos)
+    }
+
+    fn revise_min_offset_in_queue(&self) {
+        match self.mapped_file_queue.get_first_mapped_file() {
+            None => {
+                self.max_offset_in_queue.store(0, Ordering::Release);
+                self.min_offset_in_queue.store(-1, Ordering::Release);
+                self.min_logic_offset.store(-1, Ordering::Release);
+                info!(
+                    "reviseMinOffsetInQueue: no mapped file in batch consume queue {}-{}",
+                    self.topic, self.queue_id
+                );
+            }
+            Some(first_mapped_file) => {
+                self.min_logic_offset.store(
+                    first_mapped_file.get_file_from_offset() as i64,
+                    Ordering::Release,
+                );
+                let min_offset =
+                    Self::get_min_msg_offset(&first_mapped_file).map_or(-1, |min| min.msg_offset);
+                self.min_offset_in_queue
+                    .store(min_offset, Ordering::Release);
+            }
+        }
+    }
+
+    fn revise_max_offset_in_queue(&self) {
+        let mut max = self
+            .mapped_file_queue
+            .get_last_mapped_file()
+            .and_then(|last| Self::get_max_msg_offset(&last));
+        if max.is_none() {
+            let mapped_files = self.mapped_file_queue.get_mapped_files();
+            let mapped_files = mapped_files.read();
+            if mapped_files.len() >= 2 {
+                max = Self::get_max_msg_offset(&mapped_files[mapped_files.len() - 2]);
+            }
+        }
+        let max_offset = max.map_or(0, |max| max.msg_offset + max.batch_size as i64);
+        self.max_offset_in_queue
+            .store(max_offset, Ordering::Release);
+    }
+
+    #[inline]
+    fn revise_max_and_min_offset_in_queue(&self) {
+        self.revise_min_offset_in_queue();
+        self.revise_max_offset_in_queue();
+    }
+
+    /// Caches the first message offset and store time of `mapped_file`, so lookups only need to
+    /// binary search a single file.
+    fn cache_bcq(&self, mapped_file: &Arc<DefaultMappedFile>) {
+        if !self.message_store_config.search_bcq_by_cache_enable {
+            return;
+        }
+        if let Some(min) = Self::get_min_msg_offset(mapped_file) {
+            self.offset_cache
+                .write()
+                .insert(min.msg_offset, mapped_file.clone());
+            self.time_cache
+                .write()
+                .insert(min.store_timestamp, mapped_file.clone());
+        }
+    }
+
+    fn refresh_cache(&self) {
+        if !self.message_store_config.search_bcq_by_cache_enable {
+            return;
+        }
+        let mut offset_cache = BTreeMap::new();
+        let mut time_cache = BTreeMap::new();
+        for mapped_file in self.mapped_file_queue.get_mapped_files().read().iter() {
+            if let Some(min) = Self::get_min_msg_offset(mapped_file) {
+                offset_cache.insert(min.msg_offset, mapped_file.clone());
+                time_cache.insert(min.store_timestamp, mapped_file.clone());
+            }
+        }
+        *self.offset_cache.write() = offset_cache;
+        *self.time_cache.write() = time_cache;
+    }
+
+    /// Finds the file whose first message offset is the greatest one not above `msg_offset`.
+    fn search_offset(&self, msg_offset: i64) -> Option<Arc<DefaultMappedFile>> {
+        if self.message_store_config.search_bcq_by_cache_enable {
+            return self
+                .offset_cache
+                .read()
+                .range(..=msg_offset)
+                .next_back()
+                .map(|(_, mapped_file)| mapped_file.clone());
+        }
+        self.mapped_file_queue
+            .get_mapped_files()
+            .read()
+            .iter()
+            .rev()
+            .find(|mapped_file| {
+                Self::get_min_msg_offset(mapped_file)
+                    .is_some_and(|min| min.msg_offset <= msg_offset)
+            })
+            .cloned()
+    }
+
+    /// Finds the file whose first store time is the greatest one not above `timestamp`.
+    fn search_time(&self, timestamp: i64) -> Option<Arc<DefaultMappedFile>> {
+        if self.message_store_config.search_bcq_by_cache_enable {
+            return self
+                .time_cache
+                .read()
+                .range(..=timestamp)
+                .next_back()
+                .map(|(_, mapped_file)| mapped_file.clone());
+        }
+        self.mapped_file_queue
+            .get_mapped_files()
+            .read()
+            .iter()
+            .rev()
+            .find(|mapped_file| {
+                Self::get_min_msg_offset(mapped_file)
+                    .is_some_and(|min| min.store_timestamp <= timestamp)
+            })
+            .cloned()
+    }
+
+    /// Binary searches the units between `left` and `right` (both unit positions, inclusive) for
+    /// the batch containing `msg_offset`, returning the unit position.
+    fn binary_search_offset(
+        mapped_file: &DefaultMappedFile,
+        left: i32,
+        right: i32,
+        msg_offset: i64,
+    ) -> Option<i32> {
+        let mut low = left / CQ_STORE_UNIT_SIZE;
+        let mut high = right / CQ_STORE_UNIT_SIZE;
+        while low <= high {
+            let mid = (low + high) / 2;
+            let pos = mid * CQ_STORE_UNIT_SIZE;
+            let mut bytes = mapped_file.get_bytes((pos + MSG_BASE_OFFSET_INDEX) as usize, 10)?;
+            let base_offset = bytes.get_i64();
+            let batch_size = bytes.get_i16() as i64;
+            if base_offset > msg_offset {
+                high = mid - 1;
+            } else if base_offset + batch_size > msg_offset {
+                return Some(pos);
+            } else {
+                low = mid + 1;
+            }
+        }
+        None
+    }
+
+    /// Binary searches the units between `left` and `right` (both unit positions, inclusive) on
+    /// the `i64` field at `unit_shift`. With [`BoundaryType::Lower`] the first unit whose value is
+    /// not less than `target` is returned, with [`BoundaryType::Upper`] the last unit whose value
+    /// is not greater than `target`.
+    fn binary_search_boundary(
+        mapped_file: &DefaultMappedFile,
+        left: i32,
+        right: i32,
+        unit_shift: i32,
+        target: i64,
+        boundary_type: BoundaryType,
+    ) -> Option<i32> {
+        let first = left / CQ_STORE_UNIT_SIZE;
+        let last = right / CQ_STORE_UNIT_SIZE;
+        let mut low = first;
+        let mut high = last + 1;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            let value = Self::read_i64(mapped_file, mid * CQ_STORE_UNIT_SIZE + unit_shift)?;
+            let go_right = match boundary_type {
+                BoundaryType::Lower => value < target,
+                BoundaryType::Upper => value <= target,
+            };
+            if go_right {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let unit = match boundary_type {
+            BoundaryType::Lower => low,
+            BoundaryType::Upper => low - 1,
+        };
+        if unit < first || unit > last {
+            return None;
+        }
+        Some(unit * CQ_STORE_UNIT_SIZE)
+    }
+
+    /// Selects the index buffer starting at the unit that contains `msg_offset`.
+    pub fn get_batch_msg_index_buffer(&self, msg_offset: i64) -> Option<SelectMappedBufferResult> {
+        let target_bcq = self.search_offset(msg_offset)?;
+        let min = Self::get_min_msg_offset(&target_bcq)?;
+        let max = Self::get_max_msg_offset(&target_bcq)?;
+        let pos =
+            Self::binary_search_offset(&target_bcq, min.index_pos, max.index_pos, msg_offset)?;
+        let mut result = target_bcq.select_mapped_buffer_with_position(pos);
+        if let Some(ref mut result) = result {
+            result.mapped_file = Some(target_bcq);
+        }
+        result
+    }
+
+    pub fn put_batch_message_position_info(
+        &mut self,
+        offset: i64,
+        size: i32,
+        tags_code: i64,
+        store_time: i64,
+        msg_base_offset: i64,
+        batch_size: i16,
+    ) -> bool {
+        if offset <= self.get_max_physic_offset() {
+            warn!(
+                "Build batch consume queue repeatedly, maxMsgPhyOffsetInCommitLog:{}, offset:{}, \
+                 Topic: {} QID: {}",
+                self.get_max_physic_offset(),
+                offset,
+                self.topic,
+                self.queue_id
+            );
+            return true;
+        }
+
+        self.byte_buffer_item.clear();
+        self.byte_buffer_item.put_i64(offset);
+        self.byte_buffer_item.put_i32(size);
+        self.byte_buffer_item.put_i64(tags_code);
+        self.byte_buffer_item.put_i64(store_time);
+        self.byte_buffer_item.put_i64(msg_base_offset);
+        self.byte_buffer_item.put_i16(batch_size);
+        self.byte_buffer_item.put_i32(INVALID_POS);
+        self.byte_buffer_item.put_i32(0); // reserved
+
+        let max_offset = self.mapped_file_queue.get_max_offset();
+        let Some(mapped_file) = self
+            .mapped_file_queue
+            .get_last_mapped_file_mut_start_offset(max_offset as u64, true)
+        else {
+            return false;
+        };
+        let is_new_file = mapped_file.get_wrote_position() == 0;
+        if !mapped_file.append_message_bytes(&self.byte_buffer_item) {
+            return false;
+        }
+        self.max_msg_phy_offset_in_commit_log
+            .store(offset, Ordering::Release);
+        self.max_offset_in_queue
+            .store(msg_base_offset + batch_size as i64, Ordering::Release);
+        // only the first write needs to correct the min offset, later corrections are done by
+        // correct_min_offset
+        if self.min_offset_in_queue.load(Ordering::Acquire) == -1 {
+            self.revise_min_offset_in_queue();
+        }
+        if is_new_file {
+            self.cache_bcq(&mapped_file);
+        }
+        true
+    }
+}
+
 impl FileQueueLifeCycle for BatchConsumeQueue {
     #[inline]
     fn load(&mut self) -> bool {
@@ -147,49 +479,180 @@ impl FileQueueLifeCycle for BatchConsumeQueue {
         result
     }
 
-    #[inline]
     fn recover(&mut self) {
-        todo!()
+        let binding = self.mapped_file_queue.get_mapped_files();
+        let mapped_files = binding.read().clone();
+        if mapped_files.is_empty() {
+            return;
+        }
+        let mut index = mapped_files.len().saturating_sub(3);
+        let mapped_file_size_logics = self.mapped_file_size as i32;
+        let mut mapped_file = &mapped_files[index];
+        let mut process_offset = mapped_file.get_file_from_offset() as i64;
+        let mut mapped_file_offset = 0i64;
+        loop {
+            for unit in 0..(mapped_file_size_logics / CQ_STORE_UNIT_SIZE) {
+                let Some(mut bytes) = mapped_file.get_bytes(
+                    (unit * CQ_STORE_UNIT_SIZE) as usize,
+                    CQ_STORE_UNIT_SIZE as usize,
+                ) else {
+                    break;
+                };
+                let offset = bytes.get_i64();
+                let size = bytes.get_i32();
+                if offset >= 0 && size > 0 {
+                    mapped_file_offset = ((unit + 1) * CQ_STORE_UNIT_SIZE) as i64;
+                    self.max_msg_phy_offset_in_commit_log
+                        .store(offset, Ordering::Release);
+                } else {
+                    info!(
+                        "Recover current batch consume queue file over, file:{} offset:{} size:{}",
+                        mapped_file.get_file_name(),
+                        offset,
+                        size
+                    );
+                    break;
+                }
+            }
+            if mapped_file_offset == mapped_file_size_logics as i64 {
+                index += 1;
+                if index >= mapped_files.len() {
+                    info!(
+                        "Recover last batch consume queue file over, last mapped file:{}",
+                        mapped_file.get_file_name()
+                    );
+                    break;
+                }
+                mapped_file = &mapped_files[index];
+                process_offset = mapped_file.get_file_from_offset() as i64;
+                mapped_file_offset = 0;
+                info!(
+                    "Recover next batch consume queue file: {}",
+                    mapped_file.get_file_name()
+                );
+            } else {
+                info!(
+                    "Recover current batch consume queue file over {} {}",
+                    mapped_file.get_file_name(),
+                    process_offset + mapped_file_offset
+                );
+                break;
+            }
+        }
+        process_offset += mapped_file_offset;
+        self.mapped_file_queue.set_flushed_where(process_offset);
+        self.mapped_file_queue.set_committed_where(process_offset);
+        self.mapped_file_queue.truncate_dirty_files(process_offset);
+        self.revise_max_and_min_offset_in_queue();
+        self.refresh_cache();
     }
 
     #[inline]
     fn check_self(&self) {
-        todo!()
+        self.mapped_file_queue.check_self();
     }
 
     #[inline]
     fn flush(&self, flush_least_pages: i32) -> bool {
-        todo!()
+        self.mapped_file_queue.flush(flush_least_pages)
     }
 
     #[inline]
     fn destroy(&mut self) {
-        todo!()
+        self.max_msg_phy_offset_in_commit_log
+            .store(-1, Ordering::Release);
+        self.min_logic_offset.store(0, Ordering::Release);
+        self.max_offset_in_queue.store(0, Ordering::Release);
+        self.min_offset_in_queue.store(-1, Ordering::Release);
+        self.mapped_file_queue.destroy();
+        self.offset_cache.write().clear();
+        self.time_cache.write().clear();
     }
 
-    #[inline]
     fn truncate_dirty_logic_files(&mut self, max_commit_log_pos: i64) {
-        todo!()
+        let logic_file_size = self.mapped_file_size as i32;
+        self.max_msg_phy_offset_in_commit_log
+            .store(max_commit_log_pos - 1, Ordering::Release);
+        let mut stop = false;
+        while !stop {
+            let Some(mapped_file) = self.mapped_file_queue.get_last_mapped_file() else {
+                break;
+            };
+            mapped_file.set_wrote_position(0);
+            mapped_file.set_committed_position(0);
+            mapped_file.set_flushed_position(0);
+            let mut delete_last_file = false;
+            for unit in 0..(logic_file_size / CQ_STORE_UNIT_SIZE) {
+                let pos = unit * CQ_STORE_UNIT_SIZE;
+                let Some(mut bytes) =
+                    mapped_file.get_bytes(pos as usize, CQ_STORE_UNIT_SIZE as usize)
+                else {
+                    stop = true;
+                    break;
+                };
+                let offset = bytes.get_i64();
+                let size = bytes.get_i32();
+                if unit == 0 && offset >= max_commit_log_pos {
+                    delete_last_file = true;
+                    break;
+                }
+                if offset < 0 || size <= 0 || offset >= max_commit_log_pos {
+                    stop = true;
+                    break;
+                }
+                let next_pos = pos + CQ_STORE_UNIT_SIZE;
+                mapped_file.set_wrote_position(next_pos);
+                mapped_file.set_committed_position(next_pos);
+                mapped_file.set_flushed_position(next_pos);
+                self.max_msg_phy_offset_in_commit_log
+                    .store(offset, Ordering::Release);
+                if next_pos == logic_file_size {
+                    stop = true;
+                    break;
+                }
+            }
+            if delete_last_file {
+                self.mapped_file_queue.delete_last_mapped_file();
+            } else {
+                stop = true;
+            }
+        }
+        self.revise_max_and_min_offset_in_queue();
+        self.refresh_cache();
     }
 
-    #[inline]
     fn delete_expired_file(&self, min_commit_log_pos: i64) -> i32 {
-        todo!()
+        let count = self
+            .mapped_file_queue
+            .delete_expired_file_by_offset(min_commit_log_pos, CQ_STORE_UNIT_SIZE);
+        self.correct_min_offset(min_commit_log_pos);
+        self.refresh_cache();
+        count
     }
 
-    #[inline]
     fn roll_next_file(&self, next_begin_offset: i64) -> i64 {
-        todo!()
+        // the first message offset of the file following the one holding `next_begin_offset`
+        let mapped_files = self.mapped_file_queue.get_mapped_files();
+        let mapped_files = mapped_files.read();
+        mapped_files
+            .iter()
+            .filter_map(Self::get_min_msg_offset)
+            .map(|min| min.msg_offset)
+            .find(|msg_offset| *msg_offset > next_begin_offset)
+            .unwrap_or_else(|| self.get_max_offset_in_queue())
     }
 
     #[inline]
     fn is_first_file_available(&self) -> bool {
-        todo!()
+        match self.mapped_file_queue.get_first_mapped_file() {
+            None => false,
+            Some(mapped_file) => mapped_file.is_available(),
+        }
     }
 
     #[inline]
     fn is_first_file_exist(&self) -> bool {
-        todo!()
+        self.mapped_file_queue.get_first_mapped_file().is_some()
     }
 }
 
@@ -197,113 +660,206 @@ impl Swappable for BatchConsumeQueue {
     #[inline]
     fn swap_map(
         &self,
-        reserve_num: i32,
-        force_swap_interval_ms: i64,
-        normal_swap_interval_ms: i64,
+        _reserve_num: i32,
+        _force_swap_interval_ms: i64,
+        _normal_swap_interval_ms: i64,
     ) {
-        todo!()
+        // mapped files of the batch consume queue are kept mapped
     }
 
     #[inline]
-    fn clean_swapped_map(&self, force_clean_swap_interval_ms: i64) {
-        todo!()
-    }
+    fn clean_swapped_map(&self, _force_clean_swap_interval_ms: i64) {}
 }
 
 impl ConsumeQueueTrait for BatchConsumeQueue {
     #[inline]
     fn get_topic(&self) -> &CheetahString {
-        todo!()
+        &self.topic
     }
 
     #[inline]
     fn get_queue_id(&self) -> i32 {
-        todo!()
+        self.queue_id
     }
 
     #[inline]
     fn get(&self, index: i64) -> Option<CqUnit> {
-        todo!()
+        self.iterate_from(index)?.next_and_release()
     }
 
-    #[inline]
     fn get_cq_unit_and_store_time(&self, index: i64) -> Option<(CqUnit, i64)> {
-        todo!()
+        let mut result = self.get_batch_msg_index_buffer(index)?;
+        let unit = result
+            .bytes
+            .as_ref()
+            .filter(|bytes| bytes.len() >= CQ_STORE_UNIT_SIZE as usize)
+            .map(|bytes| parse_unit(bytes.slice(0..CQ_STORE_UNIT_SIZE as usize)));
+        result.release();
+        unit
     }
 
     #[inline]
     fn get_earliest_unit_and_store_time(&self) -> Option<(CqUnit, i64)> {
-        todo!()
+        self.get_cq_unit_and_store_time(self.get_min_offset_in_queue())
     }
 
     #[inline]
     fn get_earliest_unit(&self) -> Option<CqUnit> {
-        todo!()
+        self.get(self.get_min_offset_in_queue())
     }
 
     #[inline]
     fn get_latest_unit(&self) -> Option<CqUnit> {
-        todo!()
+        self.get(self.get_max_offset_in_queue() - 1)
     }
 
-    #[inline]
     fn get_last_offset(&self) -> i64 {
-        todo!()
+        self.mapped_file_queue
+            .get_last_mapped_file()
+            .and_then(|mapped_file| {
+                let max = Self::get_max_msg_offset(&mapped_file)?;
+                let mut bytes = mapped_file.get_bytes(max.index_pos as usize, 12)?;
+                let offset = bytes.get_i64();
+                let size = bytes.get_i32();
+                Some(offset + size as i64)
+            })
+            .unwrap_or(-1)
     }
 
     #[inline]
     fn get_min_offset_in_queue(&self) -> i64 {
-        todo!()
+        let min_offset = self.min_offset_in_queue.load(Ordering::Acquire);
+        if min_offset < 0 {
+            // an empty queue starts where the next batch will be written
+            return self.get_max_offset_in_queue();
+        }
+        min_offset
     }
 
     #[inline]
     fn get_max_offset_in_queue(&self) -> i64 {
-        todo!()
+        self.max_offset_in_queue.load(Ordering::Acquire)
     }
 
     #[inline]
     fn get_message_total_in_queue(&self) -> i64 {
-        todo!()
+        self.get_max_offset_in_queue() - self.get_min_offset_in_queue()
     }
 
     #[inline]
     fn get_offset_in_queue_by_time(&self, timestamp: i64) -> i64 {
-        todo!()
+        self.get_offset_in_queue_by_time_with_boundary(timestamp, BoundaryType::Lower)
     }
 
     #[inline]
     fn get_max_physic_offset(&self) -> i64 {
-        todo!()
+        self.max_msg_phy_offset_in_commit_log
+            .load(Ordering::Acquire)
     }
 
     #[inline]
     fn get_min_logic_offset(&self) -> i64 {
-        todo!()
+        self.min_logic_offset.load(Ordering::Acquire)
     }
 
     #[inline]
     fn get_cq_type(&self) -> CQType {
-        todo!()
+        CQType::BatchCQ
     }
 
     #[inline]
     fn get_total_size(&self) -> i64 {
-        todo!()
+        self.mapped_file_queue.get_mapped_files_size() as i64 * self.mapped_file_size as i64
     }
 
     #[inline]
     fn get_unit_size(&self) -> i32 {
-        todo!()
+        CQ_STORE_UNIT_SIZE
     }
 
-    #[inline]
     fn correct_min_offset(&self, min_commit_log_offset: i64) {
-        todo!()
+        let Some(first_mapped_file) = self.mapped_file_queue.get_first_mapped_file() else {
+            return;
+        };
+        let (Some(min), Some(max)) = (
+            Self::get_min_msg_offset(&first_mapped_file),
+            Self::get_max_msg_offset(&first_mapped_file),
+        ) else {
+            return;
+        };
+        let file_from_offset = first_mapped_file.get_file_from_offset() as i64;
+        match Self::binary_search_boundary(
+            &first_mapped_file,
+            min.index_pos,
+            max.index_pos,
+            0,
+            min_commit_log_offset,
+            BoundaryType::Lower,
+        ) {
+            Some(pos) => {
+                let Some(index) = Self::get_batch_offset_index(&first_mapped_file, pos) else {
+                    return;
+                };
+                self.min_offset_in_queue
+                    .store(index.msg_offset, Ordering::Release);
+                self.min_logic_offset
+                    .store(file_from_offset + pos as i64, Ordering::Release);
+            }
+            None => {
+                // every unit of the first file points below the commit log min offset
+                self.min_offset_in_queue
+                    .store(max.msg_offset + max.batch_size as i64, Ordering::Release);
+                self.min_logic_offset.store(
+                    file_from_offset + first_mapped_file.get_read_position() as i64,
+                    Ordering::Release,
+                );
+            }
+        }
+        info!(
+            "BatchConsumeQueue[topic={}, queue-id={}] min offset corrected to {}",
+            self.topic,
+            self.queue_id,
+            self.get_min_offset_in_queue()
+        );
     }
 
-    #[inline]
     fn put_message_position_info_wrapper(&mut self, request: &DispatchRequest) {
-        todo!()
+        let max_retries = 30i32;
+        // messages that are not inner batches occupy a batch of their own
+        let (msg_base_offset, batch_size) = if request.msg_base_offset < 0 {
+            (request.consume_queue_offset, request.batch_size.max(1))
+        } else {
+            (request.msg_base_offset, request.batch_size)
+        };
+        if batch_size <= 0 {
+            warn!(
+                "[NOTIFYME]unexpected dispatch request in batch consume queue topic:{} queue:{} \
+                 offset:{}",
+                self.topic, self.queue_id, request.commit_log_offset
+            );
+            return;
+        }
+        for i in 0..max_retries {
+            if self.put_batch_message_position_info(
+                request.commit_log_offset,
+                request.msg_size,
+                request.tags_code,
+                request.store_timestamp,
+                msg_base_offset,
+                batch_size,
+            ) {
+                return;
+            }
+            warn!(
+                "[BUG]put commit log position info to batch consume queue {}:{}:{} failed, retry \
+                 {} times",
+                self.topic, self.queue_id, request.commit_log_offset, i
+            );
+        }
+        error!(
+            "[BUG]batch consume queue can not write, {} {}",
+            self.topic, self.queue_id
+        );
     }
 
     #[inline]
@@ -313,34 +869,65 @@ impl ConsumeQueueTrait for BatchConsumeQueue {
         msg: &MessageExtBrokerInner,
         message_num: i16,
     ) {
-        todo!()
+        queue_offset_assigner.increase_batch_queue_offset(&Self::topic_queue_key(msg), message_num);
     }
 
-    #[inline]
     fn assign_queue_offset(
         &self,
         queue_offset_operator: &QueueOffsetOperator,
         msg: &mut MessageExtBrokerInner,
     ) {
-        todo!()
+        let queue_offset =
+            queue_offset_operator.get_batch_queue_offset(&Self::topic_queue_key(msg));
+        if MessageSysFlag::check(msg.sys_flag(), MessageSysFlag::INNER_BATCH_FLAG) {
+            MessageAccessor::put_property(
+                msg,
+                CheetahString::from_static_str(MessageConst::PROPERTY_INNER_BASE),
+                CheetahString::from_string(queue_offset.to_string()),
+            );
+            msg.properties_string =
+                MessageDecoder::message_properties_to_string(msg.get_properties());
+        }
+        msg.message_ext_inner.queue_offset = queue_offset;
     }
 
-    #[inline]
     fn estimate_message_count(&self, from: i64, to: i64, filter: &dyn MessageFilter) -> i64 {
-        todo!()
+        let Some(mut iterator) = self.iterate_from(from) else {
+            return 0;
+        };
+        let mut count = 0i64;
+        for cq_unit in iterator.by_ref() {
+            if cq_unit.queue_offset >= to {
+                break;
+            }
+            if filter.is_matched_by_consume_queue(
+                cq_unit.get_valid_tags_code_as_long(),
+                cq_unit.cq_ext_unit.as_ref(),
+            ) {
+                count += cq_unit.batch_num as i64;
+            }
+        }
+        iterator.release();
+        count
     }
 
     #[inline]
     fn iterate_from(&self, start_index: i64) -> Option<Box<dyn ReferredIterator<CqUnit>>> {
-        todo!()
+        self.get_batch_msg_index_buffer(start_index).map(|smbr| {
+            Box::new(BatchConsumeQueueIterator {
+                smbr: Some(smbr),
+                relative_pos: 0,
+            }) as Box<dyn ReferredIterator<CqUnit>>
+        })
     }
 
+    #[inline]
     fn iterate_from_with_count(
         &self,
         start_index: i64,
-        count: i32,
+        _count: i32,
     ) -> Option<Box<dyn ReferredIterator<CqUnit>>> {
-        todo!()
+        self.iterate_from(start_index)
     }
 
     fn get_offset_in_queue_by_time_with_boundary(
@@ -348,6 +935,203 @@ impl ConsumeQueueTrait for BatchConsumeQueue {
         timestamp: i64,
         boundary_type: BoundaryType,
     ) -> i64 {
-        todo!()
+        let Some(target_bcq) = self.search_time(timestamp) else {
+            // the timestamp is earlier than any stored message
+            return self.get_min_offset_in_queue();
+        };
+        let (Some(min), Some(max)) = (
+            Self::get_min_msg_offset(&target_bcq),
+            Self::get_max_msg_offset(&target_bcq),
+        ) else {
+            return -1;
+        };
+        match Self::binary_search_boundary(
+            &target_bcq,
+            min.index_pos,
+            max.index_pos,
+            MSG_STORE_TIME_OFFSET_INDEX,
+            timestamp,
+            boundary_type,
+        )
+        .and_then(|pos| Self::get_batch_offset_index(&target_bcq, pos))
+        {
+            Some(index) => index.msg_offset,
+            // every batch of the file was stored before the timestamp
+            None => max.msg_offset + max.batch_size as i64,
+        }
+    }
+}
+
+/// Decodes a store unit into a [`CqUnit`] and its store timestamp.
+fn parse_unit(mut bytes: Bytes) -> (CqUnit, i64) {
+    let pos = bytes.get_i64();
+    let size = bytes.get_i32();
+    let tags_code = bytes.get_i64();
+    let store_time = bytes.get_i64();
+    let queue_offset = bytes.get_i64();
+    let batch_num = bytes.get_i16();
+    let compacted_offset = bytes.get_i32();
+    let cq_unit = CqUnit {
+        queue_offset,
+        size,
+        pos,
+        batch_num,
+        tags_code,
+        compacted_offset,
+        ..CqUnit::default()
+    };
+    (cq_unit, store_time)
+}
+
+struct BatchConsumeQueueIterator {
+    smbr: Option<SelectMappedBufferResult>,
+    relative_pos: i32,
+}
+
+impl ReferredIterator<CqUnit> for BatchConsumeQueueIterator {
+    fn release(&mut self) {
+        if let Some(smbr) = self.smbr.as_mut() {
+            smbr.release();
+        }
+    }
+
+    fn next_and_release(&mut self) -> Option<Self::Item> {
+        let cq_unit = self.next();
+        self.release();
+        cq_unit
+    }
+}
+
+impl Iterator for BatchConsumeQueueIterator {
+    type Item = CqUnit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let smbr = self.smbr.as_ref()?;
+        if self.relative_pos + CQ_STORE_UNIT_SIZE > smbr.size {
+            return None;
+        }
+        let bytes = smbr
+            .bytes
+            .as_ref()?
+            .slice(self.relative_pos as usize..(self.relative_pos + CQ_STORE_UNIT_SIZE) as usize);
+        self.relative_pos += CQ_STORE_UNIT_SIZE;
+        let (cq_unit, _) = parse_unit(bytes);
+        if cq_unit.pos < 0 || cq_unit.size <= 0 {
+            return None;
+        }
+        Some(cq_unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_batch_consume_queue(
+        store_path: &std::path::Path,
+        search_bcq_by_cache_enable: bool,
+    ) -> BatchConsumeQueue {
+        let message_store_config = MessageStoreConfig {
+            search_bcq_by_cache_enable,
+            ..MessageStoreConfig::default()
+        };
+        BatchConsumeQueue::new(
+            CheetahString::from_static_str("batch_topic"),
+            0,
+            CheetahString::from_string(store_path.to_string_lossy().to_string()),
+            // two units per file, so lookups have to pick the right file
+            (CQ_STORE_UNIT_SIZE * 2) as usize,
+            None,
+            Arc::new(message_store_config),
+        )
+    }
+
+    fn put_batches(bcq: &mut BatchConsumeQueue) {
+        // (base offset, batch size, store time)
+        let batches = [(0i64, 10i16, 100i64), (10, 5, 200), (15, 20, 300)];
+        for (index, (base, batch_size, store_time)) in batches.into_iter().enumerate() {
+            assert!(bcq.put_batch_message_position_info(
+                index as i64 * 1000,
+                500,
+                7,
+                store_time,
+                base,
+                batch_size,
+            ));
+        }
+    }
+
+    #[test]
+    fn batch_consume_queue_searches_by_offset_and_time() {
+        for search_bcq_by_cache_enable in [false, true] {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let mut bcq = new_batch_consume_queue(temp_dir.path(), search_bcq_by_cache_enable);
+            put_batches(&mut bcq);
+
+            assert_eq!(bcq.get_min_offset_in_queue(), 0);
+            assert_eq!(bcq.get_max_offset_in_queue(), 35);
+            assert_eq!(bcq.get_message_total_in_queue(), 35);
+            assert_eq!(bcq.get_max_physic_offset(), 2000);
+
+            let unit = bcq.get(12).unwrap();
+            assert_eq!(unit.queue_offset, 10);
+            assert_eq!(unit.batch_num, 5);
+            assert_eq!(unit.pos, 1000);
+            let unit = bcq.get(34).unwrap();
+            assert_eq!(unit.queue_offset, 15);
+            assert_eq!(unit.batch_num, 20);
+            assert!(bcq.get(35).is_none());
+
+            assert_eq!(bcq.get_offset_in_queue_by_time(50), 0);
+            assert_eq!(bcq.get_offset_in_queue_by_time(150), 10);
+            assert_eq!(bcq.get_offset_in_queue_by_time(400), 35);
+            assert_eq!(
+                bcq.get_offset_in_queue_by_time_with_boundary(250, BoundaryType::Upper),
+                10
+            );
+            assert_eq!(
+                bcq.get_offset_in_queue_by_time_with_boundary(300, BoundaryType::Upper),
+                15
+            );
+        }
+    }
+
+    #[test]
+    fn batch_consume_queue_recovers_and_truncates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut bcq = new_batch_consume_queue(temp_dir.path(), false);
+        put_batches(&mut bcq);
+        // repeated dispatch of an already indexed message is ignored
+        assert!(bcq.put_batch_message_position_info(1000, 500, 7, 200, 10, 5));
+        assert_eq!(bcq.get_max_offset_in_queue(), 35);
+        bcq.flush(0);
+
+        let mut recovered = new_batch_consume_queue(temp_dir.path(), false);
+        assert!(recovered.load());
+        recovered.recover();
+        assert_eq!(recovered.get_min_offset_in_queue(), 0);
+        assert_eq!(recovered.get_max_offset_in_queue(), 35);
+
+        recovered.truncate_dirty_logic_files(1500);
+        assert_eq!(recovered.get_max_offset_in_queue(), 15);
+        assert_eq!(recovered.get_max_physic_offset(), 1000);
+        assert!(recovered.get(20).is_none());
+    }
+
+    #[test]
+    fn assign_queue_offset_uses_batch_offset_table() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let bcq = new_batch_consume_queue(temp_dir.path(), false);
+        let queue_offset_operator = QueueOffsetOperator::new();
+        let mut msg = MessageExtBrokerInner::default();
+        msg.set_topic(CheetahString::from_static_str("batch_topic"));
+
+        bcq.increase_queue_offset(&queue_offset_operator, &msg, 10);
+        bcq.assign_queue_offset(&queue_offset_operator, &mut msg);
+        assert_eq!(msg.message_ext_inner.queue_offset, 10);
+        assert_eq!(
+            queue_offset_operator.get_queue_offset("batch_topic-0".into()),
+            0
+        );
     }
 }
@@ -19,6 +19,7 @@ use rocketmq_common::common::sys_flag::message_sys_flag::MessageSysFlag;
 use crate::base::commit_log_dispatcher::CommitLogDispatcher;
 use crate::base::dispatch_request::DispatchRequest;
 use crate::queue::consume_queue_store::ConsumeQueueStoreInterface;
+use crate::queue::kv_consume_queue_store::KvConsumeQueueStore;
 use crate::queue::local_file_consume_queue_store::ConsumeQueueStore;
 
 pub struct CommitLogDispatcherBuildConsumeQueue {
@@ -34,7 +35,34 @@ impl CommitLogDispatcherBuildConsumeQueue {
 }
 
 impl CommitLogDispatcher for CommitLogDispatcherBuildConsumeQueue {
-    fn dispatch(&self, dispatch_request: &DispatchRequest) {
+    fn dispatch(&self, dispatch_request: &mut DispatchRequest) {
+        let tran_type = MessageSysFlag::get_transaction_value(dispatch_request.sys_flag);
+        match tran_type {
+            MessageSysFlag::TRANSACTION_NOT_TYPE | MessageSysFlag::TRANSACTION_COMMIT_TYPE => {
+                self.consume_queue_store
+                    .put_message_position_info_wrapper(dispatch_request);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Builds the kv consume queue next to the file one when
+/// `rocksdb_cq_double_write_enable` is set.
+pub struct CommitLogDispatcherBuildKvConsumeQueue {
+    consume_queue_store: KvConsumeQueueStore,
+}
+
+impl CommitLogDispatcherBuildKvConsumeQueue {
+    pub fn new(consume_queue_store: KvConsumeQueueStore) -> Self {
+        Self {
+            consume_queue_store,
+        }
+    }
+}
+
+impl CommitLogDispatcher for CommitLogDispatcherBuildKvConsumeQueue {
+    fn dispatch(&self, dispatch_request: &mut DispatchRequest) {
         let tran_type = MessageSysFlag::get_transaction_value(dispatch_request.sys_flag);
         match tran_type {
             MessageSysFlag::TRANSACTION_NOT_TYPE | MessageSysFlag::TRANSACTION_COMMIT_TYPE => {
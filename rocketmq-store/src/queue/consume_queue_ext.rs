@@ -14,14 +14,22 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::fmt;
+use std::fmt::Display;
+use std::fmt::Formatter;
 use std::path::PathBuf;
 
 use cheetah_string::CheetahString;
 use rocketmq_rust::ArcMut;
+use tracing::error;
 use tracing::info;
+use tracing::warn;
 
 use crate::consume_queue::consume_queue_ext::CqExtUnit;
+use crate::consume_queue::consume_queue_ext::MAX_EXT_UNIT_SIZE;
 use crate::consume_queue::mapped_file_queue::MappedFileQueue;
+use crate::log_file::mapped_file::default_mapped_file_impl::DefaultMappedFile;
+use crate::log_file::mapped_file::MappedFile;
 
 const END_BLANK_DATA_LENGTH: usize = 4;
 
@@ -69,9 +77,205 @@ impl ConsumeQueueExt {
 }
 
 impl ConsumeQueueExt {
-    pub fn truncate_by_max_address(&self, max_address: i64) {}
+    /// Transforms a physical offset of the extend files into an address, which is always
+    /// less than or equal to `MAX_ADDR`.
+    #[inline]
+    pub fn decorate(offset: i64) -> i64 {
+        if !Self::is_ext_addr(offset) {
+            return offset.wrapping_add(i64::MIN);
+        }
+        offset
+    }
 
-    pub fn truncate_by_min_address(&self, min_address: i64) {}
+    /// Inverse of [`ConsumeQueueExt::decorate`].
+    #[inline]
+    pub fn un_decorate(address: i64) -> i64 {
+        if Self::is_ext_addr(address) {
+            return address.wrapping_sub(i64::MIN);
+        }
+        address
+    }
+
+    /// Reads the unit at `address` into `cq_ext_unit`, returns `false` if it does not exist.
+    pub fn get(&self, address: i64, cq_ext_unit: &mut CqExtUnit) -> bool {
+        if !Self::is_ext_addr(address) {
+            return false;
+        }
+        let real_offset = Self::un_decorate(address);
+        let Some(mapped_file) = self
+            .mapped_file_queue
+            .find_mapped_file_by_offset(real_offset, real_offset == 0)
+        else {
+            return false;
+        };
+        let pos = (real_offset % self.mapped_file_size as i64) as i32;
+        let Some(buffer_result) = mapped_file.select_mapped_buffer_with_position(pos) else {
+            warn!(
+                "[BUG] Consume queue extend unit({}) is not found!",
+                real_offset
+            );
+            return false;
+        };
+        let ret = buffer_result
+            .bytes
+            .as_deref()
+            .is_some_and(|bytes| cq_ext_unit.read(bytes));
+        mapped_file.release();
+        ret
+    }
+
+    /// Saves `cq_ext_unit` and returns its address, or `1` if it could not be written.
+    pub fn put(&self, mut cq_ext_unit: CqExtUnit) -> i64 {
+        const RETRY_TIMES: i32 = 3;
+        let size = cq_ext_unit.calc_unit_size();
+        if size > MAX_EXT_UNIT_SIZE as i32 {
+            error!(
+                "Size of cq ext unit is greater than {}, {}",
+                MAX_EXT_UNIT_SIZE, size
+            );
+            return 1;
+        }
+        if self.mapped_file_queue.get_max_offset() + size as i64 > MAX_REAL_OFFSET {
+            warn!("Capacity of ext is maximum!{}, {}", MAX_REAL_OFFSET, size);
+            return 1;
+        }
+        let data = cq_ext_unit.write();
+        let mapped_file_queue = self.mapped_file_queue.mut_from_ref();
+        for _ in 0..RETRY_TIMES {
+            let Some(mapped_file) =
+                mapped_file_queue.get_last_mapped_file_mut_start_offset(0, true)
+            else {
+                error!(
+                    "Create mapped file when save consume queue extend, {}",
+                    self
+                );
+                continue;
+            };
+            let wrote_position = mapped_file.get_wrote_position();
+            let blank_size = self.mapped_file_size - wrote_position - END_BLANK_DATA_LENGTH as i32;
+
+            // check whether has enough space.
+            if size > blank_size {
+                self.full_fill_to_end(mapped_file.as_ref(), wrote_position);
+                info!(
+                    "No enough space(need:{}, has:{}) of file {}, so fill to end",
+                    size,
+                    blank_size,
+                    mapped_file.get_file_name()
+                );
+                continue;
+            }
+
+            if mapped_file.append_message_bytes(&data) {
+                return Self::decorate(
+                    wrote_position as i64 + mapped_file.get_file_from_offset() as i64,
+                );
+            }
+        }
+        1
+    }
+
+    fn full_fill_to_end(&self, mapped_file: &DefaultMappedFile, wrote_position: i32) {
+        // ending.
+        mapped_file.write_bytes_segment(&(-1i16).to_be_bytes(), wrote_position as usize, 0, 2);
+        mapped_file.set_wrote_position(self.mapped_file_size);
+    }
+
+    /// Loads all extend files and locates the end of the written units.
+    pub fn recover(&mut self) {
+        let mapped_files = self.mapped_file_queue.get_mapped_files();
+        let mapped_files = mapped_files.read().clone();
+        if mapped_files.is_empty() {
+            return;
+        }
+
+        // load all files, consume queue will truncate extend files.
+        let mut process_offset = 0i64;
+        for mapped_file in mapped_files.iter() {
+            let buffer = mapped_file.get_mapped_file();
+            let mut mapped_file_offset = 0usize;
+            while mapped_file_offset + 2 <= buffer.len() {
+                let size = i16::from_be_bytes([
+                    buffer[mapped_file_offset],
+                    buffer[mapped_file_offset + 1],
+                ]);
+                // check whether write sth.
+                if size <= 0 {
+                    break;
+                }
+                mapped_file_offset += size as usize;
+            }
+            process_offset = mapped_file.get_file_from_offset() as i64 + mapped_file_offset as i64;
+            info!(
+                "Recover consume queue extend file {}, offset {}",
+                mapped_file.get_file_name(),
+                mapped_file_offset
+            );
+        }
+
+        self.mapped_file_queue.set_flushed_where(process_offset);
+        self.mapped_file_queue.set_committed_where(process_offset);
+        self.mapped_file_queue.truncate_dirty_files(process_offset);
+    }
+
+    /// Deletes files whose units are all before `min_address`.
+    pub fn truncate_by_min_address(&self, min_address: i64) {
+        if !Self::is_ext_addr(min_address) {
+            return;
+        }
+        info!("Truncate consume queue ext by min {}.", min_address);
+        let real_offset = Self::un_decorate(min_address);
+        let will_remove_files: Vec<_> = self
+            .mapped_file_queue
+            .get_mapped_files()
+            .read()
+            .iter()
+            .filter(|file| {
+                let file_tail_offset =
+                    file.get_file_from_offset() as i64 + self.mapped_file_size as i64;
+                if file_tail_offset < real_offset {
+                    info!(
+                        "Destroy consume queue ext by min: file={}, fileTailOffset={}, \
+                         minOffset={}",
+                        file.get_file_name(),
+                        file_tail_offset,
+                        real_offset
+                    );
+                    return file.destroy(1000);
+                }
+                false
+            })
+            .cloned()
+            .collect();
+        self.mapped_file_queue
+            .mut_from_ref()
+            .delete_expired_file(will_remove_files);
+    }
+
+    /// Truncates the units after the one at `max_address`.
+    pub fn truncate_by_max_address(&self, max_address: i64) {
+        if !Self::is_ext_addr(max_address) {
+            return;
+        }
+        info!("Truncate consume queue ext by max {}.", max_address);
+        let mut cq_ext_unit = CqExtUnit::default();
+        if !self.get(max_address, &mut cq_ext_unit) {
+            error!(
+                "[BUG] address {} of consume queue extend not found!",
+                max_address
+            );
+            return;
+        }
+        let real_offset = Self::un_decorate(max_address);
+        let max_offset = real_offset + cq_ext_unit.size() as i64;
+        self.mapped_file_queue
+            .mut_from_ref()
+            .truncate_dirty_files(max_offset);
+        info!(
+            "Truncate consume queue ext by max, max offset {}.",
+            max_offset
+        );
+    }
 
     pub fn load(&mut self) -> bool {
         let result = self.mapped_file_queue.load();
@@ -85,17 +289,89 @@ impl ConsumeQueueExt {
         result
     }
 
-    pub fn recover(&mut self) {}
+    pub fn destroy(&mut self) {
+        self.mapped_file_queue.destroy();
+    }
+}
 
-    pub fn put(&self, cq_ext_unit: CqExtUnit) -> i64 {
-        unimplemented!()
+impl Display for ConsumeQueueExt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ConsumeQueueExt [topic={}, queue_id={}, store_path={}]",
+            self.topic, self.queue_id, self.store_path
+        )
     }
+}
 
-    pub fn destroy(&mut self) {
-        self.mapped_file_queue.destroy();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_ext(store_path: &std::path::Path) -> ConsumeQueueExt {
+        ConsumeQueueExt::new(
+            CheetahString::from_static_str("TopicTest"),
+            0,
+            CheetahString::from(store_path.to_string_lossy().to_string()),
+            // room for three units with a 64 bits bit map
+            128,
+            64,
+        )
+    }
+
+    #[test]
+    fn decorate_and_un_decorate() {
+        assert!(ConsumeQueueExt::is_ext_addr(ConsumeQueueExt::decorate(0)));
+        assert!(ConsumeQueueExt::is_ext_addr(ConsumeQueueExt::decorate(
+            1024
+        )));
+        assert_eq!(
+            ConsumeQueueExt::un_decorate(ConsumeQueueExt::decorate(1024)),
+            1024
+        );
+        assert!(!ConsumeQueueExt::is_ext_addr(1));
+    }
+
+    #[test]
+    fn put_get_and_roll_to_next_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ext = new_ext(temp_dir.path());
+
+        let addresses: Vec<i64> = (0..5)
+            .map(|i| ext.put(CqExtUnit::new(i, 1000 + i, Some(vec![i as u8; 8]))))
+            .collect();
+        assert!(addresses
+            .iter()
+            .all(|address| ConsumeQueueExt::is_ext_addr(*address)));
+        assert_eq!(ext.mapped_file_queue.get_mapped_files_size(), 2);
+
+        for (i, address) in addresses.iter().enumerate() {
+            let mut unit = CqExtUnit::default();
+            assert!(ext.get(*address, &mut unit));
+            assert_eq!(unit.tags_code(), i as i64);
+            assert_eq!(unit.msg_store_time(), 1000 + i as i64);
+            assert_eq!(unit.filter_bit_map(), &Some(vec![i as u8; 8]));
+        }
+        assert!(!ext.get(1, &mut CqExtUnit::default()));
     }
 
-    pub fn get(&self, address: i64, cq_ext_unit: &CqExtUnit) -> bool {
-        unimplemented!()
+    #[test]
+    fn recover_and_truncate_by_max_address() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ext = new_ext(temp_dir.path());
+        let first = ext.put(CqExtUnit::new(1, 1, None));
+        let second = ext.put(CqExtUnit::new(2, 2, None));
+        drop(ext);
+
+        let mut ext = new_ext(temp_dir.path());
+        assert!(ext.load());
+        ext.recover();
+        let mut unit = CqExtUnit::default();
+        assert!(ext.get(second, &mut unit));
+        assert_eq!(unit.tags_code(), 2);
+
+        ext.truncate_by_max_address(first);
+        assert!(ext.get(first, &mut unit));
+        assert!(!ext.get(second, &mut unit));
     }
 }
@@ -0,0 +1,350 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::attribute::cq_type::CQType;
+use rocketmq_common::common::boundary_type::BoundaryType;
+use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use tracing::warn;
+
+use crate::base::dispatch_request::DispatchRequest;
+use crate::base::swappable::Swappable;
+use crate::filter::MessageFilter;
+use crate::queue::consume_queue::ConsumeQueueTrait;
+use crate::queue::kv_consume_queue_table::KvConsumeQueueTable;
+use crate::queue::kv_consume_queue_table::CQ_UNIT_SIZE;
+use crate::queue::queue_offset_operator::QueueOffsetOperator;
+use crate::queue::referred_iterator::ReferredIterator;
+use crate::queue::CqUnit;
+use crate::queue::FileQueueLifeCycle;
+
+/// Units read ahead when iterating without an explicit count.
+const DEFAULT_ITERATE_COUNT: i32 = 32;
+
+/// A consume queue whose units live in the shared [`KvConsumeQueueTable`] instead of in
+/// mapped files of its own.
+pub struct KvConsumeQueue {
+    topic: CheetahString,
+    queue_id: i32,
+    kv_table: Arc<KvConsumeQueueTable>,
+}
+
+impl KvConsumeQueue {
+    pub(crate) fn new(
+        topic: CheetahString,
+        queue_id: i32,
+        kv_table: Arc<KvConsumeQueueTable>,
+    ) -> Self {
+        Self {
+            topic,
+            queue_id,
+            kv_table,
+        }
+    }
+}
+
+impl FileQueueLifeCycle for KvConsumeQueue {
+    #[inline]
+    fn load(&mut self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn recover(&mut self) {
+        // units are recovered together with the table
+    }
+
+    #[inline]
+    fn check_self(&self) {}
+
+    #[inline]
+    fn flush(&self, _flush_least_pages: i32) -> bool {
+        self.kv_table.flush()
+    }
+
+    #[inline]
+    fn destroy(&mut self) {
+        self.kv_table
+            .destroy_queue(self.topic.as_str(), self.queue_id);
+    }
+
+    #[inline]
+    fn truncate_dirty_logic_files(&mut self, max_commit_log_pos: i64) {
+        self.kv_table
+            .truncate_dirty(self.topic.as_str(), self.queue_id, max_commit_log_pos);
+    }
+
+    #[inline]
+    fn delete_expired_file(&self, min_commit_log_pos: i64) -> i32 {
+        self.kv_table
+            .delete_expired(self.topic.as_str(), self.queue_id, min_commit_log_pos)
+            .min(i32::MAX as i64) as i32
+    }
+
+    #[inline]
+    fn roll_next_file(&self, next_begin_offset: i64) -> i64 {
+        let min_offset = self.get_min_offset_in_queue();
+        if next_begin_offset < min_offset {
+            min_offset
+        } else {
+            self.get_max_offset_in_queue()
+        }
+    }
+
+    #[inline]
+    fn is_first_file_available(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn is_first_file_exist(&self) -> bool {
+        true
+    }
+}
+
+impl Swappable for KvConsumeQueue {
+    #[inline]
+    fn swap_map(
+        &self,
+        _reserve_num: i32,
+        _force_swap_interval_ms: i64,
+        _normal_swap_interval_ms: i64,
+    ) {
+    }
+
+    #[inline]
+    fn clean_swapped_map(&self, _force_clean_swap_interval_ms: i64) {}
+}
+
+impl ConsumeQueueTrait for KvConsumeQueue {
+    #[inline]
+    fn get_topic(&self) -> &CheetahString {
+        &self.topic
+    }
+
+    #[inline]
+    fn get_queue_id(&self) -> i32 {
+        self.queue_id
+    }
+
+    #[inline]
+    fn iterate_from(&self, start_index: i64) -> Option<Box<dyn ReferredIterator<CqUnit>>> {
+        self.iterate_from_with_count(start_index, DEFAULT_ITERATE_COUNT)
+    }
+
+    fn iterate_from_with_count(
+        &self,
+        start_index: i64,
+        count: i32,
+    ) -> Option<Box<dyn ReferredIterator<CqUnit>>> {
+        let max_offset = self.get_max_offset_in_queue();
+        if start_index < self.get_min_offset_in_queue() || start_index >= max_offset {
+            return None;
+        }
+        let count = count.max(1).min((max_offset - start_index) as i32);
+        let units =
+            self.kv_table
+                .range_query(self.topic.as_str(), self.queue_id, start_index, count);
+        if units.is_empty() {
+            return None;
+        }
+        Some(Box::new(KvConsumeQueueIterator {
+            units: units
+                .into_iter()
+                .map(|(cq_unit, _)| cq_unit)
+                .collect::<Vec<_>>()
+                .into_iter(),
+        }))
+    }
+
+    #[inline]
+    fn get(&self, index: i64) -> Option<CqUnit> {
+        self.get_cq_unit_and_store_time(index)
+            .map(|(cq_unit, _)| cq_unit)
+    }
+
+    #[inline]
+    fn get_cq_unit_and_store_time(&self, index: i64) -> Option<(CqUnit, i64)> {
+        self.kv_table
+            .get_unit(self.topic.as_str(), self.queue_id, index)
+    }
+
+    #[inline]
+    fn get_earliest_unit_and_store_time(&self) -> Option<(CqUnit, i64)> {
+        self.get_cq_unit_and_store_time(self.get_min_offset_in_queue())
+    }
+
+    #[inline]
+    fn get_earliest_unit(&self) -> Option<CqUnit> {
+        self.get(self.get_min_offset_in_queue())
+    }
+
+    #[inline]
+    fn get_latest_unit(&self) -> Option<CqUnit> {
+        self.kv_table
+            .get_latest_unit(self.topic.as_str(), self.queue_id)
+            .map(|(cq_unit, _)| cq_unit)
+    }
+
+    #[inline]
+    fn get_last_offset(&self) -> i64 {
+        self.get_latest_unit()
+            .map_or(-1, |cq_unit| cq_unit.pos + cq_unit.size as i64)
+    }
+
+    #[inline]
+    fn get_min_offset_in_queue(&self) -> i64 {
+        self.kv_table
+            .get_min_offset_in_queue(self.topic.as_str(), self.queue_id)
+    }
+
+    #[inline]
+    fn get_max_offset_in_queue(&self) -> i64 {
+        self.kv_table
+            .get_max_offset_in_queue(self.topic.as_str(), self.queue_id)
+    }
+
+    #[inline]
+    fn get_message_total_in_queue(&self) -> i64 {
+        self.get_max_offset_in_queue() - self.get_min_offset_in_queue()
+    }
+
+    #[inline]
+    fn get_offset_in_queue_by_time(&self, timestamp: i64) -> i64 {
+        self.get_offset_in_queue_by_time_with_boundary(timestamp, BoundaryType::Lower)
+    }
+
+    #[inline]
+    fn get_offset_in_queue_by_time_with_boundary(
+        &self,
+        timestamp: i64,
+        boundary_type: BoundaryType,
+    ) -> i64 {
+        self.kv_table.get_offset_in_queue_by_time(
+            self.topic.as_str(),
+            self.queue_id,
+            timestamp,
+            boundary_type,
+        )
+    }
+
+    #[inline]
+    fn get_max_physic_offset(&self) -> i64 {
+        self.kv_table
+            .get_max_physic_offset(self.topic.as_str(), self.queue_id)
+    }
+
+    #[inline]
+    fn get_min_logic_offset(&self) -> i64 {
+        self.get_min_offset_in_queue() * CQ_UNIT_SIZE as i64
+    }
+
+    #[inline]
+    fn get_cq_type(&self) -> CQType {
+        CQType::RocksDBCQ
+    }
+
+    #[inline]
+    fn get_total_size(&self) -> i64 {
+        self.get_message_total_in_queue() * CQ_UNIT_SIZE as i64
+    }
+
+    #[inline]
+    fn get_unit_size(&self) -> i32 {
+        CQ_UNIT_SIZE
+    }
+
+    #[inline]
+    fn correct_min_offset(&self, min_commit_log_offset: i64) {
+        self.delete_expired_file(min_commit_log_offset);
+    }
+
+    fn put_message_position_info_wrapper(&mut self, request: &DispatchRequest) {
+        if !self.kv_table.put_message_position(request) {
+            warn!(
+                "[BUG]put commit log position info to kv consume queue {}:{} failed, offset: {}",
+                self.topic, self.queue_id, request.commit_log_offset
+            );
+        }
+    }
+
+    #[inline]
+    fn increase_queue_offset(
+        &self,
+        queue_offset_assigner: &QueueOffsetOperator,
+        msg: &MessageExtBrokerInner,
+        message_num: i16,
+    ) {
+        queue_offset_assigner.increase_queue_offset(
+            CheetahString::from_string(format!("{}-{}", msg.topic(), msg.queue_id())),
+            message_num,
+        );
+    }
+
+    #[inline]
+    fn assign_queue_offset(
+        &self,
+        queue_offset_operator: &QueueOffsetOperator,
+        msg: &mut MessageExtBrokerInner,
+    ) {
+        let queue_offset = queue_offset_operator.get_queue_offset(CheetahString::from_string(
+            format!("{}-{}", msg.topic(), msg.queue_id()),
+        ));
+        msg.message_ext_inner.queue_offset = queue_offset;
+    }
+
+    fn estimate_message_count(&self, from: i64, to: i64, filter: &dyn MessageFilter) -> i64 {
+        let from = from.max(self.get_min_offset_in_queue());
+        let to = to.min(self.get_max_offset_in_queue());
+        if from >= to {
+            return 0;
+        }
+        self.kv_table
+            .range_query(
+                self.topic.as_str(),
+                self.queue_id,
+                from,
+                (to - from).min(i32::MAX as i64) as i32,
+            )
+            .iter()
+            .filter(|(cq_unit, _)| {
+                filter.is_matched_by_consume_queue(cq_unit.get_valid_tags_code_as_long(), None)
+            })
+            .count() as i64
+    }
+}
+
+struct KvConsumeQueueIterator {
+    units: std::vec::IntoIter<CqUnit>,
+}
+
+impl ReferredIterator<CqUnit> for KvConsumeQueueIterator {
+    fn release(&mut self) {}
+
+    fn next_and_release(&mut self) -> Option<Self::Item> {
+        self.next()
+    }
+}
+
+impl Iterator for KvConsumeQueueIterator {
+    type Item = CqUnit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.units.next()
+    }
+}
@@ -0,0 +1,452 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use cheetah_string::CheetahString;
+use rocketmq_common::common::boundary_type::BoundaryType;
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use rocketmq_rust::ArcMut;
+use tracing::info;
+
+use crate::base::dispatch_request::DispatchRequest;
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::queue::consume_queue::ConsumeQueueTrait;
+use crate::queue::consume_queue_store::ConsumeQueueStoreTrait;
+use crate::queue::kv_consume_queue::KvConsumeQueue;
+use crate::queue::kv_consume_queue_table::KvConsumeQueueTable;
+use crate::queue::queue_offset_operator::QueueOffsetOperator;
+use crate::queue::ArcConsumeQueue;
+use crate::queue::ConsumeQueueTable;
+use crate::queue::CqUnit;
+use crate::store_path_config_helper::get_store_path_kv_consume_queue;
+
+/// A [`ConsumeQueueStoreTrait`] implementation keeping the units of every queue in one embedded
+/// key-value store, so the number of open files does not grow with the number of queues.
+#[derive(Clone)]
+pub struct KvConsumeQueueStore {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    message_store_config: Arc<MessageStoreConfig>,
+    broker_config: Arc<BrokerConfig>,
+    queue_offset_operator: QueueOffsetOperator,
+    consume_queue_table: Arc<ConsumeQueueTable>,
+    kv_table: Arc<KvConsumeQueueTable>,
+}
+
+impl KvConsumeQueueStore {
+    pub fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        broker_config: Arc<BrokerConfig>,
+    ) -> Self {
+        let kv_table = Arc::new(KvConsumeQueueTable::new(get_store_path_kv_consume_queue(
+            message_store_config.store_path_root_dir.as_str(),
+        )));
+        Self {
+            inner: Arc::new(Inner {
+                message_store_config,
+                broker_config,
+                queue_offset_operator: QueueOffsetOperator::new(),
+                consume_queue_table: Arc::new(Default::default()),
+                kv_table,
+            }),
+        }
+    }
+
+    fn remove_consume_queue(&self, topic: &CheetahString, queue_id: i32) {
+        let mut table = self.inner.consume_queue_table.lock();
+        if let Some(queue_table) = table.get_mut(topic) {
+            queue_table.remove(&queue_id);
+            if queue_table.is_empty() {
+                table.remove(topic);
+            }
+        }
+    }
+
+    fn all_consume_queues(&self) -> Vec<ArcConsumeQueue> {
+        self.inner
+            .consume_queue_table
+            .lock()
+            .values()
+            .flat_map(|queue_table| queue_table.values().cloned())
+            .collect()
+    }
+}
+
+impl ConsumeQueueStoreTrait for KvConsumeQueueStore {
+    fn start(&self) {
+        info!("kv consume queue store start");
+    }
+
+    fn load(&mut self) -> bool {
+        if !self.inner.kv_table.load() {
+            return false;
+        }
+        let queues = self.inner.kv_table.list_queues();
+        info!("load kv consume queue store, {} queues", queues.len());
+        for (topic, queue_id) in queues {
+            self.find_or_create_consume_queue(&topic, queue_id);
+        }
+        true
+    }
+
+    fn load_after_destroy(&self) -> bool {
+        self.inner.kv_table.load()
+    }
+
+    async fn recover(&self) {
+        // the table replays its data log on load, nothing left to recover per queue
+    }
+
+    async fn recover_concurrently(&self) -> bool {
+        true
+    }
+
+    fn shutdown(&self) -> bool {
+        self.inner.kv_table.flush()
+    }
+
+    fn destroy(&self) {
+        self.inner.kv_table.destroy();
+        self.inner.consume_queue_table.lock().clear();
+    }
+
+    fn destroy_queue(&self, consume_queue: &dyn ConsumeQueueTrait) {
+        let topic = consume_queue.get_topic();
+        let queue_id = consume_queue.get_queue_id();
+        self.inner.kv_table.destroy_queue(topic.as_str(), queue_id);
+        self.remove_consume_queue(topic, queue_id);
+    }
+
+    fn flush(&self, consume_queue: &dyn ConsumeQueueTrait, flush_least_pages: i32) -> bool {
+        consume_queue.flush(flush_least_pages)
+    }
+
+    async fn clean_expired(&self, min_phy_offset: i64) {
+        for consume_queue in self.all_consume_queues() {
+            consume_queue.delete_expired_file(min_phy_offset);
+        }
+    }
+
+    fn check_self(&self) {}
+
+    fn delete_expired_file(
+        &self,
+        consume_queue: &dyn ConsumeQueueTrait,
+        min_commit_log_pos: i64,
+    ) -> i32 {
+        consume_queue.delete_expired_file(min_commit_log_pos)
+    }
+
+    fn is_first_file_available(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
+        consume_queue.is_first_file_available()
+    }
+
+    fn is_first_file_exist(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
+        consume_queue.is_first_file_exist()
+    }
+
+    fn roll_next_file(&self, consume_queue: &dyn ConsumeQueueTrait, offset: i64) -> i64 {
+        consume_queue.roll_next_file(offset)
+    }
+
+    fn truncate_dirty(&self, offset_to_truncate: i64) {
+        for mut consume_queue in self.all_consume_queues() {
+            consume_queue.truncate_dirty_logic_files(offset_to_truncate);
+        }
+    }
+
+    fn put_message_position_info_wrapper_with_cq(
+        &self,
+        consume_queue: &mut dyn ConsumeQueueTrait,
+        request: &DispatchRequest,
+    ) {
+        consume_queue.put_message_position_info_wrapper(request);
+    }
+
+    fn put_message_position_info_wrapper(&self, request: &DispatchRequest) {
+        let mut consume_queue = self.find_or_create_consume_queue(&request.topic, request.queue_id);
+        self.put_message_position_info_wrapper_with_cq(consume_queue.as_mut().as_mut(), request);
+    }
+
+    async fn range_query(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+        start_index: i64,
+        num: i32,
+    ) -> Vec<Bytes> {
+        self.inner
+            .kv_table
+            .range_query_bytes(topic.as_str(), queue_id, start_index, num)
+    }
+
+    async fn get(&self, topic: &CheetahString, queue_id: i32, start_index: i64) -> Bytes {
+        self.inner
+            .kv_table
+            .range_query_bytes(topic.as_str(), queue_id, start_index, 1)
+            .pop()
+            .unwrap_or_default()
+    }
+
+    fn get_consume_queue_table(&self) -> Arc<ConsumeQueueTable> {
+        self.inner.consume_queue_table.clone()
+    }
+
+    fn assign_queue_offset(&self, msg: &mut MessageExtBrokerInner) {
+        let consume_queue = self.find_or_create_consume_queue(msg.get_topic(), msg.queue_id());
+        consume_queue.assign_queue_offset(&self.inner.queue_offset_operator, msg);
+    }
+
+    fn increase_queue_offset(&self, msg: &MessageExtBrokerInner, message_num: i16) {
+        let consume_queue = self.find_or_create_consume_queue(msg.get_topic(), msg.queue_id());
+        consume_queue.increase_queue_offset(&self.inner.queue_offset_operator, msg, message_num);
+    }
+
+    fn increase_lmq_offset(&self, queue_key: &str, message_num: i16) {
+        self.inner
+            .queue_offset_operator
+            .increase_lmq_offset(&CheetahString::from(queue_key), message_num);
+    }
+
+    fn get_lmq_queue_offset(&self, queue_key: &str) -> i64 {
+        self.inner
+            .queue_offset_operator
+            .get_lmq_offset(&CheetahString::from(queue_key))
+    }
+
+    fn recover_offset_table(&mut self, min_phy_offset: i64) {
+        let mut topic_queue_table = HashMap::new();
+        for consume_queue in self.all_consume_queues() {
+            consume_queue.correct_min_offset(min_phy_offset);
+            topic_queue_table.insert(
+                CheetahString::from_string(format!(
+                    "{}-{}",
+                    consume_queue.get_topic(),
+                    consume_queue.get_queue_id()
+                )),
+                consume_queue.get_max_offset_in_queue(),
+            );
+        }
+        self.set_topic_queue_table(topic_queue_table);
+    }
+
+    fn set_topic_queue_table(&mut self, topic_queue_table: HashMap<CheetahString, i64>) {
+        self.inner
+            .queue_offset_operator
+            .set_topic_queue_table(topic_queue_table);
+    }
+
+    fn remove_topic_queue_table(&mut self, topic: &CheetahString, queue_id: i32) {
+        self.inner.queue_offset_operator.remove(topic, queue_id);
+    }
+
+    fn get_topic_queue_table(&self) -> HashMap<CheetahString, i64> {
+        self.inner.queue_offset_operator.get_topic_queue_table()
+    }
+
+    fn get_max_phy_offset_in_consume_queue(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+    ) -> Option<i64> {
+        Some(
+            self.inner
+                .kv_table
+                .get_max_physic_offset(topic.as_str(), queue_id),
+        )
+    }
+
+    fn get_max_offset(&self, topic: &CheetahString, queue_id: i32) -> Option<i64> {
+        self.inner
+            .queue_offset_operator
+            .get_topic_queue_next_offset(&CheetahString::from_string(format!(
+                "{}-{}",
+                topic, queue_id
+            )))
+    }
+
+    fn get_max_phy_offset_in_consume_queue_global(&self) -> i64 {
+        self.all_consume_queues()
+            .iter()
+            .map(|consume_queue| consume_queue.get_max_physic_offset())
+            .max()
+            .unwrap_or(-1)
+    }
+
+    fn get_min_offset_in_queue(&self, topic: &CheetahString, queue_id: i32) -> i64 {
+        self.inner
+            .kv_table
+            .get_min_offset_in_queue(topic.as_str(), queue_id)
+    }
+
+    fn get_max_offset_in_queue(&self, topic: &CheetahString, queue_id: i32) -> i64 {
+        self.inner
+            .kv_table
+            .get_max_offset_in_queue(topic.as_str(), queue_id)
+    }
+
+    fn get_offset_in_queue_by_time(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+        timestamp: i64,
+        boundary_type: BoundaryType,
+    ) -> i64 {
+        self.inner.kv_table.get_offset_in_queue_by_time(
+            topic.as_str(),
+            queue_id,
+            timestamp,
+            boundary_type,
+        )
+    }
+
+    fn find_or_create_consume_queue(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+    ) -> ArcConsumeQueue {
+        let mut consume_queue_table = self.inner.consume_queue_table.lock();
+        consume_queue_table
+            .entry(topic.clone())
+            .or_default()
+            .entry(queue_id)
+            .or_insert_with(|| {
+                ArcMut::new(Box::new(KvConsumeQueue::new(
+                    topic.clone(),
+                    queue_id,
+                    self.inner.kv_table.clone(),
+                )))
+            })
+            .clone()
+    }
+
+    fn find_consume_queue_map(
+        &self,
+        topic: &CheetahString,
+    ) -> Option<HashMap<i32, ArcConsumeQueue>> {
+        self.inner.consume_queue_table.lock().get(topic).cloned()
+    }
+
+    fn get_total_size(&self) -> i64 {
+        self.all_consume_queues()
+            .iter()
+            .map(|consume_queue| consume_queue.get_total_size())
+            .sum()
+    }
+
+    fn get_store_time(&self, _cq_unit: &CqUnit) -> i64 {
+        // the store time lives in the commit log, which this store has no access to
+        -1
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_request(queue_offset: i64, store_timestamp: i64) -> DispatchRequest {
+        DispatchRequest {
+            topic: CheetahString::from_static_str("kv_topic"),
+            queue_id: 1,
+            commit_log_offset: queue_offset * 100,
+            msg_size: 100,
+            tags_code: 3,
+            store_timestamp,
+            consume_queue_offset: queue_offset,
+            ..DispatchRequest::default()
+        }
+    }
+
+    fn new_store(root_dir: &std::path::Path) -> KvConsumeQueueStore {
+        let message_store_config = MessageStoreConfig {
+            store_path_root_dir: CheetahString::from_string(root_dir.to_string_lossy().to_string()),
+            ..MessageStoreConfig::default()
+        };
+        let mut store = KvConsumeQueueStore::new(Arc::new(message_store_config), Arc::default());
+        assert!(store.load());
+        store
+    }
+
+    #[test]
+    fn kv_consume_queue_store_survives_reload() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let topic = CheetahString::from_static_str("kv_topic");
+        {
+            let store = new_store(temp_dir.path());
+            for offset in 0..10 {
+                store.put_message_position_info_wrapper(&new_request(offset, 1000 + offset * 10));
+            }
+            assert!(store.shutdown());
+        }
+
+        let store = new_store(temp_dir.path());
+        let consume_queue = store.find_consume_queue_map(&topic).unwrap()[&1].clone();
+        assert_eq!(consume_queue.get_min_offset_in_queue(), 0);
+        assert_eq!(consume_queue.get_max_offset_in_queue(), 10);
+        assert_eq!(consume_queue.get_max_physic_offset(), 1000);
+        let cq_unit = consume_queue.get(4).unwrap();
+        assert_eq!(cq_unit.pos, 400);
+        assert_eq!(cq_unit.tags_code, 3);
+        let units: Vec<i64> = consume_queue
+            .iterate_from_with_count(7, 10)
+            .unwrap()
+            .map(|cq_unit| cq_unit.queue_offset)
+            .collect();
+        assert_eq!(units, vec![7, 8, 9]);
+
+        assert_eq!(consume_queue.get_offset_in_queue_by_time(1025), 3);
+        assert_eq!(
+            consume_queue.get_offset_in_queue_by_time_with_boundary(1025, BoundaryType::Upper),
+            2
+        );
+        assert_eq!(consume_queue.get_offset_in_queue_by_time(5000), 10);
+    }
+
+    #[test]
+    fn kv_consume_queue_store_cleans_and_truncates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let topic = CheetahString::from_static_str("kv_topic");
+        let store = new_store(temp_dir.path());
+        for offset in 0..10 {
+            store.put_message_position_info_wrapper(&new_request(offset, 1000 + offset));
+        }
+
+        let consume_queue = store.find_or_create_consume_queue(&topic, 1);
+        assert_eq!(consume_queue.delete_expired_file(300), 3);
+        assert_eq!(store.get_min_offset_in_queue(&topic, 1), 3);
+        assert_eq!(store.get_max_offset_in_queue(&topic, 1), 10);
+
+        store.truncate_dirty(800);
+        assert_eq!(store.get_max_offset_in_queue(&topic, 1), 8);
+        assert_eq!(consume_queue.get_max_physic_offset(), 800);
+        assert!(consume_queue.get(8).is_none());
+    }
+}
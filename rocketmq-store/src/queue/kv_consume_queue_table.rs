@@ -0,0 +1,410 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use std::path::Path;
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use cheetah_string::CheetahString;
+use rocketmq_common::common::boundary_type::BoundaryType;
+use tracing::error;
+use tracing::info;
+
+use crate::base::dispatch_request::DispatchRequest;
+use crate::kv::kv_store::KvStore;
+use crate::kv::kv_store::WriteBatch;
+use crate::queue::CqUnit;
+
+/// Size of a consume queue unit value: CommitLog Physical Offset(8) + Body Size(4) +
+/// Tag HashCode(8) + Store time(8) = 28 Bytes
+pub const CQ_UNIT_SIZE: i32 = 28;
+
+const CQ_KEY_SPACE: u8 = 1;
+const OFFSET_KEY_SPACE: u8 = 2;
+
+/// Consume queues of every topic and queue, kept in one embedded key-value store.
+///
+/// A unit is stored under `topic + queueId + queueOffset`, so the units of a queue are adjacent
+/// and ordered by queue offset. Next to the units, each queue keeps an offset record holding its
+/// max queue offset and max physical offset, which survives the units being cleaned.
+pub(crate) struct KvConsumeQueueTable {
+    kv_store: KvStore,
+}
+
+impl KvConsumeQueueTable {
+    pub fn new(store_path: impl AsRef<Path>) -> Self {
+        Self {
+            kv_store: KvStore::new(store_path),
+        }
+    }
+
+    pub fn load(&self) -> bool {
+        match self.kv_store.load() {
+            Ok(()) => true,
+            Err(e) => {
+                error!("load kv consume queue table failed: {}", e);
+                false
+            }
+        }
+    }
+
+    pub fn flush(&self) -> bool {
+        self.kv_store.flush().is_ok()
+    }
+
+    pub fn destroy(&self) {
+        if let Err(e) = self.kv_store.destroy() {
+            error!("destroy kv consume queue table failed: {}", e);
+        }
+    }
+
+    fn queue_prefix(key_space: u8, topic: &str, queue_id: i32) -> Vec<u8> {
+        let mut key = Vec::with_capacity(1 + 4 + topic.len() + 4 + 8);
+        key.put_u8(key_space);
+        key.put_u32(topic.len() as u32);
+        key.put_slice(topic.as_bytes());
+        key.put_u32(queue_id as u32);
+        key
+    }
+
+    fn unit_key(topic: &str, queue_id: i32, queue_offset: i64) -> Vec<u8> {
+        let mut key = Self::queue_prefix(CQ_KEY_SPACE, topic, queue_id);
+        key.put_i64(queue_offset.max(0));
+        key
+    }
+
+    fn queue_end_key(topic: &str, queue_id: i32) -> Vec<u8> {
+        let mut key = Self::queue_prefix(CQ_KEY_SPACE, topic, queue_id);
+        key.put_u64(u64::MAX);
+        key
+    }
+
+    fn offset_key(topic: &str, queue_id: i32) -> Vec<u8> {
+        Self::queue_prefix(OFFSET_KEY_SPACE, topic, queue_id)
+    }
+
+    fn decode_unit(key: &[u8], value: &[u8]) -> Option<(CqUnit, i64)> {
+        if key.len() < 8 || value.len() < CQ_UNIT_SIZE as usize {
+            return None;
+        }
+        let queue_offset = (&key[key.len() - 8..]).get_i64();
+        let mut value = value;
+        let pos = value.get_i64();
+        let size = value.get_i32();
+        let tags_code = value.get_i64();
+        let store_time = value.get_i64();
+        Some((
+            CqUnit {
+                queue_offset,
+                size,
+                pos,
+                tags_code,
+                ..CqUnit::default()
+            },
+            store_time,
+        ))
+    }
+
+    /// Returns `(max queue offset, max physic offset)` of a queue.
+    fn offset_record(&self, topic: &str, queue_id: i32) -> Option<(i64, i64)> {
+        let value = self.kv_store.get(&Self::offset_key(topic, queue_id))?;
+        let mut value = value.as_slice();
+        if value.len() < 16 {
+            return None;
+        }
+        Some((value.get_i64(), value.get_i64()))
+    }
+
+    fn put_offset_record(
+        batch: &mut WriteBatch,
+        topic: &str,
+        queue_id: i32,
+        max_offset: i64,
+        max_physic_offset: i64,
+    ) {
+        let mut value = Vec::with_capacity(16);
+        value.put_i64(max_offset);
+        value.put_i64(max_physic_offset);
+        batch.put(Self::offset_key(topic, queue_id), value);
+    }
+
+    pub fn put_message_position(&self, request: &DispatchRequest) -> bool {
+        let topic = request.topic.as_str();
+        let mut value = Vec::with_capacity(CQ_UNIT_SIZE as usize);
+        value.put_i64(request.commit_log_offset);
+        value.put_i32(request.msg_size);
+        value.put_i64(request.tags_code);
+        value.put_i64(request.store_timestamp);
+
+        let mut batch = WriteBatch::default();
+        batch.put(
+            Self::unit_key(topic, request.queue_id, request.consume_queue_offset),
+            value,
+        );
+        let (max_offset, max_physic_offset) = self
+            .offset_record(topic, request.queue_id)
+            .unwrap_or((0, -1));
+        Self::put_offset_record(
+            &mut batch,
+            topic,
+            request.queue_id,
+            max_offset.max(request.consume_queue_offset + 1),
+            max_physic_offset.max(request.commit_log_offset + request.msg_size as i64),
+        );
+        match self.kv_store.write(batch) {
+            Ok(()) => true,
+            Err(e) => {
+                error!(
+                    "put message position to kv consume queue failed, topic: {}, queue: {}, \
+                     error: {}",
+                    topic, request.queue_id, e
+                );
+                false
+            }
+        }
+    }
+
+    pub fn get_unit(&self, topic: &str, queue_id: i32, queue_offset: i64) -> Option<(CqUnit, i64)> {
+        if queue_offset < 0 {
+            return None;
+        }
+        let key = Self::unit_key(topic, queue_id, queue_offset);
+        let value = self.kv_store.get(&key)?;
+        Self::decode_unit(&key, &value)
+    }
+
+    /// The first unit at or after `queue_offset`.
+    fn unit_at_or_after(
+        &self,
+        topic: &str,
+        queue_id: i32,
+        queue_offset: i64,
+    ) -> Option<(CqUnit, i64)> {
+        let (key, value) = self.kv_store.first(
+            &Self::unit_key(topic, queue_id, queue_offset),
+            &Self::queue_end_key(topic, queue_id),
+        )?;
+        Self::decode_unit(&key, &value)
+    }
+
+    /// At most `num` units starting at `start_index`, together with their store time.
+    pub fn range_query(
+        &self,
+        topic: &str,
+        queue_id: i32,
+        start_index: i64,
+        num: i32,
+    ) -> Vec<(CqUnit, i64)> {
+        if num <= 0 {
+            return Vec::new();
+        }
+        self.kv_store
+            .scan(
+                &Self::unit_key(topic, queue_id, start_index),
+                &Self::unit_key(topic, queue_id, start_index.saturating_add(num as i64)),
+                num as usize,
+            )
+            .iter()
+            .filter_map(|(key, value)| Self::decode_unit(key, value))
+            .collect()
+    }
+
+    /// At most `num` raw unit values starting at `start_index`.
+    pub fn range_query_bytes(
+        &self,
+        topic: &str,
+        queue_id: i32,
+        start_index: i64,
+        num: i32,
+    ) -> Vec<Bytes> {
+        if num <= 0 {
+            return Vec::new();
+        }
+        self.kv_store
+            .scan(
+                &Self::unit_key(topic, queue_id, start_index),
+                &Self::unit_key(topic, queue_id, start_index.saturating_add(num as i64)),
+                num as usize,
+            )
+            .into_iter()
+            .map(|(_, value)| Bytes::from(value))
+            .collect()
+    }
+
+    pub fn get_max_offset_in_queue(&self, topic: &str, queue_id: i32) -> i64 {
+        self.offset_record(topic, queue_id)
+            .map_or(0, |(max_offset, _)| max_offset)
+    }
+
+    pub fn get_min_offset_in_queue(&self, topic: &str, queue_id: i32) -> i64 {
+        match self.unit_at_or_after(topic, queue_id, 0) {
+            Some((cq_unit, _)) => cq_unit.queue_offset,
+            None => self.get_max_offset_in_queue(topic, queue_id),
+        }
+    }
+
+    pub fn get_max_physic_offset(&self, topic: &str, queue_id: i32) -> i64 {
+        self.offset_record(topic, queue_id)
+            .map_or(-1, |(_, max_physic_offset)| max_physic_offset)
+    }
+
+    pub fn get_latest_unit(&self, topic: &str, queue_id: i32) -> Option<(CqUnit, i64)> {
+        let (key, value) = self.kv_store.last(
+            &Self::unit_key(topic, queue_id, 0),
+            &Self::queue_end_key(topic, queue_id),
+        )?;
+        Self::decode_unit(&key, &value)
+    }
+
+    /// Binary searches the queue for the first unit for which `before_target` does not hold,
+    /// returning its queue offset. Units must be ordered with respect to `before_target`.
+    fn lower_bound(
+        &self,
+        topic: &str,
+        queue_id: i32,
+        before_target: impl Fn(&CqUnit, i64) -> bool,
+    ) -> Option<i64> {
+        let mut low = self.get_min_offset_in_queue(topic, queue_id);
+        let mut high = self.get_max_offset_in_queue(topic, queue_id);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.unit_at_or_after(topic, queue_id, mid) {
+                Some((cq_unit, store_time)) if cq_unit.queue_offset < high => {
+                    if before_target(&cq_unit, store_time) {
+                        low = cq_unit.queue_offset + 1;
+                    } else {
+                        high = cq_unit.queue_offset;
+                    }
+                }
+                // no unit in [mid, high)
+                _ => high = mid,
+            }
+        }
+        self.unit_at_or_after(topic, queue_id, low)
+            .map(|(cq_unit, _)| cq_unit.queue_offset)
+    }
+
+    pub fn get_offset_in_queue_by_time(
+        &self,
+        topic: &str,
+        queue_id: i32,
+        timestamp: i64,
+        boundary_type: BoundaryType,
+    ) -> i64 {
+        let min_offset = self.get_min_offset_in_queue(topic, queue_id);
+        let max_offset = self.get_max_offset_in_queue(topic, queue_id);
+        match boundary_type {
+            BoundaryType::Lower => self
+                .lower_bound(topic, queue_id, |_, store_time| store_time < timestamp)
+                .unwrap_or(max_offset),
+            BoundaryType::Upper => {
+                match self.lower_bound(topic, queue_id, |_, store_time| store_time <= timestamp) {
+                    Some(offset) => (offset - 1).max(min_offset),
+                    None => (max_offset - 1).max(min_offset),
+                }
+            }
+        }
+    }
+
+    /// Deletes the units pointing below `min_physic_offset`, returning how many were removed.
+    pub fn delete_expired(&self, topic: &str, queue_id: i32, min_physic_offset: i64) -> i64 {
+        let min_offset = self.get_min_offset_in_queue(topic, queue_id);
+        let first_valid = self
+            .lower_bound(topic, queue_id, |cq_unit, _| {
+                cq_unit.pos < min_physic_offset
+            })
+            .unwrap_or_else(|| self.get_max_offset_in_queue(topic, queue_id));
+        if first_valid <= min_offset {
+            return 0;
+        }
+        let mut batch = WriteBatch::default();
+        batch.delete_range(
+            Self::unit_key(topic, queue_id, 0),
+            Self::unit_key(topic, queue_id, first_valid),
+        );
+        if let Err(e) = self.kv_store.write(batch) {
+            error!(
+                "delete expired units of kv consume queue {}-{} failed: {}",
+                topic, queue_id, e
+            );
+            return 0;
+        }
+        first_valid - min_offset
+    }
+
+    /// Deletes the units pointing at or beyond `physic_offset`.
+    pub fn truncate_dirty(&self, topic: &str, queue_id: i32, physic_offset: i64) {
+        let Some(first_dirty) =
+            self.lower_bound(topic, queue_id, |cq_unit, _| cq_unit.pos < physic_offset)
+        else {
+            return;
+        };
+        let mut batch = WriteBatch::default();
+        batch.delete_range(
+            Self::unit_key(topic, queue_id, first_dirty),
+            Self::queue_end_key(topic, queue_id),
+        );
+        let max_physic_offset = first_dirty
+            .checked_sub(1)
+            .and_then(|last| self.get_unit(topic, queue_id, last))
+            .map_or(-1, |(cq_unit, _)| cq_unit.pos + cq_unit.size as i64);
+        Self::put_offset_record(&mut batch, topic, queue_id, first_dirty, max_physic_offset);
+        match self.kv_store.write(batch) {
+            Ok(()) => info!(
+                "truncate kv consume queue {}-{} from queue offset {}",
+                topic, queue_id, first_dirty
+            ),
+            Err(e) => error!(
+                "truncate kv consume queue {}-{} failed: {}",
+                topic, queue_id, e
+            ),
+        }
+    }
+
+    pub fn destroy_queue(&self, topic: &str, queue_id: i32) {
+        let mut batch = WriteBatch::default();
+        batch.delete_range(
+            Self::unit_key(topic, queue_id, 0),
+            Self::queue_end_key(topic, queue_id),
+        );
+        batch.delete(Self::offset_key(topic, queue_id));
+        if let Err(e) = self.kv_store.write(batch) {
+            error!(
+                "destroy kv consume queue {}-{} failed: {}",
+                topic, queue_id, e
+            );
+        }
+    }
+
+    /// Every `(topic, queue id)` that has an offset record.
+    pub fn list_queues(&self) -> Vec<(CheetahString, i32)> {
+        self.kv_store
+            .scan(&[OFFSET_KEY_SPACE], &[OFFSET_KEY_SPACE + 1], usize::MAX)
+            .into_iter()
+            .filter_map(|(key, _)| {
+                let mut key = &key[1..];
+                let topic_len = key.get_u32() as usize;
+                if key.len() < topic_len + 4 {
+                    return None;
+                }
+                let topic = String::from_utf8(key[..topic_len].to_vec()).ok()?;
+                key.advance(topic_len);
+                Some((CheetahString::from_string(topic), key.get_u32() as i32))
+            })
+            .collect()
+    }
+}
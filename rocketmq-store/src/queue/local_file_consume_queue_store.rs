@@ -29,10 +29,12 @@ use rocketmq_common::common::attribute::cq_type::CQType;
 use rocketmq_common::common::boundary_type::BoundaryType;
 use rocketmq_common::common::broker::broker_config::BrokerConfig;
 use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use rocketmq_common::common::topic::TopicValidator;
 use rocketmq_common::utils::queue_type_utils::QueueTypeUtils;
 use rocketmq_rust::ArcMut;
 use tracing::error;
 use tracing::info;
+use tracing::warn;
 
 use crate::base::dispatch_request::DispatchRequest;
 use crate::config::message_store_config::MessageStoreConfig;
@@ -40,6 +42,8 @@ use crate::message_store::local_file_message_store::LocalFileMessageStore;
 use crate::queue::batch_consume_queue::BatchConsumeQueue;
 use crate::queue::consume_queue::ConsumeQueueTrait;
 use crate::queue::consume_queue_store::ConsumeQueueStoreTrait;
+use crate::queue::kv_consume_queue::KvConsumeQueue;
+use crate::queue::kv_consume_queue_table::KvConsumeQueueTable;
 use crate::queue::queue_offset_operator::QueueOffsetOperator;
 use crate::queue::single_consume_queue::ConsumeQueue;
 use crate::queue::ArcConsumeQueue;
@@ -47,6 +51,7 @@ use crate::queue::ConsumeQueueTable;
 use crate::queue::CqUnit;
 use crate::store_path_config_helper::get_store_path_batch_consume_queue;
 use crate::store_path_config_helper::get_store_path_consume_queue;
+use crate::store_path_config_helper::get_store_path_kv_consume_queue;
 
 #[derive(Clone)]
 pub struct ConsumeQueueStore {
@@ -59,6 +64,8 @@ struct Inner {
     pub(crate) broker_config: Arc<BrokerConfig>,
     pub(crate) queue_offset_operator: QueueOffsetOperator,
     pub(crate) consume_queue_table: Arc<ConsumeQueueTable>,
+    /// Holds the simple consume queues instead of mapped files when the store type is RocksDB.
+    pub(crate) kv_table: Option<Arc<KvConsumeQueueTable>>,
 }
 
 impl Inner {
@@ -77,6 +84,11 @@ impl ConsumeQueueStore {
         message_store_config: Arc<MessageStoreConfig>,
         broker_config: Arc<BrokerConfig>,
     ) -> Self {
+        let kv_table = message_store_config.is_enable_rocksdb_store().then(|| {
+            Arc::new(KvConsumeQueueTable::new(get_store_path_kv_consume_queue(
+                message_store_config.store_path_root_dir.as_str(),
+            )))
+        });
         Self {
             inner: ArcMut::new(Inner {
                 message_store: None,
@@ -84,6 +96,7 @@ impl ConsumeQueueStore {
                 broker_config,
                 queue_offset_operator: Default::default(),
                 consume_queue_table: Arc::new(Default::default()),
+                kv_table,
             }),
         }
     }
@@ -101,23 +114,31 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
     }
 
     fn load(&mut self) -> bool {
-        self.load_consume_queues(
-            get_store_path_consume_queue(
-                self.inner.message_store_config.store_path_root_dir.as_str(),
-            )
-            .as_str(),
-            CQType::SimpleCQ,
-        ) & self.load_consume_queues(
-            get_store_path_batch_consume_queue(
-                self.inner.message_store_config.store_path_root_dir.as_str(),
+        let simple_loaded = match self.inner.kv_table.clone() {
+            Some(kv_table) => self.load_kv_consume_queues(&kv_table),
+            None => self.load_consume_queues(
+                get_store_path_consume_queue(
+                    self.inner.message_store_config.store_path_root_dir.as_str(),
+                )
+                .as_str(),
+                CQType::SimpleCQ,
+            ),
+        };
+        simple_loaded
+            & self.load_consume_queues(
+                get_store_path_batch_consume_queue(
+                    self.inner.message_store_config.store_path_root_dir.as_str(),
+                )
+                .as_str(),
+                CQType::BatchCQ,
             )
-            .as_str(),
-            CQType::BatchCQ,
-        )
     }
 
     fn load_after_destroy(&self) -> bool {
-        true
+        self.inner
+            .kv_table
+            .as_ref()
+            .map_or(true, |kv_table| kv_table.load())
     }
 
     async fn recover(&self) {
@@ -133,11 +154,15 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
     }
 
     async fn recover_concurrently(&self) -> bool {
-        todo!()
+        self.recover().await;
+        true
     }
 
     fn shutdown(&self) -> bool {
-        true
+        self.inner
+            .kv_table
+            .as_ref()
+            .map_or(true, |kv_table| kv_table.flush())
     }
 
     fn destroy(&self) {
@@ -150,6 +175,9 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
                 file_queue_life_cycle.destroy();
             }
         }
+        if let Some(kv_table) = self.inner.kv_table.as_ref() {
+            kv_table.destroy();
+        }
     }
 
     fn destroy_queue(&self, consume_queue: &dyn ConsumeQueueTrait) {
@@ -163,7 +191,45 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
     }
 
     async fn clean_expired(&self, min_phy_offset: i64) {
-        todo!()
+        let cloned = self.inner.consume_queue_table.lock().clone();
+        for (topic, consume_queue_table) in cloned.iter() {
+            if TopicValidator::is_system_topic(topic.as_str()) {
+                continue;
+            }
+            for (queue_id, consume_queue) in consume_queue_table.iter() {
+                let max_cl_offset_in_consume_queue = consume_queue.get_last_offset();
+                if max_cl_offset_in_consume_queue == -1 {
+                    warn!(
+                        "maybe ConsumeQueue was created just now. topic={} queueId={} \
+                         maxPhysicOffset={} minLogicOffset={}.",
+                        topic,
+                        queue_id,
+                        consume_queue.get_max_physic_offset(),
+                        consume_queue.get_min_logic_offset()
+                    );
+                } else if max_cl_offset_in_consume_queue < min_phy_offset {
+                    info!(
+                        "cleanExpiredConsumerQueue: {} {} consumer queue destroyed, \
+                         minCommitLogOffset: {} maxCLOffsetInConsumeQueue: {}",
+                        topic, queue_id, min_phy_offset, max_cl_offset_in_consume_queue
+                    );
+                    self.inner.queue_offset_operator.remove(topic, *queue_id);
+                    self.destroy_queue(consume_queue.as_ref().as_ref());
+                    let mut table = self.inner.consume_queue_table.lock();
+                    if let Some(queue_table) = table.get_mut(topic) {
+                        queue_table.remove(queue_id);
+                    }
+                }
+            }
+            let mut table = self.inner.consume_queue_table.lock();
+            if table
+                .get(topic)
+                .is_some_and(|queue_table| queue_table.is_empty())
+            {
+                info!("cleanExpiredConsumerQueue: {},topic destroyed", topic);
+                table.remove(topic);
+            }
+        }
     }
 
     fn check_self(&self) {
@@ -175,15 +241,21 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
         consume_queue: &dyn ConsumeQueueTrait,
         min_commit_log_pos: i64,
     ) -> i32 {
-        todo!()
+        let file_queue_life_cycle =
+            self.get_life_cycle(consume_queue.get_topic(), consume_queue.get_queue_id());
+        file_queue_life_cycle.delete_expired_file(min_commit_log_pos)
     }
 
     fn is_first_file_available(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
-        todo!()
+        let file_queue_life_cycle =
+            self.get_life_cycle(consume_queue.get_topic(), consume_queue.get_queue_id());
+        file_queue_life_cycle.is_first_file_available()
     }
 
     fn is_first_file_exist(&self, consume_queue: &dyn ConsumeQueueTrait) -> bool {
-        todo!()
+        let file_queue_life_cycle =
+            self.get_life_cycle(consume_queue.get_topic(), consume_queue.get_queue_id());
+        file_queue_life_cycle.is_first_file_exist()
     }
 
     fn roll_next_file(&self, consume_queue: &dyn ConsumeQueueTrait, offset: i64) -> i64 {
@@ -262,10 +334,10 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
                     consume_queue.get_queue_id()
                 ));
                 let max_offset_in_queue = consume_queue.get_max_offset_in_queue();
-                if consume_queue.get_cq_type() == CQType::SimpleCQ {
-                    cq_offset_table.insert(key, max_offset_in_queue);
-                } else {
+                if consume_queue.get_cq_type() == CQType::BatchCQ {
                     bcq_offset_table.insert(key, max_offset_in_queue);
+                } else {
+                    cq_offset_table.insert(key, max_offset_in_queue);
                 }
                 self.correct_min_offset(&***consume_queue, min_phy_offset)
             }
@@ -350,7 +422,8 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
         timestamp: i64,
         boundary_type: BoundaryType,
     ) -> i64 {
-        todo!()
+        let consume_queue = self.find_or_create_consume_queue(topic, queue_id);
+        consume_queue.get_offset_in_queue_by_time_with_boundary(timestamp, boundary_type)
     }
 
     fn find_or_create_consume_queue(
@@ -370,17 +443,13 @@ impl ConsumeQueueStoreTrait for ConsumeQueueStore {
             let message_store = self.inner.message_store.as_ref().unwrap();
             let option = message_store.get_topic_config(topic);
             match QueueTypeUtils::get_cq_type(&option) {
-                CQType::SimpleCQ | CQType::RocksDBCQ => ArcMut::new(Box::new(ConsumeQueue::new(
-                    topic.clone(),
+                CQType::SimpleCQ | CQType::RocksDBCQ => self.new_simple_consume_queue(
+                    topic,
                     queue_id,
                     CheetahString::from_string(get_store_path_consume_queue(
                         self.inner.message_store_config.store_path_root_dir.as_str(),
                     )),
-                    self.inner
-                        .message_store_config
-                        .get_mapped_file_size_consume_queue(),
-                    self.inner.message_store.clone().unwrap(),
-                ))),
+                ),
                 CQType::BatchCQ => ArcMut::new(Box::new(BatchConsumeQueue::new(
                     topic.clone(),
                     queue_id,
@@ -598,19 +667,7 @@ impl ConsumeQueueStore {
         store_path: CheetahString,
     ) -> ArcMut<Box<dyn ConsumeQueueTrait>> {
         match cq_type {
-            CQType::SimpleCQ => {
-                let ms_ref = self.inner.message_store.as_ref().unwrap();
-                let consume_queue = ConsumeQueue::new(
-                    topic.clone(),
-                    queue_id,
-                    store_path,
-                    self.inner
-                        .message_store_config
-                        .get_mapped_file_size_consume_queue(),
-                    self.inner.message_store.clone().unwrap(),
-                );
-                ArcMut::new(Box::new(consume_queue))
-            }
+            CQType::SimpleCQ => self.new_simple_consume_queue(topic, queue_id, store_path),
             CQType::BatchCQ => {
                 let consume_queue = BatchConsumeQueue::new(
                     topic.clone(),
@@ -631,6 +688,48 @@ impl ConsumeQueueStore {
         }
     }
 
+    /// A simple consume queue, backed by the kv table when the store type is RocksDB and by
+    /// mapped files under `store_path` otherwise.
+    fn new_simple_consume_queue(
+        &self,
+        topic: &CheetahString,
+        queue_id: i32,
+        store_path: CheetahString,
+    ) -> ArcConsumeQueue {
+        match self.inner.kv_table.as_ref() {
+            Some(kv_table) => ArcMut::new(Box::new(KvConsumeQueue::new(
+                topic.clone(),
+                queue_id,
+                kv_table.clone(),
+            ))),
+            None => ArcMut::new(Box::new(ConsumeQueue::new(
+                topic.clone(),
+                queue_id,
+                store_path,
+                self.inner
+                    .message_store_config
+                    .get_mapped_file_size_consume_queue(),
+                self.inner.message_store.clone().unwrap(),
+            ))),
+        }
+    }
+
+    /// Replays the kv table and registers a consume queue for every queue it holds.
+    fn load_kv_consume_queues(&mut self, kv_table: &KvConsumeQueueTable) -> bool {
+        if !kv_table.load() {
+            return false;
+        }
+        let queues = kv_table.list_queues();
+        let queue_num = queues.len();
+        for (topic, queue_id) in queues {
+            let consume_queue =
+                self.new_simple_consume_queue(&topic, queue_id, CheetahString::empty());
+            self.put_consume_queue(topic, queue_id, consume_queue);
+        }
+        info!("load kv consume queues all over, {} queues", queue_num);
+        true
+    }
+
     #[inline]
     fn get_life_cycle(&self, topic: &CheetahString, queue_id: i32) -> ArcConsumeQueue {
         self.find_or_create_consume_queue(topic, queue_id)
@@ -135,6 +135,11 @@ impl QueueOffsetOperator {
         );
     }
 
+    #[inline]
+    pub fn get_topic_queue_table(&self) -> HashMap<CheetahString, i64> {
+        self.topic_queue_table.lock().clone()
+    }
+
     #[inline]
     pub fn set_topic_queue_table(&self, topic_queue_table: HashMap<CheetahString, i64>) {
         *self.topic_queue_table.lock() = topic_queue_table;
@@ -483,7 +483,11 @@ impl<MS: MessageStore> FileQueueLifeCycle for ConsumeQueue<MS> {
 
     #[inline]
     fn delete_expired_file(&self, min_commit_log_pos: i64) -> i32 {
-        todo!()
+        let count = self
+            .mapped_file_queue
+            .delete_expired_file_by_offset(min_commit_log_pos, CQ_STORE_UNIT_SIZE);
+        self.correct_min_offset(min_commit_log_pos);
+        count
     }
 
     #[inline]
@@ -493,12 +497,15 @@ impl<MS: MessageStore> FileQueueLifeCycle for ConsumeQueue<MS> {
 
     #[inline]
     fn is_first_file_available(&self) -> bool {
-        todo!()
+        match self.mapped_file_queue.get_first_mapped_file() {
+            None => false,
+            Some(mapped_file) => mapped_file.is_available(),
+        }
     }
 
     #[inline]
     fn is_first_file_exist(&self) -> bool {
-        todo!()
+        self.mapped_file_queue.get_first_mapped_file().is_some()
     }
 }
 
@@ -561,7 +568,27 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
 
     #[inline]
     fn get_last_offset(&self) -> i64 {
-        todo!()
+        let mut last_offset = -1;
+        if let Some(mapped_file) = self.mapped_file_queue.get_last_mapped_file() {
+            let position = (mapped_file.get_wrote_position() - CQ_STORE_UNIT_SIZE).max(0);
+            let mut index = position;
+            while index + CQ_STORE_UNIT_SIZE <= self.mapped_file_size {
+                let Some(mut bytes) =
+                    mapped_file.get_bytes(index as usize, CQ_STORE_UNIT_SIZE as usize)
+                else {
+                    break;
+                };
+                let offset = bytes.get_i64();
+                let size = bytes.get_i32();
+                if offset >= 0 && size > 0 {
+                    last_offset = offset + size as i64;
+                } else {
+                    break;
+                }
+                index += CQ_STORE_UNIT_SIZE;
+            }
+        }
+        last_offset
     }
 
     #[inline]
@@ -611,7 +638,9 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
 
     #[inline]
     fn correct_min_offset(&self, min_commit_log_offset: i64) {
-        if min_commit_log_offset >= self.mapped_file_queue.get_max_offset() {
+        // Check if the consume queue is the state of deprecation.
+        if self.min_logic_offset.load(Ordering::Acquire) >= self.mapped_file_queue.get_max_offset()
+        {
             info!(
                 "ConsumeQueue[Topic={}, queue-id={}] contains no valid entries",
                 self.topic, self.queue_id
@@ -639,7 +668,10 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
                 .mapped_file
                 .as_ref()
                 .unwrap()
-                .get_bytes(last_record.start_offset as usize, last_record.size as usize)
+                .get_bytes(
+                    (max_readable_position - CQ_STORE_UNIT_SIZE) as usize,
+                    last_record.size as usize,
+                )
                 .unwrap();
             let commit_log_offset = bytes.get_i64();
             if commit_log_offset < min_commit_log_offset {
@@ -697,10 +729,7 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
                 return;
             }
             let mapped = result.mapped_file.as_ref().unwrap();
-            let commit_log_offset = mapped
-                .get_bytes(result.start_offset as usize, 8)
-                .unwrap()
-                .get_i64();
+            let commit_log_offset = mapped.get_bytes(start as usize, 8).unwrap().get_i64();
             if intact && commit_log_offset >= min_commit_log_offset {
                 info!(
                     "Abort correction as previous min-offset points to {}, which is greater than \
@@ -716,7 +745,10 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
                     break;
                 }
                 let mid = (low + high) / 2 / CQ_STORE_UNIT_SIZE * CQ_STORE_UNIT_SIZE;
-                let commit_log_offset = mapped.get_bytes(mid as usize, 8).unwrap().get_i64();
+                let commit_log_offset = mapped
+                    .get_bytes((start + mid as i64) as usize, 8)
+                    .unwrap()
+                    .get_i64();
 
                 match commit_log_offset.cmp(&min_commit_log_offset) {
                     std::cmp::Ordering::Greater => high = mid,
@@ -730,8 +762,12 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
             }
             let mut i = low;
             while i <= high {
-                let offset_py = mapped.get_bytes(i as usize, 8).unwrap().get_i64();
-                let tags_code = mapped.get_bytes((i + 12) as usize, 8).unwrap().get_i64();
+                let position = start + i as i64;
+                let offset_py = mapped.get_bytes(position as usize, 8).unwrap().get_i64();
+                let tags_code = mapped
+                    .get_bytes((position + 12) as usize, 8)
+                    .unwrap()
+                    .get_i64();
                 if offset_py >= min_commit_log_offset {
                     self.min_logic_offset.store(
                         mapped.get_file_from_offset() as i64 + i as i64 + start,
@@ -786,12 +822,12 @@ impl<MS: MessageStore> ConsumeQueueTrait for ConsumeQueue<MS> {
                 request.consume_queue_offset,
             ) {
                 let message_store_config = self.message_store.get_message_store_config();
+                let store_checkpoint = self.message_store.get_store_checkpoint();
                 if message_store_config.broker_role == BrokerRole::Slave
                     || message_store_config.enable_dledger_commit_log
                 {
-                    unimplemented!("slave or dledger commit log not support")
+                    store_checkpoint.set_physic_msg_timestamp(request.store_timestamp as u64);
                 }
-                let store_checkpoint = self.message_store.get_store_checkpoint();
                 store_checkpoint.set_logics_msg_timestamp(request.store_timestamp as u64);
                 //if (MultiDispatchUtils.checkMultiDispatchQueue(this.messageStore.
                 // getMessageStoreConfig(), request)) {
@@ -882,7 +918,7 @@ struct ConsumeQueueIterator {
 }
 
 impl ConsumeQueueIterator {
-    fn get_ext(&self, offset: i64, cq_ext_unit: &CqExtUnit) -> bool {
+    fn get_ext(&self, offset: i64, cq_ext_unit: &mut CqExtUnit) -> bool {
         match self.consume_queue_ext.as_ref() {
             None => false,
             Some(value) => value.get(offset, cq_ext_unit),
@@ -932,8 +968,8 @@ impl Iterator for ConsumeQueueIterator {
                 };
 
                 if ConsumeQueueExt::is_ext_addr(cq_unit.tags_code) {
-                    let cq_ext_unit = CqExtUnit::default();
-                    let ext_ret = self.get_ext(cq_unit.tags_code, &cq_ext_unit);
+                    let mut cq_ext_unit = CqExtUnit::default();
+                    let ext_ret = self.get_ext(cq_unit.tags_code, &mut cq_ext_unit);
                     if ext_ret {
                         cq_unit.tags_code = cq_ext_unit.tags_code();
                         cq_unit.cq_ext_unit = Some(cq_ext_unit);
@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 use std::collections::HashMap;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
 use cheetah_string::CheetahString;
@@ -28,9 +29,10 @@ use rocketmq_common::common::statistics::statistics_item_state_getter::Statistic
 use rocketmq_common::common::statistics::statistics_kind_meta::StatisticsKindMeta;
 use rocketmq_common::common::statistics::statistics_manager::StatisticsManager;
 use rocketmq_common::common::stats::moment_stats_item_set::MomentStatsItemSet;
+use rocketmq_common::common::stats::stats_item::StatsItem;
 use rocketmq_common::common::stats::stats_item_set::StatsItemSet;
 use rocketmq_common::common::stats::Stats;
-use tracing::warn;
+use rocketmq_common::common::topic::TopicValidator;
 
 pub struct BrokerStatsManager {
     stats_table: Arc<parking_lot::RwLock<HashMap<String, StatsItemSet>>>,
@@ -100,7 +102,9 @@ impl BrokerStatsManager {
 impl BrokerStatsManager {
     #[inline]
     pub fn start(&self) {
-        //nothing to do
+        for stats in self.stats_table.read().values() {
+            stats.init();
+        }
     }
 
     #[inline]
@@ -162,9 +166,7 @@ impl BrokerStatsManager {
             Stats::GROUP_GET_FALL_TIME.to_string(),
         )));
 
-        let enable_queue_stat = true; // replace with actual condition
-
-        if enable_queue_stat {
+        if self.enable_queue_stat {
             self.stats_table.write().insert(
                 Stats::QUEUE_PUT_NUMS.to_string(),
                 StatsItemSet::new(Stats::QUEUE_PUT_NUMS.to_string()),
@@ -457,6 +459,11 @@ impl BrokerStatsManager {
         Arc::clone(&self.stats_table)
     }
 
+    #[inline]
+    pub fn get_account_stat_manager(&self) -> &StatisticsManager {
+        &self.account_stat_manager
+    }
+
     #[inline]
     pub fn get_cluster_name(&self) -> &str {
         &self.cluster_name
@@ -479,12 +486,37 @@ impl BrokerStatsManager {
 
     #[inline]
     pub fn get_broker_puts_num_without_system_topic(&self) -> u64 {
-        0
+        self.get_cluster_value(Self::BROKER_PUT_NUMS_WITHOUT_SYSTEM_TOPIC)
     }
 
     #[inline]
     pub fn get_broker_gets_num_without_system_topic(&self) -> u64 {
-        0
+        self.get_cluster_value(Self::BROKER_GET_NUMS_WITHOUT_SYSTEM_TOPIC)
+    }
+
+    /// Returns the stats item registered under `stats_name` for `stats_key`, if any.
+    pub fn get_stats_item(&self, stats_name: &str, stats_key: &str) -> Option<Arc<StatsItem>> {
+        self.stats_table
+            .read()
+            .get(stats_name)
+            .and_then(|stats| stats.get_stats_item(stats_key))
+    }
+
+    #[inline]
+    pub fn record_disk_fall_behind_time(
+        &self,
+        group: &str,
+        topic: &str,
+        queue_id: i32,
+        fall_behind: i64,
+    ) {
+        if let Some(fall_time) = self.moment_stats_item_set_fall_time.as_ref() {
+            let stats_key = format!("{}@{}@{}", queue_id, topic, group);
+            fall_time
+                .get_and_create_stats_item(stats_key)
+                .get_value()
+                .store(fall_behind, Ordering::Relaxed);
+        }
     }
 
     #[inline]
@@ -495,38 +527,197 @@ impl BrokerStatsManager {
         queue_id: i32,
         fall_behind: i64,
     ) {
+        if let Some(fall_size) = self.moment_stats_item_set_fall_size.as_ref() {
+            let stats_key = format!("{}@{}@{}", queue_id, topic, group);
+            fall_size
+                .get_and_create_stats_item(stats_key)
+                .get_value()
+                .store(fall_behind, Ordering::Relaxed);
+        }
     }
 
     #[inline]
-    pub fn inc_topic_put_nums(&self, topic: &str, num: i32, times: i32) {}
+    pub fn inc_topic_put_nums(&self, topic: &str, num: i32, times: i32) {
+        self.add_value(Stats::TOPIC_PUT_NUMS, topic, num, times);
+    }
 
     #[inline]
-    pub fn inc_topic_put_size(&self, topic: &str, size: i32) {}
+    pub fn inc_topic_put_size(&self, topic: &str, size: i32) {
+        self.add_value(Stats::TOPIC_PUT_SIZE, topic, size, 1);
+    }
 
     #[inline]
-    pub fn inc_group_get_nums(&self, group: &str, topic: &str, inc_value: i32) {}
+    pub fn inc_group_get_nums(&self, group: &str, topic: &str, inc_value: i32) {
+        let stats_key = build_stats_key(Some(topic), Some(group));
+        self.add_value(Stats::GROUP_GET_NUMS, &stats_key, inc_value, 1);
+    }
+
     #[inline]
-    pub fn inc_group_get_size(&self, group: &str, topic: &str, inc_value: i32) {}
+    pub fn inc_group_get_size(&self, group: &str, topic: &str, inc_value: i32) {
+        let stats_key = build_stats_key(Some(topic), Some(group));
+        self.add_value(Stats::GROUP_GET_SIZE, &stats_key, inc_value, 1);
+    }
 
     #[inline]
-    pub fn inc_group_ck_nums(&self, group: &str, topic: &str, inc_value: i32) {}
+    pub fn inc_group_get_latency(&self, group: &str, topic: &str, queue_id: i32, inc_value: i32) {
+        let stats_key = format!("{}@{}@{}", queue_id, topic, group);
+        self.add_value(Stats::GROUP_GET_LATENCY, &stats_key, inc_value, 1);
+    }
 
     #[inline]
-    pub fn inc_group_ack_nums(&self, group: &str, topic: &str, inc_value: i32) {}
+    pub fn inc_group_ck_nums(&self, group: &str, topic: &str, inc_value: i32) {
+        let stats_key = build_stats_key(Some(topic), Some(group));
+        self.add_value(Self::GROUP_CK_NUMS, &stats_key, inc_value, 1);
+    }
+
     #[inline]
-    pub fn inc_broker_get_nums(&self, group: &str, inc_value: i32) {}
+    pub fn inc_group_ack_nums(&self, group: &str, topic: &str, inc_value: i32) {
+        let stats_key = build_stats_key(Some(topic), Some(group));
+        self.add_value(Self::GROUP_ACK_NUMS, &stats_key, inc_value, 1);
+    }
+
     #[inline]
-    pub fn inc_broker_put_nums(&self, group: &str, inc_value: i32) {}
+    pub fn inc_send_back_nums(&self, group: &str, topic: &str) {
+        let stats_key = build_stats_key(Some(topic), Some(group));
+        self.add_value(Stats::SNDBCK_PUT_NUMS, &stats_key, 1, 1);
+    }
 
     #[inline]
-    pub fn on_topic_deleted(&self, topic: &CheetahString) {}
+    pub fn inc_dlq_stat_value(
+        &self,
+        stats_name: &str,
+        owner: &str,
+        group: &str,
+        topic: &str,
+        type_: &str,
+        inc_value: i32,
+    ) {
+        let stats_key = build_commercial_stats_key(owner, topic, group, type_);
+        self.add_value(stats_name, &stats_key, inc_value, 1);
+    }
 
     #[inline]
-    pub fn inc_queue_put_nums(&self, topic: &str, queue_id: i32, num: i32, times: i32) {}
+    pub fn inc_broker_get_nums(&self, topic: &str, inc_value: i32) {
+        self.inc_cluster_value(Stats::BROKER_GET_NUMS, inc_value);
+        if !TopicValidator::is_system_topic(topic) {
+            self.inc_cluster_value(Self::BROKER_GET_NUMS_WITHOUT_SYSTEM_TOPIC, inc_value);
+        }
+    }
+
     #[inline]
-    pub fn inc_queue_put_size(&self, topic: &str, queue_id: i32, size: i32) {}
+    pub fn inc_broker_put_nums(&self, topic: &str, inc_value: i32) {
+        self.inc_cluster_value(Stats::BROKER_PUT_NUMS, inc_value);
+        if !TopicValidator::is_system_topic(topic) {
+            self.inc_cluster_value(Self::BROKER_PUT_NUMS_WITHOUT_SYSTEM_TOPIC, inc_value);
+        }
+    }
+
+    /// Drops every stats item keyed by `topic`.
+    pub fn on_topic_deleted(&self, topic: &CheetahString) {
+        let stats_table = self.stats_table.read();
+        if let Some(stats) = stats_table.get(Stats::TOPIC_PUT_NUMS) {
+            stats.del_value(topic);
+        }
+        if let Some(stats) = stats_table.get(Stats::TOPIC_PUT_SIZE) {
+            stats.del_value(topic);
+        }
+        for stats_name in [
+            Stats::QUEUE_PUT_NUMS,
+            Stats::QUEUE_PUT_SIZE,
+            Stats::QUEUE_GET_NUMS,
+            Stats::QUEUE_GET_SIZE,
+            Stats::GROUP_GET_NUMS,
+            Stats::GROUP_GET_SIZE,
+            Self::GROUP_CK_NUMS,
+            Self::GROUP_ACK_NUMS,
+            Stats::SNDBCK_PUT_NUMS,
+        ] {
+            if let Some(stats) = stats_table.get(stats_name) {
+                stats.del_value_by_prefix_key(topic, "@");
+            }
+        }
+        if let Some(stats) = stats_table.get(Stats::GROUP_GET_LATENCY) {
+            stats.del_value_by_infix_key(topic, "@");
+        }
+        if let Some(stats) = stats_table.get(Self::TOPIC_PUT_LATENCY) {
+            stats.del_value_by_suffix_key(topic, "@");
+        }
+        for moment_stats in [
+            &self.moment_stats_item_set_fall_size,
+            &self.moment_stats_item_set_fall_time,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            moment_stats.del_value_by_infix_key(topic, "@");
+        }
+    }
+
+    /// Drops every stats item keyed by the consumer `group`.
+    pub fn on_group_deleted(&self, group: &CheetahString) {
+        let stats_table = self.stats_table.read();
+        for stats_name in [
+            Stats::QUEUE_GET_NUMS,
+            Stats::QUEUE_GET_SIZE,
+            Stats::GROUP_GET_NUMS,
+            Stats::GROUP_GET_SIZE,
+            Stats::GROUP_GET_LATENCY,
+            Self::GROUP_CK_NUMS,
+            Self::GROUP_ACK_NUMS,
+            Stats::SNDBCK_PUT_NUMS,
+        ] {
+            if let Some(stats) = stats_table.get(stats_name) {
+                stats.del_value_by_suffix_key(group, "@");
+            }
+        }
+        for moment_stats in [
+            &self.moment_stats_item_set_fall_size,
+            &self.moment_stats_item_set_fall_time,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            moment_stats.del_value_by_suffix_key(group, "@");
+        }
+    }
+
     #[inline]
-    pub fn inc_topic_put_latency(&self, topic: &str, queue_id: i32, inc_value: i32) {}
+    pub fn inc_queue_put_nums(&self, topic: &str, queue_id: i32, num: i32, times: i32) {
+        if self.enable_queue_stat {
+            let stats_key = format!("{}@{}", topic, queue_id);
+            self.add_value(Stats::QUEUE_PUT_NUMS, &stats_key, num, times);
+        }
+    }
+
+    #[inline]
+    pub fn inc_queue_put_size(&self, topic: &str, queue_id: i32, size: i32) {
+        if self.enable_queue_stat {
+            let stats_key = format!("{}@{}", topic, queue_id);
+            self.add_value(Stats::QUEUE_PUT_SIZE, &stats_key, size, 1);
+        }
+    }
+
+    #[inline]
+    pub fn inc_queue_get_nums(&self, group: &str, topic: &str, queue_id: i32, inc_value: i32) {
+        if self.enable_queue_stat {
+            let stats_key = format!("{}@{}@{}", topic, queue_id, group);
+            self.add_value(Stats::QUEUE_GET_NUMS, &stats_key, inc_value, 1);
+        }
+    }
+
+    #[inline]
+    pub fn inc_queue_get_size(&self, group: &str, topic: &str, queue_id: i32, inc_value: i32) {
+        if self.enable_queue_stat {
+            let stats_key = format!("{}@{}@{}", topic, queue_id, group);
+            self.add_value(Stats::QUEUE_GET_SIZE, &stats_key, inc_value, 1);
+        }
+    }
+
+    #[inline]
+    pub fn inc_topic_put_latency(&self, topic: &str, queue_id: i32, inc_value: i32) {
+        let stats_key = format!("{}@{}", queue_id, topic);
+        self.add_value(Self::TOPIC_PUT_LATENCY, &stats_key, inc_value, 1);
+    }
 
     #[inline]
     pub fn tps_group_get_nums(&self, group: &str, topic: &str) -> f64 {
@@ -538,21 +729,72 @@ impl BrokerStatsManager {
     }
 
     #[inline]
-    pub fn inc_broker_ack_nums(&self, inc_value: i32) {}
+    pub fn inc_broker_ack_nums(&self, inc_value: i32) {
+        self.inc_cluster_value(Self::BROKER_ACK_NUMS, inc_value);
+    }
+
+    #[inline]
+    pub fn inc_broker_ck_nums(&self, inc_value: i32) {
+        self.inc_cluster_value(Self::BROKER_CK_NUMS, inc_value);
+    }
 
     pub fn shutdown(&self) {
-        warn!("BrokerStatsManager shutdown unimplemented");
+        for stats in self.stats_table.read().values() {
+            stats.shutdown();
+        }
+    }
+
+    pub fn inc_consumer_register_time(&self, inc_value: i32) {
+        let cluster_name = self.cluster_name.clone();
+        self.add_value(Self::CONSUMER_REGISTER_TIME, &cluster_name, inc_value, 1);
+    }
+
+    pub fn inc_producer_register_time(&self, inc_value: i32) {
+        let cluster_name = self.cluster_name.clone();
+        self.add_value(Self::PRODUCER_REGISTER_TIME, &cluster_name, inc_value, 1);
+    }
+
+    pub fn inc_channel_idle_num(&self) {
+        self.add_value(Self::CHANNEL_ACTIVITY, Self::CHANNEL_ACTIVITY_IDLE, 1, 1);
     }
 
-    pub fn inc_consumer_register_time(&self, inc_value: i32) {}
+    pub fn inc_channel_exception_num(&self) {
+        self.add_value(
+            Self::CHANNEL_ACTIVITY,
+            Self::CHANNEL_ACTIVITY_EXCEPTION,
+            1,
+            1,
+        );
+    }
+
+    pub fn inc_channel_close_num(&self) {
+        self.add_value(Self::CHANNEL_ACTIVITY, Self::CHANNEL_ACTIVITY_CLOSE, 1, 1);
+    }
 
-    pub fn inc_channel_idle_num(&self) {}
+    pub fn inc_channel_connect_num(&self) {
+        self.add_value(Self::CHANNEL_ACTIVITY, Self::CHANNEL_ACTIVITY_CONNECT, 1, 1);
+    }
 
-    pub fn inc_channel_exception_num(&self) {}
+    fn add_value(&self, stats_name: &str, stats_key: &str, inc_value: i32, inc_times: i32) {
+        if let Some(stats) = self.stats_table.read().get(stats_name) {
+            stats.add_value(stats_key, inc_value.max(0) as u64, inc_times.max(0) as u64);
+        }
+    }
 
-    pub fn inc_channel_close_num(&self) {}
+    /// Broker wide counters are keyed by the cluster name and only accumulate values.
+    fn inc_cluster_value(&self, stats_name: &str, inc_value: i32) {
+        if let Some(stats) = self.stats_table.read().get(stats_name) {
+            stats
+                .get_and_create_stats_item(&self.cluster_name)
+                .get_value()
+                .fetch_add(inc_value.max(0) as u64, Ordering::Relaxed);
+        }
+    }
 
-    pub fn inc_channel_connect_num(&self) {}
+    fn get_cluster_value(&self, stats_name: &str) -> u64 {
+        self.get_stats_item(stats_name, &self.cluster_name)
+            .map_or(0, |item| item.get_value().load(Ordering::Relaxed))
+    }
 }
 
 #[inline]
@@ -697,6 +939,35 @@ mod tests {
         assert_eq!(key, "owner1|id1|topic1|group1|type1|limit1");
     }
 
+    #[tokio::test]
+    async fn counters_feed_stats_item_sets() {
+        let manager = BrokerStatsManager::new(Arc::new(BrokerConfig::default()));
+        manager.inc_topic_put_nums("TopicA", 3, 1);
+        manager.inc_topic_put_nums("TopicA", 2, 1);
+        manager.inc_group_get_nums("GroupA", "TopicA", 4);
+        manager.inc_broker_put_nums("TopicA", 5);
+        manager.inc_broker_put_nums(TopicValidator::RMQ_SYS_SCHEDULE_TOPIC, 7);
+
+        let topic_put = manager
+            .get_stats_item(Stats::TOPIC_PUT_NUMS, "TopicA")
+            .unwrap();
+        assert_eq!(topic_put.get_value().load(Ordering::Relaxed), 5);
+        assert_eq!(topic_put.get_times().load(Ordering::Relaxed), 2);
+        assert_eq!(manager.get_broker_puts_num_without_system_topic(), 5);
+        let broker_put = manager
+            .get_stats_item(Stats::BROKER_PUT_NUMS, manager.get_cluster_name())
+            .unwrap();
+        assert_eq!(broker_put.get_value().load(Ordering::Relaxed), 12);
+
+        manager.on_topic_deleted(&CheetahString::from_static_str("TopicA"));
+        assert!(manager
+            .get_stats_item(Stats::TOPIC_PUT_NUMS, "TopicA")
+            .is_none());
+        assert!(manager
+            .get_stats_item(Stats::GROUP_GET_NUMS, "TopicA@GroupA")
+            .is_none());
+    }
+
     #[test]
     fn split_account_stat_key_splits_correctly() {
         let parts = split_account_stat_key("part1|part2|part3|part4|part5");
@@ -38,6 +38,14 @@ pub fn get_store_path_batch_consume_queue(root_dir: &str) -> String {
         .into_owned()
 }
 
+pub fn get_store_path_kv_consume_queue(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("kvstore")
+        .join("consumequeue")
+        .to_string_lossy()
+        .into_owned()
+}
+
 pub fn get_store_path_index(root_dir: &str) -> String {
     PathBuf::from(root_dir)
         .join("index")
@@ -74,6 +82,30 @@ pub fn get_delay_offset_store_path(root_dir: &str) -> String {
         .into_owned()
 }
 
+pub fn get_store_path_compaction_log(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("compaction")
+        .join("compactionLog")
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn get_store_path_compaction_consume_queue(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("compaction")
+        .join("compactionCq")
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn get_compaction_position_path(root_dir: &str) -> String {
+    PathBuf::from(root_dir)
+        .join("compaction")
+        .join("position-checkpoint")
+        .to_string_lossy()
+        .into_owned()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -104,6 +136,14 @@ mod tests {
                 .to_string_lossy()
                 .into_owned()
         );
+        assert_eq!(
+            get_store_path_kv_consume_queue(root_dir),
+            PathBuf::from(root_dir)
+                .join("kvstore")
+                .join("consumequeue")
+                .to_string_lossy()
+                .into_owned()
+        );
         assert_eq!(
             get_store_path_index(root_dir),
             PathBuf::from(root_dir)
@@ -140,5 +180,29 @@ mod tests {
                 .to_string_lossy()
                 .into_owned()
         );
+        assert_eq!(
+            get_store_path_compaction_log(root_dir),
+            PathBuf::from(root_dir)
+                .join("compaction")
+                .join("compactionLog")
+                .to_string_lossy()
+                .into_owned()
+        );
+        assert_eq!(
+            get_store_path_compaction_consume_queue(root_dir),
+            PathBuf::from(root_dir)
+                .join("compaction")
+                .join("compactionCq")
+                .to_string_lossy()
+                .into_owned()
+        );
+        assert_eq!(
+            get_compaction_position_path(root_dir),
+            PathBuf::from(root_dir)
+                .join("compaction")
+                .join("position-checkpoint")
+                .to_string_lossy()
+                .into_owned()
+        );
     }
 }
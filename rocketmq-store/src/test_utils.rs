@@ -0,0 +1,79 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! Fixtures shared by the unit tests of this crate.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::broker::broker_config::BrokerConfig;
+use rocketmq_common::common::config::TopicConfig;
+use rocketmq_rust::ArcMut;
+use tempfile::TempDir;
+
+use crate::config::message_store_config::MessageStoreConfig;
+use crate::message_store::local_file_message_store::LocalFileMessageStore;
+
+pub(crate) type TopicConfigTable = Arc<parking_lot::Mutex<HashMap<CheetahString, TopicConfig>>>;
+
+/// Creates a `LocalFileMessageStore` rooted in a fresh temporary directory.
+///
+/// `store_path_root_dir` of `message_store_config` is replaced by the temporary directory,
+/// which is removed once the returned `TempDir` is dropped.
+pub(crate) fn new_message_store(
+    message_store_config: MessageStoreConfig,
+) -> (ArcMut<LocalFileMessageStore>, TempDir) {
+    new_message_store_with_topics(
+        message_store_config,
+        Arc::new(parking_lot::Mutex::new(HashMap::new())),
+    )
+}
+
+/// Same as [`new_message_store`], with a caller provided topic config table.
+pub(crate) fn new_message_store_with_topics(
+    message_store_config: MessageStoreConfig,
+    topic_config_table: TopicConfigTable,
+) -> (ArcMut<LocalFileMessageStore>, TempDir) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let message_store_config = Arc::new(MessageStoreConfig {
+        store_path_root_dir: temp_dir.path().to_string_lossy().to_string().into(),
+        ..message_store_config
+    });
+    let mut message_store = ArcMut::new(LocalFileMessageStore::new(
+        message_store_config,
+        Arc::new(BrokerConfig::default()),
+        topic_config_table,
+        None,
+        false,
+    ));
+    let message_store_clone = message_store.clone();
+    message_store.set_message_store_arc(message_store_clone);
+    (message_store, temp_dir)
+}
+
+/// Polls `condition` every 10ms for up to 10 seconds, returning whether it became true.
+pub(crate) async fn wait_until(condition: impl Fn() -> bool) -> bool {
+    for _ in 0..1000 {
+        if condition() {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    condition()
+}
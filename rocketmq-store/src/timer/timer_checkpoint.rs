@@ -0,0 +1,153 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+
+use memmap2::MmapMut;
+use parking_lot::Mutex;
+use rocketmq_common::UtilAll::ensure_dir_ok;
+use tracing::info;
+
+use crate::log_file::mapped_file::default_mapped_file_impl::OS_PAGE_SIZE;
+
+/// Persists the progress of the timer message store, the layout matches the `timercheck` file of
+/// the Java broker.
+pub struct TimerCheckpoint {
+    last_read_time_ms: AtomicI64,
+    last_timer_log_flush_pos: AtomicI64,
+    last_timer_queue_offset: AtomicI64,
+    master_timer_queue_offset: AtomicI64,
+    mmap: Mutex<MmapMut>,
+    _file: File,
+}
+
+impl TimerCheckpoint {
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        if let Some(parent) = path.as_ref().parent() {
+            ensure_dir_ok(parent.to_string_lossy().as_ref());
+        }
+        let exists = path.as_ref().exists();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path.as_ref())?;
+        file.set_len(OS_PAGE_SIZE)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        let read = |index: usize| i64::from_be_bytes(mmap[index..index + 8].try_into().unwrap());
+        let checkpoint = Self {
+            last_read_time_ms: AtomicI64::new(read(0)),
+            last_timer_log_flush_pos: AtomicI64::new(read(8)),
+            last_timer_queue_offset: AtomicI64::new(read(16)),
+            master_timer_queue_offset: AtomicI64::new(read(24)),
+            mmap: Mutex::new(mmap),
+            _file: file,
+        };
+        if exists {
+            info!("timer checkpoint file exists, {}", path.as_ref().display());
+            info!(
+                "lastReadTimeMs: {}, lastTimerLogFlushPos: {}, lastTimerQueueOffset: {}, \
+                 masterTimerQueueOffset: {}",
+                checkpoint.get_last_read_time_ms(),
+                checkpoint.get_last_timer_log_flush_pos(),
+                checkpoint.get_last_timer_queue_offset(),
+                checkpoint.get_master_timer_queue_offset()
+            );
+        }
+        Ok(checkpoint)
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        let mut mmap = self.mmap.lock();
+        mmap[0..8].copy_from_slice(&self.get_last_read_time_ms().to_be_bytes());
+        mmap[8..16].copy_from_slice(&self.get_last_timer_log_flush_pos().to_be_bytes());
+        mmap[16..24].copy_from_slice(&self.get_last_timer_queue_offset().to_be_bytes());
+        mmap[24..32].copy_from_slice(&self.get_master_timer_queue_offset().to_be_bytes());
+        mmap.flush()
+    }
+
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.flush()
+    }
+
+    pub fn get_last_read_time_ms(&self) -> i64 {
+        self.last_read_time_ms.load(Ordering::Acquire)
+    }
+
+    pub fn set_last_read_time_ms(&self, last_read_time_ms: i64) {
+        self.last_read_time_ms
+            .store(last_read_time_ms, Ordering::Release);
+    }
+
+    pub fn get_last_timer_log_flush_pos(&self) -> i64 {
+        self.last_timer_log_flush_pos.load(Ordering::Acquire)
+    }
+
+    pub fn set_last_timer_log_flush_pos(&self, last_timer_log_flush_pos: i64) {
+        self.last_timer_log_flush_pos
+            .store(last_timer_log_flush_pos, Ordering::Release);
+    }
+
+    pub fn get_last_timer_queue_offset(&self) -> i64 {
+        self.last_timer_queue_offset.load(Ordering::Acquire)
+    }
+
+    pub fn set_last_timer_queue_offset(&self, last_timer_queue_offset: i64) {
+        self.last_timer_queue_offset
+            .store(last_timer_queue_offset, Ordering::Release);
+    }
+
+    pub fn get_master_timer_queue_offset(&self) -> i64 {
+        self.master_timer_queue_offset.load(Ordering::Acquire)
+    }
+
+    pub fn set_master_timer_queue_offset(&self, master_timer_queue_offset: i64) {
+        self.master_timer_queue_offset
+            .store(master_timer_queue_offset, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkpoint_survives_reopen() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config").join("timercheck");
+        let checkpoint = TimerCheckpoint::new(&path).unwrap();
+        assert_eq!(checkpoint.get_last_read_time_ms(), 0);
+        checkpoint.set_last_read_time_ms(1000);
+        checkpoint.set_last_timer_log_flush_pos(52);
+        checkpoint.set_last_timer_queue_offset(3);
+        checkpoint.set_master_timer_queue_offset(3);
+        checkpoint.flush().unwrap();
+        drop(checkpoint);
+
+        let checkpoint = TimerCheckpoint::new(&path).unwrap();
+        assert_eq!(checkpoint.get_last_read_time_ms(), 1000);
+        assert_eq!(checkpoint.get_last_timer_log_flush_pos(), 52);
+        assert_eq!(checkpoint.get_last_timer_queue_offset(), 3);
+        assert_eq!(checkpoint.get_master_timer_queue_offset(), 3);
+    }
+}
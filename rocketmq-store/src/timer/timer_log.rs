@@ -0,0 +1,280 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use bytes::Buf;
+use bytes::BufMut;
+use bytes::Bytes;
+use bytes::BytesMut;
+use rocketmq_rust::ArcMut;
+use tracing::error;
+use tracing::info;
+
+use crate::consume_queue::mapped_file_queue::MappedFileQueue;
+use crate::log_file::mapped_file::MappedFile;
+
+/// The size of one timer log unit:
+/// ```text
+/// ┌────┬─────────┬───────┬────────────────┬────────────┬──────────┬────────┬──────────────┬──────────┐
+/// │size│prev pos │ magic │curr write time │delayed time│offset py │size py │hash of topic │ reserved │
+/// ├────┼─────────┼───────┼────────────────┼────────────┼──────────┼────────┼──────────────┼──────────┤
+/// │ 4  │    8    │   4   │       8        │     4      │    8     │   4    │      4       │    8     │
+/// └────┴─────────┴───────┴────────────────┴────────────┴──────────┴────────┴──────────────┴──────────┘
+/// ```
+pub const UNIT_SIZE: i32 = 4 + 8 + 4 + 8 + 4 + 8 + 4 + 4 + 8;
+
+/// The offset of the commit log position inside a unit.
+pub const UNIT_PRE_SIZE_FOR_MSG: i32 = 28;
+
+/// The offset of the topic hash inside a unit.
+pub const UNIT_PRE_SIZE_FOR_METRIC: i32 = 40;
+
+const MIN_BLANK_LEN: i32 = 4 + 8 + 4;
+
+pub const BLANK_MAGIC_CODE: i32 = (0xBBCCDDEE_u32 as i32) ^ (1880681586 + 8);
+
+/// One decoded unit of the timer log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerLogUnit {
+    pub size: i32,
+    pub prev_pos: i64,
+    pub magic: i32,
+    pub curr_write_time: i64,
+    pub delayed_time: i32,
+    pub offset_py: i64,
+    pub size_py: i32,
+    pub topic_hash: i32,
+}
+
+impl TimerLogUnit {
+    pub fn encode(&self) -> Bytes {
+        let mut buffer = BytesMut::with_capacity(UNIT_SIZE as usize);
+        buffer.put_i32(self.size);
+        buffer.put_i64(self.prev_pos);
+        buffer.put_i32(self.magic);
+        buffer.put_i64(self.curr_write_time);
+        buffer.put_i32(self.delayed_time);
+        buffer.put_i64(self.offset_py);
+        buffer.put_i32(self.size_py);
+        buffer.put_i32(self.topic_hash);
+        // reserved value, just set to 0 now
+        buffer.put_i64(0);
+        buffer.freeze()
+    }
+
+    pub fn decode(mut buffer: &[u8]) -> Self {
+        let size = buffer.get_i32();
+        let prev_pos = buffer.get_i64();
+        let magic = buffer.get_i32();
+        let curr_write_time = buffer.get_i64();
+        let delayed_time = buffer.get_i32();
+        let offset_py = buffer.get_i64();
+        let size_py = buffer.get_i32();
+        let topic_hash = buffer.get_i32();
+        Self {
+            size,
+            prev_pos,
+            magic,
+            curr_write_time,
+            delayed_time,
+            offset_py,
+            size_py,
+            topic_hash,
+        }
+    }
+}
+
+/// The append only log behind the timing wheel, every slot links its units through `prev_pos`.
+pub struct TimerLog {
+    file_size: i32,
+    mapped_file_queue: ArcMut<MappedFileQueue>,
+}
+
+impl TimerLog {
+    pub fn new(store_path: &str, file_size: i32) -> Self {
+        Self {
+            file_size,
+            mapped_file_queue: ArcMut::new(MappedFileQueue::new(
+                store_path.to_string(),
+                file_size as u64,
+                None,
+            )),
+        }
+    }
+
+    pub fn load(&self) -> bool {
+        self.mapped_file_queue.mut_from_ref().load()
+    }
+
+    /// Restores the write position of the last file by scanning its units, starting at
+    /// `last_flush_pos` when it lies inside that file.
+    pub fn recover(&self, last_flush_pos: i64) -> i64 {
+        let Some(mapped_file) = self.mapped_file_queue.get_last_mapped_file() else {
+            return 0;
+        };
+        let file_from_offset = mapped_file.get_file_from_offset() as i64;
+        let mut position = if last_flush_pos > file_from_offset
+            && last_flush_pos <= file_from_offset + self.file_size as i64
+        {
+            (last_flush_pos - file_from_offset) as i32
+        } else {
+            0
+        };
+        while position + MIN_BLANK_LEN <= self.file_size {
+            let Some(header) = mapped_file.get_data(position as usize, MIN_BLANK_LEN as usize)
+            else {
+                break;
+            };
+            let mut header = header.as_ref();
+            let size = header.get_i32();
+            header.advance(8);
+            let magic = header.get_i32();
+            if magic == BLANK_MAGIC_CODE && size == self.file_size - position {
+                position = self.file_size;
+                break;
+            }
+            if size != UNIT_SIZE || position + UNIT_SIZE > self.file_size {
+                break;
+            }
+            position += UNIT_SIZE;
+        }
+        mapped_file.set_wrote_position(position);
+        mapped_file.set_flushed_position(position);
+        mapped_file.set_committed_position(position);
+        let process_offset = file_from_offset + position as i64;
+        self.mapped_file_queue.set_flushed_where(process_offset);
+        self.mapped_file_queue.set_committed_where(process_offset);
+        info!("timer log recover over, process offset {}", process_offset);
+        process_offset
+    }
+
+    /// Appends `data[pos..pos + len]`, returning the log offset it was written at or `-1`.
+    pub fn append(&self, data: &[u8], pos: i32, len: i32) -> i64 {
+        let mapped_file_queue = self.mapped_file_queue.mut_from_ref();
+        let Some(mut mapped_file) =
+            mapped_file_queue.get_last_mapped_file_mut_start_offset(0, true)
+        else {
+            error!("Create mapped file for timer log error");
+            return -1;
+        };
+        let wrote_position = mapped_file.get_wrote_position();
+        if len + MIN_BLANK_LEN > self.file_size - wrote_position {
+            let mut blank = BytesMut::with_capacity(MIN_BLANK_LEN as usize);
+            blank.put_i32(self.file_size - wrote_position);
+            blank.put_i64(0);
+            blank.put_i32(BLANK_MAGIC_CODE);
+            if mapped_file.append_message_offset_length(&blank, 0, MIN_BLANK_LEN as usize) {
+                // the file is full now, the following units go to a new one
+                mapped_file.set_wrote_position(self.file_size);
+            } else {
+                error!(
+                    "Append blank error for timer log, wrote position {}",
+                    wrote_position
+                );
+                return -1;
+            }
+            mapped_file = match mapped_file_queue.get_last_mapped_file_mut_start_offset(0, true) {
+                Some(mapped_file) => mapped_file,
+                None => {
+                    error!("Create mapped file for timer log error");
+                    return -1;
+                }
+            };
+        }
+        let curr_position =
+            mapped_file.get_file_from_offset() as i64 + mapped_file.get_wrote_position() as i64;
+        if !mapped_file.append_message_offset_length(data, pos as usize, len as usize) {
+            error!("Append error for timer log");
+            return -1;
+        }
+        curr_position
+    }
+
+    /// Reads the unit written at `offset`.
+    pub fn get_unit(&self, offset: i64) -> Option<TimerLogUnit> {
+        let mapped_file = self
+            .mapped_file_queue
+            .find_mapped_file_by_offset(offset, false)?;
+        let position = (offset % self.file_size as i64) as usize;
+        let data = mapped_file.get_data(position, UNIT_SIZE as usize)?;
+        Some(TimerLogUnit::decode(data.as_ref()))
+    }
+
+    pub fn flush(&self, flush_least_pages: i32) -> bool {
+        self.mapped_file_queue.flush(flush_least_pages)
+    }
+
+    pub fn get_flushed_where(&self) -> i64 {
+        self.mapped_file_queue.get_flushed_where()
+    }
+
+    pub fn get_max_offset(&self) -> i64 {
+        self.mapped_file_queue.get_max_offset()
+    }
+
+    pub fn get_mapped_file_queue(&self) -> &ArcMut<MappedFileQueue> {
+        &self.mapped_file_queue
+    }
+
+    pub fn shutdown(&self) {
+        self.flush(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(prev_pos: i64, offset_py: i64) -> TimerLogUnit {
+        TimerLogUnit {
+            size: UNIT_SIZE,
+            prev_pos,
+            magic: 1,
+            curr_write_time: 1_700_000_000_000,
+            delayed_time: 3000,
+            offset_py,
+            size_py: 128,
+            topic_hash: 7,
+        }
+    }
+
+    #[test]
+    fn append_rolls_to_next_file_and_recovers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let store_path = temp_dir.path().join("timerlog");
+        // room for two units and less than one more unit plus the blank
+        let file_size = UNIT_SIZE * 3;
+        let timer_log = TimerLog::new(store_path.to_str().unwrap(), file_size);
+
+        let first = timer_log.append(&unit(-1, 0).encode(), 0, UNIT_SIZE);
+        let second = timer_log.append(&unit(first, 128).encode(), 0, UNIT_SIZE);
+        let third = timer_log.append(&unit(second, 256).encode(), 0, UNIT_SIZE);
+        assert_eq!(first, 0);
+        assert_eq!(second, UNIT_SIZE as i64);
+        assert_eq!(third, file_size as i64);
+        assert_eq!(timer_log.get_unit(third), Some(unit(second, 256)));
+        assert_eq!(timer_log.get_unit(second).unwrap().prev_pos, first);
+        timer_log.flush(0);
+        drop(timer_log);
+
+        let timer_log = TimerLog::new(store_path.to_str().unwrap(), file_size);
+        assert!(timer_log.load());
+        assert_eq!(timer_log.recover(0), (file_size + UNIT_SIZE) as i64);
+        assert_eq!(timer_log.get_unit(first).unwrap().offset_py, 0);
+        let fourth = timer_log.append(&unit(third, 384).encode(), 0, UNIT_SIZE);
+        assert_eq!(fourth, (file_size + UNIT_SIZE) as i64);
+    }
+}
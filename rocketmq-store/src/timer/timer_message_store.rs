@@ -14,19 +14,44 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
 use cheetah_string::CheetahString;
+use parking_lot::Mutex;
+use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::common::message::message_client_id_setter::MessageClientIDSetter;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::message_ext_broker_inner::MessageExtBrokerInner;
+use rocketmq_common::common::message::message_single;
 use rocketmq_common::common::message::MessageConst;
+use rocketmq_common::common::message::MessageTrait;
 use rocketmq_common::common::system_clock::SystemClock;
+use rocketmq_common::MessageAccessor::MessageAccessor;
+use rocketmq_common::MessageDecoder;
 use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_common::TimeUtils::get_current_nano;
 use rocketmq_rust::ArcMut;
+use tokio::sync::Notify;
 use tracing::error;
+use tracing::info;
 use tracing::warn;
 
+use crate::base::message_status_enum::PutMessageStatus;
 use crate::base::message_store::MessageStore;
+use crate::config::message_store_config::MessageStoreConfig;
 use crate::message_store::local_file_message_store::LocalFileMessageStore;
+use crate::timer::timer_checkpoint::TimerCheckpoint;
+use crate::timer::timer_log::TimerLog;
+use crate::timer::timer_log::TimerLogUnit;
+use crate::timer::timer_log::UNIT_SIZE;
 use crate::timer::timer_metrics::TimerMetrics;
+use crate::timer::timer_wheel::TimerWheel;
 
 pub const TIMER_TOPIC: &str = concat!("rmq_sys_", "wheel_timer");
 pub const TIMER_OUT_MS: &str = MessageConst::PROPERTY_TIMER_OUT_MS;
@@ -49,45 +74,719 @@ pub const MAGIC_DEFAULT: i32 = 1;
 pub const MAGIC_ROLL: i32 = 1 << 1;
 pub const MAGIC_DELETE: i32 = 1 << 2;
 
+const IDLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Enqueue/dequeue counters sampled by the flush service to compute the TPS.
+#[derive(Default)]
+struct TpsSampler {
+    last_sample_time: u64,
+    last_enqueue_count: i64,
+    last_dequeue_count: i64,
+    enqueue_tps: f32,
+    dequeue_tps: f32,
+}
+
+/// Delivers messages at an arbitrary time.
+///
+/// Messages sent with a delivery time are first stored in [`TIMER_TOPIC`]. The enqueue service
+/// reads them back from the consume queue and links them into the slot of their delivery time
+/// in the [`TimerWheel`], the units themselves being appended to the [`TimerLog`]. Once the read
+/// time reaches a slot, the dequeue service puts every message of the slot back to its real
+/// topic, or to [`TIMER_TOPIC`] again when the delay was longer than the roll window.
+#[derive(Clone)]
 pub struct TimerMessageStore {
-    pub curr_read_time_ms: AtomicI64,
-    pub curr_queue_offset: AtomicI64,
-    pub last_enqueue_but_expired_time: u64,
-    pub last_enqueue_but_expired_store_time: u64,
+    pub curr_read_time_ms: Arc<AtomicI64>,
+    pub curr_queue_offset: Arc<AtomicI64>,
+    pub last_enqueue_but_expired_time: Arc<AtomicI64>,
+    pub last_enqueue_but_expired_store_time: Arc<AtomicI64>,
     pub default_message_store: Option<ArcMut<LocalFileMessageStore>>,
-    pub timer_metrics: TimerMetrics,
+    pub timer_metrics: Arc<TimerMetrics>,
+    message_store_config: Arc<MessageStoreConfig>,
+    timer_wheel: Arc<TimerWheel>,
+    timer_log: Arc<TimerLog>,
+    timer_checkpoint: Arc<TimerCheckpoint>,
+    precision_ms: i64,
+    slots_total: i32,
+    timer_roll_window_slots: i32,
+    curr_write_time_ms: Arc<AtomicI64>,
+    commit_read_time_ms: Arc<AtomicI64>,
+    commit_queue_offset: Arc<AtomicI64>,
+    should_running_dequeue: Arc<AtomicBool>,
+    running: Arc<AtomicBool>,
+    shutdown_notify: Arc<Notify>,
+    enqueue_count: Arc<AtomicI64>,
+    dequeue_count: Arc<AtomicI64>,
+    tps_sampler: Arc<Mutex<TpsSampler>>,
 }
 
-impl Clone for TimerMessageStore {
-    fn clone(&self) -> Self {
-        Self {
-            curr_read_time_ms: AtomicI64::new(
-                self.curr_read_time_ms
-                    .load(std::sync::atomic::Ordering::Relaxed),
-            ),
-            curr_queue_offset: AtomicI64::new(
-                self.curr_queue_offset
-                    .load(std::sync::atomic::Ordering::Relaxed),
-            ),
-            last_enqueue_but_expired_time: self.last_enqueue_but_expired_time,
-            last_enqueue_but_expired_store_time: self.last_enqueue_but_expired_store_time,
-            default_message_store: self.default_message_store.clone(),
-            timer_metrics: TimerMetrics,
-        }
+impl TimerMessageStore {
+    pub fn new(
+        message_store_config: Arc<MessageStoreConfig>,
+        timer_checkpoint: Arc<TimerCheckpoint>,
+        timer_metrics: Arc<TimerMetrics>,
+        default_message_store: Option<ArcMut<LocalFileMessageStore>>,
+    ) -> std::io::Result<Self> {
+        let precision_ms = message_store_config.timer_precision_ms as i64;
+        let slots_total = TIMER_WHEEL_TTL_DAY * DAY_SECS;
+        let timer_wheel = TimerWheel::new(
+            Self::get_timer_wheel_path(message_store_config.store_path_root_dir.as_str()).as_str(),
+            slots_total,
+            precision_ms,
+        )?;
+        let timer_log = TimerLog::new(
+            Self::get_timer_log_path(message_store_config.store_path_root_dir.as_str()).as_str(),
+            message_store_config.mapped_file_size_timer_log as i32,
+        );
+        let timer_roll_window_slots = message_store_config.timer_roll_window_slot as i32;
+        Ok(Self {
+            curr_read_time_ms: Arc::new(AtomicI64::new(0)),
+            curr_queue_offset: Arc::new(AtomicI64::new(0)),
+            last_enqueue_but_expired_time: Arc::new(AtomicI64::new(0)),
+            last_enqueue_but_expired_store_time: Arc::new(AtomicI64::new(0)),
+            default_message_store,
+            timer_metrics,
+            message_store_config,
+            timer_wheel: Arc::new(timer_wheel),
+            timer_log: Arc::new(timer_log),
+            timer_checkpoint,
+            precision_ms,
+            slots_total,
+            timer_roll_window_slots,
+            curr_write_time_ms: Arc::new(AtomicI64::new(0)),
+            commit_read_time_ms: Arc::new(AtomicI64::new(0)),
+            commit_queue_offset: Arc::new(AtomicI64::new(0)),
+            should_running_dequeue: Arc::new(AtomicBool::new(false)),
+            running: Arc::new(AtomicBool::new(false)),
+            shutdown_notify: Arc::new(Notify::new()),
+            enqueue_count: Arc::new(AtomicI64::new(0)),
+            dequeue_count: Arc::new(AtomicI64::new(0)),
+            tps_sampler: Arc::new(Mutex::new(TpsSampler::default())),
+        })
+    }
+
+    pub fn get_timer_wheel_path(root_dir: &str) -> String {
+        PathBuf::from(root_dir)
+            .join("timerwheel")
+            .to_string_lossy()
+            .to_string()
+    }
+
+    pub fn get_timer_log_path(root_dir: &str) -> String {
+        PathBuf::from(root_dir)
+            .join("timerlog")
+            .to_string_lossy()
+            .to_string()
     }
-}
 
-impl TimerMessageStore {
     pub fn load(&mut self) -> bool {
-        true
+        let result = self.timer_log.load();
+        // The metrics file does not exist until the first persist, so it never fails the load.
+        self.timer_metrics.load();
+        self.recover();
+        result
+    }
+
+    /// Restores the timer log write position and the read/queue offsets from the checkpoint.
+    fn recover(&self) {
+        let last_flush_pos = self.timer_checkpoint.get_last_timer_log_flush_pos();
+        let process_offset = self.timer_log.recover(last_flush_pos);
+
+        let curr_queue_offset = self
+            .timer_checkpoint
+            .get_last_timer_queue_offset()
+            .min(self.timer_checkpoint.get_master_timer_queue_offset());
+        self.curr_queue_offset
+            .store(curr_queue_offset, Ordering::Release);
+        self.commit_queue_offset
+            .store(curr_queue_offset, Ordering::Release);
+
+        let curr_read_time_ms =
+            self.clamp_read_time_ms(self.timer_checkpoint.get_last_read_time_ms());
+        self.curr_read_time_ms
+            .store(curr_read_time_ms, Ordering::Release);
+        self.commit_read_time_ms
+            .store(curr_read_time_ms, Ordering::Release);
+
+        let check_offset = self
+            .timer_wheel
+            .check_phy_pos(curr_read_time_ms, process_offset);
+        if check_offset != process_offset {
+            warn!(
+                "Timer wheel references timer log offset {} beyond the recovered offset {}, some \
+                 timer messages may be lost",
+                check_offset, process_offset
+            );
+        }
+        info!(
+            "Timer recover ok, process offset:{} read time:{} queue offset:{}",
+            process_offset, curr_read_time_ms, curr_queue_offset
+        );
+        self.prepare_timer_checkpoint();
+        if let Err(e) = self.timer_checkpoint.flush() {
+            error!("Flush timer checkpoint failed: {}", e);
+        }
     }
 
     pub fn start(&mut self) {
-        warn!("TimerMessageStore start unimplemented, do nothing");
+        if self.running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.maybe_move_write_time();
+        tokio::spawn(self.clone().enqueue_loop());
+        tokio::spawn(self.clone().dequeue_loop());
+        tokio::spawn(self.clone().flush_loop());
+        info!("TimerMessageStore started");
+    }
+
+    pub fn shutdown(&mut self) {
+        if !self.running.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        self.shutdown_notify.notify_waiters();
+        self.flush();
+        if let Err(e) = self.timer_wheel.shutdown() {
+            error!("Shutdown timer wheel failed: {}", e);
+        }
+        self.timer_log.shutdown();
+        if let Err(e) = self.timer_checkpoint.shutdown() {
+            error!("Shutdown timer checkpoint failed: {}", e);
+        }
+        info!("TimerMessageStore shutdown");
+    }
+
+    async fn enqueue_loop(self) {
+        while self.running.load(Ordering::Acquire) {
+            if !self.enqueue(0).await {
+                self.idle().await;
+            }
+        }
+    }
+
+    async fn dequeue_loop(self) {
+        while self.running.load(Ordering::Acquire) {
+            match self.dequeue().await {
+                -1 => self.idle().await,
+                _ => tokio::task::yield_now().await,
+            }
+        }
+    }
+
+    async fn flush_loop(self) {
+        let interval =
+            Duration::from_millis(self.message_store_config.timer_flush_interval_ms as u64);
+        let mut last_progress_log = get_current_millis();
+        while self.running.load(Ordering::Acquire) {
+            tokio::select! {
+                _ = self.shutdown_notify.notified() => {}
+                _ = tokio::time::sleep(interval) => {}
+            }
+            if !self.running.load(Ordering::Acquire) {
+                break;
+            }
+            self.flush();
+            self.sample_tps();
+            let now = get_current_millis();
+            if now - last_progress_log
+                > self.message_store_config.timer_progress_log_interval_ms as u64
+            {
+                last_progress_log = now;
+                info!(
+                    "Timer progress-check commitRead:{} currRead:{} currWrite:{} readBehind:{} \
+                     currReadOffset:{} enqueueBehind:{} allCongestNum:{}",
+                    self.commit_read_time_ms.load(Ordering::Acquire),
+                    self.curr_read_time_ms.load(Ordering::Acquire),
+                    self.curr_write_time_ms.load(Ordering::Acquire),
+                    self.get_dequeue_behind(),
+                    self.curr_queue_offset.load(Ordering::Acquire),
+                    self.get_enqueue_behind_messages(),
+                    self.get_all_congest_num()
+                );
+            }
+        }
+    }
+
+    async fn idle(&self) {
+        tokio::select! {
+            _ = self.shutdown_notify.notified() => {}
+            _ = tokio::time::sleep(IDLE_INTERVAL) => {}
+        }
+    }
+
+    fn flush(&self) {
+        self.prepare_timer_checkpoint();
+        self.timer_log.flush(0);
+        if let Err(e) = self.timer_wheel.flush() {
+            error!("Flush timer wheel failed: {}", e);
+        }
+        if let Err(e) = self.timer_checkpoint.flush() {
+            error!("Flush timer checkpoint failed: {}", e);
+        }
+        self.timer_metrics.persist();
+    }
+
+    fn prepare_timer_checkpoint(&self) {
+        self.timer_checkpoint
+            .set_last_timer_log_flush_pos(self.timer_log.get_flushed_where());
+        self.timer_checkpoint
+            .set_last_read_time_ms(self.commit_read_time_ms.load(Ordering::Acquire));
+        let commit_queue_offset = self.commit_queue_offset.load(Ordering::Acquire);
+        if self.should_running_dequeue.load(Ordering::Acquire) {
+            self.timer_checkpoint
+                .set_master_timer_queue_offset(commit_queue_offset);
+        }
+        self.timer_checkpoint.set_last_timer_queue_offset(
+            commit_queue_offset.min(self.timer_checkpoint.get_master_timer_queue_offset()),
+        );
+    }
+
+    fn sample_tps(&self) {
+        let now = get_current_millis();
+        let enqueue_count = self.enqueue_count.load(Ordering::Relaxed);
+        let dequeue_count = self.dequeue_count.load(Ordering::Relaxed);
+        let mut sampler = self.tps_sampler.lock();
+        if sampler.last_sample_time != 0 && now > sampler.last_sample_time {
+            let elapsed = (now - sampler.last_sample_time) as f32 / 1000.0;
+            sampler.enqueue_tps = (enqueue_count - sampler.last_enqueue_count) as f32 / elapsed;
+            sampler.dequeue_tps = (dequeue_count - sampler.last_dequeue_count) as f32 / elapsed;
+        }
+        sampler.last_sample_time = now;
+        sampler.last_enqueue_count = enqueue_count;
+        sampler.last_dequeue_count = dequeue_count;
+    }
+
+    fn is_running_enqueue(&self) -> bool {
+        !self.message_store_config.timer_stop_enqueue
+    }
+
+    fn is_running_dequeue(&self) -> bool {
+        !self.message_store_config.timer_stop_dequeue
+            && self.should_running_dequeue.load(Ordering::Acquire)
+    }
+
+    fn maybe_move_write_time(&self) {
+        self.curr_write_time_ms.store(
+            self.format_time_ms(get_current_millis() as i64),
+            Ordering::Release,
+        );
+    }
+
+    fn move_read_time(&self) {
+        let curr_read_time_ms = self
+            .curr_read_time_ms
+            .fetch_add(self.precision_ms, Ordering::AcqRel)
+            + self.precision_ms;
+        self.commit_read_time_ms
+            .store(curr_read_time_ms, Ordering::Release);
+    }
+
+    fn format_time_ms(&self, time_ms: i64) -> i64 {
+        time_ms / self.precision_ms * self.precision_ms
+    }
+
+    /// Never reads before the oldest slot the wheel can still hold.
+    fn clamp_read_time_ms(&self, read_time_ms: i64) -> i64 {
+        let oldest = self.format_time_ms(get_current_millis() as i64)
+            - self.slots_total as i64 * self.precision_ms
+            + TIMER_BLANK_SLOTS as i64 * self.precision_ms;
+        read_time_ms.max(oldest)
+    }
+
+    /// Moves the messages appended to [`TIMER_TOPIC`] into the timing wheel, returns `true` when
+    /// at least one message was consumed.
+    pub async fn enqueue(&self, queue_id: i32) -> bool {
+        if !self.is_running_enqueue() {
+            return false;
+        }
+        let Some(message_store) = self.default_message_store.as_ref() else {
+            return false;
+        };
+        let Some(consume_queue) =
+            message_store.get_consume_queue(&CheetahString::from_static_str(TIMER_TOPIC), queue_id)
+        else {
+            return false;
+        };
+        let mut offset = self.curr_queue_offset.load(Ordering::Acquire);
+        let min_offset = consume_queue.get_min_offset_in_queue();
+        if offset < min_offset {
+            warn!(
+                "Timer queue offset {} is smaller than the min offset {}, skip to it",
+                offset, min_offset
+            );
+            offset = min_offset;
+            self.curr_queue_offset.store(offset, Ordering::Release);
+        }
+        let Some(mut iterator) = consume_queue.iterate_from(offset) else {
+            return false;
+        };
+        self.maybe_move_write_time();
+        let mut consumed = false;
+        for cq_unit in iterator.by_ref() {
+            if !self.running.load(Ordering::Acquire) && consumed {
+                break;
+            }
+            match message_store.look_message_by_offset_with_size(cq_unit.pos, cq_unit.size) {
+                Some(msg) => {
+                    self.last_enqueue_but_expired_time
+                        .store(get_current_millis() as i64, Ordering::Release);
+                    self.last_enqueue_but_expired_store_time
+                        .store(msg.store_timestamp(), Ordering::Release);
+                    let delayed_time = msg
+                        .get_property(&CheetahString::from_static_str(TIMER_OUT_MS))
+                        .and_then(|value| value.parse::<i64>().ok());
+                    match delayed_time {
+                        Some(delayed_time)
+                            if self.should_running_dequeue.load(Ordering::Acquire)
+                                && delayed_time
+                                    < self.curr_write_time_ms.load(Ordering::Acquire) =>
+                        {
+                            // Already expired, put it back to the real topic directly.
+                            self.put_message_back(msg, i64::MAX, false).await;
+                        }
+                        Some(delayed_time) => {
+                            while !self.do_enqueue(cq_unit.pos, cq_unit.size, delayed_time, &msg) {
+                                if !self.running.load(Ordering::Acquire) {
+                                    iterator.release();
+                                    return consumed;
+                                }
+                                tokio::time::sleep(IDLE_INTERVAL).await;
+                            }
+                        }
+                        None => {
+                            warn!(
+                                "Timer message without {} at offset {}, skip it",
+                                TIMER_OUT_MS, cq_unit.pos
+                            );
+                        }
+                    }
+                }
+                None => {
+                    warn!(
+                        "Timer message at commit log offset {} size {} not found, skip it",
+                        cq_unit.pos, cq_unit.size
+                    );
+                }
+            }
+            self.enqueue_count.fetch_add(1, Ordering::Relaxed);
+            let next_offset = cq_unit.queue_offset + 1;
+            self.curr_queue_offset.store(next_offset, Ordering::Release);
+            self.commit_queue_offset
+                .store(next_offset, Ordering::Release);
+            consumed = true;
+        }
+        iterator.release();
+        consumed
+    }
+
+    /// Appends a unit for the message to the timer log and links it into the slot of
+    /// `delayed_time`, returns `false` when the timer log could not be written.
+    pub fn do_enqueue(
+        &self,
+        offset_py: i64,
+        size_py: i32,
+        mut delayed_time: i64,
+        msg: &MessageExt,
+    ) -> bool {
+        let curr_write_time_ms = self.curr_write_time_ms.load(Ordering::Acquire);
+        let roll_window_ms = self.timer_roll_window_slots as i64 * self.precision_ms;
+        let mut magic = MAGIC_DEFAULT;
+        if delayed_time - curr_write_time_ms >= roll_window_ms {
+            magic |= MAGIC_ROLL;
+            if delayed_time - curr_write_time_ms - roll_window_ms
+                < (self.timer_roll_window_slots / 3) as i64 * self.precision_ms
+            {
+                // give enough time to next roll
+                delayed_time = curr_write_time_ms
+                    + (self.timer_roll_window_slots / 2) as i64 * self.precision_ms;
+            } else {
+                delayed_time = curr_write_time_ms + roll_window_ms;
+            }
+        }
+        let is_delete = msg
+            .get_property(&CheetahString::from_static_str(TIMER_DELETE_UNIQUE_KEY))
+            .is_some();
+        if is_delete {
+            magic |= MAGIC_DELETE;
+        }
+        let real_topic = msg.get_property(&CheetahString::from_static_str(
+            MessageConst::PROPERTY_REAL_TOPIC,
+        ));
+        let slot = self.timer_wheel.get_slot(delayed_time);
+        let unit = TimerLogUnit {
+            size: UNIT_SIZE,
+            prev_pos: slot.last_pos,
+            magic,
+            curr_write_time: curr_write_time_ms,
+            delayed_time: (delayed_time - curr_write_time_ms) as i32,
+            offset_py,
+            size_py,
+            topic_hash: hash_topic_for_metrics(real_topic.as_ref()),
+        };
+        let data = unit.encode();
+        let ret = self.timer_log.append(data.as_ref(), 0, UNIT_SIZE);
+        if ret == -1 {
+            return false;
+        }
+        // A delete message takes back the count of the message it deletes.
+        self.timer_wheel.put_slot_with_num(
+            delayed_time,
+            if slot.first_pos == -1 {
+                ret
+            } else {
+                slot.first_pos
+            },
+            ret,
+            if is_delete {
+                slot.num - 1
+            } else {
+                slot.num + 1
+            },
+            slot.magic,
+        );
+        self.add_metric(msg, if is_delete { -1 } else { 1 });
+        true
+    }
+
+    /// Puts the messages of the slot at the current read time back, returns `-1` when there is
+    /// nothing to do yet.
+    pub async fn dequeue(&self) -> i32 {
+        if !self.is_running_dequeue() {
+            return -1;
+        }
+        let curr_read_time_ms = self.curr_read_time_ms.load(Ordering::Acquire);
+        if curr_read_time_ms >= self.curr_write_time_ms.load(Ordering::Acquire) {
+            self.maybe_move_write_time();
+            return -1;
+        }
+        let slot = self.timer_wheel.get_slot(curr_read_time_ms);
+        if slot.time_ms == -1 {
+            self.move_read_time();
+            return 0;
+        }
+        let Some(message_store) = self.default_message_store.as_ref() else {
+            return -1;
+        };
+
+        let mut delete_units = Vec::new();
+        let mut normal_units = Vec::new();
+        let mut curr_offset = slot.last_pos;
+        while curr_offset != -1 {
+            let Some(unit) = self.timer_log.get_unit(curr_offset) else {
+                warn!("Timer log unit at {} not found", curr_offset);
+                break;
+            };
+            if need_delete(unit.magic) && !need_roll(unit.magic) {
+                delete_units.push(unit);
+            } else {
+                normal_units.push(unit);
+            }
+            // units are linked backwards, anything else is a corrupted link
+            if unit.prev_pos >= curr_offset || unit.prev_pos < slot.first_pos {
+                break;
+            }
+            curr_offset = unit.prev_pos;
+        }
+        // put the messages back in the order they were enqueued
+        normal_units.reverse();
+
+        let mut delete_keys = HashSet::new();
+        for unit in delete_units {
+            let Some(msg) =
+                message_store.look_message_by_offset_with_size(unit.offset_py, unit.size_py)
+            else {
+                continue;
+            };
+            if let Some(key) =
+                msg.get_property(&CheetahString::from_static_str(TIMER_DELETE_UNIQUE_KEY))
+            {
+                delete_keys.insert(key);
+            }
+        }
+
+        for unit in normal_units {
+            let Some(mut msg) =
+                message_store.look_message_by_offset_with_size(unit.offset_py, unit.size_py)
+            else {
+                warn!(
+                    "Timer message at commit log offset {} size {} not found, skip it",
+                    unit.offset_py, unit.size_py
+                );
+                continue;
+            };
+            let roll = need_roll(unit.magic);
+            if let Some(uniq_key) = MessageClientIDSetter::get_uniq_id(&msg) {
+                let delete_key = build_delete_key(get_real_topic(&msg).as_str(), uniq_key.as_str());
+                if delete_keys.contains(delete_key.as_str()) {
+                    continue;
+                }
+            }
+            if roll {
+                let roll_times = msg
+                    .get_property(&CheetahString::from_static_str(TIMER_ROLL_TIMES))
+                    .and_then(|value| value.parse::<i32>().ok())
+                    .unwrap_or(0);
+                MessageAccessor::put_property(
+                    &mut msg,
+                    CheetahString::from_static_str(TIMER_ROLL_TIMES),
+                    CheetahString::from_string((roll_times + 1).to_string()),
+                );
+            }
+            MessageAccessor::put_property(
+                &mut msg,
+                CheetahString::from_static_str(TIMER_DEQUEUE_MS),
+                CheetahString::from_string(get_current_millis().to_string()),
+            );
+            // a rolled delete message is counted again when it is enqueued again
+            let metric = if need_delete(unit.magic) { 1 } else { -1 };
+            self.add_metric(&msg, metric);
+            self.put_message_back(msg, unit.curr_write_time, roll).await;
+            self.dequeue_count.fetch_add(1, Ordering::Relaxed);
+        }
+        self.move_read_time();
+        1
     }
 
-    pub fn is_reject(&self, _deliver_ms: u64) -> bool {
-        false
+    /// Puts a due message to its target topic, retrying while the store is not available.
+    async fn put_message_back(&self, msg: MessageExt, enqueue_time: i64, roll: bool) {
+        loop {
+            let msg_inner = Self::convert_message(msg.clone(), enqueue_time, roll);
+            let result = self.do_put(msg_inner, roll).await;
+            if result != PUT_NEED_RETRY || !self.running.load(Ordering::Acquire) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    pub async fn do_put(&self, msg_inner: MessageExtBrokerInner, roll: bool) -> i32 {
+        let Some(message_store) = self.default_message_store.as_ref() else {
+            return PUT_NO_RETRY;
+        };
+        if !roll
+            && msg_inner
+                .get_property(&CheetahString::from_static_str(
+                    MessageConst::PROPERTY_TRANSACTION_PREPARED,
+                ))
+                .is_some_and(|value| value == "true")
+        {
+            warn!(
+                "Timer message is a prepared transaction message, topic:{}, skip it",
+                msg_inner.get_topic()
+            );
+            return PUT_NO_RETRY;
+        }
+        let topic = msg_inner.get_topic().clone();
+        let put_message_result = message_store.mut_from_ref().put_message(msg_inner).await;
+        match put_message_result.put_message_status() {
+            PutMessageStatus::PutOk => PUT_OK,
+            PutMessageStatus::ServiceNotAvailable => PUT_NEED_RETRY,
+            PutMessageStatus::MessageIllegal
+            | PutMessageStatus::PropertiesSizeExceeded
+            | PutMessageStatus::WheelTimerNotEnable
+            | PutMessageStatus::WheelTimerMsgIllegal => {
+                warn!(
+                    "Put timer message to {} failed: {}",
+                    topic,
+                    put_message_result.put_message_status()
+                );
+                PUT_NO_RETRY
+            }
+            status => {
+                warn!("Put timer message to {} failed: {}", topic, status);
+                if self.message_store_config.timer_skip_unknown_error {
+                    PUT_NO_RETRY
+                } else {
+                    PUT_NEED_RETRY
+                }
+            }
+        }
+    }
+
+    /// Converts a stored timer message to the message put to `REAL_TOPIC`, or to the timer
+    /// topic again when `roll` is set.
+    pub fn convert_message(
+        msg: MessageExt,
+        enqueue_time: i64,
+        roll: bool,
+    ) -> MessageExtBrokerInner {
+        let mut inner = MessageExtBrokerInner::default();
+        let sys_flag = msg.sys_flag();
+        let born_timestamp = msg.born_timestamp();
+        let born_host = msg.born_host();
+        let store_host = msg.store_host();
+        let reconsume_times = msg.reconsume_times();
+        let topic = msg.get_topic().clone();
+        let queue_id = msg.queue_id();
+        let message = msg.message;
+        if let Some(body) = message.body {
+            inner.set_body(body);
+        }
+        inner.set_flag(message.flag);
+        MessageAccessor::set_properties(&mut inner, message.properties);
+        if enqueue_time != -1 {
+            MessageAccessor::put_property(
+                &mut inner,
+                CheetahString::from_static_str(TIMER_ENQUEUE_MS),
+                CheetahString::from_string(enqueue_time.to_string()),
+            );
+        }
+        let topic_filter_type = message_single::parse_topic_filter_type(sys_flag);
+        inner.tags_code = MessageExtBrokerInner::tags_string2tags_code(
+            &topic_filter_type,
+            inner.get_tags().as_ref().unwrap_or(&CheetahString::empty()),
+        );
+        inner.message_ext_inner.sys_flag = sys_flag;
+        inner.message_ext_inner.born_timestamp = born_timestamp;
+        inner.message_ext_inner.born_host = born_host;
+        inner.message_ext_inner.store_host = store_host;
+        inner.message_ext_inner.reconsume_times = reconsume_times;
+        inner.set_wait_store_msg_ok(false);
+        if roll {
+            inner.set_topic(topic);
+            inner.message_ext_inner.queue_id = queue_id;
+        } else {
+            let real_topic = inner.get_property(&CheetahString::from_static_str(
+                MessageConst::PROPERTY_REAL_TOPIC,
+            ));
+            if let Some(real_topic) = real_topic {
+                inner.set_topic(real_topic);
+            }
+            let real_queue_id = inner
+                .get_property(&CheetahString::from_static_str(
+                    MessageConst::PROPERTY_REAL_QUEUE_ID,
+                ))
+                .and_then(|value| value.parse::<i32>().ok());
+            if let Some(real_queue_id) = real_queue_id {
+                inner.message_ext_inner.queue_id = real_queue_id;
+            }
+            MessageAccessor::clear_property(&mut inner, MessageConst::PROPERTY_REAL_TOPIC);
+            MessageAccessor::clear_property(&mut inner, MessageConst::PROPERTY_REAL_QUEUE_ID);
+        }
+        inner.properties_string =
+            MessageDecoder::message_properties_to_string(inner.get_properties());
+        inner
+    }
+
+    fn add_metric<T: MessageTrait>(&self, msg: &T, value: i64) {
+        let Some(topic) = msg.get_property(&CheetahString::from_static_str(
+            MessageConst::PROPERTY_REAL_TOPIC,
+        )) else {
+            return;
+        };
+        self.timer_metrics.add_and_get(&topic, value);
+    }
+
+    pub fn is_reject(&self, deliver_ms: u64) -> bool {
+        let congest_num = self.timer_wheel.get_num(deliver_ms as i64) as i64;
+        let congest_num_each_slot = self.message_store_config.timer_congest_num_each_slot as i64;
+        if congest_num <= congest_num_each_slot {
+            return false;
+        }
+        if congest_num >= congest_num_each_slot * 2 {
+            return true;
+        }
+        (get_current_nano() % 1000) as f64
+            > 1000.0 * (congest_num - congest_num_each_slot) as f64
+                / (congest_num_each_slot as f64 + 0.1)
     }
 
     pub fn get_dequeue_behind(&self) -> i64 {
@@ -95,15 +794,15 @@ impl TimerMessageStore {
     }
 
     pub fn get_dequeue_behind_millis(&self) -> i64 {
-        (SystemClock::now() as i64)
-            - self
-                .curr_read_time_ms
-                .load(std::sync::atomic::Ordering::Relaxed)
+        (SystemClock::now() as i64) - self.curr_read_time_ms.load(Ordering::Relaxed)
     }
 
     pub fn get_enqueue_behind_millis(&self) -> i64 {
-        if get_current_millis() - self.last_enqueue_but_expired_time < 2000 {
-            ((get_current_millis() - self.last_enqueue_but_expired_store_time) / 1000) as i64
+        let now = get_current_millis() as i64;
+        if now - self.last_enqueue_but_expired_time.load(Ordering::Relaxed) < 2000 {
+            now - self
+                .last_enqueue_but_expired_store_time
+                .load(Ordering::Relaxed)
         } else {
             0
         }
@@ -114,14 +813,10 @@ impl TimerMessageStore {
     }
 
     pub fn get_enqueue_behind_messages(&self) -> i64 {
-        let temp_queue_offset = self
-            .curr_queue_offset
-            .load(std::sync::atomic::Ordering::Relaxed);
-        let consume_queue = self
-            .default_message_store
-            .as_ref()
-            .unwrap()
-            .find_consume_queue(&CheetahString::from_static_str(TIMER_TOPIC), 0);
+        let temp_queue_offset = self.curr_queue_offset.load(Ordering::Relaxed);
+        let consume_queue = self.default_message_store.as_ref().and_then(|store| {
+            store.find_consume_queue(&CheetahString::from_static_str(TIMER_TOPIC), 0)
+        });
         let max_offset_in_queue = match consume_queue {
             Some(queue) => queue.get_max_offset_in_queue(),
             None => 0,
@@ -130,37 +825,40 @@ impl TimerMessageStore {
     }
 
     pub fn get_all_congest_num(&self) -> i64 {
-        0
+        self.timer_wheel
+            .get_all_num(self.curr_read_time_ms.load(Ordering::Relaxed))
     }
 
     pub fn get_enqueue_tps(&self) -> f32 {
-        0.0
+        self.tps_sampler.lock().enqueue_tps
     }
 
     pub fn get_dequeue_tps(&self) -> f32 {
-        0.0
+        self.tps_sampler.lock().dequeue_tps
     }
 
-    pub fn new(default_message_store: Option<ArcMut<LocalFileMessageStore>>) -> Self {
-        Self {
-            curr_read_time_ms: AtomicI64::new(0),
-            curr_queue_offset: AtomicI64::new(0),
-            last_enqueue_but_expired_time: 0,
-            last_enqueue_but_expired_store_time: 0,
-            default_message_store,
-            timer_metrics: TimerMetrics,
-        }
+    pub fn get_timer_wheel(&self) -> &Arc<TimerWheel> {
+        &self.timer_wheel
     }
 
-    pub fn new_empty() -> Self {
-        Self {
-            curr_read_time_ms: AtomicI64::new(0),
-            curr_queue_offset: AtomicI64::new(0),
-            last_enqueue_but_expired_time: 0,
-            last_enqueue_but_expired_store_time: 0,
-            default_message_store: None,
-            timer_metrics: TimerMetrics,
-        }
+    pub fn get_timer_log(&self) -> &Arc<TimerLog> {
+        &self.timer_log
+    }
+
+    pub fn get_timer_checkpoint(&self) -> &Arc<TimerCheckpoint> {
+        &self.timer_checkpoint
+    }
+
+    pub fn get_curr_write_time_ms(&self) -> i64 {
+        self.curr_write_time_ms.load(Ordering::Acquire)
+    }
+
+    pub fn get_commit_queue_offset(&self) -> i64 {
+        self.commit_queue_offset.load(Ordering::Acquire)
+    }
+
+    pub fn is_should_running_dequeue(&self) -> bool {
+        self.should_running_dequeue.load(Ordering::Acquire)
     }
 
     pub fn set_default_message_store(
@@ -170,14 +868,239 @@ impl TimerMessageStore {
         self.default_message_store = default_message_store;
     }
 
-    pub fn shutdown(&mut self) {
-        warn!("TimerMessageStore shutdown unimplemented, do nothing");
+    pub fn sync_last_read_time_ms(&mut self) {
+        let last_read_time_ms =
+            self.clamp_read_time_ms(self.timer_checkpoint.get_last_read_time_ms());
+        self.curr_read_time_ms
+            .store(last_read_time_ms, Ordering::Release);
+        self.commit_read_time_ms
+            .store(last_read_time_ms, Ordering::Release);
     }
 
-    pub fn sync_last_read_time_ms(&mut self) {
-        error!("sync_last_read_time_ms unimplemented");
+    pub fn set_should_running_dequeue(&mut self, should_start: bool) {
+        self.should_running_dequeue
+            .store(should_start, Ordering::Release);
     }
-    pub fn set_should_running_dequeue(&mut self, _should_start: bool) {
-        error!("set_should_running_dequeue unimplemented");
+}
+
+pub fn need_roll(magic: i32) -> bool {
+    magic & MAGIC_ROLL != 0
+}
+
+pub fn need_delete(magic: i32) -> bool {
+    magic & MAGIC_DELETE != 0
+}
+
+/// The key a delete message carries in [`TIMER_DELETE_UNIQUE_KEY`].
+pub fn build_delete_key(real_topic: &str, unique_key: &str) -> String {
+    format!("{}+{}", real_topic, unique_key)
+}
+
+pub fn get_real_topic(msg: &MessageExt) -> CheetahString {
+    msg.get_property(&CheetahString::from_static_str(
+        MessageConst::PROPERTY_REAL_TOPIC,
+    ))
+    .unwrap_or_else(|| msg.get_topic().clone())
+}
+
+/// Same value as `String#hashCode` so the units are compatible with the Java broker.
+fn hash_topic_for_metrics(topic: Option<&CheetahString>) -> i32 {
+    topic.map_or(0, |topic| {
+        topic
+            .encode_utf16()
+            .fold(0i32, |hash, c| hash.wrapping_mul(31).wrapping_add(c as i32))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::flush_disk_type::FlushDiskType;
+    use crate::test_utils::new_message_store;
+
+    const REAL_TOPIC: &str = "TimerTopicTest";
+
+    fn new_timer_message_store() -> (ArcMut<LocalFileMessageStore>, TimerMessageStore, TempDir) {
+        let (message_store, temp_dir) = new_message_store(MessageStoreConfig {
+            mapped_file_size_commit_log: 1024 * 64,
+            mapped_file_size_timer_log: UNIT_SIZE as usize * 64,
+            timer_precision_ms: 100,
+            flush_disk_type: FlushDiskType::AsyncFlush,
+            ..MessageStoreConfig::default()
+        });
+        let message_store_config = message_store.message_store_config();
+        let root_dir = message_store_config.store_path_root_dir.to_string();
+        let timer_checkpoint =
+            TimerCheckpoint::new(PathBuf::from(&root_dir).join("config").join("timercheck"))
+                .unwrap();
+        let timer_metrics = TimerMetrics::new(
+            PathBuf::from(&root_dir)
+                .join("config")
+                .join("timermetrics")
+                .to_string_lossy()
+                .to_string(),
+        );
+        let mut timer_message_store = TimerMessageStore::new(
+            message_store_config,
+            Arc::new(timer_checkpoint),
+            Arc::new(timer_metrics),
+            Some(message_store.clone()),
+        )
+        .unwrap();
+        assert!(timer_message_store.load());
+        (message_store, timer_message_store, temp_dir)
+    }
+
+    async fn put_timer_message(
+        message_store: &ArcMut<LocalFileMessageStore>,
+        deliver_ms: i64,
+        delete_key: Option<String>,
+    ) -> MessageExt {
+        let mut msg = MessageExtBrokerInner::default();
+        msg.set_topic(CheetahString::from_static_str(TIMER_TOPIC));
+        msg.set_body(Bytes::from_static(b"timer message"));
+        MessageClientIDSetter::set_uniq_id(&mut msg);
+        MessageAccessor::put_property(
+            &mut msg,
+            CheetahString::from_static_str(MessageConst::PROPERTY_REAL_TOPIC),
+            CheetahString::from_static_str(REAL_TOPIC),
+        );
+        MessageAccessor::put_property(
+            &mut msg,
+            CheetahString::from_static_str(MessageConst::PROPERTY_REAL_QUEUE_ID),
+            CheetahString::from_static_str("1"),
+        );
+        MessageAccessor::put_property(
+            &mut msg,
+            CheetahString::from_static_str(TIMER_OUT_MS),
+            CheetahString::from_string(deliver_ms.to_string()),
+        );
+        if let Some(delete_key) = delete_key {
+            MessageAccessor::put_property(
+                &mut msg,
+                CheetahString::from_static_str(TIMER_DELETE_UNIQUE_KEY),
+                CheetahString::from_string(delete_key),
+            );
+        }
+        msg.properties_string = MessageDecoder::message_properties_to_string(msg.get_properties());
+        let result = message_store.mut_from_ref().put_message(msg).await;
+        assert_eq!(result.put_message_status(), PutMessageStatus::PutOk);
+        let append_result = result.append_message_result().unwrap();
+        message_store
+            .look_message_by_offset_with_size(append_result.wrote_offset, append_result.wrote_bytes)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn delivers_due_messages_and_skips_deleted_ones() {
+        let (message_store, mut timer_message_store, _temp_dir) = new_timer_message_store();
+        timer_message_store.maybe_move_write_time();
+        let deliver_ms = timer_message_store.get_curr_write_time_ms() + 1000;
+        let real_topic = CheetahString::from_static_str(REAL_TOPIC);
+
+        let kept = put_timer_message(&message_store, deliver_ms, None).await;
+        let deleted = put_timer_message(&message_store, deliver_ms, None).await;
+        let delete_key = build_delete_key(
+            REAL_TOPIC,
+            MessageClientIDSetter::get_uniq_id(&deleted)
+                .unwrap()
+                .as_str(),
+        );
+        let marker = put_timer_message(&message_store, deliver_ms, Some(delete_key)).await;
+        for msg in [&kept, &deleted, &marker] {
+            assert!(timer_message_store.do_enqueue(
+                msg.commit_log_offset(),
+                msg.store_size(),
+                deliver_ms,
+                msg
+            ));
+        }
+        assert_eq!(timer_message_store.get_timer_wheel().get_num(deliver_ms), 1);
+        assert_eq!(
+            timer_message_store
+                .timer_metrics
+                .get_timing_count(&real_topic),
+            1
+        );
+
+        // nothing is due before the delivery time
+        timer_message_store.set_should_running_dequeue(true);
+        timer_message_store
+            .curr_read_time_ms
+            .store(deliver_ms, Ordering::Release);
+        assert_eq!(timer_message_store.dequeue().await, -1);
+
+        timer_message_store
+            .curr_write_time_ms
+            .store(deliver_ms + 1000, Ordering::Release);
+        let max_phy_offset = message_store.get_max_phy_offset();
+        assert_eq!(timer_message_store.dequeue().await, 1);
+        assert_eq!(
+            timer_message_store
+                .curr_read_time_ms
+                .load(Ordering::Acquire),
+            deliver_ms + 100
+        );
+
+        let delivered = message_store
+            .look_message_by_offset(max_phy_offset)
+            .unwrap();
+        assert_eq!(delivered.get_topic().as_str(), REAL_TOPIC);
+        assert_eq!(delivered.queue_id(), 1);
+        assert_eq!(
+            MessageClientIDSetter::get_uniq_id(&delivered),
+            MessageClientIDSetter::get_uniq_id(&kept)
+        );
+        assert!(delivered
+            .get_property(&CheetahString::from_static_str(TIMER_DEQUEUE_MS))
+            .is_some());
+        assert_eq!(
+            message_store.get_max_phy_offset(),
+            max_phy_offset + delivered.store_size() as i64
+        );
+        assert_eq!(
+            timer_message_store
+                .timer_metrics
+                .get_timing_count(&real_topic),
+            0
+        );
+    }
+
+    #[test]
+    fn long_delays_are_rolled() {
+        let (_message_store, timer_message_store, _temp_dir) = new_timer_message_store();
+        timer_message_store.maybe_move_write_time();
+        let curr_write_time_ms = timer_message_store.get_curr_write_time_ms();
+        let roll_window_ms = timer_message_store.timer_roll_window_slots as i64 * 100;
+        let mut msg = MessageExt::default();
+        let deliver_ms = curr_write_time_ms + roll_window_ms * 2;
+        MessageAccessor::put_property(
+            &mut msg,
+            CheetahString::from_static_str(TIMER_OUT_MS),
+            CheetahString::from_string(deliver_ms.to_string()),
+        );
+        assert!(timer_message_store.do_enqueue(0, 100, deliver_ms, &msg));
+        let slot = timer_message_store
+            .get_timer_wheel()
+            .get_slot(curr_write_time_ms + roll_window_ms);
+        assert_eq!(slot.num, 1);
+        let unit = timer_message_store
+            .get_timer_log()
+            .get_unit(slot.last_pos)
+            .unwrap();
+        assert!(need_roll(unit.magic));
+        assert!(!need_delete(unit.magic));
+    }
+
+    #[test]
+    fn hash_topic_matches_java_string_hash_code() {
+        assert_eq!(hash_topic_for_metrics(None), 0);
+        assert_eq!(
+            hash_topic_for_metrics(Some(&CheetahString::from_static_str("TopicTest"))),
+            -1_902_610_879
+        );
     }
 }
@@ -14,27 +14,126 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+
+use std::collections::HashMap;
+
 use cheetah_string::CheetahString;
+use parking_lot::Mutex;
 use rocketmq_common::common::config_manager::ConfigManager;
+use rocketmq_common::utils::serde_json_utils::SerdeJsonUtils;
+use rocketmq_common::TimeUtils::get_current_millis;
+use rocketmq_remoting::protocol::DataVersion;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::info;
+
+/// The number of timer messages waiting for each real topic.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Metric {
+    pub count: i64,
+    pub time_stamp: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimerMetricsSerializeWrapper {
+    timing_count: HashMap<CheetahString, Metric>,
+    data_version: DataVersion,
+}
+
+pub struct TimerMetrics {
+    config_path: String,
+    timing_count: Mutex<HashMap<CheetahString, Metric>>,
+    data_version: Mutex<DataVersion>,
+}
+
+impl TimerMetrics {
+    pub fn new(config_path: impl Into<String>) -> Self {
+        Self {
+            config_path: config_path.into(),
+            timing_count: Mutex::new(HashMap::new()),
+            data_version: Mutex::new(DataVersion::default()),
+        }
+    }
+
+    /// Adds `value` to the count of `topic` and returns the new count.
+    pub fn add_and_get(&self, topic: &CheetahString, value: i64) -> i64 {
+        let mut timing_count = self.timing_count.lock();
+        let metric = timing_count.entry(topic.clone()).or_default();
+        metric.count += value;
+        metric.time_stamp = get_current_millis() as i64;
+        metric.count
+    }
 
-pub struct TimerMetrics;
+    pub fn get_timing_count(&self, key: &CheetahString) -> i64 {
+        self.timing_count
+            .lock()
+            .get(key)
+            .map_or(0, |metric| metric.count)
+    }
+
+    pub fn get_timing_count_all(&self) -> HashMap<CheetahString, Metric> {
+        self.timing_count.lock().clone()
+    }
+
+    pub fn remove_topic(&self, topic: &CheetahString) {
+        self.timing_count.lock().remove(topic);
+    }
+
+    pub fn get_data_version(&self) -> DataVersion {
+        self.data_version.lock().clone()
+    }
+}
 
 impl ConfigManager for TimerMetrics {
     fn config_file_path(&self) -> String {
-        todo!()
+        self.config_path.clone()
     }
 
     fn encode_pretty(&self, pretty_format: bool) -> String {
-        todo!()
+        let wrapper = TimerMetricsSerializeWrapper {
+            timing_count: self.timing_count.lock().clone(),
+            data_version: self.data_version.lock().clone(),
+        };
+        if pretty_format {
+            SerdeJsonUtils::to_json_pretty(&wrapper).expect("encode failed")
+        } else {
+            SerdeJsonUtils::to_json(&wrapper).expect("encode failed")
+        }
     }
 
     fn decode(&self, json_string: &str) {
-        todo!()
+        if json_string.is_empty() {
+            return;
+        }
+        info!("decode TimerMetrics from json string:{}", json_string);
+        let wrapper: TimerMetricsSerializeWrapper =
+            SerdeJsonUtils::from_json_str(json_string).expect("decode failed");
+        *self.timing_count.lock() = wrapper.timing_count;
+        self.data_version
+            .lock()
+            .assign_new_one(&wrapper.data_version);
     }
 }
 
-impl TimerMetrics {
-    pub fn get_timing_count(&self, key: &CheetahString) -> i64 {
-        unimplemented!("get_timing_count is not implemented")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timing_count_round_trips_through_config_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("timermetrics");
+        let timer_metrics = TimerMetrics::new(config_path.to_string_lossy());
+        let topic = CheetahString::from_static_str("TopicTest");
+        assert_eq!(timer_metrics.add_and_get(&topic, 2), 2);
+        assert_eq!(timer_metrics.add_and_get(&topic, -1), 1);
+        timer_metrics.persist();
+
+        let loaded = TimerMetrics::new(config_path.to_string_lossy());
+        assert!(loaded.load());
+        assert_eq!(loaded.get_timing_count(&topic), 1);
+        assert_eq!(loaded.get_timing_count(&CheetahString::from("unknown")), 0);
     }
 }
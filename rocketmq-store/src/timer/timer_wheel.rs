@@ -0,0 +1,217 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+
+use memmap2::MmapMut;
+use parking_lot::Mutex;
+use rocketmq_common::UtilAll::ensure_dir_ok;
+use tracing::info;
+
+use crate::timer::slot::Slot;
+
+/// The timing wheel, a memory mapped ring of [`Slot`]s indexed by delivery time.
+///
+/// Twice the configured slots are kept so that a time and the same time one round later never
+/// share a slot.
+pub struct TimerWheel {
+    file_name: String,
+    slots_total: i32,
+    precision_ms: i64,
+    wheel_length: usize,
+    mmap: Mutex<MmapMut>,
+    _file: File,
+}
+
+impl TimerWheel {
+    pub fn new(file_name: &str, slots_total: i32, precision_ms: i64) -> io::Result<Self> {
+        let path = Path::new(file_name);
+        if let Some(parent) = path.parent() {
+            ensure_dir_ok(parent.to_string_lossy().as_ref());
+        }
+        let wheel_length = slots_total as usize * 2 * Slot::SIZE;
+        let exists = path.exists();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        if exists && file.metadata()?.len() != wheel_length as u64 {
+            info!(
+                "Timer wheel length:{} != expected:{}, ignore it",
+                file.metadata()?.len(),
+                wheel_length
+            );
+        }
+        file.set_len(wheel_length as u64)?;
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Ok(Self {
+            file_name: file_name.to_string(),
+            slots_total,
+            precision_ms,
+            wheel_length,
+            mmap: Mutex::new(mmap),
+            _file: file,
+        })
+    }
+
+    pub fn flush(&self) -> io::Result<()> {
+        self.mmap.lock().flush()
+    }
+
+    pub fn shutdown(&self) -> io::Result<()> {
+        self.flush()
+    }
+
+    /// Returns the slot of `time_ms`, or [`Slot::empty`] when the slot belongs to another round.
+    pub fn get_slot(&self, time_ms: i64) -> Slot {
+        let slot = self.get_raw_slot(time_ms);
+        if slot.time_ms != time_ms / self.precision_ms * self.precision_ms {
+            return Slot::empty();
+        }
+        slot
+    }
+
+    pub fn get_raw_slot(&self, time_ms: i64) -> Slot {
+        let index = self.get_slot_index(time_ms) * Slot::SIZE;
+        let mmap = self.mmap.lock();
+        Self::read_slot(&mmap[index..index + Slot::SIZE], self.precision_ms)
+    }
+
+    pub fn put_slot(&self, time_ms: i64, first_pos: i64, last_pos: i64) {
+        self.put_slot_with_num(time_ms, first_pos, last_pos, 0, 0);
+    }
+
+    pub fn put_slot_with_num(
+        &self,
+        time_ms: i64,
+        first_pos: i64,
+        last_pos: i64,
+        num: i32,
+        magic: i32,
+    ) {
+        let index = self.get_slot_index(time_ms) * Slot::SIZE;
+        let mut mmap = self.mmap.lock();
+        let buffer = &mut mmap[index..index + Slot::SIZE];
+        buffer[0..8].copy_from_slice(&(time_ms / self.precision_ms).to_be_bytes());
+        buffer[8..16].copy_from_slice(&first_pos.to_be_bytes());
+        buffer[16..24].copy_from_slice(&last_pos.to_be_bytes());
+        buffer[24..28].copy_from_slice(&num.to_be_bytes());
+        buffer[28..32].copy_from_slice(&magic.to_be_bytes());
+    }
+
+    /// Returns the smallest timer log position referenced by the wheel from `time_start_ms`
+    /// on that is beyond `max_offset`, or `max_offset` when the wheel is consistent.
+    pub fn check_phy_pos(&self, time_start_ms: i64, max_offset: i64) -> i64 {
+        let mut min_first = max_offset;
+        let first_slot_index = self.get_slot_index(time_start_ms);
+        let total = self.slots_total as usize * 2;
+        let mmap = self.mmap.lock();
+        for i in 0..total {
+            let index = (first_slot_index + i) % total * Slot::SIZE;
+            let slot = Self::read_slot(&mmap[index..index + Slot::SIZE], self.precision_ms);
+            if (time_start_ms + i as i64 * self.precision_ms) / self.precision_ms
+                != slot.time_ms / self.precision_ms
+            {
+                continue;
+            }
+            if slot.first_pos > max_offset || slot.last_pos > max_offset {
+                min_first = min_first.min(slot.first_pos);
+            }
+        }
+        min_first
+    }
+
+    pub fn get_num(&self, time_ms: i64) -> i32 {
+        self.get_slot(time_ms).num
+    }
+
+    /// Sums the messages in every slot from `time_start_ms` on.
+    pub fn get_all_num(&self, time_start_ms: i64) -> i64 {
+        let mut all_num = 0i64;
+        let first_slot_index = self.get_slot_index(time_start_ms);
+        let total = self.slots_total as usize * 2;
+        let mmap = self.mmap.lock();
+        for i in 0..total {
+            let index = (first_slot_index + i) % total * Slot::SIZE;
+            let slot = Self::read_slot(&mmap[index..index + Slot::SIZE], self.precision_ms);
+            if (time_start_ms + i as i64 * self.precision_ms) / self.precision_ms
+                == slot.time_ms / self.precision_ms
+            {
+                all_num += slot.num as i64;
+            }
+        }
+        all_num
+    }
+
+    pub fn get_slot_index(&self, time_ms: i64) -> usize {
+        ((time_ms / self.precision_ms) % (self.slots_total as i64 * 2)) as usize
+    }
+
+    pub fn get_file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn get_wheel_length(&self) -> usize {
+        self.wheel_length
+    }
+
+    fn read_slot(buffer: &[u8], precision_ms: i64) -> Slot {
+        Slot::new_with_num(
+            i64::from_be_bytes(buffer[0..8].try_into().unwrap()) * precision_ms,
+            i64::from_be_bytes(buffer[8..16].try_into().unwrap()),
+            i64::from_be_bytes(buffer[16..24].try_into().unwrap()),
+            i32::from_be_bytes(buffer[24..28].try_into().unwrap()),
+            i32::from_be_bytes(buffer[28..32].try_into().unwrap()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn put_and_get_slot() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_name = temp_dir.path().join("timerwheel");
+        let timer_wheel = TimerWheel::new(file_name.to_str().unwrap(), 10, 1000).unwrap();
+
+        let time_ms = 1_700_000_000_000;
+        assert_eq!(timer_wheel.get_slot(time_ms), Slot::empty());
+
+        timer_wheel.put_slot_with_num(time_ms, 52, 104, 2, 0);
+        let slot = timer_wheel.get_slot(time_ms + 999);
+        assert_eq!(slot, Slot::new_with_num(time_ms, 52, 104, 2, 0));
+        assert_eq!(timer_wheel.get_num(time_ms), 2);
+        assert_eq!(timer_wheel.get_all_num(time_ms - 5000), 2);
+
+        // one round later maps to the same index but must not be mistaken for this slot
+        assert_eq!(timer_wheel.get_slot(time_ms + 20 * 1000), Slot::empty());
+        assert_eq!(timer_wheel.check_phy_pos(time_ms, 104), 104);
+        assert_eq!(timer_wheel.check_phy_pos(time_ms, 60), 52);
+
+        timer_wheel.flush().unwrap();
+        drop(timer_wheel);
+        let timer_wheel = TimerWheel::new(file_name.to_str().unwrap(), 10, 1000).unwrap();
+        assert_eq!(timer_wheel.get_slot(time_ms).last_pos, 104);
+    }
+}
@@ -15,6 +15,6 @@
  * limitations under the License.
  */
 
-mod api;
+pub mod api;
 pub mod common;
 pub mod default_mq_admin_ext;
@@ -15,5 +15,5 @@
  * limitations under the License.
  */
 mod broker_operator_result;
-mod message_track;
-mod track_type;
+pub mod message_track;
+pub mod track_type;
@@ -25,30 +25,53 @@ use cheetah_string::CheetahString;
 use rocketmq_client_rust::admin::default_mq_admin_ext_impl::DefaultMQAdminExtImpl;
 use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
 use rocketmq_client_rust::base::client_config::ClientConfig;
+use rocketmq_client_rust::base::query_result::QueryResult;
 use rocketmq_client_rust::common::admin_tool_result::AdminToolResult;
+use rocketmq_client_rust::trace::trace_view::TraceView;
 use rocketmq_common::common::base::plain_access_config::PlainAccessConfig;
 use rocketmq_common::common::config::TopicConfig;
 use rocketmq_common::common::message::message_enum::MessageRequestMode;
+use rocketmq_common::common::message::message_ext::MessageExt;
 use rocketmq_common::common::message::message_queue::MessageQueue;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
 use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_error::RocketmqError;
+use rocketmq_remoting::code::response_code::ResponseCode;
 use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::consume_stats_list::ConsumeStatsList;
 use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::acl_info::AclInfo;
+use rocketmq_remoting::protocol::body::broker_body::broker_member_group::BrokerMemberGroup;
 use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::broker_replicas_info::BrokerReplicasInfo;
+use rocketmq_remoting::protocol::body::check_rocksdb_cqwrite_progress_response_body::CheckRocksdbCqWriteProgressResponseBody;
 use rocketmq_remoting::protocol::body::consume_message_directly_result::ConsumeMessageDirectlyResult;
 use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
 use rocketmq_remoting::protocol::body::consumer_running_info::ConsumerRunningInfo;
+use rocketmq_remoting::protocol::body::epoch_entry_cache::EpochEntryCache;
 use rocketmq_remoting::protocol::body::group_list::GroupList;
 use rocketmq_remoting::protocol::body::kv_table::KVTable;
 use rocketmq_remoting::protocol::body::producer_connection::ProducerConnection;
+use rocketmq_remoting::protocol::body::subscription_group_wrapper::SubscriptionGroupWrapper;
 use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
 use rocketmq_remoting::protocol::body::topic_info_wrapper::TopicConfigSerializeWrapper;
+use rocketmq_remoting::protocol::body::user_info::UserInfo;
+use rocketmq_remoting::protocol::header::elect_master_response_header::ElectMasterResponseHeader;
+use rocketmq_remoting::protocol::header::get_meta_data_response_header::GetMetaDataResponseHeader;
+use rocketmq_remoting::protocol::heartbeat::consume_type::ConsumeType;
+use rocketmq_remoting::protocol::heartbeat::message_model::MessageModel;
 use rocketmq_remoting::protocol::heartbeat::subscription_data::SubscriptionData;
 use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
 use rocketmq_remoting::protocol::static_topic::topic_queue_mapping_detail::TopicQueueMappingDetail;
+use rocketmq_remoting::protocol::subscription::broker_stats_data::BrokerStatsData;
 use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
 use rocketmq_remoting::runtime::RPCHook;
 use rocketmq_rust::ArcMut;
 
+use crate::admin::api::message_track::MessageTrack;
+use crate::admin::api::track_type::TrackType;
+
 const ADMIN_EXT_GROUP: &str = "admin_ext_group";
 
 pub struct DefaultMQAdminExt {
@@ -193,6 +216,153 @@ impl DefaultMQAdminExt {
     }
 }
 
+impl DefaultMQAdminExt {
+    /// Works out, for every consumer group subscribed to the topic of `msg`, whether the group has
+    /// consumed the message.
+    pub async fn message_track_detail(
+        &self,
+        msg: &MessageExt,
+    ) -> rocketmq_error::RocketMQResult<Vec<MessageTrack>> {
+        let mut result = Vec::new();
+        let group_list = self
+            .query_topic_consume_by_who(msg.get_topic().clone())
+            .await?;
+        for group in group_list.get_group_list() {
+            let mut message_track = MessageTrack {
+                consumer_group: group.to_string(),
+                track_type: Some(TrackType::Unknown),
+                exception_desc: String::new(),
+            };
+            let consumer_connection = match self
+                .examine_consumer_connection_info(group.clone(), None)
+                .await
+            {
+                Ok(consumer_connection) => consumer_connection,
+                Err(e) => {
+                    fill_track_error(&mut message_track, &e);
+                    result.push(message_track);
+                    continue;
+                }
+            };
+            match consumer_connection.get_consume_type() {
+                ConsumeType::ConsumeActively => message_track.set_track_type(TrackType::Pull),
+                ConsumeType::ConsumePassively | ConsumeType::ConsumePop => {
+                    if consumer_connection.get_message_model() == MessageModel::Broadcasting {
+                        message_track.set_track_type(TrackType::ConsumeBroadcasting);
+                    } else {
+                        match self.consumed(msg, group).await {
+                            Ok(true) => message_track
+                                .set_track_type(consumed_track_type(msg, &consumer_connection)),
+                            Ok(false) => message_track.set_track_type(TrackType::NotConsumedYet),
+                            Err(e) => fill_track_error(&mut message_track, &e),
+                        }
+                    }
+                }
+            }
+            result.push(message_track);
+        }
+        Ok(result)
+    }
+
+    /// Queries the trace topic for the records of message `msg_id`, ordered by time.
+    pub async fn query_trace_by_msg_id(
+        &self,
+        trace_topic: Option<CheetahString>,
+        msg_id: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<Vec<TraceView>> {
+        let trace_topic = trace_topic
+            .unwrap_or_else(|| CheetahString::from_static_str(TopicValidator::RMQ_SYS_TRACE_TOPIC));
+        let query_result = self
+            .query_message_by_key(trace_topic, msg_id.clone(), 64, 0, i64::MAX)
+            .await?;
+        let mut trace_views: Vec<TraceView> = query_result
+            .message_list()
+            .iter()
+            .flat_map(|message| TraceView::decode_from_trace_trans_data(msg_id.as_str(), message))
+            .collect();
+        trace_views.sort_by_key(|trace_view| trace_view.time_stamp);
+        Ok(trace_views)
+    }
+
+    async fn consumed(
+        &self,
+        msg: &MessageExt,
+        group: &CheetahString,
+    ) -> rocketmq_error::RocketMQResult<bool> {
+        let consume_stats = self
+            .examine_consume_stats(group.clone(), None, None, None, None)
+            .await?;
+        let topic_route_data = self
+            .examine_topic_route_info(msg.get_topic().clone())
+            .await?;
+        Ok(topic_route_data
+            .is_some_and(|topic_route_data| is_consumed(msg, &consume_stats, &topic_route_data)))
+    }
+}
+
+/// A message is consumed once the consumer offset of its queue, on the master that stored it,
+/// has moved past the message.
+fn is_consumed(
+    msg: &MessageExt,
+    consume_stats: &ConsumeStats,
+    topic_route_data: &TopicRouteData,
+) -> bool {
+    let store_host = msg.store_host().to_string();
+    consume_stats
+        .offset_table
+        .iter()
+        .filter(|(mq, _)| mq.get_topic() == msg.get_topic() && mq.get_queue_id() == msg.queue_id())
+        .any(|(mq, offset_wrapper)| {
+            topic_route_data
+                .broker_datas
+                .iter()
+                .find(|broker_data| broker_data.broker_name() == mq.get_broker_name())
+                .and_then(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+                .is_some_and(|master_addr| {
+                    master_addr.as_str() == store_host
+                        && offset_wrapper.get_consumer_offset() > msg.queue_offset()
+                })
+        })
+}
+
+/// A consumed message is reported as filtered when the group subscribes to its topic with tags
+/// that do not match the message.
+fn consumed_track_type(msg: &MessageExt, consumer_connection: &ConsumerConnection) -> TrackType {
+    let tags = msg.get_tags();
+    let filtered = consumer_connection
+        .get_subscription_table()
+        .iter()
+        .filter(|entry| entry.key() == msg.get_topic())
+        .any(|entry| {
+            let tags_set = &entry.value().tags_set;
+            !(tags_set.is_empty()
+                || tags_set.contains(SubscriptionData::SUB_ALL)
+                || tags.as_ref().is_some_and(|tags| tags_set.contains(tags)))
+        });
+    if filtered {
+        TrackType::ConsumedButFiltered
+    } else {
+        TrackType::Consumed
+    }
+}
+
+fn fill_track_error(message_track: &mut MessageTrack, error: &RocketmqError) {
+    let response_code = match error {
+        RocketmqError::MQClientBrokerError(e) => Some(e.response_code()),
+        RocketmqError::MQClientErr(e) => Some(e.response_code()),
+        _ => None,
+    };
+    match response_code {
+        Some(code) => {
+            if code == ResponseCode::ConsumerNotOnline as i32 {
+                message_track.set_track_type(TrackType::NotOnline);
+            }
+            message_track.set_exception_desc(format!("CODE:{} DESC:{}", code, error));
+        }
+        None => message_track.set_exception_desc(error.to_string()),
+    }
+}
+
 impl Default for DefaultMQAdminExt {
     fn default() -> Self {
         Self::new()
@@ -233,14 +403,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         properties: HashMap<CheetahString, CheetahString>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_broker_config(broker_addr, properties)
+            .await
     }
 
     async fn get_broker_config(
         &self,
         broker_addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<HashMap<CheetahString, CheetahString>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_broker_config(broker_addr)
+            .await
     }
 
     async fn create_and_update_topic_config(
@@ -248,7 +422,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: TopicConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_topic_config(addr, config)
+            .await
     }
 
     async fn create_and_update_topic_config_list(
@@ -264,7 +440,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: PlainAccessConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_plain_access_config(addr, config)
+            .await
     }
 
     async fn delete_plain_access_config(
@@ -272,7 +450,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         access_key: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_plain_access_config(addr, access_key)
+            .await
     }
 
     async fn update_global_white_addr_config(
@@ -281,14 +461,18 @@ impl MQAdminExt for DefaultMQAdminExt {
         global_white_addrs: CheetahString,
         acl_file_full_path: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_global_white_addr_config(addr, global_white_addrs, acl_file_full_path)
+            .await
     }
 
     async fn examine_broker_cluster_acl_version_info(
         &self,
         addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_broker_cluster_acl_version_info(addr)
+            .await
     }
 
     async fn create_and_update_subscription_group_config(
@@ -296,7 +480,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         config: SubscriptionGroupConfig,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_subscription_group_config(addr, config)
+            .await
     }
 
     async fn create_and_update_subscription_group_config_list(
@@ -304,7 +490,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         configs: Vec<SubscriptionGroupConfig>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_and_update_subscription_group_config_list(broker_addr, configs)
+            .await
     }
 
     async fn examine_subscription_group_config(
@@ -312,7 +500,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addr: CheetahString,
         group: CheetahString,
     ) -> rocketmq_error::RocketMQResult<SubscriptionGroupConfig> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_subscription_group_config(addr, group)
+            .await
     }
 
     async fn examine_topic_stats(
@@ -320,7 +510,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<TopicStatsTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_topic_stats(topic, broker_addr)
+            .await
     }
 
     async fn examine_topic_stats_concurrent(
@@ -331,7 +523,7 @@ impl MQAdminExt for DefaultMQAdminExt {
     }
 
     async fn fetch_all_topic_list(&self) -> rocketmq_error::RocketMQResult<TopicList> {
-        todo!()
+        self.default_mqadmin_ext_impl.fetch_all_topic_list().await
     }
 
     async fn fetch_topics_by_cluster(
@@ -345,7 +537,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         &self,
         broker_addr: CheetahString,
     ) -> rocketmq_error::RocketMQResult<KVTable> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .fetch_broker_runtime_stats(broker_addr)
+            .await
     }
 
     async fn examine_consume_stats(
@@ -356,11 +550,31 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: Option<CheetahString>,
         timeout_millis: Option<u64>,
     ) -> rocketmq_error::RocketMQResult<ConsumeStats> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_consume_stats(
+                consumer_group,
+                topic,
+                cluster_name,
+                broker_addr,
+                timeout_millis,
+            )
+            .await
+    }
+
+    async fn check_rocksdb_cq_write_progress(
+        &self,
+        broker_addr: CheetahString,
+        topic: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<CheckRocksdbCqWriteProgressResponseBody> {
+        self.default_mqadmin_ext_impl
+            .check_rocksdb_cq_write_progress(broker_addr, topic)
+            .await
     }
 
     async fn examine_broker_cluster_info(&self) -> rocketmq_error::RocketMQResult<ClusterInfo> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_broker_cluster_info()
+            .await
     }
 
     async fn examine_topic_route_info(
@@ -377,7 +591,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         consumer_group: CheetahString,
         broker_addr: Option<CheetahString>,
     ) -> rocketmq_error::RocketMQResult<ConsumerConnection> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_consumer_connection_info(consumer_group, broker_addr)
+            .await
     }
 
     async fn examine_producer_connection_info(
@@ -385,11 +601,15 @@ impl MQAdminExt for DefaultMQAdminExt {
         producer_group: CheetahString,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<ProducerConnection> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .examine_producer_connection_info(producer_group, topic)
+            .await
     }
 
     async fn get_name_server_address_list(&self) -> Vec<CheetahString> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_name_server_address_list()
+            .await
     }
 
     async fn wipe_write_perm_of_broker(
@@ -437,7 +657,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         topic_name: CheetahString,
         cluster_name: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic(topic_name, cluster_name)
+            .await
     }
 
     async fn delete_topic_in_broker(
@@ -445,7 +667,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         addrs: HashSet<CheetahString>,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic_in_broker(addrs, topic)
+            .await
     }
 
     async fn delete_topic_in_name_server(
@@ -454,7 +678,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         cluster_name: Option<CheetahString>,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_topic_in_name_server(addrs, cluster_name, topic)
+            .await
     }
 
     async fn delete_subscription_group(
@@ -463,7 +689,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         group_name: CheetahString,
         remove_offset: Option<bool>,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_subscription_group(addr, group_name, remove_offset)
+            .await
     }
 
     async fn create_and_update_kv_config(
@@ -491,7 +719,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         timestamp: u64,
         is_force: bool,
     ) -> rocketmq_error::RocketMQResult<HashMap<MessageQueue, u64>> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .reset_offset_by_timestamp(cluster_name, topic, group, timestamp, is_force)
+            .await
     }
 
     async fn reset_offset_new(
@@ -525,7 +755,32 @@ impl MQAdminExt for DefaultMQAdminExt {
         &self,
         topic: CheetahString,
     ) -> rocketmq_error::RocketMQResult<GroupList> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .query_topic_consume_by_who(topic)
+            .await
+    }
+
+    async fn view_message(
+        &self,
+        topic: CheetahString,
+        msg_id: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<MessageExt> {
+        self.default_mqadmin_ext_impl
+            .view_message(topic, msg_id)
+            .await
+    }
+
+    async fn query_message_by_key(
+        &self,
+        topic: CheetahString,
+        key: CheetahString,
+        max_num: i32,
+        begin: i64,
+        end: i64,
+    ) -> rocketmq_error::RocketMQResult<QueryResult> {
+        self.default_mqadmin_ext_impl
+            .query_message_by_key(topic, key, max_num, begin, end)
+            .await
     }
 
     async fn query_topics_by_consumer(
@@ -581,7 +836,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         jstack: bool,
         metrics: Option<bool>,
     ) -> rocketmq_error::RocketMQResult<ConsumerRunningInfo> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .get_consumer_running_info(consumer_group, client_id, jstack, metrics)
+            .await
     }
 
     async fn consume_message_directly(
@@ -615,6 +872,17 @@ impl MQAdminExt for DefaultMQAdminExt {
         todo!()
     }
 
+    async fn view_broker_stats_data(
+        &self,
+        broker_addr: CheetahString,
+        stats_name: CheetahString,
+        stats_key: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<BrokerStatsData> {
+        self.default_mqadmin_ext_impl
+            .view_broker_stats_data(broker_addr, stats_name, stats_key)
+            .await
+    }
+
     async fn get_cluster_list(
         &self,
         topic: String,
@@ -622,6 +890,17 @@ impl MQAdminExt for DefaultMQAdminExt {
         todo!()
     }
 
+    async fn fetch_consume_stats_in_broker(
+        &self,
+        broker_addr: CheetahString,
+        is_order: bool,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<ConsumeStatsList> {
+        self.default_mqadmin_ext_impl
+            .fetch_consume_stats_in_broker(broker_addr, is_order, timeout_millis)
+            .await
+    }
+
     async fn get_topic_cluster_list(
         &self,
         topic: String,
@@ -629,6 +908,16 @@ impl MQAdminExt for DefaultMQAdminExt {
         todo!()
     }
 
+    async fn get_all_subscription_group(
+        &self,
+        broker_addr: CheetahString,
+        timeout_millis: u64,
+    ) -> rocketmq_error::RocketMQResult<SubscriptionGroupWrapper> {
+        self.default_mqadmin_ext_impl
+            .get_all_subscription_group(broker_addr, timeout_millis)
+            .await
+    }
+
     async fn get_all_topic_config(
         &self,
         broker_addr: CheetahString,
@@ -731,6 +1020,46 @@ impl MQAdminExt for DefaultMQAdminExt {
         todo!()
     }
 
+    async fn get_in_sync_state_data(
+        &self,
+        controller_address: CheetahString,
+        brokers: Vec<CheetahString>,
+    ) -> rocketmq_error::RocketMQResult<BrokerReplicasInfo> {
+        self.default_mqadmin_ext_impl
+            .get_in_sync_state_data(controller_address, brokers)
+            .await
+    }
+
+    async fn get_broker_epoch_cache(
+        &self,
+        broker_addr: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<EpochEntryCache> {
+        self.default_mqadmin_ext_impl
+            .get_broker_epoch_cache(broker_addr)
+            .await
+    }
+
+    async fn get_controller_meta_data(
+        &self,
+        controller_addr: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<GetMetaDataResponseHeader> {
+        self.default_mqadmin_ext_impl
+            .get_controller_meta_data(controller_addr)
+            .await
+    }
+
+    async fn elect_master(
+        &self,
+        controller_addr: CheetahString,
+        cluster_name: CheetahString,
+        broker_name: CheetahString,
+        broker_id: Option<u64>,
+    ) -> rocketmq_error::RocketMQResult<(ElectMasterResponseHeader, BrokerMemberGroup)> {
+        self.default_mqadmin_ext_impl
+            .elect_master(controller_addr, cluster_name, broker_name, broker_id)
+            .await
+    }
+
     async fn reset_master_flush_offset(
         &self,
         broker_addr: CheetahString,
@@ -804,7 +1133,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         password: CheetahString,
         user_type: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_user(broker_addr, username, password, user_type)
+            .await
     }
 
     async fn update_user(
@@ -815,7 +1146,9 @@ impl MQAdminExt for DefaultMQAdminExt {
         user_type: CheetahString,
         user_status: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_user(broker_addr, username, password, user_type, user_status)
+            .await
     }
 
     async fn delete_user(
@@ -823,7 +1156,29 @@ impl MQAdminExt for DefaultMQAdminExt {
         broker_addr: CheetahString,
         username: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_user(broker_addr, username)
+            .await
+    }
+
+    async fn get_user(
+        &self,
+        broker_addr: CheetahString,
+        username: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<UserInfo> {
+        self.default_mqadmin_ext_impl
+            .get_user(broker_addr, username)
+            .await
+    }
+
+    async fn list_users(
+        &self,
+        broker_addr: CheetahString,
+        filter: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<Vec<UserInfo>> {
+        self.default_mqadmin_ext_impl
+            .list_users(broker_addr, filter)
+            .await
     }
 
     async fn create_acl(
@@ -835,7 +1190,16 @@ impl MQAdminExt for DefaultMQAdminExt {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .create_acl(
+                broker_addr,
+                subject,
+                resources,
+                actions,
+                source_ips,
+                decision,
+            )
+            .await
     }
 
     async fn update_acl(
@@ -847,7 +1211,16 @@ impl MQAdminExt for DefaultMQAdminExt {
         source_ips: Vec<CheetahString>,
         decision: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .update_acl(
+                broker_addr,
+                subject,
+                resources,
+                actions,
+                source_ips,
+                decision,
+            )
+            .await
     }
 
     async fn delete_acl(
@@ -856,6 +1229,113 @@ impl MQAdminExt for DefaultMQAdminExt {
         subject: CheetahString,
         resource: CheetahString,
     ) -> rocketmq_error::RocketMQResult<()> {
-        todo!()
+        self.default_mqadmin_ext_impl
+            .delete_acl(broker_addr, subject, resource)
+            .await
+    }
+
+    async fn get_acl(
+        &self,
+        broker_addr: CheetahString,
+        subject: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<AclInfo> {
+        self.default_mqadmin_ext_impl
+            .get_acl(broker_addr, subject)
+            .await
+    }
+
+    async fn list_acl(
+        &self,
+        broker_addr: CheetahString,
+        subject_filter: CheetahString,
+        resource_filter: CheetahString,
+    ) -> rocketmq_error::RocketMQResult<Vec<AclInfo>> {
+        self.default_mqadmin_ext_impl
+            .list_acl(broker_addr, subject_filter, resource_filter)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::SocketAddr;
+
+    use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+
+    use super::*;
+
+    fn message(topic: &str, tags: &str) -> MessageExt {
+        let mut msg = MessageExt::default();
+        msg.set_topic(CheetahString::from(topic));
+        msg.message.set_tags(CheetahString::from(tags));
+        msg.set_queue_id(1);
+        msg.set_queue_offset(10);
+        msg.set_store_host("127.0.0.1:10911".parse::<SocketAddr>().unwrap());
+        msg
+    }
+
+    fn route() -> TopicRouteData {
+        TopicRouteData {
+            broker_datas: vec![BrokerData::new(
+                CheetahString::from("DefaultCluster"),
+                CheetahString::from("broker-a"),
+                HashMap::from([(mix_all::MASTER_ID, CheetahString::from("127.0.0.1:10911"))]),
+                None,
+            )],
+            ..TopicRouteData::default()
+        }
+    }
+
+    fn consume_stats(consumer_offset: i64) -> ConsumeStats {
+        let mut offset_wrapper = OffsetWrapper::new();
+        offset_wrapper.set_consumer_offset(consumer_offset);
+        let mut consume_stats = ConsumeStats::new();
+        consume_stats.offset_table.insert(
+            MessageQueue::from_parts("TopicTest", "broker-a", 1),
+            offset_wrapper,
+        );
+        consume_stats
+    }
+
+    #[test]
+    fn message_is_consumed_once_offset_moves_past_it() {
+        let msg = message("TopicTest", "TagA");
+        assert!(is_consumed(&msg, &consume_stats(11), &route()));
+        assert!(!is_consumed(&msg, &consume_stats(10), &route()));
+        assert!(!is_consumed(
+            &message("OtherTopic", "TagA"),
+            &consume_stats(11),
+            &route()
+        ));
+    }
+
+    #[test]
+    fn consumed_message_with_unmatched_tags_is_filtered() {
+        let subscribe = |tags: &[&str]| {
+            let consumer_connection = ConsumerConnection::new();
+            consumer_connection.get_subscription_table().insert(
+                CheetahString::from("TopicTest"),
+                SubscriptionData {
+                    topic: CheetahString::from("TopicTest"),
+                    tags_set: tags.iter().map(|tag| CheetahString::from(*tag)).collect(),
+                    ..SubscriptionData::default()
+                },
+            );
+            consumer_connection
+        };
+        let msg = message("TopicTest", "TagA");
+        assert_eq!(
+            consumed_track_type(&msg, &subscribe(&["TagA", "TagB"])),
+            TrackType::Consumed
+        );
+        assert_eq!(
+            consumed_track_type(&msg, &subscribe(&["*"])),
+            TrackType::Consumed
+        );
+        assert_eq!(
+            consumed_track_type(&msg, &subscribe(&["TagB"])),
+            TrackType::ConsumedButFiltered
+        );
     }
 }
@@ -21,4 +21,14 @@ use strum::Display;
 #[derive(Debug, Clone, PartialEq, Serialize, Display, Deserialize)]
 pub enum Action {
     Quit,
+    Refresh,
+    NextView,
+    PreviousView,
+    SwitchView(usize),
+    SelectNext,
+    SelectPrevious,
+    FocusSearch,
+    LoadDetail,
+    IncreaseRefreshInterval,
+    DecreaseRefreshInterval,
 }
@@ -0,0 +1,250 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Background data loading for the dashboard.
+//!
+//! Every request is executed on its own task so a slow broker never blocks the render
+//! loop; results are delivered back to the app through an unbounded channel.
+
+use std::sync::Arc;
+
+use cheetah_string::CheetahString;
+use rocketmq_client_rust::admin::mq_admin_ext_async::MQAdminExt;
+use rocketmq_error::RocketMQResult;
+use rocketmq_tools::admin::default_mq_admin_ext::DefaultMQAdminExt;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::dashboard::snapshot;
+use crate::dashboard::snapshot::BrokerRow;
+use crate::dashboard::snapshot::ConnectionDetail;
+use crate::dashboard::snapshot::ConsumerGroupDetail;
+use crate::dashboard::snapshot::ConsumerGroupRow;
+use crate::dashboard::snapshot::MessageRow;
+use crate::dashboard::snapshot::TopicDetail;
+use crate::dashboard::snapshot::TopicRow;
+
+/// Maximum number of messages returned by a key lookup.
+const MAX_QUERY_MESSAGES: i32 = 32;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FetchRequest {
+    Cluster,
+    Topics,
+    TopicDetail(String),
+    ConsumerGroups,
+    ConsumerGroupDetail(String),
+    Connections(String),
+    Messages { topic: String, id_or_key: String },
+}
+
+impl FetchRequest {
+    /// Parses the message lookup syntax `<topic> <msgId|key>`.
+    pub(crate) fn message_lookup(input: &str) -> Option<FetchRequest> {
+        let mut parts = input.split_whitespace();
+        let topic = parts.next()?;
+        let id_or_key = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(FetchRequest::Messages {
+            topic: topic.to_string(),
+            id_or_key: id_or_key.to_string(),
+        })
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            FetchRequest::Cluster => "cluster info".to_string(),
+            FetchRequest::Topics => "topic list".to_string(),
+            FetchRequest::TopicDetail(topic) => format!("topic {}", topic),
+            FetchRequest::ConsumerGroups => "consumer groups".to_string(),
+            FetchRequest::ConsumerGroupDetail(group) => format!("consume stats of {}", group),
+            FetchRequest::Connections(group) => format!("connections of {}", group),
+            FetchRequest::Messages { topic, id_or_key } => {
+                format!("messages {} in {}", id_or_key, topic)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum FetchResult {
+    Cluster(Vec<BrokerRow>),
+    Topics(Vec<TopicRow>),
+    TopicDetail(TopicDetail),
+    ConsumerGroups(Vec<ConsumerGroupRow>),
+    ConsumerGroupDetail(ConsumerGroupDetail),
+    Connections(ConnectionDetail),
+    Messages(Vec<MessageRow>),
+    Failed { request: String, error: String },
+}
+
+pub(crate) struct DashboardAdminClient {
+    admin: Arc<DefaultMQAdminExt>,
+    sender: UnboundedSender<FetchResult>,
+}
+
+impl DashboardAdminClient {
+    /// Starts an admin client against `namesrv_addr`.
+    pub(crate) async fn connect(
+        namesrv_addr: &str,
+        sender: UnboundedSender<FetchResult>,
+    ) -> RocketMQResult<Self> {
+        let mut admin = DefaultMQAdminExt::new();
+        admin.client_config_mut().namesrv_addr = Some(CheetahString::from(namesrv_addr));
+        MQAdminExt::start(&mut admin).await?;
+        Ok(DashboardAdminClient {
+            admin: Arc::new(admin),
+            sender,
+        })
+    }
+
+    /// Runs `request` in the background and sends its result to the dashboard.
+    pub(crate) fn submit(&self, request: FetchRequest) {
+        let admin = self.admin.clone();
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            let description = request.describe();
+            let result = match fetch(admin.as_ref(), request).await {
+                Ok(result) => result,
+                Err(error) => FetchResult::Failed {
+                    request: description,
+                    error: error.to_string(),
+                },
+            };
+            let _ = sender.send(result);
+        });
+    }
+
+    pub(crate) async fn shutdown(self) {
+        if let Ok(mut admin) = Arc::try_unwrap(self.admin) {
+            MQAdminExt::shutdown(&mut admin).await;
+        }
+    }
+}
+
+async fn fetch(admin: &DefaultMQAdminExt, request: FetchRequest) -> RocketMQResult<FetchResult> {
+    match request {
+        FetchRequest::Cluster => {
+            let cluster_info = admin.examine_broker_cluster_info().await?;
+            let mut rows = snapshot::broker_rows(&cluster_info);
+            for row in rows.iter_mut() {
+                // A broker that fails to answer keeps its placeholder columns instead of
+                // failing the whole view.
+                if let Ok(kv_table) = admin
+                    .fetch_broker_runtime_stats(CheetahString::from(row.addr.as_str()))
+                    .await
+                {
+                    row.apply_runtime_stats(&kv_table.table);
+                }
+            }
+            Ok(FetchResult::Cluster(rows))
+        }
+        FetchRequest::Topics => {
+            let topic_list = admin.fetch_all_topic_list().await?;
+            Ok(FetchResult::Topics(snapshot::topic_rows(&topic_list)))
+        }
+        FetchRequest::TopicDetail(topic) => {
+            let route = admin
+                .examine_topic_route_info(CheetahString::from(topic.as_str()))
+                .await?;
+            let stats = admin
+                .examine_topic_stats(CheetahString::from(topic.as_str()), None)
+                .await
+                .ok();
+            Ok(FetchResult::TopicDetail(TopicDetail::new(
+                topic,
+                route.as_ref(),
+                stats.as_ref(),
+            )))
+        }
+        FetchRequest::ConsumerGroups => {
+            let cluster_info = admin.examine_broker_cluster_info().await?;
+            let master_addrs: Vec<CheetahString> = cluster_info
+                .broker_addr_table
+                .as_ref()
+                .map(|table| {
+                    table
+                        .values()
+                        .filter_map(|broker_data| broker_data.select_broker_addr())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let mut wrappers = Vec::with_capacity(master_addrs.len());
+            for addr in master_addrs {
+                wrappers.push(admin.get_all_subscription_group(addr, 3000).await?);
+            }
+            let rows = snapshot::consumer_group_rows(
+                wrappers
+                    .iter()
+                    .flat_map(|wrapper| wrapper.subscription_group_table().values()),
+            );
+            Ok(FetchResult::ConsumerGroups(rows))
+        }
+        FetchRequest::ConsumerGroupDetail(group) => {
+            let stats = admin
+                .examine_consume_stats(CheetahString::from(group.as_str()), None, None, None, None)
+                .await?;
+            Ok(FetchResult::ConsumerGroupDetail(ConsumerGroupDetail::new(
+                group, &stats,
+            )))
+        }
+        FetchRequest::Connections(group) => {
+            let connection = admin
+                .examine_consumer_connection_info(CheetahString::from(group.as_str()), None)
+                .await?;
+            Ok(FetchResult::Connections(ConnectionDetail::new(
+                group,
+                &connection,
+            )))
+        }
+        FetchRequest::Messages { topic, id_or_key } => {
+            let topic = CheetahString::from(topic);
+            let id_or_key = CheetahString::from(id_or_key);
+            if let Ok(msg) = admin.view_message(topic.clone(), id_or_key.clone()).await {
+                return Ok(FetchResult::Messages(vec![MessageRow::from(&msg)]));
+            }
+            let query_result = admin
+                .query_message_by_key(topic, id_or_key, MAX_QUERY_MESSAGES, 0, i64::MAX)
+                .await?;
+            Ok(FetchResult::Messages(
+                query_result
+                    .message_list()
+                    .iter()
+                    .map(MessageRow::from)
+                    .collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_lookup_requires_topic_and_key() {
+        assert_eq!(
+            FetchRequest::message_lookup(" TopicTest  order-1 "),
+            Some(FetchRequest::Messages {
+                topic: "TopicTest".to_string(),
+                id_or_key: "order-1".to_string(),
+            })
+        );
+        assert_eq!(FetchRequest::message_lookup("TopicTest"), None);
+        assert_eq!(FetchRequest::message_lookup("a b c"), None);
+    }
+}
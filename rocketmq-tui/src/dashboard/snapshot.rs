@@ -0,0 +1,701 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+//! Plain row models rendered by the dashboard tables.
+//!
+//! Everything in here is a pure conversion from the admin protocol bodies so the
+//! rendering code never has to reach into remoting types directly.
+
+use std::collections::HashMap;
+
+use cheetah_string::CheetahString;
+use rocketmq_common::common::constant::PermName;
+use rocketmq_common::common::message::message_ext::MessageExt;
+use rocketmq_common::common::message::MessageTrait;
+use rocketmq_common::common::mix_all;
+use rocketmq_common::common::topic::TopicValidator;
+use rocketmq_common::UtilAll::time_millis_to_human_string2;
+use rocketmq_remoting::protocol::admin::consume_stats::ConsumeStats;
+use rocketmq_remoting::protocol::admin::topic_stats_table::TopicStatsTable;
+use rocketmq_remoting::protocol::body::broker_body::cluster_info::ClusterInfo;
+use rocketmq_remoting::protocol::body::consumer_connection::ConsumerConnection;
+use rocketmq_remoting::protocol::body::topic::topic_list::TopicList;
+use rocketmq_remoting::protocol::route::topic_route_data::TopicRouteData;
+use rocketmq_remoting::protocol::subscription::subscription_group_config::SubscriptionGroupConfig;
+
+/// A row that can be rendered into a table and matched against the search filter.
+pub(crate) trait TableRow {
+    const HEADER: &'static [&'static str];
+
+    fn cells(&self) -> Vec<String>;
+
+    fn matches(&self, filter: &str) -> bool {
+        if filter.is_empty() {
+            return true;
+        }
+        let filter = filter.to_lowercase();
+        self.cells()
+            .iter()
+            .any(|cell| cell.to_lowercase().contains(filter.as_str()))
+    }
+}
+
+/// Returns the rows matching `filter`, case-insensitively, in their original order.
+pub(crate) fn filter_rows<'a, R: TableRow>(rows: &'a [R], filter: &str) -> Vec<&'a R> {
+    let filter = filter.trim();
+    rows.iter().filter(|row| row.matches(filter)).collect()
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    if timestamp <= 0 {
+        "-".to_string()
+    } else {
+        time_millis_to_human_string2(timestamp)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct BrokerRow {
+    pub(crate) cluster: String,
+    pub(crate) broker_name: String,
+    pub(crate) broker_id: u64,
+    pub(crate) addr: String,
+    pub(crate) version: String,
+    pub(crate) put_tps: String,
+    pub(crate) get_tps: String,
+    pub(crate) put_today: String,
+    pub(crate) get_today: String,
+    pub(crate) disk_ratio: String,
+}
+
+impl BrokerRow {
+    /// Fills the runtime columns from the `KVTable` returned by `GetBrokerRuntimeInfo`.
+    pub(crate) fn apply_runtime_stats(&mut self, table: &HashMap<CheetahString, CheetahString>) {
+        let value = |key: &str| {
+            table
+                .get(key)
+                .map(|value| value.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        };
+        self.version = value("brokerVersionDesc");
+        self.put_tps = value("putTps");
+        self.get_tps = value("getTransferredTps");
+        self.put_today = value("msgPutTotalTodayNow");
+        self.get_today = value("msgGetTotalTodayNow");
+        self.disk_ratio = value("commitLogDiskRatio");
+    }
+}
+
+impl TableRow for BrokerRow {
+    const HEADER: &'static [&'static str] = &[
+        "Cluster",
+        "Broker",
+        "BID",
+        "Address",
+        "Version",
+        "Put TPS",
+        "Get TPS",
+        "Put Today",
+        "Get Today",
+        "Disk Ratio",
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.cluster.clone(),
+            self.broker_name.clone(),
+            self.broker_id.to_string(),
+            self.addr.clone(),
+            self.version.clone(),
+            self.put_tps.clone(),
+            self.get_tps.clone(),
+            self.put_today.clone(),
+            self.get_today.clone(),
+            self.disk_ratio.clone(),
+        ]
+    }
+}
+
+/// Flattens the cluster info into one row per broker instance, sorted by cluster, broker
+/// name and broker id.
+pub(crate) fn broker_rows(cluster_info: &ClusterInfo) -> Vec<BrokerRow> {
+    let mut rows = Vec::new();
+    if let Some(broker_addr_table) = cluster_info.broker_addr_table.as_ref() {
+        for broker_data in broker_addr_table.values() {
+            for (broker_id, addr) in broker_data.broker_addrs() {
+                rows.push(BrokerRow {
+                    cluster: broker_data.cluster().to_string(),
+                    broker_name: broker_data.broker_name().to_string(),
+                    broker_id: *broker_id,
+                    addr: addr.to_string(),
+                    version: "-".to_string(),
+                    put_tps: "-".to_string(),
+                    get_tps: "-".to_string(),
+                    put_today: "-".to_string(),
+                    get_today: "-".to_string(),
+                    disk_ratio: "-".to_string(),
+                });
+            }
+        }
+    }
+    rows.sort_by(|a, b| {
+        (a.cluster.as_str(), a.broker_name.as_str(), a.broker_id).cmp(&(
+            b.cluster.as_str(),
+            b.broker_name.as_str(),
+            b.broker_id,
+        ))
+    });
+    rows
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TopicRow {
+    pub(crate) topic: String,
+    pub(crate) category: &'static str,
+}
+
+impl TableRow for TopicRow {
+    const HEADER: &'static [&'static str] = &["Topic", "Category"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![self.topic.clone(), self.category.to_string()]
+    }
+}
+
+fn topic_category(topic: &str) -> &'static str {
+    if topic.starts_with(mix_all::RETRY_GROUP_TOPIC_PREFIX) {
+        "RETRY"
+    } else if topic.starts_with(mix_all::DLQ_GROUP_TOPIC_PREFIX) {
+        "DLQ"
+    } else if TopicValidator::is_system_topic(topic) {
+        "SYSTEM"
+    } else {
+        "NORMAL"
+    }
+}
+
+pub(crate) fn topic_rows(topic_list: &TopicList) -> Vec<TopicRow> {
+    let mut rows: Vec<TopicRow> = topic_list
+        .topic_list
+        .iter()
+        .map(|topic| TopicRow {
+            topic: topic.to_string(),
+            category: topic_category(topic),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.topic.cmp(&b.topic));
+    rows.dedup();
+    rows
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TopicRouteRow {
+    pub(crate) broker_name: String,
+    pub(crate) master_addr: String,
+    pub(crate) read_queue_nums: u32,
+    pub(crate) write_queue_nums: u32,
+    pub(crate) perm: String,
+}
+
+impl TableRow for TopicRouteRow {
+    const HEADER: &'static [&'static str] = &["Broker", "Master", "Read Q", "Write Q", "Perm"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.broker_name.clone(),
+            self.master_addr.clone(),
+            self.read_queue_nums.to_string(),
+            self.write_queue_nums.to_string(),
+            self.perm.clone(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TopicQueueRow {
+    pub(crate) broker_name: String,
+    pub(crate) queue_id: i32,
+    pub(crate) min_offset: i64,
+    pub(crate) max_offset: i64,
+    pub(crate) last_update: String,
+}
+
+impl TableRow for TopicQueueRow {
+    const HEADER: &'static [&'static str] = &["Broker", "Queue", "Min", "Max", "Last Update"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.broker_name.clone(),
+            self.queue_id.to_string(),
+            self.min_offset.to_string(),
+            self.max_offset.to_string(),
+            self.last_update.clone(),
+        ]
+    }
+}
+
+/// Route and offset details of the selected topic.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TopicDetail {
+    pub(crate) topic: String,
+    pub(crate) routes: Vec<TopicRouteRow>,
+    pub(crate) queues: Vec<TopicQueueRow>,
+}
+
+impl TopicDetail {
+    pub(crate) fn new(
+        topic: impl Into<String>,
+        route: Option<&TopicRouteData>,
+        stats: Option<&TopicStatsTable>,
+    ) -> Self {
+        let mut routes = Vec::new();
+        if let Some(route) = route {
+            for queue_data in &route.queue_datas {
+                let master_addr = route
+                    .broker_datas
+                    .iter()
+                    .find(|broker_data| broker_data.broker_name() == queue_data.broker_name())
+                    .and_then(|broker_data| broker_data.broker_addrs().get(&mix_all::MASTER_ID))
+                    .map(|addr| addr.to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                routes.push(TopicRouteRow {
+                    broker_name: queue_data.broker_name().to_string(),
+                    master_addr,
+                    read_queue_nums: queue_data.read_queue_nums(),
+                    write_queue_nums: queue_data.write_queue_nums(),
+                    perm: PermName::perm2string(queue_data.perm()),
+                });
+            }
+            routes.sort_by(|a, b| a.broker_name.cmp(&b.broker_name));
+        }
+
+        let mut queues = Vec::new();
+        if let Some(stats) = stats {
+            for (mq, offset) in stats.get_offset_table() {
+                queues.push(TopicQueueRow {
+                    broker_name: mq.get_broker_name().to_string(),
+                    queue_id: mq.get_queue_id(),
+                    min_offset: offset.get_min_offset(),
+                    max_offset: offset.get_max_offset(),
+                    last_update: format_timestamp(offset.get_last_update_timestamp()),
+                });
+            }
+            queues.sort_by(|a, b| {
+                (a.broker_name.as_str(), a.queue_id).cmp(&(b.broker_name.as_str(), b.queue_id))
+            });
+        }
+
+        TopicDetail {
+            topic: topic.into(),
+            routes,
+            queues,
+        }
+    }
+
+    /// Number of messages currently retained for the topic across all queues.
+    pub(crate) fn total_messages(&self) -> i64 {
+        self.queues
+            .iter()
+            .map(|queue| (queue.max_offset - queue.min_offset).max(0))
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ConsumerGroupRow {
+    pub(crate) group: String,
+    pub(crate) consume_enable: bool,
+    pub(crate) broadcast: bool,
+    pub(crate) orderly: bool,
+    pub(crate) retry_max_times: i32,
+}
+
+impl TableRow for ConsumerGroupRow {
+    const HEADER: &'static [&'static str] =
+        &["Group", "Enabled", "Broadcast", "Orderly", "Max Retry"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.group.clone(),
+            self.consume_enable.to_string(),
+            self.broadcast.to_string(),
+            self.orderly.to_string(),
+            self.retry_max_times.to_string(),
+        ]
+    }
+}
+
+/// Builds one row per subscription group. The same group is usually registered on every
+/// broker, so duplicates are collapsed by name.
+pub(crate) fn consumer_group_rows<'a>(
+    configs: impl IntoIterator<Item = &'a SubscriptionGroupConfig>,
+) -> Vec<ConsumerGroupRow> {
+    let mut rows: Vec<ConsumerGroupRow> = configs
+        .into_iter()
+        .filter(|config| !mix_all::is_sys_consumer_group(config.group_name()))
+        .map(|config| ConsumerGroupRow {
+            group: config.group_name().to_string(),
+            consume_enable: config.consume_enable(),
+            broadcast: config.consume_broadcast_enable(),
+            orderly: config.consume_message_orderly(),
+            retry_max_times: config.retry_max_times(),
+        })
+        .collect();
+    rows.sort_by(|a, b| a.group.cmp(&b.group));
+    rows.dedup_by(|a, b| a.group == b.group);
+    rows
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QueueLagRow {
+    pub(crate) topic: String,
+    pub(crate) broker_name: String,
+    pub(crate) queue_id: i32,
+    pub(crate) broker_offset: i64,
+    pub(crate) consumer_offset: i64,
+    pub(crate) lag: i64,
+    pub(crate) last_timestamp: String,
+}
+
+impl TableRow for QueueLagRow {
+    const HEADER: &'static [&'static str] = &[
+        "Topic",
+        "Broker",
+        "Queue",
+        "Broker Offset",
+        "Consumer Offset",
+        "Lag",
+        "Last Consumed",
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.topic.clone(),
+            self.broker_name.clone(),
+            self.queue_id.to_string(),
+            self.broker_offset.to_string(),
+            self.consumer_offset.to_string(),
+            self.lag.to_string(),
+            self.last_timestamp.clone(),
+        ]
+    }
+}
+
+/// Consume progress of the selected consumer group.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConsumerGroupDetail {
+    pub(crate) group: String,
+    pub(crate) consume_tps: f64,
+    pub(crate) total_lag: i64,
+    pub(crate) queues: Vec<QueueLagRow>,
+}
+
+impl ConsumerGroupDetail {
+    pub(crate) fn new(group: impl Into<String>, stats: &ConsumeStats) -> Self {
+        let mut queues: Vec<QueueLagRow> = stats
+            .get_offset_table()
+            .into_iter()
+            .map(|(mq, offset)| QueueLagRow {
+                topic: mq.get_topic().to_string(),
+                broker_name: mq.get_broker_name().to_string(),
+                queue_id: mq.get_queue_id(),
+                broker_offset: offset.get_broker_offset(),
+                consumer_offset: offset.get_consumer_offset(),
+                lag: (offset.get_broker_offset() - offset.get_consumer_offset()).max(0),
+                last_timestamp: format_timestamp(offset.get_last_timestamp()),
+            })
+            .collect();
+        queues.sort_by(|a, b| {
+            (a.topic.as_str(), a.broker_name.as_str(), a.queue_id).cmp(&(
+                b.topic.as_str(),
+                b.broker_name.as_str(),
+                b.queue_id,
+            ))
+        });
+        ConsumerGroupDetail {
+            group: group.into(),
+            consume_tps: stats.get_consume_tps(),
+            total_lag: queues.iter().map(|queue| queue.lag).sum(),
+            queues,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ConnectionRow {
+    pub(crate) client_id: String,
+    pub(crate) client_addr: String,
+    pub(crate) language: String,
+    pub(crate) version: i32,
+}
+
+impl TableRow for ConnectionRow {
+    const HEADER: &'static [&'static str] = &["Client Id", "Address", "Language", "Version"];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.client_id.clone(),
+            self.client_addr.clone(),
+            self.language.clone(),
+            self.version.to_string(),
+        ]
+    }
+}
+
+/// Online clients and subscriptions of the selected consumer group.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionDetail {
+    pub(crate) group: String,
+    pub(crate) consume_type: String,
+    pub(crate) message_model: String,
+    pub(crate) subscriptions: Vec<String>,
+    pub(crate) connections: Vec<ConnectionRow>,
+}
+
+impl ConnectionDetail {
+    pub(crate) fn new(group: impl Into<String>, connection: &ConsumerConnection) -> Self {
+        let mut connections: Vec<ConnectionRow> = connection
+            .get_connection_set()
+            .into_iter()
+            .map(|conn| ConnectionRow {
+                client_id: conn.get_client_id().to_string(),
+                client_addr: conn.get_client_addr().to_string(),
+                language: conn.get_language().to_string(),
+                version: conn.get_version(),
+            })
+            .collect();
+        connections.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+        let mut subscriptions: Vec<String> = connection
+            .get_subscription_table()
+            .iter()
+            .map(|entry| format!("{} [{}]", entry.key(), entry.value().sub_string))
+            .collect();
+        subscriptions.sort();
+        ConnectionDetail {
+            group: group.into(),
+            consume_type: connection.get_consume_type().to_string(),
+            message_model: connection.get_message_model().to_string(),
+            subscriptions,
+            connections,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MessageRow {
+    pub(crate) msg_id: String,
+    pub(crate) topic: String,
+    pub(crate) tags: String,
+    pub(crate) keys: String,
+    pub(crate) broker_name: String,
+    pub(crate) queue_id: i32,
+    pub(crate) queue_offset: i64,
+    pub(crate) store_time: String,
+    pub(crate) body_size: usize,
+}
+
+impl From<&MessageExt> for MessageRow {
+    fn from(msg: &MessageExt) -> Self {
+        MessageRow {
+            msg_id: msg.msg_id().to_string(),
+            topic: msg.topic().to_string(),
+            tags: msg
+                .get_tags()
+                .map(|tags| tags.to_string())
+                .unwrap_or_default(),
+            keys: msg
+                .get_keys()
+                .map(|keys| keys.to_string())
+                .unwrap_or_default(),
+            broker_name: msg.broker_name().to_string(),
+            queue_id: msg.queue_id(),
+            queue_offset: msg.queue_offset(),
+            store_time: format_timestamp(msg.store_timestamp()),
+            body_size: msg.body().map(|body| body.len()).unwrap_or_default(),
+        }
+    }
+}
+
+impl TableRow for MessageRow {
+    const HEADER: &'static [&'static str] = &[
+        "Message Id",
+        "Topic",
+        "Tags",
+        "Keys",
+        "Broker",
+        "Queue",
+        "Offset",
+        "Store Time",
+        "Body",
+    ];
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.msg_id.clone(),
+            self.topic.clone(),
+            self.tags.clone(),
+            self.keys.clone(),
+            self.broker_name.clone(),
+            self.queue_id.to_string(),
+            self.queue_offset.to_string(),
+            self.store_time.clone(),
+            format!("{}B", self.body_size),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rocketmq_common::common::message::message_queue::MessageQueue;
+    use rocketmq_remoting::protocol::admin::offset_wrapper::OffsetWrapper;
+    use rocketmq_remoting::protocol::admin::topic_offset::TopicOffset;
+    use rocketmq_remoting::protocol::route::route_data_view::BrokerData;
+    use rocketmq_remoting::protocol::route::route_data_view::QueueData;
+
+    use super::*;
+
+    #[test]
+    fn broker_rows_are_flattened_and_sorted() {
+        let mut addrs = HashMap::new();
+        addrs.insert(1u64, CheetahString::from_static_str("127.0.0.1:10921"));
+        addrs.insert(0u64, CheetahString::from_static_str("127.0.0.1:10911"));
+        let broker_data = BrokerData::new(
+            CheetahString::from_static_str("DefaultCluster"),
+            CheetahString::from_static_str("broker-a"),
+            addrs,
+            None,
+        );
+        let mut broker_addr_table = HashMap::new();
+        broker_addr_table.insert(CheetahString::from_static_str("broker-a"), broker_data);
+        let cluster_info = ClusterInfo::new(Some(broker_addr_table), None);
+
+        let mut rows = broker_rows(&cluster_info);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].broker_id, 0);
+        assert_eq!(rows[1].addr, "127.0.0.1:10921");
+
+        let mut runtime = HashMap::new();
+        runtime.insert(
+            CheetahString::from_static_str("putTps"),
+            CheetahString::from_static_str("12.5 10.0 8.0"),
+        );
+        rows[0].apply_runtime_stats(&runtime);
+        assert_eq!(rows[0].put_tps, "12.5 10.0 8.0");
+        assert_eq!(rows[0].get_tps, "-");
+    }
+
+    #[test]
+    fn topic_rows_are_categorized() {
+        let topic_list = TopicList {
+            topic_list: vec![
+                CheetahString::from_static_str("TopicTest"),
+                CheetahString::from_static_str("%RETRY%group"),
+                CheetahString::from_static_str("%DLQ%group"),
+                CheetahString::from_static_str("TopicTest"),
+            ],
+            broker_addr: None,
+        };
+        let rows = topic_rows(&topic_list);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].category, "DLQ");
+        assert_eq!(rows[1].category, "RETRY");
+        assert_eq!(rows[2].category, "NORMAL");
+    }
+
+    #[test]
+    fn topic_detail_joins_route_and_stats() {
+        let mut addrs = HashMap::new();
+        addrs.insert(0u64, CheetahString::from_static_str("127.0.0.1:10911"));
+        let mut route = TopicRouteData::new();
+        route.broker_datas.push(BrokerData::new(
+            CheetahString::from_static_str("DefaultCluster"),
+            CheetahString::from_static_str("broker-a"),
+            addrs,
+            None,
+        ));
+        route.queue_datas.push(QueueData::new(
+            CheetahString::from_static_str("broker-a"),
+            4,
+            4,
+            6,
+            0,
+        ));
+
+        let mut offset_table = HashMap::new();
+        for queue_id in 0..2 {
+            let mut offset = TopicOffset::new();
+            offset.set_min_offset(10);
+            offset.set_max_offset(110);
+            offset_table.insert(
+                MessageQueue::from_parts("TopicTest", "broker-a", queue_id),
+                offset,
+            );
+        }
+        let mut stats = TopicStatsTable::new();
+        stats.set_offset_table(offset_table);
+
+        let detail = TopicDetail::new("TopicTest", Some(&route), Some(&stats));
+        assert_eq!(detail.routes.len(), 1);
+        assert_eq!(detail.routes[0].master_addr, "127.0.0.1:10911");
+        assert_eq!(detail.routes[0].perm, "RW-");
+        assert_eq!(detail.queues[0].queue_id, 0);
+        assert_eq!(detail.total_messages(), 200);
+    }
+
+    #[test]
+    fn consumer_group_detail_computes_lag() {
+        let mut offset_table = HashMap::new();
+        let mut behind = OffsetWrapper::new();
+        behind.set_broker_offset(100);
+        behind.set_consumer_offset(40);
+        offset_table.insert(MessageQueue::from_parts("TopicTest", "broker-a", 1), behind);
+        let mut ahead = OffsetWrapper::new();
+        ahead.set_broker_offset(10);
+        ahead.set_consumer_offset(12);
+        offset_table.insert(MessageQueue::from_parts("TopicTest", "broker-a", 0), ahead);
+        let mut stats = ConsumeStats::new();
+        stats.set_offset_table(offset_table);
+
+        let detail = ConsumerGroupDetail::new("group", &stats);
+        assert_eq!(detail.queues[0].queue_id, 0);
+        assert_eq!(detail.queues[0].lag, 0);
+        assert_eq!(detail.queues[1].lag, 60);
+        assert_eq!(detail.total_lag, 60);
+    }
+
+    #[test]
+    fn filter_is_case_insensitive() {
+        let rows = vec![
+            TopicRow {
+                topic: "OrderTopic".to_string(),
+                category: "NORMAL",
+            },
+            TopicRow {
+                topic: "%RETRY%order_group".to_string(),
+                category: "RETRY",
+            },
+            TopicRow {
+                topic: "PayTopic".to_string(),
+                category: "NORMAL",
+            },
+        ];
+        assert_eq!(filter_rows(&rows, "ORDER").len(), 2);
+        assert_eq!(filter_rows(&rows, "retry").len(), 1);
+        assert_eq!(filter_rows(&rows, "  ").len(), 3);
+    }
+}
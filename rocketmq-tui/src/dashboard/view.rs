@@ -0,0 +1,78 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one or more
+ * contributor license agreements.  See the NOTICE file distributed with
+ * this work for additional information regarding copyright ownership.
+ * The ASF licenses this file to You under the Apache License, Version 2.0
+ * (the "License"); you may not use this file except in compliance with
+ * the License.  You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+use strum::Display;
+use strum::EnumIter;
+use strum::IntoEnumIterator;
+
+/// The pages of the dashboard, in tab order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Display, EnumIter)]
+pub(crate) enum View {
+    #[default]
+    #[strum(to_string = "Cluster")]
+    Cluster,
+    #[strum(to_string = "Topics")]
+    Topics,
+    #[strum(to_string = "Consumer Groups")]
+    ConsumerGroups,
+    #[strum(to_string = "Connections")]
+    Connections,
+    #[strum(to_string = "Messages")]
+    Messages,
+}
+
+impl View {
+    pub fn index(&self) -> usize {
+        View::iter()
+            .position(|view| view == *self)
+            .unwrap_or_default()
+    }
+
+    pub fn from_index(index: usize) -> Option<View> {
+        View::iter().nth(index)
+    }
+
+    pub fn next(&self) -> View {
+        View::from_index((self.index() + 1) % View::iter().len()).unwrap_or_default()
+    }
+
+    pub fn previous(&self) -> View {
+        let len = View::iter().len();
+        View::from_index((self.index() + len - 1) % len).unwrap_or_default()
+    }
+
+    pub fn titles() -> Vec<String> {
+        View::iter()
+            .enumerate()
+            .map(|(index, view)| format!("{} {}", index + 1, view))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_cycle_in_tab_order() {
+        assert_eq!(View::Cluster.next(), View::Topics);
+        assert_eq!(View::Messages.next(), View::Cluster);
+        assert_eq!(View::Cluster.previous(), View::Messages);
+        assert_eq!(View::from_index(2), Some(View::ConsumerGroups));
+        assert_eq!(View::from_index(5), None);
+        assert_eq!(View::titles()[0], "1 Cluster");
+    }
+}
@@ -19,17 +19,45 @@
 #![allow(unused_variables)]
 
 mod action;
+mod dashboard;
 mod rocketmq_tui_app;
 mod ui;
 
+use std::time::Duration;
+
+use clap::Parser;
 use rocketmq_rust::rocketmq;
 
 use crate::rocketmq_tui_app::RocketmqTuiApp;
 
+#[derive(Parser, Debug)]
+#[command(about = "RocketMQ real-time dashboard(Rust)")]
+struct Args {
+    /// Name server address, e.g. 127.0.0.1:9876
+    #[arg(
+        short,
+        long,
+        value_name = "ADDR",
+        env = "NAMESRV_ADDR",
+        default_value = RocketmqTuiApp::DEFAULT_NAMESRV_ADDR
+    )]
+    namesrv_addr: String,
+
+    /// Dashboard refresh interval in seconds
+    #[arg(short, long, value_name = "SECONDS", default_value = "5")]
+    refresh_interval: u64,
+}
+
 #[rocketmq::main]
 async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
     let terminal = ratatui::try_init()?;
-    let result = RocketmqTuiApp::default().run(terminal).await;
+    let result = RocketmqTuiApp::new(
+        args.namesrv_addr,
+        Duration::from_secs(args.refresh_interval),
+    )
+    .run(terminal)
+    .await;
     ratatui::try_restore()?;
     result
 }
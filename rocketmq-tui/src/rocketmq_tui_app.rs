@@ -15,6 +15,7 @@
  * limitations under the License.
  */
 use std::time::Duration;
+use std::time::Instant;
 
 use ratatui::crossterm::event::Event;
 use ratatui::crossterm::event::EventStream;
@@ -23,24 +24,98 @@ use ratatui::crossterm::event::KeyEventKind;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
 use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::widgets::TableState;
+use ratatui::widgets::Tabs;
+use ratatui::widgets::Wrap;
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
+use tokio::sync::mpsc;
 use tokio_stream::StreamExt;
 
+use crate::action::Action;
+use crate::dashboard::admin_client::DashboardAdminClient;
+use crate::dashboard::admin_client::FetchRequest;
+use crate::dashboard::admin_client::FetchResult;
+use crate::dashboard::snapshot::filter_rows;
+use crate::dashboard::snapshot::BrokerRow;
+use crate::dashboard::snapshot::ConnectionDetail;
+use crate::dashboard::snapshot::ConsumerGroupDetail;
+use crate::dashboard::snapshot::ConsumerGroupRow;
+use crate::dashboard::snapshot::MessageRow;
+use crate::dashboard::snapshot::TableRow;
+use crate::dashboard::snapshot::TopicDetail;
+use crate::dashboard::snapshot::TopicRow;
+use crate::dashboard::view::View;
 use crate::ui::search_input_widget::SearchInputWidget;
 
-#[derive(Default)]
 pub struct RocketmqTuiApp {
     should_quit: bool,
     search_input: SearchInputWidget,
+
+    namesrv_addr: String,
+    refresh_interval: Duration,
+    last_refresh: Option<Instant>,
+    status: String,
+    error: Option<String>,
+
+    view: View,
+    table_states: [TableState; 5],
+    brokers: Vec<BrokerRow>,
+    topics: Vec<TopicRow>,
+    topic_detail: Option<TopicDetail>,
+    consumer_groups: Vec<ConsumerGroupRow>,
+    consumer_group_detail: Option<ConsumerGroupDetail>,
+    connection_detail: Option<ConnectionDetail>,
+    messages: Vec<MessageRow>,
+
+    admin: Option<DashboardAdminClient>,
+}
+
+impl Default for RocketmqTuiApp {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_NAMESRV_ADDR.to_string(),
+            Self::DEFAULT_REFRESH_INTERVAL,
+        )
+    }
 }
 
 impl RocketmqTuiApp {
-    pub fn new() -> Self {
+    pub const DEFAULT_NAMESRV_ADDR: &'static str = "127.0.0.1:9876";
+    pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+    const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+    const MAX_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(namesrv_addr: String, refresh_interval: Duration) -> Self {
         Self {
             should_quit: false,
             search_input: Default::default(),
+            namesrv_addr,
+            refresh_interval: refresh_interval
+                .clamp(Self::MIN_REFRESH_INTERVAL, Self::MAX_REFRESH_INTERVAL),
+            last_refresh: None,
+            status: String::new(),
+            error: None,
+            view: View::default(),
+            table_states: Default::default(),
+            brokers: Vec::new(),
+            topics: Vec::new(),
+            topic_detail: None,
+            consumer_groups: Vec::new(),
+            consumer_group_detail: None,
+            connection_detail: None,
+            messages: Vec::new(),
+            admin: None,
         }
     }
 
@@ -57,100 +132,580 @@ impl RocketmqTuiApp {
     const FRAMES_PER_SECOND: f32 = 60.0;
 
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> anyhow::Result<()> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        match DashboardAdminClient::connect(&self.namesrv_addr, sender).await {
+            Ok(admin) => self.admin = Some(admin),
+            Err(error) => {
+                self.error = Some(format!(
+                    "Connect to name server {} failed: {}",
+                    self.namesrv_addr, error
+                ))
+            }
+        }
+
         let period = Duration::from_secs_f32(1.0 / Self::FRAMES_PER_SECOND);
         let mut interval = tokio::time::interval(period);
+        let mut refresh_period = self.refresh_interval;
+        let mut refresh = tokio::time::interval(refresh_period);
+        refresh.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         let mut events = EventStream::new();
         while !self.should_quit {
             tokio::select! {
                 _ = interval.tick() => { terminal.draw(|frame| self.draw(frame))?; },
+                _ = refresh.tick() => self.refresh(),
+                Some(result) = receiver.recv() => self.apply_fetch_result(result),
                 Some(Ok(event)) = events.next() => self.handle_event(&event),
             }
+            if refresh_period != self.refresh_interval {
+                refresh_period = self.refresh_interval;
+                refresh = tokio::time::interval_at(
+                    tokio::time::Instant::now() + refresh_period,
+                    refresh_period,
+                );
+                refresh.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            }
+        }
+        if let Some(admin) = self.admin.take() {
+            admin.shutdown().await;
         }
         Ok(())
     }
 
     fn handle_event(&mut self, event: &Event) {
         if let Event::Key(key) = event {
-            if key.kind == KeyEventKind::Press {
-                match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-                    _ => {}
+            if key.kind != KeyEventKind::Press {
+                return;
+            }
+            if self.search_input.is_focused() {
+                self.handle_search_key(key.code);
+            } else if let Some(action) = Self::key_action(key.code) {
+                self.dispatch(action);
+            }
+        }
+    }
+
+    fn handle_search_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.search_input.set_focus(false),
+            KeyCode::Enter => {
+                self.search_input.set_focus(false);
+                if self.view == View::Messages {
+                    self.dispatch(Action::LoadDetail);
                 }
             }
+            code => {
+                self.search_input.handle_key_event(code);
+                if self.view != View::Messages {
+                    // The filter changed, so the previous selection may no longer exist.
+                    self.table_states[self.view.index()].select(None);
+                    self.clamp_selection(self.view);
+                }
+            }
+        }
+    }
+
+    fn key_action(code: KeyCode) -> Option<Action> {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            KeyCode::Char('r') => Some(Action::Refresh),
+            KeyCode::Tab | KeyCode::Right => Some(Action::NextView),
+            KeyCode::BackTab | KeyCode::Left => Some(Action::PreviousView),
+            KeyCode::Char(c @ '1'..='9') => Some(Action::SwitchView(c as usize - '1' as usize)),
+            KeyCode::Char('j') | KeyCode::Down => Some(Action::SelectNext),
+            KeyCode::Char('k') | KeyCode::Up => Some(Action::SelectPrevious),
+            KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Char('/') => {
+                Some(Action::FocusSearch)
+            }
+            KeyCode::Enter => Some(Action::LoadDetail),
+            KeyCode::Char('+') | KeyCode::Char('=') => Some(Action::IncreaseRefreshInterval),
+            KeyCode::Char('-') => Some(Action::DecreaseRefreshInterval),
+            _ => None,
+        }
+    }
+
+    fn dispatch(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit(),
+            Action::Refresh => self.refresh(),
+            Action::NextView => self.switch_view(self.view.next()),
+            Action::PreviousView => self.switch_view(self.view.previous()),
+            Action::SwitchView(index) => {
+                if let Some(view) = View::from_index(index) {
+                    self.switch_view(view);
+                }
+            }
+            Action::SelectNext => self.move_selection(1),
+            Action::SelectPrevious => self.move_selection(-1),
+            Action::FocusSearch => self.search_input.set_focus(true),
+            Action::LoadDetail => self.load_detail(),
+            Action::IncreaseRefreshInterval => {
+                self.refresh_interval = (self.refresh_interval + Duration::from_secs(1))
+                    .min(Self::MAX_REFRESH_INTERVAL);
+                self.status = format!("Refresh interval {}s", self.refresh_interval.as_secs());
+            }
+            Action::DecreaseRefreshInterval => {
+                self.refresh_interval = self
+                    .refresh_interval
+                    .saturating_sub(Duration::from_secs(1))
+                    .max(Self::MIN_REFRESH_INTERVAL);
+                self.status = format!("Refresh interval {}s", self.refresh_interval.as_secs());
+            }
+        }
+    }
+
+    fn switch_view(&mut self, view: View) {
+        if self.view == view {
+            return;
+        }
+        self.view = view;
+        self.search_input.set_input(String::new());
+        self.clamp_selection(view);
+        self.refresh();
+    }
+
+    fn submit(&mut self, request: FetchRequest) {
+        if let Some(admin) = self.admin.as_ref() {
+            admin.submit(request);
+        }
+    }
+
+    /// Reloads the data backing the current view, including the detail pane if one is open.
+    fn refresh(&mut self) {
+        if self.admin.is_none() {
+            return;
+        }
+        match self.view {
+            View::Cluster => self.submit(FetchRequest::Cluster),
+            View::Topics => {
+                self.submit(FetchRequest::Topics);
+                if let Some(topic) = self
+                    .topic_detail
+                    .as_ref()
+                    .map(|detail| detail.topic.clone())
+                {
+                    self.submit(FetchRequest::TopicDetail(topic));
+                }
+            }
+            View::ConsumerGroups => {
+                self.submit(FetchRequest::ConsumerGroups);
+                if let Some(group) = self
+                    .consumer_group_detail
+                    .as_ref()
+                    .map(|detail| detail.group.clone())
+                {
+                    self.submit(FetchRequest::ConsumerGroupDetail(group));
+                }
+            }
+            View::Connections => {
+                self.submit(FetchRequest::ConsumerGroups);
+                if let Some(group) = self
+                    .connection_detail
+                    .as_ref()
+                    .map(|detail| detail.group.clone())
+                {
+                    self.submit(FetchRequest::Connections(group));
+                }
+            }
+            // Message lookups are explicit, there is nothing to poll.
+            View::Messages => return,
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    fn load_detail(&mut self) {
+        let filter = self.search_input.get_input().to_string();
+        let selected = self.table_states[self.view.index()].selected();
+        match self.view {
+            View::Cluster => {}
+            View::Topics => {
+                if let Some(row) = selected
+                    .and_then(|index| filter_rows(&self.topics, &filter).get(index).copied())
+                {
+                    let request = FetchRequest::TopicDetail(row.topic.clone());
+                    self.status = format!("Loading topic {}", row.topic);
+                    self.submit(request);
+                }
+            }
+            View::ConsumerGroups | View::Connections => {
+                if let Some(row) = selected.and_then(|index| {
+                    filter_rows(&self.consumer_groups, &filter)
+                        .get(index)
+                        .copied()
+                }) {
+                    let group = row.group.clone();
+                    self.status = format!("Loading consumer group {}", group);
+                    let request = if self.view == View::ConsumerGroups {
+                        FetchRequest::ConsumerGroupDetail(group)
+                    } else {
+                        FetchRequest::Connections(group)
+                    };
+                    self.submit(request);
+                }
+            }
+            View::Messages => match FetchRequest::message_lookup(&filter) {
+                Some(request) => {
+                    self.status = "Querying messages".to_string();
+                    self.submit(request);
+                }
+                None => {
+                    self.error = Some("Message lookup expects \"<topic> <msgId|key>\"".to_string());
+                }
+            },
+        }
+    }
+
+    fn apply_fetch_result(&mut self, result: FetchResult) {
+        self.error = None;
+        match result {
+            FetchResult::Cluster(rows) => {
+                self.brokers = rows;
+                self.clamp_selection(View::Cluster);
+            }
+            FetchResult::Topics(rows) => {
+                self.topics = rows;
+                self.clamp_selection(View::Topics);
+            }
+            FetchResult::TopicDetail(detail) => {
+                self.status = format!("Loaded topic {}", detail.topic);
+                self.topic_detail = Some(detail);
+            }
+            FetchResult::ConsumerGroups(rows) => {
+                self.consumer_groups = rows;
+                self.clamp_selection(View::ConsumerGroups);
+                self.clamp_selection(View::Connections);
+            }
+            FetchResult::ConsumerGroupDetail(detail) => {
+                self.status = format!("Loaded consumer group {}", detail.group);
+                self.consumer_group_detail = Some(detail);
+            }
+            FetchResult::Connections(detail) => {
+                self.status = format!("Loaded connections of {}", detail.group);
+                self.connection_detail = Some(detail);
+            }
+            FetchResult::Messages(rows) => {
+                self.status = format!("Found {} message(s)", rows.len());
+                self.messages = rows;
+                self.clamp_selection(View::Messages);
+            }
+            FetchResult::Failed { request, error } => {
+                self.error = Some(format!("Load {} failed: {}", request, error));
+            }
+        }
+    }
+
+    /// Number of rows the table of `view` currently shows after filtering.
+    fn visible_rows(&self, view: View) -> usize {
+        let filter = if view == self.view {
+            self.search_input.get_input()
+        } else {
+            ""
+        };
+        match view {
+            View::Cluster => filter_rows(&self.brokers, filter).len(),
+            View::Topics => filter_rows(&self.topics, filter).len(),
+            View::ConsumerGroups | View::Connections => {
+                filter_rows(&self.consumer_groups, filter).len()
+            }
+            View::Messages => self.messages.len(),
         }
     }
 
-    fn draw(&self, frame: &mut Frame) {
+    fn clamp_selection(&mut self, view: View) {
+        let len = self.visible_rows(view);
+        let state = &mut self.table_states[view.index()];
+        match state.selected() {
+            _ if len == 0 => state.select(None),
+            Some(index) if index >= len => state.select(Some(len - 1)),
+            None => state.select(Some(0)),
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let len = self.visible_rows(self.view);
+        if len == 0 {
+            return;
+        }
+        let state = &mut self.table_states[self.view.index()];
+        let current = state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).rem_euclid(len as isize) as usize;
+        state.select(Some(next));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(5),
-                    Constraint::Percentage(92),
-                    Constraint::Percentage(3),
-                ]
-                .as_ref(),
-            )
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(3),
+            ])
             .split(frame.area());
 
-        let search = chunks[0];
-        let middle = chunks[1];
-        let progress_bar = chunks[2];
+        let tabs = Tabs::new(View::titles())
+            .select(self.view.index())
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("RocketMQ Dashboard [{}]", self.namesrv_addr)),
+            );
+        frame.render_widget(tabs, chunks[0]);
+        frame.render_widget(&self.search_input, chunks[1]);
 
-        let middle_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-            .split(middle);
+        match self.view {
+            View::Cluster => self.draw_cluster(frame, chunks[2]),
+            View::Topics => self.draw_topics(frame, chunks[2]),
+            View::ConsumerGroups => self.draw_consumer_groups(frame, chunks[2]),
+            View::Connections => self.draw_connections(frame, chunks[2]),
+            View::Messages => self.draw_messages(frame, chunks[2]),
+        }
 
-        let command_tree = middle_chunks[0];
-        let middle_right = middle_chunks[1];
+        self.draw_status(frame, chunks[3]);
+    }
 
-        let middle_right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(
-                [
-                    Constraint::Percentage(50),
-                    Constraint::Percentage(15),
-                    Constraint::Percentage(35),
-                ]
-                .as_ref(),
-            )
-            .split(middle_right);
+    fn draw_cluster(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = filter_rows(&self.brokers, self.search_input.get_input());
+        let table = build_table("Brokers", &rows);
+        frame.render_stateful_widget(table, area, &mut self.table_states[View::Cluster.index()]);
+    }
 
-        let command_detail = middle_right_chunks[0];
-        let command_args = middle_right_chunks[1];
-        let execute_command_result = middle_right_chunks[2];
+    fn draw_topics(&mut self, frame: &mut Frame, area: Rect) {
+        let [list, detail] = split_master_detail(area);
+        let rows = filter_rows(&self.topics, self.search_input.get_input());
+        let table = build_table("Topics [Enter: details]", &rows);
+        frame.render_stateful_widget(table, list, &mut self.table_states[View::Topics.index()]);
 
-        frame.render_widget(&self.search_input, search);
+        let Some(topic_detail) = self.topic_detail.as_ref() else {
+            frame.render_widget(
+                placeholder("Topic", "Select a topic and press Enter"),
+                detail,
+            );
+            return;
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(detail);
+        let routes: Vec<_> = topic_detail.routes.iter().collect();
         frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Tree"),
-            command_tree,
+            build_table(&format!("Route of {}", topic_detail.topic), &routes),
+            chunks[0],
         );
+        let queues: Vec<_> = topic_detail.queues.iter().collect();
         frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Detail"),
-            command_detail,
+            build_table(
+                &format!("Queues [{} messages]", topic_detail.total_messages()),
+                &queues,
+            ),
+            chunks[1],
+        );
+    }
+
+    fn draw_consumer_groups(&mut self, frame: &mut Frame, area: Rect) {
+        let [list, detail] = split_master_detail(area);
+        let rows = filter_rows(&self.consumer_groups, self.search_input.get_input());
+        let table = build_table("Consumer Groups [Enter: lag]", &rows);
+        frame.render_stateful_widget(
+            table,
+            list,
+            &mut self.table_states[View::ConsumerGroups.index()],
         );
+
+        let Some(group_detail) = self.consumer_group_detail.as_ref() else {
+            frame.render_widget(
+                placeholder("Consume Progress", "Select a group and press Enter"),
+                detail,
+            );
+            return;
+        };
+        let queues: Vec<_> = group_detail.queues.iter().collect();
         frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Command Args"),
-            command_args,
+            build_table(
+                &format!(
+                    "{} [TPS {:.2}, lag {}]",
+                    group_detail.group, group_detail.consume_tps, group_detail.total_lag
+                ),
+                &queues,
+            ),
+            detail,
+        );
+    }
+
+    fn draw_connections(&mut self, frame: &mut Frame, area: Rect) {
+        let [list, detail] = split_master_detail(area);
+        let rows = filter_rows(&self.consumer_groups, self.search_input.get_input());
+        let table = build_table("Consumer Groups [Enter: connections]", &rows);
+        frame.render_stateful_widget(
+            table,
+            list,
+            &mut self.table_states[View::Connections.index()],
+        );
+
+        let Some(connection_detail) = self.connection_detail.as_ref() else {
+            frame.render_widget(
+                placeholder("Connections", "Select a group and press Enter"),
+                detail,
+            );
+            return;
+        };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(detail);
+        let mut lines = vec![
+            Line::from(format!("Consume type:  {}", connection_detail.consume_type)),
+            Line::from(format!(
+                "Message model: {}",
+                connection_detail.message_model
+            )),
+            Line::from("Subscriptions:"),
+        ];
+        lines.extend(
+            connection_detail
+                .subscriptions
+                .iter()
+                .map(|subscription| Line::from(format!("  {}", subscription))),
         );
         frame.render_widget(
-            Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Execute Command Result"),
-            execute_command_result,
+            Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(connection_detail.group.as_str()),
+            ),
+            chunks[0],
+        );
+        let connections: Vec<_> = connection_detail.connections.iter().collect();
+        frame.render_widget(build_table("Clients", &connections), chunks[1]);
+    }
+
+    fn draw_messages(&mut self, frame: &mut Frame, area: Rect) {
+        let rows: Vec<_> = self.messages.iter().collect();
+        let table = build_table(
+            "Messages [search: <topic> <msgId|key>, Enter: query]",
+            &rows,
+        );
+        frame.render_stateful_widget(table, area, &mut self.table_states[View::Messages.index()]);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let updated = match self.last_refresh {
+            Some(last_refresh) => format!("updated {}s ago", last_refresh.elapsed().as_secs()),
+            None => "not loaded".to_string(),
+        };
+        let (message, style) = match self.error.as_ref() {
+            Some(error) => (error.as_str(), Style::default().fg(Color::Red)),
+            None => (self.status.as_str(), Style::default().fg(Color::Green)),
+        };
+        let text = format!(
+            "refresh {}s (+/-) | {} | {}",
+            self.refresh_interval.as_secs(),
+            updated,
+            message
         );
         frame.render_widget(
+            Paragraph::new(text).style(style).block(
+                Block::default().borders(Borders::ALL).title(
+                    "q: quit  Tab/1-5: view  j/k: select  Enter: load  r: refresh  s: search",
+                ),
+            ),
+            area,
+        );
+    }
+}
+
+fn split_master_detail(area: Rect) -> [Rect; 2] {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(area);
+    [chunks[0], chunks[1]]
+}
+
+fn placeholder<'a>(title: &'a str, hint: &'a str) -> Paragraph<'a> {
+    Paragraph::new(hint)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL).title(title))
+}
+
+fn build_table<'a, R: TableRow>(title: &str, rows: &[&R]) -> Table<'a> {
+    let header =
+        Row::new(R::HEADER.iter().copied()).style(Style::default().add_modifier(Modifier::BOLD));
+    let widths = vec![Constraint::Fill(1); R::HEADER.len()];
+    Table::new(rows.iter().map(|row| Row::new(row.cells())), widths)
+        .header(header)
+        .row_highlight_style(Style::default().bg(Color::DarkGray).fg(Color::Yellow))
+        .block(
             Block::default()
-                .borders(ratatui::widgets::Borders::ALL)
-                .title("Progress Bar"),
-            progress_bar,
+                .borders(Borders::ALL)
+                .title(format!("{} ({})", title, rows.len())),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn topic(name: &str) -> TopicRow {
+        TopicRow {
+            topic: name.to_string(),
+            category: "NORMAL",
+        }
+    }
+
+    #[test]
+    fn key_bindings() {
+        assert_eq!(
+            RocketmqTuiApp::key_action(KeyCode::Char('q')),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            RocketmqTuiApp::key_action(KeyCode::Char('3')),
+            Some(Action::SwitchView(2))
+        );
+        assert_eq!(
+            RocketmqTuiApp::key_action(KeyCode::Char('/')),
+            Some(Action::FocusSearch)
+        );
+        assert_eq!(RocketmqTuiApp::key_action(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn selection_follows_filtered_rows() {
+        let mut app = RocketmqTuiApp::default();
+        app.dispatch(Action::SwitchView(View::Topics.index()));
+        app.apply_fetch_result(FetchResult::Topics(vec![
+            topic("OrderTopic"),
+            topic("PayTopic"),
+            topic("OrderRetry"),
+        ]));
+        assert_eq!(app.table_states[View::Topics.index()].selected(), Some(0));
+
+        app.dispatch(Action::SelectPrevious);
+        assert_eq!(app.table_states[View::Topics.index()].selected(), Some(2));
+
+        app.dispatch(Action::FocusSearch);
+        for c in "pay".chars() {
+            app.handle_search_key(KeyCode::Char(c));
+        }
+        assert_eq!(app.visible_rows(View::Topics), 1);
+        assert_eq!(app.table_states[View::Topics.index()].selected(), Some(0));
+    }
+
+    #[test]
+    fn refresh_interval_is_bounded() {
+        let mut app = RocketmqTuiApp::new(
+            RocketmqTuiApp::DEFAULT_NAMESRV_ADDR.to_string(),
+            Duration::from_secs(1),
         );
+        app.dispatch(Action::DecreaseRefreshInterval);
+        assert_eq!(app.refresh_interval, Duration::from_secs(1));
+        app.dispatch(Action::IncreaseRefreshInterval);
+        assert_eq!(app.refresh_interval, Duration::from_secs(2));
     }
 }